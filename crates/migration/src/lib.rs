@@ -30,6 +30,28 @@ mod m20260704_000012_drop_legacy_wallet_contract;
 mod m20260710_234825_add_relationship_indexes;
 mod m20260717_105719_persist_lnurl_success_action;
 mod m20260814_151430_promote_wallet_account_unique_constraint;
+mod m20261018_093512_silent_payment_addresses;
+mod m20261019_101204_payment_request_addresses;
+mod m20261020_084417_ln_address_pay_profile;
+mod m20261021_092256_invoice_payer_data;
+mod m20261022_140531_invoice_payment_preimage;
+mod m20261023_101530_payjoin_tables;
+mod m20261024_093015_ln_address_domains;
+mod m20261025_091245_ln_address_aliases;
+mod m20261026_103317_ln_address_splits;
+mod m20261027_084512_internal_transfers;
+mod m20261028_101652_pay_links;
+mod m20261029_093127_local_credentials;
+mod m20261030_101845_api_key_lifecycle;
+mod m20261031_090412_auth_sessions;
+mod m20261101_083015_totp_factors;
+mod m20261102_094528_passkeys;
+mod m20261103_101207_organizations;
+mod m20261104_091532_audit_entries;
+mod m20261105_083241_rate_limit_buckets;
+mod m20261106_090318_api_key_members;
+mod m20261107_084126_payjoin_contributions;
+mod m20261108_090214_nostr_auth_events;
 
 pub struct Migrator;
 
@@ -67,6 +89,28 @@ impl MigratorTrait for Migrator {
             Box::new(m20260710_234825_add_relationship_indexes::Migration),
             Box::new(m20260717_105719_persist_lnurl_success_action::Migration),
             Box::new(m20260814_151430_promote_wallet_account_unique_constraint::Migration),
+            Box::new(m20261018_093512_silent_payment_addresses::Migration),
+            Box::new(m20261019_101204_payment_request_addresses::Migration),
+            Box::new(m20261020_084417_ln_address_pay_profile::Migration),
+            Box::new(m20261021_092256_invoice_payer_data::Migration),
+            Box::new(m20261022_140531_invoice_payment_preimage::Migration),
+            Box::new(m20261023_101530_payjoin_tables::Migration),
+            Box::new(m20261024_093015_ln_address_domains::Migration),
+            Box::new(m20261025_091245_ln_address_aliases::Migration),
+            Box::new(m20261026_103317_ln_address_splits::Migration),
+            Box::new(m20261027_084512_internal_transfers::Migration),
+            Box::new(m20261028_101652_pay_links::Migration),
+            Box::new(m20261029_093127_local_credentials::Migration),
+            Box::new(m20261030_101845_api_key_lifecycle::Migration),
+            Box::new(m20261031_090412_auth_sessions::Migration),
+            Box::new(m20261101_083015_totp_factors::Migration),
+            Box::new(m20261102_094528_passkeys::Migration),
+            Box::new(m20261103_101207_organizations::Migration),
+            Box::new(m20261104_091532_audit_entries::Migration),
+            Box::new(m20261105_083241_rate_limit_buckets::Migration),
+            Box::new(m20261106_090318_api_key_members::Migration),
+            Box::new(m20261107_084126_payjoin_contributions::Migration),
+            Box::new(m20261108_090214_nostr_auth_events::Migration),
         ]
     }
 }
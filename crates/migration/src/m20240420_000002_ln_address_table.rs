@@ -87,4 +87,9 @@ pub(crate) enum LnAddress {
     UpdatedAt,
     AllowsNostr,
     NostrPubkey,
+    PayProfile,
+    // Managed domain (added in m20261024_093015_ln_address_domains)
+    DomainId,
+    // Payment splits (added in m20261026_103317_ln_address_splits)
+    Splits,
 }
@@ -150,4 +150,17 @@ pub(crate) enum Invoice {
     ExpiresAt,
     // Bitcoin L1 support (added in m20251224_162546)
     BtcOutputId,
+    // LUD-18 payer data (added in m20261021_092256_invoice_payer_data)
+    PayerData,
+    // Settled invoice preimage (added in m20261022_140531_invoice_payment_preimage)
+    PaymentPreimage,
+    // Lightning Address alias paid (added in m20261025_091245_ln_address_aliases)
+    LnAddressAlias,
+    // Lightning Address payment splits (added in m20261026_103317_ln_address_splits)
+    SplitInvoiceId,
+    // Internal transfers (added in m20261027_084512_internal_transfers)
+    TransferId,
+    // LNURL-pay links (added in m20261028_101652_pay_links)
+    PayLinkId,
+    Reference,
 }
@@ -90,4 +90,8 @@ pub(crate) enum Payment {
     ReservedAmount,
     // Raw LNURL success action (added in m20260717_105719)
     RawSuccessAction,
+    // Lightning Address payment splits (added in m20261026_103317_ln_address_splits)
+    SplitInvoiceId,
+    // Internal transfers (added in m20261027_084512_internal_transfers)
+    TransferId,
 }
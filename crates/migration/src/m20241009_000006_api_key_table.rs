@@ -70,4 +70,8 @@ pub(crate) enum ApiKey {
     CreatedAt,
     ExpiresAt,
     AccountId,
+    LastUsedAt,
+    LastUsedIp,
+    WalletIds,
+    MemberAccountId,
 }
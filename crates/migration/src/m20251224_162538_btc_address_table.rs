@@ -75,4 +75,7 @@ pub(crate) enum BtcAddress {
     Used,
     CreatedAt,
     UpdatedAt,
+    SilentPaymentLabel,
+    SilentPaymentTweak,
+    InvoiceId,
 }
@@ -0,0 +1,48 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20251224_162538_btc_address_table::BtcAddress;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BtcAddress::Table)
+                    .add_column(uuid_null(BtcAddress::InvoiceId))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_btc_address_invoice_id")
+                    .table(BtcAddress::Table)
+                    .col(BtcAddress::InvoiceId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_btc_address_invoice_id")
+                    .table(BtcAddress::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BtcAddress::Table)
+                    .drop_column(BtcAddress::InvoiceId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayjoinInput::Table)
+                    .if_not_exists()
+                    .col(string_len(PayjoinInput::Outpoint, 255).primary_key())
+                    .col(timestamp(PayjoinInput::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayjoinFallback::Table)
+                    .if_not_exists()
+                    .col(uuid(PayjoinFallback::Id).primary_key())
+                    .col(string_len(PayjoinFallback::Txid, 255))
+                    .col(text(PayjoinFallback::TxHex))
+                    .col(string_len(PayjoinFallback::LockId, 255))
+                    .col(string_len(PayjoinFallback::LockedTxid, 255))
+                    .col(unsigned(PayjoinFallback::LockedOutputIndex))
+                    .col(timestamp(PayjoinFallback::BroadcastAfter))
+                    .col(timestamp(PayjoinFallback::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_payjoin_fallback_broadcast_after")
+                    .table(PayjoinFallback::Table)
+                    .col(PayjoinFallback::BroadcastAfter)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_payjoin_fallback_broadcast_after")
+                    .table(PayjoinFallback::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PayjoinFallback::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PayjoinInput::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum PayjoinInput {
+    Table,
+    Outpoint,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum PayjoinFallback {
+    Table,
+    Id,
+    Txid,
+    TxHex,
+    LockId,
+    LockedTxid,
+    LockedOutputIndex,
+    BroadcastAfter,
+    CreatedAt,
+}
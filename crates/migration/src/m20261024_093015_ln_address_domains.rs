@@ -0,0 +1,140 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend};
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20240420_000002_ln_address_table::LnAddress;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LnAddressDomain::Table)
+                    .if_not_exists()
+                    .col(uuid(LnAddressDomain::Id).primary_key())
+                    .col(string_len_uniq(LnAddressDomain::Name, 255))
+                    .col(string_len(LnAddressDomain::Host, 255))
+                    .col(timestamp(LnAddressDomain::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(LnAddressDomain::UpdatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Usernames become unique per domain, so the column-level UNIQUE constraint goes away.
+        // SQLite cannot drop it in place and the table has to be rebuilt.
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            rebuild_sqlite(manager.get_connection()).await?;
+        } else {
+            manager
+                .get_connection()
+                .execute_unprepared("ALTER TABLE ln_address DROP CONSTRAINT IF EXISTS ln_address_username_key")
+                .await?;
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(LnAddress::Table)
+                        .add_column(uuid_null(LnAddress::DomainId))
+                        .add_foreign_key(
+                            TableForeignKey::new()
+                                .name("fk_ln_address_domain")
+                                .from_tbl(LnAddress::Table)
+                                .from_col(LnAddress::DomainId)
+                                .to_tbl(LnAddressDomain::Table)
+                                .to_col(LnAddressDomain::Id)
+                                .on_delete(ForeignKeyAction::Restrict),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ln_address_domain_username")
+                    .table(LnAddress::Table)
+                    .col(LnAddress::DomainId)
+                    .col(LnAddress::Username)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // NULL domains are distinct in the index above: addresses on the configured domain need their own.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ln_address_default_domain_username")
+                    .table(LnAddress::Table)
+                    .col(LnAddress::Username)
+                    .unique()
+                    .and_where(Expr::col(LnAddress::DomainId).is_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Err(DbErr::Migration(
+            "per-domain lightning address usernames cannot be made globally unique again".to_string(),
+        ))
+    }
+}
+
+async fn rebuild_sqlite(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    db.execute_unprepared("PRAGMA foreign_keys = OFF").await?;
+    db.execute_unprepared(
+        r#"
+        CREATE TABLE "ln_address_new" (
+            "id" uuid_text NOT NULL PRIMARY KEY,
+            "account_id" uuid_text NOT NULL,
+            "wallet_id" uuid_text NOT NULL UNIQUE,
+            "domain_id" uuid_text NULL,
+            "username" varchar(255) NOT NULL,
+            "active" boolean NOT NULL DEFAULT TRUE,
+            "created_at" timestamp_text NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" timestamp_text NULL,
+            "allows_nostr" boolean NOT NULL DEFAULT FALSE,
+            "nostr_pubkey" varchar(255) NULL,
+            "pay_profile" jsonb_text NULL,
+            CONSTRAINT "idx_ln_address_account" UNIQUE ("account_id"),
+            FOREIGN KEY ("account_id") REFERENCES "account" ("id") ON DELETE CASCADE,
+            FOREIGN KEY ("account_id", "wallet_id") REFERENCES "wallet" ("account_id", "id") ON DELETE CASCADE,
+            FOREIGN KEY ("domain_id") REFERENCES "ln_address_domain" ("id") ON DELETE RESTRICT
+        )
+        "#,
+    )
+    .await?;
+    db.execute_unprepared(
+        r#"
+        INSERT INTO "ln_address_new" (
+            "id", "account_id", "wallet_id", "username", "active", "created_at", "updated_at",
+            "allows_nostr", "nostr_pubkey", "pay_profile"
+        )
+        SELECT
+            "id", "account_id", "wallet_id", "username", "active", "created_at", "updated_at",
+            "allows_nostr", "nostr_pubkey", "pay_profile"
+        FROM "ln_address"
+        "#,
+    )
+    .await?;
+    db.execute_unprepared(r#"DROP TABLE "ln_address""#).await?;
+    db.execute_unprepared(r#"ALTER TABLE "ln_address_new" RENAME TO "ln_address""#)
+        .await?;
+    db.execute_unprepared("PRAGMA foreign_keys = ON").await?;
+
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum LnAddressDomain {
+    Table,
+    Id,
+    Name,
+    Host,
+    CreatedAt,
+    UpdatedAt,
+}
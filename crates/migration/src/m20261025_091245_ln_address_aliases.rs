@@ -0,0 +1,116 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend};
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m20240420_000002_ln_address_table::LnAddress, m20240420_000003_invoice_table::Invoice};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Accounts and wallets can have several addresses, so their UNIQUE constraints go away.
+        // SQLite cannot drop them in place and the table has to be rebuilt.
+        if manager.get_database_backend() == DatabaseBackend::Sqlite {
+            rebuild_sqlite(manager.get_connection()).await?;
+        } else {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    r#"
+                    DROP INDEX IF EXISTS idx_ln_address_account;
+                    ALTER TABLE ln_address DROP CONSTRAINT IF EXISTS ln_address_wallet_id_key;
+                    "#,
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ln_address_account_id")
+                    .table(LnAddress::Table)
+                    .col(LnAddress::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ln_address_wallet_id")
+                    .table(LnAddress::Table)
+                    .col(LnAddress::WalletId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .add_column(string_len_null(Invoice::LnAddressAlias, 255))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Err(DbErr::Migration(
+            "several lightning addresses per account cannot be made unique again".to_string(),
+        ))
+    }
+}
+
+async fn rebuild_sqlite(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    db.execute_unprepared("PRAGMA foreign_keys = OFF").await?;
+    db.execute_unprepared(
+        r#"
+        CREATE TABLE "ln_address_new" (
+            "id" uuid_text NOT NULL PRIMARY KEY,
+            "account_id" uuid_text NOT NULL,
+            "wallet_id" uuid_text NOT NULL,
+            "domain_id" uuid_text NULL,
+            "username" varchar(255) NOT NULL,
+            "active" boolean NOT NULL DEFAULT TRUE,
+            "created_at" timestamp_text NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            "updated_at" timestamp_text NULL,
+            "allows_nostr" boolean NOT NULL DEFAULT FALSE,
+            "nostr_pubkey" varchar(255) NULL,
+            "pay_profile" jsonb_text NULL,
+            FOREIGN KEY ("account_id") REFERENCES "account" ("id") ON DELETE CASCADE,
+            FOREIGN KEY ("account_id", "wallet_id") REFERENCES "wallet" ("account_id", "id") ON DELETE CASCADE,
+            FOREIGN KEY ("domain_id") REFERENCES "ln_address_domain" ("id") ON DELETE RESTRICT
+        )
+        "#,
+    )
+    .await?;
+    db.execute_unprepared(
+        r#"
+        INSERT INTO "ln_address_new" (
+            "id", "account_id", "wallet_id", "domain_id", "username", "active", "created_at", "updated_at",
+            "allows_nostr", "nostr_pubkey", "pay_profile"
+        )
+        SELECT
+            "id", "account_id", "wallet_id", "domain_id", "username", "active", "created_at", "updated_at",
+            "allows_nostr", "nostr_pubkey", "pay_profile"
+        FROM "ln_address"
+        "#,
+    )
+    .await?;
+    db.execute_unprepared(r#"DROP TABLE "ln_address""#).await?;
+    db.execute_unprepared(r#"ALTER TABLE "ln_address_new" RENAME TO "ln_address""#)
+        .await?;
+
+    // The per-domain username indexes were dropped with the old table.
+    db.execute_unprepared(
+        r#"CREATE UNIQUE INDEX "idx_ln_address_domain_username" ON "ln_address" ("domain_id", "username")"#,
+    )
+    .await?;
+    db.execute_unprepared(
+        r#"CREATE UNIQUE INDEX "idx_ln_address_default_domain_username" ON "ln_address" ("username") WHERE "domain_id" IS NULL"#,
+    )
+    .await?;
+    db.execute_unprepared("PRAGMA foreign_keys = ON").await?;
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{
+    m20240420_000002_ln_address_table::LnAddress, m20240420_000003_invoice_table::Invoice,
+    m20240420_000004_payment_table::Payment,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LnAddress::Table)
+                    .add_column(json_binary_null(LnAddress::Splits))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Both legs of a split transfer point to the incoming invoice they were split from.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .add_column(uuid_null(Invoice::SplitInvoiceId))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .add_column(uuid_null(Payment::SplitInvoiceId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .drop_column(Payment::SplitInvoiceId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .drop_column(Invoice::SplitInvoiceId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LnAddress::Table)
+                    .drop_column(LnAddress::Splits)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,126 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m20240420_000001_wallet_table::Wallet, m20240420_000003_invoice_table::Invoice};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayLink::Table)
+                    .if_not_exists()
+                    .col(uuid(PayLink::Id).primary_key())
+                    .col(uuid(PayLink::WalletId))
+                    .col(string_len(PayLink::Name, 255))
+                    .col(string_len_null(PayLink::Description, 255))
+                    .col(big_integer_null(PayLink::AmountMsat))
+                    .col(string_len_null(PayLink::Reference, 255))
+                    .col(boolean(PayLink::Active).default(true))
+                    .col(timestamp(PayLink::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(PayLink::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_pay_link_wallet")
+                            .from(PayLink::Table, PayLink::WalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_pay_link_wallet_name")
+                    .table(PayLink::Table)
+                    .col(PayLink::WalletId)
+                    .col(PayLink::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Invoices keep the link and reference they were paid through after the link is deleted.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .add_column(uuid_null(Invoice::PayLinkId))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .add_column(string_len_null(Invoice::Reference, 255))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_invoice_pay_link_id")
+                    .table(Invoice::Table)
+                    .col(Invoice::PayLinkId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_invoice_pay_link_id")
+                    .table(Invoice::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .drop_column(Invoice::Reference)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Invoice::Table)
+                    .drop_column(Invoice::PayLinkId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_pay_link_wallet_name")
+                    .table(PayLink::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager.drop_table(Table::drop().table(PayLink::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum PayLink {
+    Table,
+    Id,
+    WalletId,
+    Name,
+    Description,
+    AmountMsat,
+    Reference,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
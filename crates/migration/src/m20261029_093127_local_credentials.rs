@@ -0,0 +1,125 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m20260704_000001_account_table::Account, m20260704_000002_auth_identity_table::AuthIdentity};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Passwords of local accounts other than the bootstrap admin, whose hash stays in the config table.
+        manager
+            .create_table(
+                Table::create()
+                    .table(LocalCredential::Table)
+                    .if_not_exists()
+                    .col(uuid(LocalCredential::IdentityId).primary_key())
+                    .col(string_len_null(LocalCredential::Email, 255).unique_key())
+                    .col(string_len(LocalCredential::PasswordHash, 255))
+                    .col(timestamp(LocalCredential::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(LocalCredential::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_local_credential_identity")
+                            .from(LocalCredential::Table, LocalCredential::IdentityId)
+                            .to(AuthIdentity::Table, AuthIdentity::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Invitation::Table)
+                    .if_not_exists()
+                    .col(uuid(Invitation::Id).primary_key())
+                    .col(binary_len_uniq(Invitation::TokenHash, 32))
+                    .col(string_len_null(Invitation::Email, 255))
+                    .col(json(Invitation::Permissions))
+                    .col(uuid_null(Invitation::AccountId))
+                    .col(timestamp(Invitation::ExpiresAt))
+                    .col(timestamp_null(Invitation::UsedAt))
+                    .col(timestamp(Invitation::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_invitation_account")
+                            .from(Invitation::Table, Invitation::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordReset::Table)
+                    .if_not_exists()
+                    .col(uuid(PasswordReset::Id).primary_key())
+                    .col(uuid(PasswordReset::AccountId))
+                    .col(binary_len_uniq(PasswordReset::TokenHash, 32))
+                    .col(timestamp(PasswordReset::ExpiresAt))
+                    .col(timestamp_null(PasswordReset::UsedAt))
+                    .col(timestamp(PasswordReset::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_password_reset_account")
+                            .from(PasswordReset::Table, PasswordReset::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PasswordReset::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Invitation::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(LocalCredential::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LocalCredential {
+    Table,
+    IdentityId,
+    Email,
+    PasswordHash,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Invitation {
+    Table,
+    Id,
+    TokenHash,
+    Email,
+    Permissions,
+    AccountId,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PasswordReset {
+    Table,
+    Id,
+    AccountId,
+    TokenHash,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20241009_000006_api_key_table::ApiKey;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one column per ALTER TABLE statement.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .add_column(timestamp_null(ApiKey::LastUsedAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .add_column(string_len_null(ApiKey::LastUsedIp, 45))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .add_column(json_null(ApiKey::WalletIds))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .drop_column(ApiKey::WalletIds)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .drop_column(ApiKey::LastUsedIp)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApiKey::Table)
+                    .drop_column(ApiKey::LastUsedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
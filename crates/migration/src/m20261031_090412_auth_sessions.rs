@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260704_000001_account_table::Account;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sign-in sessions of the `JWT` provider. Access tokens carry the session ID and stop
+        // authenticating once the session is deleted.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuthSession::Table)
+                    .if_not_exists()
+                    .col(uuid(AuthSession::Id).primary_key())
+                    .col(uuid(AuthSession::AccountId))
+                    .col(binary_len_uniq(AuthSession::RefreshTokenHash, 32))
+                    .col(timestamp(AuthSession::ExpiresAt))
+                    .col(timestamp(AuthSession::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(AuthSession::RefreshedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_auth_session_account")
+                            .from(AuthSession::Table, AuthSession::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_auth_session_account_id")
+                    .table(AuthSession::Table)
+                    .col(AuthSession::AccountId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuthSession::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub(crate) enum AuthSession {
+    Table,
+    Id,
+    AccountId,
+    RefreshTokenHash,
+    ExpiresAt,
+    CreatedAt,
+    RefreshedAt,
+    VerifiedAt,
+}
@@ -0,0 +1,111 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m20260704_000001_account_table::Account, m20261031_090412_auth_sessions::AuthSession};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // TOTP second factor of an account, enabled once confirmed. `last_used_step` rejects replayed codes.
+        manager
+            .create_table(
+                Table::create()
+                    .table(TotpFactor::Table)
+                    .if_not_exists()
+                    .col(uuid(TotpFactor::AccountId).primary_key())
+                    .col(binary_len(TotpFactor::Secret, 20))
+                    .col(big_integer(TotpFactor::LastUsedStep).default(0))
+                    .col(timestamp_null(TotpFactor::ConfirmedAt))
+                    .col(timestamp(TotpFactor::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_totp_factor_account")
+                            .from(TotpFactor::Table, TotpFactor::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TotpRecoveryCode::Table)
+                    .if_not_exists()
+                    .col(uuid(TotpRecoveryCode::Id).primary_key())
+                    .col(uuid(TotpRecoveryCode::AccountId))
+                    .col(binary_len(TotpRecoveryCode::CodeHash, 32))
+                    .col(timestamp_null(TotpRecoveryCode::UsedAt))
+                    .col(timestamp(TotpRecoveryCode::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_totp_recovery_code_account")
+                            .from(TotpRecoveryCode::Table, TotpRecoveryCode::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_totp_recovery_code_account_id")
+                    .table(TotpRecoveryCode::Table)
+                    .col(TotpRecoveryCode::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Last verification of the second factor within a session, for step-up of sensitive operations.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthSession::Table)
+                    .add_column(timestamp_null(AuthSession::VerifiedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthSession::Table)
+                    .drop_column(AuthSession::VerifiedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TotpRecoveryCode::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TotpFactor::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TotpFactor {
+    Table,
+    AccountId,
+    Secret,
+    LastUsedStep,
+    ConfirmedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum TotpRecoveryCode {
+    Table,
+    Id,
+    AccountId,
+    CodeHash,
+    UsedAt,
+    CreatedAt,
+}
@@ -0,0 +1,111 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m20260704_000001_account_table::Account, m20260704_000002_auth_identity_table::AuthIdentity};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // WebAuthn credentials signing in to a local identity.
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasskeyCredential::Table)
+                    .if_not_exists()
+                    .col(uuid(PasskeyCredential::Id).primary_key())
+                    .col(uuid(PasskeyCredential::IdentityId))
+                    .col(var_binary_uniq(PasskeyCredential::CredentialId, 1023))
+                    .col(blob(PasskeyCredential::PublicKey))
+                    .col(big_integer(PasskeyCredential::SignCount).default(0))
+                    .col(string_len(PasskeyCredential::Name, 255))
+                    .col(timestamp(PasskeyCredential::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(PasskeyCredential::LastUsedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_passkey_credential_identity")
+                            .from(PasskeyCredential::Table, PasskeyCredential::IdentityId)
+                            .to(AuthIdentity::Table, AuthIdentity::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_passkey_credential_identity_id")
+                    .table(PasskeyCredential::Table)
+                    .col(PasskeyCredential::IdentityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Challenges of ongoing registration and authentication ceremonies, each used once. Registration
+        // challenges belong to the account adding the passkey.
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasskeyChallenge::Table)
+                    .if_not_exists()
+                    .col(uuid(PasskeyChallenge::Id).primary_key())
+                    .col(binary_len(PasskeyChallenge::Challenge, 32))
+                    .col(uuid_null(PasskeyChallenge::AccountId))
+                    .col(timestamp(PasskeyChallenge::ExpiresAt))
+                    .col(timestamp(PasskeyChallenge::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_passkey_challenge_account")
+                            .from(PasskeyChallenge::Table, PasskeyChallenge::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_passkey_challenge_expires_at")
+                    .table(PasskeyChallenge::Table)
+                    .col(PasskeyChallenge::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PasskeyChallenge::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PasskeyCredential::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PasskeyCredential {
+    Table,
+    Id,
+    IdentityId,
+    CredentialId,
+    PublicKey,
+    SignCount,
+    Name,
+    CreatedAt,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum PasskeyChallenge {
+    Table,
+    Id,
+    Challenge,
+    AccountId,
+    ExpiresAt,
+    CreatedAt,
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::m20260704_000001_account_table::Account;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // An organization shares the ID of the account owning its wallets and other resources.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Organization::Table)
+                    .if_not_exists()
+                    .col(uuid(Organization::Id).primary_key())
+                    .col(string_len(Organization::Name, 255))
+                    .col(timestamp(Organization::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(Organization::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_account")
+                            .from(Organization::Table, Organization::Id)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrganizationMember::Table)
+                    .if_not_exists()
+                    .col(uuid(OrganizationMember::OrganizationId))
+                    .col(uuid(OrganizationMember::AccountId))
+                    .col(string_len(OrganizationMember::Role, 255))
+                    .col(timestamp(OrganizationMember::CreatedAt).default(Expr::current_timestamp()))
+                    .col(timestamp_null(OrganizationMember::UpdatedAt))
+                    .primary_key(
+                        Index::create()
+                            .name("pk_organization_member")
+                            .col(OrganizationMember::OrganizationId)
+                            .col(OrganizationMember::AccountId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_member_organization")
+                            .from(OrganizationMember::Table, OrganizationMember::OrganizationId)
+                            .to(Organization::Table, Organization::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_organization_member_account")
+                            .from(OrganizationMember::Table, OrganizationMember::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_organization_member_account_id")
+                    .table(OrganizationMember::Table)
+                    .col(OrganizationMember::AccountId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrganizationMember::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Organization::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Organization {
+    Table,
+    Id,
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OrganizationMember {
+    Table,
+    OrganizationId,
+    AccountId,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,113 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Append-only audit log. Entries reference accounts, API keys and resources without foreign keys so that
+        // they outlive them.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEntry::Table)
+                    .if_not_exists()
+                    .col(uuid(AuditEntry::Id).primary_key())
+                    .col(uuid_null(AuditEntry::AccountId))
+                    .col(uuid_null(AuditEntry::MemberAccountId))
+                    .col(uuid_null(AuditEntry::ApiKeyId))
+                    .col(string_len_null(AuditEntry::Subject, 255))
+                    .col(string_len_null(AuditEntry::SourceIp, 45))
+                    .col(string_len(AuditEntry::Method, 16))
+                    .col(text(AuditEntry::Path))
+                    .col(uuid_null(AuditEntry::ResourceId))
+                    .col(integer(AuditEntry::Status))
+                    .col(json_null(AuditEntry::Diff))
+                    .col(string_len_null(AuditEntry::PreviousHash, 64))
+                    .col(string_len_null(AuditEntry::Hash, 64))
+                    .col(timestamp(AuditEntry::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_entry_created_at")
+                    .table(AuditEntry::Table)
+                    .col(AuditEntry::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_entry_account_id")
+                    .table(AuditEntry::Table)
+                    .col(AuditEntry::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_entry_resource_id")
+                    .table(AuditEntry::Table)
+                    .col(AuditEntry::ResourceId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Latest entry of the hash chain. Writers lock its single row to append to the chain one at a time, which
+        // also holds before the first entry exists.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditChainHead::Table)
+                    .if_not_exists()
+                    .col(integer(AuditChainHead::Id).primary_key())
+                    .col(uuid_null(AuditChainHead::EntryId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditChainHead::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AuditEntry::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEntry {
+    Table,
+    Id,
+    AccountId,
+    MemberAccountId,
+    ApiKeyId,
+    Subject,
+    SourceIp,
+    Method,
+    Path,
+    ResourceId,
+    Status,
+    Diff,
+    PreviousHash,
+    Hash,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum AuditChainHead {
+    Table,
+    Id,
+    EntryId,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Value our payjoin proposals added to the payment output, by outpoint, which is not credited to the deposit.
+        manager
+            .create_table(
+                Table::create()
+                    .table(PayjoinContribution::Table)
+                    .if_not_exists()
+                    .col(string_len(PayjoinContribution::Outpoint, 255).primary_key())
+                    .col(big_integer(PayjoinContribution::AmountSat))
+                    .col(timestamp(PayjoinContribution::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PayjoinContribution::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PayjoinContribution {
+    Table,
+    Outpoint,
+    AmountSat,
+    CreatedAt,
+}
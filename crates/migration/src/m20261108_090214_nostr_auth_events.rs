@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // NIP-98 events already accepted by any instance, kept until they expire so that they cannot be replayed.
+        manager
+            .create_table(
+                Table::create()
+                    .table(NostrAuthEvent::Table)
+                    .if_not_exists()
+                    .col(string_len(NostrAuthEvent::Id, 64).primary_key())
+                    .col(timestamp(NostrAuthEvent::ExpiresAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_nostr_auth_event_expires_at")
+                    .table(NostrAuthEvent::Table)
+                    .col(NostrAuthEvent::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NostrAuthEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum NostrAuthEvent {
+    Table,
+    Id,
+    ExpiresAt,
+}
@@ -52,10 +52,10 @@ pub struct AuthIdentity {
     /// Stable internal identity ID.
     pub id: Uuid,
 
-    /// Authentication provider namespace, such as `jwt` or `oauth2`.
+    /// Authentication provider namespace, such as `jwt`, `oauth2` or `nostr`.
     pub provider: AuthProvider,
 
-    /// Provider subject, such as a JWT username or OAuth2 `sub`.
+    /// Provider subject, such as a JWT username, OAuth2 `sub` or hex Nostr public key.
     #[schema(example = "auth0|numeraire")]
     pub subject: String,
 
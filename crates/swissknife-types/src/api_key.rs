@@ -29,6 +29,17 @@ pub struct ApiKey {
     pub created_at: DateTime<Utc>,
     /// Date of expiration
     pub expires_at: Option<DateTime<Utc>>,
+    /// Wallets the API key is restricted to. Unrestricted if absent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_ids: Option<Vec<Uuid>>,
+    /// Date the API key was last used to authenticate
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Source IP address of the last authentication
+    pub last_used_ip: Option<String>,
+    /// Member who issued the API key of an organization account. The key holds no more than the role of the
+    /// member, and stops authenticating when they leave the organization.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_account_id: Option<Uuid>,
 }
 
 /// Create API Key Request
@@ -46,6 +57,22 @@ pub struct CreateApiKeyRequest {
     pub description: Option<String>,
     /// Expiration time in seconds
     pub expiry: Option<u32>,
+    /// Wallets of the owning account the API key is restricted to.
+    ///
+    /// Wallet-scoped keys can only act through the `/v1/me/wallets/{wallet_id}` routes of these wallets
+    /// and therefore cannot hold permissions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_ids: Option<Vec<Uuid>>,
+}
+
+/// Rotate API Key Request
+#[derive(Debug, Default, Deserialize, ToSchema, Serialize)]
+pub struct RotateApiKeyRequest {
+    /// Seconds during which the rotated key keeps working. The rotated key stops working immediately if absent.
+    #[schema(example = 3600)]
+    pub grace_period: Option<u32>,
+    /// Expiration time of the replacement key in seconds. Defaults to the lifetime of the rotated key.
+    pub expiry: Option<u32>,
 }
 
 /// API key query filter.
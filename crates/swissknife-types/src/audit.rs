@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::OrderDirection;
+
+/// Audit Entry
+///
+/// Append-only record of a state-changing request.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct AuditEntry {
+    /// Internal ID
+    pub id: Uuid,
+    /// Account the request acted for. Absent for unauthenticated requests, such as sign-ins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<Uuid>,
+    /// Account of the organization member acting for `account_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_account_id: Option<Uuid>,
+    /// API key the request authenticated with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_id: Option<Uuid>,
+    /// Subject of the token the request authenticated with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// IP address of the peer, which is the reverse proxy's when deployed behind one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "203.0.113.7")]
+    pub source_ip: Option<String>,
+    /// HTTP method
+    #[schema(example = "DELETE")]
+    pub method: String,
+    /// Path of the target resource
+    #[schema(example = "/v1/wallets/3fa85f64-5717-4562-b3fc-2c963f66afa6")]
+    pub path: String,
+    /// ID of the target resource, the last ID of the path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<Uuid>,
+    /// HTTP status of the response
+    #[schema(example = 200)]
+    pub status: u16,
+    /// State of the target resource before and after the request. Absent when it changed nothing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<AuditDiff>,
+    /// Hash of the previous entry of the chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_hash: Option<String>,
+    /// Hex-encoded SHA-256 of this entry serialized to JSON without `hash`. Set when hash chaining is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+}
+
+/// Audit Diff
+///
+/// State of a resource before and after a change, with keys, passwords, tokens, codes and other secrets redacted.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AuditDiff {
+    /// State before the change. Absent for created resources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = json!({ "name": "treasury", "permissions": ["read:wallet"] }))]
+    pub before: Option<Value>,
+    /// State after the change. Absent for deleted resources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = json!({ "name": "treasury", "permissions": ["read:wallet", "write:wallet"] }))]
+    pub after: Option<Value>,
+}
+
+/// Audit query filter.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, IntoParams)]
+pub struct AuditFilter {
+    /// Total amount of results to return
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub limit: Option<u64>,
+    /// Offset where to start returning results
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub offset: Option<u64>,
+    /// Account the requests acted for
+    pub account_id: Option<Uuid>,
+    /// Account of the organization member
+    pub member_account_id: Option<Uuid>,
+    /// API key ID
+    pub api_key_id: Option<Uuid>,
+    /// Token subject
+    pub subject: Option<String>,
+    /// Target resource ID
+    pub resource_id: Option<Uuid>,
+    /// Prefix of the path of the target resource
+    pub path: Option<String>,
+    /// HTTP method
+    pub method: Option<String>,
+    /// Entries created at or after this date
+    pub created_after: Option<DateTime<Utc>>,
+    /// Entries created before this date
+    pub created_before: Option<DateTime<Utc>>,
+    /// Direction of the ordering of results
+    #[serde(default)]
+    pub order_direction: OrderDirection,
+}
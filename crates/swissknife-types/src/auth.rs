@@ -1,6 +1,11 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
 use strum_macros::{Display, EnumString};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{OrderDirection, Permission};
 
 /// Authentication provider namespace.
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, EnumString, Display, PartialEq, Eq, Default, ToSchema)]
@@ -10,6 +15,8 @@ pub enum AuthProvider {
     #[default]
     Jwt,
     OAuth2,
+    /// Nostr public keys linked to accounts, authenticating with NIP-98 events. Not a sign-in provider.
+    Nostr,
 }
 
 /// Sign Up Request
@@ -23,9 +30,17 @@ pub struct SignUpRequest {
 /// Sign In Request
 #[derive(Debug, Deserialize, ToSchema, Serialize)]
 pub struct SignInRequest {
+    /// Username or email of a local account. Omit to sign in as the initial admin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "alice")]
+    pub login: Option<String>,
     /// User password
     #[schema(example = "password")]
     pub password: String,
+    /// TOTP code or unused recovery code. Required when two-factor authentication is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "123456")]
+    pub otp: Option<String>,
 }
 
 /// Change Password Request
@@ -45,4 +60,161 @@ pub struct SignInResponse {
     /// JWT token
     #[schema(example = "eyJ0eXAiOiJKV1QiLCJhbGciOiJ...")]
     pub token: String,
+    /// Single-use token exchanged for a new JWT token and refresh token at `/v1/auth/refresh`
+    #[schema(example = "q9Jm3n1Xq2i4xXJ7m0vF0x0tC7l2y3q8rV1h6bO0cYk")]
+    pub refresh_token: String,
+}
+
+/// Refresh Token Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct RefreshTokenRequest {
+    /// Refresh token returned at sign-in or by the last refresh
+    #[schema(example = "q9Jm3n1Xq2i4xXJ7m0vF0x0tC7l2y3q8rV1h6bO0cYk")]
+    pub refresh_token: String,
+}
+
+/// TOTP Enrollment
+///
+/// Pending TOTP factor, enabled once a code of the authenticator is confirmed.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct TotpEnrollment {
+    /// Base32 secret, for manual entry in the authenticator
+    #[schema(example = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP")]
+    pub secret: String,
+    /// Key URI, usually rendered as a QR code for the authenticator
+    #[schema(example = "otpauth://totp/Swissknife:alice?secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP&issuer=Swissknife")]
+    pub otpauth_uri: String,
+}
+
+/// TOTP Code Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct TotpCodeRequest {
+    /// Current TOTP code, or an unused recovery code where accepted
+    #[schema(example = "123456")]
+    pub code: String,
+}
+
+/// TOTP Recovery Codes
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct TotpRecoveryCodes {
+    /// Single-use codes accepted instead of a TOTP code (only returned once, on confirmation)
+    #[schema(example = json!(["k7d2m-q4xa9"]))]
+    pub recovery_codes: Vec<String>,
+}
+
+/// Link Nostr Key Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct LinkNostrKeyRequest {
+    /// NIP-98 event signed by the Nostr key for `POST` on this endpoint, base64 encoded as in the `Authorization`
+    /// header. Proves ownership of the key.
+    #[schema(
+        example = "eyJpZCI6ImZlOTY0ZTc1ODkwMzM2MGYyOGQ4NDI0ZDA5MmRhODQ5NGVkMjA3Y2JhODIzMTEwYmUzYTU3ZGZlNGI1Nzg3MzQiLCJraW5kIjoyNzIzNSwi..."
+    )]
+    pub event: String,
+}
+
+/// Register Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct RegisterRequest {
+    /// Username used to sign in. Lowercase letters, digits, `.`, `-` and `_`.
+    #[schema(example = "alice")]
+    pub username: String,
+    /// Email that can be used to sign in instead of the username
+    #[schema(example = "alice@example.com")]
+    pub email: Option<String>,
+    /// User password
+    #[schema(example = "password")]
+    pub password: String,
+    /// Display name of the new account
+    pub display_name: Option<String>,
+    /// Invitation token. Required unless self-registration is enabled.
+    pub invitation: Option<String>,
+}
+
+/// Invitation
+///
+/// One-time token, issued by an administrator, to register a local account with the given permissions.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct Invitation {
+    /// Internal ID
+    pub id: Uuid,
+    /// Invitation token (only returned once on creation, to be handed to the invitee)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Hashed invitation token. Internal only.
+    #[serde(skip)]
+    pub token_hash: Vec<u8>,
+    /// Email of the invitee, informational
+    pub email: Option<String>,
+    /// Permissions granted to the registered account
+    pub permissions: Vec<Permission>,
+    /// Account registered with this invitation
+    pub account_id: Option<Uuid>,
+    /// Date of expiration
+    pub expires_at: DateTime<Utc>,
+    /// Date the invitation was used
+    pub used_at: Option<DateTime<Utc>>,
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create Invitation Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct CreateInvitationRequest {
+    /// Email of the invitee, informational
+    pub email: Option<String>,
+    /// Permissions granted to the registered account. Must be a subset of the caller's permissions.
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+    /// Expiration time in seconds. Defaults to the configured invitation expiry.
+    pub expiry: Option<u32>,
+}
+
+/// Invitation query filter.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, IntoParams)]
+pub struct InvitationFilter {
+    /// Total amount of results to return
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub limit: Option<u64>,
+    /// Offset where to start returning results
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub offset: Option<u64>,
+    /// List of IDs
+    pub ids: Option<Vec<Uuid>>,
+    /// Whether the invitation was used
+    pub used: Option<bool>,
+    /// Direction of the ordering of results
+    #[serde(default)]
+    pub order_direction: OrderDirection,
+}
+
+/// Password Reset
+///
+/// One-time token, issued by an administrator, to set a new password for a local account.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasswordReset {
+    /// Account whose password can be reset
+    pub account_id: Uuid,
+    /// Reset token (only returned once, to be handed to the account owner)
+    pub token: String,
+    /// Date of expiration
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Create Password Reset Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct CreatePasswordResetRequest {
+    /// Account whose password can be reset
+    pub account_id: Uuid,
+}
+
+/// Reset Password Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct ResetPasswordRequest {
+    /// Password reset token
+    pub token: String,
+    /// New user password
+    #[schema(example = "new-password")]
+    pub new_password: String,
 }
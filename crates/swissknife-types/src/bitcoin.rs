@@ -20,6 +20,13 @@ pub struct BtcAddress {
     pub used: bool,
     /// Address type
     pub address_type: BtcAddressType,
+    /// BIP21 URI for the address, carrying a `pj=` payjoin endpoint. Only set when payjoin receiving is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "bitcoin:bc1q...?pj=https://api.numeraire.tech/v1/payjoin")]
+    pub payjoin_uri: Option<String>,
+    /// Invoice this address was issued with, as the on-chain leg of a unified payment request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice_id: Option<Uuid>,
     /// Date of creation in database
     pub created_at: DateTime<Utc>,
     /// Date of update in database
@@ -37,6 +44,10 @@ pub enum BtcAddressType {
     #[default]
     P2wpkh,
     P2tr,
+    /// Static BIP352 silent payment address. Each payment to it lands in a new taproot output
+    #[serde(rename = "silent_payment")]
+    #[strum(serialize = "silent_payment")]
+    SilentPayment,
 }
 
 /// Bitcoin Output
@@ -76,6 +87,8 @@ pub enum BtcOutputStatus {
     Confirmed,
     Spent,
     Immature,
+    /// Dropped from the chain and the mempool, by a reorg or a double spend
+    Failed,
 }
 
 impl From<BtcOutputStatus> for InvoiceStatus {
@@ -85,10 +98,71 @@ impl From<BtcOutputStatus> for InvoiceStatus {
             BtcOutputStatus::Confirmed => InvoiceStatus::Settled,
             BtcOutputStatus::Spent => InvoiceStatus::Settled,
             BtcOutputStatus::Immature => InvoiceStatus::Pending,
+            BtcOutputStatus::Failed => InvoiceStatus::Expired,
         }
     }
 }
 
+/// Feerate estimate for an on-chain confirmation target.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+pub struct BtcFeerate {
+    /// Number of blocks within which the transaction should confirm
+    #[schema(example = 6)]
+    pub conf_target: u32,
+    /// Feerate, in satoshis per virtual byte
+    #[schema(example = 12)]
+    pub feerate_sat_vb: u32,
+}
+
+/// On-chain fee quote for a prospective withdrawal at a confirmation target.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+pub struct BtcFeeQuote {
+    /// Number of blocks within which the transaction should confirm
+    #[schema(example = 6)]
+    pub conf_target: u32,
+    /// Feerate, in satoshis per virtual byte
+    #[schema(example = 12)]
+    pub feerate_sat_vb: u32,
+    /// Transaction fee at this feerate, in millisatoshis
+    #[schema(example = 1692000)]
+    pub fee_msat: u64,
+}
+
+/// Payjoin (BIP78) receiver query parameters, as sent by the payjoin sender
+#[derive(Clone, Debug, Default, Deserialize, IntoParams)]
+pub struct PayjoinQueryParams {
+    /// Protocol version. Only version 1 is supported
+    pub v: Option<u32>,
+    /// Index of the sender output the receiver may reduce to pay for its additional input
+    #[serde(rename = "additionalfeeoutputindex")]
+    #[param(rename = "additionalfeeoutputindex")]
+    pub additional_fee_output_index: Option<usize>,
+    /// Maximum amount, in satoshis, the sender accepts to pay for the receiver's additional input
+    #[serde(rename = "maxadditionalfeecontribution")]
+    #[param(rename = "maxadditionalfeecontribution")]
+    pub max_additional_fee_contribution: Option<u64>,
+    /// Minimum feerate, in satoshis per virtual byte, the payjoin transaction must keep
+    #[serde(rename = "minfeerate")]
+    #[param(rename = "minfeerate")]
+    pub min_feerate: Option<f64>,
+    /// Whether the receiver is forbidden from substituting its output
+    #[serde(rename = "disableoutputsubstitution", default)]
+    #[param(rename = "disableoutputsubstitution")]
+    pub disable_output_substitution: bool,
+}
+
+/// Payjoin (BIP78) error response
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PayjoinErrorResponse {
+    /// Well-known BIP78 error code
+    #[schema(example = "original-psbt-rejected")]
+    pub error_code: String,
+    /// Error message
+    #[schema(example = "No output pays a deposit address.")]
+    pub message: String,
+}
+
 /// New Bitcoin Address Request
 #[derive(Deserialize, ToSchema, Serialize)]
 pub struct NewBtcAddressRequest {
@@ -7,7 +7,7 @@ use strum_macros::{Display, EnumString};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::{BtcOutput, Ledger, OrderDirection};
+use crate::{BtcAddress, BtcAddressType, BtcOutput, Ledger, LnUrlPayerData, OrderDirection};
 
 /// An incoming payment request, over Lightning and/or on-chain.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
@@ -21,6 +21,32 @@ pub struct Invoice {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ln_address_id: Option<Uuid>,
 
+    /// Lightning Address username the invoice was requested through, such as `username+tag` for sub-addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "dario_nakamoto+tips")]
+    pub ln_address_alias: Option<String>,
+
+    /// Payer identity sent with the LNURL callback (LUD-18)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_data: Option<LnUrlPayerData>,
+
+    /// Incoming invoice this amount is a share of. Populated for Lightning Address payment splits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_invoice_id: Option<Uuid>,
+
+    /// Transfer ID shared with the outgoing payment. Populated for internal transfers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Uuid>,
+
+    /// LNURL-pay link the invoice was requested through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pay_link_id: Option<Uuid>,
+
+    /// Merchant reference of the payment, such as an order ID. Populated for LNURL-pay link invoices
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "order-1042")]
+    pub reference: Option<String>,
+
     /// Description
     pub description: Option<String>,
     /// Amount requested in millisatoshis.
@@ -96,6 +122,11 @@ pub struct LnInvoice {
 
     /// Date of expiry
     pub expires_at: DateTime<Utc>,
+
+    /// Payment preimage. Populated once the invoice is settled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "2a7b3f08f1c4ab2d...")]
+    pub payment_preimage: Option<String>,
 }
 
 /// Lifecycle status of an invoice.
@@ -120,6 +151,33 @@ pub struct NewInvoiceRequest {
     pub expiry: Option<u32>,
 }
 
+/// New Payment Request
+#[derive(Deserialize, ToSchema, Serialize)]
+pub struct NewPaymentRequest {
+    /// Wallet ID to receive into. Required by admin endpoints; derived from the path on wallet-scoped endpoints.
+    pub wallet_id: Option<Uuid>,
+    /// Amount in millisatoshis. Must be a whole number of satoshis to be payable on-chain
+    pub amount_msat: u64,
+    /// Description of the invoice. Visible by the payer
+    pub description: Option<String>,
+    /// Expiration time in seconds of the Lightning invoice
+    pub expiry: Option<u32>,
+    /// Type of the deposit address
+    pub address_type: Option<BtcAddressType>,
+}
+
+/// Unified payment request, payable over Lightning or on-chain. Once either leg is paid, both are settled.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct PaymentRequest {
+    /// BIP21 URI combining the deposit address, the amount and the Lightning invoice
+    #[schema(example = "bitcoin:bc1q...?amount=0.0001&message=Numeraire%20Invoice&lightning=lnbc100u1p...")]
+    pub uri: String,
+    /// Lightning leg. Its ID identifies the payment request
+    pub invoice: Invoice,
+    /// On-chain leg, a fresh deposit address dedicated to this request
+    pub address: BtcAddress,
+}
+
 /// Invoice query filter.
 #[serde_as]
 #[derive(Clone, Debug, Deserialize, Serialize, Default, IntoParams, ToSchema)]
@@ -138,6 +196,14 @@ pub struct InvoiceFilter {
     pub status: Option<InvoiceStatus>,
     /// Ledger
     pub ledger: Option<Ledger>,
+    /// Lightning Address ID
+    pub ln_address_id: Option<Uuid>,
+    /// Lightning Address username the invoice was requested through, such as `username+tag`
+    pub ln_address_alias: Option<String>,
+    /// LNURL-pay link ID
+    pub pay_link_id: Option<Uuid>,
+    /// Merchant reference of the payment
+    pub reference: Option<String>,
     /// Order by
     #[serde(default)]
     pub order_by: InvoiceOrderBy,
@@ -8,6 +8,7 @@
 
 mod account;
 mod api_key;
+mod audit;
 mod auth;
 mod bitcoin;
 mod error;
@@ -16,6 +17,9 @@ mod ln_address;
 mod lnurl;
 mod network;
 mod nostr;
+mod organization;
+mod passkey;
+mod pay_link;
 mod payment;
 mod permission;
 mod query;
@@ -27,18 +31,51 @@ pub use account::{
     Account, AccountFilter, AccountPreferences, AuthIdentity, CreateAccountRequest, UpdateAccountPermissionsRequest,
     UpdateAccountPreferencesRequest, UpdateAccountRequest,
 };
-pub use api_key::{ApiKey, ApiKeyFilter, CreateApiKeyRequest};
-pub use auth::{AuthProvider, ChangePasswordRequest, SignInRequest, SignInResponse, SignUpRequest};
-pub use bitcoin::{BtcAddress, BtcAddressFilter, BtcAddressType, BtcOutput, BtcOutputStatus, NewBtcAddressRequest};
+pub use api_key::{ApiKey, ApiKeyFilter, CreateApiKeyRequest, RotateApiKeyRequest};
+pub use audit::{AuditDiff, AuditEntry, AuditFilter};
+pub use auth::{
+    AuthProvider, ChangePasswordRequest, CreateInvitationRequest, CreatePasswordResetRequest, Invitation,
+    InvitationFilter, LinkNostrKeyRequest, PasswordReset, RefreshTokenRequest, RegisterRequest, ResetPasswordRequest,
+    SignInRequest, SignInResponse, SignUpRequest, TotpCodeRequest, TotpEnrollment, TotpRecoveryCodes,
+};
+pub use bitcoin::{
+    BtcAddress, BtcAddressFilter, BtcAddressType, BtcFeeQuote, BtcFeerate, BtcOutput, BtcOutputStatus,
+    NewBtcAddressRequest, PayjoinErrorResponse, PayjoinQueryParams,
+};
 pub use error::ErrorResponse;
-pub use invoice::{Invoice, InvoiceFilter, InvoiceOrderBy, InvoiceStatus, LnInvoice, NewInvoiceRequest};
-pub use ln_address::{LnAddress, LnAddressFilter, RegisterLnAddressRequest, UpdateLnAddressRequest};
-pub use lnurl::{LNUrlpInvoiceQueryParams, LnURLPayRequest, LnUrlCallback, LnUrlPaySuccessAction, LnUrlSuccessAction};
+pub use invoice::{
+    Invoice, InvoiceFilter, InvoiceOrderBy, InvoiceStatus, LnInvoice, NewInvoiceRequest, NewPaymentRequest,
+    PaymentRequest,
+};
+pub use ln_address::{
+    LnAddress, LnAddressBip353Record, LnAddressDomain, LnAddressFilter, LnAddressPayProfile, LnAddressPayerData,
+    LnAddressSplit, LnAddressSuccessAction, RegisterLnAddressDomainRequest, RegisterLnAddressRequest,
+    UpdateLnAddressRequest,
+};
+pub use lnurl::{
+    LNUrlpInvoiceQueryParams, LnURLPayRequest, LnUrlCallback, LnUrlPayPreview, LnUrlPaySuccessAction, LnUrlPayerData,
+    LnUrlPayerDataAuth, LnUrlPayerDataAuthField, LnUrlPayerDataField, LnUrlPayerDataRequest, LnUrlSuccessAction,
+    LnUrlVerifyResponse,
+};
 pub use network::BtcNetwork;
 pub use nostr::{NostrNIP05QueryParams, NostrNIP05Response};
+pub use organization::{
+    AddOrganizationMemberRequest, CreateOrganizationRequest, Organization, OrganizationMember, OrganizationRole,
+    UpdateOrganizationMemberRequest, UpdateOrganizationRequest,
+};
+pub use passkey::{
+    Passkey, PasskeyAssertion, PasskeyAssertionResponse, PasskeyAttestation, PasskeyAttestationResponse,
+    PasskeyAuthenticationOptions, PasskeyAuthenticatorSelection, PasskeyCreationOptions, PasskeyCredentialDescriptor,
+    PasskeyCredentialParameters, PasskeyRegistrationOptions, PasskeyRelyingParty, PasskeyRequestOptions,
+    PasskeySignInRequest, PasskeyUser, RegisterPasskeyRequest,
+};
+pub use pay_link::{
+    PayLink, PayLinkCallbackQueryParams, PayLinkFilter, PayLinkQueryParams, RegisterPayLinkRequest,
+    UpdatePayLinkRequest,
+};
 pub use payment::{
     BtcPayment, InternalPayment, LnPayment, Payment, PaymentFeeEstimate, PaymentFilter, PaymentStatus,
-    SendPaymentRequest,
+    PreviewPaymentRequest, SendPaymentRequest, TransferRequest,
 };
 pub use permission::Permission;
 pub use query::OrderDirection;
@@ -8,7 +8,10 @@ use uuid::Uuid;
 use crate::OrderDirection;
 
 /// Lightning Address
-#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+///
+/// An account can hold several addresses, each routed to its own wallet. Sub-addresses such as
+/// `username+tag@domain` are received by the `username` address.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
 pub struct LnAddress {
     /// Internal ID
     pub id: Uuid,
@@ -18,6 +21,9 @@ pub struct LnAddress {
     pub wallet_id: Uuid,
     /// Username
     pub username: String,
+    /// Managed domain of the address. Unset for the instance's configured domain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_id: Option<Uuid>,
     /// Active status. Inactive addresses cannot receive funds
     pub active: bool,
     /// Nostr enabled
@@ -26,6 +32,12 @@ pub struct LnAddress {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[schema(value_type = Option<String>, example = "d9c2ec59a98c...")]
     pub nostr_pubkey: Option<PublicKey>,
+    /// LNURL-pay profile served for this address
+    #[serde(default)]
+    pub pay_profile: LnAddressPayProfile,
+    /// Shares of every received payment passed on to other wallets. The receiving wallet keeps the rest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<LnAddressSplit>,
     /// Date of creation in database
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -42,6 +54,10 @@ pub struct RegisterLnAddressRequest {
     /// Username such as `username@domain`
     pub username: String,
 
+    /// Domain of the address. Defaults to the instance's configured domain
+    #[schema(example = "numeraire.tech")]
+    pub domain: Option<String>,
+
     /// Nostr enabled
     #[serde(default)]
     pub allows_nostr: bool,
@@ -49,6 +65,18 @@ pub struct RegisterLnAddressRequest {
     /// Nostr public key
     #[schema(value_type = Option<String>, example = "npub1m8pwckdf3...")]
     pub nostr_pubkey: Option<PublicKey>,
+
+    /// Wallet that receives invoices generated for this address. Defaults to the account's BTC wallet
+    #[serde(default)]
+    pub wallet_id: Option<Uuid>,
+
+    /// LNURL-pay profile, such as the description shown to payers
+    #[serde(default)]
+    pub pay_profile: Option<LnAddressPayProfile>,
+
+    /// Shares of every received payment passed on to other wallets
+    #[serde(default)]
+    pub splits: Vec<LnAddressSplit>,
 }
 
 /// Update Lightning Address Request
@@ -57,6 +85,10 @@ pub struct UpdateLnAddressRequest {
     /// Username such as `username@domain`
     pub username: Option<String>,
 
+    /// Wallet that receives invoices generated for this address
+    #[serde(default)]
+    pub wallet_id: Option<Uuid>,
+
     /// Active status
     #[serde(default)]
     pub active: Option<bool>,
@@ -68,6 +100,153 @@ pub struct UpdateLnAddressRequest {
     /// Nostr public key
     #[schema(value_type = Option<String>, example = "npub1m8pwckdf3...")]
     pub nostr_pubkey: Option<PublicKey>,
+
+    /// LNURL-pay profile. Replaces the current profile entirely when set
+    pub pay_profile: Option<LnAddressPayProfile>,
+
+    /// Payment splits. Replaces the current splits entirely when set, an empty list removes them
+    #[serde(default)]
+    pub splits: Option<Vec<LnAddressSplit>>,
+}
+
+/// Share of the payments received by a Lightning Address passed on to another wallet.
+///
+/// Shares are settled as internal transfers from the receiving wallet when the payment is received.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnAddressSplit {
+    /// Wallet receiving the share
+    pub wallet_id: Uuid,
+
+    /// Share of the received amount in basis points, where 10000 is the whole amount
+    #[schema(example = 1000)]
+    pub share_bps: u16,
+}
+
+/// LNURL-pay profile of a Lightning Address.
+///
+/// Unset fields fall back to the server defaults.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnAddressPayProfile {
+    /// Min amount in millisatoshis accepted by the address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 1000)]
+    pub min_sendable: Option<u64>,
+
+    /// Max amount in millisatoshis accepted by the address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 250000000)]
+    pub max_sendable: Option<u64>,
+
+    /// Number of characters accepted for payer comments. 0 disables comments (LUD-12)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 255)]
+    pub comment_allowed: Option<u16>,
+
+    /// Description shown to the payer as `text/plain` metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Coffee at Dario's")]
+    pub description: Option<String>,
+
+    /// Avatar served as `image/png;base64` metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "iVBORw0KGgoAAAANSUhEUgAA...")]
+    pub image: Option<String>,
+
+    /// Success action returned with every invoice (LUD-09)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_action: Option<LnAddressSuccessAction>,
+
+    /// Payer data requested from payers (LUD-18). Defaults to an optional name, pubkey, identifier and email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_data: Option<LnAddressPayerData>,
+}
+
+/// Payer data requested by a Lightning Address.
+///
+/// `true` makes a field mandatory, `false` optional. Unset fields are not requested.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnAddressPayerData {
+    /// Payer name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<bool>,
+    /// Payer secp256k1 public key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<bool>,
+    /// Payer identifier, such as a Lightning Address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<bool>,
+    /// Payer email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<bool>,
+}
+
+/// Success action configured on a Lightning Address.
+///
+/// AES secrets are stored in clear and encrypted with the invoice preimage on every callback (LUD-10).
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+#[serde(tag = "tag", rename_all = "lowercase")]
+pub enum LnAddressSuccessAction {
+    /// Message displayed to the payer
+    Message { message: String },
+    /// URL for the payer to open, with a description
+    Url { description: String, url: String },
+    /// Secret revealed to the payer once the invoice is paid
+    Aes { description: String, secret: String },
+}
+
+/// Lightning Address Domain
+///
+/// Additional domain served by the instance next to its configured domain.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct LnAddressDomain {
+    /// Internal ID
+    pub id: Uuid,
+    /// Domain of the addresses, such as `username@domain`
+    #[schema(example = "numeraire.tech")]
+    pub name: String,
+    /// Public URL serving the LNURL callbacks of the domain
+    #[schema(example = "https://pay.numeraire.tech")]
+    pub host: String,
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Date of update in database
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Register Lightning Address Domain Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct RegisterLnAddressDomainRequest {
+    /// Domain of the addresses, such as `username@domain`
+    #[schema(example = "numeraire.tech")]
+    pub name: String,
+
+    /// Public URL serving the LNURL callbacks of the domain
+    #[schema(example = "https://pay.numeraire.tech")]
+    pub host: String,
+}
+
+/// BIP353 DNS Record
+///
+/// TXT record publishing the payment instructions of a Lightning Address as `₿username@domain`. The zone must
+/// be signed with DNSSEC for payers to accept it.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnAddressBip353Record {
+    /// Human-readable name the record is published for
+    #[schema(example = "₿satoshi@numeraire.tech")]
+    pub bip353_name: String,
+    /// Fully qualified name of the record
+    #[schema(example = "satoshi.user._bitcoin-payment.numeraire.tech.")]
+    pub name: String,
+    /// Record TTL in seconds
+    #[schema(example = 3600)]
+    pub ttl: u32,
+    /// Payment instructions held by the record
+    #[schema(example = "bitcoin:?sp=sp1qq...")]
+    pub value: String,
+    /// Record in zone file format
+    #[schema(example = "satoshi.user._bitcoin-payment.numeraire.tech. 3600 IN TXT \"bitcoin:?sp=sp1qq...\"")]
+    pub zone_record: String,
 }
 
 /// Lightning address query filter.
@@ -88,6 +267,8 @@ pub struct LnAddressFilter {
     pub wallet_id: Option<Uuid>,
     /// Username
     pub username: Option<String>,
+    /// Managed domain ID
+    pub domain_id: Option<Uuid>,
     /// Active
     pub active: Option<bool>,
     /// Direction of the ordering of results
@@ -16,12 +16,33 @@ pub struct LnUrlCallback {
     pub disposable: Option<bool>,
     /// array with payment routes, should be left empty if no routes are to be provided
     pub routes: Vec<String>,
+    /// URL to check whether the invoice has been paid. See [LUD-21](https://github.com/lnurl/luds/blob/luds/21.md)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "https://numeraire.tech/lnurlp/dario_nakamoto/verify/b587c7f76339e3fb87ad2b...")]
+    pub verify: Option<String>,
+}
+
+/// LNURL-pay verify response. Reports the settlement status of an invoice
+/// issued by a callback (LUD-21).
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct LnUrlVerifyResponse {
+    /// Always `OK`
+    #[schema(example = "OK")]
+    pub status: String,
+    /// Whether the invoice has been paid
+    pub settled: bool,
+    /// Hex-encoded payment preimage. Only returned once the invoice is settled
+    #[schema(example = "2a7b3f08f1c4ab2d...")]
+    pub preimage: Option<String>,
+    /// bech32-serialized Lightning invoice
+    #[schema(example = "lnbcrt1m1png24kasp5...")]
+    pub pr: String,
 }
 
 /// LNURL success action shown to the payer after a successful payment (LUD-09).
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Default)]
 pub struct LnUrlSuccessAction {
-    /// Action type. One of `url` or `message`
+    /// Action type. One of `url`, `message` or `aes`
     pub tag: String,
 
     /// Message displayed to the user
@@ -35,6 +56,14 @@ pub struct LnUrlSuccessAction {
     /// URL for the user to open on success
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+
+    /// Base64 AES-256-CBC ciphertext, decrypted with the payment preimage (LUD-10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ciphertext: Option<String>,
+
+    /// Base64 initialization vector of the ciphertext
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iv: Option<String>,
 }
 
 /// Validated LNURL success action retained internally until payment settlement.
@@ -92,6 +121,133 @@ pub struct LnURLPayRequest {
     /// Nostr public key
     #[schema(value_type = Option<String>, example = "d9c2ec59a98c...")]
     pub nostr_pubkey: Option<PublicKey>,
+
+    /// Payer identity data accepted on the callback. See [LUD-18](https://github.com/lnurl/luds/blob/luds/18.md)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_data: Option<LnUrlPayerDataRequest>,
+}
+
+/// LNURL-pay Preview
+///
+/// Terms of a remote LNURL-pay service (LUD-06), to render a confirmation screen before paying.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnUrlPayPreview {
+    /// Lightning Address paid, when the input is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "dario_nakamoto@numeraire.tech")]
+    pub ln_address: Option<String>,
+
+    /// Minimum amount accepted, in millisatoshis
+    #[schema(example = 1000)]
+    pub min_sendable: u64,
+
+    /// Maximum amount accepted, in millisatoshis
+    #[schema(example = 250000000)]
+    pub max_sendable: u64,
+
+    /// Maximum comment length in characters. 0 when comments are not accepted. See [LUD-12](https://github.com/lnurl/luds/blob/luds/12.md)
+    #[schema(example = 255)]
+    pub comment_allowed: u16,
+
+    /// Short description of the payment (`text/plain` metadata)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "dario_nakamoto never refuses sats")]
+    pub description: Option<String>,
+
+    /// Long description of the payment (`text/long-desc` metadata)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_description: Option<String>,
+
+    /// Image of the recipient as a data URI (`image/png;base64` or `image/jpeg;base64` metadata)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "data:image/png;base64,iVBORw0KGgo...")]
+    pub image: Option<String>,
+
+    /// Raw metadata, committed to by the description hash of the invoice
+    #[schema(example = "[[\"text/plain\",\"dario_nakamoto never refuses sats\"]]")]
+    pub metadata: String,
+
+    /// Payer data requested by the service. See [LUD-18](https://github.com/lnurl/luds/blob/luds/18.md)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer_data: Option<LnUrlPayerDataRequest>,
+}
+
+/// Payer data requested by a LNURL-pay service (LUD-18).
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnUrlPayerDataRequest {
+    /// Payer name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<LnUrlPayerDataField>,
+
+    /// Payer secp256k1 public key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<LnUrlPayerDataField>,
+
+    /// Payer identifier, such as a Lightning Address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<LnUrlPayerDataField>,
+
+    /// Payer email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<LnUrlPayerDataField>,
+
+    /// LNURL-auth signature of `k1` (LUD-04)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<LnUrlPayerDataAuthField>,
+}
+
+/// Requested payer data field.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnUrlPayerDataField {
+    /// Whether the callback is refused without this field
+    pub mandatory: bool,
+}
+
+/// Requested LNURL-auth payer data field.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnUrlPayerDataAuthField {
+    /// Whether the callback is refused without this field
+    pub mandatory: bool,
+
+    /// Hex-encoded challenge to sign
+    #[schema(example = "e2af6254a8df433264fa23f67eb8188635d15ce883e8fc020989d5f82ae6f11e")]
+    pub k1: String,
+}
+
+/// Payer data sent with a LNURL-pay callback (LUD-18).
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnUrlPayerData {
+    /// Payer name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Hex-encoded payer secp256k1 public key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
+
+    /// Payer identifier, such as a Lightning Address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "dario_nakamoto@numeraire.tech")]
+    pub identifier: Option<String>,
+
+    /// Payer email
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// LNURL-auth proof
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<LnUrlPayerDataAuth>,
+}
+
+/// LNURL-auth proof sent as payer data.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, ToSchema, PartialEq, Eq)]
+pub struct LnUrlPayerDataAuth {
+    /// Hex-encoded linking public key
+    pub key: String,
+    /// Hex-encoded challenge from the `payRequest`
+    pub k1: String,
+    /// Hex-encoded DER signature of `k1`
+    pub sig: String,
 }
 
 /// LNURL-pay callback query parameters
@@ -101,4 +257,6 @@ pub struct LNUrlpInvoiceQueryParams {
     pub amount: u64,
     /// Optional comment for the recipient
     pub comment: Option<String>,
+    /// Optional payer data JSON (LUD-18). Committed to the invoice description hash
+    pub payerdata: Option<String>,
 }
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::Permission;
+
+/// Role of a member in an organization.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, EnumString, Display, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum OrganizationRole {
+    /// Reads wallets, balances and transactions.
+    Viewer,
+    /// Viewer who also receives and sends payments.
+    Accountant,
+    /// Accountant who also manages wallets, Lightning addresses, API keys and members other than owners.
+    Admin,
+    /// Admin who also manages the organization itself and its owners.
+    Owner,
+}
+
+impl OrganizationRole {
+    /// Permissions of the role over the organization's resources.
+    pub fn permissions(&self) -> Vec<Permission> {
+        let mut permissions = vec![
+            Permission::ReadAccount,
+            Permission::ReadWallet,
+            Permission::ReadLnAddress,
+            Permission::ReadTransaction,
+            Permission::ReadBtcAddress,
+        ];
+
+        if *self >= Self::Accountant {
+            permissions.extend([Permission::WriteTransaction, Permission::WriteBtcAddress]);
+        }
+
+        if *self >= Self::Admin {
+            permissions.extend([
+                Permission::WriteWallet,
+                Permission::WriteLnAddress,
+                Permission::ReadApiKey,
+                Permission::WriteApiKey,
+            ]);
+        }
+
+        if *self == Self::Owner {
+            permissions.push(Permission::WriteAccount);
+        }
+
+        permissions
+    }
+}
+
+/// Organization
+///
+/// Shared owner of wallets, backed by an account that members act for.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct Organization {
+    /// Internal ID, which is also the ID of the account owning the organization's resources
+    pub id: Uuid,
+    /// Name of the organization
+    #[schema(example = "Numeraire")]
+    pub name: String,
+    /// Role of the caller in the organization
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<OrganizationRole>,
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+    /// Date of update in database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Organization Member
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct OrganizationMember {
+    /// Organization ID
+    pub organization_id: Uuid,
+    /// Account ID of the member
+    pub account_id: Uuid,
+    /// Role of the member
+    pub role: OrganizationRole,
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+    /// Date of update in database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Create Organization Request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct CreateOrganizationRequest {
+    /// Name of the organization. The caller becomes its first owner.
+    #[schema(example = "Numeraire")]
+    pub name: String,
+}
+
+/// Update Organization Request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateOrganizationRequest {
+    /// Name of the organization
+    #[schema(example = "Numeraire")]
+    pub name: String,
+}
+
+/// Add Organization Member Request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AddOrganizationMemberRequest {
+    /// Account ID of the new member
+    pub account_id: Uuid,
+    /// Role of the new member
+    pub role: OrganizationRole,
+}
+
+/// Update Organization Member Request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateOrganizationMemberRequest {
+    /// New role of the member
+    pub role: OrganizationRole,
+}
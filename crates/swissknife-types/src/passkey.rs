@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Passkey
+///
+/// WebAuthn credential used to sign in to a local account without a password.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct Passkey {
+    /// Internal ID
+    pub id: Uuid,
+    /// Owning account ID
+    pub account_id: Uuid,
+    /// Name given to the passkey, such as the device or password manager holding it
+    #[schema(example = "MacBook")]
+    pub name: String,
+    /// Login identity the passkey signs in to. Internal only.
+    #[serde(skip)]
+    pub identity_id: Uuid,
+    /// WebAuthn credential ID. Internal only.
+    #[serde(skip)]
+    pub credential_id: Vec<u8>,
+    /// COSE public key of the credential. Internal only.
+    #[serde(skip)]
+    pub public_key: Vec<u8>,
+    /// Signature counter of the authenticator. Internal only.
+    #[serde(skip)]
+    pub sign_count: u32,
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+    /// Date the passkey was last used to sign in
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Passkey Registration Options
+///
+/// Start of a passkey registration. `public_key` is passed to `navigator.credentials.create()`, after
+/// `PublicKeyCredential.parseCreationOptionsFromJSON()`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyRegistrationOptions {
+    /// ID of the ceremony, sent back with the created credential
+    pub ceremony_id: Uuid,
+    /// WebAuthn `PublicKeyCredentialCreationOptions`, in JSON form
+    pub public_key: PasskeyCreationOptions,
+}
+
+/// WebAuthn `PublicKeyCredentialCreationOptions`, with binary fields encoded as base64url.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCreationOptions {
+    pub challenge: String,
+    pub rp: PasskeyRelyingParty,
+    pub user: PasskeyUser,
+    pub pub_key_cred_params: Vec<PasskeyCredentialParameters>,
+    /// Time to complete the ceremony, in milliseconds
+    pub timeout: u64,
+    /// Passkeys already registered to the account
+    pub exclude_credentials: Vec<PasskeyCredentialDescriptor>,
+    pub authenticator_selection: PasskeyAuthenticatorSelection,
+    #[schema(example = "none")]
+    pub attestation: String,
+}
+
+/// WebAuthn relying party, the domain of the dashboard or a parent domain of it.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyRelyingParty {
+    #[schema(example = "numeraire.tech")]
+    pub id: String,
+    #[schema(example = "Swissknife")]
+    pub name: String,
+}
+
+/// WebAuthn user entity of the account.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyUser {
+    /// Base64url user handle
+    pub id: String,
+    #[schema(example = "alice")]
+    pub name: String,
+    #[schema(example = "Alice")]
+    pub display_name: String,
+}
+
+/// WebAuthn credential type and COSE algorithm.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyCredentialParameters {
+    #[serde(rename = "type")]
+    #[schema(example = "public-key")]
+    pub credential_type: String,
+    #[schema(example = -7)]
+    pub alg: i64,
+}
+
+/// WebAuthn credential descriptor.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyCredentialDescriptor {
+    #[serde(rename = "type")]
+    #[schema(example = "public-key")]
+    pub credential_type: String,
+    /// Base64url credential ID
+    pub id: String,
+}
+
+/// WebAuthn authenticator selection criteria.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAuthenticatorSelection {
+    #[schema(example = "required")]
+    pub resident_key: String,
+    pub require_resident_key: bool,
+    #[schema(example = "required")]
+    pub user_verification: String,
+}
+
+/// Register Passkey Request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct RegisterPasskeyRequest {
+    /// ID of the registration ceremony
+    pub ceremony_id: Uuid,
+    /// Name given to the passkey
+    #[schema(example = "MacBook")]
+    pub name: Option<String>,
+    /// Result of `navigator.credentials.create()`, as returned by `PublicKeyCredential.toJSON()`
+    pub credential: PasskeyAttestation,
+}
+
+/// WebAuthn `RegistrationResponseJSON`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyAttestation {
+    /// Base64url credential ID
+    pub id: String,
+    pub response: PasskeyAttestationResponse,
+}
+
+/// WebAuthn `AuthenticatorAttestationResponseJSON`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAttestationResponse {
+    /// Base64url client data JSON
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// Base64url attestation object
+    pub attestation_object: String,
+}
+
+/// Passkey Authentication Options
+///
+/// Start of a passkey sign-in. `public_key` is passed to `navigator.credentials.get()`, after
+/// `PublicKeyCredential.parseRequestOptionsFromJSON()`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyAuthenticationOptions {
+    /// ID of the ceremony, sent back with the assertion
+    pub ceremony_id: Uuid,
+    /// WebAuthn `PublicKeyCredentialRequestOptions`, in JSON form
+    pub public_key: PasskeyRequestOptions,
+}
+
+/// WebAuthn `PublicKeyCredentialRequestOptions`, with binary fields encoded as base64url.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRequestOptions {
+    pub challenge: String,
+    /// Time to complete the ceremony, in milliseconds
+    pub timeout: u64,
+    #[schema(example = "numeraire.tech")]
+    pub rp_id: String,
+    /// Empty, as passkeys are discoverable credentials offered by the authenticator
+    pub allow_credentials: Vec<PasskeyCredentialDescriptor>,
+    #[schema(example = "required")]
+    pub user_verification: String,
+}
+
+/// Passkey Sign In Request
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeySignInRequest {
+    /// ID of the authentication ceremony
+    pub ceremony_id: Uuid,
+    /// Result of `navigator.credentials.get()`, as returned by `PublicKeyCredential.toJSON()`
+    pub credential: PasskeyAssertion,
+}
+
+/// WebAuthn `AuthenticationResponseJSON`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct PasskeyAssertion {
+    /// Base64url credential ID
+    pub id: String,
+    pub response: PasskeyAssertionResponse,
+}
+
+/// WebAuthn `AuthenticatorAssertionResponseJSON`.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAssertionResponse {
+    /// Base64url client data JSON
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// Base64url authenticator data
+    pub authenticator_data: String,
+    /// Base64url signature
+    pub signature: String,
+    /// Base64url user handle of the account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_handle: Option<String>,
+}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::OrderDirection;
+
+/// LNURL-pay Link
+///
+/// Reusable LNURL-pay endpoint of a wallet, such as one per cash register or product, printable as a static QR
+/// code. Links are not tied to a Lightning Address and tag every invoice they issue with a reference.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct PayLink {
+    /// Internal ID
+    pub id: Uuid,
+
+    /// Wallet that receives invoices generated for this link
+    pub wallet_id: Uuid,
+
+    /// Name, unique per wallet
+    #[schema(example = "Register 3")]
+    pub name: String,
+
+    /// Description shown to the payer as `text/plain` metadata. Defaults to the name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Coffee at Dario's")]
+    pub description: Option<String>,
+
+    /// Fixed amount in millisatoshis. Payers choose the amount when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = 21000)]
+    pub amount_msat: Option<u64>,
+
+    /// Reference attached to invoices paid without a per-payment reference
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "register-3")]
+    pub reference: Option<String>,
+
+    /// Active status. Inactive links cannot receive funds
+    pub active: bool,
+
+    /// Bech32 LNURL of the link (LUD-01), to print as a QR code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "lnurl1dp68gurn8ghj7...")]
+    pub lnurl: Option<String>,
+
+    /// Date of creation in database
+    pub created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Date of update in database
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Register LNURL-pay Link Request
+#[derive(Debug, Deserialize, ToSchema, Serialize)]
+pub struct RegisterPayLinkRequest {
+    /// Wallet that receives invoices generated for the link. Required for admin routes; wallet-scoped routes use the path.
+    pub wallet_id: Option<Uuid>,
+
+    /// Name, unique per wallet
+    #[schema(example = "Register 3")]
+    pub name: String,
+
+    /// Description shown to the payer. Defaults to the name
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Fixed amount in millisatoshis. Payers choose the amount when unset
+    #[serde(default)]
+    pub amount_msat: Option<u64>,
+
+    /// Reference attached to invoices paid without a per-payment reference
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+/// Update LNURL-pay Link Request
+#[derive(Debug, Default, Deserialize, ToSchema, Serialize)]
+pub struct UpdatePayLinkRequest {
+    /// Name, unique per wallet
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Description shown to the payer. An empty description falls back to the name
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Fixed amount in millisatoshis. 0 lets payers choose the amount
+    #[serde(default)]
+    pub amount_msat: Option<u64>,
+
+    /// Reference attached to invoices paid without a per-payment reference. An empty reference removes it
+    #[serde(default)]
+    pub reference: Option<String>,
+
+    /// Active status
+    #[serde(default)]
+    pub active: Option<bool>,
+}
+
+/// LNURL-pay Link query parameters
+///
+/// Encoding the link URL with a `reference` gives a QR code for a single order.
+#[derive(Debug, Default, Deserialize, Serialize, IntoParams)]
+pub struct PayLinkQueryParams {
+    /// Reference attached to the invoice, such as an order ID. Defaults to the reference of the link
+    pub reference: Option<String>,
+}
+
+/// LNURL-pay Link callback query parameters
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+pub struct PayLinkCallbackQueryParams {
+    /// Amount in millisatoshis
+    pub amount: u64,
+    /// Optional comment for the recipient
+    pub comment: Option<String>,
+    /// Reference attached to the invoice, such as an order ID. Defaults to the reference of the link
+    pub reference: Option<String>,
+}
+
+/// LNURL-pay Link query filter.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, IntoParams)]
+pub struct PayLinkFilter {
+    /// Total amount of results to return
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub limit: Option<u64>,
+
+    /// Offset where to start returning results
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub offset: Option<u64>,
+
+    /// List of IDs
+    pub ids: Option<Vec<Uuid>>,
+
+    /// Receiving wallet ID
+    pub wallet_id: Option<Uuid>,
+
+    /// Active
+    pub active: Option<bool>,
+
+    /// Direction of the ordering of results
+    #[serde(default)]
+    pub order_direction: OrderDirection,
+}
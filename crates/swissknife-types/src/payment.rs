@@ -5,7 +5,7 @@ use strum_macros::{Display, EnumString};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::{Ledger, LnUrlPaySuccessAction, LnUrlSuccessAction, OrderDirection};
+use crate::{BtcFeeQuote, Ledger, LnUrlPayPreview, LnUrlPaySuccessAction, LnUrlSuccessAction, OrderDirection};
 
 /// An outgoing payment, over Lightning, on-chain, or internal to the instance.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
@@ -126,6 +126,14 @@ pub struct InternalPayment {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[schema(example = "b587c7f76339e3fb87ad2b...")]
     pub payment_hash: Option<String>,
+
+    /// Incoming invoice this payment passes a share of. Populated for Lightning Address payment splits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_invoice_id: Option<Uuid>,
+
+    /// Transfer ID shared with the counterpart invoice. Populated for internal transfers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Uuid>,
 }
 
 /// Lifecycle status of a payment.
@@ -151,6 +159,54 @@ pub struct SendPaymentRequest {
     pub amount_msat: Option<u64>,
     /// Comment of the payment. Visible by the recipient for LNURL payments
     pub comment: Option<String>,
+
+    /// Confirmation target in blocks for on-chain payments. Mutually exclusive with `feerate_sat_vb`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 6)]
+    pub conf_target: Option<u32>,
+
+    /// Explicit feerate in satoshis per virtual byte for on-chain payments. Mutually exclusive with `conf_target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feerate_sat_vb: Option<u32>,
+}
+
+/// Preview Payment Request
+#[derive(Debug, Deserialize, Clone, ToSchema, Serialize)]
+pub struct PreviewPaymentRequest {
+    /// Wallet ID to pay from. Required by admin endpoints; derived from the path on wallet-scoped endpoints.
+    pub wallet_id: Option<Uuid>,
+
+    /// Recipient. Can be a LNURL or LN Address.
+    #[schema(example = "hello@numeraire.tech")]
+    pub input: String,
+}
+
+/// Transfer Request
+///
+/// Moves funds to another wallet on this instance without a Lightning invoice. Exactly one recipient must be set.
+#[derive(Debug, Deserialize, Clone, ToSchema, Serialize)]
+pub struct TransferRequest {
+    /// Wallet ID to transfer from. Required by admin endpoints; derived from the path on wallet-scoped endpoints.
+    pub wallet_id: Option<Uuid>,
+
+    /// Recipient wallet ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_wallet_id: Option<Uuid>,
+
+    /// Recipient account ID. Funds go to its wallet holding the same asset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_account_id: Option<Uuid>,
+
+    /// Recipient Lightning Address on this instance. A bare username uses the default domain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "hello@numeraire.tech")]
+    pub to_ln_address: Option<String>,
+
+    /// Amount in millisatoshis.
+    pub amount_msat: u64,
+
+    /// Comment of the transfer. Visible by the recipient
+    pub comment: Option<String>,
 }
 
 /// Fee quote for a prospective outgoing payment.
@@ -180,6 +236,14 @@ pub struct PaymentFeeEstimate {
 
     /// Amount plus the Lightning cap or current on-chain fee, in millisatoshis.
     pub maximum_total_msat: u64,
+
+    /// On-chain fee at each standard confirmation target, for choosing between a slow and an urgent withdrawal.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub onchain_fees: Vec<BtcFeeQuote>,
+
+    /// Terms of the recipient service, for LNURL and Lightning Address payments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lnurl_pay: Option<LnUrlPayPreview>,
 }
 
 /// Payment query filter.
@@ -32,6 +32,8 @@ pub enum Permission {
     ReadBtcAddress,
     #[serde(rename = "write:btc_address")]
     WriteBtcAddress,
+    #[serde(rename = "read:audit")]
+    ReadAudit,
 }
 
 impl Permission {
@@ -51,6 +53,7 @@ impl Permission {
             Permission::WriteApiKey,
             Permission::ReadBtcAddress,
             Permission::WriteBtcAddress,
+            Permission::ReadAudit,
         ]
     }
 }
@@ -42,6 +42,11 @@ pub struct Balance {
     /// Amount available to spend.
     #[schema(example = 989999000)]
     pub available_msat: i64,
+
+    /// On-chain deposits seen but still awaiting the required confirmations
+    #[serde(default)]
+    #[schema(example = 50000000)]
+    pub pending_msat: u64,
 }
 
 /// A counterparty the wallet has paid, with the date of first contact.
@@ -102,7 +107,7 @@ pub struct Wallet {
     /// Optional account-specific display label.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
-    /// Lightning Address
+    /// Lightning Address. The oldest one when the wallet receives through several
     pub ln_address: Option<LnAddress>,
     /// Wallet balance
     pub balance: Balance,
@@ -136,7 +141,7 @@ pub struct WalletOverview {
     /// Optional account-specific display label.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
-    /// Lightning Address
+    /// Lightning Address. The oldest one when the wallet receives through several
     pub ln_address: Option<LnAddress>,
     /// Wallet balance
     pub balance: Balance,
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use http::StatusCode;
 use tower_http::timeout::TimeoutLayer;
@@ -8,10 +8,18 @@ use crate::{
         composition::{AppConfig, AuthProvider, LightningProvider},
         errors::{ApplicationError, ConfigError},
     },
-    domains::bitcoin::BitcoinWallet,
+    domains::{
+        bitcoin::{
+            BitcoinWallet, PayjoinReceiver, SilentPaymentKeys, SilentPayments, SILENT_PAYMENT_SPEND_SECRET_LABEL,
+        },
+        payment::DnsResolver,
+    },
     infra::{
+        axum::{ClientIpResolver, InMemoryRateLimitStore, RateLimitBackend, RateLimitStore, RateLimiter},
+        bitcoind::BitcoindRpcClient,
         database::sea_orm::SeaOrmStore,
-        jwt::{local::LocalAuthenticator, oauth2::OAuth2Authenticator, JWTAuthenticator},
+        dns::DohResolver,
+        jwt::{local::LocalAuthenticator, oauth2::OAuth2Authenticator, JWTAuthenticator, MultiIssuerAuthenticator},
         lightning::{
             cln::{ClnGrpcClient, ClnRestClient},
             lnd::{LndGrpcClient, LndRestClient},
@@ -27,8 +35,13 @@ pub struct AppAdapters {
     pub store: AppStore,
     pub ln_client: Arc<dyn LnClient>,
     pub timeout_layer: TimeoutLayer,
+    pub client_ip_resolver: ClientIpResolver,
+    pub rate_limiter: Option<RateLimiter>,
     pub bitcoin_wallet: Arc<dyn BitcoinWallet>,
     pub jwt_authenticator: Arc<dyn JWTAuthenticator>,
+    pub silent_payments: Option<Arc<SilentPayments>>,
+    pub payjoin: Option<Arc<PayjoinReceiver>>,
+    pub dns_resolver: Arc<dyn DnsResolver>,
 }
 
 impl AppAdapters {
@@ -37,15 +50,33 @@ impl AppAdapters {
 
         let timeout_layer = TimeoutLayer::with_status_code(StatusCode::REQUEST_TIMEOUT, web.request_timeout);
         let store = SeaOrmStore::connect(database).await?;
+        let client_ip_resolver = ClientIpResolver::new(web.client_ip_header);
+        let rate_limiter = web.rate_limit.enabled.then(|| {
+            let rate_limit_store: Arc<dyn RateLimitStore> = match web.rate_limit.backend {
+                RateLimitBackend::Memory => Arc::new(InMemoryRateLimitStore::default()),
+                RateLimitBackend::Database => store.rate_limit.clone(),
+            };
+            RateLimiter::new(web.rate_limit, rate_limit_store)
+        });
         let jwt_authenticator = get_authenticator(config.clone()).await?;
-        let lightning = get_ln_client(config).await?;
+        let payjoin = get_payjoin(config.clone()).await?;
+        let lightning = get_ln_client(config.clone()).await?;
+        let dns_resolver = Arc::new(
+            DohResolver::new(config.dns_resolver.clone()).map_err(|e| ConfigError::DnsResolver(e.to_string()))?,
+        );
+        let silent_payments = get_silent_payments(config, lightning.bitcoin_wallet.as_ref()).await?;
 
         Ok(AppAdapters {
             store,
             ln_client: lightning.ln_client,
             timeout_layer,
+            client_ip_resolver,
+            rate_limiter,
             bitcoin_wallet: lightning.bitcoin_wallet,
             jwt_authenticator,
+            silent_payments,
+            payjoin,
+            dns_resolver,
         })
     }
 }
@@ -56,15 +87,19 @@ struct LightningAdapter {
 }
 
 async fn get_authenticator(config: AppConfig) -> Result<Arc<dyn JWTAuthenticator>, ApplicationError> {
-    match config.auth_provider {
+    let mut issuers = Vec::with_capacity(config.oauth2_issuers.len() + 1);
+    // Names namespace the subjects of each issuer, so they must tell issuers apart.
+    let mut names = HashSet::new();
+    let local = match config.auth_provider {
         AuthProvider::OAuth2 => {
             let oauth2_config = config
                 .oauth2
                 .clone()
                 .ok_or_else(|| ConfigError::MissingAuthProviderConfig(config.auth_provider.to_string()))?;
+            names.extend(oauth2_config.name().map(str::to_string));
 
-            let authenticator = OAuth2Authenticator::new(oauth2_config.clone()).await?;
-            Ok(Arc::new(authenticator) as Arc<dyn JWTAuthenticator>)
+            issuers.push(OAuth2Authenticator::new(oauth2_config).await?);
+            None
         }
         AuthProvider::Jwt => {
             let jwt_config = config
@@ -72,10 +107,69 @@ async fn get_authenticator(config: AppConfig) -> Result<Arc<dyn JWTAuthenticator
                 .clone()
                 .ok_or_else(|| ConfigError::MissingAuthProviderConfig(config.auth_provider.to_string()))?;
 
-            let authenticator = LocalAuthenticator::new(jwt_config.clone()).await?;
-            Ok(Arc::new(authenticator) as Arc<dyn JWTAuthenticator>)
+            Some(LocalAuthenticator::new(jwt_config).await?)
+        }
+        AuthProvider::Nostr => {
+            return Err(ConfigError::UnsupportedAuthProvider(config.auth_provider.to_string()).into())
+        }
+    };
+
+    for issuer_config in config.oauth2_issuers {
+        let name = issuer_config
+            .name()
+            .ok_or_else(|| ConfigError::InvalidOAuth2Issuer("missing name".to_string()))?;
+        if name.is_empty() || name.contains(':') {
+            return Err(ConfigError::InvalidOAuth2Issuer(format!("invalid name {name:?}")).into());
+        }
+        if !names.insert(name.to_string()) {
+            return Err(ConfigError::InvalidOAuth2Issuer(format!("duplicate name {name:?}")).into());
+        }
+
+        let issuer = OAuth2Authenticator::new(issuer_config).await?;
+        if issuers
+            .iter()
+            .any(|trusted| trusted.issuer_url() == issuer.issuer_url())
+        {
+            return Err(ConfigError::InvalidOAuth2Issuer(format!("duplicate issuer {}", issuer.issuer_url())).into());
         }
+        issuers.push(issuer);
     }
+
+    Ok(Arc::new(MultiIssuerAuthenticator::new(local, issuers)))
+}
+
+async fn get_silent_payments(
+    config: AppConfig,
+    bitcoin_wallet: &dyn BitcoinWallet,
+) -> Result<Option<Arc<SilentPayments>>, ApplicationError> {
+    let Some(silent_payments_config) = config.silent_payments else {
+        return Ok(None);
+    };
+
+    let spend_secret = bitcoin_wallet.derive_secret(SILENT_PAYMENT_SPEND_SECRET_LABEL).await?;
+    let keys = SilentPaymentKeys::from_node_secret(&silent_payments_config.scan_secret, &spend_secret)
+        .map_err(ConfigError::SilentPaymentKeys)?;
+    let chain = Arc::new(BitcoindRpcClient::new(silent_payments_config.bitcoind).await?);
+
+    Ok(Some(Arc::new(SilentPayments::new(
+        keys,
+        chain,
+        silent_payments_config.birth_height,
+    ))))
+}
+
+async fn get_payjoin(config: AppConfig) -> Result<Option<Arc<PayjoinReceiver>>, ApplicationError> {
+    let Some(payjoin_config) = config.payjoin else {
+        return Ok(None);
+    };
+
+    let chain = Arc::new(BitcoindRpcClient::new(payjoin_config.bitcoind).await?);
+
+    Ok(Some(Arc::new(PayjoinReceiver::new(
+        format!("{}/v1/payjoin", config.host),
+        chain,
+        payjoin_config.fallback_delay,
+    ))))
 }
 
 async fn get_ln_client(config: AppConfig) -> Result<LightningAdapter, ApplicationError> {
@@ -5,11 +5,13 @@ use strum_macros::{Display, EnumString};
 pub use swissknife_types::AuthProvider;
 
 use crate::{
-    domains::bitcoin::BtcAddressType,
+    domains::bitcoin::{BtcAddressType, DepositConfirmationPolicy},
     infra::{
         axum::AxumServerConfig,
-        config::config_rs::deserialize_duration,
+        bitcoind::BitcoindRpcConfig,
+        config::config_rs::{deserialize_duration, deserialize_period},
         database::sea_orm::SeaOrmConfig,
+        dns::DohResolverConfig,
         jwt::{local::JwtConfig, oauth2::OAuth2Config},
         lightning::{
             cln::{ClnClientConfig, ClnRestClientConfig},
@@ -27,11 +29,26 @@ pub struct AppConfig {
     pub host: String,
     pub auth_provider: AuthProvider,
     pub oauth2: Option<OAuth2Config>,
+    /// Further trusted OAuth2 issuers, alongside the `auth_provider`
+    #[serde(default)]
+    pub oauth2_issuers: Vec<OAuth2Config>,
     pub jwt: Option<JwtConfig>,
+    #[serde(default)]
+    pub local_auth: LocalAuthConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
     #[serde(deserialize_with = "deserialize_duration")]
     pub invoice_expiry: Duration,
     #[serde(default)]
     pub bitcoin_address_type: BtcAddressType,
+    #[serde(default)]
+    pub deposit_confirmations: DepositConfirmationPolicy,
+    pub payjoin: Option<PayjoinConfig>,
+    pub silent_payments: Option<SilentPaymentsConfig>,
+    #[serde(default)]
+    pub dns_resolver: DohResolverConfig,
+    #[serde(default = "default_onchain_sync_interval", deserialize_with = "deserialize_period")]
+    pub onchain_sync_interval: Duration,
     pub ln_provider: LightningProvider,
     pub database: SeaOrmConfig,
     pub cln_grpc_config: Option<ClnClientConfig>,
@@ -42,6 +59,122 @@ pub struct AppConfig {
     pub logging: TracingLoggerConfig,
 }
 
+/// Local accounts of the `JWT` auth provider, besides the bootstrap admin.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LocalAuthConfig {
+    /// Lets anyone register an account without an invitation. Such accounts have no permissions.
+    #[serde(default)]
+    pub self_registration: bool,
+    /// Default validity of invitations issued by administrators
+    #[serde(default = "default_invitation_expiry", deserialize_with = "deserialize_duration")]
+    pub invitation_expiry: Duration,
+    /// Validity of password reset tokens
+    #[serde(default = "default_password_reset_expiry", deserialize_with = "deserialize_duration")]
+    pub password_reset_expiry: Duration,
+    /// Validity of sign-in sessions, extended whenever their refresh token is used
+    #[serde(default = "default_refresh_token_expiry", deserialize_with = "deserialize_duration")]
+    pub refresh_token_expiry: Duration,
+    /// How long a verification of the second factor allows sensitive operations in a session
+    #[serde(default = "default_step_up_window", deserialize_with = "deserialize_duration")]
+    pub step_up_window: Duration,
+    /// Payments above this amount require a recent verification of the second factor
+    #[serde(default = "default_step_up_payment_threshold_msat")]
+    pub step_up_payment_threshold_msat: u64,
+    /// WebAuthn relying party ID of passkeys, the hostname of `host` when not set. Passkeys are bound to it and
+    /// stop working if it changes.
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub passkey_rp_id: Option<String>,
+    /// Origin of the dashboard performing passkey ceremonies, the origin of `host` when not set
+    #[serde(default, deserialize_with = "deserialize_optional_string")]
+    pub passkey_origin: Option<String>,
+    /// Time to complete a passkey registration or sign-in
+    #[serde(
+        default = "default_passkey_challenge_expiry",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub passkey_challenge_expiry: Duration,
+}
+
+impl Default for LocalAuthConfig {
+    fn default() -> Self {
+        Self {
+            self_registration: false,
+            invitation_expiry: default_invitation_expiry(),
+            password_reset_expiry: default_password_reset_expiry(),
+            refresh_token_expiry: default_refresh_token_expiry(),
+            step_up_window: default_step_up_window(),
+            step_up_payment_threshold_msat: default_step_up_payment_threshold_msat(),
+            passkey_rp_id: None,
+            passkey_origin: None,
+            passkey_challenge_expiry: default_passkey_challenge_expiry(),
+        }
+    }
+}
+
+fn default_invitation_expiry() -> Duration {
+    Duration::from_secs(7 * 24 * 60 * 60)
+}
+
+fn default_password_reset_expiry() -> Duration {
+    Duration::from_secs(60 * 60)
+}
+
+fn default_refresh_token_expiry() -> Duration {
+    Duration::from_secs(30 * 24 * 60 * 60)
+}
+
+fn default_step_up_window() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+fn default_step_up_payment_threshold_msat() -> u64 {
+    100_000_000
+}
+
+fn default_passkey_challenge_expiry() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+/// Audit log of state-changing requests.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AuditConfig {
+    /// Links every entry to the previous one by hash, so that altered or deleted entries break the chain. Entries
+    /// are then appended one at a time, across the instances sharing the database.
+    #[serde(default)]
+    pub hash_chain: bool,
+}
+
+/// Silent payment (BIP352) scan key and the full node scanned for payments. The spend key is derived from the
+/// seed of the Lightning node.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SilentPaymentsConfig {
+    pub scan_secret: String,
+    /// First block that can contain payments to the keys, where scanning starts
+    #[serde(default)]
+    pub birth_height: u32,
+    pub bitcoind: BitcoindRpcConfig,
+}
+
+/// BIP78 payjoin receiver, with the full node checking and broadcasting original transactions.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PayjoinConfig {
+    /// Time the sender has to broadcast the payjoin before we broadcast its original transaction
+    #[serde(
+        default = "default_payjoin_fallback_delay",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub fallback_delay: Duration,
+    pub bitcoind: BitcoindRpcConfig,
+}
+
+fn default_payjoin_fallback_delay() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_onchain_sync_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
 fn deserialize_optional_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
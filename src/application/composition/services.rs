@@ -3,7 +3,11 @@ use std::sync::Arc;
 use crate::{
     application::composition::{AppAdapters, AppConfig},
     domains::{
-        account::{AccountService, AccountUseCases, ApiKeyService, ApiKeyUseCases, AuthService, AuthUseCases},
+        account::{
+            AccountService, AccountUseCases, ApiKeyService, ApiKeyUseCases, AuthService, AuthUseCases,
+            OrganizationService, OrganizationUseCases,
+        },
+        audit::{AuditService, AuditUseCases},
         bitcoin::{BitcoinService, BitcoinUseCases},
         event::{EventService, EventUseCases},
         invoice::{InvoiceService, InvoiceUseCases},
@@ -27,7 +31,9 @@ pub struct AppServices {
     pub system: Arc<dyn SystemUseCases>,
     pub nostr: Box<dyn NostrUseCases>,
     pub api_key: Box<dyn ApiKeyUseCases>,
-    pub bitcoin: Box<dyn BitcoinUseCases>,
+    pub organization: Box<dyn OrganizationUseCases>,
+    pub audit: Box<dyn AuditUseCases>,
+    pub bitcoin: Arc<dyn BitcoinUseCases>,
     pub event: Arc<dyn EventUseCases>,
 }
 
@@ -38,7 +44,10 @@ impl AppServices {
             host,
             invoice_expiry,
             auth_provider,
+            local_auth,
+            audit,
             bitcoin_address_type,
+            deposit_confirmations,
             ..
         } = config;
 
@@ -47,16 +56,27 @@ impl AppServices {
             ln_client,
             bitcoin_wallet,
             jwt_authenticator,
+            silent_payments,
+            payjoin,
+            dns_resolver,
             ..
         } = adapters;
 
-        let event = Arc::new(EventService::new(store.clone()));
+        let reorg_window = deposit_confirmations.reorg_window;
+        let event = Arc::new(EventService::new(
+            store.clone(),
+            ln_client.clone(),
+            bitcoin_wallet.clone(),
+            deposit_confirmations,
+        ));
         let payments = PaymentService::new(
             store.clone(),
             ln_client.clone(),
             bitcoin_wallet.clone(),
             domain.clone(),
             event.clone(),
+            silent_payments.clone(),
+            dns_resolver,
         );
         let invoices = InvoiceService::new(
             store.clone(),
@@ -64,15 +84,17 @@ impl AppServices {
             invoice_expiry.as_secs() as u32,
             event.clone(),
             bitcoin_wallet.network(),
+            bitcoin_wallet.clone(),
+            bitcoin_address_type,
         );
         let lnurl = LnUrlService::new(
             store.clone(),
             ln_client.clone(),
             invoice_expiry.as_secs() as u32,
-            domain,
-            host,
+            domain.clone(),
+            host.clone(),
+            bitcoin_wallet.network(),
         );
-        let ln_address = LnAddressService::new(store.clone(), bitcoin_wallet.network());
         let account = AccountService::new(store.clone());
         let wallet = WalletService::new(store.clone());
         let auth = AuthService::new(
@@ -80,17 +102,25 @@ impl AppServices {
             store.clone(),
             auth_provider,
             bitcoin_wallet.network(),
+            local_auth,
+            host.clone(),
         );
         let system = Arc::new(SystemService::new(store.clone(), ln_client.clone()));
-        let nostr = NostrService::new(store.clone());
-        let api_key = ApiKeyService::new(store.clone());
-        let bitcoin = BitcoinService::new(
+        let bitcoin = Arc::new(BitcoinService::new(
             store.clone(),
-            bitcoin_wallet,
+            bitcoin_wallet.clone(),
             bitcoin_address_type,
             event.clone(),
             system.clone(),
-        );
+            reorg_window,
+            payjoin,
+            silent_payments,
+        ));
+        let ln_address = LnAddressService::new(store.clone(), bitcoin_wallet.network(), domain, host, bitcoin.clone());
+        let nostr = NostrService::new(store.clone());
+        let api_key = ApiKeyService::new(store.clone());
+        let organization = OrganizationService::new(store.clone());
+        let audit = AuditService::new(store.clone(), audit);
 
         AppServices {
             invoice: Box::new(invoices),
@@ -103,7 +133,9 @@ impl AppServices {
             system,
             nostr: Box::new(nostr),
             api_key: Box::new(api_key),
-            bitcoin: Box::new(bitcoin),
+            organization: Box::new(organization),
+            audit: Box::new(audit),
+            bitcoin,
             event,
         }
     }
@@ -125,6 +157,8 @@ pub struct MockAppServicesBuilder {
     pub system: crate::domains::system::MockSystemUseCases,
     pub nostr: crate::domains::nostr::MockNostrUseCases,
     pub api_key: crate::domains::account::MockApiKeyUseCases,
+    pub organization: crate::domains::account::MockOrganizationUseCases,
+    pub audit: crate::domains::audit::MockAuditUseCases,
     pub bitcoin: crate::domains::bitcoin::MockBitcoinUseCases,
     pub event: crate::domains::event::MockEventUseCases,
 }
@@ -143,6 +177,8 @@ impl MockAppServicesBuilder {
             system: crate::domains::system::MockSystemUseCases::new(),
             nostr: crate::domains::nostr::MockNostrUseCases::new(),
             api_key: crate::domains::account::MockApiKeyUseCases::new(),
+            organization: crate::domains::account::MockOrganizationUseCases::new(),
+            audit: crate::domains::audit::MockAuditUseCases::new(),
             bitcoin: crate::domains::bitcoin::MockBitcoinUseCases::new(),
             event: crate::domains::event::MockEventUseCases::new(),
         }
@@ -160,7 +196,9 @@ impl MockAppServicesBuilder {
             system: Arc::new(self.system),
             nostr: Box::new(self.nostr),
             api_key: Box::new(self.api_key),
-            bitcoin: Box::new(self.bitcoin),
+            organization: Box::new(self.organization),
+            audit: Box::new(self.audit),
+            bitcoin: Arc::new(self.bitcoin),
             event: Arc::new(self.event),
         }
     }
@@ -1,29 +1,49 @@
 use std::sync::Arc;
 
-use crate::domains::{
-    account::{AccountRepository, ApiKeyRepository},
-    asset::AssetRepository,
-    bitcoin::{BtcAddressRepository, BtcOutputRepository},
-    event::EventProjectionUnitOfWork,
-    invoice::InvoiceRepository,
-    ln_address::LnAddressRepository,
-    payment::{PaymentRepository, PaymentUnitOfWork},
-    system::{ConfigRepository, HealthProbe},
-    wallet::WalletRepository,
+use crate::{
+    domains::{
+        account::{
+            AccountRepository, ApiKeyRepository, CredentialRepository, InvitationRepository, NostrEventRepository,
+            OrganizationRepository, PasskeyRepository, SessionRepository, TotpRepository,
+        },
+        asset::AssetRepository,
+        audit::AuditRepository,
+        bitcoin::{BtcAddressRepository, BtcOutputRepository, PayjoinRepository},
+        event::EventProjectionUnitOfWork,
+        invoice::InvoiceRepository,
+        ln_address::{LnAddressDomainRepository, LnAddressRepository},
+        lnurl::PayLinkRepository,
+        payment::{PaymentRepository, PaymentUnitOfWork},
+        system::{ConfigRepository, HealthProbe},
+        wallet::WalletRepository,
+    },
+    infra::axum::RateLimitStore,
 };
 
 #[derive(Clone)]
 pub struct AppStore {
     pub ln_address: Arc<dyn LnAddressRepository>,
+    pub ln_address_domain: Arc<dyn LnAddressDomainRepository>,
+    pub pay_link: Arc<dyn PayLinkRepository>,
     pub payment: Arc<dyn PaymentRepository>,
     pub invoice: Arc<dyn InvoiceRepository>,
     pub wallet: Arc<dyn WalletRepository>,
     pub account: Arc<dyn AccountRepository>,
     pub asset: Arc<dyn AssetRepository>,
     pub api_key: Arc<dyn ApiKeyRepository>,
+    pub credential: Arc<dyn CredentialRepository>,
+    pub invitation: Arc<dyn InvitationRepository>,
+    pub session: Arc<dyn SessionRepository>,
+    pub totp: Arc<dyn TotpRepository>,
+    pub passkey: Arc<dyn PasskeyRepository>,
+    pub nostr_event: Arc<dyn NostrEventRepository>,
+    pub organization: Arc<dyn OrganizationRepository>,
+    pub audit: Arc<dyn AuditRepository>,
+    pub rate_limit: Arc<dyn RateLimitStore>,
     pub config: Arc<dyn ConfigRepository>,
     pub btc_address: Arc<dyn BtcAddressRepository>,
     pub btc_output: Arc<dyn BtcOutputRepository>,
+    pub payjoin: Arc<dyn PayjoinRepository>,
     pub health: Arc<dyn HealthProbe>,
     pub payment_uow: Arc<dyn PaymentUnitOfWork>,
     pub event_uow: Arc<dyn EventProjectionUnitOfWork>,
@@ -33,30 +53,54 @@ impl AppStore {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         ln_address: Arc<dyn LnAddressRepository>,
+        ln_address_domain: Arc<dyn LnAddressDomainRepository>,
+        pay_link: Arc<dyn PayLinkRepository>,
         payment: Arc<dyn PaymentRepository>,
         invoice: Arc<dyn InvoiceRepository>,
         wallet: Arc<dyn WalletRepository>,
         account: Arc<dyn AccountRepository>,
         asset: Arc<dyn AssetRepository>,
         api_key: Arc<dyn ApiKeyRepository>,
+        credential: Arc<dyn CredentialRepository>,
+        invitation: Arc<dyn InvitationRepository>,
+        session: Arc<dyn SessionRepository>,
+        totp: Arc<dyn TotpRepository>,
+        passkey: Arc<dyn PasskeyRepository>,
+        nostr_event: Arc<dyn NostrEventRepository>,
+        organization: Arc<dyn OrganizationRepository>,
+        audit: Arc<dyn AuditRepository>,
+        rate_limit: Arc<dyn RateLimitStore>,
         config: Arc<dyn ConfigRepository>,
         btc_address: Arc<dyn BtcAddressRepository>,
         btc_output: Arc<dyn BtcOutputRepository>,
+        payjoin: Arc<dyn PayjoinRepository>,
         health: Arc<dyn HealthProbe>,
         payment_uow: Arc<dyn PaymentUnitOfWork>,
         event_uow: Arc<dyn EventProjectionUnitOfWork>,
     ) -> Self {
         Self {
             ln_address,
+            ln_address_domain,
+            pay_link,
             payment,
             invoice,
             wallet,
             account,
             asset,
             api_key,
+            credential,
+            invitation,
+            session,
+            totp,
+            passkey,
+            nostr_event,
+            organization,
+            audit,
+            rate_limit,
             config,
             btc_address,
             btc_output,
+            payjoin,
             health,
             payment_uow,
             event_uow,
@@ -67,15 +111,27 @@ impl AppStore {
 #[cfg(test)]
 pub struct MockAppStoreBuilder {
     pub ln_address: crate::domains::ln_address::MockLnAddressRepository,
+    pub ln_address_domain: crate::domains::ln_address::MockLnAddressDomainRepository,
+    pub pay_link: crate::domains::lnurl::MockPayLinkRepository,
     pub payment: crate::domains::payment::MockPaymentRepository,
     pub invoice: crate::domains::invoice::MockInvoiceRepository,
     pub wallet: crate::domains::wallet::MockWalletRepository,
     pub account: crate::domains::account::MockAccountRepository,
     pub asset: crate::domains::asset::MockAssetRepository,
     pub api_key: crate::domains::account::MockApiKeyRepository,
+    pub credential: crate::domains::account::MockCredentialRepository,
+    pub invitation: crate::domains::account::MockInvitationRepository,
+    pub session: crate::domains::account::MockSessionRepository,
+    pub totp: crate::domains::account::MockTotpRepository,
+    pub passkey: crate::domains::account::MockPasskeyRepository,
+    pub nostr_event: crate::domains::account::MockNostrEventRepository,
+    pub organization: crate::domains::account::MockOrganizationRepository,
+    pub audit: crate::domains::audit::MockAuditRepository,
+    pub rate_limit: crate::infra::axum::MockRateLimitStore,
     pub config: crate::domains::system::MockConfigRepository,
     pub btc_address: crate::domains::bitcoin::MockBtcAddressRepository,
     pub btc_output: crate::domains::bitcoin::MockBtcOutputRepository,
+    pub payjoin: crate::domains::bitcoin::MockPayjoinRepository,
     pub health: crate::domains::system::MockHealthProbe,
     pub payment_uow: crate::domains::payment::MockPaymentUnitOfWork,
     pub event_uow: crate::domains::event::MockEventProjectionUnitOfWork,
@@ -86,15 +142,27 @@ impl MockAppStoreBuilder {
     pub fn new() -> Self {
         Self {
             ln_address: crate::domains::ln_address::MockLnAddressRepository::new(),
+            ln_address_domain: crate::domains::ln_address::MockLnAddressDomainRepository::new(),
+            pay_link: crate::domains::lnurl::MockPayLinkRepository::new(),
             payment: crate::domains::payment::MockPaymentRepository::new(),
             invoice: crate::domains::invoice::MockInvoiceRepository::new(),
             wallet: crate::domains::wallet::MockWalletRepository::new(),
             account: crate::domains::account::MockAccountRepository::new(),
             asset: crate::domains::asset::MockAssetRepository::new(),
             api_key: crate::domains::account::MockApiKeyRepository::new(),
+            credential: crate::domains::account::MockCredentialRepository::new(),
+            invitation: crate::domains::account::MockInvitationRepository::new(),
+            session: crate::domains::account::MockSessionRepository::new(),
+            totp: crate::domains::account::MockTotpRepository::new(),
+            passkey: crate::domains::account::MockPasskeyRepository::new(),
+            nostr_event: crate::domains::account::MockNostrEventRepository::new(),
+            organization: crate::domains::account::MockOrganizationRepository::new(),
+            audit: crate::domains::audit::MockAuditRepository::new(),
+            rate_limit: crate::infra::axum::MockRateLimitStore::new(),
             config: crate::domains::system::MockConfigRepository::new(),
             btc_address: crate::domains::bitcoin::MockBtcAddressRepository::new(),
             btc_output: crate::domains::bitcoin::MockBtcOutputRepository::new(),
+            payjoin: crate::domains::bitcoin::MockPayjoinRepository::new(),
             health: crate::domains::system::MockHealthProbe::new(),
             payment_uow: crate::domains::payment::MockPaymentUnitOfWork::new(),
             event_uow: crate::domains::event::MockEventProjectionUnitOfWork::new(),
@@ -104,15 +172,27 @@ impl MockAppStoreBuilder {
     pub fn build(self) -> AppStore {
         AppStore::new(
             Arc::new(self.ln_address),
+            Arc::new(self.ln_address_domain),
+            Arc::new(self.pay_link),
             Arc::new(self.payment),
             Arc::new(self.invoice),
             Arc::new(self.wallet),
             Arc::new(self.account),
             Arc::new(self.asset),
             Arc::new(self.api_key),
+            Arc::new(self.credential),
+            Arc::new(self.invitation),
+            Arc::new(self.session),
+            Arc::new(self.totp),
+            Arc::new(self.passkey),
+            Arc::new(self.nostr_event),
+            Arc::new(self.organization),
+            Arc::new(self.audit),
+            Arc::new(self.rate_limit),
             Arc::new(self.config),
             Arc::new(self.btc_address),
             Arc::new(self.btc_output),
+            Arc::new(self.payjoin),
             Arc::new(self.health),
             Arc::new(self.payment_uow),
             Arc::new(self.event_uow),
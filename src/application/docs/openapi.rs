@@ -3,11 +3,12 @@ use swissknife_types::{ErrorResponse, OrderDirection};
 use crate::{
     application::composition::Ledger,
     domains::{
-        account::{AccountHandler, ApiKeyHandler, AuthHandler},
-        bitcoin::BtcAddressHandler,
+        account::{AccountHandler, ApiKeyHandler, AuthHandler, OrganizationHandler},
+        audit::AuditHandler,
+        bitcoin::{BtcAddressHandler, BtcFeeHandler, PayjoinHandler},
         invoice::InvoiceHandler,
-        ln_address::LnAddressHandler,
-        lnurl::LnURLHandler,
+        ln_address::{LnAddressDomainHandler, LnAddressHandler},
+        lnurl::{LnURLHandler, PayLinkHandler},
         nostr::NostrHandler,
         payment::PaymentHandler,
         system::SystemHandler,
@@ -44,11 +45,17 @@ pub fn merged_openapi() -> OpenApi {
     openapi.merge(InvoiceHandler::openapi());
     openapi.merge(PaymentHandler::openapi());
     openapi.merge(LnAddressHandler::openapi());
+    openapi.merge(LnAddressDomainHandler::openapi());
     openapi.merge(LnURLHandler::openapi());
+    openapi.merge(PayLinkHandler::openapi());
     openapi.merge(NostrHandler::openapi());
     openapi.merge(SystemHandler::openapi());
     openapi.merge(ApiKeyHandler::openapi());
+    openapi.merge(OrganizationHandler::openapi());
+    openapi.merge(AuditHandler::openapi());
     openapi.merge(BtcAddressHandler::openapi());
+    openapi.merge(BtcFeeHandler::openapi());
+    openapi.merge(PayjoinHandler::openapi());
 
     openapi
 }
@@ -102,6 +109,13 @@ pub const UNPROCESSABLE_EXAMPLE: &str = r#"
 }
 "#;
 
+pub const TOO_MANY_REQUESTS_EXAMPLE: &str = r#"
+{
+    "status": "429 Too Many Requests",
+    "reason": "Too many requests, please retry later"
+}
+"#;
+
 pub const INTERNAL_EXAMPLE: &str = r#"
 {
     "status": "500 Internal Server Error",
@@ -3,7 +3,8 @@ use thiserror::Error;
 use crate::application::errors::BitcoinError;
 
 use super::{
-    AuthenticationError, AuthorizationError, ConfigError, DataError, DatabaseError, LightningError, WebServerError,
+    AuthenticationError, AuthorizationError, ConfigError, DataError, DatabaseError, LightningError, PayjoinError,
+    RateLimitError, WebServerError,
 };
 
 #[derive(Debug, Error)]
@@ -31,4 +32,10 @@ pub enum ApplicationError {
 
     #[error("Data Error: {0}")]
     Data(#[from] DataError),
+
+    #[error("Payjoin Error: {0}")]
+    Payjoin(#[from] PayjoinError),
+
+    #[error("Rate Limit Error: {0}")]
+    RateLimit(#[from] RateLimitError),
 }
@@ -35,6 +35,9 @@ pub enum AuthenticationError {
     #[error("Invalid credentials")]
     InvalidCredentials,
 
+    #[error("Missing two-factor authentication code")]
+    OtpRequired,
+
     #[error("Unsupported operation")]
     UnsupportedOperation,
 }
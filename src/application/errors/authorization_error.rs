@@ -1,9 +1,31 @@
 use thiserror::Error;
+use uuid::Uuid;
 
-use crate::domains::account::Permission;
+use crate::domains::account::{OrganizationRole, Permission};
 
 #[derive(Debug, Error)]
 pub enum AuthorizationError {
     #[error("Missing required permission: {0:?}")]
     MissingPermission(Permission),
+
+    #[error("Wallet {0} is outside the scope of the credentials")]
+    WalletNotAllowed(Uuid),
+
+    #[error("Wallet-scoped credentials cannot perform account-wide operations")]
+    WalletScoped,
+
+    #[error("Operation requires a recent verification of the second factor")]
+    StepUpRequired,
+
+    #[error("Operation requires a sign-in session")]
+    SessionRequired,
+
+    #[error("Account is not a member of organization {0}")]
+    NotMember(Uuid),
+
+    #[error("Operation requires the {0} role in the organization")]
+    RoleRequired(OrganizationRole),
+
+    #[error("Only the bootstrap admin can perform this operation on its account")]
+    BootstrapAdminRequired,
 }
@@ -17,18 +17,48 @@ pub enum BitcoinError {
     #[error("Failed to sign and send bitcoin transaction: {0}")]
     FinalizeTransaction(String),
 
+    #[error("Failed to sign bitcoin PSBT: {0}")]
+    SignPsbt(String),
+
+    #[error("Failed to list unspent bitcoin outputs: {0}")]
+    ListUnspent(String),
+
     #[error("Failed to broadcast bitcoin transaction: {0}")]
     BroadcastTransaction(String),
 
+    #[error("Bitcoin transaction spends outputs already spent on chain: {0}")]
+    InputsSpent(String),
+
     #[error("Failed to release prepared bitcoin transaction: {0}")]
     ReleaseTransaction(String),
 
+    #[error("Failed to lock bitcoin output: {0}")]
+    LockUtxo(String),
+
+    #[error("Failed to unlock bitcoin output: {0}")]
+    UnlockUtxo(String),
+
+    #[error("Failed to derive secret from the node: {0}")]
+    DeriveSecret(String),
+
+    #[error("Failed to test bitcoin transaction acceptance: {0}")]
+    TestMempoolAccept(String),
+
     #[error("Failed to get bitcoin output: {0}")]
     GetOutput(String),
 
     #[error("Failed to get bitcoin transaction: {0}")]
     GetTransaction(String),
 
+    #[error("Failed to estimate feerate: {0}")]
+    EstimateFee(String),
+
+    #[error("Failed to get block height: {0}")]
+    BlockHeight(String),
+
     #[error("Failed to synchronize bitcoin transactions: {0}")]
     Synchronize(String),
+
+    #[error("Failed to get bitcoin block: {0}")]
+    GetBlock(String),
 }
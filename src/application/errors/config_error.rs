@@ -10,4 +10,16 @@ pub enum ConfigError {
 
     #[error("Missing auth provider config: {0}")]
     MissingAuthProviderConfig(String),
+
+    #[error("Unsupported auth provider: {0}")]
+    UnsupportedAuthProvider(String),
+
+    #[error("Invalid OAuth2 issuer config: {0}")]
+    InvalidOAuth2Issuer(String),
+
+    #[error("Invalid silent payment keys: {0}")]
+    SilentPaymentKeys(String),
+
+    #[error("Invalid DNS resolver: {0}")]
+    DnsResolver(String),
 }
@@ -6,6 +6,8 @@ mod config_error;
 mod data_error;
 mod database_error;
 mod lightning_error;
+mod payjoin_error;
+mod rate_limit_error;
 mod web_server_error;
 
 pub use application_error::ApplicationError;
@@ -16,4 +18,6 @@ pub use config_error::ConfigError;
 pub use data_error::DataError;
 pub use database_error::DatabaseError;
 pub use lightning_error::LightningError;
+pub use payjoin_error::PayjoinError;
+pub use rate_limit_error::RateLimitError;
 pub use web_server_error::WebServerError;
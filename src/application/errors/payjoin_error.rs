@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PayjoinError {
+    #[error("Payjoin unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Payjoin version not supported: {0}")]
+    VersionUnsupported(String),
+
+    #[error("Original PSBT rejected: {0}")]
+    OriginalPsbtRejected(String),
+
+    #[error("Payjoin proposal rejected: {0}")]
+    ProposalRejected(String),
+
+    #[error("Payjoin request failed: {0}")]
+    Request(String),
+}
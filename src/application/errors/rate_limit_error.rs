@@ -0,0 +1,9 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    #[error("Rate limit {0} exceeded, retry after {1:?}")]
+    Exceeded(String, Duration),
+}
@@ -178,6 +178,7 @@ async fn replace_account_permissions(
     Json(payload): Json<UpdateAccountPermissionsRequest>,
 ) -> Result<Json<Account>, ApplicationError> {
     user.check_permission(Permission::WriteAccount)?;
+    services.auth.check_step_up(&user).await?;
     Ok(Json(
         services.account.update_permissions(id, payload.permissions).await?,
     ))
@@ -205,7 +206,7 @@ async fn delete_account_by_id(
     Path(id): Path<Uuid>,
 ) -> Result<(), ApplicationError> {
     user.check_permission(Permission::WriteAccount)?;
-    if id == user.account_id {
+    if id == user.principal_account_id() {
         return Err(DataError::Conflict("The authenticated account cannot delete itself.".to_string()).into());
     }
 
@@ -238,7 +239,11 @@ async fn delete_accounts(
 ) -> Result<Json<u64>, ApplicationError> {
     user.check_permission(Permission::WriteAccount)?;
 
-    if filter.ids.as_ref().is_none_or(|ids| ids.contains(&user.account_id)) {
+    if filter
+        .ids
+        .as_ref()
+        .is_none_or(|ids| ids.contains(&user.principal_account_id()))
+    {
         return Err(DataError::Conflict("The authenticated account cannot delete itself.".to_string()).into());
     }
 
@@ -247,7 +252,7 @@ async fn delete_accounts(
 
 #[cfg(test)]
 mod tests {
-    use crate::application::composition::MockAppServicesBuilder;
+    use crate::application::{composition::MockAppServicesBuilder, errors::AuthorizationError};
 
     use super::*;
 
@@ -255,6 +260,11 @@ mod tests {
         User {
             account_id,
             permissions,
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: None,
         }
     }
 
@@ -289,6 +299,32 @@ mod tests {
         assert!(matches!(result, Err(ApplicationError::Authorization(_))));
     }
 
+    #[tokio::test]
+    async fn update_permissions_requires_a_step_up_of_two_factor_sessions() {
+        let mut builder = MockAppServicesBuilder::new();
+        builder
+            .auth
+            .expect_check_step_up()
+            .times(1)
+            .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+        builder.account.expect_update_permissions().never();
+
+        let result = replace_account_permissions(
+            State(Arc::new(builder.build())),
+            user(Uuid::new_v4(), vec![Permission::WriteAccount]),
+            Path(Uuid::new_v4()),
+            Json(UpdateAccountPermissionsRequest {
+                permissions: vec![Permission::ReadWallet],
+            }),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+        ));
+    }
+
     #[tokio::test]
     async fn delete_rejects_the_authenticated_account() {
         let account_id = Uuid::new_v4();
@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::application::errors::DatabaseError;
 
-use super::{Account, AccountFilter, AccountPreferences, AuthProvider, Permission};
+use super::{Account, AccountFilter, AccountPreferences, AuthIdentity, AuthProvider, Permission};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -30,4 +30,17 @@ pub trait AccountRepository: Send + Sync {
         dashboard_settings: serde_json::Value,
     ) -> Result<Option<AccountPreferences>, DatabaseError>;
     async fn delete_many(&self, filter: AccountFilter) -> Result<u64, DatabaseError>;
+    /// Identities of a provider linked to the account, in order of creation.
+    async fn find_identities(
+        &self,
+        account_id: Uuid,
+        provider: AuthProvider,
+    ) -> Result<Vec<AuthIdentity>, DatabaseError>;
+    async fn insert_identity(
+        &self,
+        account_id: Uuid,
+        provider: AuthProvider,
+        subject: &str,
+    ) -> Result<AuthIdentity, DatabaseError>;
+    async fn delete_identity(&self, account_id: Uuid, provider: AuthProvider, id: Uuid) -> Result<u64, DatabaseError>;
 }
@@ -3,9 +3,12 @@ use serde_json::Value;
 use tracing::{debug, info, trace};
 use uuid::Uuid;
 
-use crate::application::{
-    composition::AppStore,
-    errors::{ApplicationError, DataError},
+use crate::{
+    application::{
+        composition::AppStore,
+        errors::{ApplicationError, DataError},
+    },
+    domains::audit::{record_created, record_deleted, record_updated},
 };
 
 use super::{Account, AccountFilter, AccountPreferences, AccountUseCases, CreateAccountRequest, Permission};
@@ -30,6 +33,7 @@ impl AccountUseCases for AccountService {
             .account
             .insert(request.display_name, &request.permissions)
             .await?;
+        record_created(&account);
 
         info!(id = %account.id, "Account created successfully");
         Ok(account)
@@ -67,8 +71,10 @@ impl AccountUseCases for AccountService {
             .find(id)
             .await?
             .ok_or_else(|| DataError::NotFound("Account not found.".to_string()))?;
+        let before = account.clone();
         account.display_name = display_name;
         let account = self.store.account.update(account).await?;
+        record_updated(&before, &account);
 
         info!(%id, "Account updated successfully");
         Ok(account)
@@ -89,8 +95,10 @@ impl AccountUseCases for AccountService {
                 unique_permissions.push(permission);
             }
         }
+        let before = account.clone();
         account.permissions = Some(unique_permissions);
         let account = self.store.account.update(account).await?;
+        record_updated(&before, &account);
 
         info!(%id, "Account permissions updated successfully");
         Ok(account)
@@ -117,6 +125,12 @@ impl AccountUseCases for AccountService {
     async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
         debug!(%id, "Deleting account");
 
+        let account = self
+            .store
+            .account
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Account not found.".to_string()))?;
         let n_deleted = self
             .store
             .account
@@ -129,6 +143,7 @@ impl AccountUseCases for AccountService {
         if n_deleted == 0 {
             return Err(DataError::NotFound("Account not found.".to_string()).into());
         }
+        record_deleted(&account);
 
         info!(%id, "Account deleted successfully");
         Ok(())
@@ -270,12 +285,8 @@ mod tests {
         #[tokio::test]
         async fn reports_a_missing_account() {
             let mut store = MockAppStoreBuilder::new();
-            store
-                .account
-                .expect_delete_many()
-                .withf(|filter| filter.ids.as_ref().is_some_and(|ids| ids.len() == 1))
-                .times(1)
-                .returning(|_| Ok(0));
+            store.account.expect_find().times(1).returning(|_| Ok(None));
+            store.account.expect_delete_many().never();
             let service = AccountService::new(store.build());
 
             let error = service.delete(Uuid::new_v4()).await.unwrap_err();
@@ -1,21 +1,96 @@
+use std::net::IpAddr;
+
 use async_trait::async_trait;
 use serde_json::Value;
 use uuid::Uuid;
 
-use swissknife_types::CreateApiKeyRequest;
+use swissknife_types::{
+    AddOrganizationMemberRequest, CreateApiKeyRequest, CreateOrganizationRequest, RotateApiKeyRequest,
+};
 
 use crate::application::errors::ApplicationError;
 
-use super::{Account, AccountFilter, AccountPreferences, ApiKey, ApiKeyFilter, CreateAccountRequest, Permission, User};
+use super::{
+    Account, AccountFilter, AccountPreferences, ApiKey, ApiKeyFilter, AuthIdentity, CreateAccountRequest,
+    CreateInvitationRequest, Invitation, InvitationFilter, Organization, OrganizationMember, OrganizationRole, Passkey,
+    PasskeyAuthenticationOptions, PasskeyRegistrationOptions, PasskeySignInRequest, PasswordReset, Permission,
+    RegisterPasskeyRequest, RegisterRequest, SignInResponse, TotpEnrollment, TotpRecoveryCodes, User,
+};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait AuthUseCases: Send + Sync {
-    async fn sign_up(&self, password: String) -> Result<String, ApplicationError>;
-    async fn sign_in(&self, password: String) -> Result<String, ApplicationError>;
-    async fn change_password(&self, current_password: String, new_password: String) -> Result<(), ApplicationError>;
+    async fn sign_up(&self, password: String) -> Result<SignInResponse, ApplicationError>;
+    /// Signs in the local account matching `login`, or the bootstrap admin when omitted. `otp` is a TOTP or
+    /// recovery code, required when the account has two-factor authentication enabled.
+    async fn sign_in(
+        &self,
+        login: Option<String>,
+        password: String,
+        otp: Option<String>,
+    ) -> Result<SignInResponse, ApplicationError>;
+    /// Exchanges a refresh token for a new access token and refresh token of the same session.
+    async fn refresh(&self, refresh_token: String) -> Result<SignInResponse, ApplicationError>;
+    /// Ends the session of a refresh token. Unknown tokens are ignored.
+    async fn sign_out(&self, refresh_token: String) -> Result<(), ApplicationError>;
+    /// Ends every session of the caller's account.
+    async fn sign_out_all(&self, user: User) -> Result<u64, ApplicationError>;
+    async fn change_password(
+        &self,
+        user: User,
+        current_password: String,
+        new_password: String,
+    ) -> Result<(), ApplicationError>;
+    async fn register(&self, request: RegisterRequest) -> Result<SignInResponse, ApplicationError>;
+    async fn create_invitation(
+        &self,
+        user: User,
+        request: CreateInvitationRequest,
+    ) -> Result<Invitation, ApplicationError>;
+    async fn list_invitations(&self, filter: InvitationFilter) -> Result<Vec<Invitation>, ApplicationError>;
+    async fn revoke_invitation(&self, id: Uuid) -> Result<(), ApplicationError>;
+    /// Issues a password reset of an account holding no more permissions than the caller.
+    async fn create_password_reset(&self, user: User, account_id: Uuid) -> Result<PasswordReset, ApplicationError>;
+    async fn reset_password(&self, token: String, new_password: String) -> Result<(), ApplicationError>;
+    /// Starts a TOTP enrollment of the caller's account, replacing a pending one.
+    async fn enroll_totp(&self, user: User) -> Result<TotpEnrollment, ApplicationError>;
+    /// Enables the pending TOTP factor with its first code and returns the recovery codes.
+    async fn confirm_totp(&self, user: User, code: String) -> Result<TotpRecoveryCodes, ApplicationError>;
+    /// Disables two-factor authentication with a TOTP or recovery code.
+    async fn disable_totp(&self, user: User, code: String) -> Result<(), ApplicationError>;
+    /// Verifies the second factor in the caller's session, allowing sensitive operations for the step-up window.
+    async fn step_up(&self, user: User, code: String) -> Result<(), ApplicationError>;
+    /// Rejects sensitive operations from sessions without a recent verification of the second factor.
+    async fn check_step_up(&self, user: &User) -> Result<(), ApplicationError>;
+    /// Largest payment amount the caller can send without a step-up, `None` when unlimited.
+    async fn payment_step_up_limit(&self, user: &User) -> Result<Option<u64>, ApplicationError>;
+    /// Starts the registration of a passkey for the caller's account.
+    async fn passkey_registration_options(&self, user: User) -> Result<PasskeyRegistrationOptions, ApplicationError>;
+    /// Completes a passkey registration with the credential created by the authenticator.
+    async fn register_passkey(&self, user: User, request: RegisterPasskeyRequest) -> Result<Passkey, ApplicationError>;
+    /// Starts a passkey sign-in, for any account.
+    async fn passkey_authentication_options(&self) -> Result<PasskeyAuthenticationOptions, ApplicationError>;
+    /// Signs in the account of the passkey that signed the challenge. Passkeys verify the user, so no second
+    /// factor is required.
+    async fn sign_in_passkey(&self, request: PasskeySignInRequest) -> Result<SignInResponse, ApplicationError>;
+    async fn list_passkeys(&self, user: User) -> Result<Vec<Passkey>, ApplicationError>;
+    async fn delete_passkey(&self, user: User, id: Uuid) -> Result<(), ApplicationError>;
+    /// Links the Nostr key that signed the NIP-98 `event` for this operation to the caller's account.
+    async fn link_nostr_key(&self, user: User, event: String) -> Result<AuthIdentity, ApplicationError>;
+    async fn list_nostr_keys(&self, user: User) -> Result<Vec<AuthIdentity>, ApplicationError>;
+    async fn unlink_nostr_key(&self, user: User, id: Uuid) -> Result<(), ApplicationError>;
     async fn authenticate_jwt(&self, token: &str) -> Result<User, ApplicationError>;
-    async fn authenticate_api_key(&self, token: Vec<u8>) -> Result<User, ApplicationError>;
+    /// Authenticates an unexpired API key and records its usage from `source_ip`.
+    async fn authenticate_api_key(&self, token: Vec<u8>, source_ip: Option<IpAddr>) -> Result<User, ApplicationError>;
+    /// Authenticates the linked Nostr key that signed the NIP-98 `token` for the request to `path` (with its query),
+    /// whose body has the hex SHA-256 `payload_hash`, if not empty.
+    async fn authenticate_nostr(
+        &self,
+        token: &str,
+        method: &str,
+        path: &str,
+        payload_hash: Option<String>,
+    ) -> Result<User, ApplicationError>;
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -43,4 +118,38 @@ pub trait ApiKeyUseCases: Send + Sync {
     async fn list(&self, filter: ApiKeyFilter) -> Result<Vec<ApiKey>, ApplicationError>;
     async fn revoke(&self, id: Uuid) -> Result<(), ApplicationError>;
     async fn revoke_many(&self, filter: ApiKeyFilter) -> Result<u64, ApplicationError>;
+    /// Issues a replacement for an API key, which keeps working for the grace period.
+    async fn rotate(&self, user: User, id: Uuid, request: RotateApiKeyRequest) -> Result<ApiKey, ApplicationError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait OrganizationUseCases: Send + Sync {
+    /// Creates an organization with the caller as its first owner.
+    async fn create(&self, user: User, request: CreateOrganizationRequest) -> Result<Organization, ApplicationError>;
+    /// Lists the organizations the caller is a member of.
+    async fn list(&self, user: User) -> Result<Vec<Organization>, ApplicationError>;
+    async fn get(&self, user: User, id: Uuid) -> Result<Organization, ApplicationError>;
+    async fn update(&self, user: User, id: Uuid, name: String) -> Result<Organization, ApplicationError>;
+    /// Deletes the organization with its account and everything the account owns.
+    async fn delete(&self, user: User, id: Uuid) -> Result<(), ApplicationError>;
+    async fn list_members(&self, user: User, id: Uuid) -> Result<Vec<OrganizationMember>, ApplicationError>;
+    async fn add_member(
+        &self,
+        user: User,
+        id: Uuid,
+        request: AddOrganizationMemberRequest,
+    ) -> Result<OrganizationMember, ApplicationError>;
+    async fn update_member(
+        &self,
+        user: User,
+        id: Uuid,
+        account_id: Uuid,
+        role: OrganizationRole,
+    ) -> Result<OrganizationMember, ApplicationError>;
+    /// Removes a member. Members may leave on their own, except for the last owner.
+    async fn remove_member(&self, user: User, id: Uuid, account_id: Uuid) -> Result<(), ApplicationError>;
+    /// Resolves the caller into the context of an organization it is a member of, acting for the organization's
+    /// account within the permissions of its role.
+    async fn act_for(&self, user: User, id: Uuid) -> Result<User, ApplicationError>;
 }
@@ -9,7 +9,7 @@ use axum_extra::extract::Query;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use swissknife_types::{CreateApiKeyRequest, ErrorResponse};
+use swissknife_types::{CreateApiKeyRequest, ErrorResponse, RotateApiKeyRequest};
 
 use crate::{
     application::{
@@ -27,8 +27,8 @@ use super::{ApiKey, ApiKeyFilter, Permission, User};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(create_api_key, get_api_key, list_api_keys, revoke_api_key, revoke_api_keys),
-    components(schemas(CreateApiKeyRequest, ApiKey, Permission)),
+    paths(create_api_key, get_api_key, list_api_keys, revoke_api_key, revoke_api_keys, rotate_api_key),
+    components(schemas(CreateApiKeyRequest, RotateApiKeyRequest, ApiKey, Permission)),
     tags(
         (name = "API Keys", description = "API Key Management. Require `read:api_key` or `write:api_key` permissions. "),
     )
@@ -43,12 +43,14 @@ pub fn api_key_router() -> Router<Arc<AppServices>> {
         .route("/{id}", get(get_api_key))
         .route("/{id}", delete(revoke_api_key))
         .route("/", delete(revoke_api_keys))
+        .route("/{id}/rotate", post(rotate_api_key))
 }
 
 /// Generate a new API Key
 ///
 /// Returns the generated API Key for the requested account. Callers can create API keys with permissions
-/// as a subset of their current permissions.
+/// as a subset of their current permissions. Keys restricted to `wallet_ids` of the account hold no permissions
+/// and can only act through the `/v1/me/wallets/{wallet_id}` routes of those wallets.
 #[utoipa::path(
     post,
     path = "",
@@ -70,6 +72,7 @@ async fn create_api_key(
     Json(payload): Json<CreateApiKeyRequest>,
 ) -> Result<Json<ApiKey>, ApplicationError> {
     user.check_permission(Permission::WriteApiKey)?;
+    services.auth.check_step_up(&user).await?;
 
     let api_key = services.api_key.generate(user, payload).await?;
     Ok(Json(api_key))
@@ -188,9 +191,54 @@ async fn revoke_api_keys(
     Ok(n_revoked.into())
 }
 
+/// Rotate an API Key
+///
+/// Issues a replacement with the same name, permissions and wallets, returned with its key only once.
+/// The rotated key keeps working for the requested grace period, but never longer than it would have.
+#[utoipa::path(
+    post,
+    path = "/{id}/rotate",
+    tag = "API Keys",
+    context_path = CONTEXT_PATH,
+    request_body = RotateApiKeyRequest,
+    responses(
+        (status = 200, description = "API Key Rotated", body = ApiKey),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn rotate_api_key(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RotateApiKeyRequest>,
+) -> Result<Json<ApiKey>, ApplicationError> {
+    user.check_permission(Permission::WriteApiKey)?;
+    services.auth.check_step_up(&user).await?;
+
+    let api_key = services.api_key.rotate(user, id, payload).await?;
+    Ok(Json(api_key))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{application::composition::MockAppServicesBuilder, domains::account::ApiKey};
+    use chrono::Utc;
+
+    use crate::{
+        application::{
+            composition::{AuthProvider, LocalAuthConfig, MockAppServicesBuilder, MockAppStoreBuilder},
+            errors::AuthorizationError,
+        },
+        domains::{
+            account::{ApiKey, AuthService, TotpFactor},
+            bitcoin::BtcNetwork,
+        },
+        infra::jwt::MockJWTAuthenticator,
+    };
 
     use super::*;
 
@@ -208,6 +256,7 @@ mod tests {
             permissions: vec![Permission::ReadWallet],
             description: None,
             expiry: None,
+            wallet_ids: None,
         }
     }
 
@@ -233,6 +282,7 @@ mod tests {
             #[tokio::test]
             async fn delegates_to_the_service() {
                 let mut builder = MockAppServicesBuilder::new();
+                builder.auth.expect_check_step_up().times(1).returning(|_| Ok(()));
                 builder
                     .api_key
                     .expect_generate()
@@ -248,6 +298,67 @@ mod tests {
 
                 assert!(result.is_ok());
             }
+
+            #[tokio::test]
+            async fn without_a_required_step_up_is_forbidden() {
+                let mut builder = MockAppServicesBuilder::new();
+                builder
+                    .auth
+                    .expect_check_step_up()
+                    .times(1)
+                    .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+                builder.api_key.expect_generate().never();
+
+                let result = create_api_key(
+                    State(Arc::new(builder.build())),
+                    user(vec![Permission::WriteApiKey]),
+                    Json(create_request()),
+                )
+                .await;
+
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+                ));
+            }
+
+            #[tokio::test]
+            async fn with_a_nostr_key_of_a_two_factor_account_is_forbidden() {
+                let account_id = Uuid::new_v4();
+                let mut store = MockAppStoreBuilder::new();
+                store.totp.expect_find().times(1).returning(|account_id| {
+                    Ok(Some(TotpFactor {
+                        account_id,
+                        secret: vec![0; 20],
+                        last_used_step: 0,
+                        confirmed_at: Some(Utc::now()),
+                    }))
+                });
+                let mut builder = MockAppServicesBuilder::new();
+                builder.api_key.expect_generate().never();
+                let mut services = builder.build();
+                services.auth = Box::new(AuthService::new(
+                    Arc::new(MockJWTAuthenticator::new()),
+                    store.build(),
+                    AuthProvider::Jwt,
+                    BtcNetwork::Regtest,
+                    LocalAuthConfig::default(),
+                    "https://app.numeraire.tech".to_string(),
+                ));
+
+                // Nostr keys authenticate without a session, in which they could step up.
+                let nostr_key = User {
+                    account_id,
+                    subject: Some("3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d".to_string()),
+                    ..user(vec![Permission::WriteApiKey])
+                };
+                let result = create_api_key(State(Arc::new(services)), nostr_key, Json(create_request())).await;
+
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+                ));
+            }
         }
     }
 
@@ -286,4 +397,55 @@ mod tests {
             }
         }
     }
+
+    mod rotate_api_key {
+        use super::*;
+
+        #[tokio::test]
+        async fn without_the_write_permission_is_forbidden() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = rotate_api_key(
+                State(Arc::new(services)),
+                user(vec![Permission::ReadApiKey]),
+                Path(Uuid::new_v4()),
+                Json(RotateApiKeyRequest::default()),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn returns_the_replacement() {
+            let id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_check_step_up().times(1).returning(|_| Ok(()));
+            builder
+                .api_key
+                .expect_rotate()
+                .withf(move |_, rotated_id, request| *rotated_id == id && request.grace_period == Some(60))
+                .times(1)
+                .returning(|_, _, _| {
+                    Ok(ApiKey {
+                        key: Some("replacement".to_string()),
+                        ..Default::default()
+                    })
+                });
+
+            let Json(api_key) = rotate_api_key(
+                State(Arc::new(builder.build())),
+                user(vec![Permission::WriteApiKey]),
+                Path(id),
+                Json(RotateApiKeyRequest {
+                    grace_period: Some(60),
+                    expiry: None,
+                }),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(api_key.key.as_deref(), Some("replacement"));
+        }
+    }
 }
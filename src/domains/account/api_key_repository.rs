@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::application::errors::DatabaseError;
@@ -12,5 +13,14 @@ pub trait ApiKeyRepository: Send + Sync {
     async fn find_by_key_hash(&self, key_hash: Vec<u8>) -> Result<Option<ApiKey>, DatabaseError>;
     async fn find_many(&self, filter: ApiKeyFilter) -> Result<Vec<ApiKey>, DatabaseError>;
     async fn insert(&self, api_key: ApiKey) -> Result<ApiKey, DatabaseError>;
+    /// Sets the expiry of a rotated key and inserts its replacement atomically.
+    /// Returns `None` if the key no longer exists.
+    async fn rotate(
+        &self,
+        id: Uuid,
+        replacement: ApiKey,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<ApiKey>, DatabaseError>;
+    async fn record_usage(&self, id: Uuid, ip: Option<String>) -> Result<(), DatabaseError>;
     async fn delete_many(&self, filter: ApiKeyFilter) -> Result<u64, DatabaseError>;
 }
@@ -5,16 +5,20 @@ use serde_bolt::bitcoin::hashes::{sha256, Hash};
 use tracing::{debug, info, trace};
 use uuid::Uuid;
 
-use swissknife_types::CreateApiKeyRequest;
-
-use crate::application::{
-    composition::AppStore,
-    errors::{ApplicationError, DataError},
+use swissknife_types::{CreateApiKeyRequest, RotateApiKeyRequest};
+
+use crate::{
+    application::{
+        composition::AppStore,
+        errors::{ApplicationError, AuthorizationError, DataError},
+    },
+    domains::audit::{record_created, record_deleted, record_updated},
 };
 
-use super::{ApiKey, ApiKeyFilter, ApiKeyUseCases, User};
+use super::{ApiKey, ApiKeyFilter, ApiKeyUseCases, Permission, User};
 
 const MAX_ALLOWED_EXPIRY_SECONDS: u32 = 31_536_000; // 1 year in seconds
+const MAX_GRACE_PERIOD_SECONDS: u32 = 2_592_000; // 30 days in seconds
 
 pub struct ApiKeyService {
     store: AppStore,
@@ -24,6 +28,52 @@ impl ApiKeyService {
     pub fn new(store: AppStore) -> Self {
         ApiKeyService { store }
     }
+
+    /// Validates the wallets of a wallet-scoped key, returning them deduplicated.
+    async fn validate_wallet_ids(
+        &self,
+        account_id: Uuid,
+        permissions: &[Permission],
+        wallet_ids: Vec<Uuid>,
+    ) -> Result<Vec<Uuid>, ApplicationError> {
+        if wallet_ids.is_empty() {
+            return Err(DataError::Validation("Wallet-scoped API keys require at least one wallet".to_string()).into());
+        }
+
+        // Permissions unlock the administrative routes, which are not wallet-scoped.
+        if !permissions.is_empty() {
+            return Err(DataError::Validation("Wallet-scoped API keys cannot hold permissions".to_string()).into());
+        }
+
+        let mut wallet_ids = wallet_ids;
+        wallet_ids.sort();
+        wallet_ids.dedup();
+
+        for wallet_id in &wallet_ids {
+            if !self.store.wallet.exists_for_account(account_id, *wallet_id).await? {
+                return Err(DataError::NotFound("Wallet not found.".to_string()).into());
+            }
+        }
+
+        Ok(wallet_ids)
+    }
+}
+
+/// Keys issued by a wallet-scoped principal must stay within its wallets.
+fn check_wallet_scope(user: &User, wallet_ids: Option<&[Uuid]>) -> Result<(), AuthorizationError> {
+    match wallet_ids {
+        Some(wallet_ids) => wallet_ids
+            .iter()
+            .try_for_each(|wallet_id| user.check_wallet(*wallet_id)),
+        None => user.check_account_scope(),
+    }
+}
+
+/// Generates a new API key, returned to the caller, and the hash stored in its place.
+fn generate_key() -> (String, Vec<u8>) {
+    let bytes: [u8; 32] = rand::random();
+    let key_hash = sha256::Hash::hash(&bytes).to_byte_array().to_vec();
+    (BASE64_STANDARD.encode(bytes), key_hash)
 }
 
 #[async_trait]
@@ -49,10 +99,23 @@ impl ApiKeyUseCases for ApiKeyService {
 
         let account_id = request.account_id.expect("account_id should be defined");
 
-        // Generate a new API key
-        let bytes: [u8; 32] = rand::random();
-        let api_key_plain = BASE64_STANDARD.encode(bytes);
-        let key_hash = sha256::Hash::hash(&bytes).to_byte_array().to_vec();
+        check_wallet_scope(&user, request.wallet_ids.as_deref())?;
+        let wallet_ids = match request.wallet_ids {
+            Some(wallet_ids) => Some(
+                self.validate_wallet_ids(account_id, &request.permissions, wallet_ids)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        // Keys of an organization account are bound to the member issuing them.
+        let member_account_id = user
+            .membership
+            .as_ref()
+            .filter(|_| account_id == user.account_id)
+            .map(|membership| membership.account_id);
+
+        let (api_key_plain, key_hash) = generate_key();
 
         let api_key = ApiKey {
             account_id,
@@ -61,10 +124,13 @@ impl ApiKeyUseCases for ApiKeyService {
             permissions: request.permissions.clone(),
             expires_at,
             description: request.description,
+            wallet_ids,
+            member_account_id,
             ..Default::default()
         };
 
         let mut api_key = self.store.api_key.insert(api_key).await?;
+        record_created(&api_key);
         api_key.key = Some(api_key_plain);
 
         info!(id = %api_key.id, "API key generated successfully");
@@ -97,6 +163,12 @@ impl ApiKeyUseCases for ApiKeyService {
     async fn revoke(&self, id: Uuid) -> Result<(), ApplicationError> {
         debug!(%id, "Revoking API key");
 
+        let api_key = self
+            .store
+            .api_key
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("API key not found.".to_string()))?;
         let n_deleted = self
             .store
             .api_key
@@ -109,6 +181,7 @@ impl ApiKeyUseCases for ApiKeyService {
         if n_deleted == 0 {
             return Err(DataError::NotFound("API key not found.".to_string()).into());
         }
+        record_deleted(&api_key);
 
         info!(%id, "API key revoked successfully");
         Ok(())
@@ -122,13 +195,87 @@ impl ApiKeyUseCases for ApiKeyService {
         info!(?filter, n_deleted, "API keys revoked successfully");
         Ok(n_deleted)
     }
+
+    async fn rotate(&self, user: User, id: Uuid, request: RotateApiKeyRequest) -> Result<ApiKey, ApplicationError> {
+        debug!(%id, ?request, "Rotating API key");
+
+        let api_key = self
+            .store
+            .api_key
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("API key not found.".to_string()))?;
+        let rotated = api_key.clone();
+
+        // The replacement is issued by the caller, who cannot grant more than they hold.
+        if !api_key.permissions.iter().all(|p| user.has_permission(p.clone())) {
+            return Err(DataError::Validation("Invalid permissions".to_string()).into());
+        }
+        check_wallet_scope(&user, api_key.wallet_ids.as_deref())?;
+
+        let now = Utc::now();
+        if api_key.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(DataError::Validation("Expired API keys cannot be rotated".to_string()).into());
+        }
+
+        let grace_period = request.grace_period.unwrap_or(0);
+        if grace_period > MAX_GRACE_PERIOD_SECONDS {
+            return Err(DataError::Validation("Grace period too long".to_string()).into());
+        }
+        let rotated_expires_at = now + Duration::seconds(grace_period as i64);
+        let rotated_expires_at = api_key
+            .expires_at
+            .map_or(rotated_expires_at, |expires_at| expires_at.min(rotated_expires_at));
+
+        let expires_at = match request.expiry {
+            Some(seconds) if seconds > MAX_ALLOWED_EXPIRY_SECONDS => {
+                return Err(DataError::Validation("Expiry too far in the future".to_string()).into());
+            }
+            Some(seconds) => Some(now + Duration::seconds(seconds as i64)),
+            None => api_key
+                .expires_at
+                .map(|expires_at| now + (expires_at - api_key.created_at)),
+        };
+
+        // The replacement is bound to the member rotating it, not to the one who issued the rotated key.
+        let member_account_id = user
+            .membership
+            .as_ref()
+            .filter(|_| api_key.account_id == user.account_id)
+            .map(|membership| membership.account_id);
+
+        let (api_key_plain, key_hash) = generate_key();
+        let replacement = ApiKey {
+            account_id: api_key.account_id,
+            name: api_key.name,
+            key_hash,
+            permissions: api_key.permissions,
+            description: api_key.description,
+            expires_at,
+            wallet_ids: api_key.wallet_ids,
+            member_account_id,
+            ..Default::default()
+        };
+
+        let mut replacement = self
+            .store
+            .api_key
+            .rotate(id, replacement, rotated_expires_at)
+            .await?
+            .ok_or_else(|| DataError::NotFound("API key not found.".to_string()))?;
+        record_updated(&rotated, &replacement);
+        replacement.key = Some(api_key_plain);
+
+        info!(%id, replacement_id = %replacement.id, %rotated_expires_at, "API key rotated successfully");
+        Ok(replacement)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         application::{composition::MockAppStoreBuilder, errors::DatabaseError},
-        domains::account::Permission,
+        domains::account::{OrganizationMember, OrganizationRole, Permission},
     };
 
     use super::*;
@@ -137,6 +284,11 @@ mod tests {
         User {
             account_id: Uuid::new_v4(),
             permissions,
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: None,
         }
     }
 
@@ -147,6 +299,7 @@ mod tests {
             permissions,
             description: None,
             expiry,
+            wallet_ids: None,
         }
     }
 
@@ -184,6 +337,40 @@ mod tests {
             }
         }
 
+        mod in_organization_context {
+            use super::*;
+
+            #[tokio::test]
+            async fn binds_the_key_to_the_issuing_member() {
+                let mut user = user_with(vec![Permission::ReadWallet]);
+                let organization_id = user.account_id;
+                let member_account_id = Uuid::new_v4();
+                user.membership = Some(OrganizationMember {
+                    organization_id,
+                    account_id: member_account_id,
+                    role: OrganizationRole::Admin,
+                    created_at: Utc::now(),
+                    updated_at: None,
+                });
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .api_key
+                    .expect_insert()
+                    .withf(move |api_key| {
+                        api_key.account_id == organization_id && api_key.member_account_id == Some(member_account_id)
+                    })
+                    .times(1)
+                    .returning(Ok);
+
+                let service = ApiKeyService::new(store.build());
+                let mut request = create_request(vec![Permission::ReadWallet], None);
+                request.account_id = Some(organization_id);
+
+                service.generate(user, request).await.unwrap();
+            }
+        }
+
         mod for_an_explicit_account {
             use super::*;
 
@@ -351,6 +538,11 @@ mod tests {
             #[tokio::test]
             async fn succeeds() {
                 let mut store = MockAppStoreBuilder::new();
+                store
+                    .api_key
+                    .expect_find()
+                    .times(1)
+                    .returning(|_| Ok(Some(ApiKey::default())));
                 store.api_key.expect_delete_many().times(1).returning(|_| Ok(1));
 
                 let service = ApiKeyService::new(store.build());
@@ -359,13 +551,14 @@ mod tests {
             }
         }
 
-        mod when_nothing_is_removed {
+        mod when_the_key_is_missing {
             use super::*;
 
             #[tokio::test]
             async fn returns_not_found() {
                 let mut store = MockAppStoreBuilder::new();
-                store.api_key.expect_delete_many().times(1).returning(|_| Ok(0));
+                store.api_key.expect_find().times(1).returning(|_| Ok(None));
+                store.api_key.expect_delete_many().never();
 
                 let service = ApiKeyService::new(store.build());
 
@@ -389,4 +582,312 @@ mod tests {
             assert_eq!(service.revoke_many(ApiKeyFilter::default()).await.unwrap(), 4);
         }
     }
+
+    mod generate_with_wallets {
+        use super::*;
+
+        #[tokio::test]
+        async fn scopes_the_key_to_wallets_of_the_account() {
+            let user = user_with(vec![Permission::WriteApiKey]);
+            let account_id = Uuid::new_v4();
+            let wallet_id = Uuid::new_v4();
+            let mut request = create_request(vec![], None);
+            request.account_id = Some(account_id);
+            request.wallet_ids = Some(vec![wallet_id, wallet_id]);
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .wallet
+                .expect_exists_for_account()
+                .withf(move |account, wallet| *account == account_id && *wallet == wallet_id)
+                .times(1)
+                .returning(|_, _| Ok(true));
+            store
+                .api_key
+                .expect_insert()
+                .withf(move |api_key| api_key.wallet_ids == Some(vec![wallet_id]))
+                .times(1)
+                .returning(Ok);
+
+            let service = ApiKeyService::new(store.build());
+
+            service.generate(user, request).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn rejects_wallets_of_another_account() {
+            let mut request = create_request(vec![], None);
+            request.account_id = Some(Uuid::new_v4());
+            request.wallet_ids = Some(vec![Uuid::new_v4()]);
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .wallet
+                .expect_exists_for_account()
+                .times(1)
+                .returning(|_, _| Ok(false));
+
+            let service = ApiKeyService::new(store.build());
+
+            let err = service.generate(user_with(vec![]), request).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_permissions_on_a_wallet_scoped_key() {
+            let mut request = create_request(vec![Permission::ReadWallet], None);
+            request.account_id = Some(Uuid::new_v4());
+            request.wallet_ids = Some(vec![Uuid::new_v4()]);
+
+            let service = ApiKeyService::new(MockAppStoreBuilder::new().build());
+
+            let err = service
+                .generate(user_with(vec![Permission::ReadWallet]), request)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn a_wallet_scoped_user_cannot_issue_an_unscoped_key() {
+            let mut request = create_request(vec![], None);
+            request.account_id = Some(Uuid::new_v4());
+            let user = User {
+                wallet_ids: Some(vec![Uuid::new_v4()]),
+                ..Default::default()
+            };
+
+            let service = ApiKeyService::new(MockAppStoreBuilder::new().build());
+
+            let err = service.generate(user, request).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::WalletScoped)
+            ));
+        }
+    }
+
+    mod rotate {
+        use super::*;
+
+        fn existing_key(expires_at: Option<chrono::DateTime<Utc>>) -> ApiKey {
+            ApiKey {
+                id: Uuid::new_v4(),
+                account_id: Uuid::new_v4(),
+                name: "primary".to_string(),
+                permissions: vec![Permission::ReadWallet],
+                created_at: Utc::now() - Duration::days(10),
+                expires_at,
+                ..Default::default()
+            }
+        }
+
+        #[tokio::test]
+        async fn issues_a_replacement_and_keeps_the_old_key_for_the_grace_period() {
+            let existing = existing_key(Some(Utc::now() + Duration::days(20)));
+            let id = existing.id;
+            let account_id = existing.account_id;
+
+            let mut store = MockAppStoreBuilder::new();
+            let found = existing.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+            store
+                .api_key
+                .expect_rotate()
+                .withf(move |rotated_id, replacement, expires_at| {
+                    let grace_end = Utc::now() + Duration::seconds(3_600);
+                    // The replacement keeps the 30 day lifetime of the rotated key.
+                    let lifetime_end = Utc::now() + Duration::days(30);
+                    *rotated_id == id
+                        && replacement.account_id == account_id
+                        && replacement.name == "primary"
+                        && replacement.permissions == vec![Permission::ReadWallet]
+                        && replacement
+                            .expires_at
+                            .is_some_and(|t| (t - lifetime_end).num_seconds().abs() < 5)
+                        && (*expires_at - grace_end).num_seconds().abs() < 5
+                })
+                .times(1)
+                .returning(|_, replacement, _| Ok(Some(replacement)));
+
+            let service = ApiKeyService::new(store.build());
+
+            let replacement = service
+                .rotate(
+                    user_with(vec![Permission::ReadWallet]),
+                    id,
+                    RotateApiKeyRequest {
+                        grace_period: Some(3_600),
+                        expiry: None,
+                    },
+                )
+                .await
+                .unwrap();
+
+            assert!(replacement.key.is_some());
+        }
+
+        #[tokio::test]
+        async fn binds_the_replacement_to_the_rotating_member() {
+            let member = user_with(vec![Permission::ReadWallet]);
+            let member_account_id = Uuid::new_v4();
+            let user = User {
+                membership: Some(OrganizationMember {
+                    organization_id: member.account_id,
+                    account_id: member_account_id,
+                    role: OrganizationRole::Admin,
+                    created_at: Utc::now(),
+                    updated_at: None,
+                }),
+                ..member
+            };
+            let existing = ApiKey {
+                account_id: user.account_id,
+                member_account_id: Some(Uuid::new_v4()),
+                ..existing_key(None)
+            };
+
+            let mut store = MockAppStoreBuilder::new();
+            let found = existing.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+            store
+                .api_key
+                .expect_rotate()
+                .withf(move |_, replacement, _| replacement.member_account_id == Some(member_account_id))
+                .times(1)
+                .returning(|_, replacement, _| Ok(Some(replacement)));
+
+            let service = ApiKeyService::new(store.build());
+
+            service
+                .rotate(user, existing.id, RotateApiKeyRequest::default())
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn never_extends_the_old_key() {
+            let expires_at = Utc::now() + Duration::seconds(60);
+            let existing = existing_key(Some(expires_at));
+
+            let mut store = MockAppStoreBuilder::new();
+            let found = existing.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+            store
+                .api_key
+                .expect_rotate()
+                .withf(move |_, _, rotated_expires_at| *rotated_expires_at == expires_at)
+                .times(1)
+                .returning(|_, replacement, _| Ok(Some(replacement)));
+
+            let service = ApiKeyService::new(store.build());
+
+            service
+                .rotate(
+                    user_with(vec![Permission::ReadWallet]),
+                    existing.id,
+                    RotateApiKeyRequest {
+                        grace_period: Some(3_600),
+                        expiry: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn rejects_an_expired_key() {
+            let existing = existing_key(Some(Utc::now() - Duration::seconds(1)));
+
+            let mut store = MockAppStoreBuilder::new();
+            let found = existing.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+
+            let service = ApiKeyService::new(store.build());
+
+            let err = service
+                .rotate(
+                    user_with(vec![Permission::ReadWallet]),
+                    existing.id,
+                    RotateApiKeyRequest::default(),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_permissions_beyond_the_user() {
+            let existing = existing_key(None);
+
+            let mut store = MockAppStoreBuilder::new();
+            let found = existing.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+
+            let service = ApiKeyService::new(store.build());
+
+            let err = service
+                .rotate(user_with(vec![]), existing.id, RotateApiKeyRequest::default())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn returns_not_found_when_the_key_is_revoked_concurrently() {
+            let existing = existing_key(None);
+
+            let mut store = MockAppStoreBuilder::new();
+            let found = existing.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+            store
+                .api_key
+                .expect_rotate()
+                .withf(|_, replacement, _| replacement.expires_at.is_none())
+                .times(1)
+                .returning(|_, _, _| Ok(None));
+
+            let service = ApiKeyService::new(store.build());
+
+            let err = service
+                .rotate(
+                    user_with(vec![Permission::ReadWallet]),
+                    existing.id,
+                    RotateApiKeyRequest::default(),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+        }
+    }
 }
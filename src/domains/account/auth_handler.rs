@@ -1,28 +1,105 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Router};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Extension, Router,
+};
+use axum_extra::extract::Query;
 use utoipa::OpenApi;
+use uuid::Uuid;
 
-use swissknife_types::{ChangePasswordRequest, ErrorResponse, SignInRequest, SignInResponse, SignUpRequest};
+use swissknife_types::{
+    ChangePasswordRequest, CreatePasswordResetRequest, ErrorResponse, LinkNostrKeyRequest, PasskeyAssertion,
+    PasskeyAssertionResponse, PasskeyAttestation, PasskeyAttestationResponse, PasskeyAuthenticatorSelection,
+    PasskeyCreationOptions, PasskeyCredentialDescriptor, PasskeyCredentialParameters, PasskeyRelyingParty,
+    PasskeyRequestOptions, PasskeyUser, RefreshTokenRequest, ResetPasswordRequest, SignInRequest, SignInResponse,
+    SignUpRequest, TotpCodeRequest, TotpEnrollment, TotpRecoveryCodes,
+};
 
 use crate::{
     application::{
         composition::AppServices,
         docs::{
-            BAD_REQUEST_EXAMPLE, CONFLICT_EXAMPLE, NOT_FOUND_EXAMPLE, UNAUTHORIZED_EXAMPLE, UNPROCESSABLE_EXAMPLE,
-            UNSUPPORTED_EXAMPLE,
+            BAD_REQUEST_EXAMPLE, CONFLICT_EXAMPLE, FORBIDDEN_EXAMPLE, NOT_FOUND_EXAMPLE, TOO_MANY_REQUESTS_EXAMPLE,
+            UNAUTHORIZED_EXAMPLE, UNPROCESSABLE_EXAMPLE, UNSUPPORTED_EXAMPLE,
         },
-        errors::ApplicationError,
+        errors::{ApplicationError, AuthenticationError, DataError},
     },
-    infra::axum::Json,
+    infra::axum::{ClientIp, Json, Path, RateLimiter},
 };
 
-use super::User;
+use super::{
+    AuthIdentity, CreateInvitationRequest, Invitation, InvitationFilter, Passkey, PasskeyAuthenticationOptions,
+    PasskeyRegistrationOptions, PasskeySignInRequest, PasswordReset, Permission, RegisterPasskeyRequest,
+    RegisterRequest, User,
+};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(sign_in, sign_up, change_password),
-    components(schemas(ChangePasswordRequest, SignUpRequest, SignInRequest, SignInResponse)),
+    paths(
+        sign_in,
+        sign_up,
+        refresh,
+        sign_out,
+        sign_out_all,
+        change_password,
+        enroll_totp,
+        confirm_totp,
+        disable_totp,
+        step_up,
+        passkey_registration_options,
+        register_passkey,
+        list_passkeys,
+        delete_passkey,
+        passkey_authentication_options,
+        sign_in_passkey,
+        link_nostr_key,
+        list_nostr_keys,
+        unlink_nostr_key,
+        register,
+        create_invitation,
+        list_invitations,
+        revoke_invitation,
+        create_password_reset,
+        reset_password
+    ),
+    components(schemas(
+        ChangePasswordRequest,
+        SignUpRequest,
+        SignInRequest,
+        SignInResponse,
+        RefreshTokenRequest,
+        TotpEnrollment,
+        TotpCodeRequest,
+        TotpRecoveryCodes,
+        Passkey,
+        PasskeyRegistrationOptions,
+        PasskeyCreationOptions,
+        PasskeyRelyingParty,
+        PasskeyUser,
+        PasskeyCredentialParameters,
+        PasskeyCredentialDescriptor,
+        PasskeyAuthenticatorSelection,
+        RegisterPasskeyRequest,
+        PasskeyAttestation,
+        PasskeyAttestationResponse,
+        PasskeyAuthenticationOptions,
+        PasskeyRequestOptions,
+        PasskeySignInRequest,
+        PasskeyAssertion,
+        PasskeyAssertionResponse,
+        LinkNostrKeyRequest,
+        AuthIdentity,
+        RegisterRequest,
+        CreateInvitationRequest,
+        Invitation,
+        CreatePasswordResetRequest,
+        PasswordReset,
+        ResetPasswordRequest
+    )),
     tags(
         (name = "Authentication", description = "Some endpoints are public, but some require authentication. We provide all the required endpoints to create an account and authorize yourself.")
     )
@@ -34,12 +111,34 @@ pub fn auth_router() -> Router<Arc<AppServices>> {
     Router::new()
         .route("/sign-up", post(sign_up))
         .route("/sign-in", post(sign_in))
+        .route("/refresh", post(refresh))
+        .route("/sign-out", post(sign_out))
+        .route("/sign-out-all", post(sign_out_all))
         .route("/change-password", post(change_password))
+        .route("/totp", post(enroll_totp))
+        .route("/totp/confirm", post(confirm_totp))
+        .route("/totp/disable", post(disable_totp))
+        .route("/step-up", post(step_up))
+        .route("/passkeys/registration-options", post(passkey_registration_options))
+        .route("/passkeys", post(register_passkey))
+        .route("/passkeys", get(list_passkeys))
+        .route("/passkeys/{id}", delete(delete_passkey))
+        .route("/passkeys/authentication-options", post(passkey_authentication_options))
+        .route("/passkeys/sign-in", post(sign_in_passkey))
+        .route("/nostr-keys", post(link_nostr_key))
+        .route("/nostr-keys", get(list_nostr_keys))
+        .route("/nostr-keys/{id}", delete(unlink_nostr_key))
+        .route("/register", post(register))
+        .route("/invitations", post(create_invitation))
+        .route("/invitations", get(list_invitations))
+        .route("/invitations/{id}", delete(revoke_invitation))
+        .route("/password-resets", post(create_password_reset))
+        .route("/reset-password", post(reset_password))
 }
 
 /// Sign up
 ///
-/// Creates the initial admin account. Returns a JWT token containing the account's effective permissions. Sign-up is only available for the `JWT` provider.
+/// Creates the initial admin account. Returns a JWT token containing the account's effective permissions and a refresh token. Sign-up is only available for the `JWT` provider.
 #[utoipa::path(
     post,
     path = "/sign-up",
@@ -58,13 +157,17 @@ async fn sign_up(
     State(services): State<Arc<AppServices>>,
     Json(payload): Json<SignUpRequest>,
 ) -> Result<Json<SignInResponse>, ApplicationError> {
-    let token = services.auth.sign_up(payload.password).await?;
-    Ok(SignInResponse { token }.into())
+    let response = services.auth.sign_up(payload.password).await?;
+    Ok(response.into())
 }
 
 /// Sign In
 ///
-/// Returns a JWT token to be used for authentication. The JWT token contains authentication and permissions. Sign in is only available for `JWT` Auth provider.
+/// Returns a JWT token to be used for authentication and a refresh token to renew it. The JWT token contains authentication and permissions
+/// and stays valid until it expires or its session ends. Sign in is only available for `JWT` Auth provider.
+/// Local accounts sign in with their username or email as `login`; omitting it signs in as the initial admin.
+/// Accounts with two-factor authentication enabled also send a TOTP or recovery code as `otp`.
+/// Attempts are rate limited per client IP, and failed attempts per login.
 #[utoipa::path(
     post,
     path = "/sign-in",
@@ -76,20 +179,108 @@ async fn sign_up(
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
-        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE))
     )
 )]
 async fn sign_in(
     State(services): State<Arc<AppServices>>,
+    rate_limiter: Option<Extension<RateLimiter>>,
     Json(payload): Json<SignInRequest>,
 ) -> Result<Json<SignInResponse>, ApplicationError> {
-    let token = services.auth.sign_in(payload.password).await?;
-    Ok(SignInResponse { token }.into())
+    // Omitted logins sign in as the initial admin, whose failures are counted with the `admin` login.
+    let login = payload
+        .login
+        .as_deref()
+        .map(str::trim)
+        .filter(|login| !login.is_empty())
+        .unwrap_or("admin")
+        .to_lowercase();
+    let response = limit_failures(
+        rate_limiter,
+        format!("login:{login}"),
+        services.auth.sign_in(payload.login, payload.password, payload.otp),
+    )
+    .await?;
+    Ok(response.into())
+}
+
+/// Refresh
+///
+/// Exchanges a refresh token for a new JWT token and a new refresh token, extending the session. Each refresh token can only be used once.
+#[utoipa::path(
+    post,
+    path = "/refresh",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "Token refreshed", body = SignInResponse),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    )
+)]
+async fn refresh(
+    State(services): State<Arc<AppServices>>,
+    Json(payload): Json<RefreshTokenRequest>,
+) -> Result<Json<SignInResponse>, ApplicationError> {
+    let response = services.auth.refresh(payload.refresh_token).await?;
+    Ok(response.into())
+}
+
+/// Sign Out
+///
+/// Ends the session of a refresh token. JWT tokens of the session stop authenticating immediately.
+#[utoipa::path(
+    post,
+    path = "/sign-out",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 204, description = "Signed out"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    )
+)]
+async fn sign_out(
+    State(services): State<Arc<AppServices>>,
+    Json(payload): Json<RefreshTokenRequest>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    services.auth.sign_out(payload.refresh_token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Sign Out of All Sessions
+///
+/// Ends every session of the signed-in account, invalidating all of its JWT and refresh tokens.
+#[utoipa::path(
+    post,
+    path = "/sign-out-all",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 204, description = "Signed out of all sessions"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn sign_out_all(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<impl IntoResponse, ApplicationError> {
+    services.auth.check_step_up(&user).await?;
+
+    services.auth.sign_out_all(user).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 /// Change Password
 ///
-/// Changes the local owner password for `JWT` auth provider deployments.
+/// Changes the password of the signed-in local account for `JWT` auth provider deployments. All sessions of the account end, so it has to sign in again.
 #[utoipa::path(
     post,
     path = "/change-password",
@@ -108,98 +299,1220 @@ async fn sign_in(
 )]
 async fn change_password(
     State(services): State<Arc<AppServices>>,
-    _user: User,
+    user: User,
     Json(payload): Json<ChangePasswordRequest>,
 ) -> Result<impl IntoResponse, ApplicationError> {
     services
         .auth
-        .change_password(payload.current_password, payload.new_password)
+        .change_password(user, payload.current_password, payload.new_password)
         .await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::application::{composition::MockAppServicesBuilder, errors::DataError};
+/// Enroll TOTP
+///
+/// Starts two-factor authentication for the signed-in local account. Returns the TOTP secret and its `otpauth` URI for an authenticator app.
+/// The factor is only enabled once a first code is confirmed. A pending enrollment is replaced.
+#[utoipa::path(
+    post,
+    path = "/totp",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Enrollment started", body = TotpEnrollment),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 409, description = "Already enabled", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn enroll_totp(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<TotpEnrollment>, ApplicationError> {
+    let enrollment = services.auth.enroll_totp(user).await?;
+    Ok(enrollment.into())
+}
 
-    use super::*;
+/// Confirm TOTP
+///
+/// Enables two-factor authentication with a first code of the authenticator. Returns single-use recovery codes, which are only returned once.
+/// Attempts are rate limited per client IP, and failed attempts per account.
+#[utoipa::path(
+    post,
+    path = "/totp/confirm",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "Two-factor authentication enabled", body = TotpRecoveryCodes),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn confirm_totp(
+    State(services): State<Arc<AppServices>>,
+    rate_limiter: Option<Extension<RateLimiter>>,
+    user: User,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<Json<TotpRecoveryCodes>, ApplicationError> {
+    let subject = format!("account:{}", user.principal_account_id());
+    let recovery_codes = limit_failures(rate_limiter, subject, services.auth.confirm_totp(user, payload.code)).await?;
+    Ok(recovery_codes.into())
+}
 
-    mod sign_up {
-        use super::*;
+/// Disable TOTP
+///
+/// Disables two-factor authentication of the signed-in local account with a TOTP or recovery code.
+/// Attempts are rate limited per client IP, and failed attempts per account.
+#[utoipa::path(
+    post,
+    path = "/totp/disable",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 204, description = "Two-factor authentication disabled"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn disable_totp(
+    State(services): State<Arc<AppServices>>,
+    rate_limiter: Option<Extension<RateLimiter>>,
+    user: User,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let subject = format!("account:{}", user.principal_account_id());
+    limit_failures(rate_limiter, subject, services.auth.disable_totp(user, payload.code)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-        #[tokio::test]
-        async fn returns_the_issued_token() {
-            let mut builder = MockAppServicesBuilder::new();
-            builder
-                .auth
-                .expect_sign_up()
-                .withf(|password| password == "secret")
-                .times(1)
-                .returning(|_| Ok("token".to_string()));
+/// Step Up
+///
+/// Verifies the second factor again with a TOTP or recovery code. Sessions of accounts with two-factor authentication need a recent
+/// verification to create or rotate API keys, change permissions, issue invitations and password resets, and send payments above the configured threshold.
+/// Attempts are rate limited per client IP, and failed attempts per account.
+#[utoipa::path(
+    post,
+    path = "/step-up",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 204, description = "Session verified"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn step_up(
+    State(services): State<Arc<AppServices>>,
+    rate_limiter: Option<Extension<RateLimiter>>,
+    user: User,
+    Json(payload): Json<TotpCodeRequest>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    let subject = format!("account:{}", user.principal_account_id());
+    limit_failures(rate_limiter, subject, services.auth.step_up(user, payload.code)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-            let result = sign_up(
-                State(Arc::new(builder.build())),
-                Json(SignUpRequest {
-                    password: "secret".to_string(),
-                }),
-            )
-            .await;
+/// Start Passkey Registration
+///
+/// Starts the registration of a passkey for the signed-in local account. Pass `publicKey` to `navigator.credentials.create()` and send the
+/// created credential with the `ceremonyId` to complete the registration before the timeout.
+#[utoipa::path(
+    post,
+    path = "/passkeys/registration-options",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Registration started", body = PasskeyRegistrationOptions),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn passkey_registration_options(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<PasskeyRegistrationOptions>, ApplicationError> {
+    services.auth.check_step_up(&user).await?;
 
-            let Json(response) = result.unwrap();
-            assert_eq!(response.token, "token");
-        }
-    }
+    let options = services.auth.passkey_registration_options(user).await?;
+    Ok(options.into())
+}
 
-    mod sign_in {
-        use super::*;
+/// Register Passkey
+///
+/// Completes a passkey registration with the credential created by the authenticator. The passkey can then be used to sign in without a password.
+#[utoipa::path(
+    post,
+    path = "/passkeys",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = RegisterPasskeyRequest,
+    responses(
+        (status = 200, description = "Passkey registered", body = Passkey),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn register_passkey(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<RegisterPasskeyRequest>,
+) -> Result<Json<Passkey>, ApplicationError> {
+    let passkey = services.auth.register_passkey(user, payload).await?;
+    Ok(passkey.into())
+}
 
-        #[tokio::test]
-        async fn propagates_service_errors() {
-            let mut builder = MockAppServicesBuilder::new();
-            builder
-                .auth
-                .expect_sign_in()
-                .times(1)
-                .returning(|_| Err(DataError::NotFound("missing".to_string()).into()));
+/// List Passkeys
+///
+/// Returns the passkeys of the signed-in local account.
+#[utoipa::path(
+    get,
+    path = "/passkeys",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<Passkey>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn list_passkeys(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<Vec<Passkey>>, ApplicationError> {
+    let passkeys = services.auth.list_passkeys(user).await?;
+    Ok(passkeys.into())
+}
 
-            let result = sign_in(
-                State(Arc::new(builder.build())),
-                Json(SignInRequest {
-                    password: "secret".to_string(),
-                }),
-            )
-            .await;
+/// Delete Passkey
+///
+/// Deletes a passkey of the signed-in local account so that it can no longer be used to sign in. Returns an empty body.
+#[utoipa::path(
+    delete,
+    path = "/passkeys/{id}",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn delete_passkey(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    services.auth.check_step_up(&user).await?;
 
-            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
-        }
-    }
+    services.auth.delete_passkey(user, id).await?;
+    Ok(())
+}
 
-    mod change_password {
-        use super::*;
+/// Start Passkey Sign In
+///
+/// Starts a passkey sign-in. Pass `publicKey` to `navigator.credentials.get()`, which lets the user pick one of their passkeys,
+/// and send the assertion with the `ceremonyId` to sign in before the timeout.
+#[utoipa::path(
+    post,
+    path = "/passkeys/authentication-options",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Sign-in started", body = PasskeyAuthenticationOptions),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    )
+)]
+async fn passkey_authentication_options(
+    State(services): State<Arc<AppServices>>,
+) -> Result<Json<PasskeyAuthenticationOptions>, ApplicationError> {
+    let options = services.auth.passkey_authentication_options().await?;
+    Ok(options.into())
+}
 
-        #[tokio::test]
-        async fn returns_no_content_when_password_changed() {
-            let mut builder = MockAppServicesBuilder::new();
-            builder
-                .auth
-                .expect_change_password()
-                .withf(|current_password, new_password| current_password == "old" && new_password == "new")
-                .times(1)
-                .returning(|_, _| Ok(()));
+/// Sign In with Passkey
+///
+/// Returns a JWT token and a refresh token for the account of the passkey that signed the challenge. Passkeys verify the user,
+/// so no TOTP code is required and the session counts as verified for the step-up window. Attempts are rate limited per client IP,
+/// and failed attempts per passkey.
+#[utoipa::path(
+    post,
+    path = "/passkeys/sign-in",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = PasskeySignInRequest,
+    responses(
+        (status = 200, description = "Token Created", body = SignInResponse),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE))
+    )
+)]
+async fn sign_in_passkey(
+    State(services): State<Arc<AppServices>>,
+    rate_limiter: Option<Extension<RateLimiter>>,
+    Json(payload): Json<PasskeySignInRequest>,
+) -> Result<Json<SignInResponse>, ApplicationError> {
+    let subject = format!("passkey:{}", payload.credential.id);
+    let response = limit_failures(rate_limiter, subject, services.auth.sign_in_passkey(payload)).await?;
+    Ok(response.into())
+}
 
-            let response = change_password(
-                State(Arc::new(builder.build())),
-                User::default(),
-                Json(ChangePasswordRequest {
-                    current_password: "old".to_string(),
-                    new_password: "new".to_string(),
-                }),
-            )
+/// Link Nostr Key
+///
+/// Links a Nostr key to the signed-in account, proven by a NIP-98 event it signed for this request. The key can then
+/// authenticate requests to the API with an `Authorization: Nostr <base64 event>` header, holding the permissions of the account.
+#[utoipa::path(
+    post,
+    path = "/nostr-keys",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = LinkNostrKeyRequest,
+    responses(
+        (status = 200, description = "Nostr key linked", body = AuthIdentity),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn link_nostr_key(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<LinkNostrKeyRequest>,
+) -> Result<Json<AuthIdentity>, ApplicationError> {
+    services.auth.check_step_up(&user).await?;
+
+    let identity = services.auth.link_nostr_key(user, payload.event).await?;
+    Ok(identity.into())
+}
+
+/// List Nostr Keys
+///
+/// Returns the Nostr keys linked to the signed-in account.
+#[utoipa::path(
+    get,
+    path = "/nostr-keys",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<AuthIdentity>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn list_nostr_keys(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<Vec<AuthIdentity>>, ApplicationError> {
+    let identities = services.auth.list_nostr_keys(user).await?;
+    Ok(identities.into())
+}
+
+/// Unlink Nostr Key
+///
+/// Unlinks a Nostr key from the signed-in account so that it can no longer authenticate. Returns an empty body.
+#[utoipa::path(
+    delete,
+    path = "/nostr-keys/{id}",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn unlink_nostr_key(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    services.auth.unlink_nostr_key(user, id).await?;
+    Ok(())
+}
+
+/// Register
+///
+/// Creates a local account and returns a JWT token for it. Requires an invitation token unless self-registration is enabled,
+/// in which case accounts registered without an invitation start with no permissions. Only available for the `JWT` provider.
+#[utoipa::path(
+    post,
+    path = "/register",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "Account registered", body = SignInResponse),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    )
+)]
+async fn register(
+    State(services): State<Arc<AppServices>>,
+    Json(payload): Json<RegisterRequest>,
+) -> Result<Json<SignInResponse>, ApplicationError> {
+    let response = services.auth.register(payload).await?;
+    Ok(response.into())
+}
+
+/// Create an invitation
+///
+/// Returns a single-use invitation token. The invitation grants the requested permissions, which must be a subset of the caller's, to the account registered with it.
+/// The token is only returned once.
+#[utoipa::path(
+    post,
+    path = "/invitations",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = CreateInvitationRequest,
+    responses(
+        (status = 200, description = "Invitation created", body = Invitation),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn create_invitation(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<CreateInvitationRequest>,
+) -> Result<Json<Invitation>, ApplicationError> {
+    user.check_permission(Permission::WriteAccount)?;
+    services.auth.check_step_up(&user).await?;
+
+    let invitation = services.auth.create_invitation(user, payload).await?;
+    Ok(invitation.into())
+}
+
+/// List invitations
+///
+/// Returns the invitations given a filter. Tokens are never returned.
+#[utoipa::path(
+    get,
+    path = "/invitations",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    params(InvitationFilter),
+    responses(
+        (status = 200, description = "Success", body = Vec<Invitation>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn list_invitations(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Query(filter): Query<InvitationFilter>,
+) -> Result<Json<Vec<Invitation>>, ApplicationError> {
+    user.check_permission(Permission::ReadAccount)?;
+
+    let invitations = services.auth.list_invitations(filter).await?;
+    Ok(invitations.into())
+}
+
+/// Revoke an invitation
+///
+/// Deletes an invitation by ID so that it can no longer be used. Returns an empty body.
+#[utoipa::path(
+    delete,
+    path = "/invitations/{id}",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Revoked"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn revoke_invitation(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    user.check_permission(Permission::WriteAccount)?;
+
+    services.auth.revoke_invitation(id).await?;
+    Ok(())
+}
+
+/// Create a password reset
+///
+/// Returns a single-use token with which the password of a local account can be reset. Hand it to the account owner out of band.
+/// The account cannot hold permissions the caller lacks, and only the bootstrap admin can reset its own password.
+#[utoipa::path(
+    post,
+    path = "/password-resets",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = CreatePasswordResetRequest,
+    responses(
+        (status = 200, description = "Password reset created", body = PasswordReset),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE))
+    ),
+    security(("jwt" = []))
+)]
+async fn create_password_reset(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<CreatePasswordResetRequest>,
+) -> Result<Json<PasswordReset>, ApplicationError> {
+    user.check_permission(Permission::WriteAccount)?;
+    services.auth.check_step_up(&user).await?;
+
+    let reset = services.auth.create_password_reset(user, payload.account_id).await?;
+    Ok(reset.into())
+}
+
+/// Reset Password
+///
+/// Sets a new password using a password reset token. Each token can only be used once. Failed attempts are rate limited
+/// per client IP.
+#[utoipa::path(
+    post,
+    path = "/reset-password",
+    tag = "Authentication",
+    context_path = CONTEXT_PATH,
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 204, description = "Password reset"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 422, description = "Validation failed", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 405, description = "Unsupported", body = ErrorResponse, example = json!(UNSUPPORTED_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE))
+    )
+)]
+async fn reset_password(
+    State(services): State<Arc<AppServices>>,
+    rate_limiter: Option<Extension<RateLimiter>>,
+    client_ip: Option<Extension<ClientIp>>,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<impl IntoResponse, ApplicationError> {
+    // Reset tokens name no account until consumed, so their failures are counted per client IP.
+    let subject = match client_ip {
+        Some(Extension(ClientIp(ip))) => format!("password-reset:ip:{ip}"),
+        None => "password-reset".to_string(),
+    };
+    limit_failures(
+        rate_limiter,
+        subject,
+        services.auth.reset_password(payload.token, payload.new_password),
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Runs an authentication of `subject`, rejected while its failures are exhausted. Wrong passwords and codes count as
+/// failures. Nothing is limited when rate limiting is disabled.
+async fn limit_failures<T>(
+    rate_limiter: Option<Extension<RateLimiter>>,
+    subject: String,
+    authentication: impl Future<Output = Result<T, ApplicationError>>,
+) -> Result<T, ApplicationError> {
+    let Some(Extension(rate_limiter)) = rate_limiter else {
+        return authentication.await;
+    };
+
+    rate_limiter.check_failures(&subject).await?;
+
+    let result = authentication.await;
+    if let Err(
+        ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+        | ApplicationError::Data(DataError::Validation(_)),
+    ) = &result
+    {
+        rate_limiter.record_failure(&subject).await;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+
+    use crate::{
+        application::{
+            composition::MockAppServicesBuilder,
+            errors::{AuthorizationError, RateLimitError},
+        },
+        domains::account::AuthProvider,
+        infra::axum::{InMemoryRateLimitStore, RateLimit, RateLimitConfig},
+    };
+
+    use super::*;
+
+    fn failures_limiter(failures: u32) -> RateLimiter {
+        RateLimiter::new(
+            RateLimitConfig {
+                auth_failures: RateLimit::new(failures, Duration::from_secs(60)),
+                ..Default::default()
+            },
+            Arc::new(InMemoryRateLimitStore::default()),
+        )
+    }
+
+    mod sign_up {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_the_issued_token() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_sign_up()
+                .withf(|password| password == "secret")
+                .times(1)
+                .returning(|_| {
+                    Ok(SignInResponse {
+                        token: "token".to_string(),
+                        refresh_token: "refresh".to_string(),
+                    })
+                });
+
+            let result = sign_up(
+                State(Arc::new(builder.build())),
+                Json(SignUpRequest {
+                    password: "secret".to_string(),
+                }),
+            )
+            .await;
+
+            let Json(response) = result.unwrap();
+            assert_eq!(response.token, "token");
+            assert_eq!(response.refresh_token, "refresh");
+        }
+    }
+
+    mod sign_in {
+        use super::*;
+
+        #[tokio::test]
+        async fn propagates_service_errors() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_sign_in()
+                .times(1)
+                .returning(|_, _, _| Err(DataError::NotFound("missing".to_string()).into()));
+
+            let result = sign_in(
+                State(Arc::new(builder.build())),
+                None,
+                Json(SignInRequest {
+                    login: None,
+                    password: "secret".to_string(),
+                    otp: None,
+                }),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+
+        #[tokio::test]
+        async fn rejects_logins_once_their_failures_are_exhausted() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_sign_in()
+                .times(2)
+                .returning(|_, _, _| Err(AuthenticationError::InvalidCredentials.into()));
+            let services = Arc::new(builder.build());
+            let rate_limiter = failures_limiter(2);
+            let request = |login: &str| SignInRequest {
+                login: Some(login.to_string()),
+                password: "guess".to_string(),
+                otp: None,
+            };
+
+            for _ in 0..2 {
+                let result = sign_in(
+                    State(services.clone()),
+                    Some(Extension(rate_limiter.clone())),
+                    Json(request("alice")),
+                )
+                .await;
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::Authentication(
+                        AuthenticationError::InvalidCredentials
+                    ))
+                ));
+            }
+            let result = sign_in(State(services), Some(Extension(rate_limiter)), Json(request(" Alice "))).await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::RateLimit(RateLimitError::Exceeded(key, _))) if key == "auth-failures:login:alice"
+            ));
+        }
+    }
+
+    mod refresh {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_the_new_tokens() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_refresh()
+                .withf(|refresh_token| refresh_token == "refresh")
+                .times(1)
+                .returning(|_| {
+                    Ok(SignInResponse {
+                        token: "token".to_string(),
+                        refresh_token: "next".to_string(),
+                    })
+                });
+
+            let result = refresh(
+                State(Arc::new(builder.build())),
+                Json(RefreshTokenRequest {
+                    refresh_token: "refresh".to_string(),
+                }),
+            )
+            .await;
+
+            let Json(response) = result.unwrap();
+            assert_eq!(response.refresh_token, "next");
+        }
+    }
+
+    mod sign_out {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_no_content() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_sign_out()
+                .withf(|refresh_token| refresh_token == "refresh")
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let response = sign_out(
+                State(Arc::new(builder.build())),
+                Json(RefreshTokenRequest {
+                    refresh_token: "refresh".to_string(),
+                }),
+            )
+            .await
+            .unwrap()
+            .into_response();
+
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+
+        #[tokio::test]
+        async fn all_requires_a_step_up_of_two_factor_sessions() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_check_step_up()
+                .times(1)
+                .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+            builder.auth.expect_sign_out_all().never();
+
+            let result = sign_out_all(State(Arc::new(builder.build())), User::default()).await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+    }
+
+    mod change_password {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_no_content_when_password_changed() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_change_password()
+                .withf(|_, current_password, new_password| current_password == "old" && new_password == "new")
+                .times(1)
+                .returning(|_, _, _| Ok(()));
+
+            let response = change_password(
+                State(Arc::new(builder.build())),
+                User::default(),
+                Json(ChangePasswordRequest {
+                    current_password: "old".to_string(),
+                    new_password: "new".to_string(),
+                }),
+            )
+            .await
+            .unwrap()
+            .into_response();
+
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+    }
+
+    mod create_invitation {
+        use super::*;
+
+        #[tokio::test]
+        async fn requires_write_account_permission() {
+            let result = create_invitation(
+                State(Arc::new(MockAppServicesBuilder::new().build())),
+                User {
+                    permissions: vec![Permission::ReadAccount],
+                    ..Default::default()
+                },
+                Json(CreateInvitationRequest {
+                    email: None,
+                    permissions: vec![],
+                    expiry: None,
+                }),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn requires_a_step_up_of_two_factor_sessions() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_check_step_up()
+                .times(1)
+                .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+
+            let result = create_invitation(
+                State(Arc::new(builder.build())),
+                User {
+                    permissions: vec![Permission::WriteAccount],
+                    ..Default::default()
+                },
+                Json(CreateInvitationRequest {
+                    email: None,
+                    permissions: vec![],
+                    expiry: None,
+                }),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+    }
+
+    mod totp {
+        use super::*;
+
+        #[tokio::test]
+        async fn confirm_returns_the_recovery_codes() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_confirm_totp()
+                .withf(|_, code| code == "123456")
+                .times(1)
+                .returning(|_, _| {
+                    Ok(TotpRecoveryCodes {
+                        recovery_codes: vec!["abcde-fghij".to_string()],
+                    })
+                });
+
+            let result = confirm_totp(
+                State(Arc::new(builder.build())),
+                None,
+                User::default(),
+                Json(TotpCodeRequest {
+                    code: "123456".to_string(),
+                }),
+            )
+            .await;
+
+            let Json(response) = result.unwrap();
+            assert_eq!(response.recovery_codes, vec!["abcde-fghij".to_string()]);
+        }
+
+        #[tokio::test]
+        async fn disable_rejects_codes_once_the_account_failures_are_exhausted() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_disable_totp()
+                .times(3)
+                .returning(|_, _| Err(DataError::Validation("Invalid code.".to_string()).into()));
+            let services = Arc::new(builder.build());
+            let rate_limiter = failures_limiter(3);
+            let user = User::default();
+
+            for _ in 0..3 {
+                let result = disable_totp(
+                    State(services.clone()),
+                    Some(Extension(rate_limiter.clone())),
+                    user.clone(),
+                    Json(TotpCodeRequest {
+                        code: "000000".to_string(),
+                    }),
+                )
+                .await;
+                assert!(matches!(result, Err(ApplicationError::Data(DataError::Validation(_)))));
+            }
+            let response = disable_totp(
+                State(services),
+                Some(Extension(rate_limiter)),
+                user,
+                Json(TotpCodeRequest {
+                    code: "000000".to_string(),
+                }),
+            )
+            .await
+            .into_response();
+
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        #[tokio::test]
+        async fn step_up_returns_no_content() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_step_up()
+                .withf(|_, code| code == "123456")
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let response = step_up(
+                State(Arc::new(builder.build())),
+                None,
+                User::default(),
+                Json(TotpCodeRequest {
+                    code: "123456".to_string(),
+                }),
+            )
+            .await
+            .unwrap()
+            .into_response();
+
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        }
+    }
+
+    mod passkeys {
+        use super::*;
+
+        fn sign_in_request(ceremony_id: Uuid) -> PasskeySignInRequest {
+            PasskeySignInRequest {
+                ceremony_id,
+                credential: PasskeyAssertion {
+                    id: "AQID".to_string(),
+                    response: PasskeyAssertionResponse {
+                        client_data_json: "e30".to_string(),
+                        authenticator_data: "AA".to_string(),
+                        signature: "AA".to_string(),
+                        user_handle: None,
+                    },
+                },
+            }
+        }
+
+        #[tokio::test]
+        async fn registration_options_require_a_step_up_of_two_factor_sessions() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_check_step_up()
+                .times(1)
+                .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+            builder.auth.expect_passkey_registration_options().never();
+
+            let result = passkey_registration_options(State(Arc::new(builder.build())), User::default()).await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+
+        #[tokio::test]
+        async fn sign_in_returns_the_issued_token() {
+            let ceremony_id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_sign_in_passkey()
+                .withf(move |request| request.ceremony_id == ceremony_id && request.credential.id == "AQID")
+                .times(1)
+                .returning(|_| {
+                    Ok(SignInResponse {
+                        token: "token".to_string(),
+                        refresh_token: "refresh".to_string(),
+                    })
+                });
+
+            let result = sign_in_passkey(
+                State(Arc::new(builder.build())),
+                None,
+                Json(sign_in_request(ceremony_id)),
+            )
+            .await;
+
+            let Json(response) = result.unwrap();
+            assert_eq!(response.token, "token");
+            assert_eq!(response.refresh_token, "refresh");
+        }
+
+        #[tokio::test]
+        async fn sign_in_rejects_passkeys_once_their_failures_are_exhausted() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_sign_in_passkey()
+                .times(1)
+                .returning(|_| Err(AuthenticationError::InvalidCredentials.into()));
+            let services = Arc::new(builder.build());
+            let rate_limiter = failures_limiter(1);
+
+            let result = sign_in_passkey(
+                State(services.clone()),
+                Some(Extension(rate_limiter.clone())),
+                Json(sign_in_request(Uuid::new_v4())),
+            )
+            .await;
+            assert!(result.is_err());
+            let result = sign_in_passkey(
+                State(services),
+                Some(Extension(rate_limiter)),
+                Json(sign_in_request(Uuid::new_v4())),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::RateLimit(RateLimitError::Exceeded(key, _))) if key == "auth-failures:passkey:AQID"
+            ));
+        }
+
+        #[tokio::test]
+        async fn delete_requires_a_step_up_of_two_factor_sessions() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_check_step_up()
+                .times(1)
+                .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+            builder.auth.expect_delete_passkey().never();
+
+            let result = delete_passkey(State(Arc::new(builder.build())), User::default(), Path(Uuid::new_v4())).await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+
+        #[tokio::test]
+        async fn delete_propagates_not_found() {
+            let id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_check_step_up().times(1).returning(|_| Ok(()));
+            builder
+                .auth
+                .expect_delete_passkey()
+                .withf(move |_, passkey_id| *passkey_id == id)
+                .times(1)
+                .returning(|_, _| Err(DataError::NotFound("Passkey not found.".to_string()).into()));
+
+            let result = delete_passkey(State(Arc::new(builder.build())), User::default(), Path(id)).await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+    }
+
+    mod nostr_keys {
+        use super::*;
+
+        #[tokio::test]
+        async fn link_requires_a_step_up_of_two_factor_sessions() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_check_step_up()
+                .times(1)
+                .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+            builder.auth.expect_link_nostr_key().never();
+
+            let result = link_nostr_key(
+                State(Arc::new(builder.build())),
+                User::default(),
+                Json(LinkNostrKeyRequest {
+                    event: "e30=".to_string(),
+                }),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+
+        #[tokio::test]
+        async fn link_returns_the_linked_key() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_check_step_up().times(1).returning(|_| Ok(()));
+            builder
+                .auth
+                .expect_link_nostr_key()
+                .withf(|_, event| event == "e30=")
+                .times(1)
+                .returning(|_, _| {
+                    Ok(AuthIdentity {
+                        id: Uuid::new_v4(),
+                        provider: AuthProvider::Nostr,
+                        subject: "3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d".to_string(),
+                        created_at: Utc::now(),
+                    })
+                });
+
+            let result = link_nostr_key(
+                State(Arc::new(builder.build())),
+                User::default(),
+                Json(LinkNostrKeyRequest {
+                    event: "e30=".to_string(),
+                }),
+            )
+            .await;
+
+            let Json(identity) = result.unwrap();
+            assert_eq!(identity.provider, AuthProvider::Nostr);
+        }
+    }
+
+    mod reset_password {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_no_content_when_password_reset() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_reset_password()
+                .withf(|token, new_password| token == "token" && new_password == "battery staple")
+                .times(1)
+                .returning(|_, _| Ok(()));
+
+            let response = reset_password(
+                State(Arc::new(builder.build())),
+                None,
+                None,
+                Json(ResetPasswordRequest {
+                    token: "token".to_string(),
+                    new_password: "battery staple".to_string(),
+                }),
+            )
             .await
             .unwrap()
             .into_response();
 
             assert_eq!(response.status(), StatusCode::NO_CONTENT);
         }
+
+        #[tokio::test]
+        async fn counts_invalid_tokens_per_client_ip() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_reset_password().times(1).returning(|_, _| {
+                Err(DataError::Validation("Invalid or expired password reset token.".to_string()).into())
+            });
+            let services = Arc::new(builder.build());
+            let rate_limiter = failures_limiter(1);
+            let client_ip = ClientIp("203.0.113.1".parse().unwrap());
+            let request = || ResetPasswordRequest {
+                token: "guess".to_string(),
+                new_password: "battery staple".to_string(),
+            };
+
+            let result = reset_password(
+                State(services.clone()),
+                Some(Extension(rate_limiter.clone())),
+                Some(Extension(client_ip)),
+                Json(request()),
+            )
+            .await;
+            assert!(result.is_err());
+            let result = reset_password(
+                State(services),
+                Some(Extension(rate_limiter)),
+                Some(Extension(client_ip)),
+                Json(request()),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::RateLimit(RateLimitError::Exceeded(key, _)))
+                    if key == "auth-failures:password-reset:ip:203.0.113.1"
+            ));
+        }
     }
 }
@@ -1,27 +1,95 @@
 use std::sync::Arc;
 
-use axum::{extract::FromRequestParts, http::request::Parts, RequestPartsExt};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, OriginalUri, Request},
+    http::{header::AUTHORIZATION, request::Parts, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    RequestPartsExt,
+};
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
 use base64::{prelude::BASE64_STANDARD, Engine};
+use uuid::Uuid;
 
-use crate::application::{
-    composition::AppServices,
-    errors::{ApplicationError, AuthenticationError},
+use crate::{
+    application::{
+        composition::AppServices,
+        errors::{ApplicationError, AuthenticationError, DataError},
+    },
+    domains::audit::AuditActor,
+    infra::axum::{ClientIp, RateLimiter},
 };
 
-use super::User;
+use super::{nip98, User};
+
+/// Header selecting the organization the caller acts for.
+const ORGANIZATION_HEADER: &str = "organization-id";
+/// Largest body of NIP-98 authenticated requests, the default limit of the `Json` extractor.
+const MAX_NOSTR_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Hex SHA-256 hash of the body of a NIP-98 authenticated request, `None` when empty.
+#[derive(Clone)]
+pub struct NostrPayload(Option<String>);
+
+/// Hashes the body of requests with an `Authorization: Nostr` header, which their event commits to.
+pub async fn nostr_payload_middleware(request: Request, next: Next) -> Response {
+    if nostr_token(request.headers()).is_none() {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_NOSTR_BODY_BYTES).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+
+    let payload_hash = (!bytes.is_empty()).then(|| nip98::payload_hash(&bytes));
+    parts.extensions.insert(NostrPayload(payload_hash));
+
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
+
+fn nostr_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix(nip98::SCHEME)?
+        .strip_prefix(' ')
+}
 
 impl FromRequestParts<Arc<AppServices>> for User {
     type Rejection = ApplicationError;
 
     async fn from_request_parts(parts: &mut Parts, services: &Arc<AppServices>) -> Result<Self, Self::Rejection> {
         // Try to extract the Authorization header as Bearer token
-        if let Ok(TypedHeader(Authorization(bearer))) = parts.extract::<TypedHeader<Authorization<Bearer>>>().await {
-            let user = services.auth.authenticate_jwt(bearer.token()).await?;
-            Ok(user)
+        let user = if let Ok(TypedHeader(Authorization(bearer))) =
+            parts.extract::<TypedHeader<Authorization<Bearer>>>().await
+        {
+            services.auth.authenticate_jwt(bearer.token()).await?
+        }
+        // Try to extract the Authorization header as NIP-98 event
+        else if let Some(token) = nostr_token(&parts.headers) {
+            // Without the hash of the body, the event could not be checked against it.
+            let NostrPayload(payload_hash) = parts
+                .extensions
+                .get::<NostrPayload>()
+                .cloned()
+                .ok_or(AuthenticationError::InvalidCredentials)?;
+            // Nested routers strip their prefix from the URI.
+            let uri = parts
+                .extensions
+                .get::<OriginalUri>()
+                .map_or(&parts.uri, |OriginalUri(uri)| uri);
+            let path = uri.path_and_query().map_or(uri.path(), |path| path.as_str());
+
+            services
+                .auth
+                .authenticate_nostr(token, parts.method.as_str(), path, payload_hash)
+                .await?
         }
         // Try to extract the Api-Key header
         else if let Some(value) = parts.headers.get("api-key") {
@@ -30,12 +98,38 @@ impl FromRequestParts<Arc<AppServices>> for User {
                 .decode(value_str)
                 .map_err(|_| AuthenticationError::InvalidCredentials)?;
 
-            let user = services.auth.authenticate_api_key(api_key).await?;
-            Ok(user)
+            services
+                .auth
+                .authenticate_api_key(api_key, ClientIp::of(&parts.extensions))
+                .await?
         }
         // If no Authorization header is present, return an error
         else {
-            Err(AuthenticationError::MissingAuthorizationHeader.into())
+            return Err(AuthenticationError::MissingAuthorizationHeader.into());
+        };
+
+        if let Some(rate_limiter) = parts.extensions.get::<RateLimiter>() {
+            rate_limiter.check_principal(user.account_id, user.api_key_id).await?;
         }
+
+        // Members act for an organization by naming it, within the permissions of their role.
+        let user = match parts.headers.get(ORGANIZATION_HEADER) {
+            Some(value) => {
+                let organization_id = value
+                    .to_str()
+                    .ok()
+                    .and_then(|value| Uuid::parse_str(value).ok())
+                    .ok_or_else(|| DataError::Validation(format!("Invalid {ORGANIZATION_HEADER} header")))?;
+
+                services.organization.act_for(user, organization_id).await?
+            }
+            None => user,
+        };
+
+        if let Some(actor) = parts.extensions.get::<AuditActor>() {
+            actor.set(&user);
+        }
+
+        Ok(user)
     }
 }
@@ -1,32 +1,67 @@
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 
 use async_trait::async_trait;
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
 use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Url;
 use serde_bolt::bitcoin::hashes::{sha256, Hash};
+use swissknife_types::{
+    PasskeyAuthenticatorSelection, PasskeyCreationOptions, PasskeyCredentialDescriptor, PasskeyCredentialParameters,
+    PasskeyRelyingParty, PasskeyRequestOptions, PasskeyUser,
+};
 use tokio::sync::OnceCell;
+use uuid::Uuid;
 
-use tracing::{debug, trace};
+use tracing::{debug, info, trace, warn};
 
 use crate::{
     application::{
         composition::AppStore,
-        composition::AuthProvider,
-        errors::{ApplicationError, AuthenticationError, DataError},
+        composition::{AuthProvider, LocalAuthConfig},
+        errors::{ApplicationError, AuthenticationError, AuthorizationError, DataError},
+    },
+    domains::{
+        audit::{record_created, record_deleted},
+        bitcoin::BtcNetwork,
     },
-    domains::bitcoin::BtcNetwork,
     infra::jwt::JWTAuthenticator,
 };
 
-use super::{AuthUseCases, Permission, User};
+use super::{
+    nip98,
+    totp::{self, RECOVERY_CODE_COUNT},
+    webauthn, Account, AccountProvisioning, AuthIdentity, AuthUseCases, CreateInvitationRequest, Invitation,
+    InvitationFilter, LocalRegistration, Passkey, PasskeyAuthenticationOptions, PasskeyChallenge,
+    PasskeyRegistrationOptions, PasskeySignInRequest, PasswordReset, Permission, RegisterPasskeyRequest,
+    RegisterRequest, SignInResponse, TotpEnrollment, TotpFactor, TotpRecoveryCodes, User,
+};
 
 pub const PASSWORD_HASH_KEY: &str = "password_hash";
 const BOOTSTRAP_ADMIN_SUBJECT: &str = "admin";
+const MIN_PASSWORD_LENGTH: usize = 8;
+const MAX_USERNAME_LENGTH: usize = 64;
+const MAX_INVITATION_EXPIRY_SECONDS: u32 = 31_536_000; // 1 year in seconds
+/// API key usage is recorded at most once per interval and source IP to avoid a write on every request.
+const API_KEY_USAGE_RESOLUTION_SECONDS: i64 = 60;
+/// Issuer shown by authenticator apps and passkey managers next to the account name.
+const ISSUER: &str = "Swissknife";
+const DEFAULT_PASSKEY_NAME: &str = "Passkey";
+const MAX_PASSKEY_NAME_LENGTH: usize = 64;
+const PUBLIC_KEY_CREDENTIAL_TYPE: &str = "public-key";
+/// Endpoint linking Nostr keys, for which their NIP-98 proof of ownership is signed.
+const NOSTR_KEYS_PATH: &str = "/v1/auth/nostr-keys";
 
 pub struct AuthService {
     jwt_authenticator: Arc<dyn JWTAuthenticator>,
     store: AppStore,
     provider: AuthProvider,
     network: BtcNetwork,
+    local_auth: LocalAuthConfig,
+    passkey_rp_id: String,
+    passkey_origin: String,
+    /// Public URL of the API, which NIP-98 events are signed for.
+    api_url: String,
     active_asset_id: OnceCell<uuid::Uuid>,
 }
 
@@ -36,12 +71,31 @@ impl AuthService {
         store: AppStore,
         provider: AuthProvider,
         network: BtcNetwork,
+        local_auth: LocalAuthConfig,
+        host: String,
     ) -> Self {
+        let api_url = host.trim_end_matches('/').to_string();
+        let host_url = Url::parse(&host).ok();
+        let passkey_rp_id = local_auth
+            .passkey_rp_id
+            .clone()
+            .or_else(|| host_url.as_ref().and_then(|url| url.host_str().map(str::to_string)))
+            .unwrap_or_else(|| host.clone());
+        let passkey_origin = local_auth
+            .passkey_origin
+            .clone()
+            .or_else(|| host_url.map(|url| url.origin().ascii_serialization()))
+            .unwrap_or(host);
+
         AuthService {
             jwt_authenticator,
             store,
             provider,
             network,
+            local_auth,
+            passkey_rp_id,
+            passkey_origin,
+            api_url,
             active_asset_id: OnceCell::new(),
         }
     }
@@ -66,22 +120,331 @@ impl AuthService {
             })
             .await?)
     }
+
+    fn ensure_local_provider(&self) -> Result<(), ApplicationError> {
+        if self.provider != AuthProvider::Jwt {
+            return Err(AuthenticationError::UnsupportedOperation.into());
+        }
+
+        Ok(())
+    }
+
+    async fn admin_password_hash(&self) -> Result<Option<String>, ApplicationError> {
+        match self.store.config.find(PASSWORD_HASH_KEY).await? {
+            Some(password_hash) => Ok(Some(
+                password_hash
+                    .as_str()
+                    .ok_or_else(|| DataError::Inconsistency("Expected string in password hash".to_string()))?
+                    .to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Starts a sign-in session for `account` and issues its access and refresh tokens. `verified_at` is set when
+    /// the second factor was verified at sign-in.
+    async fn start_session(
+        &self,
+        account: Account,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<SignInResponse, ApplicationError> {
+        let (refresh_token, refresh_token_hash) = generate_token();
+        let expires_at = Utc::now() + Duration::seconds(self.local_auth.refresh_token_expiry.as_secs() as i64);
+        let session = self
+            .store
+            .session
+            .insert(account.id, refresh_token_hash, expires_at, verified_at)
+            .await?;
+        record_created(&session);
+        let token = self.jwt_authenticator.encode(account, session.id)?;
+
+        Ok(SignInResponse { token, refresh_token })
+    }
+
+    async fn confirmed_totp_factor(&self, account_id: Uuid) -> Result<Option<TotpFactor>, ApplicationError> {
+        Ok(self.store.totp.find(account_id).await?.filter(TotpFactor::is_confirmed))
+    }
+
+    /// Verifies a TOTP code or recovery code of `factor`. Each code is accepted once.
+    async fn verify_second_factor(&self, factor: &TotpFactor, code: &str) -> Result<bool, ApplicationError> {
+        if totp::is_totp_code(code) {
+            return match totp::verify_code(&factor.secret, code, Utc::now(), factor.last_used_step) {
+                Some(step) => Ok(self.store.totp.use_step(factor.account_id, step).await?),
+                None => Ok(false),
+            };
+        }
+
+        Ok(self
+            .store
+            .totp
+            .use_recovery_code(factor.account_id, totp::recovery_code_hash(code))
+            .await?)
+    }
+
+    /// Checks the second factor of a sign-in, if the account has one, and returns the verification time.
+    async fn verify_sign_in_otp(
+        &self,
+        account_id: Uuid,
+        otp: Option<String>,
+    ) -> Result<Option<DateTime<Utc>>, ApplicationError> {
+        let Some(factor) = self.confirmed_totp_factor(account_id).await? else {
+            return Ok(None);
+        };
+
+        let otp = otp
+            .filter(|otp| !otp.trim().is_empty())
+            .ok_or(AuthenticationError::OtpRequired)?;
+        if !self.verify_second_factor(&factor, &otp).await? {
+            return Err(AuthenticationError::InvalidCredentials.into());
+        }
+
+        Ok(Some(Utc::now()))
+    }
+
+    /// Whether the caller must verify its second factor again before a sensitive operation. API keys never require
+    /// it. Other principals without a session, such as Nostr keys, always do when their account has a second factor,
+    /// as they have no session to verify it in.
+    async fn requires_step_up(&self, user: &User) -> Result<bool, ApplicationError> {
+        if user.api_key_id.is_some() {
+            return Ok(false);
+        }
+
+        if self.confirmed_totp_factor(user.principal_account_id()).await?.is_none() {
+            return Ok(false);
+        }
+
+        let Some(session_id) = user.session_id else {
+            return Ok(true);
+        };
+
+        let session = self
+            .store
+            .session
+            .find(session_id)
+            .await?
+            .ok_or(AuthenticationError::InvalidCredentials)?;
+        let window = Duration::seconds(self.local_auth.step_up_window.as_secs() as i64);
+
+        Ok(session
+            .verified_at
+            .is_none_or(|verified_at| Utc::now() - verified_at >= window))
+    }
+
+    async fn sign_in_admin(&self, password: String, otp: Option<String>) -> Result<SignInResponse, ApplicationError> {
+        let password_hash = self
+            .admin_password_hash()
+            .await?
+            .ok_or_else(|| DataError::NotFound("Missing admin credentials".into()))?;
+
+        if !verify(password, &password_hash).map_err(|e| AuthenticationError::Hash(e.to_string()))? {
+            return Err(AuthenticationError::InvalidCredentials.into());
+        }
+
+        let account = self
+            .store
+            .account
+            .find_by_identity(self.provider, BOOTSTRAP_ADMIN_SUBJECT)
+            .await?
+            .ok_or_else(|| {
+                DataError::Inconsistency("Admin credentials exist without an account identity".to_string())
+            })?;
+
+        let verified_at = self.verify_sign_in_otp(account.id, otp).await?;
+        let response = self.start_session(account, verified_at).await?;
+
+        debug!("User logged in successfully");
+        Ok(response)
+    }
+
+    /// Stores the challenge of a new passkey ceremony, bound to `account_id` for registrations.
+    async fn insert_passkey_challenge(&self, account_id: Option<Uuid>) -> Result<PasskeyChallenge, ApplicationError> {
+        let now = Utc::now();
+        let challenge = PasskeyChallenge {
+            id: Uuid::new_v4(),
+            challenge: webauthn::generate_challenge(),
+            account_id,
+            expires_at: now + Duration::seconds(self.local_auth.passkey_challenge_expiry.as_secs() as i64),
+            created_at: now,
+        };
+        self.store.passkey.insert_challenge(challenge.clone()).await?;
+
+        Ok(challenge)
+    }
+
+    fn passkey_timeout_ms(&self) -> u64 {
+        self.local_auth.passkey_challenge_expiry.as_millis() as u64
+    }
+
+    /// Verifies a passkey assertion and returns the passkey with its new signature counter.
+    async fn verify_passkey_assertion(
+        &self,
+        request: &PasskeySignInRequest,
+    ) -> Result<(Passkey, u32), ApplicationError> {
+        let challenge = self
+            .store
+            .passkey
+            .consume_challenge(request.ceremony_id)
+            .await?
+            .filter(|challenge| challenge.account_id.is_none())
+            .ok_or_else(|| DataError::Validation("Invalid or expired passkey ceremony.".to_string()))?;
+
+        let response = &request.credential.response;
+        let passkey = self
+            .store
+            .passkey
+            .find_by_credential_id(webauthn::decode(&request.credential.id)?)
+            .await?
+            .ok_or_else(|| DataError::Validation("Unknown passkey.".to_string()))?;
+
+        if let Some(user_handle) = &response.user_handle {
+            if webauthn::decode(user_handle)? != passkey.account_id.as_bytes() {
+                return Err(DataError::Validation("User handle does not match the passkey.".to_string()).into());
+            }
+        }
+
+        let sign_count = webauthn::verify_assertion(
+            &self.passkey_rp_id,
+            &self.passkey_origin,
+            &challenge.challenge,
+            &passkey.public_key,
+            &webauthn::decode(&response.client_data_json)?,
+            &webauthn::decode(&response.authenticator_data)?,
+            &webauthn::decode(&response.signature)?,
+        )?;
+
+        Ok((passkey, sign_count))
+    }
+
+    /// Stores the new password hash of an account, in the config table for the bootstrap admin, and ends all of
+    /// its sessions.
+    async fn set_password(&self, account: &Account, password_hash: String) -> Result<(), ApplicationError> {
+        if is_bootstrap_admin(account) {
+            self.store
+                .config
+                .upsert(PASSWORD_HASH_KEY, password_hash.into())
+                .await?;
+        } else {
+            let credential = self
+                .store
+                .credential
+                .find_by_account(account.id)
+                .await?
+                .ok_or_else(|| DataError::NotFound("Missing local credentials".into()))?;
+            self.store
+                .credential
+                .update_password(credential.identity_id, password_hash)
+                .await?;
+        }
+
+        let n_revoked = self.store.session.delete_by_account(account.id).await?;
+
+        debug!(account_id = %account.id, n_revoked, "Sessions revoked after password change");
+        Ok(())
+    }
+
+    /// Verifies a NIP-98 event for the request and remembers it so that it cannot authorize another one, on any
+    /// instance. Returns the public key that signed it.
+    async fn accept_nostr_event(
+        &self,
+        token: &str,
+        request: &nip98::SignedRequest<'_>,
+    ) -> Result<String, ApplicationError> {
+        let event = nip98::verify(token, request, Utc::now().timestamp() as u64)?;
+
+        let expires_at = DateTime::from_timestamp(event.expires_at as i64, 0).unwrap_or(DateTime::<Utc>::MAX_UTC);
+        if !self.store.nostr_event.insert(event.id, expires_at).await? {
+            return Err(DataError::Validation("NIP-98 event was already used.".to_string()).into());
+        }
+
+        Ok(event.pubkey)
+    }
+}
+
+fn is_bootstrap_admin(account: &Account) -> bool {
+    account
+        .identity
+        .as_ref()
+        .is_some_and(|identity| identity.provider == AuthProvider::Jwt && identity.subject == BOOTSTRAP_ADMIN_SUBJECT)
+}
+
+/// Login identity of a local account, to which its passkeys are linked.
+fn local_identity(account: &Account) -> Result<&AuthIdentity, DataError> {
+    account
+        .identity
+        .as_ref()
+        .filter(|identity| identity.provider == AuthProvider::Jwt)
+        .ok_or_else(|| DataError::Validation("Account has no local login identity.".to_string()))
+}
+
+fn hash_password(password: &str) -> Result<String, ApplicationError> {
+    Ok(hash(password, DEFAULT_COST).map_err(|e| AuthenticationError::Hash(e.to_string()))?)
+}
+
+fn validate_password(password: &str) -> Result<(), DataError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(DataError::Validation(format!(
+            "Password must be at least {MIN_PASSWORD_LENGTH} characters long."
+        )));
+    }
+
+    Ok(())
+}
+
+fn normalize_username(username: &str) -> Result<String, DataError> {
+    let username = username.trim().to_lowercase();
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if username.is_empty() || username.len() > MAX_USERNAME_LENGTH || !valid_chars {
+        return Err(DataError::Validation(format!(
+            "Username must be 1 to {MAX_USERNAME_LENGTH} lowercase letters, digits, '.', '-' or '_'."
+        )));
+    }
+
+    if username == BOOTSTRAP_ADMIN_SUBJECT {
+        return Err(DataError::Conflict("Username is already taken.".to_string()));
+    }
+
+    Ok(username)
+}
+
+fn normalize_email(email: &str) -> Result<String, DataError> {
+    let email = email.trim().to_lowercase();
+    let valid = email.len() <= 255
+        && email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && !domain.is_empty() && !domain.contains('@'));
+    if !valid {
+        return Err(DataError::Validation("Invalid email address.".to_string()));
+    }
+
+    Ok(email)
+}
+
+/// Generates a one-time token, returned to the caller, and the hash stored in its place.
+fn generate_token() -> (String, Vec<u8>) {
+    let bytes: [u8; 32] = rand::random();
+    let token_hash = sha256::Hash::hash(&bytes).to_byte_array().to_vec();
+    (BASE64_URL_SAFE_NO_PAD.encode(bytes), token_hash)
+}
+
+fn token_hash(token: &str) -> Option<Vec<u8>> {
+    let bytes = BASE64_URL_SAFE_NO_PAD.decode(token.trim()).ok()?;
+    Some(sha256::Hash::hash(&bytes).to_byte_array().to_vec())
 }
 
 #[async_trait]
 impl AuthUseCases for AuthService {
-    async fn sign_up(&self, password: String) -> Result<String, ApplicationError> {
+    async fn sign_up(&self, password: String) -> Result<SignInResponse, ApplicationError> {
         trace!("Start sign up");
 
-        if self.provider != AuthProvider::Jwt {
-            return Err(AuthenticationError::UnsupportedOperation.into());
-        }
+        self.ensure_local_provider()?;
 
         if self.store.config.find(PASSWORD_HASH_KEY).await?.is_some() {
             return Err(DataError::Conflict("Admin account already created".into()).into());
         }
 
-        let password_hash = hash(&password, DEFAULT_COST).map_err(|e| AuthenticationError::Hash(e.to_string()))?;
+        let password_hash = hash_password(&password)?;
 
         let permissions = Permission::all_permissions();
         let account = self
@@ -99,402 +462,2132 @@ impl AuthUseCases for AuthService {
             return Err(DataError::Conflict("Admin account already created".into()).into());
         }
 
-        let token = self.jwt_authenticator.encode(account)?;
+        let response = self.start_session(account, None).await?;
 
         debug!("Admin account created successfully");
-        Ok(token)
+        Ok(response)
     }
 
-    async fn sign_in(&self, password: String) -> Result<String, ApplicationError> {
-        trace!("Start login");
+    async fn sign_in(
+        &self,
+        login: Option<String>,
+        password: String,
+        otp: Option<String>,
+    ) -> Result<SignInResponse, ApplicationError> {
+        trace!(?login, "Start login");
+
+        self.ensure_local_provider()?;
+
+        let login = login
+            .map(|login| login.trim().to_lowercase())
+            .filter(|login| !login.is_empty());
+        let login = match login.as_deref() {
+            None | Some(BOOTSTRAP_ADMIN_SUBJECT) => return self.sign_in_admin(password, otp).await,
+            Some(login) => login,
+        };
 
-        if self.provider != AuthProvider::Jwt {
-            return Err(AuthenticationError::UnsupportedOperation.into());
+        // Unknown logins and wrong passwords are indistinguishable to the caller.
+        let credential = self
+            .store
+            .credential
+            .find_by_login(login)
+            .await?
+            .ok_or(AuthenticationError::InvalidCredentials)?;
+
+        if !verify(password, &credential.password_hash).map_err(|e| AuthenticationError::Hash(e.to_string()))? {
+            return Err(AuthenticationError::InvalidCredentials.into());
         }
-        match self.store.config.find(PASSWORD_HASH_KEY).await? {
-            Some(password_hash) => {
-                let password_hash_str = password_hash
-                    .as_str()
-                    .ok_or_else(|| DataError::Inconsistency("Expected string in password hash".to_string()))?;
 
-                if !verify(password, password_hash_str).map_err(|e| AuthenticationError::Hash(e.to_string()))? {
-                    return Err(AuthenticationError::InvalidCredentials.into());
-                }
+        let account = self
+            .store
+            .account
+            .find_by_identity(self.provider, &credential.username)
+            .await?
+            .ok_or_else(|| {
+                DataError::Inconsistency("Local credentials exist without an account identity".to_string())
+            })?;
 
-                let account = self
-                    .store
-                    .account
-                    .find_by_identity(self.provider, BOOTSTRAP_ADMIN_SUBJECT)
-                    .await?
-                    .ok_or_else(|| {
-                        DataError::Inconsistency("Admin credentials exist without an account identity".to_string())
-                    })?;
+        let verified_at = self.verify_sign_in_otp(account.id, otp).await?;
+        let response = self.start_session(account, verified_at).await?;
+
+        debug!(account_id = %credential.account_id, "User logged in successfully");
+        Ok(response)
+    }
+
+    async fn refresh(&self, refresh_token: String) -> Result<SignInResponse, ApplicationError> {
+        trace!("Start token refresh");
 
-                let token = self.jwt_authenticator.encode(account)?;
+        self.ensure_local_provider()?;
 
-                debug!("User logged in successfully");
-                Ok(token)
+        let (new_refresh_token, new_refresh_token_hash) = generate_token();
+        let expires_at = Utc::now() + Duration::seconds(self.local_auth.refresh_token_expiry.as_secs() as i64);
+        let session = match token_hash(&refresh_token) {
+            Some(refresh_token_hash) => {
+                self.store
+                    .session
+                    .refresh(refresh_token_hash, new_refresh_token_hash, expires_at)
+                    .await?
             }
-            None => Err(DataError::NotFound("Missing admin credentials".into()).into()),
+            None => None,
         }
+        .ok_or(AuthenticationError::InvalidCredentials)?;
+
+        let account = self
+            .store
+            .account
+            .find(session.account_id)
+            .await?
+            .ok_or(AuthenticationError::InvalidCredentials)?;
+        let token = self.jwt_authenticator.encode(account, session.id)?;
+
+        debug!(session_id = %session.id, "Token refreshed successfully");
+        Ok(SignInResponse {
+            token,
+            refresh_token: new_refresh_token,
+        })
     }
 
-    async fn change_password(&self, current_password: String, new_password: String) -> Result<(), ApplicationError> {
-        trace!("Start password change");
+    async fn sign_out(&self, refresh_token: String) -> Result<(), ApplicationError> {
+        trace!("Start sign out");
 
-        if self.provider != AuthProvider::Jwt {
-            return Err(AuthenticationError::UnsupportedOperation.into());
+        self.ensure_local_provider()?;
+
+        if let Some(refresh_token_hash) = token_hash(&refresh_token) {
+            if let Some(session) = self.store.session.delete_by_refresh_token(refresh_token_hash).await? {
+                record_deleted(&session);
+            }
         }
 
-        let password_hash = self
+        debug!("User signed out successfully");
+        Ok(())
+    }
+
+    async fn sign_out_all(&self, user: User) -> Result<u64, ApplicationError> {
+        trace!(account_id = %user.principal_account_id(), "Start sign out of all sessions");
+
+        self.ensure_local_provider()?;
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let n_revoked = self
             .store
-            .config
-            .find(PASSWORD_HASH_KEY)
+            .session
+            .delete_by_account(user.principal_account_id())
+            .await?;
+        record_deleted(&serde_json::json!({ "account_id": user.principal_account_id(), "sessions": n_revoked }));
+
+        info!(account_id = %user.principal_account_id(), n_revoked, "All sessions signed out successfully");
+        Ok(n_revoked)
+    }
+
+    async fn change_password(
+        &self,
+        user: User,
+        current_password: String,
+        new_password: String,
+    ) -> Result<(), ApplicationError> {
+        trace!(account_id = %user.principal_account_id(), "Start password change");
+
+        self.ensure_local_provider()?;
+
+        let account = self
+            .store
+            .account
+            .find(user.principal_account_id())
             .await?
-            .ok_or_else(|| DataError::NotFound("Missing admin credentials".into()))?;
-        let password_hash_str = password_hash
-            .as_str()
-            .ok_or_else(|| DataError::Inconsistency("Expected string in password hash".to_string()))?;
+            .ok_or_else(|| DataError::NotFound("Account not found.".into()))?;
+
+        let password_hash = if is_bootstrap_admin(&account) {
+            self.admin_password_hash()
+                .await?
+                .ok_or_else(|| DataError::NotFound("Missing admin credentials".into()))?
+        } else {
+            validate_password(&new_password)?;
+            self.store
+                .credential
+                .find_by_account(account.id)
+                .await?
+                .ok_or_else(|| DataError::NotFound("Missing local credentials".into()))?
+                .password_hash
+        };
 
-        if !verify(&current_password, password_hash_str).map_err(|e| AuthenticationError::Hash(e.to_string()))? {
+        if !verify(&current_password, &password_hash).map_err(|e| AuthenticationError::Hash(e.to_string()))? {
             return Err(DataError::Validation("Current password is incorrect".to_string()).into());
         }
 
-        let new_password_hash =
-            hash(&new_password, DEFAULT_COST).map_err(|e| AuthenticationError::Hash(e.to_string()))?;
+        self.set_password(&account, hash_password(&new_password)?).await?;
 
-        self.store
-            .config
-            .upsert(PASSWORD_HASH_KEY, new_password_hash.into())
-            .await?;
-
-        debug!("Admin password changed successfully");
+        debug!(account_id = %account.id, "Password changed successfully");
         Ok(())
     }
 
-    async fn authenticate_jwt(&self, token: &str) -> Result<User, ApplicationError> {
-        trace!("Start JWT authentication");
+    async fn register(&self, request: RegisterRequest) -> Result<SignInResponse, ApplicationError> {
+        trace!(username = %request.username, "Start registration");
 
-        let claims = self.jwt_authenticator.decode(token).await?;
-        let account = match self.store.account.find_by_identity(self.provider, &claims.sub).await? {
-            Some(account) => account,
-            None => self.store.account.upsert(self.provider, &claims.sub, None, &[]).await?,
-        };
-        let permissions = if self.provider == AuthProvider::Jwt {
-            account.permissions.unwrap_or_default()
-        } else {
-            // OAuth2 claims are authoritative for request-time permissions; DB
-            // account permissions are only used by local JWT identities.
-            claims.permissions
+        self.ensure_local_provider()?;
+
+        let username = normalize_username(&request.username)?;
+        let email = request.email.as_deref().map(normalize_email).transpose()?;
+        validate_password(&request.password)?;
+
+        let (permissions, invitation_id) = match request.invitation {
+            Some(token) => {
+                let invitation = match token_hash(&token) {
+                    Some(token_hash) => self.store.invitation.find_by_token_hash(token_hash).await?,
+                    None => None,
+                }
+                .ok_or_else(|| DataError::Validation("Invalid or expired invitation.".to_string()))?;
+
+                (invitation.permissions, Some(invitation.id))
+            }
+            None if self.local_auth.self_registration => (Vec::new(), None),
+            None => return Err(DataError::Validation("An invitation is required to register.".to_string()).into()),
         };
 
-        let asset_id = self.active_asset_id().await?;
-        let wallet = match self
+        if self
             .store
-            .wallet
-            .find_by_account_and_asset(account.id, asset_id)
+            .account
+            .find_by_identity(self.provider, &username)
             .await?
+            .is_some()
         {
-            Some(wallet) => wallet,
-            None => self.store.wallet.upsert(account.id, asset_id).await?,
-        };
+            return Err(DataError::Conflict("Username is already taken.".to_string()).into());
+        }
 
-        trace!(
-            wallet_id = %wallet.id,
-            account_id = %account.id,
-            "Account active asset wallet available after authentication"
-        );
+        if let Some(email) = &email {
+            if self.store.credential.find_by_login(email).await?.is_some() {
+                return Err(DataError::Conflict("Email is already registered.".to_string()).into());
+            }
+        }
 
-        let user = User {
-            account_id: account.id,
-            permissions,
-        };
+        let account = self
+            .store
+            .credential
+            .register(LocalRegistration {
+                username,
+                email,
+                password_hash: hash_password(&request.password)?,
+                display_name: request.display_name,
+                permissions,
+                invitation_id,
+            })
+            .await?
+            .ok_or_else(|| DataError::Validation("Invalid or expired invitation.".to_string()))?;
 
-        Ok(user)
+        let account_id = account.id;
+        let response = self.start_session(account, None).await?;
+
+        info!(%account_id, ?invitation_id, "Local account registered successfully");
+        Ok(response)
     }
 
-    async fn authenticate_api_key(&self, token: Vec<u8>) -> Result<User, ApplicationError> {
-        trace!("Start API Key authentication");
+    async fn create_invitation(
+        &self,
+        user: User,
+        request: CreateInvitationRequest,
+    ) -> Result<Invitation, ApplicationError> {
+        debug!(email = ?request.email, "Creating invitation");
 
-        let key_hash = sha256::Hash::hash(&token).to_byte_array().to_vec();
-        let api_key_opt = self.store.api_key.find_by_key_hash(key_hash).await?;
+        self.ensure_local_provider()?;
 
-        let api_key = match api_key_opt {
-            Some(api_key) => api_key,
-            None => {
-                return Err(AuthenticationError::InvalidCredentials.into());
+        // Invitations cannot grant more than their issuer holds.
+        if !request.permissions.iter().all(|p| user.has_permission(p.clone())) {
+            return Err(DataError::Validation("Invalid permissions".to_string()).into());
+        }
+
+        let expiry_seconds = match request.expiry {
+            Some(seconds) if seconds > MAX_INVITATION_EXPIRY_SECONDS => {
+                return Err(DataError::Validation("Expiry too far in the future".to_string()).into());
             }
+            Some(seconds) => seconds as i64,
+            None => self.local_auth.invitation_expiry.as_secs() as i64,
         };
 
-        let user = User {
-            account_id: api_key.account_id,
-            permissions: api_key.permissions,
-        };
+        let email = request.email.as_deref().map(normalize_email).transpose()?;
+        let (token, token_hash) = generate_token();
 
-        Ok(user)
-    }
-}
+        let mut invitation = self
+            .store
+            .invitation
+            .insert(Invitation {
+                token_hash,
+                email,
+                permissions: request.permissions,
+                expires_at: Utc::now() + Duration::seconds(expiry_seconds),
+                ..Default::default()
+            })
+            .await?;
+        invitation.token = Some(token);
 
-#[cfg(test)]
-mod tests {
-    use chrono::Utc;
-    use uuid::Uuid;
+        info!(id = %invitation.id, "Invitation created successfully");
+        Ok(invitation)
+    }
 
-    use crate::{
-        application::{composition::MockAppStoreBuilder, errors::DatabaseError},
-        domains::{
-            account::{Account, ApiKey, AuthClaims, AuthIdentity},
-            asset::{Asset, Protocol, NATIVE_ASSET_REF},
-            bitcoin::BtcNetwork,
-            wallet::Wallet,
-        },
-        infra::jwt::MockJWTAuthenticator,
-    };
+    async fn list_invitations(&self, filter: InvitationFilter) -> Result<Vec<Invitation>, ApplicationError> {
+        trace!(?filter, "Listing invitations");
 
-    use super::*;
+        let invitations = self.store.invitation.find_many(filter.clone()).await?;
 
-    fn service(jwt: MockJWTAuthenticator, store: MockAppStoreBuilder, provider: AuthProvider) -> AuthService {
-        AuthService::new(Arc::new(jwt), store.build(), provider, BtcNetwork::Regtest)
+        debug!(?filter, "Invitations listed successfully");
+        Ok(invitations)
     }
 
-    fn claims(sub: &str) -> AuthClaims {
-        AuthClaims {
-            exp: 0,
-            iat: 0,
-            sub: sub.to_string(),
-            permissions: vec![Permission::ReadWallet],
-        }
-    }
+    async fn revoke_invitation(&self, id: Uuid) -> Result<(), ApplicationError> {
+        debug!(%id, "Revoking invitation");
 
-    fn asset_fixture(id: Uuid) -> Asset {
-        Asset {
-            id,
-            code: "BTC".to_string(),
-            name: Some("Bitcoin regtest".to_string()),
-            protocol: Protocol::Bitcoin,
-            network: BtcNetwork::Regtest,
-            asset_ref: NATIVE_ASSET_REF.to_string(),
-            display_ticker: "rBTC".to_string(),
-            decimals: 11,
-            created_at: chrono::Utc::now(),
-            updated_at: None,
+        let n_deleted = self
+            .store
+            .invitation
+            .delete_many(InvitationFilter {
+                ids: Some(vec![id]),
+                ..Default::default()
+            })
+            .await?;
+
+        if n_deleted == 0 {
+            return Err(DataError::NotFound("Invitation not found.".to_string()).into());
         }
+
+        info!(%id, "Invitation revoked successfully");
+        Ok(())
     }
 
-    fn wallet_fixture(id: Uuid, account_id: Uuid, asset_id: Uuid) -> Wallet {
-        Wallet {
-            id,
+    async fn create_password_reset(&self, user: User, account_id: Uuid) -> Result<PasswordReset, ApplicationError> {
+        debug!(%account_id, "Creating password reset");
+
+        self.ensure_local_provider()?;
+
+        let account = self
+            .store
+            .account
+            .find(account_id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Account not found.".into()))?;
+
+        // Taking over an account must not grant more than the caller already holds.
+        if is_bootstrap_admin(&account) && user.principal_account_id() != account.id {
+            return Err(AuthorizationError::BootstrapAdminRequired.into());
+        }
+        if let Some(permission) = account
+            .permissions
+            .iter()
+            .flatten()
+            .find(|permission| !user.has_permission((*permission).clone()))
+        {
+            return Err(AuthorizationError::MissingPermission(permission.clone()).into());
+        }
+
+        if !is_bootstrap_admin(&account) && self.store.credential.find_by_account(account_id).await?.is_none() {
+            return Err(DataError::Validation("Account has no local credentials.".to_string()).into());
+        }
+
+        let (token, token_hash) = generate_token();
+        let expires_at = Utc::now() + Duration::seconds(self.local_auth.password_reset_expiry.as_secs() as i64);
+        self.store
+            .credential
+            .insert_password_reset(account_id, token_hash, expires_at)
+            .await?;
+
+        info!(%account_id, "Password reset created successfully");
+        Ok(PasswordReset {
             account_id,
-            asset_id,
-            ..Default::default()
+            token,
+            expires_at,
+        })
+    }
+
+    async fn reset_password(&self, token: String, new_password: String) -> Result<(), ApplicationError> {
+        trace!("Start password reset");
+
+        self.ensure_local_provider()?;
+        validate_password(&new_password)?;
+
+        let account_id = match token_hash(&token) {
+            Some(token_hash) => self.store.credential.consume_password_reset(token_hash).await?,
+            None => None,
+        }
+        .ok_or_else(|| DataError::Validation("Invalid or expired password reset token.".to_string()))?;
+
+        let account = self
+            .store
+            .account
+            .find(account_id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Account not found.".into()))?;
+
+        self.set_password(&account, hash_password(&new_password)?).await?;
+
+        info!(%account_id, "Password reset successfully");
+        Ok(())
+    }
+
+    async fn enroll_totp(&self, user: User) -> Result<TotpEnrollment, ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), "Starting TOTP enrollment");
+
+        self.ensure_local_provider()?;
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let account = self
+            .store
+            .account
+            .find(user.principal_account_id())
+            .await?
+            .ok_or_else(|| DataError::NotFound("Account not found.".into()))?;
+
+        let factor = self
+            .store
+            .totp
+            .insert_pending(account.id, totp::generate_secret())
+            .await?
+            .ok_or_else(|| DataError::Conflict("Two-factor authentication is already enabled.".to_string()))?;
+
+        let account_name = account
+            .identity
+            .map(|identity| identity.subject)
+            .unwrap_or_else(|| account.id.to_string());
+
+        info!(account_id = %account.id, "TOTP enrollment started successfully");
+        Ok(TotpEnrollment {
+            secret: totp::encode_base32(&factor.secret),
+            otpauth_uri: totp::otpauth_uri(ISSUER, &account_name, &factor.secret),
+        })
+    }
+
+    async fn confirm_totp(&self, user: User, code: String) -> Result<TotpRecoveryCodes, ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), "Confirming TOTP enrollment");
+
+        self.ensure_local_provider()?;
+        let session_id = user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let factor = self
+            .store
+            .totp
+            .find(user.principal_account_id())
+            .await?
+            .filter(|factor| !factor.is_confirmed())
+            .ok_or_else(|| DataError::NotFound("No pending two-factor enrollment.".into()))?;
+
+        let step = totp::verify_code(&factor.secret, &code, Utc::now(), factor.last_used_step)
+            .ok_or_else(|| DataError::Validation("Invalid code.".to_string()))?;
+
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| totp::generate_recovery_code())
+            .collect();
+        let recovery_code_hashes = recovery_codes
+            .iter()
+            .map(|code| totp::recovery_code_hash(code))
+            .collect();
+
+        if !self
+            .store
+            .totp
+            .confirm(user.principal_account_id(), step, recovery_code_hashes)
+            .await?
+        {
+            return Err(DataError::Conflict("Two-factor authentication is already enabled.".to_string()).into());
+        }
+
+        // The code just proved possession of the factor in this session.
+        self.store.session.verify(session_id).await?;
+
+        info!(account_id = %user.principal_account_id(), "Two-factor authentication enabled successfully");
+        Ok(TotpRecoveryCodes { recovery_codes })
+    }
+
+    async fn disable_totp(&self, user: User, code: String) -> Result<(), ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), "Disabling two-factor authentication");
+
+        self.ensure_local_provider()?;
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let factor = self
+            .confirmed_totp_factor(user.principal_account_id())
+            .await?
+            .ok_or_else(|| DataError::NotFound("Two-factor authentication is not enabled.".into()))?;
+
+        if !self.verify_second_factor(&factor, &code).await? {
+            return Err(DataError::Validation("Invalid code.".to_string()).into());
+        }
+
+        self.store.totp.delete(user.principal_account_id()).await?;
+
+        info!(account_id = %user.principal_account_id(), "Two-factor authentication disabled successfully");
+        Ok(())
+    }
+
+    async fn step_up(&self, user: User, code: String) -> Result<(), ApplicationError> {
+        trace!(account_id = %user.principal_account_id(), "Start step-up verification");
+
+        self.ensure_local_provider()?;
+        let session_id = user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let factor = self
+            .confirmed_totp_factor(user.principal_account_id())
+            .await?
+            .ok_or_else(|| DataError::Validation("Two-factor authentication is not enabled.".to_string()))?;
+
+        if !self.verify_second_factor(&factor, &code).await? {
+            return Err(DataError::Validation("Invalid code.".to_string()).into());
+        }
+
+        if !self.store.session.verify(session_id).await? {
+            return Err(AuthenticationError::InvalidCredentials.into());
+        }
+
+        debug!(account_id = %user.principal_account_id(), %session_id, "Session verified successfully");
+        Ok(())
+    }
+
+    async fn check_step_up(&self, user: &User) -> Result<(), ApplicationError> {
+        if self.requires_step_up(user).await? {
+            return Err(AuthorizationError::StepUpRequired.into());
+        }
+
+        Ok(())
+    }
+
+    async fn payment_step_up_limit(&self, user: &User) -> Result<Option<u64>, ApplicationError> {
+        if self.requires_step_up(user).await? {
+            return Ok(Some(self.local_auth.step_up_payment_threshold_msat));
+        }
+
+        Ok(None)
+    }
+
+    async fn passkey_registration_options(&self, user: User) -> Result<PasskeyRegistrationOptions, ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), "Starting passkey registration");
+
+        self.ensure_local_provider()?;
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let account = self
+            .store
+            .account
+            .find(user.principal_account_id())
+            .await?
+            .ok_or_else(|| DataError::NotFound("Account not found.".into()))?;
+        let identity = local_identity(&account)?;
+        let passkeys = self.store.passkey.find_many(account.id).await?;
+        let challenge = self.insert_passkey_challenge(Some(account.id)).await?;
+
+        info!(account_id = %account.id, ceremony_id = %challenge.id, "Passkey registration started successfully");
+        Ok(PasskeyRegistrationOptions {
+            ceremony_id: challenge.id,
+            public_key: PasskeyCreationOptions {
+                challenge: webauthn::encode(&challenge.challenge),
+                rp: PasskeyRelyingParty {
+                    id: self.passkey_rp_id.clone(),
+                    name: ISSUER.to_string(),
+                },
+                user: PasskeyUser {
+                    id: webauthn::encode(account.id.as_bytes()),
+                    display_name: account.display_name.clone().unwrap_or_else(|| identity.subject.clone()),
+                    name: identity.subject.clone(),
+                },
+                pub_key_cred_params: webauthn::SUPPORTED_ALGORITHMS
+                    .iter()
+                    .map(|alg| PasskeyCredentialParameters {
+                        credential_type: PUBLIC_KEY_CREDENTIAL_TYPE.to_string(),
+                        alg: *alg,
+                    })
+                    .collect(),
+                timeout: self.passkey_timeout_ms(),
+                exclude_credentials: passkeys
+                    .iter()
+                    .map(|passkey| PasskeyCredentialDescriptor {
+                        credential_type: PUBLIC_KEY_CREDENTIAL_TYPE.to_string(),
+                        id: webauthn::encode(&passkey.credential_id),
+                    })
+                    .collect(),
+                authenticator_selection: PasskeyAuthenticatorSelection {
+                    resident_key: "required".to_string(),
+                    require_resident_key: true,
+                    user_verification: "required".to_string(),
+                },
+                attestation: "none".to_string(),
+            },
+        })
+    }
+
+    async fn register_passkey(&self, user: User, request: RegisterPasskeyRequest) -> Result<Passkey, ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), ceremony_id = %request.ceremony_id, "Registering passkey");
+
+        self.ensure_local_provider()?;
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let name = match request.name.as_deref().map(str::trim) {
+            Some(name) if name.chars().count() > MAX_PASSKEY_NAME_LENGTH => {
+                return Err(DataError::Validation(format!(
+                    "Passkey name must be at most {MAX_PASSKEY_NAME_LENGTH} characters long."
+                ))
+                .into());
+            }
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => DEFAULT_PASSKEY_NAME.to_string(),
+        };
+
+        let challenge = self
+            .store
+            .passkey
+            .consume_challenge(request.ceremony_id)
+            .await?
+            .filter(|challenge| challenge.account_id == Some(user.principal_account_id()))
+            .ok_or_else(|| DataError::Validation("Invalid or expired passkey ceremony.".to_string()))?;
+
+        let response = &request.credential.response;
+        let credential = webauthn::verify_registration(
+            &self.passkey_rp_id,
+            &self.passkey_origin,
+            &challenge.challenge,
+            &webauthn::decode(&response.client_data_json)?,
+            &webauthn::decode(&response.attestation_object)?,
+        )?;
+        if webauthn::decode(&request.credential.id)? != credential.credential_id {
+            return Err(DataError::Validation("Credential ID does not match the attestation.".to_string()).into());
+        }
+
+        if self
+            .store
+            .passkey
+            .find_by_credential_id(credential.credential_id.clone())
+            .await?
+            .is_some()
+        {
+            return Err(DataError::Conflict("Passkey is already registered.".to_string()).into());
+        }
+
+        let account = self
+            .store
+            .account
+            .find(user.principal_account_id())
+            .await?
+            .ok_or_else(|| DataError::NotFound("Account not found.".into()))?;
+        let identity = local_identity(&account)?;
+
+        let passkey = self
+            .store
+            .passkey
+            .insert(Passkey {
+                account_id: account.id,
+                name,
+                identity_id: identity.id,
+                credential_id: credential.credential_id,
+                public_key: credential.public_key,
+                sign_count: credential.sign_count,
+                ..Default::default()
+            })
+            .await?;
+
+        info!(account_id = %account.id, id = %passkey.id, "Passkey registered successfully");
+        Ok(passkey)
+    }
+
+    async fn passkey_authentication_options(&self) -> Result<PasskeyAuthenticationOptions, ApplicationError> {
+        trace!("Starting passkey sign-in");
+
+        self.ensure_local_provider()?;
+
+        let challenge = self.insert_passkey_challenge(None).await?;
+
+        Ok(PasskeyAuthenticationOptions {
+            ceremony_id: challenge.id,
+            public_key: PasskeyRequestOptions {
+                challenge: webauthn::encode(&challenge.challenge),
+                timeout: self.passkey_timeout_ms(),
+                rp_id: self.passkey_rp_id.clone(),
+                allow_credentials: Vec::new(),
+                user_verification: "required".to_string(),
+            },
+        })
+    }
+
+    async fn sign_in_passkey(&self, request: PasskeySignInRequest) -> Result<SignInResponse, ApplicationError> {
+        trace!(ceremony_id = %request.ceremony_id, "Start passkey login");
+
+        self.ensure_local_provider()?;
+
+        // Failed ceremonies are indistinguishable to the caller.
+        let (passkey, sign_count) = self.verify_passkey_assertion(&request).await.map_err(|e| match e {
+            ApplicationError::Data(e) => {
+                debug!(error = %e, "Passkey assertion rejected");
+                AuthenticationError::InvalidCredentials.into()
+            }
+            e => e,
+        })?;
+
+        if !self.store.passkey.record_use(passkey.id, sign_count).await? {
+            warn!(id = %passkey.id, sign_count, "Passkey signature counter did not increase, possible cloned authenticator");
+            return Err(AuthenticationError::InvalidCredentials.into());
+        }
+
+        let account = self
+            .store
+            .account
+            .find(passkey.account_id)
+            .await?
+            .ok_or_else(|| DataError::Inconsistency("Passkey exists without an account".to_string()))?;
+
+        // Passkeys require user verification, a second factor on their own.
+        let response = self.start_session(account, Some(Utc::now())).await?;
+
+        debug!(account_id = %passkey.account_id, id = %passkey.id, "User logged in with passkey successfully");
+        Ok(response)
+    }
+
+    async fn list_passkeys(&self, user: User) -> Result<Vec<Passkey>, ApplicationError> {
+        trace!(account_id = %user.principal_account_id(), "Listing passkeys");
+
+        self.ensure_local_provider()?;
+
+        let passkeys = self.store.passkey.find_many(user.principal_account_id()).await?;
+
+        debug!(account_id = %user.principal_account_id(), "Passkeys listed successfully");
+        Ok(passkeys)
+    }
+
+    async fn delete_passkey(&self, user: User, id: Uuid) -> Result<(), ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), %id, "Deleting passkey");
+
+        self.ensure_local_provider()?;
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let n_deleted = self.store.passkey.delete(user.principal_account_id(), id).await?;
+        if n_deleted == 0 {
+            return Err(DataError::NotFound("Passkey not found.".to_string()).into());
+        }
+
+        info!(account_id = %user.principal_account_id(), %id, "Passkey deleted successfully");
+        Ok(())
+    }
+
+    async fn link_nostr_key(&self, user: User, event: String) -> Result<AuthIdentity, ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), "Linking Nostr key");
+
+        // Keys grant full access to the account, so API keys cannot link them.
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let url = format!("{}{NOSTR_KEYS_PATH}", self.api_url);
+        let request = nip98::SignedRequest {
+            url: &url,
+            method: "POST",
+            payload_hash: None,
+        };
+        let pubkey = self.accept_nostr_event(&event, &request).await?;
+
+        if self
+            .store
+            .account
+            .find_by_identity(AuthProvider::Nostr, &pubkey)
+            .await?
+            .is_some()
+        {
+            return Err(DataError::Conflict("Nostr key is already linked to an account.".to_string()).into());
+        }
+
+        let identity = self
+            .store
+            .account
+            .insert_identity(user.principal_account_id(), AuthProvider::Nostr, &pubkey)
+            .await?;
+
+        info!(account_id = %user.principal_account_id(), id = %identity.id, %pubkey, "Nostr key linked successfully");
+        Ok(identity)
+    }
+
+    async fn list_nostr_keys(&self, user: User) -> Result<Vec<AuthIdentity>, ApplicationError> {
+        trace!(account_id = %user.principal_account_id(), "Listing Nostr keys");
+
+        let identities = self
+            .store
+            .account
+            .find_identities(user.principal_account_id(), AuthProvider::Nostr)
+            .await?;
+
+        debug!(account_id = %user.principal_account_id(), "Nostr keys listed successfully");
+        Ok(identities)
+    }
+
+    async fn unlink_nostr_key(&self, user: User, id: Uuid) -> Result<(), ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), %id, "Unlinking Nostr key");
+
+        user.session_id.ok_or(AuthorizationError::SessionRequired)?;
+
+        let n_deleted = self
+            .store
+            .account
+            .delete_identity(user.principal_account_id(), AuthProvider::Nostr, id)
+            .await?;
+        if n_deleted == 0 {
+            return Err(DataError::NotFound("Nostr key not found.".to_string()).into());
+        }
+
+        info!(account_id = %user.principal_account_id(), %id, "Nostr key unlinked successfully");
+        Ok(())
+    }
+
+    async fn authenticate_jwt(&self, token: &str) -> Result<User, ApplicationError> {
+        trace!("Start JWT authentication");
+
+        let claims = self.jwt_authenticator.decode(token).await?;
+        let (provider, subject) = match &claims.issuer {
+            Some(issuer) => (AuthProvider::OAuth2, issuer.identity_subject(&claims.sub)),
+            None => (AuthProvider::Jwt, claims.sub.clone()),
+        };
+
+        // Local tokens only authenticate while their session exists, which ends on sign-out, password changes
+        // and deletion of the account.
+        let session = if provider == AuthProvider::Jwt {
+            let session_id = claims.sid.ok_or(AuthenticationError::InvalidCredentials)?;
+            let session = self
+                .store
+                .session
+                .find(session_id)
+                .await?
+                .ok_or(AuthenticationError::InvalidCredentials)?;
+            Some(session)
+        } else {
+            None
+        };
+
+        let account = match self.store.account.find_by_identity(provider, &subject).await? {
+            Some(account) => account,
+            None => match claims.issuer.as_ref().map(|issuer| issuer.provisioning) {
+                Some(AccountProvisioning::Existing) => {
+                    debug!(%subject, "No account for the subject of an issuer without provisioning");
+                    return Err(AuthenticationError::InvalidCredentials.into());
+                }
+                _ => self.store.account.upsert(provider, &subject, None, &[]).await?,
+            },
+        };
+        let permissions = if provider == AuthProvider::Jwt {
+            account.permissions.unwrap_or_default()
+        } else {
+            // OAuth2 claims are authoritative for request-time permissions; DB
+            // account permissions are only used by local JWT identities.
+            claims.permissions
+        };
+
+        if session.as_ref().is_some_and(|session| session.account_id != account.id) {
+            return Err(AuthenticationError::InvalidCredentials.into());
+        }
+
+        let asset_id = self.active_asset_id().await?;
+        let wallet = match self
+            .store
+            .wallet
+            .find_by_account_and_asset(account.id, asset_id)
+            .await?
+        {
+            Some(wallet) => wallet,
+            None => self.store.wallet.upsert(account.id, asset_id).await?,
+        };
+
+        trace!(
+            wallet_id = %wallet.id,
+            account_id = %account.id,
+            "Account active asset wallet available after authentication"
+        );
+
+        let user = User {
+            account_id: account.id,
+            permissions,
+            wallet_ids: None,
+            session_id: session.map(|session| session.id),
+            membership: None,
+            api_key_id: None,
+            subject: Some(subject),
+        };
+
+        Ok(user)
+    }
+
+    async fn authenticate_api_key(&self, token: Vec<u8>, source_ip: Option<IpAddr>) -> Result<User, ApplicationError> {
+        trace!("Start API Key authentication");
+
+        let key_hash = sha256::Hash::hash(&token).to_byte_array().to_vec();
+        let api_key_opt = self.store.api_key.find_by_key_hash(key_hash).await?;
+
+        let api_key = match api_key_opt {
+            Some(api_key) => api_key,
+            None => {
+                return Err(AuthenticationError::InvalidCredentials.into());
+            }
+        };
+
+        let now = Utc::now();
+        if api_key.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            debug!(id = %api_key.id, "Expired API key rejected");
+            return Err(AuthenticationError::InvalidCredentials.into());
+        }
+
+        // Keys issued by a member act within their current role, and only while they remain a member.
+        let membership = match api_key.member_account_id {
+            Some(member_account_id) => Some(
+                self.store
+                    .organization
+                    .find_member(api_key.account_id, member_account_id)
+                    .await?
+                    .ok_or_else(|| {
+                        debug!(id = %api_key.id, "API key of a former organization member rejected");
+                        AuthenticationError::InvalidCredentials
+                    })?,
+            ),
+            None => None,
+        };
+
+        let source_ip = source_ip.map(|ip| ip.to_string());
+        let recently_used = api_key
+            .last_used_at
+            .is_some_and(|last_used_at| now - last_used_at < Duration::seconds(API_KEY_USAGE_RESOLUTION_SECONDS));
+        if !recently_used || api_key.last_used_ip != source_ip {
+            // Usage tracking is best effort and never fails the request.
+            if let Err(e) = self.store.api_key.record_usage(api_key.id, source_ip).await {
+                warn!(id = %api_key.id, error = %e, "Failed to record API key usage");
+            }
+        }
+
+        let user = User {
+            account_id: api_key.account_id,
+            permissions: api_key.permissions,
+            wallet_ids: api_key.wallet_ids,
+            session_id: None,
+            membership,
+            api_key_id: Some(api_key.id),
+            subject: None,
+        };
+
+        Ok(user)
+    }
+
+    async fn authenticate_nostr(
+        &self,
+        token: &str,
+        method: &str,
+        path: &str,
+        payload_hash: Option<String>,
+    ) -> Result<User, ApplicationError> {
+        trace!("Start NIP-98 authentication");
+
+        let url = format!("{}{path}", self.api_url);
+        let request = nip98::SignedRequest {
+            url: &url,
+            method,
+            payload_hash: payload_hash.as_deref(),
+        };
+        let pubkey = self.accept_nostr_event(token, &request).await.map_err(|e| match e {
+            ApplicationError::Data(e) => {
+                debug!(error = %e, "NIP-98 event rejected");
+                AuthenticationError::InvalidCredentials.into()
+            }
+            e => e,
+        })?;
+
+        let account = self
+            .store
+            .account
+            .find_by_identity(AuthProvider::Nostr, &pubkey)
+            .await?
+            .ok_or(AuthenticationError::InvalidCredentials)?;
+
+        let user = User {
+            account_id: account.id,
+            permissions: account.permissions.unwrap_or_default(),
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: Some(pubkey),
+        };
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        application::{composition::MockAppStoreBuilder, errors::DatabaseError},
+        domains::{
+            account::{
+                Account, ApiKey, AuthClaims, AuthIdentity, LocalCredential, OrganizationMember, OrganizationRole,
+                Session, TokenIssuer,
+            },
+            asset::{Asset, Protocol, NATIVE_ASSET_REF},
+            bitcoin::BtcNetwork,
+            wallet::Wallet,
+        },
+        infra::jwt::MockJWTAuthenticator,
+    };
+
+    use super::*;
+
+    const HOST: &str = "https://app.numeraire.tech";
+
+    fn service(jwt: MockJWTAuthenticator, store: MockAppStoreBuilder, provider: AuthProvider) -> AuthService {
+        AuthService::new(
+            Arc::new(jwt),
+            store.build(),
+            provider,
+            BtcNetwork::Regtest,
+            LocalAuthConfig::default(),
+            HOST.to_string(),
+        )
+    }
+
+    fn claims(sub: &str) -> AuthClaims {
+        AuthClaims {
+            exp: 0,
+            iat: 0,
+            sub: sub.to_string(),
+            permissions: vec![Permission::ReadWallet],
+            sid: None,
+            issuer: None,
+        }
+    }
+
+    fn issuer_claims(sub: &str, name: &str, provisioning: AccountProvisioning) -> AuthClaims {
+        AuthClaims {
+            issuer: Some(TokenIssuer {
+                name: Some(name.to_string()),
+                provisioning,
+            }),
+            ..claims(sub)
+        }
+    }
+
+    /// Expects a session to be started for `account_id` and returns its ID.
+    fn expect_session_start(store: &mut MockAppStoreBuilder, account_id: Uuid) -> Uuid {
+        let session_id = Uuid::new_v4();
+        store
+            .session
+            .expect_insert()
+            .withf(move |id, refresh_token_hash, _, _| *id == account_id && refresh_token_hash.len() == 32)
+            .times(1)
+            .returning(move |account_id, _, expires_at, verified_at| {
+                Ok(Session {
+                    id: session_id,
+                    account_id,
+                    expires_at,
+                    verified_at,
+                    ..Default::default()
+                })
+            });
+        session_id
+    }
+
+    fn expect_no_totp_factor(store: &mut MockAppStoreBuilder, account_id: Uuid) {
+        store
+            .totp
+            .expect_find()
+            .withf(move |id| *id == account_id)
+            .returning(|_| Ok(None));
+    }
+
+    fn expect_sessions_revoked(store: &mut MockAppStoreBuilder, account_id: Uuid) {
+        store
+            .session
+            .expect_delete_by_account()
+            .withf(move |id| *id == account_id)
+            .times(1)
+            .returning(|_| Ok(1));
+    }
+
+    fn asset_fixture(id: Uuid) -> Asset {
+        Asset {
+            id,
+            code: "BTC".to_string(),
+            name: Some("Bitcoin regtest".to_string()),
+            protocol: Protocol::Bitcoin,
+            network: BtcNetwork::Regtest,
+            asset_ref: NATIVE_ASSET_REF.to_string(),
+            display_ticker: "rBTC".to_string(),
+            decimals: 11,
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    fn wallet_fixture(id: Uuid, account_id: Uuid, asset_id: Uuid) -> Wallet {
+        Wallet {
+            id,
+            account_id,
+            asset_id,
+            ..Default::default()
+        }
+    }
+
+    fn account_fixture(id: Uuid, provider: AuthProvider, subject: &str, permissions: Vec<Permission>) -> Account {
+        Account {
+            id,
+            display_name: None,
+            identity: Some(AuthIdentity {
+                id: Uuid::new_v4(),
+                provider,
+                subject: subject.to_string(),
+                created_at: Utc::now(),
+            }),
+            permissions: Some(permissions),
+            preferences: None,
+            wallets: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    /// Store whose caller is the bootstrap admin.
+    fn admin_store() -> MockAppStoreBuilder {
+        let mut store = MockAppStoreBuilder::new();
+        store.account.expect_find().times(1).returning(|id| {
+            Ok(Some(account_fixture(
+                id,
+                AuthProvider::Jwt,
+                BOOTSTRAP_ADMIN_SUBJECT,
+                Permission::all_permissions(),
+            )))
+        });
+        store
+    }
+
+    mod sign_up {
+        use super::*;
+
+        mod when_provider_is_not_jwt {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_unsupported_operation() {
+                let service = service(
+                    MockJWTAuthenticator::new(),
+                    MockAppStoreBuilder::new(),
+                    AuthProvider::OAuth2,
+                );
+
+                let err = service.sign_up("password".to_string()).await.unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::UnsupportedOperation)
+                ));
+            }
+        }
+
+        mod when_admin_already_exists {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_conflict() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(|_| Ok(Some("existing-hash".into())));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_up("password".to_string()).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+            }
+        }
+
+        mod when_first_admin {
+            use super::*;
+
+            #[tokio::test]
+            async fn persists_hash_and_returns_token() {
+                let account_id = Uuid::new_v4();
+                let permissions = Permission::all_permissions();
+                let mut store = MockAppStoreBuilder::new();
+                store.config.expect_find().times(1).returning(|_| Ok(None));
+                store
+                    .config
+                    .expect_insert_if_absent()
+                    .withf(|key, _| key == PASSWORD_HASH_KEY)
+                    .times(1)
+                    .returning(|_, _| Ok(true));
+                store
+                    .account
+                    .expect_upsert()
+                    .withf(|provider, subject, display_name, granted| {
+                        *provider == AuthProvider::Jwt
+                            && subject == "admin"
+                            && display_name.is_none()
+                            && granted == Permission::all_permissions().as_slice()
+                    })
+                    .times(1)
+                    .returning(move |provider, subject, _, permissions| {
+                        Ok(account_fixture(account_id, provider, subject, permissions.to_vec()))
+                    });
+
+                let session_id = expect_session_start(&mut store, account_id);
+
+                let mut jwt = MockJWTAuthenticator::new();
+                let expected_permissions = permissions.clone();
+                jwt.expect_encode()
+                    .withf(move |account, sid| {
+                        account
+                            .identity
+                            .as_ref()
+                            .is_some_and(|identity| identity.subject == "admin")
+                            && account.permissions.as_ref() == Some(&expected_permissions)
+                            && *sid == session_id
+                    })
+                    .times(1)
+                    .returning(|_, _| Ok("token".to_string()));
+
+                let service = service(jwt, store, AuthProvider::Jwt);
+
+                let response = service.sign_up("password".to_string()).await.unwrap();
+
+                assert_eq!(response.token, "token");
+                assert!(token_hash(&response.refresh_token).is_some());
+            }
+        }
+
+        mod when_another_request_claims_the_admin_during_sign_up {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_conflict() {
+                let account_id = Uuid::new_v4();
+                let mut store = MockAppStoreBuilder::new();
+                store.config.expect_find().times(1).returning(|_| Ok(None));
+                store
+                    .account
+                    .expect_upsert()
+                    .withf(|provider, subject, display_name, granted| {
+                        *provider == AuthProvider::Jwt
+                            && subject == "admin"
+                            && display_name.is_none()
+                            && granted == Permission::all_permissions().as_slice()
+                    })
+                    .times(1)
+                    .returning(move |provider, subject, _, permissions| {
+                        Ok(account_fixture(account_id, provider, subject, permissions.to_vec()))
+                    });
+                store
+                    .config
+                    .expect_insert_if_absent()
+                    .withf(|key, _| key == PASSWORD_HASH_KEY)
+                    .times(1)
+                    .returning(|_, _| Ok(false));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_up("password".to_string()).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+            }
+        }
+
+        mod when_admin_account_creation_fails {
+            use super::*;
+
+            #[tokio::test]
+            async fn does_not_claim_the_password() {
+                let mut store = MockAppStoreBuilder::new();
+                store.config.expect_find().times(1).returning(|_| Ok(None));
+                store
+                    .account
+                    .expect_upsert()
+                    .times(1)
+                    .returning(|_, _, _, _| Err(DatabaseError::Insert("boom".to_string())));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_up("password".to_string()).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Database(DatabaseError::Insert(_))));
+            }
+        }
+    }
+
+    mod sign_in {
+        use super::*;
+
+        mod when_credentials_are_missing {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_not_found() {
+                let mut store = MockAppStoreBuilder::new();
+                store.config.expect_find().times(1).returning(|_| Ok(None));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_in(None, "password".to_string(), None).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+
+        mod with_a_wrong_password {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_invalid_credentials() {
+                let stored_hash = hash("correct", 4).unwrap();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_in(None, "wrong".to_string(), None).await.unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+                ));
+            }
+        }
+
+        mod with_the_correct_password_but_missing_account_identity {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_inconsistency_without_recreating_the_account() {
+                let stored_hash = hash("correct", 4).unwrap();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+                store
+                    .account
+                    .expect_find_by_identity()
+                    .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "admin")
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_in(None, "correct".to_string(), None).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Inconsistency(_))));
+            }
+        }
+
+        mod with_the_correct_password_and_account_identity {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_token() {
+                let account_id = Uuid::new_v4();
+                let stored_hash = hash("correct", 4).unwrap();
+                let permissions = Permission::all_permissions();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+                let account_permissions = permissions.clone();
+                store
+                    .account
+                    .expect_find_by_identity()
+                    .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "admin")
+                    .times(1)
+                    .returning(move |provider, subject| {
+                        Ok(Some(account_fixture(
+                            account_id,
+                            provider,
+                            subject,
+                            account_permissions.clone(),
+                        )))
+                    });
+
+                expect_no_totp_factor(&mut store, account_id);
+                let session_id = expect_session_start(&mut store, account_id);
+
+                let expected_permissions = permissions.clone();
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_encode()
+                    .withf(move |account, sid| {
+                        account
+                            .identity
+                            .as_ref()
+                            .is_some_and(|identity| identity.subject == "admin")
+                            && account.permissions.as_ref() == Some(&expected_permissions)
+                            && *sid == session_id
+                    })
+                    .times(1)
+                    .returning(|_, _| Ok("token".to_string()));
+
+                let service = service(jwt, store, AuthProvider::Jwt);
+
+                let response = service.sign_in(None, "correct".to_string(), None).await.unwrap();
+
+                assert_eq!(response.token, "token");
+            }
+        }
+
+        mod when_stored_hash_is_not_a_string {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_inconsistency() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(|_| Ok(Some(serde_json::json!(42))));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.sign_in(None, "password".to_string(), None).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Inconsistency(_))));
+            }
+        }
+    }
+
+    mod change_password {
+        use super::*;
+
+        mod when_provider_is_not_jwt {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_unsupported_operation() {
+                let service = service(
+                    MockJWTAuthenticator::new(),
+                    MockAppStoreBuilder::new(),
+                    AuthProvider::OAuth2,
+                );
+
+                let err = service
+                    .change_password(User::default(), "current".to_string(), "new".to_string())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::UnsupportedOperation)
+                ));
+            }
+        }
+
+        mod when_credentials_are_missing {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_not_found() {
+                let mut store = admin_store();
+                store.config.expect_find().times(1).returning(|_| Ok(None));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service
+                    .change_password(User::default(), "current".to_string(), "new".to_string())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+
+        mod with_a_wrong_current_password {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_validation_error() {
+                let stored_hash = hash("correct", 4).unwrap();
+
+                let mut store = admin_store();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service
+                    .change_password(User::default(), "wrong".to_string(), "new".to_string())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+        }
+
+        mod with_the_correct_current_password {
+            use super::*;
+
+            #[tokio::test]
+            async fn persists_the_new_password_hash() {
+                let stored_hash = hash("current", 4).unwrap();
+
+                let mut store = admin_store();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+                store
+                    .config
+                    .expect_upsert()
+                    .withf(|key, value| {
+                        key == PASSWORD_HASH_KEY
+                            && value.as_str().is_some_and(|hash| verify("new", hash).unwrap_or(false))
+                    })
+                    .times(1)
+                    .returning(|_, _| Ok(()));
+                expect_sessions_revoked(&mut store, Uuid::nil());
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                service
+                    .change_password(User::default(), "current".to_string(), "new".to_string())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        mod when_stored_hash_is_not_a_string {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_inconsistency() {
+                let mut store = admin_store();
+                store
+                    .config
+                    .expect_find()
+                    .times(1)
+                    .returning(|_| Ok(Some(serde_json::json!(42))));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service
+                    .change_password(User::default(), "current".to_string(), "new".to_string())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Inconsistency(_))));
+            }
+        }
+    }
+
+    mod authenticate_jwt {
+        use super::*;
+
+        mod when_token_is_valid {
+            use super::*;
+
+            #[tokio::test]
+            async fn ensures_the_active_asset_wallet() {
+                let wallet_id = Uuid::new_v4();
+                let account_id = Uuid::new_v4();
+                let asset_id = Uuid::new_v4();
+                let session_id = Uuid::new_v4();
+
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode().times(1).returning(move |_| {
+                    Ok(AuthClaims {
+                        sid: Some(session_id),
+                        ..claims("alice")
+                    })
+                });
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .session
+                    .expect_find()
+                    .withf(move |id| *id == session_id)
+                    .times(1)
+                    .returning(move |id| {
+                        Ok(Some(Session {
+                            id,
+                            account_id,
+                            ..Default::default()
+                        }))
+                    });
+                store
+                    .account
+                    .expect_find_by_identity()
+                    .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "alice")
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .account
+                    .expect_upsert()
+                    .withf(|provider, subject, display_name, granted| {
+                        *provider == AuthProvider::Jwt
+                            && subject == "alice"
+                            && display_name.is_none()
+                            && granted.is_empty()
+                    })
+                    .times(1)
+                    .returning(move |provider, subject, _, _| {
+                        Ok(account_fixture(
+                            account_id,
+                            provider,
+                            subject,
+                            vec![Permission::ReadApiKey],
+                        ))
+                    });
+                store
+                    .wallet
+                    .expect_find_by_account_and_asset()
+                    .withf(move |account, asset| *account == account_id && *asset == asset_id)
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .wallet
+                    .expect_upsert()
+                    .withf(move |account, asset| *account == account_id && *asset == asset_id)
+                    .times(1)
+                    .returning(move |account, asset| Ok(wallet_fixture(wallet_id, account, asset)));
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .withf(|network| *network == BtcNetwork::Regtest)
+                    .times(1)
+                    .returning(move |_| Ok(Some(asset_fixture(asset_id))));
+
+                let service = service(jwt, store, AuthProvider::Jwt);
+
+                let user = service.authenticate_jwt("token").await.unwrap();
+
+                assert_eq!(user.account_id, account_id);
+                assert_eq!(user.permissions, vec![Permission::ReadApiKey]);
+            }
+        }
+
+        mod when_the_token_is_of_a_named_issuer {
+            use super::*;
+
+            #[tokio::test]
+            async fn provisions_an_account_for_the_namespaced_subject_without_session() {
+                let account_id = Uuid::new_v4();
+                let asset_id = Uuid::new_v4();
+
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode()
+                    .times(1)
+                    .returning(|_| Ok(issuer_claims("alice", "corporate", AccountProvisioning::Auto)));
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .account
+                    .expect_find_by_identity()
+                    .withf(|provider, subject| *provider == AuthProvider::OAuth2 && subject == "corporate:alice")
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .account
+                    .expect_upsert()
+                    .withf(|provider, subject, _, granted| {
+                        *provider == AuthProvider::OAuth2 && subject == "corporate:alice" && granted.is_empty()
+                    })
+                    .times(1)
+                    .returning(move |provider, subject, _, _| {
+                        Ok(account_fixture(account_id, provider, subject, vec![]))
+                    });
+                store
+                    .wallet
+                    .expect_find_by_account_and_asset()
+                    .times(1)
+                    .returning(|account, asset| Ok(Some(wallet_fixture(Uuid::new_v4(), account, asset))));
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .times(1)
+                    .returning(move |_| Ok(Some(asset_fixture(asset_id))));
+
+                let service = service(jwt, store, AuthProvider::Jwt);
+
+                let user = service.authenticate_jwt("token").await.unwrap();
+
+                assert_eq!(user.account_id, account_id);
+                assert_eq!(user.permissions, vec![Permission::ReadWallet]);
+                assert_eq!(user.session_id, None);
+                assert_eq!(user.subject.as_deref(), Some("corporate:alice"));
+            }
         }
-    }
 
-    fn account_fixture(id: Uuid, provider: AuthProvider, subject: &str, permissions: Vec<Permission>) -> Account {
-        Account {
-            id,
-            display_name: None,
-            identity: Some(AuthIdentity {
-                id: Uuid::new_v4(),
-                provider,
-                subject: subject.to_string(),
-                created_at: Utc::now(),
-            }),
-            permissions: Some(permissions),
-            preferences: None,
-            wallets: Vec::new(),
-            created_at: Utc::now(),
-            updated_at: None,
+        mod when_the_issuer_only_authenticates_existing_accounts {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_invalid_credentials_for_unknown_subjects() {
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode()
+                    .times(1)
+                    .returning(|_| Ok(issuer_claims("bob", "corporate", AccountProvisioning::Existing)));
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .account
+                    .expect_find_by_identity()
+                    .withf(|provider, subject| *provider == AuthProvider::OAuth2 && subject == "corporate:bob")
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store.account.expect_upsert().never();
+
+                let service = service(jwt, store, AuthProvider::Jwt);
+
+                let err = service.authenticate_jwt("token").await.unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+                ));
+            }
         }
-    }
 
-    mod sign_up {
-        use super::*;
+        mod when_token_is_invalid {
+            use super::*;
 
-        mod when_provider_is_not_jwt {
+            #[tokio::test]
+            async fn propagates_authentication_error() {
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode()
+                    .times(1)
+                    .returning(|_| Err(AuthenticationError::InvalidCredentials));
+
+                let service = service(jwt, MockAppStoreBuilder::new(), AuthProvider::Jwt);
+
+                let err = service.authenticate_jwt("token").await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Authentication(_)));
+            }
+        }
+
+        mod when_the_session_is_revoked {
             use super::*;
 
             #[tokio::test]
-            async fn returns_unsupported_operation() {
-                let service = service(
-                    MockJWTAuthenticator::new(),
-                    MockAppStoreBuilder::new(),
-                    AuthProvider::OAuth2,
-                );
+            async fn returns_invalid_credentials() {
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode().times(1).returning(|_| {
+                    Ok(AuthClaims {
+                        sid: Some(Uuid::new_v4()),
+                        ..claims("alice")
+                    })
+                });
+                let mut store = MockAppStoreBuilder::new();
+                store.session.expect_find().times(1).returning(|_| Ok(None));
 
-                let err = service.sign_up("password".to_string()).await.unwrap_err();
+                let service = service(jwt, store, AuthProvider::Jwt);
+
+                let err = service.authenticate_jwt("token").await.unwrap_err();
 
                 assert!(matches!(
                     err,
-                    ApplicationError::Authentication(AuthenticationError::UnsupportedOperation)
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
                 ));
             }
         }
 
-        mod when_admin_already_exists {
+        mod when_a_local_token_has_no_session {
             use super::*;
 
             #[tokio::test]
-            async fn returns_conflict() {
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(|_| Ok(Some("existing-hash".into())));
+            async fn returns_invalid_credentials() {
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode().times(1).returning(|_| Ok(claims("alice")));
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+                let service = service(jwt, MockAppStoreBuilder::new(), AuthProvider::Jwt);
 
-                let err = service.sign_up("password".to_string()).await.unwrap_err();
+                let err = service.authenticate_jwt("token").await.unwrap_err();
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+                ));
             }
         }
 
-        mod when_first_admin {
+        mod when_the_session_belongs_to_another_account {
             use super::*;
 
             #[tokio::test]
-            async fn persists_hash_and_returns_token() {
-                let account_id = Uuid::new_v4();
-                let permissions = Permission::all_permissions();
+            async fn returns_invalid_credentials() {
+                let mut jwt = MockJWTAuthenticator::new();
+                jwt.expect_decode().times(1).returning(|_| {
+                    Ok(AuthClaims {
+                        sid: Some(Uuid::new_v4()),
+                        ..claims("alice")
+                    })
+                });
                 let mut store = MockAppStoreBuilder::new();
-                store.config.expect_find().times(1).returning(|_| Ok(None));
-                store
-                    .config
-                    .expect_insert_if_absent()
-                    .withf(|key, _| key == PASSWORD_HASH_KEY)
-                    .times(1)
-                    .returning(|_, _| Ok(true));
+                store.session.expect_find().times(1).returning(|id| {
+                    Ok(Some(Session {
+                        id,
+                        account_id: Uuid::new_v4(),
+                        ..Default::default()
+                    }))
+                });
                 store
                     .account
-                    .expect_upsert()
-                    .withf(|provider, subject, display_name, granted| {
-                        *provider == AuthProvider::Jwt
-                            && subject == "admin"
-                            && display_name.is_none()
-                            && granted == Permission::all_permissions().as_slice()
-                    })
+                    .expect_find_by_identity()
                     .times(1)
-                    .returning(move |provider, subject, _, permissions| {
-                        Ok(account_fixture(account_id, provider, subject, permissions.to_vec()))
+                    .returning(|provider, subject| {
+                        Ok(Some(account_fixture(Uuid::new_v4(), provider, subject, vec![])))
                     });
 
-                let mut jwt = MockJWTAuthenticator::new();
-                let expected_permissions = permissions.clone();
-                jwt.expect_encode()
-                    .withf(move |account| {
-                        account
-                            .identity
-                            .as_ref()
-                            .is_some_and(|identity| identity.subject == "admin")
-                            && account.permissions.as_ref() == Some(&expected_permissions)
-                    })
-                    .times(1)
-                    .returning(|_| Ok("token".to_string()));
-
                 let service = service(jwt, store, AuthProvider::Jwt);
 
-                let token = service.sign_up("password".to_string()).await.unwrap();
+                let err = service.authenticate_jwt("token").await.unwrap_err();
 
-                assert_eq!(token, "token");
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+                ));
             }
         }
+    }
+
+    mod refresh {
+        use super::*;
+
+        #[tokio::test]
+        async fn replaces_the_refresh_token_and_issues_a_token_for_the_session() {
+            let account_id = Uuid::new_v4();
+            let session_id = Uuid::new_v4();
+            let (refresh_token, refresh_token_hash) = generate_token();
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .session
+                .expect_refresh()
+                .withf(move |hash, new_hash, expires_at| {
+                    *hash == refresh_token_hash
+                        && new_hash.len() == 32
+                        && *new_hash != *hash
+                        && *expires_at > Utc::now()
+                })
+                .times(1)
+                .returning(move |_, _, expires_at| {
+                    Ok(Some(Session {
+                        id: session_id,
+                        account_id,
+                        expires_at,
+                        ..Default::default()
+                    }))
+                });
+            store
+                .account
+                .expect_find()
+                .withf(move |id| *id == account_id)
+                .times(1)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode()
+                .withf(move |account, sid| account.id == account_id && *sid == session_id)
+                .times(1)
+                .returning(|_, _| Ok("token".to_string()));
+
+            let service = service(jwt, store, AuthProvider::Jwt);
+
+            let response = service.refresh(refresh_token.clone()).await.unwrap();
+
+            assert_eq!(response.token, "token");
+            assert_ne!(response.refresh_token, refresh_token);
+        }
 
-        mod when_another_request_claims_the_admin_during_sign_up {
+        #[tokio::test]
+        async fn an_unknown_or_used_refresh_token_returns_invalid_credentials() {
+            let mut store = MockAppStoreBuilder::new();
+            store.session.expect_refresh().times(1).returning(|_, _, _| Ok(None));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service.refresh(generate_token().0).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
+
+        #[tokio::test]
+        async fn a_malformed_refresh_token_returns_invalid_credentials() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
+
+            let err = service.refresh("not a token".to_string()).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
+    }
+
+    mod sign_out {
+        use super::*;
+
+        #[tokio::test]
+        async fn deletes_the_session_of_the_refresh_token() {
+            let (refresh_token, refresh_token_hash) = generate_token();
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .session
+                .expect_delete_by_refresh_token()
+                .withf(move |hash| *hash == refresh_token_hash)
+                .times(1)
+                .returning(|_| Ok(Some(Session::default())));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            service.sign_out(refresh_token).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn deletes_every_session_of_the_caller() {
+            let account_id = Uuid::new_v4();
+
+            let mut store = MockAppStoreBuilder::new();
+            expect_sessions_revoked(&mut store, account_id);
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let n_revoked = service
+                .sign_out_all(User {
+                    account_id,
+                    session_id: Some(Uuid::new_v4()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(n_revoked, 1);
+        }
+
+        #[tokio::test]
+        async fn requires_a_session() {
+            let mut store = MockAppStoreBuilder::new();
+            store.session.expect_delete_by_account().never();
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service.sign_out_all(User::default()).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::SessionRequired)
+            ));
+        }
+
+        #[tokio::test]
+        async fn is_unsupported_for_oauth2() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::OAuth2,
+            );
+
+            let err = service.sign_out_all(User::default()).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::UnsupportedOperation)
+            ));
+        }
+    }
+
+    mod authenticate_api_key {
+        use super::*;
+
+        mod when_key_is_unknown {
             use super::*;
 
             #[tokio::test]
-            async fn returns_conflict() {
+            async fn returns_invalid_credentials() {
+                let mut store = MockAppStoreBuilder::new();
+                store.api_key.expect_find_by_key_hash().times(1).returning(|_| Ok(None));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let err = service.authenticate_api_key(vec![1, 2, 3], None).await.unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+                ));
+            }
+        }
+
+        mod when_key_is_valid {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_user_with_api_key_permissions() {
                 let account_id = Uuid::new_v4();
+                let wallet_id = Uuid::new_v4();
+
                 let mut store = MockAppStoreBuilder::new();
-                store.config.expect_find().times(1).returning(|_| Ok(None));
+                store.api_key.expect_find_by_key_hash().times(1).returning(move |_| {
+                    Ok(Some(ApiKey {
+                        account_id,
+                        permissions: vec![Permission::ReadWallet],
+                        wallet_ids: Some(vec![wallet_id]),
+                        ..Default::default()
+                    }))
+                });
                 store
-                    .account
-                    .expect_upsert()
-                    .withf(|provider, subject, display_name, granted| {
-                        *provider == AuthProvider::Jwt
-                            && subject == "admin"
-                            && display_name.is_none()
-                            && granted == Permission::all_permissions().as_slice()
-                    })
+                    .api_key
+                    .expect_record_usage()
+                    .withf(|_, ip| ip.as_deref() == Some("203.0.113.7"))
+                    .times(1)
+                    .returning(|_, _| Ok(()));
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                let user = service
+                    .authenticate_api_key(vec![1, 2, 3], Some("203.0.113.7".parse().unwrap()))
+                    .await
+                    .unwrap();
+
+                assert_eq!(user.account_id, account_id);
+                assert_eq!(user.permissions, vec![Permission::ReadWallet]);
+                assert_eq!(user.wallet_ids, Some(vec![wallet_id]));
+            }
+
+            #[tokio::test]
+            async fn does_not_record_usage_again_within_the_resolution() {
+                let mut store = MockAppStoreBuilder::new();
+                store.api_key.expect_find_by_key_hash().times(1).returning(|_| {
+                    Ok(Some(ApiKey {
+                        last_used_at: Some(Utc::now()),
+                        last_used_ip: Some("203.0.113.7".to_string()),
+                        ..Default::default()
+                    }))
+                });
+                store.api_key.expect_record_usage().never();
+
+                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+                service
+                    .authenticate_api_key(vec![1, 2, 3], Some("203.0.113.7".parse().unwrap()))
+                    .await
+                    .unwrap();
+            }
+
+            #[tokio::test]
+            async fn a_usage_recording_failure_does_not_fail_authentication() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .api_key
+                    .expect_find_by_key_hash()
                     .times(1)
-                    .returning(move |provider, subject, _, permissions| {
-                        Ok(account_fixture(account_id, provider, subject, permissions.to_vec()))
-                    });
+                    .returning(|_| Ok(Some(ApiKey::default())));
                 store
-                    .config
-                    .expect_insert_if_absent()
-                    .withf(|key, _| key == PASSWORD_HASH_KEY)
+                    .api_key
+                    .expect_record_usage()
                     .times(1)
-                    .returning(|_, _| Ok(false));
+                    .returning(|_, _| Err(DatabaseError::Update("database unavailable".to_string())));
 
                 let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-                let err = service.sign_up("password".to_string()).await.unwrap_err();
-
-                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+                assert!(service.authenticate_api_key(vec![1, 2, 3], None).await.is_ok());
             }
         }
 
-        mod when_admin_account_creation_fails {
+        mod when_key_is_bound_to_a_member {
             use super::*;
 
             #[tokio::test]
-            async fn does_not_claim_the_password() {
+            async fn acts_with_the_current_role_of_the_member() {
+                let organization_id = Uuid::new_v4();
+                let member_account_id = Uuid::new_v4();
+
                 let mut store = MockAppStoreBuilder::new();
-                store.config.expect_find().times(1).returning(|_| Ok(None));
+                store.api_key.expect_find_by_key_hash().times(1).returning(move |_| {
+                    Ok(Some(ApiKey {
+                        account_id: organization_id,
+                        member_account_id: Some(member_account_id),
+                        ..Default::default()
+                    }))
+                });
                 store
-                    .account
-                    .expect_upsert()
+                    .organization
+                    .expect_find_member()
+                    .withf(move |org, account| *org == organization_id && *account == member_account_id)
                     .times(1)
-                    .returning(|_, _, _, _| Err(DatabaseError::Insert("boom".to_string())));
+                    .returning(move |_, _| {
+                        Ok(Some(OrganizationMember {
+                            organization_id,
+                            account_id: member_account_id,
+                            role: OrganizationRole::Viewer,
+                            created_at: Utc::now(),
+                            updated_at: None,
+                        }))
+                    });
+                store.api_key.expect_record_usage().returning(|_, _| Ok(()));
 
                 let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-                let err = service.sign_up("password".to_string()).await.unwrap_err();
+                let user = service.authenticate_api_key(vec![1, 2, 3], None).await.unwrap();
 
-                assert!(matches!(err, ApplicationError::Database(DatabaseError::Insert(_))));
+                assert_eq!(user.account_id, organization_id);
+                let membership = user.membership.unwrap();
+                assert_eq!(membership.account_id, member_account_id);
+                assert_eq!(membership.role, OrganizationRole::Viewer);
             }
-        }
-    }
-
-    mod sign_in {
-        use super::*;
-
-        mod when_credentials_are_missing {
-            use super::*;
 
             #[tokio::test]
-            async fn returns_not_found() {
+            async fn rejects_keys_of_former_members() {
                 let mut store = MockAppStoreBuilder::new();
-                store.config.expect_find().times(1).returning(|_| Ok(None));
+                store.api_key.expect_find_by_key_hash().times(1).returning(|_| {
+                    Ok(Some(ApiKey {
+                        member_account_id: Some(Uuid::new_v4()),
+                        ..Default::default()
+                    }))
+                });
+                store
+                    .organization
+                    .expect_find_member()
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store.api_key.expect_record_usage().never();
 
                 let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-                let err = service.sign_in("password".to_string()).await.unwrap_err();
+                let err = service.authenticate_api_key(vec![1, 2, 3], None).await.unwrap_err();
 
-                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+                assert!(matches!(
+                    err,
+                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+                ));
             }
         }
 
-        mod with_a_wrong_password {
+        mod when_key_is_expired {
             use super::*;
 
             #[tokio::test]
             async fn returns_invalid_credentials() {
-                let stored_hash = hash("correct", 4).unwrap();
-
                 let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+                store.api_key.expect_find_by_key_hash().times(1).returning(|_| {
+                    Ok(Some(ApiKey {
+                        expires_at: Some(Utc::now() - Duration::seconds(1)),
+                        ..Default::default()
+                    }))
+                });
+                store.api_key.expect_record_usage().never();
 
                 let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-                let err = service.sign_in("wrong".to_string()).await.unwrap_err();
+                let err = service.authenticate_api_key(vec![1, 2, 3], None).await.unwrap_err();
 
                 assert!(matches!(
                     err,
@@ -502,364 +2595,1669 @@ mod tests {
                 ));
             }
         }
+    }
 
-        mod with_the_correct_password_but_missing_account_identity {
-            use super::*;
+    fn self_registration_service(jwt: MockJWTAuthenticator, store: MockAppStoreBuilder) -> AuthService {
+        AuthService::new(
+            Arc::new(jwt),
+            store.build(),
+            AuthProvider::Jwt,
+            BtcNetwork::Regtest,
+            LocalAuthConfig {
+                self_registration: true,
+                ..Default::default()
+            },
+            HOST.to_string(),
+        )
+    }
 
-            #[tokio::test]
-            async fn returns_inconsistency_without_recreating_the_account() {
-                let stored_hash = hash("correct", 4).unwrap();
+    fn credential_fixture(account_id: Uuid, username: &str, password: &str) -> LocalCredential {
+        LocalCredential {
+            identity_id: Uuid::new_v4(),
+            account_id,
+            username: username.to_string(),
+            password_hash: hash(password, 4).unwrap(),
+        }
+    }
 
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
-                store
-                    .account
-                    .expect_find_by_identity()
-                    .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "admin")
-                    .times(1)
-                    .returning(|_, _| Ok(None));
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+    fn register_request(invitation: Option<String>) -> RegisterRequest {
+        RegisterRequest {
+            username: " Alice ".to_string(),
+            email: Some("Alice@Example.com".to_string()),
+            password: "correct horse".to_string(),
+            display_name: None,
+            invitation,
+        }
+    }
 
-                let err = service.sign_in("correct".to_string()).await.unwrap_err();
+    mod sign_in_with_a_login {
+        use super::*;
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Inconsistency(_))));
-            }
+        #[tokio::test]
+        async fn an_unknown_login_returns_invalid_credentials() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .credential
+                .expect_find_by_login()
+                .withf(|login| login == "bob@example.com")
+                .times(1)
+                .returning(|_| Ok(None));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .sign_in(Some(" Bob@Example.com ".to_string()), "password".to_string(), None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
         }
 
-        mod with_the_correct_password_and_account_identity {
-            use super::*;
+        #[tokio::test]
+        async fn the_correct_password_returns_a_token_for_the_account() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .credential
+                .expect_find_by_login()
+                .times(1)
+                .returning(move |_| Ok(Some(credential_fixture(account_id, "alice", "correct horse"))));
+            store
+                .account
+                .expect_find_by_identity()
+                .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "alice")
+                .times(1)
+                .returning(move |provider, subject| {
+                    Ok(Some(account_fixture(
+                        account_id,
+                        provider,
+                        subject,
+                        vec![Permission::ReadWallet],
+                    )))
+                });
 
-            #[tokio::test]
-            async fn returns_token() {
-                let account_id = Uuid::new_v4();
-                let stored_hash = hash("correct", 4).unwrap();
-                let permissions = Permission::all_permissions();
+            expect_no_totp_factor(&mut store, account_id);
+            let session_id = expect_session_start(&mut store, account_id);
 
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
-                let account_permissions = permissions.clone();
-                store
-                    .account
-                    .expect_find_by_identity()
-                    .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "admin")
-                    .times(1)
-                    .returning(move |provider, subject| {
-                        Ok(Some(account_fixture(
-                            account_id,
-                            provider,
-                            subject,
-                            account_permissions.clone(),
-                        )))
-                    });
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode()
+                .withf(move |account, sid| {
+                    account.id == account_id
+                        && account.permissions.as_deref() == Some(&[Permission::ReadWallet][..])
+                        && *sid == session_id
+                })
+                .times(1)
+                .returning(|_, _| Ok("token".to_string()));
 
-                let expected_permissions = permissions.clone();
-                let mut jwt = MockJWTAuthenticator::new();
-                jwt.expect_encode()
-                    .withf(move |account| {
-                        account
-                            .identity
-                            .as_ref()
-                            .is_some_and(|identity| identity.subject == "admin")
-                            && account.permissions.as_ref() == Some(&expected_permissions)
-                    })
-                    .times(1)
-                    .returning(|_| Ok("token".to_string()));
+            let service = service(jwt, store, AuthProvider::Jwt);
 
-                let service = service(jwt, store, AuthProvider::Jwt);
+            let response = service
+                .sign_in(Some("alice".to_string()), "correct horse".to_string(), None)
+                .await
+                .unwrap();
+
+            assert_eq!(response.token, "token");
+        }
+
+        #[tokio::test]
+        async fn a_wrong_password_returns_invalid_credentials() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .credential
+                .expect_find_by_login()
+                .times(1)
+                .returning(|_| Ok(Some(credential_fixture(Uuid::new_v4(), "alice", "correct horse"))));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .sign_in(Some("alice".to_string()), "wrong".to_string(), None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
+    }
+
+    mod change_password_of_a_local_account {
+        use super::*;
+
+        #[tokio::test]
+        async fn updates_the_credential_of_the_caller() {
+            let account_id = Uuid::new_v4();
+            let credential = credential_fixture(account_id, "alice", "correct horse");
+            let identity_id = credential.identity_id;
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find()
+                .withf(move |id| *id == account_id)
+                .times(1)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+            store
+                .credential
+                .expect_find_by_account()
+                .times(2)
+                .returning(move |_| Ok(Some(credential.clone())));
+            store
+                .credential
+                .expect_update_password()
+                .withf(move |id, hash| *id == identity_id && verify("battery staple", hash).unwrap_or(false))
+                .times(1)
+                .returning(|_, _| Ok(()));
+            expect_sessions_revoked(&mut store, account_id);
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            service
+                .change_password(
+                    User {
+                        account_id,
+                        ..Default::default()
+                    },
+                    "correct horse".to_string(),
+                    "battery staple".to_string(),
+                )
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn rejects_a_short_new_password() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find()
+                .times(1)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .change_password(User::default(), "correct horse".to_string(), "short".to_string())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+    }
+
+    mod register {
+        use super::*;
+
+        #[tokio::test]
+        async fn requires_an_invitation_unless_self_registration_is_enabled() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
+
+            let err = service.register(register_request(None)).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn reserves_the_admin_username() {
+            let service = self_registration_service(MockJWTAuthenticator::new(), MockAppStoreBuilder::new());
+
+            let err = service
+                .register(RegisterRequest {
+                    username: "Admin".to_string(),
+                    ..register_request(None)
+                })
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_a_taken_username() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find_by_identity()
+                .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "alice")
+                .times(1)
+                .returning(|provider, subject| Ok(Some(account_fixture(Uuid::new_v4(), provider, subject, vec![]))));
+
+            let service = self_registration_service(MockJWTAuthenticator::new(), store);
+
+            let err = service.register(register_request(None)).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
+
+        #[tokio::test]
+        async fn self_registered_accounts_have_no_permissions() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(|_, _| Ok(None));
+            store
+                .credential
+                .expect_find_by_login()
+                .withf(|login| login == "alice@example.com")
+                .times(1)
+                .returning(|_| Ok(None));
+            store
+                .credential
+                .expect_register()
+                .withf(|registration| {
+                    registration.username == "alice"
+                        && registration.email.as_deref() == Some("alice@example.com")
+                        && registration.permissions.is_empty()
+                        && registration.invitation_id.is_none()
+                        && verify("correct horse", &registration.password_hash).unwrap_or(false)
+                })
+                .times(1)
+                .returning(move |registration| {
+                    Ok(Some(account_fixture(
+                        account_id,
+                        AuthProvider::Jwt,
+                        &registration.username,
+                        registration.permissions,
+                    )))
+                });
+
+            expect_session_start(&mut store, account_id);
+
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode()
+                .withf(move |account, _| account.id == account_id)
+                .times(1)
+                .returning(|_, _| Ok("token".to_string()));
+
+            let service = self_registration_service(jwt, store);
+
+            let response = service.register(register_request(None)).await.unwrap();
+
+            assert_eq!(response.token, "token");
+        }
+
+        #[tokio::test]
+        async fn grants_the_permissions_of_the_invitation() {
+            let (token, token_hash) = generate_token();
+            let invitation_id = Uuid::new_v4();
+            let account_id = Uuid::new_v4();
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .invitation
+                .expect_find_by_token_hash()
+                .withf(move |hash| *hash == token_hash)
+                .times(1)
+                .returning(move |_| {
+                    Ok(Some(Invitation {
+                        id: invitation_id,
+                        permissions: vec![Permission::ReadWallet],
+                        ..Default::default()
+                    }))
+                });
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(|_, _| Ok(None));
+            store.credential.expect_find_by_login().times(1).returning(|_| Ok(None));
+            store
+                .credential
+                .expect_register()
+                .withf(move |registration| {
+                    registration.permissions == vec![Permission::ReadWallet]
+                        && registration.invitation_id == Some(invitation_id)
+                })
+                .times(1)
+                .returning(move |registration| {
+                    Ok(Some(account_fixture(
+                        account_id,
+                        AuthProvider::Jwt,
+                        &registration.username,
+                        registration.permissions,
+                    )))
+                });
+            expect_session_start(&mut store, account_id);
+
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode().times(1).returning(|_, _| Ok("token".to_string()));
+
+            let service = service(jwt, store, AuthProvider::Jwt);
+
+            service.register(register_request(Some(token))).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn an_invitation_consumed_concurrently_is_rejected() {
+            let (token, _) = generate_token();
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .invitation
+                .expect_find_by_token_hash()
+                .times(1)
+                .returning(|_| Ok(Some(Invitation::default())));
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(|_, _| Ok(None));
+            store.credential.expect_find_by_login().times(1).returning(|_| Ok(None));
+            store.credential.expect_register().times(1).returning(|_| Ok(None));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service.register(register_request(Some(token))).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+    }
+
+    mod create_invitation {
+        use super::*;
+
+        #[tokio::test]
+        async fn cannot_grant_permissions_the_caller_does_not_hold() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
+
+            let err = service
+                .create_invitation(
+                    User {
+                        permissions: vec![Permission::WriteAccount],
+                        ..Default::default()
+                    },
+                    CreateInvitationRequest {
+                        email: None,
+                        permissions: vec![Permission::WriteLnNode],
+                        expiry: None,
+                    },
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn stores_the_hash_of_the_returned_token() {
+            let mut store = MockAppStoreBuilder::new();
+            store.invitation.expect_insert().times(1).returning(Ok);
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let invitation = service
+                .create_invitation(
+                    User {
+                        permissions: vec![Permission::ReadWallet],
+                        ..Default::default()
+                    },
+                    CreateInvitationRequest {
+                        email: Some("Bob@Example.com".to_string()),
+                        permissions: vec![Permission::ReadWallet],
+                        expiry: Some(3_600),
+                    },
+                )
+                .await
+                .unwrap();
+
+            let token = invitation.token.as_deref().unwrap();
+            assert_eq!(token_hash(token), Some(invitation.token_hash));
+            assert_eq!(invitation.email.as_deref(), Some("bob@example.com"));
+            assert!(invitation.expires_at > Utc::now());
+        }
+    }
+
+    mod reset_password {
+        use super::*;
+
+        #[tokio::test]
+        async fn rejects_an_unknown_token() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .credential
+                .expect_consume_password_reset()
+                .times(1)
+                .returning(|_| Ok(None));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let (token, _) = generate_token();
+            let err = service
+                .reset_password(token, "battery staple".to_string())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
 
-                let token = service.sign_in("correct".to_string()).await.unwrap();
+        #[tokio::test]
+        async fn sets_the_new_password_of_the_token_account() {
+            let account_id = Uuid::new_v4();
+            let credential = credential_fixture(account_id, "alice", "forgotten");
+            let identity_id = credential.identity_id;
+            let (token, token_hash) = generate_token();
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .credential
+                .expect_consume_password_reset()
+                .withf(move |hash| *hash == token_hash)
+                .times(1)
+                .returning(move |_| Ok(Some(account_id)));
+            store
+                .account
+                .expect_find()
+                .times(1)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+            store
+                .credential
+                .expect_find_by_account()
+                .times(1)
+                .returning(move |_| Ok(Some(credential.clone())));
+            store
+                .credential
+                .expect_update_password()
+                .withf(move |id, hash| *id == identity_id && verify("battery staple", hash).unwrap_or(false))
+                .times(1)
+                .returning(|_, _| Ok(()));
+            expect_sessions_revoked(&mut store, account_id);
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            service
+                .reset_password(token, "battery staple".to_string())
+                .await
+                .unwrap();
+        }
+    }
+
+    mod create_password_reset {
+        use super::*;
+
+        #[tokio::test]
+        async fn rejects_accounts_without_local_credentials() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find()
+                .times(1)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::OAuth2, "auth0|bob", vec![]))));
+            store
+                .credential
+                .expect_find_by_account()
+                .times(1)
+                .returning(|_| Ok(None));
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .create_password_reset(User::default(), Uuid::new_v4())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_accounts_with_permissions_the_caller_lacks() {
+            let mut store = MockAppStoreBuilder::new();
+            store.account.expect_find().times(1).returning(|id| {
+                Ok(Some(account_fixture(
+                    id,
+                    AuthProvider::Jwt,
+                    "bob",
+                    vec![Permission::WriteAccount, Permission::WriteTransaction],
+                )))
+            });
+            store.credential.expect_insert_password_reset().never();
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+            let caller = User {
+                permissions: vec![Permission::WriteAccount],
+                ..Default::default()
+            };
+
+            let err = service.create_password_reset(caller, Uuid::new_v4()).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::MissingPermission(Permission::WriteTransaction))
+            ));
+        }
 
-                assert_eq!(token, "token");
+        #[tokio::test]
+        async fn rejects_the_bootstrap_admin_unless_the_caller_is_the_admin() {
+            let admin_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store.account.expect_find().times(1).returning(|id| {
+                Ok(Some(account_fixture(
+                    id,
+                    AuthProvider::Jwt,
+                    BOOTSTRAP_ADMIN_SUBJECT,
+                    Permission::all_permissions(),
+                )))
+            });
+            store.credential.expect_insert_password_reset().never();
+
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+            let caller = User {
+                account_id: Uuid::new_v4(),
+                permissions: Permission::all_permissions(),
+                ..Default::default()
+            };
+
+            let err = service.create_password_reset(caller, admin_id).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::BootstrapAdminRequired)
+            ));
+        }
+    }
+
+    mod two_factor {
+        use super::*;
+
+        const SECRET: &[u8] = b"12345678901234567890";
+
+        fn factor(account_id: Uuid, confirmed: bool) -> TotpFactor {
+            TotpFactor {
+                account_id,
+                secret: SECRET.to_vec(),
+                confirmed_at: confirmed.then(Utc::now),
+                ..Default::default()
             }
         }
 
-        mod when_stored_hash_is_not_a_string {
-            use super::*;
+        fn expect_factor(store: &mut MockAppStoreBuilder, account_id: Uuid, confirmed: bool) {
+            store
+                .totp
+                .expect_find()
+                .withf(move |id| *id == account_id)
+                .returning(move |account_id| Ok(Some(factor(account_id, confirmed))));
+        }
 
-            #[tokio::test]
-            async fn returns_inconsistency() {
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(|_| Ok(Some(serde_json::json!(42))));
+        fn session_user(account_id: Uuid, session_id: Uuid) -> User {
+            User {
+                account_id,
+                session_id: Some(session_id),
+                ..Default::default()
+            }
+        }
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+        /// Store of a local account `alice` with a confirmed factor, signing in with the right password.
+        fn sign_in_store(account_id: Uuid) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .credential
+                .expect_find_by_login()
+                .times(1)
+                .returning(move |_| Ok(Some(credential_fixture(account_id, "alice", "correct horse"))));
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(move |provider, subject| Ok(Some(account_fixture(account_id, provider, subject, vec![]))));
+            expect_factor(&mut store, account_id, true);
+            store
+        }
+
+        fn session_store(
+            account_id: Uuid,
+            session_id: Uuid,
+            verified_at: Option<DateTime<Utc>>,
+        ) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            expect_factor(&mut store, account_id, true);
+            store
+                .session
+                .expect_find()
+                .withf(move |id| *id == session_id)
+                .returning(move |id| {
+                    Ok(Some(Session {
+                        id,
+                        account_id,
+                        verified_at,
+                        ..Default::default()
+                    }))
+                });
+            store
+        }
+
+        #[tokio::test]
+        async fn sign_in_without_a_code_requires_one() {
+            let store = sign_in_store(Uuid::new_v4());
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .sign_in(Some("alice".to_string()), "correct horse".to_string(), None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::OtpRequired)
+            ));
+        }
+
+        #[tokio::test]
+        async fn sign_in_with_a_valid_code_starts_a_verified_session() {
+            let account_id = Uuid::new_v4();
+            let mut store = sign_in_store(account_id);
+            store
+                .totp
+                .expect_use_step()
+                .withf(move |id, step| *id == account_id && *step > 0)
+                .times(1)
+                .returning(|_, _| Ok(true));
+            store
+                .session
+                .expect_insert()
+                .withf(|_, _, _, verified_at| verified_at.is_some())
+                .times(1)
+                .returning(|account_id, _, expires_at, verified_at| {
+                    Ok(Session {
+                        account_id,
+                        expires_at,
+                        verified_at,
+                        ..Default::default()
+                    })
+                });
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode().times(1).returning(|_, _| Ok("token".to_string()));
+            let service = service(jwt, store, AuthProvider::Jwt);
+
+            let response = service
+                .sign_in(
+                    Some("alice".to_string()),
+                    "correct horse".to_string(),
+                    Some(totp::code_at(SECRET, Utc::now())),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.token, "token");
+        }
+
+        #[tokio::test]
+        async fn sign_in_with_a_replayed_code_returns_invalid_credentials() {
+            let mut store = sign_in_store(Uuid::new_v4());
+            store.totp.expect_use_step().times(1).returning(|_, _| Ok(false));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .sign_in(
+                    Some("alice".to_string()),
+                    "correct horse".to_string(),
+                    Some(totp::code_at(SECRET, Utc::now())),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
 
-                let err = service.sign_in("password".to_string()).await.unwrap_err();
+        #[tokio::test]
+        async fn sign_in_accepts_a_recovery_code() {
+            let account_id = Uuid::new_v4();
+            let mut store = sign_in_store(account_id);
+            store
+                .totp
+                .expect_use_recovery_code()
+                .withf(move |id, code_hash| *id == account_id && *code_hash == totp::recovery_code_hash("abcde-fghij"))
+                .times(1)
+                .returning(|_, _| Ok(true));
+            expect_session_start(&mut store, account_id);
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode().times(1).returning(|_, _| Ok("token".to_string()));
+            let service = service(jwt, store, AuthProvider::Jwt);
+
+            service
+                .sign_in(
+                    Some("alice".to_string()),
+                    "correct horse".to_string(),
+                    Some("ABCDE-FGHIJ".to_string()),
+                )
+                .await
+                .unwrap();
+        }
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Inconsistency(_))));
-            }
+        #[tokio::test]
+        async fn enroll_requires_a_session() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
+
+            let err = service
+                .enroll_totp(User {
+                    account_id: Uuid::new_v4(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::SessionRequired)
+            ));
         }
-    }
 
-    mod change_password {
-        use super::*;
+        #[tokio::test]
+        async fn enroll_returns_the_secret_and_key_uri() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find()
+                .times(1)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+            store
+                .totp
+                .expect_insert_pending()
+                .withf(move |id, secret| *id == account_id && secret.len() == totp::TOTP_SECRET_LENGTH)
+                .times(1)
+                .returning(|account_id, secret| {
+                    Ok(Some(TotpFactor {
+                        account_id,
+                        secret,
+                        ..Default::default()
+                    }))
+                });
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-        mod when_provider_is_not_jwt {
-            use super::*;
+            let enrollment = service
+                .enroll_totp(session_user(account_id, Uuid::new_v4()))
+                .await
+                .unwrap();
 
-            #[tokio::test]
-            async fn returns_unsupported_operation() {
-                let service = service(
-                    MockJWTAuthenticator::new(),
-                    MockAppStoreBuilder::new(),
-                    AuthProvider::OAuth2,
-                );
+            assert_eq!(enrollment.secret.len(), 32);
+            assert!(enrollment
+                .otpauth_uri
+                .starts_with(&format!("otpauth://totp/Swissknife:alice?secret={}", enrollment.secret)));
+        }
 
-                let err = service
-                    .change_password("current".to_string(), "new".to_string())
-                    .await
-                    .unwrap_err();
+        #[tokio::test]
+        async fn enroll_conflicts_with_an_enabled_factor() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_find()
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+            store.totp.expect_insert_pending().returning(|_, _| Ok(None));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .enroll_totp(session_user(Uuid::new_v4(), Uuid::new_v4()))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
 
-                assert!(matches!(
-                    err,
-                    ApplicationError::Authentication(AuthenticationError::UnsupportedOperation)
-                ));
-            }
+        #[tokio::test]
+        async fn confirm_enables_the_factor_and_verifies_the_session() {
+            let account_id = Uuid::new_v4();
+            let session_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_factor(&mut store, account_id, false);
+            store
+                .totp
+                .expect_confirm()
+                .withf(move |id, step, hashes| *id == account_id && *step > 0 && hashes.len() == RECOVERY_CODE_COUNT)
+                .times(1)
+                .returning(|_, _, _| Ok(true));
+            store
+                .session
+                .expect_verify()
+                .withf(move |id| *id == session_id)
+                .times(1)
+                .returning(|_| Ok(true));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let codes = service
+                .confirm_totp(session_user(account_id, session_id), totp::code_at(SECRET, Utc::now()))
+                .await
+                .unwrap();
+
+            assert_eq!(codes.recovery_codes.len(), RECOVERY_CODE_COUNT);
         }
 
-        mod when_credentials_are_missing {
-            use super::*;
+        #[tokio::test]
+        async fn confirm_rejects_an_invalid_code() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_factor(&mut store, account_id, false);
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-            #[tokio::test]
-            async fn returns_not_found() {
-                let mut store = MockAppStoreBuilder::new();
-                store.config.expect_find().times(1).returning(|_| Ok(None));
+            let err = service
+                .confirm_totp(session_user(account_id, Uuid::new_v4()), "12345".to_string())
+                .await
+                .unwrap_err();
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
 
-                let err = service
-                    .change_password("current".to_string(), "new".to_string())
-                    .await
-                    .unwrap_err();
+        #[tokio::test]
+        async fn disable_with_a_recovery_code_deletes_the_factor() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_factor(&mut store, account_id, true);
+            store
+                .totp
+                .expect_use_recovery_code()
+                .times(1)
+                .returning(|_, _| Ok(true));
+            store
+                .totp
+                .expect_delete()
+                .withf(move |id| *id == account_id)
+                .times(1)
+                .returning(|_| Ok(1));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            service
+                .disable_totp(session_user(account_id, Uuid::new_v4()), "abcde-fghij".to_string())
+                .await
+                .unwrap();
+        }
 
-                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
-            }
+        #[tokio::test]
+        async fn step_up_verifies_the_session() {
+            let account_id = Uuid::new_v4();
+            let session_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_factor(&mut store, account_id, true);
+            store.totp.expect_use_step().times(1).returning(|_, _| Ok(true));
+            store
+                .session
+                .expect_verify()
+                .withf(move |id| *id == session_id)
+                .times(1)
+                .returning(|_| Ok(true));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            service
+                .step_up(session_user(account_id, session_id), totp::code_at(SECRET, Utc::now()))
+                .await
+                .unwrap();
         }
 
-        mod with_a_wrong_current_password {
-            use super::*;
+        #[tokio::test]
+        async fn check_step_up_ignores_api_keys() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
+            let user = User {
+                api_key_id: Some(Uuid::new_v4()),
+                ..Default::default()
+            };
+
+            service.check_step_up(&user).await.unwrap();
+            assert_eq!(service.payment_step_up_limit(&user).await.unwrap(), None);
+        }
 
-            #[tokio::test]
-            async fn returns_validation_error() {
-                let stored_hash = hash("correct", 4).unwrap();
+        #[tokio::test]
+        async fn check_step_up_rejects_principals_without_a_session_of_accounts_with_a_factor() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_factor(&mut store, account_id, true);
+            store.session.expect_find().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+            let user = User {
+                account_id,
+                subject: Some("3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d".to_string()),
+                ..Default::default()
+            };
+
+            let err = service.check_step_up(&user).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::StepUpRequired)
+            ));
+            assert_eq!(
+                service.payment_step_up_limit(&user).await.unwrap(),
+                Some(LocalAuthConfig::default().step_up_payment_threshold_msat)
+            );
+        }
 
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
+        #[tokio::test]
+        async fn check_step_up_ignores_accounts_without_a_factor() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_no_totp_factor(&mut store, account_id);
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            service
+                .check_step_up(&session_user(account_id, Uuid::new_v4()))
+                .await
+                .unwrap();
+        }
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+        #[tokio::test]
+        async fn check_step_up_rejects_sessions_without_a_recent_verification() {
+            let account_id = Uuid::new_v4();
+            let session_id = Uuid::new_v4();
+            let store = session_store(account_id, session_id, Some(Utc::now() - Duration::minutes(10)));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+            let user = session_user(account_id, session_id);
+
+            let err = service.check_step_up(&user).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::StepUpRequired)
+            ));
+            assert_eq!(
+                service.payment_step_up_limit(&user).await.unwrap(),
+                Some(LocalAuthConfig::default().step_up_payment_threshold_msat)
+            );
+        }
 
-                let err = service
-                    .change_password("wrong".to_string(), "new".to_string())
-                    .await
-                    .unwrap_err();
+        #[tokio::test]
+        async fn check_step_up_accepts_recently_verified_sessions() {
+            let account_id = Uuid::new_v4();
+            let session_id = Uuid::new_v4();
+            let store = session_store(account_id, session_id, Some(Utc::now() - Duration::minutes(1)));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+            let user = session_user(account_id, session_id);
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
-            }
+            service.check_step_up(&user).await.unwrap();
+            assert_eq!(service.payment_step_up_limit(&user).await.unwrap(), None);
         }
+    }
 
-        mod with_the_correct_current_password {
-            use super::*;
+    mod passkeys {
+        use swissknife_types::{
+            PasskeyAssertion, PasskeyAssertionResponse, PasskeyAttestation, PasskeyAttestationResponse,
+        };
 
-            #[tokio::test]
-            async fn persists_the_new_password_hash() {
-                let stored_hash = hash("current", 4).unwrap();
+        use crate::domains::account::webauthn::testing::SoftAuthenticator;
 
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(move |_| Ok(Some(stored_hash.clone().into())));
-                store
-                    .config
-                    .expect_upsert()
-                    .withf(|key, value| {
-                        key == PASSWORD_HASH_KEY
-                            && value.as_str().is_some_and(|hash| verify("new", hash).unwrap_or(false))
-                    })
-                    .times(1)
-                    .returning(|_, _| Ok(()));
+        use super::*;
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+        const RP_ID: &str = "app.numeraire.tech";
+        const CHALLENGE: [u8; 32] = [7; 32];
 
-                service
-                    .change_password("current".to_string(), "new".to_string())
-                    .await
-                    .unwrap();
+        fn session_user(account_id: Uuid) -> User {
+            User {
+                account_id,
+                session_id: Some(Uuid::new_v4()),
+                ..Default::default()
             }
         }
 
-        mod when_stored_hash_is_not_a_string {
-            use super::*;
-
-            #[tokio::test]
-            async fn returns_inconsistency() {
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .config
-                    .expect_find()
-                    .times(1)
-                    .returning(|_| Ok(Some(serde_json::json!(42))));
+        fn expect_challenge(store: &mut MockAppStoreBuilder, ceremony_id: Uuid, account_id: Option<Uuid>) {
+            store
+                .passkey
+                .expect_consume_challenge()
+                .withf(move |id| *id == ceremony_id)
+                .times(1)
+                .returning(move |id| {
+                    Ok(Some(PasskeyChallenge {
+                        id,
+                        challenge: CHALLENGE.to_vec(),
+                        account_id,
+                        ..Default::default()
+                    }))
+                });
+        }
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+        fn expect_account(store: &mut MockAppStoreBuilder, account_id: Uuid) {
+            store
+                .account
+                .expect_find()
+                .withf(move |id| *id == account_id)
+                .returning(|id| Ok(Some(account_fixture(id, AuthProvider::Jwt, "alice", vec![]))));
+        }
 
-                let err = service
-                    .change_password("current".to_string(), "new".to_string())
-                    .await
-                    .unwrap_err();
+        fn registration(authenticator: &mut SoftAuthenticator, ceremony_id: Uuid) -> RegisterPasskeyRequest {
+            let (client_data, attestation_object) = authenticator.register(RP_ID, HOST, &CHALLENGE);
+            RegisterPasskeyRequest {
+                ceremony_id,
+                name: Some(" YubiKey ".to_string()),
+                credential: PasskeyAttestation {
+                    id: webauthn::encode(&authenticator.credential_id),
+                    response: PasskeyAttestationResponse {
+                        client_data_json: webauthn::encode(&client_data),
+                        attestation_object: webauthn::encode(&attestation_object),
+                    },
+                },
+            }
+        }
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Inconsistency(_))));
+        fn sign_in_request(authenticator: &mut SoftAuthenticator, ceremony_id: Uuid) -> PasskeySignInRequest {
+            let (client_data, auth_data, signature) = authenticator.assert(RP_ID, HOST, &CHALLENGE);
+            PasskeySignInRequest {
+                ceremony_id,
+                credential: PasskeyAssertion {
+                    id: webauthn::encode(&authenticator.credential_id),
+                    response: PasskeyAssertionResponse {
+                        client_data_json: webauthn::encode(&client_data),
+                        authenticator_data: webauthn::encode(&auth_data),
+                        signature: webauthn::encode(&signature),
+                        user_handle: None,
+                    },
+                },
             }
         }
-    }
 
-    mod authenticate_jwt {
-        use super::*;
+        /// Store finding the passkey of `authenticator`, registered to `account_id`.
+        fn sign_in_store(
+            authenticator: &SoftAuthenticator,
+            ceremony_id: Uuid,
+            account_id: Uuid,
+        ) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            expect_challenge(&mut store, ceremony_id, None);
+            let credential_id = authenticator.credential_id.clone();
+            let public_key = authenticator.cose_public_key();
+            store
+                .passkey
+                .expect_find_by_credential_id()
+                .withf(move |id| *id == credential_id)
+                .returning(move |credential_id| {
+                    Ok(Some(Passkey {
+                        id: Uuid::new_v4(),
+                        account_id,
+                        credential_id,
+                        public_key: public_key.clone(),
+                        ..Default::default()
+                    }))
+                });
+            store
+        }
 
-        mod when_token_is_valid {
-            use super::*;
+        #[tokio::test]
+        async fn registration_options_bind_a_challenge_to_the_account() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            expect_account(&mut store, account_id);
+            store.passkey.expect_find_many().times(1).returning(|account_id| {
+                Ok(vec![Passkey {
+                    account_id,
+                    credential_id: vec![1, 2, 3],
+                    ..Default::default()
+                }])
+            });
+            store
+                .passkey
+                .expect_insert_challenge()
+                .withf(move |challenge| {
+                    challenge.account_id == Some(account_id) && challenge.challenge.len() == webauthn::CHALLENGE_LENGTH
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let options = service
+                .passkey_registration_options(session_user(account_id))
+                .await
+                .unwrap();
+
+            assert_eq!(options.public_key.rp.id, RP_ID);
+            assert_eq!(options.public_key.user.id, webauthn::encode(account_id.as_bytes()));
+            assert_eq!(options.public_key.user.name, "alice");
+            assert_eq!(options.public_key.exclude_credentials.len(), 1);
+            assert_eq!(options.public_key.exclude_credentials[0].id, "AQID");
+            assert_eq!(options.public_key.authenticator_selection.user_verification, "required");
+        }
 
-            #[tokio::test]
-            async fn ensures_the_active_asset_wallet() {
-                let wallet_id = Uuid::new_v4();
-                let account_id = Uuid::new_v4();
-                let asset_id = Uuid::new_v4();
+        #[tokio::test]
+        async fn registration_options_require_a_session() {
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
 
-                let mut jwt = MockJWTAuthenticator::new();
-                jwt.expect_decode().times(1).returning(|_| Ok(claims("alice")));
+            let err = service.passkey_registration_options(User::default()).await.unwrap_err();
 
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .account
-                    .expect_find_by_identity()
-                    .withf(|provider, subject| *provider == AuthProvider::Jwt && subject == "alice")
-                    .times(1)
-                    .returning(|_, _| Ok(None));
-                store
-                    .account
-                    .expect_upsert()
-                    .withf(|provider, subject, display_name, granted| {
-                        *provider == AuthProvider::Jwt
-                            && subject == "alice"
-                            && display_name.is_none()
-                            && granted.is_empty()
-                    })
-                    .times(1)
-                    .returning(move |provider, subject, _, _| {
-                        Ok(account_fixture(
-                            account_id,
-                            provider,
-                            subject,
-                            vec![Permission::ReadApiKey],
-                        ))
-                    });
-                store
-                    .wallet
-                    .expect_find_by_account_and_asset()
-                    .withf(move |account, asset| *account == account_id && *asset == asset_id)
-                    .times(1)
-                    .returning(|_, _| Ok(None));
-                store
-                    .wallet
-                    .expect_upsert()
-                    .withf(move |account, asset| *account == account_id && *asset == asset_id)
-                    .times(1)
-                    .returning(move |account, asset| Ok(wallet_fixture(wallet_id, account, asset)));
-                store
-                    .asset
-                    .expect_find_native_btc_by_network()
-                    .withf(|network| *network == BtcNetwork::Regtest)
-                    .times(1)
-                    .returning(move |_| Ok(Some(asset_fixture(asset_id))));
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::SessionRequired)
+            ));
+        }
 
-                let service = service(jwt, store, AuthProvider::Jwt);
+        #[tokio::test]
+        async fn register_stores_the_credential() {
+            let account_id = Uuid::new_v4();
+            let ceremony_id = Uuid::new_v4();
+            let mut authenticator = SoftAuthenticator::ed25519();
+            let mut store = MockAppStoreBuilder::new();
+            expect_challenge(&mut store, ceremony_id, Some(account_id));
+            expect_account(&mut store, account_id);
+            store
+                .passkey
+                .expect_find_by_credential_id()
+                .times(1)
+                .returning(|_| Ok(None));
+            let credential_id = authenticator.credential_id.clone();
+            let public_key = authenticator.cose_public_key();
+            store
+                .passkey
+                .expect_insert()
+                .withf(move |passkey| {
+                    passkey.account_id == account_id
+                        && passkey.name == "YubiKey"
+                        && passkey.credential_id == credential_id
+                        && passkey.public_key == public_key
+                })
+                .times(1)
+                .returning(Ok);
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let passkey = service
+                .register_passkey(session_user(account_id), registration(&mut authenticator, ceremony_id))
+                .await
+                .unwrap();
+
+            assert_eq!(passkey.name, "YubiKey");
+        }
 
-                let user = service.authenticate_jwt("token").await.unwrap();
+        #[tokio::test]
+        async fn register_rejects_a_ceremony_of_another_account() {
+            let ceremony_id = Uuid::new_v4();
+            let mut authenticator = SoftAuthenticator::ed25519();
+            let mut store = MockAppStoreBuilder::new();
+            expect_challenge(&mut store, ceremony_id, Some(Uuid::new_v4()));
+            store.passkey.expect_insert().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .register_passkey(
+                    session_user(Uuid::new_v4()),
+                    registration(&mut authenticator, ceremony_id),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
 
-                assert_eq!(user.account_id, account_id);
-                assert_eq!(user.permissions, vec![Permission::ReadApiKey]);
-            }
+        #[tokio::test]
+        async fn register_rejects_a_credential_already_registered() {
+            let account_id = Uuid::new_v4();
+            let ceremony_id = Uuid::new_v4();
+            let mut authenticator = SoftAuthenticator::ed25519();
+            let mut store = MockAppStoreBuilder::new();
+            expect_challenge(&mut store, ceremony_id, Some(account_id));
+            store
+                .passkey
+                .expect_find_by_credential_id()
+                .times(1)
+                .returning(|_| Ok(Some(Passkey::default())));
+            store.passkey.expect_insert().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .register_passkey(session_user(account_id), registration(&mut authenticator, ceremony_id))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
         }
 
-        mod when_token_is_invalid {
-            use super::*;
+        #[tokio::test]
+        async fn sign_in_starts_a_verified_session() {
+            let account_id = Uuid::new_v4();
+            let ceremony_id = Uuid::new_v4();
+            let mut authenticator = SoftAuthenticator::p256();
+            let mut store = sign_in_store(&authenticator, ceremony_id, account_id);
+            store
+                .passkey
+                .expect_record_use()
+                .withf(|_, sign_count| *sign_count == 1)
+                .times(1)
+                .returning(|_, _| Ok(true));
+            expect_account(&mut store, account_id);
+            store
+                .session
+                .expect_insert()
+                .withf(move |id, _, _, verified_at| *id == account_id && verified_at.is_some())
+                .times(1)
+                .returning(|account_id, _, expires_at, verified_at| {
+                    Ok(Session {
+                        account_id,
+                        expires_at,
+                        verified_at,
+                        ..Default::default()
+                    })
+                });
+            let mut jwt = MockJWTAuthenticator::new();
+            jwt.expect_encode()
+                .withf(move |account, _| account.id == account_id)
+                .times(1)
+                .returning(|_, _| Ok("token".to_string()));
+            let service = service(jwt, store, AuthProvider::Jwt);
+
+            let response = service
+                .sign_in_passkey(sign_in_request(&mut authenticator, ceremony_id))
+                .await
+                .unwrap();
+
+            assert_eq!(response.token, "token");
+        }
 
-            #[tokio::test]
-            async fn propagates_authentication_error() {
-                let mut jwt = MockJWTAuthenticator::new();
-                jwt.expect_decode()
-                    .times(1)
-                    .returning(|_| Err(AuthenticationError::InvalidCredentials));
+        #[tokio::test]
+        async fn sign_in_with_a_wrong_signature_returns_invalid_credentials() {
+            let ceremony_id = Uuid::new_v4();
+            let mut authenticator = SoftAuthenticator::ed25519();
+            let mut store = sign_in_store(&authenticator, ceremony_id, Uuid::new_v4());
+            store.passkey.expect_record_use().never();
+            store.session.expect_insert().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let mut request = sign_in_request(&mut authenticator, ceremony_id);
+            let mut signature = webauthn::decode(&request.credential.response.signature).unwrap();
+            signature[0] ^= 1;
+            request.credential.response.signature = webauthn::encode(&signature);
+
+            let err = service.sign_in_passkey(request).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
 
-                let service = service(jwt, MockAppStoreBuilder::new(), AuthProvider::Jwt);
+        #[tokio::test]
+        async fn sign_in_with_a_stale_counter_returns_invalid_credentials() {
+            let ceremony_id = Uuid::new_v4();
+            let mut authenticator = SoftAuthenticator::ed25519();
+            let mut store = sign_in_store(&authenticator, ceremony_id, Uuid::new_v4());
+            store.passkey.expect_record_use().times(1).returning(|_, _| Ok(false));
+            store.session.expect_insert().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .sign_in_passkey(sign_in_request(&mut authenticator, ceremony_id))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
 
-                let err = service.authenticate_jwt("token").await.unwrap_err();
+        #[tokio::test]
+        async fn sign_in_with_an_unknown_ceremony_returns_invalid_credentials() {
+            let mut authenticator = SoftAuthenticator::ed25519();
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .passkey
+                .expect_consume_challenge()
+                .times(1)
+                .returning(|_| Ok(None));
+            store.passkey.expect_find_by_credential_id().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .sign_in_passkey(sign_in_request(&mut authenticator, Uuid::new_v4()))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
 
-                assert!(matches!(err, ApplicationError::Authentication(_)));
-            }
+        #[tokio::test]
+        async fn delete_of_an_unknown_passkey_returns_not_found() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .passkey
+                .expect_delete()
+                .withf(move |id, _| *id == account_id)
+                .times(1)
+                .returning(|_, _| Ok(0));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .delete_passkey(session_user(account_id), Uuid::new_v4())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
         }
-    }
 
-    mod authenticate_api_key {
-        use super::*;
+        #[tokio::test]
+        async fn delete_requires_a_session() {
+            let mut store = MockAppStoreBuilder::new();
+            store.passkey.expect_delete().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
 
-        mod when_key_is_unknown {
-            use super::*;
+            let err = service
+                .delete_passkey(
+                    User {
+                        account_id: Uuid::new_v4(),
+                        ..Default::default()
+                    },
+                    Uuid::new_v4(),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::SessionRequired)
+            ));
+        }
+    }
 
-            #[tokio::test]
-            async fn returns_invalid_credentials() {
-                let mut store = MockAppStoreBuilder::new();
-                store.api_key.expect_find_by_key_hash().times(1).returning(|_| Ok(None));
+    mod nostr_keys {
+        use nostr_sdk::prelude::{Keys, Kind};
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+        use crate::domains::account::nip98::{payload_hash, testing::sign};
 
-                let err = service.authenticate_api_key(vec![1, 2, 3]).await.unwrap_err();
+        use super::*;
 
-                assert!(matches!(
-                    err,
-                    ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
-                ));
+        const NOSTR_KEYS_URL: &str = "https://app.numeraire.tech/v1/auth/nostr-keys";
+        const PAYMENTS_URL: &str = "https://app.numeraire.tech/v1/payments";
+
+        fn session_user(account_id: Uuid) -> User {
+            User {
+                account_id,
+                session_id: Some(Uuid::new_v4()),
+                ..Default::default()
             }
         }
 
-        mod when_key_is_valid {
-            use super::*;
+        fn now() -> u64 {
+            Utc::now().timestamp() as u64
+        }
 
-            #[tokio::test]
-            async fn returns_user_with_api_key_permissions() {
-                let account_id = Uuid::new_v4();
+        fn nostr_identity(subject: &str) -> AuthIdentity {
+            AuthIdentity {
+                id: Uuid::new_v4(),
+                provider: AuthProvider::Nostr,
+                subject: subject.to_string(),
+                created_at: Utc::now(),
+            }
+        }
 
-                let mut store = MockAppStoreBuilder::new();
-                store.api_key.expect_find_by_key_hash().times(1).returning(move |_| {
-                    Ok(Some(ApiKey {
+        #[tokio::test]
+        async fn authenticate_returns_the_account_of_the_linked_key() {
+            let keys = Keys::generate();
+            let pubkey = keys.public_key().to_hex();
+            let account_id = Uuid::new_v4();
+            let body_hash = payload_hash(b"{}");
+            let token = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", PAYMENTS_URL], &["method", "POST"], &["payload", &body_hash]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().times(1).returning(|_, _| Ok(true));
+            let expected_pubkey = pubkey.clone();
+            store
+                .account
+                .expect_find_by_identity()
+                .withf(move |provider, subject| *provider == AuthProvider::Nostr && subject == expected_pubkey)
+                .times(1)
+                .returning(move |provider, subject| {
+                    Ok(Some(account_fixture(
                         account_id,
-                        permissions: vec![Permission::ReadWallet],
-                        ..Default::default()
-                    }))
+                        provider,
+                        subject,
+                        vec![Permission::WriteTransaction],
+                    )))
                 });
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let user = service
+                .authenticate_nostr(&token, "POST", "/v1/payments", Some(body_hash))
+                .await
+                .unwrap();
+
+            assert_eq!(user.account_id, account_id);
+            assert_eq!(user.permissions, vec![Permission::WriteTransaction]);
+            assert_eq!(user.subject, Some(pubkey));
+            assert_eq!(user.session_id, None);
+            assert_eq!(user.api_key_id, None);
+        }
 
-                let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+        #[tokio::test]
+        async fn authenticate_with_an_unlinked_key_returns_invalid_credentials() {
+            let keys = Keys::generate();
+            let token = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", PAYMENTS_URL], &["method", "GET"]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().times(1).returning(|_, _| Ok(true));
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(|_, _| Ok(None));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .authenticate_nostr(&token, "GET", "/v1/payments", None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
 
-                let user = service.authenticate_api_key(vec![1, 2, 3]).await.unwrap();
+        #[tokio::test]
+        async fn authenticate_with_an_event_for_another_request_returns_invalid_credentials() {
+            let keys = Keys::generate();
+            let token = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", PAYMENTS_URL], &["method", "GET"]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().never();
+            store.account.expect_find_by_identity().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .authenticate_nostr(&token, "DELETE", "/v1/payments", None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
 
-                assert_eq!(user.account_id, account_id);
-                assert_eq!(user.permissions, vec![Permission::ReadWallet]);
-            }
+        #[tokio::test]
+        async fn authenticate_with_a_replayed_event_returns_invalid_credentials() {
+            let keys = Keys::generate();
+            let token = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", PAYMENTS_URL], &["method", "GET"]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().times(1).returning(|_, _| Ok(false));
+            store.account.expect_find_by_identity().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .authenticate_nostr(&token, "GET", "/v1/payments", None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authentication(AuthenticationError::InvalidCredentials)
+            ));
+        }
+
+        #[tokio::test]
+        async fn link_inserts_the_signing_key() {
+            let keys = Keys::generate();
+            let pubkey = keys.public_key().to_hex();
+            let account_id = Uuid::new_v4();
+            let event = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", NOSTR_KEYS_URL], &["method", "POST"]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().times(1).returning(|_, _| Ok(true));
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(|_, _| Ok(None));
+            let expected_pubkey = pubkey.clone();
+            store
+                .account
+                .expect_insert_identity()
+                .withf(move |id, provider, subject| {
+                    *id == account_id && *provider == AuthProvider::Nostr && subject == expected_pubkey
+                })
+                .times(1)
+                .returning(|_, _, subject| Ok(nostr_identity(subject)));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let identity = service.link_nostr_key(session_user(account_id), event).await.unwrap();
+
+            assert_eq!(identity.provider, AuthProvider::Nostr);
+            assert_eq!(identity.subject, pubkey);
+        }
+
+        #[tokio::test]
+        async fn link_of_a_key_linked_to_an_account_returns_conflict() {
+            let keys = Keys::generate();
+            let event = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", NOSTR_KEYS_URL], &["method", "POST"]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().times(1).returning(|_, _| Ok(true));
+            store
+                .account
+                .expect_find_by_identity()
+                .times(1)
+                .returning(|provider, subject| Ok(Some(account_fixture(Uuid::new_v4(), provider, subject, vec![]))));
+            store.account.expect_insert_identity().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .link_nostr_key(session_user(Uuid::new_v4()), event)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
+
+        #[tokio::test]
+        async fn link_with_an_event_for_another_url_returns_validation_error() {
+            let keys = Keys::generate();
+            let event = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", PAYMENTS_URL], &["method", "POST"]],
+            );
+
+            let mut store = MockAppStoreBuilder::new();
+            store.nostr_event.expect_insert().never();
+            store.account.expect_insert_identity().never();
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .link_nostr_key(session_user(Uuid::new_v4()), event)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn link_without_a_session_returns_session_required() {
+            let keys = Keys::generate();
+            let event = sign(
+                &keys,
+                Kind::HttpAuth,
+                now(),
+                &[&["u", NOSTR_KEYS_URL], &["method", "POST"]],
+            );
+            let user = User {
+                api_key_id: Some(Uuid::new_v4()),
+                ..Default::default()
+            };
+            let service = service(
+                MockJWTAuthenticator::new(),
+                MockAppStoreBuilder::new(),
+                AuthProvider::Jwt,
+            );
+
+            let err = service.link_nostr_key(user, event).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::SessionRequired)
+            ));
+        }
+
+        #[tokio::test]
+        async fn unlink_of_an_unknown_key_returns_not_found() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .account
+                .expect_delete_identity()
+                .withf(move |id, provider, _| *id == account_id && *provider == AuthProvider::Nostr)
+                .times(1)
+                .returning(|_, _, _| Ok(0));
+            let service = service(MockJWTAuthenticator::new(), store, AuthProvider::Jwt);
+
+            let err = service
+                .unlink_nostr_key(session_user(account_id), Uuid::new_v4())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
         }
     }
 }
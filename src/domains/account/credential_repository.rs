@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+
+use super::{Account, LocalCredential, LocalRegistration};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CredentialRepository: Send + Sync {
+    /// Finds the credential whose username or email matches `login`.
+    async fn find_by_login(&self, login: &str) -> Result<Option<LocalCredential>, DatabaseError>;
+    async fn find_by_account(&self, account_id: Uuid) -> Result<Option<LocalCredential>, DatabaseError>;
+    /// Returns `None` when the invitation is already used or expired, without creating the account.
+    async fn register(&self, registration: LocalRegistration) -> Result<Option<Account>, DatabaseError>;
+    async fn update_password(&self, identity_id: Uuid, password_hash: String) -> Result<(), DatabaseError>;
+    async fn insert_password_reset(
+        &self,
+        account_id: Uuid,
+        token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DatabaseError>;
+    /// Marks an unused, unexpired reset token as used and returns its account.
+    async fn consume_password_reset(&self, token_hash: Vec<u8>) -> Result<Option<Uuid>, DatabaseError>;
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use super::Permission;
 
@@ -8,4 +9,41 @@ pub struct AuthClaims {
     pub iat: usize, // Optional. Issued at (as UTC timestamp)
     pub sub: String,
     pub permissions: Vec<Permission>,
+    /// Session of locally issued tokens, absent from OAuth2 tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Trusted OAuth2 issuer that verified the token, `None` for locally issued tokens
+    #[serde(skip)]
+    pub issuer: Option<TokenIssuer>,
+}
+
+/// OAuth2 issuer of a token, as configured.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenIssuer {
+    /// Namespace of the subjects of the issuer in account identities. Subjects of the unnamed `[oauth2]` issuer are
+    /// stored as is.
+    pub name: Option<String>,
+    pub provisioning: AccountProvisioning,
+}
+
+impl TokenIssuer {
+    /// Subject of the account identity of `sub`, prefixed by the issuer name so subjects of different issuers never
+    /// collide.
+    pub fn identity_subject(&self, sub: &str) -> String {
+        match &self.name {
+            Some(name) => format!("{name}:{sub}"),
+            None => sub.to_string(),
+        }
+    }
+}
+
+/// Whether the first token of an unknown subject creates its account.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountProvisioning {
+    /// Accounts are created on the first request of their subject
+    #[default]
+    Auto,
+    /// Only subjects with an existing account authenticate
+    Existing,
 }
@@ -0,0 +1,27 @@
+use uuid::Uuid;
+
+use super::Permission;
+
+/// Password of a local (`JWT` provider) account, keyed by its auth identity.
+///
+/// The username is the identity subject. The bootstrap admin keeps its password in the config table instead.
+#[derive(Clone, Debug)]
+pub struct LocalCredential {
+    pub identity_id: Uuid,
+    pub account_id: Uuid,
+    pub username: String,
+    pub password_hash: String,
+}
+
+/// Local account created with its identity and credential in one transaction.
+///
+/// When `invitation_id` is set, the invitation is consumed by the same transaction.
+#[derive(Clone, Debug, Default)]
+pub struct LocalRegistration {
+    pub username: String,
+    pub email: Option<String>,
+    pub password_hash: String,
+    pub display_name: Option<String>,
+    pub permissions: Vec<Permission>,
+    pub invitation_id: Option<Uuid>,
+}
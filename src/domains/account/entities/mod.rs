@@ -1,9 +1,19 @@
 mod auth;
+mod credential;
+mod passkey;
+mod session;
+mod totp;
 mod user;
 
-pub use auth::AuthClaims;
+pub use auth::{AccountProvisioning, AuthClaims, TokenIssuer};
+pub use credential::{LocalCredential, LocalRegistration};
+pub use passkey::PasskeyChallenge;
+pub use session::Session;
 pub use swissknife_types::{
     Account, AccountFilter, AccountPreferences, ApiKey, ApiKeyFilter, AuthIdentity, AuthProvider, CreateAccountRequest,
-    Permission,
+    CreateInvitationRequest, Invitation, InvitationFilter, Organization, OrganizationMember, OrganizationRole, Passkey,
+    PasskeyAuthenticationOptions, PasskeyRegistrationOptions, PasskeySignInRequest, PasswordReset, Permission,
+    RegisterPasskeyRequest, RegisterRequest, SignInResponse, TotpEnrollment, TotpRecoveryCodes,
 };
+pub use totp::TotpFactor;
 pub use user::User;
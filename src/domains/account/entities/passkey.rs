@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Pending WebAuthn ceremony.
+///
+/// Registrations are bound to the account registering the passkey, sign-ins are not bound to any account
+/// since the authenticator chooses the passkey. The challenge is consumed by the first response.
+#[derive(Clone, Debug, Default)]
+pub struct PasskeyChallenge {
+    pub id: Uuid,
+    pub challenge: Vec<u8>,
+    pub account_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Sign-in session of a local (`JWT` provider) account.
+///
+/// Access tokens carry the session ID and only authenticate while the session exists. The session is
+/// renewed with its refresh token, of which only the hash is stored, and ends unless renewed before it expires.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    /// Last verification of the second factor, required recently for sensitive operations.
+    pub verified_at: Option<DateTime<Utc>>,
+}
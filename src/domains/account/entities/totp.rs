@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// TOTP second factor of a local account.
+///
+/// The factor is pending until its first code is confirmed, after which sign-in and sensitive operations require
+/// a code or one of its recovery codes.
+#[derive(Clone, Debug, Default)]
+pub struct TotpFactor {
+    pub account_id: Uuid,
+    pub secret: Vec<u8>,
+    /// Time step of the last accepted code. Codes of this step or earlier are rejected.
+    pub last_used_step: i64,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+impl TotpFactor {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
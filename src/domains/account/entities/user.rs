@@ -2,16 +2,25 @@ use uuid::Uuid;
 
 use crate::application::errors::AuthorizationError;
 
-use super::Permission;
+use super::{OrganizationMember, Permission};
 
 /// Runtime principal produced by authentication for one request.
 ///
 /// `Account` is the persisted owner aggregate. `User` is the effective actor:
-/// account ID and request-time permissions.
+/// account ID, request-time permissions, for wallet-scoped API keys, the
+/// wallets it may act on and, for local access tokens, their sign-in session.
+/// When a member acts for an organization, `account_id` is the organization's
+/// and `membership` holds the member's own account and role. `api_key_id` and
+/// `subject` identify the API key or the token subject that authenticated.
 #[derive(Clone, Debug, Default)]
 pub struct User {
     pub account_id: Uuid,
     pub permissions: Vec<Permission>,
+    pub wallet_ids: Option<Vec<Uuid>>,
+    pub session_id: Option<Uuid>,
+    pub membership: Option<OrganizationMember>,
+    pub api_key_id: Option<Uuid>,
+    pub subject: Option<String>,
 }
 
 impl User {
@@ -26,11 +35,68 @@ impl User {
 
         Ok(())
     }
+
+    /// Account of the authenticated principal itself, which owns its sessions and second factors.
+    pub fn principal_account_id(&self) -> Uuid {
+        self.membership
+            .as_ref()
+            .map_or(self.account_id, |membership| membership.account_id)
+    }
+
+    /// Rejects members whose organization role lacks `permission`. Principals acting for their own account
+    /// hold every permission over it.
+    pub fn check_role_permission(&self, permission: Permission) -> Result<(), AuthorizationError> {
+        if self
+            .membership
+            .as_ref()
+            .is_some_and(|membership| !membership.role.permissions().contains(&permission))
+        {
+            return Err(AuthorizationError::MissingPermission(permission));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects wallets outside the scope of a wallet-scoped principal.
+    pub fn check_wallet(&self, wallet_id: Uuid) -> Result<(), AuthorizationError> {
+        if self.wallet_ids.as_ref().is_some_and(|ids| !ids.contains(&wallet_id)) {
+            return Err(AuthorizationError::WalletNotAllowed(wallet_id));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects wallet-scoped principals from account-wide operations.
+    pub fn check_account_scope(&self) -> Result<(), AuthorizationError> {
+        if self.wallet_ids.is_some() {
+            return Err(AuthorizationError::WalletScoped);
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::Utc;
+
     use super::*;
+    use crate::domains::account::OrganizationRole;
+
+    fn member(role: OrganizationRole) -> User {
+        let organization_id = Uuid::new_v4();
+        User {
+            account_id: organization_id,
+            membership: Some(OrganizationMember {
+                organization_id,
+                account_id: Uuid::new_v4(),
+                role,
+                created_at: Utc::now(),
+                updated_at: None,
+            }),
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn has_permission_returns_true_only_for_assigned_permissions() {
@@ -67,4 +133,69 @@ mod tests {
             AuthorizationError::MissingPermission(Permission::WriteWallet)
         ));
     }
+
+    #[test]
+    fn check_wallet_allows_any_wallet_without_a_scope() {
+        assert!(User::default().check_wallet(Uuid::new_v4()).is_ok());
+        assert!(User::default().check_account_scope().is_ok());
+    }
+
+    #[test]
+    fn check_wallet_rejects_wallets_outside_the_scope() {
+        let wallet_id = Uuid::new_v4();
+        let user = User {
+            wallet_ids: Some(vec![wallet_id]),
+            ..Default::default()
+        };
+
+        assert!(user.check_wallet(wallet_id).is_ok());
+        assert!(matches!(
+            user.check_wallet(Uuid::new_v4()),
+            Err(AuthorizationError::WalletNotAllowed(_))
+        ));
+        assert!(matches!(
+            user.check_account_scope(),
+            Err(AuthorizationError::WalletScoped)
+        ));
+    }
+
+    #[test]
+    fn principal_account_id_is_the_member_account_in_an_organization() {
+        let user = User {
+            account_id: Uuid::new_v4(),
+            ..Default::default()
+        };
+        assert_eq!(user.principal_account_id(), user.account_id);
+
+        let user = member(OrganizationRole::Viewer);
+        let membership = user.membership.as_ref().unwrap();
+        assert_eq!(user.principal_account_id(), membership.account_id);
+        assert_ne!(user.principal_account_id(), user.account_id);
+    }
+
+    #[test]
+    fn check_role_permission_follows_the_organization_role() {
+        assert!(User::default()
+            .check_role_permission(Permission::WriteTransaction)
+            .is_ok());
+
+        let viewer = member(OrganizationRole::Viewer);
+        assert!(viewer.check_role_permission(Permission::ReadTransaction).is_ok());
+        assert!(matches!(
+            viewer.check_role_permission(Permission::WriteTransaction),
+            Err(AuthorizationError::MissingPermission(Permission::WriteTransaction))
+        ));
+
+        let accountant = member(OrganizationRole::Accountant);
+        assert!(accountant.check_role_permission(Permission::WriteTransaction).is_ok());
+        assert!(accountant.check_role_permission(Permission::WriteWallet).is_err());
+
+        let admin = member(OrganizationRole::Admin);
+        assert!(admin.check_role_permission(Permission::WriteApiKey).is_ok());
+        assert!(admin.check_role_permission(Permission::WriteAccount).is_err());
+
+        let owner = member(OrganizationRole::Owner);
+        assert!(owner.check_role_permission(Permission::WriteAccount).is_ok());
+        assert!(owner.check_role_permission(Permission::WriteLnNode).is_err());
+    }
 }
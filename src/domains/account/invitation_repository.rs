@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+
+use crate::application::errors::DatabaseError;
+
+use super::{Invitation, InvitationFilter};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    /// Finds an unused, unexpired invitation.
+    async fn find_by_token_hash(&self, token_hash: Vec<u8>) -> Result<Option<Invitation>, DatabaseError>;
+    async fn find_many(&self, filter: InvitationFilter) -> Result<Vec<Invitation>, DatabaseError>;
+    async fn insert(&self, invitation: Invitation) -> Result<Invitation, DatabaseError>;
+    async fn delete_many(&self, filter: InvitationFilter) -> Result<u64, DatabaseError>;
+}
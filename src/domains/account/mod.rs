@@ -8,7 +8,19 @@ mod api_key_service;
 mod auth_handler;
 mod auth_middleware;
 mod auth_service;
+mod credential_repository;
 mod entities;
+mod invitation_repository;
+mod nip98;
+mod nostr_event_repository;
+mod organization_handler;
+mod organization_repository;
+mod organization_service;
+mod passkey_repository;
+mod session_repository;
+mod totp;
+mod totp_repository;
+mod webauthn;
 
 pub use account_handler::*;
 pub use account_repository::*;
@@ -18,5 +30,15 @@ pub use api_key_handler::*;
 pub use api_key_repository::*;
 pub use api_key_service::*;
 pub use auth_handler::*;
+pub use auth_middleware::*;
 pub use auth_service::*;
+pub use credential_repository::*;
 pub use entities::*;
+pub use invitation_repository::*;
+pub use nostr_event_repository::*;
+pub use organization_handler::*;
+pub use organization_repository::*;
+pub use organization_service::*;
+pub use passkey_repository::*;
+pub use session_repository::*;
+pub use totp_repository::*;
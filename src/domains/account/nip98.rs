@@ -0,0 +1,289 @@
+//! NIP-98 HTTP authentication: verification of the kind-27235 events sent as `Authorization: Nostr <base64 event>`.
+//!
+//! An event authorizes a single request: it is signed for its absolute URL and method, shortly before it is sent, and
+//! commits to the hash of the request body when there is one.
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use nostr_sdk::prelude::{Event, Kind};
+use serde_bolt::bitcoin::hashes::{sha256, Hash};
+
+use crate::application::errors::DataError;
+
+/// Scheme of the `Authorization` header.
+pub const SCHEME: &str = "Nostr";
+/// Largest accepted header value, far above the size of an HTTP auth event.
+const MAX_TOKEN_LENGTH: usize = 64 * 1024;
+/// How long an event remains valid after being signed.
+const MAX_AGE_SECONDS: u64 = 60;
+/// Tolerated clock drift of clients ahead of the server.
+const MAX_CLOCK_DRIFT_SECONDS: u64 = 30;
+
+/// Request an event must be signed for.
+pub struct SignedRequest<'a> {
+    /// Absolute URL, including the query
+    pub url: &'a str,
+    pub method: &'a str,
+    /// Hex SHA-256 hash of the body, if not empty
+    pub payload_hash: Option<&'a str>,
+}
+
+/// Event that authorizes the request it was verified against.
+#[derive(Debug)]
+pub struct VerifiedEvent {
+    /// Hex id, which must be remembered until the event expires so that a captured header cannot be replayed
+    pub id: String,
+    /// Hex public key that signed the event
+    pub pubkey: String,
+    /// UNIX seconds after which the event is no longer accepted
+    pub expires_at: u64,
+}
+
+/// Hex SHA-256 hash of a request body, as committed to by the `payload` tag.
+pub fn payload_hash(body: &[u8]) -> String {
+    hex::encode(sha256::Hash::hash(body).to_byte_array())
+}
+
+/// Verifies the base64 event of an `Authorization: Nostr` header for the request at `now` (UNIX seconds). Callers
+/// accept each event once by remembering its id.
+pub fn verify(token: &str, request: &SignedRequest, now: u64) -> Result<VerifiedEvent, DataError> {
+    if token.len() > MAX_TOKEN_LENGTH {
+        return Err(DataError::Malformed("NIP-98 event is too large.".to_string()));
+    }
+
+    let json = BASE64_STANDARD
+        .decode(token.trim())
+        .map_err(|_| DataError::Malformed("NIP-98 event is not valid base64.".to_string()))?;
+    let event = Event::from_json(json).map_err(|e| DataError::Malformed(format!("Invalid NIP-98 event: {e}")))?;
+
+    if event.kind != Kind::HttpAuth {
+        return Err(DataError::Validation(format!(
+            "NIP-98 event must be of kind {}.",
+            Kind::HttpAuth.as_u16()
+        )));
+    }
+
+    event
+        .verify()
+        .map_err(|e| DataError::Validation(format!("Invalid NIP-98 event: {e}")))?;
+
+    let created_at = event.created_at.as_secs();
+    if created_at.saturating_add(MAX_AGE_SECONDS) < now || created_at > now.saturating_add(MAX_CLOCK_DRIFT_SECONDS) {
+        return Err(DataError::Validation(
+            "NIP-98 event is expired or not yet valid.".to_string(),
+        ));
+    }
+
+    if tag_value(&event, "u") != Some(request.url) {
+        return Err(DataError::Validation(
+            "NIP-98 event is signed for another URL.".to_string(),
+        ));
+    }
+
+    if !tag_value(&event, "method").is_some_and(|method| method.eq_ignore_ascii_case(request.method)) {
+        return Err(DataError::Validation(
+            "NIP-98 event is signed for another method.".to_string(),
+        ));
+    }
+
+    match (tag_value(&event, "payload"), request.payload_hash) {
+        (Some(payload), Some(hash)) if payload.eq_ignore_ascii_case(hash) => {}
+        (None, None) => {}
+        // Events may commit to the empty body of requests without one.
+        (Some(payload), None) if payload.eq_ignore_ascii_case(&payload_hash(&[])) => {}
+        (None, Some(_)) => {
+            return Err(DataError::Validation(
+                "NIP-98 event must have a payload tag for requests with a body.".to_string(),
+            ))
+        }
+        (Some(_), _) => {
+            return Err(DataError::Validation(
+                "NIP-98 event is signed for another payload.".to_string(),
+            ))
+        }
+    }
+
+    // Events are accepted from `MAX_CLOCK_DRIFT_SECONDS` before their creation until `MAX_AGE_SECONDS` after it.
+    Ok(VerifiedEvent {
+        id: event.id.to_hex(),
+        pubkey: event.pubkey.to_hex(),
+        expires_at: created_at.saturating_add(MAX_AGE_SECONDS),
+    })
+}
+
+fn tag_value<'a>(event: &'a Event, name: &str) -> Option<&'a str> {
+    event.tags.iter().find_map(|tag| match tag.as_slice() {
+        [tag_name, value, ..] if tag_name == name => Some(value.as_str()),
+        _ => None,
+    })
+}
+
+/// Signing of NIP-98 events for tests.
+#[cfg(test)]
+pub mod testing {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use nostr_sdk::prelude::{EventBuilder, FinalizeEvent, Keys, Kind, Tag, Timestamp};
+
+    /// Base64 event of `kind` signed by `keys` at `created_at` (UNIX seconds) with the given tags.
+    pub fn sign(keys: &Keys, kind: Kind, created_at: u64, tags: &[&[&str]]) -> String {
+        let tags = tags.iter().map(|tag| Tag::parse(tag.iter().copied()).unwrap());
+        let event = EventBuilder::new(kind, "")
+            .tags(tags)
+            .custom_created_at(Timestamp::from_secs(created_at))
+            .finalize(keys)
+            .unwrap();
+
+        BASE64_STANDARD.encode(event.as_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr_sdk::prelude::Keys;
+
+    use super::{testing::sign, *};
+
+    const URL: &str = "https://app.numeraire.tech/v1/payments?limit=10";
+    const NOW: u64 = 1_760_000_000;
+
+    fn token(keys: &Keys, created_at: u64, tags: &[&[&str]]) -> String {
+        sign(keys, Kind::HttpAuth, created_at, tags)
+    }
+
+    fn request(payload_hash: Option<&str>) -> SignedRequest<'_> {
+        SignedRequest {
+            url: URL,
+            method: "POST",
+            payload_hash,
+        }
+    }
+
+    #[test]
+    fn returns_the_public_key_of_a_valid_event() {
+        let keys = Keys::generate();
+        let hash = payload_hash(b"{\"amount_msat\":1000}");
+        let token = token(&keys, NOW - 5, &[&["u", URL], &["method", "post"], &["payload", &hash]]);
+
+        let event = verify(&token, &request(Some(&hash)), NOW).unwrap();
+
+        assert_eq!(event.pubkey, keys.public_key().to_hex());
+    }
+
+    #[test]
+    fn accepts_requests_without_body_or_payload_tag() {
+        let keys = Keys::generate();
+        let token = token(&keys, NOW, &[&["u", URL], &["method", "POST"]]);
+
+        assert!(verify(&token, &request(None), NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_tampered_events() {
+        let keys = Keys::generate();
+        let token = token(&keys, NOW, &[&["u", URL], &["method", "POST"]]);
+        let json = String::from_utf8(BASE64_STANDARD.decode(token).unwrap()).unwrap();
+        let tampered = BASE64_STANDARD.encode(json.replace("limit=10", "limit=11"));
+
+        let request = SignedRequest {
+            url: "https://app.numeraire.tech/v1/payments?limit=11",
+            method: "POST",
+            payload_hash: None,
+        };
+        assert!(matches!(
+            verify(&tampered, &request, NOW),
+            Err(DataError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_other_kinds() {
+        let keys = Keys::generate();
+        let token = sign(&keys, Kind::TextNote, NOW, &[&["u", URL], &["method", "POST"]]);
+
+        assert!(matches!(
+            verify(&token, &request(None), NOW),
+            Err(DataError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_events_outside_the_time_window() {
+        let keys = Keys::generate();
+        let tags: &[&[&str]] = &[&["u", URL], &["method", "POST"]];
+
+        assert!(verify(&token(&keys, NOW - 61, tags), &request(None), NOW).is_err());
+        assert!(verify(&token(&keys, NOW + 31, tags), &request(None), NOW).is_err());
+        assert!(verify(&token(&keys, NOW - 60, tags), &request(None), NOW).is_ok());
+        assert!(verify(&token(&keys, NOW + 30, tags), &request(None), NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_urls_and_methods() {
+        let keys = Keys::generate();
+
+        let other_url = token(
+            &keys,
+            NOW,
+            &[&["u", "https://app.numeraire.tech/v1/payments"], &["method", "POST"]],
+        );
+        assert!(verify(&other_url, &request(None), NOW).is_err());
+
+        let other_method = token(&keys, NOW, &[&["u", URL], &["method", "GET"]]);
+        assert!(verify(&other_method, &request(None), NOW).is_err());
+
+        let missing_method = token(&keys, NOW, &[&["u", URL]]);
+        assert!(verify(&missing_method, &request(None), NOW).is_err());
+    }
+
+    #[test]
+    fn checks_the_payload_hash() {
+        let keys = Keys::generate();
+        let hash = payload_hash(b"{}");
+        let other_hash = payload_hash(b"{\"amount_msat\":1000}");
+
+        let missing = token(&keys, NOW, &[&["u", URL], &["method", "POST"]]);
+        assert!(verify(&missing, &request(Some(&hash)), NOW).is_err());
+
+        let other = token(
+            &keys,
+            NOW,
+            &[&["u", URL], &["method", "POST"], &["payload", &other_hash]],
+        );
+        assert!(verify(&other, &request(Some(&hash)), NOW).is_err());
+
+        let empty = token(
+            &keys,
+            NOW,
+            &[&["u", URL], &["method", "POST"], &["payload", &payload_hash(&[])]],
+        );
+        assert!(verify(&empty, &request(None), NOW).is_ok());
+        assert!(verify(&other, &request(None), NOW).is_err());
+    }
+
+    #[test]
+    fn returns_the_id_and_expiry_of_the_event() {
+        let keys = Keys::generate();
+        let tags: &[&[&str]] = &[&["u", URL], &["method", "POST"]];
+
+        let first = verify(&token(&keys, NOW - 5, tags), &request(None), NOW).unwrap();
+        let second = verify(&token(&keys, NOW, tags), &request(None), NOW).unwrap();
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.expires_at, NOW + 55);
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        assert!(matches!(
+            verify("not base64!", &request(None), NOW),
+            Err(DataError::Malformed(_))
+        ));
+        assert!(matches!(
+            verify(&BASE64_STANDARD.encode("{}"), &request(None), NOW),
+            Err(DataError::Malformed(_))
+        ));
+        assert!(matches!(
+            verify(&"A".repeat(MAX_TOKEN_LENGTH + 4), &request(None), NOW),
+            Err(DataError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::application::errors::DatabaseError;
+
+/// Ids of the NIP-98 events already accepted, shared by every instance using the database so that a captured header
+/// cannot be replayed against another one.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait NostrEventRepository: Send + Sync {
+    /// Remembers the event until `expires_at` and forgets the expired ones. Returns `false` if it was already seen.
+    async fn insert(&self, id: String, expires_at: DateTime<Utc>) -> Result<bool, DatabaseError>;
+}
@@ -0,0 +1,399 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{delete, get, post, put},
+    Router,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use swissknife_types::{
+    AddOrganizationMemberRequest, CreateOrganizationRequest, ErrorResponse, UpdateOrganizationMemberRequest,
+    UpdateOrganizationRequest,
+};
+
+use crate::{
+    application::{
+        composition::AppServices,
+        docs::{
+            BAD_REQUEST_EXAMPLE, CONFLICT_EXAMPLE, FORBIDDEN_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE,
+            UNAUTHORIZED_EXAMPLE, UNPROCESSABLE_EXAMPLE,
+        },
+        errors::ApplicationError,
+    },
+    infra::axum::{Json, Path},
+};
+
+use super::{Organization, OrganizationMember, OrganizationRole, User};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_organization,
+        list_organizations,
+        get_organization,
+        update_organization,
+        delete_organization,
+        list_organization_members,
+        add_organization_member,
+        update_organization_member,
+        remove_organization_member,
+    ),
+    components(schemas(
+        Organization,
+        OrganizationMember,
+        OrganizationRole,
+        CreateOrganizationRequest,
+        UpdateOrganizationRequest,
+        AddOrganizationMemberRequest,
+        UpdateOrganizationMemberRequest
+    )),
+    tags(
+        (name = "Organizations", description = "Organizations own wallets shared by their members. Members act for an organization by sending its ID in the `organization-id` header, with the permissions of their role: `viewer` reads, `accountant` also receives and sends payments, `admin` also manages wallets, Lightning addresses, API keys and members, and `owner` also manages the organization and its owners.")
+    )
+)]
+pub struct OrganizationHandler;
+pub const CONTEXT_PATH: &str = "/v1/organizations";
+
+pub fn organization_router() -> Router<Arc<AppServices>> {
+    Router::new()
+        .route("/", post(create_organization))
+        .route("/", get(list_organizations))
+        .route("/{id}", get(get_organization))
+        .route("/{id}", put(update_organization))
+        .route("/{id}", delete(delete_organization))
+        .route("/{id}/members", get(list_organization_members))
+        .route("/{id}/members", post(add_organization_member))
+        .route("/{id}/members/{account_id}", put(update_organization_member))
+        .route("/{id}/members/{account_id}", delete(remove_organization_member))
+}
+
+/// Create an organization
+///
+/// Creates an organization with the caller as its first owner.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 200, description = "Organization Created", body = Organization),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn create_organization(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<CreateOrganizationRequest>,
+) -> Result<Json<Organization>, ApplicationError> {
+    let organization = services.organization.create(user, payload).await?;
+    Ok(Json(organization))
+}
+
+/// List organizations
+///
+/// Returns the organizations the caller is a member of, with its role in each.
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<Organization>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_organizations(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<Vec<Organization>>, ApplicationError> {
+    let organizations = services.organization.list(user).await?;
+    Ok(Json(organizations))
+}
+
+/// Find an organization
+///
+/// Returns the organization by its ID, with the role of the caller.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Found", body = Organization),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn get_organization(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Organization>, ApplicationError> {
+    let organization = services.organization.get(user, id).await?;
+    Ok(Json(organization))
+}
+
+/// Update an organization
+///
+/// Renames the organization. Requires the `owner` role.
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    request_body = UpdateOrganizationRequest,
+    responses(
+        (status = 200, description = "Organization Updated", body = Organization),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn update_organization(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateOrganizationRequest>,
+) -> Result<Json<Organization>, ApplicationError> {
+    let organization = services.organization.update(user, id, payload.name).await?;
+    Ok(Json(organization))
+}
+
+/// Delete an organization
+///
+/// Deletes the organization with its wallets and all data related to them. Requires the `owner` role. Returns an
+/// empty body.
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_organization(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    services.auth.check_step_up(&user).await?;
+
+    services.organization.delete(user, id).await?;
+    Ok(())
+}
+
+/// List organization members
+#[utoipa::path(
+    get,
+    path = "/{id}/members",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<OrganizationMember>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_organization_members(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationMember>>, ApplicationError> {
+    let members = services.organization.list_members(user, id).await?;
+    Ok(Json(members))
+}
+
+/// Add an organization member
+///
+/// Adds an account to the organization. Requires the `admin` role, or the `owner` role to add owners.
+#[utoipa::path(
+    post,
+    path = "/{id}/members",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    request_body = AddOrganizationMemberRequest,
+    responses(
+        (status = 200, description = "Member Added", body = OrganizationMember),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn add_organization_member(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AddOrganizationMemberRequest>,
+) -> Result<Json<OrganizationMember>, ApplicationError> {
+    services.auth.check_step_up(&user).await?;
+
+    let member = services.organization.add_member(user, id, payload).await?;
+    Ok(Json(member))
+}
+
+/// Update an organization member
+///
+/// Changes the role of a member. Requires the `admin` role, or the `owner` role when owners are involved. The last
+/// owner cannot be demoted.
+#[utoipa::path(
+    put,
+    path = "/{id}/members/{account_id}",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    request_body = UpdateOrganizationMemberRequest,
+    responses(
+        (status = 200, description = "Member Updated", body = OrganizationMember),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn update_organization_member(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path((id, account_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateOrganizationMemberRequest>,
+) -> Result<Json<OrganizationMember>, ApplicationError> {
+    services.auth.check_step_up(&user).await?;
+
+    let member = services
+        .organization
+        .update_member(user, id, account_id, payload.role)
+        .await?;
+    Ok(Json(member))
+}
+
+/// Remove an organization member
+///
+/// Removes a member from the organization. Members can leave on their own, removing others requires the `admin`
+/// role, or the `owner` role to remove owners. The last owner cannot leave. Returns an empty body.
+#[utoipa::path(
+    delete,
+    path = "/{id}/members/{account_id}",
+    tag = "Organizations",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Member Removed"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn remove_organization_member(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path((id, account_id)): Path<(Uuid, Uuid)>,
+) -> Result<(), ApplicationError> {
+    services.organization.remove_member(user, id, account_id).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::application::{composition::MockAppServicesBuilder, errors::AuthorizationError};
+
+    use super::*;
+
+    mod add_organization_member {
+        use super::*;
+
+        fn request() -> AddOrganizationMemberRequest {
+            AddOrganizationMemberRequest {
+                account_id: Uuid::new_v4(),
+                role: OrganizationRole::Viewer,
+            }
+        }
+
+        #[tokio::test]
+        async fn delegates_to_the_service() {
+            let id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_check_step_up().times(1).returning(|_| Ok(()));
+            builder
+                .organization
+                .expect_add_member()
+                .withf(move |_, organization_id, request| {
+                    *organization_id == id && request.role == OrganizationRole::Viewer
+                })
+                .times(1)
+                .returning(|_, organization_id, request| {
+                    Ok(OrganizationMember {
+                        organization_id,
+                        account_id: request.account_id,
+                        role: request.role,
+                        created_at: Utc::now(),
+                        updated_at: None,
+                    })
+                });
+
+            let Json(member) = add_organization_member(
+                State(Arc::new(builder.build())),
+                User::default(),
+                Path(id),
+                Json(request()),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(member.organization_id, id);
+        }
+
+        #[tokio::test]
+        async fn without_a_required_step_up_is_forbidden() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_check_step_up()
+                .times(1)
+                .returning(|_| Err(AuthorizationError::StepUpRequired.into()));
+            builder.organization.expect_add_member().never();
+
+            let result = add_organization_member(
+                State(Arc::new(builder.build())),
+                User::default(),
+                Path(Uuid::new_v4()),
+                Json(request()),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+
+use super::{Organization, OrganizationMember, OrganizationRole};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait OrganizationRepository: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<Organization>, DatabaseError>;
+    /// Organizations `account_id` is a member of, with its role in each.
+    async fn find_many(&self, account_id: Uuid) -> Result<Vec<Organization>, DatabaseError>;
+    /// Creates the organization with its own account and `owner_id` as first owner.
+    async fn insert(&self, name: String, owner_id: Uuid) -> Result<Organization, DatabaseError>;
+    async fn update(&self, id: Uuid, name: String) -> Result<Option<Organization>, DatabaseError>;
+    async fn find_member(
+        &self,
+        organization_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<OrganizationMember>, DatabaseError>;
+    async fn find_members(&self, organization_id: Uuid) -> Result<Vec<OrganizationMember>, DatabaseError>;
+    async fn insert_member(&self, member: OrganizationMember) -> Result<OrganizationMember, DatabaseError>;
+    /// Changes the role of a member. Returns `None` when the member does not exist or is the last owner losing
+    /// the owner role.
+    async fn update_member(
+        &self,
+        organization_id: Uuid,
+        account_id: Uuid,
+        role: OrganizationRole,
+    ) -> Result<Option<OrganizationMember>, DatabaseError>;
+    /// Removes a member, unless it is the last owner.
+    async fn delete_member(&self, organization_id: Uuid, account_id: Uuid) -> Result<u64, DatabaseError>;
+}
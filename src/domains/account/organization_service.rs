@@ -0,0 +1,683 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::{debug, info, trace};
+use uuid::Uuid;
+
+use swissknife_types::{AddOrganizationMemberRequest, CreateOrganizationRequest};
+
+use crate::{
+    application::{
+        composition::AppStore,
+        errors::{ApplicationError, AuthorizationError, DataError},
+    },
+    domains::audit::{record_created, record_deleted, record_updated},
+};
+
+use super::{AccountFilter, Organization, OrganizationMember, OrganizationRole, OrganizationUseCases, User};
+
+const MAX_ORGANIZATION_NAME_LENGTH: usize = 255;
+
+pub struct OrganizationService {
+    store: AppStore,
+}
+
+impl OrganizationService {
+    pub fn new(store: AppStore) -> Self {
+        OrganizationService { store }
+    }
+
+    /// Membership of the caller's own account, which members act with on their organizations.
+    async fn caller_membership(&self, user: &User, id: Uuid) -> Result<OrganizationMember, ApplicationError> {
+        user.check_account_scope()?;
+
+        let member = self
+            .store
+            .organization
+            .find_member(id, user.principal_account_id())
+            .await?
+            .ok_or(AuthorizationError::NotMember(id))?;
+
+        Ok(member)
+    }
+
+    async fn find_member(&self, id: Uuid, account_id: Uuid) -> Result<OrganizationMember, ApplicationError> {
+        let member = self
+            .store
+            .organization
+            .find_member(id, account_id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Organization member not found.".to_string()))?;
+
+        Ok(member)
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, DataError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_ORGANIZATION_NAME_LENGTH {
+        return Err(DataError::Validation(format!(
+            "Organization name must be between 1 and {MAX_ORGANIZATION_NAME_LENGTH} characters long."
+        )));
+    }
+
+    Ok(name.to_string())
+}
+
+fn check_role(member: &OrganizationMember, role: OrganizationRole) -> Result<(), AuthorizationError> {
+    if member.role < role {
+        return Err(AuthorizationError::RoleRequired(role));
+    }
+
+    Ok(())
+}
+
+/// Admins manage members, while owners are granted and managed by owners only.
+fn role_to_manage(roles: &[OrganizationRole]) -> OrganizationRole {
+    if roles.contains(&OrganizationRole::Owner) {
+        OrganizationRole::Owner
+    } else {
+        OrganizationRole::Admin
+    }
+}
+
+#[async_trait]
+impl OrganizationUseCases for OrganizationService {
+    async fn create(&self, user: User, request: CreateOrganizationRequest) -> Result<Organization, ApplicationError> {
+        debug!(account_id = %user.principal_account_id(), "Creating organization");
+
+        user.check_account_scope()?;
+        let name = validate_name(&request.name)?;
+
+        let owner_id = user.principal_account_id();
+        if self.store.organization.find(owner_id).await?.is_some() {
+            return Err(DataError::Validation("Organizations cannot own organizations.".to_string()).into());
+        }
+
+        let organization = self.store.organization.insert(name, owner_id).await?;
+
+        info!(id = %organization.id, %owner_id, "Organization created successfully");
+        Ok(organization)
+    }
+
+    async fn list(&self, user: User) -> Result<Vec<Organization>, ApplicationError> {
+        trace!(account_id = %user.principal_account_id(), "Listing organizations");
+
+        let organizations = self.store.organization.find_many(user.principal_account_id()).await?;
+
+        debug!(account_id = %user.principal_account_id(), "Organizations listed successfully");
+        Ok(organizations)
+    }
+
+    async fn get(&self, user: User, id: Uuid) -> Result<Organization, ApplicationError> {
+        trace!(%id, "Fetching organization");
+
+        let member = self.caller_membership(&user, id).await?;
+        let mut organization = self
+            .store
+            .organization
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Organization not found.".to_string()))?;
+        organization.role = Some(member.role);
+
+        debug!(%id, "Organization fetched successfully");
+        Ok(organization)
+    }
+
+    async fn update(&self, user: User, id: Uuid, name: String) -> Result<Organization, ApplicationError> {
+        debug!(%id, "Updating organization");
+
+        let member = self.caller_membership(&user, id).await?;
+        check_role(&member, OrganizationRole::Owner)?;
+        let name = validate_name(&name)?;
+
+        let mut organization = self
+            .store
+            .organization
+            .update(id, name)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Organization not found.".to_string()))?;
+        organization.role = Some(member.role);
+
+        info!(%id, "Organization updated successfully");
+        Ok(organization)
+    }
+
+    async fn delete(&self, user: User, id: Uuid) -> Result<(), ApplicationError> {
+        debug!(%id, "Deleting organization");
+
+        let member = self.caller_membership(&user, id).await?;
+        check_role(&member, OrganizationRole::Owner)?;
+
+        // Deleting the account cascades to the organization and its members.
+        let n_deleted = self
+            .store
+            .account
+            .delete_many(AccountFilter {
+                ids: Some(vec![id]),
+                ..Default::default()
+            })
+            .await?;
+
+        if n_deleted == 0 {
+            return Err(DataError::NotFound("Organization not found.".to_string()).into());
+        }
+
+        info!(%id, "Organization deleted successfully");
+        Ok(())
+    }
+
+    async fn list_members(&self, user: User, id: Uuid) -> Result<Vec<OrganizationMember>, ApplicationError> {
+        trace!(%id, "Listing organization members");
+
+        self.caller_membership(&user, id).await?;
+        let members = self.store.organization.find_members(id).await?;
+
+        debug!(%id, "Organization members listed successfully");
+        Ok(members)
+    }
+
+    async fn add_member(
+        &self,
+        user: User,
+        id: Uuid,
+        request: AddOrganizationMemberRequest,
+    ) -> Result<OrganizationMember, ApplicationError> {
+        debug!(%id, account_id = %request.account_id, role = %request.role, "Adding organization member");
+
+        let caller = self.caller_membership(&user, id).await?;
+        check_role(&caller, role_to_manage(&[request.role]))?;
+
+        if self.store.account.find(request.account_id).await?.is_none() {
+            return Err(DataError::NotFound("Account not found.".to_string()).into());
+        }
+        if self.store.organization.find(request.account_id).await?.is_some() {
+            return Err(DataError::Validation("Organizations cannot be members of organizations.".to_string()).into());
+        }
+        if self
+            .store
+            .organization
+            .find_member(id, request.account_id)
+            .await?
+            .is_some()
+        {
+            return Err(DataError::Conflict("Account is already a member of the organization.".to_string()).into());
+        }
+
+        let member = self
+            .store
+            .organization
+            .insert_member(OrganizationMember {
+                organization_id: id,
+                account_id: request.account_id,
+                role: request.role,
+                created_at: Utc::now(),
+                updated_at: None,
+            })
+            .await?;
+        record_created(&member);
+
+        info!(%id, account_id = %member.account_id, "Organization member added successfully");
+        Ok(member)
+    }
+
+    async fn update_member(
+        &self,
+        user: User,
+        id: Uuid,
+        account_id: Uuid,
+        role: OrganizationRole,
+    ) -> Result<OrganizationMember, ApplicationError> {
+        debug!(%id, %account_id, %role, "Updating organization member");
+
+        let caller = self.caller_membership(&user, id).await?;
+        let member = self.find_member(id, account_id).await?;
+        check_role(&caller, role_to_manage(&[member.role, role]))?;
+
+        let before = member;
+        let member = self
+            .store
+            .organization
+            .update_member(id, account_id, role)
+            .await?
+            .ok_or_else(|| DataError::Validation("An organization must keep at least one owner.".to_string()))?;
+        record_updated(&before, &member);
+
+        info!(%id, %account_id, %role, "Organization member updated successfully");
+        Ok(member)
+    }
+
+    async fn remove_member(&self, user: User, id: Uuid, account_id: Uuid) -> Result<(), ApplicationError> {
+        debug!(%id, %account_id, "Removing organization member");
+
+        let caller = self.caller_membership(&user, id).await?;
+        let member = self.find_member(id, account_id).await?;
+        if caller.account_id != account_id {
+            check_role(&caller, role_to_manage(&[member.role]))?;
+        }
+
+        let n_deleted = self.store.organization.delete_member(id, account_id).await?;
+        if n_deleted == 0 {
+            return Err(DataError::Validation("An organization must keep at least one owner.".to_string()).into());
+        }
+        record_deleted(&member);
+
+        info!(%id, %account_id, "Organization member removed successfully");
+        Ok(())
+    }
+
+    async fn act_for(&self, user: User, id: Uuid) -> Result<User, ApplicationError> {
+        trace!(%id, account_id = %user.account_id, "Resolving organization context");
+
+        // Wallet-scoped credentials are bound to the wallets of their own account.
+        user.check_account_scope()?;
+
+        // API keys of an organization already act for it, within the role of their member.
+        if user.membership.is_some() {
+            return if user.account_id == id {
+                Ok(user)
+            } else {
+                Err(AuthorizationError::NotMember(id).into())
+            };
+        }
+
+        let member = self
+            .store
+            .organization
+            .find_member(id, user.principal_account_id())
+            .await?
+            .ok_or(AuthorizationError::NotMember(id))?;
+
+        trace!(%id, account_id = %member.account_id, role = %member.role, "Organization context resolved");
+        Ok(User {
+            account_id: id,
+            permissions: user.permissions,
+            wallet_ids: None,
+            session_id: user.session_id,
+            membership: Some(member),
+            api_key_id: user.api_key_id,
+            subject: user.subject,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{application::composition::MockAppStoreBuilder, domains::account::Account};
+
+    use super::*;
+
+    fn user(account_id: Uuid) -> User {
+        User {
+            account_id,
+            ..Default::default()
+        }
+    }
+
+    fn member(organization_id: Uuid, account_id: Uuid, role: OrganizationRole) -> OrganizationMember {
+        OrganizationMember {
+            organization_id,
+            account_id,
+            role,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    /// Store where `caller_id` is a member of `id` with `role`.
+    fn store_with_caller(id: Uuid, caller_id: Uuid, role: OrganizationRole) -> MockAppStoreBuilder {
+        let mut store = MockAppStoreBuilder::new();
+        store
+            .organization
+            .expect_find_member()
+            .withf(move |organization_id, account_id| *organization_id == id && *account_id == caller_id)
+            .returning(move |organization_id, account_id| Ok(Some(member(organization_id, account_id, role))));
+        store
+    }
+
+    mod create {
+        use super::*;
+
+        #[tokio::test]
+        async fn makes_the_caller_the_first_owner() {
+            let account_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store.organization.expect_find().returning(|_| Ok(None));
+            store
+                .organization
+                .expect_insert()
+                .withf(move |name, owner_id| name == "Treasury" && *owner_id == account_id)
+                .times(1)
+                .returning(|name, _| {
+                    Ok(Organization {
+                        id: Uuid::new_v4(),
+                        name,
+                        role: Some(OrganizationRole::Owner),
+                        ..Default::default()
+                    })
+                });
+
+            let service = OrganizationService::new(store.build());
+
+            let organization = service
+                .create(
+                    user(account_id),
+                    CreateOrganizationRequest {
+                        name: "  Treasury ".to_string(),
+                    },
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(organization.role, Some(OrganizationRole::Owner));
+        }
+
+        #[tokio::test]
+        async fn rejects_blank_names() {
+            let service = OrganizationService::new(MockAppStoreBuilder::new().build());
+
+            let err = service
+                .create(
+                    user(Uuid::new_v4()),
+                    CreateOrganizationRequest { name: " ".to_string() },
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+    }
+
+    mod add_member {
+        use super::*;
+
+        fn request(role: OrganizationRole) -> AddOrganizationMemberRequest {
+            AddOrganizationMemberRequest {
+                account_id: Uuid::new_v4(),
+                role,
+            }
+        }
+
+        #[tokio::test]
+        async fn lets_admins_add_accountants() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let request = request(OrganizationRole::Accountant);
+            let new_member_id = request.account_id;
+            let mut store = store_with_caller(id, caller_id, OrganizationRole::Admin);
+            store.account.expect_find().returning(|id| {
+                Ok(Some(Account {
+                    id,
+                    ..Default::default()
+                }))
+            });
+            store.organization.expect_find().returning(|_| Ok(None));
+            store
+                .organization
+                .expect_find_member()
+                .withf(move |_, account_id| *account_id == new_member_id)
+                .returning(|_, _| Ok(None));
+            store
+                .organization
+                .expect_insert_member()
+                .withf(move |member| {
+                    member.organization_id == id
+                        && member.account_id == new_member_id
+                        && member.role == OrganizationRole::Accountant
+                })
+                .times(1)
+                .returning(Ok);
+
+            let service = OrganizationService::new(store.build());
+
+            let member = service.add_member(user(caller_id), id, request).await.unwrap();
+
+            assert_eq!(member.role, OrganizationRole::Accountant);
+        }
+
+        #[tokio::test]
+        async fn requires_an_owner_to_add_owners() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let store = store_with_caller(id, caller_id, OrganizationRole::Admin);
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service
+                .add_member(user(caller_id), id, request(OrganizationRole::Owner))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::RoleRequired(OrganizationRole::Owner))
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_accountants() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let store = store_with_caller(id, caller_id, OrganizationRole::Accountant);
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service
+                .add_member(user(caller_id), id, request(OrganizationRole::Viewer))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::RoleRequired(OrganizationRole::Admin))
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_non_members() {
+            let mut store = MockAppStoreBuilder::new();
+            store.organization.expect_find_member().returning(|_, _| Ok(None));
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service
+                .add_member(user(Uuid::new_v4()), Uuid::new_v4(), request(OrganizationRole::Viewer))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::NotMember(_))
+            ));
+        }
+    }
+
+    mod update_member {
+        use super::*;
+
+        #[tokio::test]
+        async fn prevents_admins_from_demoting_owners() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let owner_id = Uuid::new_v4();
+            let mut store = store_with_caller(id, caller_id, OrganizationRole::Admin);
+            store
+                .organization
+                .expect_find_member()
+                .withf(move |_, account_id| *account_id == owner_id)
+                .returning(|organization_id, account_id| {
+                    Ok(Some(member(organization_id, account_id, OrganizationRole::Owner)))
+                });
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service
+                .update_member(user(caller_id), id, owner_id, OrganizationRole::Viewer)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::RoleRequired(OrganizationRole::Owner))
+            ));
+        }
+
+        #[tokio::test]
+        async fn keeps_the_last_owner() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let mut store = store_with_caller(id, caller_id, OrganizationRole::Owner);
+            store
+                .organization
+                .expect_update_member()
+                .times(1)
+                .returning(|_, _, _| Ok(None));
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service
+                .update_member(user(caller_id), id, caller_id, OrganizationRole::Admin)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+    }
+
+    mod remove_member {
+        use super::*;
+
+        #[tokio::test]
+        async fn lets_members_leave() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let mut store = store_with_caller(id, caller_id, OrganizationRole::Viewer);
+            store
+                .organization
+                .expect_delete_member()
+                .withf(move |organization_id, account_id| *organization_id == id && *account_id == caller_id)
+                .times(1)
+                .returning(|_, _| Ok(1));
+
+            let service = OrganizationService::new(store.build());
+
+            service.remove_member(user(caller_id), id, caller_id).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn rejects_viewers_removing_others() {
+            let id = Uuid::new_v4();
+            let caller_id = Uuid::new_v4();
+            let other_id = Uuid::new_v4();
+            let mut store = store_with_caller(id, caller_id, OrganizationRole::Viewer);
+            store
+                .organization
+                .expect_find_member()
+                .withf(move |_, account_id| *account_id == other_id)
+                .returning(|organization_id, account_id| {
+                    Ok(Some(member(organization_id, account_id, OrganizationRole::Viewer)))
+                });
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service.remove_member(user(caller_id), id, other_id).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::RoleRequired(OrganizationRole::Admin))
+            ));
+        }
+    }
+
+    mod act_for {
+        use super::*;
+
+        #[tokio::test]
+        async fn acts_for_the_organization_account_with_the_member_role() {
+            let id = Uuid::new_v4();
+            let account_id = Uuid::new_v4();
+            let session_id = Uuid::new_v4();
+            let store = store_with_caller(id, account_id, OrganizationRole::Accountant);
+
+            let service = OrganizationService::new(store.build());
+
+            let user = service
+                .act_for(
+                    User {
+                        account_id,
+                        session_id: Some(session_id),
+                        ..Default::default()
+                    },
+                    id,
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(user.account_id, id);
+            assert_eq!(user.principal_account_id(), account_id);
+            assert_eq!(user.session_id, Some(session_id));
+            assert_eq!(user.membership.unwrap().role, OrganizationRole::Accountant);
+        }
+
+        #[tokio::test]
+        async fn rejects_non_members() {
+            let mut store = MockAppStoreBuilder::new();
+            store.organization.expect_find_member().returning(|_, _| Ok(None));
+
+            let service = OrganizationService::new(store.build());
+
+            let err = service.act_for(user(Uuid::new_v4()), Uuid::new_v4()).await.unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::NotMember(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn keeps_api_keys_of_members_on_their_organization() {
+            let id = Uuid::new_v4();
+            let membership = member(id, Uuid::new_v4(), OrganizationRole::Viewer);
+            let mut store = MockAppStoreBuilder::new();
+            store.organization.expect_find_member().never();
+
+            let service = OrganizationService::new(store.build());
+            let key_user = User {
+                account_id: id,
+                membership: Some(membership),
+                ..Default::default()
+            };
+
+            let user = service.act_for(key_user.clone(), id).await.unwrap();
+            assert_eq!(user.membership.unwrap().role, OrganizationRole::Viewer);
+
+            let err = service.act_for(key_user, Uuid::new_v4()).await.unwrap_err();
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::NotMember(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_wallet_scoped_credentials() {
+            let service = OrganizationService::new(MockAppStoreBuilder::new().build());
+
+            let err = service
+                .act_for(
+                    User {
+                        account_id: Uuid::new_v4(),
+                        wallet_ids: Some(vec![Uuid::new_v4()]),
+                        ..Default::default()
+                    },
+                    Uuid::new_v4(),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::WalletScoped)
+            ));
+        }
+    }
+}
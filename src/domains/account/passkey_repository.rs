@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+
+use super::{Passkey, PasskeyChallenge};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PasskeyRepository: Send + Sync {
+    async fn find_by_credential_id(&self, credential_id: Vec<u8>) -> Result<Option<Passkey>, DatabaseError>;
+    async fn find_many(&self, account_id: Uuid) -> Result<Vec<Passkey>, DatabaseError>;
+    async fn insert(&self, passkey: Passkey) -> Result<Passkey, DatabaseError>;
+    /// Records a sign-in with the passkey and its new signature counter. Returns `false` when the counter did not
+    /// increase, as happens when the authenticator was cloned. Authenticators without a counter always report 0.
+    async fn record_use(&self, id: Uuid, sign_count: u32) -> Result<bool, DatabaseError>;
+    async fn delete(&self, account_id: Uuid, id: Uuid) -> Result<u64, DatabaseError>;
+    /// Stores the challenge of a new ceremony and purges expired ones.
+    async fn insert_challenge(&self, challenge: PasskeyChallenge) -> Result<(), DatabaseError>;
+    /// Deletes and returns an unexpired challenge, so that every challenge is answered at most once.
+    async fn consume_challenge(&self, id: Uuid) -> Result<Option<PasskeyChallenge>, DatabaseError>;
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+
+use super::Session;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    /// Returns the session if it has not expired.
+    async fn find(&self, id: Uuid) -> Result<Option<Session>, DatabaseError>;
+    async fn insert(
+        &self,
+        account_id: Uuid,
+        refresh_token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<Session, DatabaseError>;
+    /// Replaces the refresh token of an unexpired session and extends the session to `expires_at`.
+    /// Returns `None` when the refresh token is unknown, expired or already replaced.
+    async fn refresh(
+        &self,
+        refresh_token_hash: Vec<u8>,
+        new_refresh_token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<Session>, DatabaseError>;
+    /// Records a verification of the second factor in the session. Returns `false` when the session has ended.
+    async fn verify(&self, id: Uuid) -> Result<bool, DatabaseError>;
+    /// Ends the session of the refresh token, returning it if it existed.
+    async fn delete_by_refresh_token(&self, refresh_token_hash: Vec<u8>) -> Result<Option<Session>, DatabaseError>;
+    async fn delete_by_account(&self, account_id: Uuid) -> Result<u64, DatabaseError>;
+}
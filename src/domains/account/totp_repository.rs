@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+
+use super::TotpFactor;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait TotpRepository: Send + Sync {
+    async fn find(&self, account_id: Uuid) -> Result<Option<TotpFactor>, DatabaseError>;
+    /// Stores a pending factor, replacing a previous pending one. Returns `None` when the account already has a
+    /// confirmed factor.
+    async fn insert_pending(&self, account_id: Uuid, secret: Vec<u8>) -> Result<Option<TotpFactor>, DatabaseError>;
+    /// Confirms a pending factor with the step of its first code and stores its recovery codes, in one
+    /// transaction. Returns `false` when the factor is not pending anymore.
+    async fn confirm(
+        &self,
+        account_id: Uuid,
+        step: i64,
+        recovery_code_hashes: Vec<Vec<u8>>,
+    ) -> Result<bool, DatabaseError>;
+    /// Records the use of the code of `step`. Returns `false` when a code of this step or a later one was used.
+    async fn use_step(&self, account_id: Uuid, step: i64) -> Result<bool, DatabaseError>;
+    /// Marks an unused recovery code as used. Returns `false` when the code is unknown or already used.
+    async fn use_recovery_code(&self, account_id: Uuid, code_hash: Vec<u8>) -> Result<bool, DatabaseError>;
+    /// Deletes the factor and its recovery codes.
+    async fn delete(&self, account_id: Uuid) -> Result<u64, DatabaseError>;
+}
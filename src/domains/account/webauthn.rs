@@ -0,0 +1,671 @@
+//! WebAuthn ceremonies of passkeys: checks of the client data and authenticator data, parsing of COSE public keys
+//! and verification of assertion signatures.
+//!
+//! Passkeys are registered with `attestation: none`, so attestation statements are not verified. Signatures are
+//! verified with the ES256, EdDSA and RS256 implementations of `jsonwebtoken`.
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{crypto, Algorithm, DecodingKey};
+use serde::Deserialize;
+use serde_bolt::bitcoin::hashes::{sha256, Hash};
+
+use crate::application::errors::DataError;
+
+pub const CHALLENGE_LENGTH: usize = 32;
+/// COSE algorithms accepted for passkeys, in order of preference.
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [COSE_ALG_ES256, COSE_ALG_EDDSA, COSE_ALG_RS256];
+const COSE_ALG_ES256: i64 = -7;
+const COSE_ALG_EDDSA: i64 = -8;
+const COSE_ALG_RS256: i64 = -257;
+const COSE_KTY_OKP: i64 = 1;
+const COSE_KTY_EC2: i64 = 2;
+const COSE_KTY_RSA: i64 = 3;
+const COSE_CRV_P256: i64 = 1;
+const COSE_CRV_ED25519: i64 = 6;
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+const MAX_CREDENTIAL_ID_LENGTH: usize = 1023;
+const MAX_CBOR_DEPTH: usize = 16;
+
+/// Credential created by an authenticator during a registration ceremony.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE encoding of the public key, as stored for later assertions.
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    ceremony_type: String,
+    challenge: String,
+    origin: String,
+    #[serde(default, rename = "crossOrigin")]
+    cross_origin: bool,
+}
+
+struct AuthenticatorData<'a> {
+    flags: u8,
+    sign_count: u32,
+    attested_credential_data: &'a [u8],
+}
+
+pub fn generate_challenge() -> Vec<u8> {
+    rand::random::<[u8; CHALLENGE_LENGTH]>().to_vec()
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Decodes a base64url field of a credential, with or without padding.
+pub fn decode(value: &str) -> Result<Vec<u8>, DataError> {
+    BASE64_URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| DataError::Validation("Invalid base64url encoding in credential.".to_string()))
+}
+
+/// Verifies the response of `navigator.credentials.create()` and returns the new credential.
+pub fn verify_registration(
+    rp_id: &str,
+    origin: &str,
+    challenge: &[u8],
+    client_data_json: &[u8],
+    attestation_object: &[u8],
+) -> Result<NewCredential, DataError> {
+    verify_client_data(client_data_json, "webauthn.create", challenge, origin)?;
+
+    let (attestation, _) = Cbor::parse(attestation_object, 0)?;
+    let auth_data = attestation
+        .get_text("authData")
+        .and_then(Cbor::as_bytes)
+        .ok_or_else(|| invalid("Attestation object without authenticator data."))?;
+    let auth_data = parse_authenticator_data(rp_id, auth_data)?;
+    if auth_data.flags & FLAG_ATTESTED_CREDENTIAL_DATA == 0 {
+        return Err(invalid("Authenticator data without a credential."));
+    }
+
+    // AAGUID (16 bytes), credential ID length (2 bytes), credential ID, then the COSE public key.
+    let data = auth_data.attested_credential_data;
+    let id_length = data
+        .get(16..18)
+        .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+        .ok_or_else(|| invalid("Truncated attested credential data."))?;
+    if id_length == 0 || id_length > MAX_CREDENTIAL_ID_LENGTH {
+        return Err(invalid("Invalid credential ID length."));
+    }
+    let credential_id = data
+        .get(18..18 + id_length)
+        .ok_or_else(|| invalid("Truncated attested credential data."))?;
+    let key_bytes = &data[18 + id_length..];
+    let (_, rest) = Cbor::parse(key_bytes, 0)?;
+    let public_key = &key_bytes[..key_bytes.len() - rest.len()];
+    decoding_key(public_key)?;
+
+    Ok(NewCredential {
+        credential_id: credential_id.to_vec(),
+        public_key: public_key.to_vec(),
+        sign_count: auth_data.sign_count,
+    })
+}
+
+/// Verifies the response of `navigator.credentials.get()` with the stored COSE `public_key` and returns the
+/// signature counter of the authenticator.
+pub fn verify_assertion(
+    rp_id: &str,
+    origin: &str,
+    challenge: &[u8],
+    public_key: &[u8],
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+) -> Result<u32, DataError> {
+    verify_client_data(client_data_json, "webauthn.get", challenge, origin)?;
+    let auth_data = parse_authenticator_data(rp_id, authenticator_data)?;
+
+    let (key, algorithm) = decoding_key(public_key)?;
+    let signature = match algorithm {
+        Algorithm::ES256 => der_to_fixed_signature(signature)?,
+        _ => signature.to_vec(),
+    };
+    let mut message = authenticator_data.to_vec();
+    message.extend_from_slice(&sha256::Hash::hash(client_data_json).to_byte_array());
+
+    let valid = crypto::verify(&encode(&signature), &message, &key, algorithm)
+        .map_err(|e| DataError::Validation(format!("Invalid passkey signature: {e}")))?;
+    if !valid {
+        return Err(invalid("Invalid passkey signature."));
+    }
+
+    Ok(auth_data.sign_count)
+}
+
+fn invalid(message: &str) -> DataError {
+    DataError::Validation(message.to_string())
+}
+
+fn verify_client_data(
+    client_data_json: &[u8],
+    ceremony_type: &str,
+    challenge: &[u8],
+    origin: &str,
+) -> Result<(), DataError> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).map_err(|_| invalid("Invalid client data."))?;
+
+    if client_data.ceremony_type != ceremony_type {
+        return Err(invalid("Unexpected ceremony type."));
+    }
+    if decode(&client_data.challenge)? != challenge {
+        return Err(invalid("Challenge mismatch."));
+    }
+    if client_data.origin != origin || client_data.cross_origin {
+        return Err(DataError::Validation(format!(
+            "Unexpected origin {}.",
+            client_data.origin
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks the relying party and the user presence and verification flags. Passkeys replace passwords, so the
+/// authenticator must have verified the user with a PIN or biometrics.
+fn parse_authenticator_data<'a>(rp_id: &str, data: &'a [u8]) -> Result<AuthenticatorData<'a>, DataError> {
+    if data.len() < 37 {
+        return Err(invalid("Truncated authenticator data."));
+    }
+
+    if data[..32] != sha256::Hash::hash(rp_id.as_bytes()).to_byte_array() {
+        return Err(invalid("Credential of another relying party."));
+    }
+
+    let flags = data[32];
+    if flags & FLAG_USER_PRESENT == 0 || flags & FLAG_USER_VERIFIED == 0 {
+        return Err(invalid("The authenticator did not verify the user."));
+    }
+
+    Ok(AuthenticatorData {
+        flags,
+        sign_count: u32::from_be_bytes([data[33], data[34], data[35], data[36]]),
+        attested_credential_data: &data[37..],
+    })
+}
+
+/// Decoding key and JWS algorithm of a COSE public key.
+fn decoding_key(cose_key: &[u8]) -> Result<(DecodingKey, Algorithm), DataError> {
+    let (key, _) = Cbor::parse(cose_key, 0)?;
+    let int = |label: i64| key.get_int(label).and_then(Cbor::as_int);
+    let bytes = |label: i64| key.get_int(label).and_then(Cbor::as_bytes);
+
+    match (int(1), int(3), int(-1)) {
+        (Some(COSE_KTY_EC2), Some(COSE_ALG_ES256), Some(COSE_CRV_P256)) => match (bytes(-2), bytes(-3)) {
+            (Some(x), Some(y)) if x.len() == 32 && y.len() == 32 => Ok((
+                DecodingKey::from_ec_components(&encode(x), &encode(y))
+                    .map_err(|_| invalid("Invalid P-256 public key."))?,
+                Algorithm::ES256,
+            )),
+            _ => Err(invalid("Invalid P-256 public key.")),
+        },
+        (Some(COSE_KTY_OKP), Some(COSE_ALG_EDDSA), Some(COSE_CRV_ED25519)) => match bytes(-2) {
+            Some(x) if x.len() == 32 => Ok((
+                DecodingKey::from_ed_components(&encode(x)).map_err(|_| invalid("Invalid Ed25519 public key."))?,
+                Algorithm::EdDSA,
+            )),
+            _ => Err(invalid("Invalid Ed25519 public key.")),
+        },
+        (Some(COSE_KTY_RSA), Some(COSE_ALG_RS256), _) => match (bytes(-1), bytes(-2)) {
+            (Some(n), Some(e)) => Ok((DecodingKey::from_rsa_raw_components(n, e), Algorithm::RS256)),
+            _ => Err(invalid("Invalid RSA public key.")),
+        },
+        _ => Err(invalid("Unsupported passkey algorithm.")),
+    }
+}
+
+/// Converts a DER encoded ECDSA signature, as produced by authenticators, to the fixed size `r || s` encoding.
+fn der_to_fixed_signature(der: &[u8]) -> Result<Vec<u8>, DataError> {
+    fn integer(input: &[u8]) -> Option<(&[u8], &[u8])> {
+        let (&tag, input) = input.split_first()?;
+        let (&length, input) = input.split_first()?;
+        let length = length as usize;
+        if tag != 0x02 || length > input.len() {
+            return None;
+        }
+
+        let (value, rest) = input.split_at(length);
+        let value = match value {
+            [0, value @ ..] => value,
+            value => value,
+        };
+        (value.len() <= 32).then_some((value, rest))
+    }
+
+    let parse = || {
+        let body = match der {
+            [0x30, length, body @ ..] if *length as usize == body.len() => body,
+            _ => return None,
+        };
+        let (r, rest) = integer(body)?;
+        let (s, rest) = integer(rest)?;
+        if !rest.is_empty() {
+            return None;
+        }
+
+        let mut signature = vec![0u8; 64];
+        signature[32 - r.len()..32].copy_from_slice(r);
+        signature[64 - s.len()..].copy_from_slice(s);
+        Some(signature)
+    };
+
+    parse().ok_or_else(|| invalid("Invalid ECDSA signature encoding."))
+}
+
+/// Subset of CBOR (RFC 8949) used by WebAuthn: definite lengths and no tags or floats.
+#[derive(Debug, PartialEq)]
+enum Cbor<'a> {
+    Int(i64),
+    Bytes(&'a [u8]),
+    Text(&'a str),
+    Array(Vec<Cbor<'a>>),
+    Map(Vec<(Cbor<'a>, Cbor<'a>)>),
+    Simple(u8),
+}
+
+impl<'a> Cbor<'a> {
+    /// Parses one item and returns it with the remaining input.
+    fn parse(input: &'a [u8], depth: usize) -> Result<(Self, &'a [u8]), DataError> {
+        let malformed = || invalid("Malformed CBOR.");
+        if depth > MAX_CBOR_DEPTH {
+            return Err(malformed());
+        }
+
+        let (&initial, mut input) = input.split_first().ok_or_else(malformed)?;
+        let major = initial >> 5;
+        let argument = match initial & 0x1f {
+            value @ 0..=23 => value as u64,
+            size @ 24..=27 => {
+                let n_bytes = 1usize << (size - 24);
+                if input.len() < n_bytes {
+                    return Err(malformed());
+                }
+                let (bytes, rest) = input.split_at(n_bytes);
+                input = rest;
+                bytes.iter().fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte))
+            }
+            _ => return Err(malformed()),
+        };
+
+        let take = |input: &'a [u8], length: u64| -> Result<(&'a [u8], &'a [u8]), DataError> {
+            let length = usize::try_from(length).map_err(|_| malformed())?;
+            if length > input.len() {
+                return Err(malformed());
+            }
+            Ok(input.split_at(length))
+        };
+
+        match major {
+            0 => Ok((Cbor::Int(i64::try_from(argument).map_err(|_| malformed())?), input)),
+            1 => Ok((Cbor::Int(-1 - i64::try_from(argument).map_err(|_| malformed())?), input)),
+            2 => {
+                let (bytes, rest) = take(input, argument)?;
+                Ok((Cbor::Bytes(bytes), rest))
+            }
+            3 => {
+                let (bytes, rest) = take(input, argument)?;
+                let text = std::str::from_utf8(bytes).map_err(|_| malformed())?;
+                Ok((Cbor::Text(text), rest))
+            }
+            4 => {
+                let mut items = Vec::new();
+                for _ in 0..argument.min(input.len() as u64) {
+                    let (item, rest) = Cbor::parse(input, depth + 1)?;
+                    items.push(item);
+                    input = rest;
+                }
+                if items.len() as u64 != argument {
+                    return Err(malformed());
+                }
+                Ok((Cbor::Array(items), input))
+            }
+            5 => {
+                let mut entries = Vec::new();
+                for _ in 0..argument.min(input.len() as u64) {
+                    let (key, rest) = Cbor::parse(input, depth + 1)?;
+                    let (value, rest) = Cbor::parse(rest, depth + 1)?;
+                    entries.push((key, value));
+                    input = rest;
+                }
+                if entries.len() as u64 != argument {
+                    return Err(malformed());
+                }
+                Ok((Cbor::Map(entries), input))
+            }
+            7 if argument < 24 => Ok((Cbor::Simple(argument as u8), input)),
+            _ => Err(malformed()),
+        }
+    }
+
+    fn get(&self, key: &Cbor) -> Option<&Cbor<'a>> {
+        match self {
+            Cbor::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn get_int(&self, key: i64) -> Option<&Cbor<'a>> {
+        self.get(&Cbor::Int(key))
+    }
+
+    fn get_text(&self, key: &str) -> Option<&Cbor<'a>> {
+        self.get(&Cbor::Text(key))
+    }
+
+    fn as_int(&self) -> Option<i64> {
+        match self {
+            Cbor::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Cbor::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+/// Software authenticator for tests, with an Ed25519 key or a P-256 key.
+#[cfg(test)]
+pub mod testing {
+    use jsonwebtoken::{crypto, Algorithm, EncodingKey};
+    use serde_bolt::bitcoin::hashes::{sha256, Hash};
+
+    use super::{decode, encode, FLAG_ATTESTED_CREDENTIAL_DATA, FLAG_USER_PRESENT, FLAG_USER_VERIFIED};
+
+    // RFC 8032 test vector 1.
+    const ED25519_SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const ED25519_PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    // RFC 6979 A.2.5 key.
+    const P256_SECRET: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const P256_X: &str = "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6";
+    const P256_Y: &str = "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+
+    pub struct SoftAuthenticator {
+        pub credential_id: Vec<u8>,
+        pub sign_count: u32,
+        pub flags: u8,
+        algorithm: Algorithm,
+    }
+
+    impl SoftAuthenticator {
+        pub fn ed25519() -> Self {
+            Self::new(Algorithm::EdDSA)
+        }
+
+        pub fn p256() -> Self {
+            Self::new(Algorithm::ES256)
+        }
+
+        fn new(algorithm: Algorithm) -> Self {
+            Self {
+                credential_id: rand::random::<[u8; 16]>().to_vec(),
+                sign_count: 0,
+                flags: FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+                algorithm,
+            }
+        }
+
+        pub fn cose_public_key(&self) -> Vec<u8> {
+            match self.algorithm {
+                Algorithm::EdDSA => {
+                    // {1: 1, 3: -8, -1: 6, -2: x}
+                    let mut key = vec![0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20];
+                    key.extend(hex::decode(ED25519_PUBLIC_KEY).unwrap());
+                    key
+                }
+                _ => {
+                    // {1: 2, 3: -7, -1: 1, -2: x, -3: y}
+                    let mut key = vec![0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20];
+                    key.extend(hex::decode(P256_X).unwrap());
+                    key.extend([0x22, 0x58, 0x20]);
+                    key.extend(hex::decode(P256_Y).unwrap());
+                    key
+                }
+            }
+        }
+
+        pub fn client_data(ceremony_type: &str, challenge: &[u8], origin: &str) -> Vec<u8> {
+            serde_json::json!({
+                "type": ceremony_type,
+                "challenge": encode(challenge),
+                "origin": origin,
+                "crossOrigin": false,
+            })
+            .to_string()
+            .into_bytes()
+        }
+
+        fn authenticator_data(&self, rp_id: &str, attested_credential_data: &[u8]) -> Vec<u8> {
+            let mut data = sha256::Hash::hash(rp_id.as_bytes()).to_byte_array().to_vec();
+            data.push(self.flags);
+            data.extend(self.sign_count.to_be_bytes());
+            data.extend(attested_credential_data);
+            data
+        }
+
+        /// Returns the client data JSON and attestation object of a `none` attestation.
+        pub fn register(&mut self, rp_id: &str, origin: &str, challenge: &[u8]) -> (Vec<u8>, Vec<u8>) {
+            let mut attested_credential_data = vec![0u8; 16];
+            attested_credential_data.extend((self.credential_id.len() as u16).to_be_bytes());
+            attested_credential_data.extend(&self.credential_id);
+            attested_credential_data.extend(self.cose_public_key());
+            self.flags |= FLAG_ATTESTED_CREDENTIAL_DATA;
+            let auth_data = self.authenticator_data(rp_id, &attested_credential_data);
+            self.flags &= !FLAG_ATTESTED_CREDENTIAL_DATA;
+
+            // {"fmt": "none", "attStmt": {}, "authData": auth_data}
+            let mut attestation_object = vec![0xa3, 0x63];
+            attestation_object.extend(b"fmt");
+            attestation_object.push(0x64);
+            attestation_object.extend(b"none");
+            attestation_object.push(0x67);
+            attestation_object.extend(b"attStmt");
+            attestation_object.push(0xa0);
+            attestation_object.push(0x68);
+            attestation_object.extend(b"authData");
+            attestation_object.extend([0x59, (auth_data.len() >> 8) as u8, auth_data.len() as u8]);
+            attestation_object.extend(auth_data);
+
+            (
+                Self::client_data("webauthn.create", challenge, origin),
+                attestation_object,
+            )
+        }
+
+        /// Returns the client data JSON, authenticator data and signature of an assertion.
+        pub fn assert(&mut self, rp_id: &str, origin: &str, challenge: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+            self.sign_count += 1;
+            let client_data = Self::client_data("webauthn.get", challenge, origin);
+            let auth_data = self.authenticator_data(rp_id, &[]);
+            let signature = self.sign(&auth_data, &client_data);
+
+            (client_data, auth_data, signature)
+        }
+
+        pub fn sign(&self, auth_data: &[u8], client_data: &[u8]) -> Vec<u8> {
+            let mut message = auth_data.to_vec();
+            message.extend(sha256::Hash::hash(client_data).to_byte_array());
+
+            match self.algorithm {
+                Algorithm::EdDSA => {
+                    let mut pkcs8 = hex::decode("302e020100300506032b657004220420").unwrap();
+                    pkcs8.extend(hex::decode(ED25519_SEED).unwrap());
+                    let key = EncodingKey::from_ed_der(&pkcs8);
+                    decode(&crypto::sign(&message, &key, Algorithm::EdDSA).unwrap()).unwrap()
+                }
+                _ => {
+                    let mut pkcs8 =
+                        hex::decode("3041020100301306072a8648ce3d020106082a8648ce3d030107042730250201010420").unwrap();
+                    pkcs8.extend(hex::decode(P256_SECRET).unwrap());
+                    let key = EncodingKey::from_ec_der(&pkcs8);
+                    let fixed = decode(&crypto::sign(&message, &key, Algorithm::ES256).unwrap()).unwrap();
+                    fixed_to_der_signature(&fixed)
+                }
+            }
+        }
+    }
+
+    fn fixed_to_der_signature(fixed: &[u8]) -> Vec<u8> {
+        let integer = |value: &[u8]| {
+            let value: Vec<u8> = value.iter().copied().skip_while(|byte| *byte == 0).collect();
+            let mut encoded = vec![0x02];
+            if value.first().is_some_and(|byte| byte & 0x80 != 0) {
+                encoded.extend([value.len() as u8 + 1, 0]);
+            } else {
+                encoded.push(value.len() as u8);
+            }
+            encoded.extend(value);
+            encoded
+        };
+
+        let body = [integer(&fixed[..32]), integer(&fixed[32..])].concat();
+        [vec![0x30, body.len() as u8], body].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{testing::SoftAuthenticator, *};
+
+    const RP_ID: &str = "numeraire.tech";
+    const ORIGIN: &str = "https://swissknife.numeraire.tech";
+
+    fn registered(authenticator: &mut SoftAuthenticator, challenge: &[u8]) -> NewCredential {
+        let (client_data, attestation_object) = authenticator.register(RP_ID, ORIGIN, challenge);
+        verify_registration(RP_ID, ORIGIN, challenge, &client_data, &attestation_object).unwrap()
+    }
+
+    #[test]
+    fn registration_returns_the_credential_of_the_authenticator() {
+        let challenge = generate_challenge();
+        let mut authenticator = SoftAuthenticator::p256();
+
+        let credential = registered(&mut authenticator, &challenge);
+
+        assert_eq!(credential.credential_id, authenticator.credential_id);
+        assert_eq!(credential.public_key, authenticator.cose_public_key());
+        assert_eq!(credential.sign_count, 0);
+    }
+
+    #[test]
+    fn registration_rejects_other_challenges_origins_and_relying_parties() {
+        let challenge = generate_challenge();
+        let mut authenticator = SoftAuthenticator::ed25519();
+        let (client_data, attestation_object) = authenticator.register(RP_ID, ORIGIN, &challenge);
+
+        assert!(verify_registration(RP_ID, ORIGIN, &generate_challenge(), &client_data, &attestation_object).is_err());
+        assert!(verify_registration(
+            RP_ID,
+            "https://evil.example",
+            &challenge,
+            &client_data,
+            &attestation_object
+        )
+        .is_err());
+        assert!(verify_registration("evil.example", ORIGIN, &challenge, &client_data, &attestation_object).is_err());
+
+        let (client_data, attestation_object) = authenticator.register("evil.example", ORIGIN, &challenge);
+        assert!(verify_registration(RP_ID, ORIGIN, &challenge, &client_data, &attestation_object).is_err());
+    }
+
+    #[test]
+    fn registration_requires_user_verification() {
+        let challenge = generate_challenge();
+        let mut authenticator = SoftAuthenticator::ed25519();
+        authenticator.flags = FLAG_USER_PRESENT;
+        let (client_data, attestation_object) = authenticator.register(RP_ID, ORIGIN, &challenge);
+
+        assert!(verify_registration(RP_ID, ORIGIN, &challenge, &client_data, &attestation_object).is_err());
+    }
+
+    #[test]
+    fn assertions_are_verified_with_the_registered_key() {
+        for mut authenticator in [SoftAuthenticator::ed25519(), SoftAuthenticator::p256()] {
+            let credential = registered(&mut authenticator, &generate_challenge());
+            let challenge = generate_challenge();
+            let (client_data, auth_data, signature) = authenticator.assert(RP_ID, ORIGIN, &challenge);
+
+            let sign_count = verify_assertion(
+                RP_ID,
+                ORIGIN,
+                &challenge,
+                &credential.public_key,
+                &client_data,
+                &auth_data,
+                &signature,
+            )
+            .unwrap();
+            assert_eq!(sign_count, 1);
+
+            assert!(verify_assertion(
+                RP_ID,
+                ORIGIN,
+                &generate_challenge(),
+                &credential.public_key,
+                &client_data,
+                &auth_data,
+                &signature,
+            )
+            .is_err());
+
+            let mut tampered = auth_data.clone();
+            tampered[36] ^= 1;
+            assert!(verify_assertion(
+                RP_ID,
+                ORIGIN,
+                &challenge,
+                &credential.public_key,
+                &client_data,
+                &tampered,
+                &signature,
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn assertions_of_registration_client_data_are_rejected() {
+        let mut authenticator = SoftAuthenticator::ed25519();
+        let credential = registered(&mut authenticator, &generate_challenge());
+        let challenge = generate_challenge();
+        let (_, auth_data, _) = authenticator.assert(RP_ID, ORIGIN, &challenge);
+        let client_data = SoftAuthenticator::client_data("webauthn.create", &challenge, ORIGIN);
+        let signature = authenticator.sign(&auth_data, &client_data);
+
+        assert!(verify_assertion(
+            RP_ID,
+            ORIGIN,
+            &challenge,
+            &credential.public_key,
+            &client_data,
+            &auth_data,
+            &signature,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn cbor_rejects_truncated_and_deeply_nested_input() {
+        assert!(Cbor::parse(&[0x58, 0x20, 0x00], 0).is_err());
+        assert!(Cbor::parse(&[0xa1, 0x01], 0).is_err());
+        assert!(Cbor::parse(&[0x81; 64], 0).is_err());
+        assert_eq!(
+            Cbor::parse(&[0x82, 0x20, 0x39, 0x01, 0x00], 0).unwrap().0,
+            Cbor::Array(vec![Cbor::Int(-1), Cbor::Int(-257)])
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::{cell::RefCell, future::Future};
+
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::AuditDiff;
+
+const REDACTED: &str = "[REDACTED]";
+/// Fields whose name contains one of these are never recorded.
+const SECRET_FIELDS: [&str; 7] = ["password", "token", "secret", "code", "otp", "invitation", "signature"];
+/// Fields with one of these names are never recorded, such as the plaintext of a generated API key.
+const SECRET_FIELD_NAMES: [&str; 1] = ["key"];
+
+tokio::task_local! {
+    static CHANGES: RefCell<Option<AuditDiff>>;
+}
+
+/// Runs `future`, returning the change it recorded with [`record_created`], [`record_updated`] or
+/// [`record_deleted`].
+pub async fn collect_changes<F: Future>(future: F) -> (F::Output, Option<AuditDiff>) {
+    CHANGES
+        .scope(RefCell::new(None), async {
+            let output = future.await;
+            (output, CHANGES.with(|changes| changes.take()))
+        })
+        .await
+}
+
+/// Records the state of a resource created by the audited request.
+pub fn record_created(after: &impl Serialize) {
+    record(None, Some(audited_value(after)));
+}
+
+/// Records the state of a resource before and after its change by the audited request.
+pub fn record_updated(before: &impl Serialize, after: &impl Serialize) {
+    record(Some(audited_value(before)), Some(audited_value(after)));
+}
+
+/// Records the state of a resource deleted by the audited request.
+pub fn record_deleted(before: &impl Serialize) {
+    record(Some(audited_value(before)), None);
+}
+
+/// ID of the changed resource, if it has one.
+pub fn changed_resource_id(diff: &AuditDiff) -> Option<Uuid> {
+    diff.after
+        .as_ref()
+        .or(diff.before.as_ref())
+        .and_then(|state| state.get("id"))
+        .and_then(Value::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+fn record(before: Option<Value>, after: Option<Value>) {
+    // Changes made outside of audited requests, such as by background tasks, are not recorded.
+    let _ = CHANGES.try_with(|changes| {
+        let mut changes = changes.borrow_mut();
+        match changes.as_mut() {
+            // Requests changing a resource in several steps keep its state before the first one.
+            Some(diff) => diff.after = after,
+            None => *changes = Some(AuditDiff { before, after }),
+        }
+    });
+}
+
+fn audited_value(state: &impl Serialize) -> Value {
+    serde_json::to_value(state).map(redact).unwrap_or(Value::Null)
+}
+
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let lowercase = name.to_lowercase();
+                    if SECRET_FIELD_NAMES.contains(&lowercase.as_str())
+                        || SECRET_FIELDS.iter().any(|secret| lowercase.contains(secret))
+                    {
+                        (name, Value::String(REDACTED.to_string()))
+                    } else {
+                        (name, redact(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(redact).collect()),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn redacts_secrets_at_any_depth() {
+        let state = json!({
+            "username": "alice",
+            "password": "hunter2",
+            "otp": "123456",
+            "invitation": "abc",
+            "new_password": "hunter3",
+            "key": "sk_live",
+            "api_key_id": "kept",
+            "factor": { "code": "123456", "recovery_codes": ["a", "b"] },
+            "items": [{ "refresh_token": "abc", "name": "kept" }],
+        });
+
+        assert_eq!(
+            redact(state),
+            json!({
+                "username": "alice",
+                "password": REDACTED,
+                "otp": REDACTED,
+                "invitation": REDACTED,
+                "new_password": REDACTED,
+                "key": REDACTED,
+                "api_key_id": "kept",
+                "factor": { "code": REDACTED, "recovery_codes": REDACTED },
+                "items": [{ "refresh_token": REDACTED, "name": "kept" }],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn collects_the_first_state_before_and_the_last_state_after() {
+        let id = Uuid::new_v4();
+
+        let ((), diff) = collect_changes(async {
+            record_updated(&json!({ "id": id, "name": "a" }), &json!({ "id": id, "name": "b" }));
+            record_updated(&json!({ "id": id, "name": "b" }), &json!({ "id": id, "name": "c" }));
+        })
+        .await;
+
+        let diff = diff.unwrap();
+        assert_eq!(diff.before, Some(json!({ "id": id, "name": "a" })));
+        assert_eq!(diff.after, Some(json!({ "id": id, "name": "c" })));
+        assert_eq!(changed_resource_id(&diff), Some(id));
+    }
+
+    #[tokio::test]
+    async fn ignores_changes_outside_of_audited_requests() {
+        record_deleted(&json!({ "name": "a" }));
+
+        let ((), diff) = collect_changes(async {}).await;
+
+        assert!(diff.is_none());
+    }
+}
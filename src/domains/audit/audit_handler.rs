@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing::get, Router};
+use axum_extra::extract::Query;
+use utoipa::OpenApi;
+
+use swissknife_types::ErrorResponse;
+
+use crate::{
+    application::{
+        composition::AppServices,
+        docs::{BAD_REQUEST_EXAMPLE, FORBIDDEN_EXAMPLE, INTERNAL_EXAMPLE, UNAUTHORIZED_EXAMPLE},
+        errors::ApplicationError,
+    },
+    domains::account::{Permission, User},
+    infra::axum::Json,
+};
+
+use super::{AuditDiff, AuditEntry, AuditFilter};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_audit_entries),
+    components(schemas(AuditEntry, AuditDiff)),
+    tags(
+        (name = "Audit", description = "Audit log of state-changing requests. Requires the `read:audit` permission."),
+    )
+)]
+pub struct AuditHandler;
+pub const CONTEXT_PATH: &str = "/v1/audit";
+
+pub fn router() -> Router<Arc<AppServices>> {
+    Router::new().route("/", get(list_audit_entries))
+}
+
+/// List audit entries
+///
+/// Returns the entries recorded for every state-changing request, such as account, API key, wallet and Lightning
+/// address changes, payments and sign-ins, given a filter. Entries are never updated nor deleted.
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "Audit",
+    context_path = CONTEXT_PATH,
+    params(AuditFilter),
+    responses(
+        (status = 200, description = "Success", body = Vec<AuditEntry>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_audit_entries(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Query(filter): Query<AuditFilter>,
+) -> Result<Json<Vec<AuditEntry>>, ApplicationError> {
+    user.check_permission(Permission::ReadAudit)?;
+
+    let entries = services.audit.list(filter).await?;
+    Ok(Json(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::composition::MockAppServicesBuilder;
+
+    use super::*;
+
+    fn user(permissions: Vec<Permission>) -> User {
+        User {
+            permissions,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn without_the_read_permission_is_forbidden() {
+        let mut builder = MockAppServicesBuilder::new();
+        builder.audit.expect_list().never();
+
+        let result = list_audit_entries(
+            State(Arc::new(builder.build())),
+            user(
+                Permission::all_permissions()
+                    .into_iter()
+                    .filter(|permission| *permission != Permission::ReadAudit)
+                    .collect(),
+            ),
+            Query(AuditFilter::default()),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn with_the_read_permission_delegates_to_the_service() {
+        let mut builder = MockAppServicesBuilder::new();
+        builder
+            .audit
+            .expect_list()
+            .withf(|filter| filter.path.as_deref() == Some("/v1/wallets"))
+            .times(1)
+            .returning(|_| Ok(vec![AuditEntry::default()]));
+
+        let result = list_audit_entries(
+            State(Arc::new(builder.build())),
+            user(vec![Permission::ReadAudit]),
+            Query(AuditFilter {
+                path: Some("/v1/wallets".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await;
+
+        assert_eq!(result.unwrap().0.len(), 1);
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::{Arc, OnceLock};
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{application::composition::AppServices, domains::account::User, infra::axum::ClientIp};
+
+use super::{changed_resource_id, collect_changes, AuditEntry};
+
+/// Principal of an audited request, set by the `User` extractor once authenticated.
+#[derive(Clone, Default)]
+pub struct AuditActor(Arc<OnceLock<User>>);
+
+impl AuditActor {
+    pub fn set(&self, user: &User) {
+        let _ = self.0.set(user.clone());
+    }
+}
+
+/// Records an audit entry for every state-changing request, whatever its outcome, with the change the services made.
+pub async fn audit_middleware(State(services): State<Arc<AppServices>>, mut request: Request, next: Next) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+
+    let actor = AuditActor::default();
+    request.extensions_mut().insert(actor.clone());
+
+    let source_ip = ClientIp::of(request.extensions()).map(|ip| ip.to_string());
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let (response, diff) = collect_changes(next.run(request)).await;
+
+    let user = actor.0.get();
+    let entry = AuditEntry {
+        account_id: user.map(|user| user.account_id),
+        member_account_id: user.and_then(|user| user.membership.as_ref().map(|membership| membership.account_id)),
+        api_key_id: user.and_then(|user| user.api_key_id),
+        subject: user.and_then(|user| user.subject.clone()),
+        source_ip,
+        resource_id: diff
+            .as_ref()
+            .and_then(changed_resource_id)
+            .or_else(|| resource_id(&path)),
+        method,
+        path,
+        status: response.status().as_u16(),
+        diff,
+        ..Default::default()
+    };
+
+    // The request is already processed, so a failure to record it is reported without failing it.
+    if let Err(e) = services.audit.record(entry).await {
+        error!(error = %e, "Failed to record audit entry");
+    }
+
+    response
+}
+
+/// The last ID of the path, which identifies the target resource when it recorded no change.
+fn resource_id(path: &str) -> Option<Uuid> {
+    path.rsplit('/').find_map(|segment| Uuid::parse_str(segment).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_id_is_the_last_id_of_the_path() {
+        let wallet_id = Uuid::new_v4();
+        let payment_id = Uuid::new_v4();
+
+        assert_eq!(resource_id(&format!("/v1/wallets/{wallet_id}")), Some(wallet_id));
+        assert_eq!(
+            resource_id(&format!("/v1/me/wallets/{wallet_id}/payments/{payment_id}")),
+            Some(payment_id)
+        );
+        assert_eq!(
+            resource_id(&format!("/v1/me/wallets/{wallet_id}/payments")),
+            Some(wallet_id)
+        );
+        assert_eq!(resource_id("/v1/auth/sign-in"), None);
+    }
+}
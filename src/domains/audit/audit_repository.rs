@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+
+use crate::application::errors::DatabaseError;
+
+use super::{AuditEntry, AuditFilter};
+
+/// Append-only store of audit entries, which are never updated nor deleted.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn find_many(&self, filter: AuditFilter) -> Result<Vec<AuditEntry>, DatabaseError>;
+    async fn insert(&self, entry: AuditEntry) -> Result<AuditEntry, DatabaseError>;
+    /// Inserts `entry` hashed and linked to the most recent hashed entry. Concurrent writers, including other
+    /// instances, append one at a time so that the chain does not fork.
+    async fn insert_chained(&self, entry: AuditEntry) -> Result<AuditEntry, DatabaseError>;
+}
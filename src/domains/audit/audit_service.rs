@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use chrono::{Duration, SubsecRound, Utc};
+use serde_bolt::bitcoin::hashes::{sha256, Hash};
+use tracing::{debug, trace};
+use uuid::Uuid;
+
+use crate::application::{
+    composition::{AppStore, AuditConfig},
+    errors::ApplicationError,
+};
+
+use super::{AuditEntry, AuditFilter, AuditUseCases};
+
+pub struct AuditService {
+    store: AppStore,
+    hash_chain: bool,
+}
+
+impl AuditService {
+    pub fn new(store: AppStore, config: AuditConfig) -> Self {
+        AuditService {
+            store,
+            hash_chain: config.hash_chain,
+        }
+    }
+}
+
+/// Hex-encoded SHA-256 of the entry serialized to JSON without its hash, which covers the previous hash.
+pub fn audit_hash(entry: &AuditEntry) -> String {
+    let unhashed = AuditEntry {
+        hash: None,
+        ..entry.clone()
+    };
+    let json = serde_json::to_vec(&unhashed).expect("audit entries serialize to JSON");
+
+    hex::encode(sha256::Hash::hash(&json).to_byte_array())
+}
+
+/// Links `entry` to `last`, the latest hashed entry, and hashes it.
+pub fn chain_entry(mut entry: AuditEntry, last: Option<&AuditEntry>) -> AuditEntry {
+    if let Some(last) = last {
+        // Chained entries are ordered by creation, which must be strictly increasing.
+        if entry.created_at <= last.created_at {
+            entry.created_at = last.created_at + Duration::microseconds(1);
+        }
+        entry.previous_hash = last.hash.clone();
+    }
+    entry.hash = Some(audit_hash(&entry));
+
+    entry
+}
+
+#[async_trait]
+impl AuditUseCases for AuditService {
+    async fn record(&self, mut entry: AuditEntry) -> Result<AuditEntry, ApplicationError> {
+        trace!(method = %entry.method, path = %entry.path, status = entry.status, "Recording audit entry");
+
+        entry.id = Uuid::new_v4();
+        // Databases keep microseconds, which the hash of the stored entry must match.
+        entry.created_at = Utc::now().trunc_subsecs(6);
+
+        if !self.hash_chain {
+            let entry = self.store.audit.insert(entry).await?;
+            debug!(id = %entry.id, "Audit entry recorded successfully");
+            return Ok(entry);
+        }
+
+        let entry = self.store.audit.insert_chained(entry).await?;
+
+        debug!(id = %entry.id, "Chained audit entry recorded successfully");
+        Ok(entry)
+    }
+
+    async fn list(&self, filter: AuditFilter) -> Result<Vec<AuditEntry>, ApplicationError> {
+        trace!(?filter, "Listing audit entries");
+
+        let entries = self.store.audit.find_many(filter.clone()).await?;
+
+        debug!(?filter, "Audit entries listed successfully");
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::composition::MockAppStoreBuilder;
+
+    use super::*;
+
+    fn entry() -> AuditEntry {
+        AuditEntry {
+            account_id: Some(Uuid::new_v4()),
+            method: "DELETE".to_string(),
+            path: "/v1/api-keys".to_string(),
+            status: 200,
+            ..Default::default()
+        }
+    }
+
+    fn service(builder: MockAppStoreBuilder, hash_chain: bool) -> AuditService {
+        AuditService::new(builder.build(), AuditConfig { hash_chain })
+    }
+
+    mod record {
+        use super::*;
+
+        #[tokio::test]
+        async fn does_not_hash_entries_without_chaining() {
+            let mut builder = MockAppStoreBuilder::new();
+            builder.audit.expect_insert_chained().never();
+            builder
+                .audit
+                .expect_insert()
+                .withf(|entry| !entry.id.is_nil() && entry.hash.is_none() && entry.previous_hash.is_none())
+                .times(1)
+                .returning(Ok);
+
+            let result = service(builder, false).record(entry()).await;
+
+            assert!(result.is_ok());
+        }
+
+        #[tokio::test]
+        async fn appends_entries_to_the_chain_with_chaining() {
+            let mut builder = MockAppStoreBuilder::new();
+            builder.audit.expect_insert().never();
+            builder
+                .audit
+                .expect_insert_chained()
+                .withf(|entry| !entry.id.is_nil())
+                .times(1)
+                .returning(|entry| Ok(chain_entry(entry, None)));
+
+            let recorded = service(builder, true).record(entry()).await.unwrap();
+
+            assert!(recorded.hash.is_some());
+        }
+    }
+
+    mod chain {
+        use super::*;
+
+        #[test]
+        fn links_entries_to_the_last_hash() {
+            let mut last = entry();
+            last.created_at = Utc::now() + Duration::seconds(10);
+            last.hash = Some(audit_hash(&last));
+
+            let chained = chain_entry(entry(), Some(&last));
+
+            assert_eq!(chained.previous_hash, last.hash);
+            assert_eq!(chained.hash, Some(audit_hash(&chained)));
+            assert!(
+                chained.created_at > last.created_at,
+                "chained entries are strictly ordered"
+            );
+        }
+
+        #[test]
+        fn starts_the_chain_without_a_previous_hash() {
+            let chained = chain_entry(entry(), None);
+
+            assert!(chained.previous_hash.is_none());
+            assert_eq!(chained.hash, Some(audit_hash(&chained)));
+        }
+    }
+
+    #[test]
+    fn audit_hash_covers_the_content_and_the_previous_hash() {
+        let original = entry();
+        let hash = audit_hash(&original);
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(
+            audit_hash(&AuditEntry {
+                hash: Some("ignored".to_string()),
+                ..original.clone()
+            }),
+            hash
+        );
+        assert_ne!(
+            audit_hash(&AuditEntry {
+                status: 500,
+                ..original.clone()
+            }),
+            hash
+        );
+        assert_ne!(
+            audit_hash(&AuditEntry {
+                previous_hash: Some("00".repeat(32)),
+                ..original
+            }),
+            hash
+        );
+    }
+}
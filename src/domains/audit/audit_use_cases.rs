@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use crate::application::errors::ApplicationError;
+
+use super::{AuditEntry, AuditFilter};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditUseCases: Send + Sync {
+    async fn record(&self, entry: AuditEntry) -> Result<AuditEntry, ApplicationError>;
+    async fn list(&self, filter: AuditFilter) -> Result<Vec<AuditEntry>, ApplicationError>;
+}
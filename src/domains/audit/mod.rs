@@ -0,0 +1,14 @@
+mod audit_changes;
+mod audit_handler;
+mod audit_middleware;
+mod audit_repository;
+mod audit_service;
+mod audit_use_cases;
+
+pub use audit_changes::*;
+pub use audit_handler::*;
+pub use audit_middleware::*;
+pub use audit_repository::*;
+pub use audit_service::*;
+pub use audit_use_cases::*;
+pub use swissknife_types::{AuditDiff, AuditEntry, AuditFilter};
@@ -216,6 +216,8 @@ mod tests {
             address: "bcrt1qexample".to_string(),
             used: false,
             address_type: BtcAddressType::P2wpkh,
+            payjoin_uri: None,
+            invoice_id: None,
             created_at: Utc::now(),
             updated_at: None,
         }
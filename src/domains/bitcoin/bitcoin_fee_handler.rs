@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing::get, Router};
+use utoipa::OpenApi;
+
+use swissknife_types::ErrorResponse;
+
+use crate::{
+    application::{
+        composition::AppServices,
+        docs::{FORBIDDEN_EXAMPLE, INTERNAL_EXAMPLE, UNAUTHORIZED_EXAMPLE},
+        errors::ApplicationError,
+    },
+    domains::{
+        account::{Permission, User},
+        bitcoin::BtcFeerate,
+    },
+    infra::axum::Json,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(estimate_feerates),
+    components(schemas(BtcFeerate)),
+    tags(
+        (name = "Bitcoin Fees", description = "On-chain fee estimation endpoints. Require `write:transaction` permission.")
+    ),
+)]
+pub struct BtcFeeHandler;
+pub const CONTEXT_PATH: &str = "/v1/bitcoin/fees";
+
+pub fn fee_router() -> Router<Arc<AppServices>> {
+    Router::new().route("/", get(estimate_feerates))
+}
+
+/// Estimate on-chain feerates
+///
+/// Returns the node's feerate estimate for several confirmation targets, from next block to about a day.
+/// Pass one of them as `conf_target` (or an explicit `feerate_sat_vb`) when sending an on-chain payment.
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "Bitcoin Fees",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<BtcFeerate>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn estimate_feerates(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<Vec<BtcFeerate>>, ApplicationError> {
+    user.check_permission(Permission::WriteTransaction)?;
+
+    let feerates = services.bitcoin.estimate_feerates().await?;
+
+    Ok(Json(feerates))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::composition::MockAppServicesBuilder;
+
+    use super::*;
+
+    fn user(permissions: Vec<Permission>) -> User {
+        User {
+            permissions,
+            ..Default::default()
+        }
+    }
+
+    mod estimate_feerates {
+        use super::*;
+
+        mod without_the_write_permission {
+            use super::*;
+
+            #[tokio::test]
+            async fn is_forbidden_and_does_not_call_the_service() {
+                let services = MockAppServicesBuilder::new().build();
+
+                let result = estimate_feerates(State(Arc::new(services)), user(vec![])).await;
+
+                assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+            }
+        }
+
+        mod with_the_write_permission {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_the_estimates() {
+                let mut builder = MockAppServicesBuilder::new();
+                builder.bitcoin.expect_estimate_feerates().times(1).returning(|| {
+                    Ok(vec![BtcFeerate {
+                        conf_target: 6,
+                        feerate_sat_vb: 12,
+                    }])
+                });
+
+                let Json(feerates) = estimate_feerates(
+                    State(Arc::new(builder.build())),
+                    user(vec![Permission::WriteTransaction]),
+                )
+                .await
+                .unwrap();
+
+                assert_eq!(feerates[0].feerate_sat_vb, 12);
+            }
+        }
+    }
+}
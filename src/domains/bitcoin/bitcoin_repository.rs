@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::{application::errors::DatabaseError, domains::bitcoin::BtcAddressFilter};
 
-use super::{BtcAddress, BtcAddressType, BtcOutput};
+use super::{BtcAddress, BtcAddressType, BtcOutput, BtcOutputStatus, PayjoinFallback, SilentPaymentOutput};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -22,8 +22,36 @@ pub trait BtcAddressRepository: Send + Sync {
         address: &str,
         address_type: BtcAddressType,
     ) -> Result<BtcAddress, DatabaseError>;
+    /// Inserts a deposit address dedicated to `invoice_id`, as the on-chain leg of a unified payment request.
+    async fn insert_for_invoice(
+        &self,
+        wallet_id: Uuid,
+        address: &str,
+        address_type: BtcAddressType,
+        invoice_id: Uuid,
+    ) -> Result<BtcAddress, DatabaseError>;
+    async fn find_by_invoice_id(&self, invoice_id: Uuid) -> Result<Option<BtcAddress>, DatabaseError>;
     async fn mark_used(&self, id: Uuid) -> Result<(), DatabaseError>;
     async fn delete_many(&self, filter: BtcAddressFilter) -> Result<u64, DatabaseError>;
+
+    /// Highest label given to a silent payment address, if any.
+    async fn max_silent_payment_label(&self) -> Result<Option<u32>, DatabaseError>;
+    /// Inserts a silent payment address, or returns `None` if another address already took `label`.
+    async fn insert_silent_payment(
+        &self,
+        wallet_id: Uuid,
+        address: &str,
+        label: u32,
+    ) -> Result<Option<BtcAddress>, DatabaseError>;
+    /// Labels of the silent payment addresses, with the wallet each belongs to.
+    async fn find_silent_payment_labels(&self) -> Result<Vec<(u32, Uuid)>, DatabaseError>;
+    /// Records the taproot output address of a silent payment to `wallet_id`, with the tweak spending it.
+    async fn insert_silent_payment_output(
+        &self,
+        wallet_id: Uuid,
+        address: &str,
+        tweak: &str,
+    ) -> Result<BtcAddress, DatabaseError>;
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -32,4 +60,27 @@ pub trait BtcOutputRepository: Send + Sync {
     async fn find_by_outpoint(&self, outpoint: &str) -> Result<Option<BtcOutput>, DatabaseError>;
     async fn upsert(&self, output: BtcOutput) -> Result<BtcOutput, DatabaseError>;
     async fn max_block_height(&self) -> Result<Option<u32>, DatabaseError>;
+    /// Outputs still awaiting confirmations, plus those mined at or above `min_block_height`
+    /// (still within the reorg window).
+    async fn find_unconfirmed_or_since(&self, min_block_height: u32) -> Result<Vec<BtcOutput>, DatabaseError>;
+    /// Outputs received through silent payments, with the tweaks spending them.
+    async fn find_silent_payment_outputs(
+        &self,
+        status: BtcOutputStatus,
+    ) -> Result<Vec<SilentPaymentOutput>, DatabaseError>;
+    async fn mark_spent(&self, outpoints: Vec<String>) -> Result<(), DatabaseError>;
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PayjoinRepository: Send + Sync {
+    /// Records the inputs of an original PSBT. Returns false if any of them was already seen.
+    async fn insert_inputs(&self, outpoints: Vec<String>) -> Result<bool, DatabaseError>;
+    async fn insert_fallback(&self, fallback: PayjoinFallback) -> Result<(), DatabaseError>;
+    /// Fallbacks whose broadcast delay has elapsed.
+    async fn find_due_fallbacks(&self) -> Result<Vec<PayjoinFallback>, DatabaseError>;
+    async fn delete_fallback(&self, id: Uuid) -> Result<(), DatabaseError>;
+    /// Records the value a proposal added to the payment output at `outpoint`, which the deposit is not credited for.
+    async fn insert_contribution(&self, outpoint: String, amount_sat: u64) -> Result<(), DatabaseError>;
+    async fn find_contribution(&self, outpoint: &str) -> Result<Option<u64>, DatabaseError>;
 }
@@ -1,38 +1,65 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
-use tracing::{debug, info, trace};
+use bitcoin::consensus::encode::serialize_hex;
+use chrono::Utc;
+use tracing::{debug, info, trace, warn};
 use uuid::Uuid;
 
 use crate::{
     application::{
         composition::AppStore,
-        errors::{ApplicationError, DataError},
+        errors::{ApplicationError, BitcoinError, DataError, PayjoinError},
     },
     domains::{
-        bitcoin::{BitcoinWallet, BtcAddressFilter, OnchainSyncCursor, OnchainTransaction},
-        event::EventUseCases,
+        bitcoin::{
+            address_script, bitcoin_network, build_payjoin_proposal, check_original_psbt, decode_psbt, encode_psbt,
+            extract_transaction, finish_payjoin_proposal, input_outpoints, script_address, spends_utxo, BitcoinWallet,
+            BtcAddressFilter, BtcFeerate, BtcOutput, BtcOutputStatus, ChainTransaction, FoundSilentPayment,
+            OnchainSyncCursor, OnchainTransaction, PayjoinFallback, PayjoinInputType, PayjoinQueryParams,
+            PayjoinReceiver, SilentPaymentScanCursor, SilentPayments, FEE_ESTIMATE_CONF_TARGETS, PAYJOIN_VERSION,
+        },
+        event::{EventUseCases, OnchainDepositEvent, OnchainWithdrawalEvent},
         system::SystemUseCases,
     },
 };
 
 use super::{BitcoinUseCases, BtcAddress, BtcAddressType};
 
+/// Most blocks scanned for silent payments per sync, so a long catch-up doesn't hold back the rest of the sync.
+const SILENT_PAYMENT_SCAN_BATCH: u32 = 100;
+
+/// Confirmation target of the sweeps of silent payment outputs to the node wallet, which are never urgent.
+const SILENT_PAYMENT_SWEEP_CONF_TARGET: u32 = 144;
+
+/// Attempts at allocating a silent payment label before giving up, when other addresses keep taking it.
+const SILENT_PAYMENT_LABEL_ATTEMPTS: usize = 5;
+
 pub struct BitcoinService {
     store: AppStore,
     wallet: Arc<dyn BitcoinWallet>,
     address_type: BtcAddressType,
     events: Arc<dyn EventUseCases>,
     system: Arc<dyn SystemUseCases>,
+    reorg_window: u32,
+    payjoin: Option<Arc<PayjoinReceiver>>,
+    silent_payments: Option<Arc<SilentPayments>>,
 }
 
 impl BitcoinService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         store: AppStore,
         wallet: Arc<dyn BitcoinWallet>,
         address_type: BtcAddressType,
         events: Arc<dyn EventUseCases>,
         system: Arc<dyn SystemUseCases>,
+        reorg_window: u32,
+        payjoin: Option<Arc<PayjoinReceiver>>,
+        silent_payments: Option<Arc<SilentPayments>>,
     ) -> Self {
         Self {
             store,
@@ -40,7 +67,349 @@ impl BitcoinService {
             address_type,
             events,
             system,
+            reorg_window,
+            payjoin,
+            silent_payments,
+        }
+    }
+
+    /// Attaches the BIP21 URI advertising our payjoin endpoint, when payjoin is enabled.
+    fn with_payjoin_uri(&self, mut address: BtcAddress) -> BtcAddress {
+        address.payjoin_uri = self
+            .payjoin
+            .as_ref()
+            .filter(|_| address.address_type != BtcAddressType::SilentPayment)
+            .map(|payjoin| format!("bitcoin:{}?pj={}", address.address, payjoin.endpoint));
+        address
+    }
+
+    /// Broadcasts the original transaction of the payjoins whose delay elapsed. If the sender broadcast the payjoin
+    /// and it confirmed, the original double spends it and is rejected; if it broadcasts, the output we contributed is
+    /// unlocked. Fallbacks failing for any other reason, such as a payjoin still in the mempool, are retried on the
+    /// next sync.
+    async fn broadcast_payjoin_fallbacks(&self, payjoin: &PayjoinReceiver) -> Result<(), ApplicationError> {
+        for fallback in self.store.payjoin.find_due_fallbacks().await? {
+            match payjoin.chain.broadcast_transaction(&fallback.tx_hex).await {
+                Ok(txid) => {
+                    info!(%txid, "Payjoin not broadcast by the sender, original transaction broadcast");
+                    if let Err(err) = self.wallet.unlock_utxo(&fallback.locked_utxo).await {
+                        warn!(%err, txid = %fallback.locked_utxo.txid, "Failed to unlock the output of a payjoin");
+                    }
+                }
+                Err(BitcoinError::InputsSpent(err)) => {
+                    debug!(txid = %fallback.txid, %err, "Payjoin confirmed, original transaction dropped");
+                }
+                Err(err) => {
+                    warn!(txid = %fallback.txid, %err, "Failed to broadcast payjoin original transaction, retrying");
+                    continue;
+                }
+            }
+
+            self.store.payjoin.delete_fallback(fallback.id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Issues the static silent payment address of the wallet, labeled with the next free label. Labels taken
+    /// concurrently by another wallet are skipped.
+    async fn new_silent_payment_address(&self, wallet_id: Uuid) -> Result<BtcAddress, ApplicationError> {
+        let silent_payments = self
+            .silent_payments
+            .as_ref()
+            .ok_or_else(|| BitcoinError::AddressType("Silent payments are not enabled.".to_string()))?;
+
+        for _ in 0..SILENT_PAYMENT_LABEL_ATTEMPTS {
+            // BIP352 reserves label 0 for change.
+            let label = self
+                .store
+                .btc_address
+                .max_silent_payment_label()
+                .await?
+                .map_or(1, |label| label + 1);
+            let address = silent_payments.keys.address(label, self.wallet.network());
+
+            match self
+                .store
+                .btc_address
+                .insert_silent_payment(wallet_id, &address, label)
+                .await?
+            {
+                Some(btc_address) => {
+                    info!(%wallet_id, address = %btc_address.address, label, "New silent payment address issued");
+                    return Ok(btc_address);
+                }
+                None => debug!(%wallet_id, label, "Silent payment label already taken, retrying"),
+            }
+        }
+
+        Err(DataError::Conflict("Failed to allocate a silent payment label, please retry.".to_string()).into())
+    }
+
+    /// Current state of an output the node wallet doesn't know, such as a silent payment output, from the chain.
+    async fn chain_output(&self, output: &BtcOutput) -> Result<Option<BtcOutput>, ApplicationError> {
+        let (Some(silent_payments), Some(block_height)) = (&self.silent_payments, output.block_height) else {
+            return Ok(None);
+        };
+
+        let mut current = output.clone();
+        if !silent_payments.chain.is_in_block(&output.txid, block_height).await? {
+            current.block_height = None;
+        }
+
+        Ok(Some(current))
+    }
+
+    /// Scans the blocks mined since the last scan for silent payments to our addresses, projecting each as a
+    /// deposit, and for spends of our silent payment outputs, settling the matching withdrawal.
+    async fn scan_silent_payments(&self, silent_payments: &SilentPayments) -> Result<u32, ApplicationError> {
+        let tip_height = silent_payments.chain.block_height().await?;
+        let start_height = match self.system.get_silent_payment_cursor().await? {
+            None => silent_payments.birth_height,
+            Some(cursor) => {
+                let hash = match cursor.height <= tip_height {
+                    true => Some(silent_payments.chain.block_hash(cursor.height).await?),
+                    false => None,
+                };
+
+                if hash.as_ref() == Some(&cursor.hash) {
+                    cursor.height + 1
+                } else {
+                    warn!(
+                        height = cursor.height,
+                        "Last block scanned for silent payments was reorged out; rescanning"
+                    );
+                    cursor
+                        .height
+                        .saturating_sub(self.reorg_window)
+                        .max(silent_payments.birth_height)
+                }
+            }
+        };
+        if start_height > tip_height {
+            return Ok(0);
+        }
+        let end_height = tip_height.min(start_height + SILENT_PAYMENT_SCAN_BATCH - 1);
+
+        trace!(start_height, end_height, "Scanning blocks for silent payments");
+
+        let wallets: HashMap<u32, Uuid> = self
+            .store
+            .btc_address
+            .find_silent_payment_labels()
+            .await?
+            .into_iter()
+            .collect();
+        let labels = wallets
+            .keys()
+            .map(|label| (silent_payments.keys.label_point(*label), *label))
+            .collect();
+        let spent: HashSet<String> = self
+            .store
+            .btc_output
+            .find_silent_payment_outputs(BtcOutputStatus::Spent)
+            .await?
+            .into_iter()
+            .map(|spent| spent.output.outpoint)
+            .collect();
+        let mut synced = 0;
+
+        for height in start_height..=end_height {
+            let block = silent_payments.chain.block(height).await?;
+
+            for transaction in &block.transactions {
+                let txid = transaction.transaction.compute_txid().to_string();
+
+                if transaction
+                    .transaction
+                    .input
+                    .iter()
+                    .any(|input| spent.contains(&input.previous_output.to_string()))
+                {
+                    let event = OnchainWithdrawalEvent {
+                        txid: txid.clone(),
+                        block_height: Some(height),
+                    };
+                    if self.events.onchain_withdrawal(event).await? {
+                        synced += 1;
+                    }
+                }
+
+                let found =
+                    silent_payments
+                        .keys
+                        .scan_transaction(&transaction.transaction, &transaction.prevouts, &labels);
+                for payment in found {
+                    if self
+                        .project_silent_payment(transaction, &txid, height, tip_height, payment, &wallets)
+                        .await?
+                    {
+                        synced += 1;
+                    }
+                }
+            }
+
+            self.system
+                .set_silent_payment_cursor(SilentPaymentScanCursor {
+                    height,
+                    hash: block.hash,
+                })
+                .await?;
+        }
+
+        Ok(synced)
+    }
+
+    /// Sweeps the confirmed outputs of our silent payment keys to a new address of the node wallet, which funds
+    /// the on-chain payments. Outputs too small to cover the fee wait for more to join them.
+    async fn sweep_silent_payments(&self, silent_payments: &SilentPayments) -> Result<(), ApplicationError> {
+        let outputs = self
+            .store
+            .btc_output
+            .find_silent_payment_outputs(BtcOutputStatus::Confirmed)
+            .await?;
+        if outputs.is_empty() {
+            return Ok(());
+        }
+
+        let feerate = self.wallet.estimate_feerate(SILENT_PAYMENT_SWEEP_CONF_TARGET).await?;
+        let address = self.wallet.new_address(BtcAddressType::P2tr).await?;
+        let destination = address_script(&address, bitcoin_network(self.wallet.network()))
+            .ok_or_else(|| BitcoinError::Address(format!("Invalid node wallet address: {address}")))?;
+
+        let sweep = match silent_payments.keys.sweep_transaction(&outputs, destination, feerate) {
+            Ok(sweep) => sweep,
+            Err(err) => {
+                debug!(outputs = outputs.len(), %err, "Silent payment outputs not swept");
+                return Ok(());
+            }
+        };
+
+        match silent_payments
+            .chain
+            .broadcast_transaction(&serialize_hex(&sweep.transaction))
+            .await
+        {
+            Ok(txid) => {
+                self.store
+                    .btc_output
+                    .mark_spent(sweep.spent.into_iter().map(|spent| spent.output.outpoint).collect())
+                    .await?;
+                info!(%txid, %address, fee_sat = sweep.fee_sat, "Silent payment outputs swept to the node wallet");
+            }
+            Err(err) => warn!(%err, "Failed to broadcast the sweep of silent payment outputs"),
+        }
+
+        Ok(())
+    }
+
+    /// Records the output address of a silent payment found in a block for the wallet owning its label, then
+    /// projects the deposit.
+    async fn project_silent_payment(
+        &self,
+        transaction: &ChainTransaction,
+        txid: &str,
+        block_height: u32,
+        tip_height: u32,
+        payment: FoundSilentPayment,
+        wallets: &HashMap<u32, Uuid>,
+    ) -> Result<bool, ApplicationError> {
+        let outpoint = format!("{txid}:{}", payment.output_index);
+        let Some(wallet_id) = payment.label.and_then(|label| wallets.get(&label)) else {
+            warn!(%outpoint, "Silent payment to an address not issued to any wallet; ignoring");
+            return Ok(false);
+        };
+        let output = &transaction.transaction.output[payment.output_index as usize];
+        let network = bitcoin_network(self.wallet.network());
+        let Some(address) = script_address(&output.script_pubkey, network) else {
+            return Ok(false);
+        };
+
+        // Blocks are rescanned after a reorg; outputs still in the same block are already projected.
+        if let Some(existing) = self.store.btc_output.find_by_outpoint(&outpoint).await? {
+            if existing.block_height == Some(block_height) {
+                return Ok(false);
+            }
+        }
+
+        if self.store.btc_address.find_by_address(&address).await?.is_none() {
+            let tweak = hex::encode(payment.tweak.secret_bytes());
+            self.store
+                .btc_address
+                .insert_silent_payment_output(*wallet_id, &address, &tweak)
+                .await?;
+        }
+
+        debug!(%wallet_id, %outpoint, "Silent payment found");
+
+        self.events
+            .onchain_deposit(OnchainDepositEvent {
+                txid: txid.to_string(),
+                output_index: payment.output_index,
+                address,
+                amount_sat: output.value.to_sat(),
+                block_height: Some(block_height),
+                tip_height: Some(tip_height),
+            })
+            .await
+    }
+
+    /// Re-projects deposits the sync cursor has already moved past: outputs still awaiting
+    /// confirmations (so they mature) and recently mined ones (so a reorg reverses their credit).
+    /// Outputs no longer known at all were reorged out or double spent, and are dropped.
+    async fn refresh_recent_deposits(&self, tip_height: u32) -> Result<u32, ApplicationError> {
+        let min_block_height = tip_height.saturating_sub(self.reorg_window);
+        let outputs = self
+            .store
+            .btc_output
+            .find_unconfirmed_or_since(min_block_height)
+            .await?;
+        let mut refreshed = 0;
+
+        for output in outputs {
+            let current = match self
+                .wallet
+                .get_output(&output.txid, Some(output.output_index), None, true)
+                .await?
+            {
+                Some(current) => Some(current),
+                None => self.chain_output(&output).await?,
+            };
+            let Some(current) = current else {
+                if self.events.onchain_deposit_dropped(output).await? {
+                    refreshed += 1;
+                }
+                continue;
+            };
+
+            if output.status != BtcOutputStatus::Unconfirmed && current.block_height == output.block_height {
+                continue;
+            }
+
+            let event = self.deposit_event(current, tip_height).await?;
+            if self.events.onchain_deposit(event).await? {
+                refreshed += 1;
+            }
+        }
+
+        Ok(refreshed)
+    }
+
+    /// Deposit event of an output, credited for what the sender paid when we contributed to it through a payjoin.
+    async fn deposit_event(&self, output: BtcOutput, tip_height: u32) -> Result<OnchainDepositEvent, ApplicationError> {
+        let mut event = OnchainDepositEvent {
+            tip_height: Some(tip_height),
+            ..output.into()
+        };
+
+        if self.payjoin.is_some() {
+            let outpoint = format!("{}:{}", event.txid, event.output_index);
+            if let Some(contributed_sat) = self.store.payjoin.find_contribution(&outpoint).await? {
+                event.amount_sat = event.amount_sat.saturating_sub(contributed_sat);
+            }
         }
+
+        Ok(event)
     }
 }
 
@@ -61,7 +430,11 @@ impl BitcoinUseCases for BitcoinService {
             .find_by_wallet_unused(wallet_id, address_type)
             .await?
         {
-            return Ok(address);
+            return Ok(self.with_payjoin_uri(address));
+        }
+
+        if address_type == BtcAddressType::SilentPayment {
+            return self.new_silent_payment_address(wallet_id).await;
         }
 
         let address = self.wallet.new_address(address_type).await?;
@@ -70,7 +443,7 @@ impl BitcoinUseCases for BitcoinService {
 
         info!(%wallet_id, address = %btc_address.address, "New bitcoin deposit address issued");
 
-        Ok(btc_address)
+        Ok(self.with_payjoin_uri(btc_address))
     }
 
     async fn get_address(&self, id: Uuid) -> Result<BtcAddress, ApplicationError> {
@@ -84,7 +457,7 @@ impl BitcoinUseCases for BitcoinService {
             .ok_or_else(|| DataError::NotFound("Bitcoin address not found.".to_string()))?;
 
         debug!(%id, "Bitcoin address fetched successfully");
-        Ok(address)
+        Ok(self.with_payjoin_uri(address))
     }
 
     async fn list_addresses(&self, filter: BtcAddressFilter) -> Result<Vec<BtcAddress>, ApplicationError> {
@@ -93,7 +466,10 @@ impl BitcoinUseCases for BitcoinService {
         let addresses = self.store.btc_address.find_many(filter.clone()).await?;
 
         debug!(?filter, "Bitcoin addresses listed successfully");
-        Ok(addresses)
+        Ok(addresses
+            .into_iter()
+            .map(|address| self.with_payjoin_uri(address))
+            .collect())
     }
 
     async fn delete_address(&self, id: Uuid) -> Result<(), ApplicationError> {
@@ -125,6 +501,149 @@ impl BitcoinUseCases for BitcoinService {
         Ok(n_deleted)
     }
 
+    async fn estimate_feerates(&self) -> Result<Vec<BtcFeerate>, ApplicationError> {
+        trace!("Estimating on-chain feerates");
+
+        let mut feerates = Vec::with_capacity(FEE_ESTIMATE_CONF_TARGETS.len());
+        for conf_target in FEE_ESTIMATE_CONF_TARGETS {
+            let feerate_sat_vb = self.wallet.estimate_feerate(conf_target).await?;
+            feerates.push(BtcFeerate {
+                conf_target,
+                feerate_sat_vb,
+            });
+        }
+
+        debug!(?feerates, "On-chain feerates estimated successfully");
+        Ok(feerates)
+    }
+
+    async fn payjoin_proposal(
+        &self,
+        original_psbt: String,
+        params: PayjoinQueryParams,
+    ) -> Result<String, ApplicationError> {
+        let payjoin = self
+            .payjoin
+            .as_deref()
+            .ok_or_else(|| PayjoinError::Unavailable("Payjoin is not enabled.".to_string()))?;
+        if params.v.unwrap_or(PAYJOIN_VERSION) != PAYJOIN_VERSION {
+            return Err(
+                PayjoinError::VersionUnsupported(format!("Only version {PAYJOIN_VERSION} is supported.")).into(),
+            );
+        }
+
+        trace!(?params, "Processing payjoin original PSBT");
+
+        let original = decode_psbt(&original_psbt).map_err(PayjoinError::OriginalPsbtRejected)?;
+        let input_type = check_original_psbt(&original)?;
+        let network = bitcoin_network(self.wallet.network());
+
+        let mut receiver = None;
+        for (index, output) in original.unsigned_tx.output.iter().enumerate() {
+            let Some(address) = script_address(&output.script_pubkey, network) else {
+                continue;
+            };
+            if let Some(btc_address) = self.store.btc_address.find_by_address(&address).await? {
+                receiver = Some((index, btc_address));
+                break;
+            }
+        }
+        let (receiver_output, btc_address) = receiver.ok_or_else(|| {
+            PayjoinError::OriginalPsbtRejected("The PSBT does not pay a deposit address.".to_string())
+        })?;
+
+        // The original transaction is our fallback if the sender never broadcasts the payjoin.
+        let (original_txid, original_hex) = extract_transaction(&original);
+        if let Some(reason) = payjoin.chain.test_mempool_accept(&original_hex).await? {
+            return Err(PayjoinError::OriginalPsbtRejected(format!(
+                "The original transaction cannot be broadcast: {reason}"
+            ))
+            .into());
+        }
+
+        // Senders replaying the same inputs could otherwise probe the outputs we contribute.
+        if !self.store.payjoin.insert_inputs(input_outpoints(&original)).await? {
+            return Err(PayjoinError::OriginalPsbtRejected("The PSBT inputs were already seen.".to_string()).into());
+        }
+
+        let utxos = self.wallet.list_unspent().await?;
+        if utxos.iter().any(|utxo| spends_utxo(&original, utxo)) {
+            return Err(
+                PayjoinError::OriginalPsbtRejected("The PSBT spends outputs of the receiver.".to_string()).into(),
+            );
+        }
+
+        // Contribute the smallest output of the sender's script type that covers its own fee.
+        let mut candidates: Vec<_> = utxos
+            .into_iter()
+            .filter_map(|utxo| {
+                let script = address_script(&utxo.address, network)?;
+                (PayjoinInputType::from_script(&script) == Some(input_type)).then_some((utxo, script))
+            })
+            .collect();
+        candidates.sort_by_key(|(utxo, _)| utxo.amount_sat);
+        let (utxo, utxo_script) = candidates
+            .into_iter()
+            .find(|(utxo, script)| {
+                build_payjoin_proposal(&original, receiver_output, utxo, script.clone(), &params).is_ok()
+            })
+            .ok_or_else(|| PayjoinError::Unavailable("No output available to contribute.".to_string()))?;
+
+        let locked_utxo = self
+            .wallet
+            .lock_utxo(&utxo, payjoin.lock_expiry())
+            .await
+            .map_err(|e| PayjoinError::Unavailable(e.to_string()))?;
+
+        let proposal = async {
+            let proposal = build_payjoin_proposal(&original, receiver_output, &utxo, utxo_script, &params)?;
+
+            let signed = self.wallet.sign_psbt(&encode_psbt(&proposal)).await?;
+            let signed = decode_psbt(&signed).map_err(PayjoinError::Unavailable)?;
+            let proposal = finish_payjoin_proposal(signed, &original)?;
+
+            // The deposit is credited for what the sender paid, not for the value of the output we contributed.
+            let payjoin_txid = proposal.unsigned_tx.compute_txid();
+            let contributed_sat =
+                proposal.unsigned_tx.output[receiver_output].value - original.unsigned_tx.output[receiver_output].value;
+            self.store
+                .payjoin
+                .insert_contribution(format!("{payjoin_txid}:{receiver_output}"), contributed_sat.to_sat())
+                .await?;
+
+            self.store
+                .payjoin
+                .insert_fallback(PayjoinFallback {
+                    id: Uuid::new_v4(),
+                    txid: original_txid,
+                    tx_hex: original_hex,
+                    locked_utxo: locked_utxo.clone(),
+                    broadcast_after: Utc::now() + payjoin.fallback_delay,
+                })
+                .await?;
+
+            Ok::<_, ApplicationError>(proposal)
+        }
+        .await;
+
+        let proposal = match proposal {
+            Ok(proposal) => proposal,
+            Err(err) => {
+                if let Err(unlock_err) = self.wallet.unlock_utxo(&locked_utxo).await {
+                    warn!(%unlock_err, txid = %locked_utxo.txid, "Failed to unlock the output of a failed payjoin");
+                }
+                return Err(err);
+            }
+        };
+
+        info!(
+            wallet_id = %btc_address.wallet_id,
+            address = %btc_address.address,
+            "Payjoin proposal returned to the sender"
+        );
+        Ok(encode_psbt(&proposal))
+    }
+
     async fn sync(&self) -> Result<u32, ApplicationError> {
         trace!("Synchronizing on-chain bitcoin transactions...");
 
@@ -137,12 +656,14 @@ impl BitcoinUseCases for BitcoinService {
         }
 
         let result = self.wallet.synchronize(cursor).await?;
+        let tip_height = self.wallet.block_height().await?;
         let mut synced = 0;
 
         for transaction in result.events {
             match transaction {
                 OnchainTransaction::Deposit(output) => {
-                    if self.events.onchain_deposit(output.into()).await? {
+                    let event = self.deposit_event(output, tip_height).await?;
+                    if self.events.onchain_deposit(event).await? {
                         synced += 1;
                     }
                 }
@@ -158,6 +679,17 @@ impl BitcoinUseCases for BitcoinService {
             self.system.set_onchain_cursor(next_cursor).await?;
         }
 
+        synced += self.refresh_recent_deposits(tip_height).await?;
+
+        if let Some(silent_payments) = &self.silent_payments {
+            synced += self.scan_silent_payments(silent_payments).await?;
+            self.sweep_silent_payments(silent_payments).await?;
+        }
+
+        if let Some(payjoin) = &self.payjoin {
+            self.broadcast_payjoin_fallbacks(payjoin).await?;
+        }
+
         debug!(synced, "On-chain bitcoin transactions synchronized successfully");
         Ok(synced)
     }
@@ -165,13 +697,18 @@ impl BitcoinUseCases for BitcoinService {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use chrono::Utc;
 
     use crate::{
         application::composition::MockAppStoreBuilder,
         domains::{
-            bitcoin::{BtcNetwork, BtcOutput, MockBitcoinWallet, OnchainSyncBatch, OnchainTransaction},
-            event::{MockEventUseCases, OnchainWithdrawalEvent},
+            bitcoin::{
+                decode_silent_payment_address, silent_payment_fee, BtcNetwork, BtcOutput, ChainBlock, MockBitcoinChain,
+                MockBitcoinWallet, OnchainSyncBatch, OnchainTransaction, SilentPaymentKeys, SilentPaymentOutput,
+            },
+            event::MockEventUseCases,
             system::MockSystemUseCases,
         },
     };
@@ -190,9 +727,98 @@ mod tests {
             BtcAddressType::P2wpkh,
             Arc::new(events),
             Arc::new(system),
+            6,
+            None,
+            None,
+        )
+    }
+
+    fn payjoin_service(
+        store: MockAppStoreBuilder,
+        wallet: MockBitcoinWallet,
+        chain: MockBitcoinChain,
+    ) -> BitcoinService {
+        BitcoinService::new(
+            store.build(),
+            Arc::new(wallet),
+            BtcAddressType::P2wpkh,
+            Arc::new(MockEventUseCases::new()),
+            Arc::new(MockSystemUseCases::new()),
+            6,
+            Some(Arc::new(PayjoinReceiver::new(
+                "https://api.numeraire.tech/v1/payjoin".to_string(),
+                Arc::new(chain),
+                Duration::from_secs(60),
+            ))),
+            None,
+        )
+    }
+
+    fn silent_payment_keys() -> SilentPaymentKeys {
+        SilentPaymentKeys::from_hex(&"11".repeat(32), &"22".repeat(32)).unwrap()
+    }
+
+    fn silent_payment_service(
+        store: MockAppStoreBuilder,
+        wallet: MockBitcoinWallet,
+        events: MockEventUseCases,
+        system: MockSystemUseCases,
+        chain: MockBitcoinChain,
+    ) -> BitcoinService {
+        BitcoinService::new(
+            store.build(),
+            Arc::new(wallet),
+            BtcAddressType::P2wpkh,
+            Arc::new(events),
+            Arc::new(system),
+            6,
+            None,
+            Some(Arc::new(SilentPayments::new(
+                silent_payment_keys(),
+                Arc::new(chain),
+                100,
+            ))),
         )
     }
 
+    /// Transaction paying `amount_sat` to our silent payment address with `label`, from another silent payment
+    /// wallet.
+    fn silent_payment_transaction(label: u32, amount_sat: u64) -> ChainTransaction {
+        let sender = SilentPaymentKeys::from_hex(&"33".repeat(32), &"44".repeat(32)).unwrap();
+        let tweak = "2b".repeat(32);
+        let funding_sat = amount_sat + silent_payment_fee(1, 1);
+        let funding = SilentPaymentOutput {
+            output: BtcOutput {
+                txid: "ab".repeat(32),
+                amount_sat: funding_sat,
+                ..Default::default()
+            },
+            tweak: tweak.clone(),
+        };
+        let recipient =
+            decode_silent_payment_address(&silent_payment_keys().address(label, BtcNetwork::Regtest)).unwrap();
+
+        let built = sender.build_transaction(&funding, &recipient, 1).unwrap();
+
+        ChainTransaction {
+            transaction: built.transaction,
+            prevouts: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(funding_sat),
+                script_pubkey: sender.output_script(&tweak).unwrap(),
+            }],
+        }
+    }
+
+    fn expect_no_recent_deposits(store: &mut MockAppStoreBuilder, wallet: &mut MockBitcoinWallet) {
+        wallet.expect_block_height().times(1).returning(|| Ok(1_000));
+        store
+            .btc_output
+            .expect_find_unconfirmed_or_since()
+            .withf(|min_block_height| *min_block_height == 994)
+            .times(1)
+            .returning(|_| Ok(vec![]));
+    }
+
     fn btc_address(wallet_id: Uuid, address: &str) -> BtcAddress {
         BtcAddress {
             id: Uuid::new_v4(),
@@ -200,6 +826,8 @@ mod tests {
             address: address.to_string(),
             used: false,
             address_type: BtcAddressType::P2wpkh,
+            payjoin_uri: None,
+            invoice_id: None,
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -234,6 +862,30 @@ mod tests {
                 let address = service.new_deposit_address(wallet_id, None).await.unwrap();
 
                 assert_eq!(address.address, "bc1qreused");
+                assert_eq!(address.payjoin_uri, None);
+            }
+        }
+
+        mod when_payjoin_is_enabled {
+            use super::*;
+
+            #[tokio::test]
+            async fn advertises_the_payjoin_endpoint() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_wallet_unused()
+                    .times(1)
+                    .returning(move |wallet_id, _| Ok(Some(btc_address(wallet_id, "bc1qreused"))));
+
+                let service = payjoin_service(store, MockBitcoinWallet::new(), MockBitcoinChain::new());
+
+                let address = service.new_deposit_address(Uuid::new_v4(), None).await.unwrap();
+
+                assert_eq!(
+                    address.payjoin_uri.as_deref(),
+                    Some("bitcoin:bc1qreused?pj=https://api.numeraire.tech/v1/payjoin")
+                );
             }
         }
 
@@ -270,18 +922,18 @@ mod tests {
                 assert_eq!(address.address, "bc1qfresh");
             }
         }
-    }
 
-    mod get_address {
-        use super::*;
-
-        mod when_missing {
+        mod when_requesting_a_silent_payment_address {
             use super::*;
 
             #[tokio::test]
-            async fn returns_not_found() {
+            async fn fails_when_silent_payments_are_disabled() {
                 let mut store = MockAppStoreBuilder::new();
-                store.btc_address.expect_find().times(1).returning(|_| Ok(None));
+                store
+                    .btc_address
+                    .expect_find_by_wallet_unused()
+                    .times(1)
+                    .returning(|_, _| Ok(None));
 
                 let service = service(
                     store,
@@ -290,64 +942,736 @@ mod tests {
                     MockSystemUseCases::new(),
                 );
 
-                let err = service.get_address(Uuid::new_v4()).await.unwrap_err();
+                let err = service
+                    .new_deposit_address(Uuid::new_v4(), Some(BtcAddressType::SilentPayment))
+                    .await
+                    .unwrap_err();
 
-                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+                assert!(matches!(err, ApplicationError::Bitcoin(BitcoinError::AddressType(_))));
             }
-        }
-    }
-
-    mod delete_address {
-        use super::*;
-
-        mod when_nothing_is_removed {
-            use super::*;
 
             #[tokio::test]
-            async fn returns_not_found() {
+            async fn issues_the_address_with_the_next_label() {
+                let wallet_id = Uuid::new_v4();
+                let expected = silent_payment_keys().address(4, BtcNetwork::Regtest);
+                let inserted = expected.clone();
+
                 let mut store = MockAppStoreBuilder::new();
-                store.btc_address.expect_delete_many().times(1).returning(|_| Ok(0));
+                store
+                    .btc_address
+                    .expect_find_by_wallet_unused()
+                    .withf(|_, address_type| *address_type == BtcAddressType::SilentPayment)
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .btc_address
+                    .expect_max_silent_payment_label()
+                    .times(1)
+                    .returning(|| Ok(Some(3)));
+                store
+                    .btc_address
+                    .expect_insert_silent_payment()
+                    .withf(move |id, address, label| *id == wallet_id && address == inserted && *label == 4)
+                    .times(1)
+                    .returning(|wallet_id, address, _| {
+                        Ok(Some(BtcAddress {
+                            address_type: BtcAddressType::SilentPayment,
+                            ..btc_address(wallet_id, address)
+                        }))
+                    });
 
-                let service = service(
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet.expect_new_address().never();
+
+                let service = silent_payment_service(
                     store,
-                    MockBitcoinWallet::new(),
+                    wallet,
                     MockEventUseCases::new(),
                     MockSystemUseCases::new(),
+                    MockBitcoinChain::new(),
                 );
 
-                let err = service.delete_address(Uuid::new_v4()).await.unwrap_err();
+                let address = service
+                    .new_deposit_address(wallet_id, Some(BtcAddressType::SilentPayment))
+                    .await
+                    .unwrap();
 
-                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+                assert_eq!(address.address, expected);
+                assert!(address.address.starts_with("sprt1q"));
             }
-        }
-    }
-
-    mod sync {
-        use super::*;
-
-        mod with_an_existing_cursor_and_mixed_events {
-            use super::*;
 
             #[tokio::test]
-            async fn projects_each_event_and_advances_the_cursor() {
-                let mut system = MockSystemUseCases::new();
-                system
-                    .expect_get_onchain_cursor()
+            async fn retries_with_the_next_label_when_taken_concurrently() {
+                let expected = silent_payment_keys().address(5, BtcNetwork::Regtest);
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_wallet_unused()
                     .times(1)
-                    .returning(|| Ok(Some(OnchainSyncCursor::BlockHeight(100))));
-                system
-                    .expect_set_onchain_cursor()
-                    .withf(|cursor| *cursor == OnchainSyncCursor::BlockHeight(200))
+                    .returning(|_, _| Ok(None));
+                let mut sequence = mockall::Sequence::new();
+                store
+                    .btc_address
+                    .expect_max_silent_payment_label()
                     .times(1)
-                    .returning(|_| Ok(()));
-
-                let mut wallet = MockBitcoinWallet::new();
-                wallet.expect_network().returning(|| BtcNetwork::Regtest);
-                wallet.expect_synchronize().times(1).returning(|_| {
-                    Ok(OnchainSyncBatch {
-                        events: vec![
-                            OnchainTransaction::Deposit(BtcOutput {
-                                amount_sat: 1_000,
+                    .in_sequence(&mut sequence)
+                    .returning(|| Ok(Some(3)));
+                store
+                    .btc_address
+                    .expect_insert_silent_payment()
+                    .withf(|_, _, label| *label == 4)
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|_, _, _| Ok(None));
+                store
+                    .btc_address
+                    .expect_max_silent_payment_label()
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|| Ok(Some(4)));
+                store
+                    .btc_address
+                    .expect_insert_silent_payment()
+                    .withf(|_, _, label| *label == 5)
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|wallet_id, address, _| Ok(Some(btc_address(wallet_id, address))));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+
+                let service = silent_payment_service(
+                    store,
+                    wallet,
+                    MockEventUseCases::new(),
+                    MockSystemUseCases::new(),
+                    MockBitcoinChain::new(),
+                );
+
+                let address = service
+                    .new_deposit_address(Uuid::new_v4(), Some(BtcAddressType::SilentPayment))
+                    .await
+                    .unwrap();
+
+                assert_eq!(address.address, expected);
+            }
+
+            #[tokio::test]
+            async fn gives_up_when_labels_keep_being_taken() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_wallet_unused()
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .btc_address
+                    .expect_max_silent_payment_label()
+                    .times(SILENT_PAYMENT_LABEL_ATTEMPTS)
+                    .returning(|| Ok(None));
+                store
+                    .btc_address
+                    .expect_insert_silent_payment()
+                    .times(SILENT_PAYMENT_LABEL_ATTEMPTS)
+                    .returning(|_, _, _| Ok(None));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+
+                let service = silent_payment_service(
+                    store,
+                    wallet,
+                    MockEventUseCases::new(),
+                    MockSystemUseCases::new(),
+                    MockBitcoinChain::new(),
+                );
+
+                let err = service
+                    .new_deposit_address(Uuid::new_v4(), Some(BtcAddressType::SilentPayment))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+            }
+        }
+    }
+
+    mod get_address {
+        use super::*;
+
+        mod when_missing {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_not_found() {
+                let mut store = MockAppStoreBuilder::new();
+                store.btc_address.expect_find().times(1).returning(|_| Ok(None));
+
+                let service = service(
+                    store,
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                    MockSystemUseCases::new(),
+                );
+
+                let err = service.get_address(Uuid::new_v4()).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+    }
+
+    mod delete_address {
+        use super::*;
+
+        mod when_nothing_is_removed {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_not_found() {
+                let mut store = MockAppStoreBuilder::new();
+                store.btc_address.expect_delete_many().times(1).returning(|_| Ok(0));
+
+                let service = service(
+                    store,
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                    MockSystemUseCases::new(),
+                );
+
+                let err = service.delete_address(Uuid::new_v4()).await.unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+    }
+
+    mod estimate_feerates {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_the_node_feerate_for_each_target() {
+            let mut wallet = MockBitcoinWallet::new();
+            wallet
+                .expect_estimate_feerate()
+                .times(FEE_ESTIMATE_CONF_TARGETS.len())
+                .returning(|conf_target| Ok(if conf_target == 1 { 20 } else { 5 }));
+
+            let service = service(
+                MockAppStoreBuilder::new(),
+                wallet,
+                MockEventUseCases::new(),
+                MockSystemUseCases::new(),
+            );
+
+            let feerates = service.estimate_feerates().await.unwrap();
+
+            assert_eq!(feerates.len(), FEE_ESTIMATE_CONF_TARGETS.len());
+            assert_eq!(
+                feerates[0],
+                BtcFeerate {
+                    conf_target: 1,
+                    feerate_sat_vb: 20
+                }
+            );
+            assert!(feerates[1..].iter().all(|feerate| feerate.feerate_sat_vb == 5));
+        }
+    }
+
+    mod payjoin_proposal {
+        use bitcoin::{
+            absolute::LockTime, hashes::Hash, psbt::Psbt, transaction::Version, Amount, Network, OutPoint, ScriptBuf,
+            Sequence, Transaction, TxIn, TxOut, Txid, WPubkeyHash, Witness,
+        };
+
+        use crate::domains::bitcoin::{BtcLockedUtxo, BtcUtxo};
+
+        use super::*;
+
+        mod when_payjoin_is_disabled {
+            use super::*;
+
+            #[tokio::test]
+            async fn is_unavailable() {
+                let service = service(
+                    MockAppStoreBuilder::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                    MockSystemUseCases::new(),
+                );
+
+                let err = service
+                    .payjoin_proposal("original".to_string(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Payjoin(PayjoinError::Unavailable(_))));
+            }
+        }
+
+        mod with_an_unsupported_version {
+            use super::*;
+
+            #[tokio::test]
+            async fn is_rejected_before_reading_the_psbt() {
+                let service = payjoin_service(
+                    MockAppStoreBuilder::new(),
+                    MockBitcoinWallet::new(),
+                    MockBitcoinChain::new(),
+                );
+                let params = PayjoinQueryParams {
+                    v: Some(2),
+                    ..Default::default()
+                };
+
+                let err = service
+                    .payjoin_proposal("original".to_string(), params)
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Payjoin(PayjoinError::VersionUnsupported(_))
+                ));
+            }
+        }
+
+        const PAYEE: u8 = 2;
+        const RECEIVER_UTXO: u8 = 4;
+
+        fn script(byte: u8) -> ScriptBuf {
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([byte; 20]))
+        }
+
+        fn address(byte: u8) -> String {
+            script_address(&script(byte), Network::Regtest).unwrap()
+        }
+
+        /// Sender's signed original: 100_000 sat in, 50_000 to our deposit address, 49_000 back as change.
+        fn original_psbt() -> String {
+            let tx = Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![TxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_byte_array([1; 32]),
+                        vout: 0,
+                    },
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    ..Default::default()
+                }],
+                output: vec![
+                    TxOut {
+                        value: Amount::from_sat(50_000),
+                        script_pubkey: script(PAYEE),
+                    },
+                    TxOut {
+                        value: Amount::from_sat(49_000),
+                        script_pubkey: script(3),
+                    },
+                ],
+            };
+
+            let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+            psbt.inputs[0].witness_utxo = Some(TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: script(1),
+            });
+            psbt.inputs[0].final_script_witness = Some(Witness::from_slice(&[vec![0u8; 72], vec![2u8; 33]]));
+            encode_psbt(&psbt)
+        }
+
+        fn receiver_utxo() -> BtcUtxo {
+            BtcUtxo {
+                txid: Txid::from_byte_array([RECEIVER_UTXO; 32]).to_string(),
+                output_index: 1,
+                address: address(RECEIVER_UTXO),
+                amount_sat: 20_000,
+            }
+        }
+
+        fn locked_utxo() -> BtcLockedUtxo {
+            let utxo = receiver_utxo();
+            BtcLockedUtxo {
+                id: "lease".to_string(),
+                txid: utxo.txid,
+                output_index: utxo.output_index,
+            }
+        }
+
+        /// Store where the original pays a deposit address and its inputs were never seen.
+        fn receiving_store() -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            store.btc_address.expect_find_by_address().returning(|address| {
+                Ok((address == self::address(PAYEE)).then(|| btc_address(Uuid::new_v4(), address)))
+            });
+            store.payjoin.expect_insert_inputs().times(1).returning(|_| Ok(true));
+            store
+        }
+
+        fn accepting_chain() -> MockBitcoinChain {
+            let mut chain = MockBitcoinChain::new();
+            chain.expect_test_mempool_accept().times(1).returning(|_| Ok(None));
+            chain
+        }
+
+        /// Wallet owning `receiver_utxo`, signing its input with a dummy witness.
+        fn receiving_wallet() -> MockBitcoinWallet {
+            let mut wallet = MockBitcoinWallet::new();
+            wallet.expect_network().returning(|| BtcNetwork::Regtest);
+            wallet
+                .expect_list_unspent()
+                .times(1)
+                .returning(|| Ok(vec![receiver_utxo()]));
+            wallet
+        }
+
+        fn sign_our_inputs(psbt: &str) -> String {
+            let mut psbt = decode_psbt(psbt).unwrap();
+            for (input, txin) in psbt.inputs.iter_mut().zip(&psbt.unsigned_tx.input) {
+                if txin.previous_output.txid == Txid::from_byte_array([RECEIVER_UTXO; 32]) {
+                    input.final_script_witness = Some(Witness::from_slice(&[vec![0u8; 72], vec![2u8; 33]]));
+                }
+            }
+            encode_psbt(&psbt)
+        }
+
+        mod with_a_valid_original {
+            use super::*;
+
+            #[tokio::test]
+            async fn locks_the_contributed_output_and_schedules_the_fallback() {
+                let mut store = receiving_store();
+                store
+                    .payjoin
+                    .expect_insert_contribution()
+                    .withf(|outpoint, amount_sat| outpoint.ends_with(":0") && (1..20_000).contains(amount_sat))
+                    .times(1)
+                    .returning(|_, _| Ok(()));
+                store
+                    .payjoin
+                    .expect_insert_fallback()
+                    .withf(|fallback| {
+                        fallback.locked_utxo.id == "lease"
+                            && !fallback.tx_hex.is_empty()
+                            && fallback.broadcast_after > Utc::now() + chrono::Duration::seconds(30)
+                    })
+                    .times(1)
+                    .returning(|_| Ok(()));
+
+                let mut wallet = receiving_wallet();
+                let mut sequence = mockall::Sequence::new();
+                wallet
+                    .expect_lock_utxo()
+                    .withf(|utxo, expiry| utxo.txid == receiver_utxo().txid && *expiry > Duration::from_secs(60))
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|_, _| Ok(locked_utxo()));
+                wallet
+                    .expect_sign_psbt()
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|psbt| Ok(sign_our_inputs(psbt)));
+                wallet.expect_unlock_utxo().never();
+
+                let service = payjoin_service(store, wallet, accepting_chain());
+                let proposal = service
+                    .payjoin_proposal(original_psbt(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap();
+
+                let proposal = decode_psbt(&proposal).unwrap();
+                assert_eq!(proposal.unsigned_tx.input.len(), 2);
+                assert_eq!(proposal.unsigned_tx.output.len(), 2);
+                assert!(proposal.unsigned_tx.output[0].value > Amount::from_sat(50_000));
+            }
+
+            #[tokio::test]
+            async fn unlocks_the_output_when_signing_fails() {
+                let mut wallet = receiving_wallet();
+                wallet.expect_lock_utxo().times(1).returning(|_, _| Ok(locked_utxo()));
+                wallet
+                    .expect_sign_psbt()
+                    .times(1)
+                    .returning(|_| Err(BitcoinError::SignPsbt("locked wallet".to_string())));
+                wallet
+                    .expect_unlock_utxo()
+                    .withf(|utxo| utxo.id == "lease")
+                    .times(1)
+                    .returning(|_| Ok(()));
+
+                let service = payjoin_service(receiving_store(), wallet, accepting_chain());
+                let err = service
+                    .payjoin_proposal(original_psbt(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Bitcoin(BitcoinError::SignPsbt(_))));
+            }
+        }
+
+        mod when_no_output_can_be_contributed {
+            use super::*;
+
+            #[tokio::test]
+            async fn locks_nothing() {
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet.expect_list_unspent().times(1).returning(|| Ok(vec![]));
+                wallet.expect_lock_utxo().never();
+
+                let service = payjoin_service(receiving_store(), wallet, accepting_chain());
+                let err = service
+                    .payjoin_proposal(original_psbt(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Payjoin(PayjoinError::Unavailable(_))));
+            }
+        }
+
+        mod when_the_original_cannot_be_broadcast {
+            use super::*;
+
+            #[tokio::test]
+            async fn rejects_it_without_recording_its_inputs() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .returning(|address| Ok(Some(btc_address(Uuid::new_v4(), address))));
+                store.payjoin.expect_insert_inputs().never();
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet.expect_list_unspent().never();
+
+                let mut chain = MockBitcoinChain::new();
+                chain
+                    .expect_test_mempool_accept()
+                    .times(1)
+                    .returning(|_| Ok(Some("missing-inputs".to_string())));
+
+                let service = payjoin_service(store, wallet, chain);
+                let err = service
+                    .payjoin_proposal(original_psbt(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Payjoin(PayjoinError::OriginalPsbtRejected(reason)) if reason.contains("missing-inputs")
+                ));
+            }
+        }
+
+        mod when_the_inputs_were_already_seen {
+            use super::*;
+
+            #[tokio::test]
+            async fn rejects_the_original() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .returning(|address| Ok(Some(btc_address(Uuid::new_v4(), address))));
+                store.payjoin.expect_insert_inputs().times(1).returning(|_| Ok(false));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet.expect_list_unspent().never();
+
+                let service = payjoin_service(store, wallet, accepting_chain());
+                let err = service
+                    .payjoin_proposal(original_psbt(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Payjoin(PayjoinError::OriginalPsbtRejected(_))
+                ));
+            }
+        }
+
+        mod with_an_invalid_psbt {
+            use super::*;
+
+            #[tokio::test]
+            async fn rejects_the_original() {
+                let service = payjoin_service(
+                    MockAppStoreBuilder::new(),
+                    MockBitcoinWallet::new(),
+                    MockBitcoinChain::new(),
+                );
+
+                let err = service
+                    .payjoin_proposal("not a psbt".to_string(), PayjoinQueryParams::default())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Payjoin(PayjoinError::OriginalPsbtRejected(_))
+                ));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn deposit_event_credits_payjoin_deposits_for_what_the_sender_paid() {
+        let mut store = MockAppStoreBuilder::new();
+        store
+            .payjoin
+            .expect_find_contribution()
+            .withf(|outpoint| outpoint == "payjoin:0")
+            .times(1)
+            .returning(|_| Ok(Some(19_500)));
+        let service = payjoin_service(store, MockBitcoinWallet::new(), MockBitcoinChain::new());
+
+        let output = BtcOutput {
+            txid: "payjoin".to_string(),
+            output_index: 0,
+            amount_sat: 69_500,
+            ..Default::default()
+        };
+        let event = service.deposit_event(output, 100).await.unwrap();
+
+        assert_eq!(event.amount_sat, 50_000);
+        assert_eq!(event.tip_height, Some(100));
+    }
+
+    mod broadcast_payjoin_fallbacks {
+        use crate::domains::bitcoin::{BtcLockedUtxo, PayjoinFallback};
+
+        use super::*;
+
+        fn due_fallback() -> PayjoinFallback {
+            PayjoinFallback {
+                id: Uuid::new_v4(),
+                txid: "original".to_string(),
+                tx_hex: "0200".to_string(),
+                locked_utxo: BtcLockedUtxo {
+                    id: "lease".to_string(),
+                    txid: "utxo".to_string(),
+                    output_index: 1,
+                },
+                broadcast_after: Utc::now(),
+            }
+        }
+
+        fn store_with_due_fallback(n_deleted: usize) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .payjoin
+                .expect_find_due_fallbacks()
+                .times(1)
+                .returning(|| Ok(vec![due_fallback()]));
+            store
+                .payjoin
+                .expect_delete_fallback()
+                .times(n_deleted)
+                .returning(|_| Ok(()));
+            store
+        }
+
+        mod when_the_sender_did_not_broadcast_the_payjoin {
+            use super::*;
+
+            #[tokio::test]
+            async fn broadcasts_the_original_and_unlocks_the_output() {
+                let mut chain = MockBitcoinChain::new();
+                chain
+                    .expect_broadcast_transaction()
+                    .withf(|tx_hex| tx_hex == "0200")
+                    .times(1)
+                    .returning(|_| Ok("original".to_string()));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet
+                    .expect_unlock_utxo()
+                    .withf(|utxo| utxo.id == "lease")
+                    .times(1)
+                    .returning(|_| Ok(()));
+
+                let service = payjoin_service(store_with_due_fallback(1), wallet, chain);
+                let payjoin = service.payjoin.clone().unwrap();
+
+                service.broadcast_payjoin_fallbacks(&payjoin).await.unwrap();
+            }
+        }
+
+        mod when_the_payjoin_confirmed {
+            use super::*;
+
+            #[tokio::test]
+            async fn keeps_the_output_locked_and_drops_the_fallback() {
+                let mut chain = MockBitcoinChain::new();
+                chain
+                    .expect_broadcast_transaction()
+                    .times(1)
+                    .returning(|_| Err(BitcoinError::InputsSpent("bad-txns-inputs-missingorspent".to_string())));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_unlock_utxo().never();
+
+                let service = payjoin_service(store_with_due_fallback(1), wallet, chain);
+                let payjoin = service.payjoin.clone().unwrap();
+
+                service.broadcast_payjoin_fallbacks(&payjoin).await.unwrap();
+            }
+        }
+
+        mod when_the_original_cannot_be_broadcast_yet {
+            use super::*;
+
+            #[tokio::test]
+            async fn keeps_the_fallback_for_the_next_sync() {
+                let mut chain = MockBitcoinChain::new();
+                chain
+                    .expect_broadcast_transaction()
+                    .times(1)
+                    .returning(|_| Err(BitcoinError::BroadcastTransaction("txn-mempool-conflict".to_string())));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_unlock_utxo().never();
+
+                let service = payjoin_service(store_with_due_fallback(0), wallet, chain);
+                let payjoin = service.payjoin.clone().unwrap();
+
+                service.broadcast_payjoin_fallbacks(&payjoin).await.unwrap();
+            }
+        }
+    }
+
+    mod sync {
+        use super::*;
+
+        mod with_an_existing_cursor_and_mixed_events {
+            use super::*;
+
+            #[tokio::test]
+            async fn projects_each_event_and_advances_the_cursor() {
+                let mut system = MockSystemUseCases::new();
+                system
+                    .expect_get_onchain_cursor()
+                    .times(1)
+                    .returning(|| Ok(Some(OnchainSyncCursor::BlockHeight(100))));
+                system
+                    .expect_set_onchain_cursor()
+                    .withf(|cursor| *cursor == OnchainSyncCursor::BlockHeight(200))
+                    .times(1)
+                    .returning(|_| Ok(()));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet.expect_synchronize().times(1).returning(|_| {
+                    Ok(OnchainSyncBatch {
+                        events: vec![
+                            OnchainTransaction::Deposit(BtcOutput {
+                                amount_sat: 1_000,
                                 ..Default::default()
                             }),
                             OnchainTransaction::Withdrawal(OnchainWithdrawalEvent {
@@ -363,7 +1687,10 @@ mod tests {
                 events.expect_onchain_deposit().times(1).returning(|_| Ok(true));
                 events.expect_onchain_withdrawal().times(1).returning(|_| Ok(true));
 
-                let service = service(MockAppStoreBuilder::new(), wallet, events, system);
+                let mut store = MockAppStoreBuilder::new();
+                expect_no_recent_deposits(&mut store, &mut wallet);
+
+                let service = service(store, wallet, events, system);
 
                 assert_eq!(service.sync().await.unwrap(), 2);
             }
@@ -397,11 +1724,385 @@ mod tests {
                     .withf(|cursor| *cursor == Some(OnchainSyncCursor::BlockHeight(50)))
                     .times(1)
                     .returning(|_| Ok(OnchainSyncBatch::default()));
+                expect_no_recent_deposits(&mut store, &mut wallet);
 
                 let service = service(store, wallet, MockEventUseCases::new(), system);
 
                 assert_eq!(service.sync().await.unwrap(), 0);
             }
         }
+
+        mod with_recent_deposits {
+            use super::*;
+
+            fn stored_output(outpoint: &str, status: BtcOutputStatus, block_height: Option<u32>) -> BtcOutput {
+                BtcOutput {
+                    outpoint: outpoint.to_string(),
+                    txid: outpoint.to_string(),
+                    status,
+                    block_height,
+                    amount_sat: 1_000,
+                    ..Default::default()
+                }
+            }
+
+            #[tokio::test]
+            async fn re_projects_maturing_and_reorged_outputs_only() {
+                let mut system = MockSystemUseCases::new();
+                system
+                    .expect_get_onchain_cursor()
+                    .times(1)
+                    .returning(|| Ok(Some(OnchainSyncCursor::BlockHeight(1_000))));
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_output
+                    .expect_find_unconfirmed_or_since()
+                    .withf(|min_block_height| *min_block_height == 994)
+                    .times(1)
+                    .returning(|_| {
+                        Ok(vec![
+                            stored_output("maturing", BtcOutputStatus::Unconfirmed, Some(999)),
+                            stored_output("reorged", BtcOutputStatus::Confirmed, Some(998)),
+                            stored_output("stable", BtcOutputStatus::Confirmed, Some(997)),
+                        ])
+                    });
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet
+                    .expect_synchronize()
+                    .times(1)
+                    .returning(|_| Ok(OnchainSyncBatch::default()));
+                wallet.expect_block_height().times(1).returning(|| Ok(1_000));
+                wallet
+                    .expect_get_output()
+                    .times(3)
+                    .returning(|txid, _, _, _| match txid {
+                        "maturing" => Ok(Some(stored_output(txid, BtcOutputStatus::Unconfirmed, Some(999)))),
+                        "reorged" => Ok(Some(stored_output(txid, BtcOutputStatus::Unconfirmed, None))),
+                        _ => Ok(Some(stored_output(txid, BtcOutputStatus::Confirmed, Some(997)))),
+                    });
+
+                let mut events = MockEventUseCases::new();
+                events
+                    .expect_onchain_deposit()
+                    .withf(|event| {
+                        (event.txid == "maturing" || event.txid == "reorged") && event.tip_height == Some(1_000)
+                    })
+                    .times(2)
+                    .returning(|_| Ok(true));
+
+                let service = service(store, wallet, events, system);
+
+                assert_eq!(service.sync().await.unwrap(), 2);
+            }
+
+            #[tokio::test]
+            async fn drops_outputs_no_longer_known_to_the_wallet() {
+                let mut system = MockSystemUseCases::new();
+                system
+                    .expect_get_onchain_cursor()
+                    .times(1)
+                    .returning(|| Ok(Some(OnchainSyncCursor::BlockHeight(1_000))));
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_output
+                    .expect_find_unconfirmed_or_since()
+                    .times(1)
+                    .returning(|_| {
+                        Ok(vec![stored_output(
+                            "double-spent",
+                            BtcOutputStatus::Confirmed,
+                            Some(998),
+                        )])
+                    });
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet
+                    .expect_synchronize()
+                    .times(1)
+                    .returning(|_| Ok(OnchainSyncBatch::default()));
+                wallet.expect_block_height().times(1).returning(|| Ok(1_000));
+                wallet.expect_get_output().times(1).returning(|_, _, _, _| Ok(None));
+
+                let mut events = MockEventUseCases::new();
+                events.expect_onchain_deposit().never();
+                events
+                    .expect_onchain_deposit_dropped()
+                    .withf(|output| output.outpoint == "double-spent")
+                    .times(1)
+                    .returning(|_| Ok(true));
+
+                let service = service(store, wallet, events, system);
+
+                assert_eq!(service.sync().await.unwrap(), 1);
+            }
+        }
+    }
+
+    mod scan_silent_payments {
+        use super::*;
+
+        #[tokio::test]
+        async fn projects_payments_to_issued_labels_from_the_birth_height() {
+            let wallet_id = Uuid::new_v4();
+            let transaction = silent_payment_transaction(2, 25_000);
+            let txid = transaction.transaction.compute_txid().to_string();
+
+            let mut system = MockSystemUseCases::new();
+            system
+                .expect_get_silent_payment_cursor()
+                .times(1)
+                .returning(|| Ok(None));
+            system
+                .expect_set_silent_payment_cursor()
+                .withf(|cursor| cursor.height == 100 && cursor.hash == "hash100")
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let mut chain = MockBitcoinChain::new();
+            chain.expect_block_height().times(1).returning(|| Ok(100));
+            chain
+                .expect_block()
+                .withf(|height| *height == 100)
+                .times(1)
+                .returning(move |_| {
+                    Ok(ChainBlock {
+                        hash: "hash100".to_string(),
+                        transactions: vec![transaction.clone()],
+                    })
+                });
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .btc_address
+                .expect_find_silent_payment_labels()
+                .times(1)
+                .returning(move || Ok(vec![(2, wallet_id)]));
+            store
+                .btc_output
+                .expect_find_silent_payment_outputs()
+                .times(1)
+                .returning(|_| Ok(vec![]));
+            store
+                .btc_output
+                .expect_find_by_outpoint()
+                .times(1)
+                .returning(|_| Ok(None));
+            store
+                .btc_address
+                .expect_find_by_address()
+                .times(1)
+                .returning(|_| Ok(None));
+            store
+                .btc_address
+                .expect_insert_silent_payment_output()
+                .withf(move |id, address, tweak| *id == wallet_id && address.starts_with("bcrt1p") && tweak.len() == 64)
+                .times(1)
+                .returning(|wallet_id, address, _| Ok(btc_address(wallet_id, address)));
+
+            let mut wallet = MockBitcoinWallet::new();
+            wallet.expect_network().returning(|| BtcNetwork::Regtest);
+
+            let mut events = MockEventUseCases::new();
+            events
+                .expect_onchain_deposit()
+                .withf(move |event| {
+                    event.txid == txid
+                        && event.output_index == 0
+                        && event.amount_sat == 25_000
+                        && event.block_height == Some(100)
+                })
+                .times(1)
+                .returning(|_| Ok(true));
+
+            let service = silent_payment_service(store, wallet, events, system, chain);
+            let silent_payments = service.silent_payments.clone().unwrap();
+
+            assert_eq!(service.scan_silent_payments(&silent_payments).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn rescans_the_reorg_window_when_the_last_scanned_block_changed() {
+            let mut system = MockSystemUseCases::new();
+            system.expect_get_silent_payment_cursor().times(1).returning(|| {
+                Ok(Some(SilentPaymentScanCursor {
+                    height: 200,
+                    hash: "stale".to_string(),
+                }))
+            });
+            system.expect_set_silent_payment_cursor().times(7).returning(|_| Ok(()));
+
+            let mut chain = MockBitcoinChain::new();
+            chain.expect_block_height().times(1).returning(|| Ok(200));
+            chain
+                .expect_block_hash()
+                .withf(|height| *height == 200)
+                .times(1)
+                .returning(|_| Ok("replacement".to_string()));
+            chain
+                .expect_block()
+                .withf(|height| (194..=200).contains(height))
+                .times(7)
+                .returning(|height| {
+                    Ok(ChainBlock {
+                        hash: format!("hash{height}"),
+                        transactions: vec![],
+                    })
+                });
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .btc_address
+                .expect_find_silent_payment_labels()
+                .times(1)
+                .returning(|| Ok(vec![]));
+            store
+                .btc_output
+                .expect_find_silent_payment_outputs()
+                .times(1)
+                .returning(|_| Ok(vec![]));
+
+            let service =
+                silent_payment_service(store, MockBitcoinWallet::new(), MockEventUseCases::new(), system, chain);
+            let silent_payments = service.silent_payments.clone().unwrap();
+
+            assert_eq!(service.scan_silent_payments(&silent_payments).await.unwrap(), 0);
+        }
+    }
+
+    mod sweep_silent_payments {
+        use super::*;
+
+        fn confirmed_output(amount_sat: u64, output_index: u32) -> SilentPaymentOutput {
+            SilentPaymentOutput {
+                output: BtcOutput {
+                    outpoint: format!("{}:{output_index}", "cd".repeat(32)),
+                    txid: "cd".repeat(32),
+                    output_index,
+                    amount_sat,
+                    status: BtcOutputStatus::Confirmed,
+                    ..Default::default()
+                },
+                tweak: "2b".repeat(32),
+            }
+        }
+
+        fn node_wallet() -> MockBitcoinWallet {
+            let mut wallet = MockBitcoinWallet::new();
+            wallet.expect_network().returning(|| BtcNetwork::Regtest);
+            wallet
+                .expect_estimate_feerate()
+                .withf(|conf_target| *conf_target == SILENT_PAYMENT_SWEEP_CONF_TARGET)
+                .times(1)
+                .returning(|_| Ok(2));
+            wallet
+                .expect_new_address()
+                .withf(|address_type| *address_type == BtcAddressType::P2tr)
+                .times(1)
+                .returning(|_| {
+                    let script = silent_payment_keys().output_script(&"3c".repeat(32)).unwrap();
+                    Ok(script_address(&script, bitcoin::Network::Regtest).unwrap())
+                });
+            wallet
+        }
+
+        #[tokio::test]
+        async fn sweeps_the_confirmed_outputs_to_the_node_wallet() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .btc_output
+                .expect_find_silent_payment_outputs()
+                .withf(|status| *status == BtcOutputStatus::Confirmed)
+                .times(1)
+                .returning(|_| Ok(vec![confirmed_output(20_000, 0), confirmed_output(5_000, 1)]));
+            store
+                .btc_output
+                .expect_mark_spent()
+                .withf(|outpoints| {
+                    *outpoints == vec![format!("{}:0", "cd".repeat(32)), format!("{}:1", "cd".repeat(32))]
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let mut chain = MockBitcoinChain::new();
+            chain
+                .expect_broadcast_transaction()
+                .withf(|tx_hex| {
+                    let transaction: bitcoin::Transaction =
+                        bitcoin::consensus::encode::deserialize_hex(tx_hex).unwrap();
+                    transaction.input.len() == 2
+                        && transaction.output[0].value.to_sat() == 25_000 - silent_payment_fee(2, 2)
+                })
+                .times(1)
+                .returning(|_| Ok("sweep".to_string()));
+
+            let service = silent_payment_service(
+                store,
+                node_wallet(),
+                MockEventUseCases::new(),
+                MockSystemUseCases::new(),
+                chain,
+            );
+            let silent_payments = service.silent_payments.clone().unwrap();
+
+            service.sweep_silent_payments(&silent_payments).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn leaves_outputs_too_small_to_cover_the_fee() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .btc_output
+                .expect_find_silent_payment_outputs()
+                .times(1)
+                .returning(|_| Ok(vec![confirmed_output(600, 0)]));
+            store.btc_output.expect_mark_spent().never();
+
+            let mut chain = MockBitcoinChain::new();
+            chain.expect_broadcast_transaction().never();
+
+            let service = silent_payment_service(
+                store,
+                node_wallet(),
+                MockEventUseCases::new(),
+                MockSystemUseCases::new(),
+                chain,
+            );
+            let silent_payments = service.silent_payments.clone().unwrap();
+
+            service.sweep_silent_payments(&silent_payments).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn keeps_the_outputs_when_the_sweep_is_not_broadcast() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .btc_output
+                .expect_find_silent_payment_outputs()
+                .times(1)
+                .returning(|_| Ok(vec![confirmed_output(20_000, 0)]));
+            store.btc_output.expect_mark_spent().never();
+
+            let mut chain = MockBitcoinChain::new();
+            chain
+                .expect_broadcast_transaction()
+                .times(1)
+                .returning(|_| Err(BitcoinError::BroadcastTransaction("rejected".to_string())));
+
+            let service = silent_payment_service(
+                store,
+                node_wallet(),
+                MockEventUseCases::new(),
+                MockSystemUseCases::new(),
+                chain,
+            );
+            let silent_payments = service.silent_payments.clone().unwrap();
+
+            service.sweep_silent_payments(&silent_payments).await.unwrap();
+        }
     }
 }
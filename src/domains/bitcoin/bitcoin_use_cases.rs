@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     application::errors::ApplicationError,
-    domains::bitcoin::{BtcAddressFilter, BtcAddressType},
+    domains::bitcoin::{BtcAddressFilter, BtcAddressType, BtcFeerate, PayjoinQueryParams},
 };
 
 use super::BtcAddress;
@@ -20,5 +20,14 @@ pub trait BitcoinUseCases: Send + Sync {
     async fn list_addresses(&self, filter: BtcAddressFilter) -> Result<Vec<BtcAddress>, ApplicationError>;
     async fn delete_address(&self, id: Uuid) -> Result<(), ApplicationError>;
     async fn delete_many_addresses(&self, filter: BtcAddressFilter) -> Result<u64, ApplicationError>;
+    async fn estimate_feerates(&self) -> Result<Vec<BtcFeerate>, ApplicationError>;
+
+    /// Handles a BIP78 payjoin request: contributes one of the node's outputs to the sender's original PSBT, paying
+    /// one of our deposit addresses, and returns the signed proposal PSBT as base64.
+    async fn payjoin_proposal(
+        &self,
+        original_psbt: String,
+        params: PayjoinQueryParams,
+    ) -> Result<String, ApplicationError>;
     async fn sync(&self) -> Result<u32, ApplicationError>;
 }
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use bitcoin::{Transaction, TxOut};
+
+use crate::application::errors::BitcoinError;
+
+/// Direct access to the chain through a full node, for what the node wallet cannot do such as scanning
+/// blocks for silent payments.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BitcoinChain: Sync + Send {
+    async fn block_height(&self) -> Result<u32, BitcoinError>;
+    async fn block_hash(&self, height: u32) -> Result<String, BitcoinError>;
+
+    /// Block at `height` in the active chain, with the outputs spent by each of its transactions.
+    async fn block(&self, height: u32) -> Result<ChainBlock, BitcoinError>;
+
+    /// Whether `txid` is part of the block at `height` in the active chain.
+    async fn is_in_block(&self, txid: &str, height: u32) -> Result<bool, BitcoinError>;
+
+    /// Whether the node would accept the raw transaction into its mempool. Returns the reject reason if not.
+    async fn test_mempool_accept(&self, tx_hex: &str) -> Result<Option<String>, BitcoinError>;
+
+    /// Broadcasts the raw transaction. Returns its txid, also when it is already confirmed, and
+    /// [`BitcoinError::InputsSpent`] when another transaction spending its inputs is.
+    async fn broadcast_transaction(&self, tx_hex: &str) -> Result<String, BitcoinError>;
+}
+
+#[derive(Clone, Debug)]
+pub struct ChainBlock {
+    pub hash: String,
+    pub transactions: Vec<ChainTransaction>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChainTransaction {
+    pub transaction: Transaction,
+    /// Outputs spent by the inputs of the transaction, in order. Empty for the coinbase.
+    pub prevouts: Vec<TxOut>,
+}
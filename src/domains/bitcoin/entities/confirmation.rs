@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+const DEFAULT_CONFIRMATIONS: u32 = 1;
+const DEFAULT_REORG_WINDOW: u32 = 6;
+
+/// Confirmations required once a deposit reaches `min_amount_sat`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct ConfirmationTier {
+    pub min_amount_sat: u64,
+    pub confirmations: u32,
+}
+
+/// Confirmation rule overriding the default policy for a single asset.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct AssetConfirmationPolicy {
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u32,
+    #[serde(default)]
+    pub tiers: Vec<ConfirmationTier>,
+}
+
+/// Operator policy deciding when an on-chain deposit counts toward the available balance.
+///
+/// Deposits below their required confirmations stay pending. Outputs confirmed within
+/// `reorg_window` blocks of the tip are re-checked on sync so a reorged block reverses the credit.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct DepositConfirmationPolicy {
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u32,
+    #[serde(default)]
+    pub tiers: Vec<ConfirmationTier>,
+    #[serde(default)]
+    pub assets: HashMap<Uuid, AssetConfirmationPolicy>,
+    #[serde(default = "default_reorg_window")]
+    pub reorg_window: u32,
+}
+
+impl Default for DepositConfirmationPolicy {
+    fn default() -> Self {
+        Self {
+            confirmations: DEFAULT_CONFIRMATIONS,
+            tiers: Vec::new(),
+            assets: HashMap::new(),
+            reorg_window: DEFAULT_REORG_WINDOW,
+        }
+    }
+}
+
+impl DepositConfirmationPolicy {
+    /// Confirmations a deposit of `amount_sat` into a wallet of `asset_id` needs. Never less than one.
+    pub fn required_confirmations(&self, asset_id: Uuid, amount_sat: u64) -> u32 {
+        let (confirmations, tiers) = match self.assets.get(&asset_id) {
+            Some(asset) => (asset.confirmations, &asset.tiers),
+            None => (self.confirmations, &self.tiers),
+        };
+
+        tiers
+            .iter()
+            .filter(|tier| amount_sat >= tier.min_amount_sat)
+            .map(|tier| tier.confirmations)
+            .fold(confirmations, u32::max)
+            .max(1)
+    }
+}
+
+fn default_confirmations() -> u32 {
+    DEFAULT_CONFIRMATIONS
+}
+
+fn default_reorg_window() -> u32 {
+    DEFAULT_REORG_WINDOW
+}
+
+/// Number of confirmations of an output mined at `block_height`, given the current chain tip.
+pub fn confirmations(block_height: Option<u32>, tip_height: u32) -> u32 {
+    match block_height {
+        Some(height) if height > 0 && height <= tip_height => tip_height - height + 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(min_amount_sat: u64, confirmations: u32) -> ConfirmationTier {
+        ConfirmationTier {
+            min_amount_sat,
+            confirmations,
+        }
+    }
+
+    mod required_confirmations {
+        use super::*;
+
+        #[test]
+        fn uses_the_default_without_tiers() {
+            let policy = DepositConfirmationPolicy::default();
+
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 1_000), 1);
+        }
+
+        #[test]
+        fn picks_the_highest_matching_tier() {
+            let policy = DepositConfirmationPolicy {
+                tiers: vec![tier(1_000_000, 3), tier(100_000, 2), tier(10_000_000, 6)],
+                ..Default::default()
+            };
+
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 50_000), 1);
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 100_000), 2);
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 5_000_000), 3);
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 10_000_000), 6);
+        }
+
+        #[test]
+        fn prefers_the_asset_override() {
+            let asset_id = Uuid::new_v4();
+            let policy = DepositConfirmationPolicy {
+                tiers: vec![tier(0, 6)],
+                assets: HashMap::from([(
+                    asset_id,
+                    AssetConfirmationPolicy {
+                        confirmations: 2,
+                        tiers: vec![tier(1_000_000, 4)],
+                    },
+                )]),
+                ..Default::default()
+            };
+
+            assert_eq!(policy.required_confirmations(asset_id, 1_000), 2);
+            assert_eq!(policy.required_confirmations(asset_id, 1_000_000), 4);
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 1_000), 6);
+        }
+
+        #[test]
+        fn never_requires_less_than_one() {
+            let policy = DepositConfirmationPolicy {
+                confirmations: 0,
+                ..Default::default()
+            };
+
+            assert_eq!(policy.required_confirmations(Uuid::new_v4(), 1_000), 1);
+        }
+    }
+
+    mod confirmations {
+        use super::*;
+
+        #[test]
+        fn counts_the_block_itself() {
+            assert_eq!(confirmations(Some(100), 100), 1);
+            assert_eq!(confirmations(Some(100), 102), 3);
+        }
+
+        #[test]
+        fn returns_zero_when_unmined_or_ahead_of_tip() {
+            assert_eq!(confirmations(None, 100), 0);
+            assert_eq!(confirmations(Some(0), 100), 0);
+            assert_eq!(confirmations(Some(101), 100), 0);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::application::errors::DataError;
+
+/// Confirmation targets, in blocks, quoted by fee estimates: next block, ~30 minutes, ~1 hour and ~1 day.
+pub const FEE_ESTIMATE_CONF_TARGETS: [u32; 4] = [1, 3, 6, 144];
+
+/// Longest confirmation target accepted (about one week), matching bitcoind's `estimatesmartfee` horizon.
+const MAX_CONF_TARGET: u32 = 1008;
+
+/// Highest explicit feerate accepted, a guard against fat-fingered withdrawals burning funds.
+const MAX_FEERATE_SAT_VB: u32 = 10_000;
+
+/// How the feerate of an on-chain withdrawal is chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BtcFeeSelection {
+    /// Let the node apply its own default feerate.
+    #[default]
+    NodeDefault,
+    /// Use the node's feerate estimate for confirming within this many blocks.
+    ConfTarget(u32),
+    /// Use this feerate, in satoshis per virtual byte.
+    Feerate(u32),
+}
+
+impl BtcFeeSelection {
+    pub fn new(conf_target: Option<u32>, feerate_sat_vb: Option<u32>) -> Result<Self, DataError> {
+        match (conf_target, feerate_sat_vb) {
+            (Some(_), Some(_)) => Err(DataError::Validation(
+                "Only one of conf_target or feerate_sat_vb can be set.".to_string(),
+            )),
+            (Some(target), None) if target == 0 || target > MAX_CONF_TARGET => Err(DataError::Validation(format!(
+                "conf_target must be between 1 and {MAX_CONF_TARGET} blocks."
+            ))),
+            (Some(target), None) => Ok(Self::ConfTarget(target)),
+            (None, Some(feerate)) if feerate == 0 || feerate > MAX_FEERATE_SAT_VB => Err(DataError::Validation(
+                format!("feerate_sat_vb must be between 1 and {MAX_FEERATE_SAT_VB}."),
+            )),
+            (None, Some(feerate)) => Ok(Self::Feerate(feerate)),
+            (None, None) => Ok(Self::NodeDefault),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn defaults_to_the_node_feerate() {
+            assert_eq!(BtcFeeSelection::new(None, None).unwrap(), BtcFeeSelection::NodeDefault);
+        }
+
+        #[test]
+        fn accepts_a_target_or_a_feerate() {
+            assert_eq!(
+                BtcFeeSelection::new(Some(6), None).unwrap(),
+                BtcFeeSelection::ConfTarget(6)
+            );
+            assert_eq!(
+                BtcFeeSelection::new(None, Some(12)).unwrap(),
+                BtcFeeSelection::Feerate(12)
+            );
+        }
+
+        #[test]
+        fn rejects_both_or_out_of_range_values() {
+            assert!(BtcFeeSelection::new(Some(6), Some(12)).is_err());
+            assert!(BtcFeeSelection::new(Some(0), None).is_err());
+            assert!(BtcFeeSelection::new(Some(MAX_CONF_TARGET + 1), None).is_err());
+            assert!(BtcFeeSelection::new(None, Some(0)).is_err());
+            assert!(BtcFeeSelection::new(None, Some(MAX_FEERATE_SAT_VB + 1)).is_err());
+        }
+    }
+}
@@ -1,6 +1,17 @@
+mod chain;
+mod confirmation;
+mod fee;
+mod payjoin;
 mod transaction;
 mod wallet;
 
-pub use swissknife_types::{BtcAddress, BtcAddressFilter, BtcAddressType, BtcNetwork, BtcOutput, BtcOutputStatus};
+pub use chain::*;
+pub use confirmation::*;
+pub use fee::*;
+pub use payjoin::*;
+pub use swissknife_types::{
+    BtcAddress, BtcAddressFilter, BtcAddressType, BtcFeeQuote, BtcFeerate, BtcNetwork, BtcOutput, BtcOutputStatus,
+    PayjoinErrorResponse, PayjoinQueryParams,
+};
 pub use transaction::*;
 pub use wallet::*;
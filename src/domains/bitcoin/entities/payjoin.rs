@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::BtcLockedUtxo;
+
+/// Original transaction of a payjoin we proposed, broadcast in its place if the sender never broadcasts the
+/// payjoin. The output we contributed stays locked until then.
+#[derive(Clone, Debug)]
+pub struct PayjoinFallback {
+    pub id: Uuid,
+    pub txid: String,
+    pub tx_hex: String,
+    pub locked_utxo: BtcLockedUtxo,
+    pub broadcast_after: DateTime<Utc>,
+}
@@ -10,6 +10,13 @@ pub enum OnchainSyncCursor {
     CreatedIndex(u64),
 }
 
+/// Last block scanned for silent payments. The hash detects a reorg of that block since the last scan.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SilentPaymentScanCursor {
+    pub height: u32,
+    pub hash: String,
+}
+
 #[derive(Clone, Debug)]
 pub enum OnchainTransaction {
     Deposit(BtcOutput),
@@ -39,6 +46,7 @@ impl BtcTransaction {
             address: output.address.clone(),
             amount_sat: output.amount_sat,
             block_height: self.block_height,
+            tip_height: None,
         }
     }
 
@@ -72,3 +80,12 @@ pub struct BtcLockedUtxo {
     pub txid: String,
     pub output_index: u32,
 }
+
+/// Confirmed output of the node wallet that is available for spending.
+#[derive(Clone, Debug)]
+pub struct BtcUtxo {
+    pub txid: String,
+    pub output_index: u32,
+    pub address: String,
+    pub amount_sat: u64,
+}
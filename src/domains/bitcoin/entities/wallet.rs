@@ -1,10 +1,12 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 
 use crate::{
     application::errors::BitcoinError,
     domains::bitcoin::{
-        BtcAddressType, BtcNetwork, BtcOutput, BtcPreparedTransaction, BtcTransaction, OnchainSyncBatch,
-        OnchainSyncCursor,
+        BtcAddressType, BtcLockedUtxo, BtcNetwork, BtcOutput, BtcPreparedTransaction, BtcTransaction, BtcUtxo,
+        OnchainSyncBatch, OnchainSyncCursor,
     },
 };
 
@@ -23,6 +25,24 @@ pub trait BitcoinWallet: Sync + Send {
     /// if the real txid is only known after broadcast.
     async fn sign_send_transaction(&self, prepared: &BtcPreparedTransaction) -> Result<Option<String>, BitcoinError>;
     async fn release_prepared_transaction(&self, prepared: &BtcPreparedTransaction) -> Result<(), BitcoinError>;
+
+    /// Signs and finalizes the inputs of `psbt` owned by the wallet, leaving the others untouched. Their reservations
+    /// are left as they were, so a signed PSBT that is never broadcast locks nothing. Returns the PSBT as base64, in
+    /// version 0.
+    async fn sign_psbt(&self, psbt: &str) -> Result<String, BitcoinError>;
+
+    /// Extracts the fully signed transaction from `psbt` and broadcasts it. Returns its txid.
+    async fn broadcast_psbt(&self, psbt: &str) -> Result<String, BitcoinError>;
+
+    /// Locks `utxo` for `expiry` so coin selection skips it. Fails if it is already locked.
+    async fn lock_utxo(&self, utxo: &BtcUtxo, expiry: Duration) -> Result<BtcLockedUtxo, BitcoinError>;
+    async fn unlock_utxo(&self, utxo: &BtcLockedUtxo) -> Result<(), BitcoinError>;
+
+    /// Secret derived from the node's seed, always the same for the same `label`.
+    async fn derive_secret(&self, label: &str) -> Result<[u8; 32], BitcoinError>;
+
+    /// Confirmed outputs the wallet can spend, excluding reserved ones.
+    async fn list_unspent(&self) -> Result<Vec<BtcUtxo>, BitcoinError>;
     async fn get_transaction(&self, txid: &str) -> Result<Option<BtcTransaction>, BitcoinError>;
     async fn synchronize(&self, cursor: Option<OnchainSyncCursor>) -> Result<OnchainSyncBatch, BitcoinError>;
     async fn get_output<'a>(
@@ -32,5 +52,11 @@ pub trait BitcoinWallet: Sync + Send {
         address: Option<&'a str>,
         include_spent: bool,
     ) -> Result<Option<BtcOutput>, BitcoinError>;
+
+    /// Feerate, in sat/vB, the node estimates for confirming within `conf_target` blocks.
+    async fn estimate_feerate(&self, conf_target: u32) -> Result<u32, BitcoinError>;
+
+    /// Height of the chain tip as seen by the node, used to count deposit confirmations.
+    async fn block_height(&self) -> Result<u32, BitcoinError>;
     fn network(&self) -> BtcNetwork;
 }
@@ -1,12 +1,20 @@
 pub mod entities;
 
 mod bitcoin_address_handler;
+mod bitcoin_fee_handler;
 mod bitcoin_repository;
 mod bitcoin_service;
 mod bitcoin_use_cases;
+mod payjoin;
+mod payjoin_handler;
+mod silent_payment;
 
 pub use bitcoin_address_handler::*;
+pub use bitcoin_fee_handler::*;
 pub use bitcoin_repository::*;
 pub use bitcoin_service::*;
 pub use bitcoin_use_cases::*;
 pub use entities::*;
+pub use payjoin::*;
+pub use payjoin_handler::*;
+pub use silent_payment::*;
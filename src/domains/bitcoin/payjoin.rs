@@ -0,0 +1,852 @@
+use std::{collections::HashSet, str::FromStr, sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bitcoin::{
+    consensus::encode::serialize_hex,
+    psbt::{Input, Psbt},
+    Address, Amount, Network, OutPoint, Script, ScriptBuf, TxIn, TxOut, Txid,
+};
+use reqwest::{header::CONTENT_TYPE, Url};
+
+use crate::{
+    application::errors::PayjoinError,
+    domains::bitcoin::{BitcoinChain, BtcNetwork, BtcUtxo, PayjoinErrorResponse, PayjoinQueryParams},
+};
+
+/// Only BIP78 (payjoin v1) is implemented. BIP77 (v2) needs an OHTTP relay and a payjoin directory.
+pub const PAYJOIN_VERSION: u32 = 1;
+
+const PAYJOIN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Smallest value the sender's output paying part of the fee is left with.
+const PAYJOIN_DUST_LIMIT_SAT: u64 = 546;
+
+/// How long the output we contribute stays locked past the fallback delay, so the lock outlives the fallback even
+/// when the sync processing it runs late.
+const PAYJOIN_LOCK_MARGIN: Duration = Duration::from_secs(3600);
+
+/// Receiver side of payjoin: the endpoint advertised on deposit addresses and the full node checking and
+/// broadcasting the senders' original transactions.
+pub struct PayjoinReceiver {
+    pub endpoint: String,
+    pub chain: Arc<dyn BitcoinChain>,
+    /// Time the sender has to broadcast the payjoin before we broadcast its original transaction
+    pub fallback_delay: Duration,
+}
+
+impl PayjoinReceiver {
+    pub fn new(endpoint: String, chain: Arc<dyn BitcoinChain>, fallback_delay: Duration) -> Self {
+        Self {
+            endpoint,
+            chain,
+            fallback_delay,
+        }
+    }
+
+    /// How long to lock the output contributed to a payjoin.
+    pub fn lock_expiry(&self) -> Duration {
+        self.fallback_delay + PAYJOIN_LOCK_MARGIN
+    }
+}
+
+/// Script types a payjoin can be negotiated for. The receiver only contributes inputs of the same type as
+/// the sender's so the transaction doesn't reveal which inputs belong to whom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayjoinInputType {
+    P2wpkh,
+    P2tr,
+}
+
+impl PayjoinInputType {
+    pub fn from_script(script: &Script) -> Option<Self> {
+        if script.is_p2wpkh() {
+            Some(Self::P2wpkh)
+        } else if script.is_p2tr() {
+            Some(Self::P2tr)
+        } else {
+            None
+        }
+    }
+
+    /// Virtual size of a signed input of this type.
+    fn input_vsize(self) -> u64 {
+        match self {
+            Self::P2wpkh => 68,
+            Self::P2tr => 58,
+        }
+    }
+}
+
+pub fn bitcoin_network(network: BtcNetwork) -> Network {
+    match network {
+        BtcNetwork::Bitcoin => Network::Bitcoin,
+        BtcNetwork::Testnet => Network::Testnet,
+        BtcNetwork::Testnet4 => Network::Testnet4,
+        BtcNetwork::Signet => Network::Signet,
+        BtcNetwork::Regtest | BtcNetwork::Simnet => Network::Regtest,
+    }
+}
+
+pub fn address_script(address: &str, network: Network) -> Option<ScriptBuf> {
+    Address::from_str(address)
+        .ok()?
+        .require_network(network)
+        .ok()
+        .map(|address| address.script_pubkey())
+}
+
+pub fn decode_psbt(psbt_base64: &str) -> Result<Psbt, String> {
+    let bytes = STANDARD.decode(psbt_base64.trim()).map_err(|e| e.to_string())?;
+
+    Psbt::deserialize(&bytes).map_err(|e| e.to_string())
+}
+
+pub fn encode_psbt(psbt: &Psbt) -> String {
+    STANDARD.encode(psbt.serialize())
+}
+
+pub fn script_address(script: &Script, network: Network) -> Option<String> {
+    Address::from_script(script, network)
+        .ok()
+        .map(|address| address.to_string())
+}
+
+fn input_script(input: &Input, txin: &TxIn) -> Option<ScriptBuf> {
+    match (&input.witness_utxo, &input.non_witness_utxo) {
+        (Some(utxo), _) => Some(utxo.script_pubkey.clone()),
+        (None, Some(tx)) => tx
+            .output
+            .get(txin.previous_output.vout as usize)
+            .map(|output| output.script_pubkey.clone()),
+        (None, None) => None,
+    }
+}
+
+fn is_finalized(input: &Input) -> bool {
+    input.final_script_witness.is_some() || input.final_script_sig.is_some()
+}
+
+fn outpoint(utxo: &BtcUtxo) -> Option<OutPoint> {
+    Some(OutPoint {
+        txid: Txid::from_str(&utxo.txid).ok()?,
+        vout: utxo.output_index,
+    })
+}
+
+fn outpoints(psbt: &Psbt) -> HashSet<OutPoint> {
+    psbt.unsigned_tx
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect()
+}
+
+/// Whether `psbt` spends `utxo`.
+pub fn spends_utxo(psbt: &Psbt, utxo: &BtcUtxo) -> bool {
+    outpoint(utxo).is_some_and(|outpoint| outpoints(psbt).contains(&outpoint))
+}
+
+/// Outpoints spent by `psbt`, as `txid:vout`.
+pub fn input_outpoints(psbt: &Psbt) -> Vec<String> {
+    psbt.unsigned_tx
+        .input
+        .iter()
+        .map(|input| input.previous_output.to_string())
+        .collect()
+}
+
+/// Txid and raw hex of the transaction of a finalized `psbt`.
+pub fn extract_transaction(psbt: &Psbt) -> (String, String) {
+    let tx = psbt.clone().extract_tx_unchecked_fee_rate();
+
+    (tx.compute_txid().to_string(), serialize_hex(&tx))
+}
+
+/// Virtual size `psbt` will have once signed, from the script types of its inputs. Fails on inputs without
+/// their UTXO or of a type the wallet doesn't produce.
+pub fn signed_vsize(psbt: &Psbt) -> Result<u64, String> {
+    // Segwit marker and flag.
+    let mut weight = psbt.unsigned_tx.weight().to_wu() + 2;
+
+    for (input, txin) in psbt.inputs.iter().zip(&psbt.unsigned_tx.input) {
+        let script = input_script(input, txin).ok_or("PSBT input without its UTXO")?;
+        weight += if script.is_p2wpkh() {
+            // Item count, signature and public key.
+            1 + 73 + 34
+        } else if script.is_p2tr() {
+            // Item count and Schnorr signature of a key path spend.
+            1 + 65
+        } else if script.is_p2sh() {
+            // Nested P2WPKH: the redeem script push, at 4 weight units per byte, and the P2WPKH witness.
+            23 * 4 + 1 + 73 + 34
+        } else {
+            return Err(format!("Unsupported input script {script}"));
+        };
+    }
+
+    Ok(weight.div_ceil(4))
+}
+
+/// Receiver check of the sender's original PSBT: every input must be finalized with its UTXO attached and be of
+/// a single supported type, so the PSBT can be broadcast as is if the payjoin fails.
+pub fn check_original_psbt(original: &Psbt) -> Result<PayjoinInputType, PayjoinError> {
+    let mut input_type = None;
+
+    if original.inputs.is_empty() {
+        return Err(PayjoinError::OriginalPsbtRejected(
+            "The PSBT has no inputs.".to_string(),
+        ));
+    }
+
+    for (input, txin) in original.inputs.iter().zip(&original.unsigned_tx.input) {
+        if !is_finalized(input) {
+            return Err(PayjoinError::OriginalPsbtRejected(
+                "The PSBT inputs must be finalized.".to_string(),
+            ));
+        }
+
+        let script = input_script(input, txin).ok_or_else(|| {
+            PayjoinError::OriginalPsbtRejected("The PSBT inputs must include their UTXO.".to_string())
+        })?;
+        let script_type = PayjoinInputType::from_script(&script)
+            .ok_or_else(|| PayjoinError::Unavailable("Unsupported input script type.".to_string()))?;
+
+        if input_type.is_some_and(|input_type| input_type != script_type) {
+            return Err(PayjoinError::Unavailable("Mixed input script types.".to_string()));
+        }
+        input_type = Some(script_type);
+    }
+
+    original
+        .fee()
+        .map_err(|e| PayjoinError::OriginalPsbtRejected(e.to_string()))?;
+
+    Ok(input_type.expect("checked that the PSBT has inputs"))
+}
+
+/// Builds the receiver's payjoin proposal: `utxo` is added as an input and its value, net of the fee its input adds,
+/// is added to the receiver's payment output, consolidating it without a change output that would pair with the
+/// input. The sender may cover part of the fee through the BIP78 parameters.
+pub fn build_payjoin_proposal(
+    original: &Psbt,
+    receiver_output: usize,
+    utxo: &BtcUtxo,
+    utxo_script: ScriptBuf,
+    params: &PayjoinQueryParams,
+) -> Result<Psbt, PayjoinError> {
+    let input_type = PayjoinInputType::from_script(&utxo_script)
+        .ok_or_else(|| PayjoinError::Unavailable("Unsupported contributed input script type.".to_string()))?;
+    let previous_output = outpoint(utxo).ok_or_else(|| PayjoinError::Unavailable("Invalid UTXO txid.".to_string()))?;
+
+    let original_fee = original
+        .fee()
+        .map_err(|e| PayjoinError::OriginalPsbtRejected(e.to_string()))?
+        .to_sat();
+    let original_vsize = original.clone().extract_tx_unchecked_fee_rate().vsize() as u64;
+    let original_feerate = original_fee as f64 / original_vsize as f64;
+    let feerate = original_feerate.max(params.min_feerate.unwrap_or_default());
+
+    let required_fee = (feerate * (original_vsize + input_type.input_vsize()) as f64).ceil() as u64;
+    let additional_fee = required_fee.saturating_sub(original_fee);
+
+    let mut proposal = original.clone();
+    proposal.xpub.clear();
+    for output in proposal.outputs.iter_mut() {
+        output.bip32_derivation.clear();
+        output.tap_key_origins.clear();
+    }
+
+    // The sender may pay for the weight of our input, taken from the output it designates (its change).
+    let mut sender_contribution = 0;
+    if let (Some(index), Some(max_contribution)) = (
+        params.additional_fee_output_index,
+        params.max_additional_fee_contribution,
+    ) {
+        if index == receiver_output {
+            return Err(PayjoinError::OriginalPsbtRejected(
+                "The additional fee output cannot be the payment output.".to_string(),
+            ));
+        }
+        let output = proposal.unsigned_tx.output.get_mut(index).ok_or_else(|| {
+            PayjoinError::OriginalPsbtRejected("The additional fee output does not exist.".to_string())
+        })?;
+
+        let input_fee = (original_feerate * input_type.input_vsize() as f64).ceil() as u64;
+        let available = output.value.to_sat().saturating_sub(PAYJOIN_DUST_LIMIT_SAT);
+        sender_contribution = input_fee.min(max_contribution).min(available).min(additional_fee);
+        output.value -= Amount::from_sat(sender_contribution);
+    }
+
+    let contributed_sat = utxo
+        .amount_sat
+        .checked_sub(additional_fee - sender_contribution)
+        .filter(|contributed_sat| *contributed_sat > 0)
+        .ok_or_else(|| PayjoinError::Unavailable("The contributed output cannot cover its fee.".to_string()))?;
+    let payment = proposal
+        .unsigned_tx
+        .output
+        .get_mut(receiver_output)
+        .ok_or_else(|| PayjoinError::OriginalPsbtRejected("The payment output does not exist.".to_string()))?;
+    payment.value += Amount::from_sat(contributed_sat);
+
+    let sequence = original.unsigned_tx.input[0].sequence;
+    let input_index = rand::random_range(0..=proposal.unsigned_tx.input.len());
+    proposal.unsigned_tx.input.insert(
+        input_index,
+        TxIn {
+            previous_output,
+            sequence,
+            ..Default::default()
+        },
+    );
+    proposal.inputs.insert(
+        input_index,
+        Input {
+            witness_utxo: Some(TxOut {
+                value: Amount::from_sat(utxo.amount_sat),
+                script_pubkey: utxo_script,
+            }),
+            ..Default::default()
+        },
+    );
+
+    Ok(proposal)
+}
+
+/// Prepares the signed proposal for the sender: only the receiver's inputs stay finalized with their UTXO, the
+/// sender's inputs are stripped since the sender re-signs them.
+pub fn finish_payjoin_proposal(mut signed: Psbt, original: &Psbt) -> Result<Psbt, PayjoinError> {
+    let sender_outpoints = outpoints(original);
+
+    for (input, txin) in signed.inputs.iter_mut().zip(&signed.unsigned_tx.input) {
+        if sender_outpoints.contains(&txin.previous_output) {
+            *input = Input::default();
+        } else if !is_finalized(input) {
+            return Err(PayjoinError::Unavailable(
+                "The contributed input could not be signed.".to_string(),
+            ));
+        }
+    }
+
+    Ok(signed)
+}
+
+/// Strips the key origins of the sender's original PSBT before it is shared with the receiver.
+pub fn prepare_original_psbt(mut original: Psbt) -> Psbt {
+    original.xpub.clear();
+    for input in original.inputs.iter_mut() {
+        input.bip32_derivation.clear();
+        input.tap_key_origins.clear();
+    }
+    for output in original.outputs.iter_mut() {
+        output.bip32_derivation.clear();
+        output.tap_key_origins.clear();
+    }
+
+    original
+}
+
+/// Sender check of the receiver's proposal (BIP78 sender checklist, with output substitution disabled and no
+/// additional fee offered). Returns the proposal with the sender's inputs restored, ready to be signed.
+pub fn check_payjoin_proposal(
+    original: &Psbt,
+    mut proposal: Psbt,
+    payee_script: &Script,
+) -> Result<Psbt, PayjoinError> {
+    let rejected = |reason: &str| PayjoinError::ProposalRejected(reason.to_string());
+
+    if proposal.unsigned_tx.version != original.unsigned_tx.version
+        || proposal.unsigned_tx.lock_time != original.unsigned_tx.lock_time
+    {
+        return Err(rejected("The transaction version or lock time changed."));
+    }
+    if proposal.inputs.len() != proposal.unsigned_tx.input.len()
+        || proposal.outputs.len() != proposal.unsigned_tx.output.len()
+    {
+        return Err(rejected("The proposal PSBT is inconsistent."));
+    }
+
+    let sender_type = original
+        .inputs
+        .iter()
+        .zip(&original.unsigned_tx.input)
+        .find_map(|(input, txin)| input_script(input, txin))
+        .and_then(|script| PayjoinInputType::from_script(&script))
+        .ok_or_else(|| rejected("Unsupported original input type."))?;
+    let sequence = original.unsigned_tx.input[0].sequence;
+
+    let mut sender_inputs = 0;
+    let mut receiver_inputs = 0;
+    for (input, txin) in proposal.inputs.iter_mut().zip(&proposal.unsigned_tx.input) {
+        let original_input = original
+            .unsigned_tx
+            .input
+            .iter()
+            .position(|original_txin| original_txin.previous_output == txin.previous_output);
+
+        match original_input {
+            Some(index) => {
+                if txin.sequence != original.unsigned_tx.input[index].sequence {
+                    return Err(rejected("The sequence of an original input changed."));
+                }
+
+                let original_input = &original.inputs[index];
+                *input = Input {
+                    witness_utxo: original_input.witness_utxo.clone(),
+                    non_witness_utxo: original_input.non_witness_utxo.clone(),
+                    redeem_script: original_input.redeem_script.clone(),
+                    bip32_derivation: original_input.bip32_derivation.clone(),
+                    tap_internal_key: original_input.tap_internal_key,
+                    tap_key_origins: original_input.tap_key_origins.clone(),
+                    ..Default::default()
+                };
+                sender_inputs += 1;
+            }
+            None => {
+                if !is_finalized(input) {
+                    return Err(rejected("The receiver inputs must be finalized."));
+                }
+                let script = input_script(input, txin).ok_or_else(|| rejected("A receiver input has no UTXO."))?;
+                if PayjoinInputType::from_script(&script) != Some(sender_type) {
+                    return Err(rejected("A receiver input has a different script type."));
+                }
+                if txin.sequence != sequence {
+                    return Err(rejected("A receiver input has a different sequence."));
+                }
+                receiver_inputs += 1;
+            }
+        }
+    }
+
+    if sender_inputs != original.unsigned_tx.input.len() {
+        return Err(rejected("An original input is missing."));
+    }
+    if receiver_inputs == 0 {
+        return Err(rejected("The receiver did not contribute any input."));
+    }
+
+    let mut matched = vec![false; proposal.unsigned_tx.output.len()];
+    for original_output in &original.unsigned_tx.output {
+        let index = proposal
+            .unsigned_tx
+            .output
+            .iter()
+            .enumerate()
+            .position(|(index, output)| !matched[index] && output.script_pubkey == original_output.script_pubkey)
+            .ok_or_else(|| rejected("An original output is missing."))?;
+        matched[index] = true;
+
+        let value = proposal.unsigned_tx.output[index].value;
+        let accepted = match original_output.script_pubkey.as_script() == payee_script {
+            true => value >= original_output.value,
+            false => value == original_output.value,
+        };
+        if !accepted {
+            return Err(rejected("The value of an original output changed."));
+        }
+    }
+
+    let original_fee = original.fee().map_err(|e| rejected(&e.to_string()))?;
+    let proposal_fee = proposal.fee().map_err(|e| rejected(&e.to_string()))?;
+    if proposal_fee < original_fee {
+        return Err(rejected("The proposal lowers the transaction fee."));
+    }
+
+    Ok(proposal)
+}
+
+/// Checks that signing the proposal only touched the sender's own inputs.
+pub fn check_signed_payjoin(checked: &Psbt, signed: &Psbt, original: &Psbt) -> Result<(), PayjoinError> {
+    let sender_outpoints = outpoints(original);
+
+    if signed.unsigned_tx != checked.unsigned_tx {
+        return Err(PayjoinError::ProposalRejected(
+            "Signing changed the transaction.".to_string(),
+        ));
+    }
+
+    for ((checked_input, signed_input), txin) in checked
+        .inputs
+        .iter()
+        .zip(&signed.inputs)
+        .zip(&checked.unsigned_tx.input)
+    {
+        if sender_outpoints.contains(&txin.previous_output) {
+            continue;
+        }
+        if checked_input.final_script_witness != signed_input.final_script_witness
+            || checked_input.final_script_sig != signed_input.final_script_sig
+        {
+            return Err(PayjoinError::ProposalRejected(
+                "Signing modified a receiver input.".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the original PSBT to the receiver's payjoin endpoint and returns its proposal PSBT, as base64.
+pub async fn request_payjoin_proposal(endpoint: &str, original_psbt: &str) -> Result<String, PayjoinError> {
+    let mut url = Url::parse(endpoint).map_err(|e| PayjoinError::Request(e.to_string()))?;
+    url.query_pairs_mut()
+        .append_pair("v", &PAYJOIN_VERSION.to_string())
+        .append_pair("disableoutputsubstitution", "true");
+
+    let client = reqwest::Client::builder()
+        .timeout(PAYJOIN_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| PayjoinError::Request(e.to_string()))?;
+    let response = client
+        .post(url)
+        .header(CONTENT_TYPE, "text/plain")
+        .body(original_psbt.to_string())
+        .send()
+        .await
+        .map_err(|e| PayjoinError::Request(e.to_string()))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| PayjoinError::Request(e.to_string()))?;
+
+    if !status.is_success() {
+        let reason = serde_json::from_str::<PayjoinErrorResponse>(&body)
+            .map(|error| format!("{}: {}", error.error_code, error.message))
+            .unwrap_or_else(|_| status.to_string());
+        return Err(PayjoinError::Request(reason));
+    }
+
+    Ok(body.trim().to_string())
+}
+
+/// A payjoin endpoint must be served over HTTPS, or plain HTTP over Tor. Plain HTTP is also accepted off mainnet so
+/// a local receiver can be used for testing.
+pub fn valid_payjoin_endpoint(endpoint: &str, network: BtcNetwork) -> bool {
+    let Ok(url) = Url::parse(endpoint) else {
+        return false;
+    };
+
+    match url.scheme() {
+        "https" => true,
+        "http" => network != BtcNetwork::Bitcoin || url.host_str().is_some_and(|host| host.ends_with(".onion")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, hashes::Hash, transaction::Version, Sequence, Transaction, WPubkeyHash, Witness,
+    };
+    use wiremock::{
+        matchers::{body_string, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    const SENDER: u8 = 1;
+    const PAYEE: u8 = 2;
+    const SENDER_CHANGE: u8 = 3;
+    const RECEIVER_UTXO: u8 = 4;
+
+    fn script(byte: u8) -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([byte; 20]))
+    }
+
+    fn witness() -> Witness {
+        Witness::from_slice(&[vec![0u8; 72], vec![2u8; 33]])
+    }
+
+    fn output_value(psbt: &Psbt, byte: u8) -> u64 {
+        psbt.unsigned_tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == script(byte))
+            .map(|output| output.value.to_sat())
+            .unwrap()
+    }
+
+    /// Sender's signed original PSBT: 100_000 sat in, 50_000 to the payee, 49_000 back as change.
+    fn original_psbt() -> Psbt {
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array([SENDER; 32]),
+                    vout: 0,
+                },
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                ..Default::default()
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(50_000),
+                    script_pubkey: script(PAYEE),
+                },
+                TxOut {
+                    value: Amount::from_sat(49_000),
+                    script_pubkey: script(SENDER_CHANGE),
+                },
+            ],
+        };
+
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: script(SENDER),
+        });
+        psbt.inputs[0].final_script_witness = Some(witness());
+        psbt
+    }
+
+    fn receiver_utxo(amount_sat: u64) -> BtcUtxo {
+        BtcUtxo {
+            txid: Txid::from_byte_array([RECEIVER_UTXO; 32]).to_string(),
+            output_index: 1,
+            address: Address::from_script(&script(RECEIVER_UTXO), Network::Regtest)
+                .unwrap()
+                .to_string(),
+            amount_sat,
+        }
+    }
+
+    /// Receiver side of the exchange, with the node wallet signing stubbed by a dummy witness.
+    fn receiver_proposal(original: &Psbt) -> Psbt {
+        let mut proposal = build_payjoin_proposal(
+            original,
+            0,
+            &receiver_utxo(20_000),
+            script(RECEIVER_UTXO),
+            &PayjoinQueryParams::default(),
+        )
+        .unwrap();
+
+        let sender_outpoints = outpoints(original);
+        for (input, txin) in proposal.inputs.iter_mut().zip(&proposal.unsigned_tx.input) {
+            if !sender_outpoints.contains(&txin.previous_output) {
+                input.final_script_witness = Some(witness());
+            }
+        }
+
+        finish_payjoin_proposal(proposal, original).unwrap()
+    }
+
+    mod check_original_psbt {
+        use super::*;
+
+        #[test]
+        fn accepts_a_finalized_psbt() {
+            assert_eq!(check_original_psbt(&original_psbt()).unwrap(), PayjoinInputType::P2wpkh);
+        }
+
+        #[test]
+        fn rejects_unsigned_inputs() {
+            let mut original = original_psbt();
+            original.inputs[0].final_script_witness = None;
+
+            assert!(matches!(
+                check_original_psbt(&original),
+                Err(PayjoinError::OriginalPsbtRejected(_))
+            ));
+        }
+    }
+
+    mod signed_vsize {
+        use super::*;
+
+        #[test]
+        fn matches_the_size_of_the_signed_transaction() {
+            let original = original_psbt();
+            let signed = original.clone().extract_tx_unchecked_fee_rate();
+
+            assert_eq!(signed_vsize(&original).unwrap(), signed.vsize() as u64);
+        }
+
+        #[test]
+        fn rejects_unsupported_input_types() {
+            let mut original = original_psbt();
+            original.inputs[0].witness_utxo = Some(TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: ScriptBuf::new_p2pkh(&bitcoin::PubkeyHash::from_byte_array([SENDER; 20])),
+            });
+
+            assert!(signed_vsize(&original).is_err());
+        }
+    }
+
+    mod build_payjoin_proposal {
+        use super::*;
+
+        #[test]
+        fn adds_the_receiver_input_to_the_payment_output() {
+            let original = original_psbt();
+
+            let proposal = receiver_proposal(&original);
+
+            assert_eq!(proposal.unsigned_tx.input.len(), 2);
+            assert_eq!(proposal.unsigned_tx.output.len(), original.unsigned_tx.output.len());
+            assert!(output_value(&proposal, PAYEE) > 50_000);
+            assert!(output_value(&proposal, PAYEE) < 50_000 + 20_000);
+            assert_eq!(output_value(&proposal, SENDER_CHANGE), 49_000);
+        }
+
+        #[test]
+        fn takes_the_sender_contribution_from_the_designated_output() {
+            let params = PayjoinQueryParams {
+                additional_fee_output_index: Some(1),
+                max_additional_fee_contribution: Some(10_000),
+                ..Default::default()
+            };
+
+            let proposal = build_payjoin_proposal(
+                &original_psbt(),
+                0,
+                &receiver_utxo(20_000),
+                script(RECEIVER_UTXO),
+                &params,
+            )
+            .unwrap();
+
+            assert!(output_value(&proposal, SENDER_CHANGE) < 49_000);
+            let receiver_paid = receiver_proposal(&original_psbt());
+            assert!(output_value(&proposal, PAYEE) > output_value(&receiver_paid, PAYEE));
+        }
+
+        #[test]
+        fn refuses_an_output_that_cannot_cover_its_fee() {
+            let result = build_payjoin_proposal(
+                &original_psbt(),
+                0,
+                &receiver_utxo(400),
+                script(RECEIVER_UTXO),
+                &PayjoinQueryParams::default(),
+            );
+
+            assert!(matches!(result, Err(PayjoinError::Unavailable(_))));
+        }
+    }
+
+    mod check_payjoin_proposal {
+        use super::*;
+
+        #[test]
+        fn accepts_the_receiver_proposal_and_restores_the_sender_inputs() {
+            let original = prepare_original_psbt(original_psbt());
+            let proposal = receiver_proposal(&original);
+
+            let checked = check_payjoin_proposal(&original, proposal, &script(PAYEE)).unwrap();
+
+            assert!(checked.fee().unwrap() >= original.fee().unwrap());
+            let sender_input = checked
+                .unsigned_tx
+                .input
+                .iter()
+                .position(|txin| txin.previous_output.txid == Txid::from_byte_array([SENDER; 32]))
+                .unwrap();
+            assert_eq!(
+                checked.inputs[sender_input].witness_utxo,
+                original.inputs[0].witness_utxo
+            );
+            assert!(checked.inputs[sender_input].final_script_witness.is_none());
+        }
+
+        #[test]
+        fn rejects_a_proposal_taking_from_the_sender_outputs() {
+            let original = original_psbt();
+            let mut proposal = receiver_proposal(&original);
+            let change = proposal
+                .unsigned_tx
+                .output
+                .iter_mut()
+                .find(|output| output.script_pubkey == script(SENDER_CHANGE))
+                .unwrap();
+            change.value = Amount::from_sat(48_000);
+
+            assert!(matches!(
+                check_payjoin_proposal(&original, proposal, &script(PAYEE)),
+                Err(PayjoinError::ProposalRejected(_))
+            ));
+        }
+
+        #[test]
+        fn rejects_a_proposal_without_receiver_inputs() {
+            let original = original_psbt();
+            let mut proposal = original.clone();
+            proposal.inputs[0] = Input::default();
+
+            assert!(matches!(
+                check_payjoin_proposal(&original, proposal, &script(PAYEE)),
+                Err(PayjoinError::ProposalRejected(_))
+            ));
+        }
+    }
+
+    mod check_signed_payjoin {
+        use super::*;
+
+        #[test]
+        fn rejects_a_signature_over_a_receiver_input() {
+            let original = original_psbt();
+            let checked = check_payjoin_proposal(&original, receiver_proposal(&original), &script(PAYEE)).unwrap();
+            let mut signed = checked.clone();
+            for input in signed.inputs.iter_mut() {
+                input.final_script_witness = Some(Witness::from_slice(&[vec![1u8; 64]]));
+            }
+
+            assert!(check_signed_payjoin(&checked, &signed, &original).is_err());
+        }
+    }
+
+    mod request_payjoin_proposal {
+        use super::*;
+
+        #[tokio::test]
+        async fn posts_the_original_and_returns_the_proposal() {
+            let receiver = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/v1/payjoin"))
+                .and(query_param("v", "1"))
+                .and(query_param("disableoutputsubstitution", "true"))
+                .and(body_string("original"))
+                .respond_with(ResponseTemplate::new(200).set_body_string("proposal\n"))
+                .expect(1)
+                .mount(&receiver)
+                .await;
+
+            let proposal = request_payjoin_proposal(&format!("{}/v1/payjoin", receiver.uri()), "original")
+                .await
+                .unwrap();
+
+            assert_eq!(proposal, "proposal");
+        }
+
+        #[tokio::test]
+        async fn surfaces_the_receiver_error_code() {
+            let receiver = MockServer::start().await;
+            Mock::given(method("POST"))
+                .respond_with(ResponseTemplate::new(400).set_body_json(PayjoinErrorResponse {
+                    error_code: "original-psbt-rejected".to_string(),
+                    message: "bad".to_string(),
+                }))
+                .mount(&receiver)
+                .await;
+
+            let error = request_payjoin_proposal(&receiver.uri(), "original").await.unwrap_err();
+
+            assert!(error.to_string().contains("original-psbt-rejected"));
+        }
+    }
+
+    #[test]
+    fn valid_payjoin_endpoint_requires_https_on_mainnet() {
+        assert!(valid_payjoin_endpoint("https://example.com/pj", BtcNetwork::Bitcoin));
+        assert!(valid_payjoin_endpoint("http://example.onion/pj", BtcNetwork::Bitcoin));
+        assert!(!valid_payjoin_endpoint("http://example.com/pj", BtcNetwork::Bitcoin));
+        assert!(valid_payjoin_endpoint("http://127.0.0.1:3000/pj", BtcNetwork::Regtest));
+        assert!(!valid_payjoin_endpoint("ftp://example.com/pj", BtcNetwork::Regtest));
+    }
+}
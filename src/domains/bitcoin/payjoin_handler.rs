@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing::post, Router};
+use utoipa::OpenApi;
+
+use crate::{
+    application::{composition::AppServices, errors::ApplicationError},
+    infra::axum::Query,
+};
+
+use super::{PayjoinErrorResponse, PayjoinQueryParams};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(payjoin),
+    components(schemas(PayjoinErrorResponse)),
+    tags(
+        (name = "Payjoin", description = "Public payjoin receiver endpoint as defined in [BIP78](https://github.com/bitcoin/bips/blob/master/bip-0078.mediawiki). Advertised by deposit addresses through the `pj=` BIP21 parameter")
+    ),
+)]
+pub struct PayjoinHandler;
+pub const PAYJOIN_CONTEXT_PATH: &str = "/v1/payjoin";
+
+pub fn payjoin_router() -> Router<Arc<AppServices>> {
+    Router::new().route("/", post(payjoin))
+}
+
+/// Payjoin endpoint
+///
+/// Receives the sender's original PSBT (base64, as plain text) paying one of our deposit addresses and returns the
+/// payjoin proposal PSBT, with one of the node's outputs added as input. Errors follow the BIP78 JSON format.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Payjoin",
+    context_path = PAYJOIN_CONTEXT_PATH,
+    params(PayjoinQueryParams),
+    request_body(content = String, content_type = "text/plain", description = "Original PSBT, base64 encoded"),
+    responses(
+        (status = 200, description = "Payjoin proposal PSBT, base64 encoded", body = String, content_type = "text/plain"),
+        (status = 400, description = "Original PSBT rejected or version unsupported", body = PayjoinErrorResponse),
+        (status = 422, description = "Payjoin unavailable for this PSBT", body = PayjoinErrorResponse),
+        (status = 503, description = "Payjoin unavailable", body = PayjoinErrorResponse)
+    )
+)]
+async fn payjoin(
+    State(services): State<Arc<AppServices>>,
+    Query(params): Query<PayjoinQueryParams>,
+    original_psbt: String,
+) -> Result<String, ApplicationError> {
+    let proposal = services.bitcoin.payjoin_proposal(original_psbt, params).await?;
+
+    Ok(proposal)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{application::composition::MockAppServicesBuilder, application::errors::PayjoinError};
+
+    use super::*;
+
+    mod payjoin {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_the_proposal_as_plain_text() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .bitcoin
+                .expect_payjoin_proposal()
+                .withf(|original, params| original == "original" && params.v == Some(1))
+                .times(1)
+                .returning(|_, _| Ok("proposal".to_string()));
+
+            let params = PayjoinQueryParams {
+                v: Some(1),
+                ..Default::default()
+            };
+            let proposal = payjoin(State(Arc::new(builder.build())), Query(params), "original".to_string())
+                .await
+                .unwrap();
+
+            assert_eq!(proposal, "proposal");
+        }
+
+        #[tokio::test]
+        async fn forwards_payjoin_errors() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .bitcoin
+                .expect_payjoin_proposal()
+                .times(1)
+                .returning(|_, _| Err(PayjoinError::Unavailable("disabled".to_string()).into()));
+
+            let result = payjoin(
+                State(Arc::new(builder.build())),
+                Query(PayjoinQueryParams::default()),
+                "original".to_string(),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Payjoin(PayjoinError::Unavailable(_)))
+            ));
+        }
+    }
+}
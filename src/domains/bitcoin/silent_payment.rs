@@ -0,0 +1,1000 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use bitcoin::{
+    absolute::LockTime,
+    bech32::{
+        primitives::{
+            decode::CheckedHrpstring,
+            iter::{ByteIterExt, Fe32IterExt},
+        },
+        Bech32m, Fe32, Hrp,
+    },
+    consensus::serialize,
+    hashes::{hash160, sha256, Hash, HashEngine},
+    key::{Keypair, Parity, TweakedPublicKey},
+    script::Instruction,
+    secp256k1::{All, Message, PublicKey, Scalar, Secp256k1, SecretKey, XOnlyPublicKey},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot, transaction, Amount, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+
+use crate::domains::bitcoin::{BitcoinChain, BtcNetwork, BtcOutput};
+
+/// Version of the silent payment addresses we can send to and issue.
+const SILENT_PAYMENT_VERSION: Fe32 = Fe32::Q;
+
+/// Length of the scan and spend public keys of a version 0 address.
+const SILENT_PAYMENT_PAYLOAD_LEN: usize = 66;
+
+/// Internal key of taproot script path spends that provably have no key path (BIP341 `H`). Such inputs
+/// don't contribute to the shared secret.
+const NUMS_INTERNAL_KEY: [u8; 32] = [
+    0x50, 0x92, 0x9b, 0x74, 0xc1, 0xa0, 0x49, 0x54, 0xb7, 0x8b, 0x4b, 0x60, 0x35, 0xe9, 0x7a, 0x5e, 0x07, 0x8a, 0x5a,
+    0x0f, 0x28, 0xec, 0x96, 0xd5, 0x47, 0xbf, 0xee, 0x9a, 0xce, 0x80, 0x3a, 0xc0,
+];
+
+const TAPROOT_ANNEX_PREFIX: u8 = 0x50;
+
+/// Smallest output worth creating. Spends leaving less than this after the fee are refused.
+const SILENT_PAYMENT_DUST_LIMIT_SAT: u64 = 546;
+
+/// Label the spend secret is derived from the node's seed with, so it never sits in the configuration.
+pub const SILENT_PAYMENT_SPEND_SECRET_LABEL: &str = "swissknife/silent-payments/spend";
+
+/// Silent payment (BIP352) keys held by SwissKnife. Deposits to silent payment addresses land in outputs only
+/// these keys can find and spend, so they are scanned for here and swept to the node wallet.
+pub struct SilentPaymentKeys {
+    secp: Secp256k1<All>,
+    scan_secret: SecretKey,
+    spend_secret: SecretKey,
+}
+
+impl fmt::Debug for SilentPaymentKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SilentPaymentKeys")
+            .field("scan_public_key", &self.scan_public_key())
+            .field("spend_public_key", &self.spend_public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decoded silent payment address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SilentPaymentAddress {
+    pub scan_public_key: PublicKey,
+    pub spend_public_key: PublicKey,
+    pub network: BtcNetwork,
+}
+
+/// Output of a transaction paying one of our silent payment addresses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoundSilentPayment {
+    pub output_index: u32,
+    /// Tweak added to the spend secret to spend the output
+    pub tweak: SecretKey,
+    /// Label of the address the output pays, `None` for the unlabeled address
+    pub label: Option<u32>,
+}
+
+/// Silent payment output owned by SwissKnife.
+#[derive(Clone, Debug)]
+pub struct SilentPaymentOutput {
+    pub output: BtcOutput,
+    /// Hex encoded tweak added to the spend secret to spend the output
+    pub tweak: String,
+}
+
+/// Signed transaction spending outputs of our silent payment keys.
+#[derive(Clone, Debug)]
+pub struct SilentPaymentTransaction {
+    pub transaction: Transaction,
+    pub fee_sat: u64,
+    pub spent: Vec<SilentPaymentOutput>,
+}
+
+/// Silent payment receiving and sending, enabled when keys and a full node are configured.
+pub struct SilentPayments {
+    pub keys: SilentPaymentKeys,
+    pub chain: Arc<dyn BitcoinChain>,
+    /// First block that can contain outputs for our keys, where scanning starts
+    pub birth_height: u32,
+}
+
+impl SilentPayments {
+    pub fn new(keys: SilentPaymentKeys, chain: Arc<dyn BitcoinChain>, birth_height: u32) -> Self {
+        Self {
+            keys,
+            chain,
+            birth_height,
+        }
+    }
+}
+
+impl SilentPaymentKeys {
+    pub fn new(scan_secret: SecretKey, spend_secret: SecretKey) -> Self {
+        Self {
+            secp: Secp256k1::new(),
+            scan_secret,
+            spend_secret,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn from_hex(scan_secret: &str, spend_secret: &str) -> Result<Self, String> {
+        let spend_secret = hex::decode(spend_secret.trim()).map_err(|e| format!("Invalid spend secret: {e}"))?;
+
+        Self::from_node_secret(scan_secret, &spend_secret)
+    }
+
+    /// Keys with the hex encoded scan secret of the configuration and the spend secret derived by the node.
+    pub fn from_node_secret(scan_secret: &str, spend_secret: &[u8]) -> Result<Self, String> {
+        let scan_secret = hex::decode(scan_secret.trim())
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| SecretKey::from_slice(&bytes).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Invalid scan secret: {e}"))?;
+        let spend_secret = SecretKey::from_slice(spend_secret).map_err(|e| format!("Invalid spend secret: {e}"))?;
+
+        Ok(Self::new(scan_secret, spend_secret))
+    }
+
+    pub fn scan_public_key(&self) -> PublicKey {
+        self.scan_secret.public_key(&self.secp)
+    }
+
+    pub fn spend_public_key(&self) -> PublicKey {
+        self.spend_secret.public_key(&self.secp)
+    }
+
+    /// Tweak of label `m`, added to the spend key of the labeled address.
+    pub fn label_tweak(&self, m: u32) -> SecretKey {
+        let hash = tagged_hash("BIP0352/Label", &[&self.scan_secret.secret_bytes(), &m.to_be_bytes()]);
+        SecretKey::from_slice(&hash).expect("hash is a valid secret key with overwhelming probability")
+    }
+
+    /// Point of label `m`, as found when scanning outputs paying the labeled address.
+    pub fn label_point(&self, m: u32) -> PublicKey {
+        self.label_tweak(m).public_key(&self.secp)
+    }
+
+    /// Address with label `m`. Labels let every wallet have its own address while scanning only once for all.
+    pub fn address(&self, m: u32, network: BtcNetwork) -> String {
+        let spend_public_key = self
+            .spend_public_key()
+            .add_exp_tweak(&self.secp, &Scalar::from(self.label_tweak(m)))
+            .expect("labeled spend key is valid with overwhelming probability");
+
+        encode_silent_payment_address(&SilentPaymentAddress {
+            scan_public_key: self.scan_public_key(),
+            spend_public_key,
+            network,
+        })
+    }
+
+    /// Finds the outputs of `tx` paying our addresses, unlabeled or labeled with one of `labels` (point to label).
+    /// `prevouts` are the outputs spent by the inputs of `tx`, in order.
+    pub fn scan_transaction(
+        &self,
+        tx: &Transaction,
+        prevouts: &[TxOut],
+        labels: &HashMap<PublicKey, u32>,
+    ) -> Vec<FoundSilentPayment> {
+        let Some((input_hash, input_public_key)) = input_hash(tx, prevouts) else {
+            return Vec::new();
+        };
+        let Some(shared_secret) = self
+            .scan_secret
+            .mul_tweak(&Scalar::from(input_hash))
+            .ok()
+            .and_then(|tweak| input_public_key.mul_tweak(&self.secp, &Scalar::from(tweak)).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut outputs: Vec<(u32, XOnlyPublicKey)> = tx
+            .output
+            .iter()
+            .enumerate()
+            .filter_map(|(index, output)| Some((index as u32, taproot_output_key(&output.script_pubkey)?)))
+            .collect();
+        let spend_public_key = self.spend_public_key();
+        let mut found = Vec::new();
+
+        for k in 0.. {
+            let tweak = shared_secret_tweak(&shared_secret, k);
+            let Ok(output_key) = spend_public_key.add_exp_tweak(&self.secp, &Scalar::from(tweak)) else {
+                break;
+            };
+            let negated_output_key = output_key.negate(&self.secp);
+
+            let matched = outputs.iter().enumerate().find_map(|(position, (index, output))| {
+                if output_key.x_only_public_key().0 == *output {
+                    return Some((
+                        position,
+                        FoundSilentPayment {
+                            output_index: *index,
+                            tweak,
+                            label: None,
+                        },
+                    ));
+                }
+
+                let even = PublicKey::from_x_only_public_key(*output, Parity::Even);
+                [even, even.negate(&self.secp)].into_iter().find_map(|candidate| {
+                    let label_point = candidate.combine(&negated_output_key).ok()?;
+                    let label = *labels.get(&label_point)?;
+                    let tweak = tweak.add_tweak(&Scalar::from(self.label_tweak(label))).ok()?;
+                    Some((
+                        position,
+                        FoundSilentPayment {
+                            output_index: *index,
+                            tweak,
+                            label: Some(label),
+                        },
+                    ))
+                })
+            });
+
+            let Some((position, payment)) = matched else {
+                break;
+            };
+            outputs.remove(position);
+            found.push(payment);
+        }
+
+        found
+    }
+
+    /// Secret key of an output found with `tweak`, normalized for use as a taproot key path input.
+    fn output_secret(&self, tweak: &str) -> Result<SecretKey, String> {
+        let tweak = hex::decode(tweak).map_err(|e| e.to_string())?;
+        let tweak = SecretKey::from_slice(&tweak).map_err(|e| e.to_string())?;
+        let secret = self
+            .spend_secret
+            .add_tweak(&Scalar::from(tweak))
+            .map_err(|e| e.to_string())?;
+
+        Ok(match secret.x_only_public_key(&self.secp).1 {
+            Parity::Even => secret,
+            Parity::Odd => secret.negate(),
+        })
+    }
+
+    /// Script of the output found with `tweak`.
+    pub fn output_script(&self, tweak: &str) -> Result<ScriptBuf, String> {
+        let secret = self.output_secret(tweak)?;
+        Ok(taproot_script(secret.x_only_public_key(&self.secp).0))
+    }
+
+    /// Builds and signs the transaction paying `recipient` all of `funding` but the fee. `funding` is a single use
+    /// output the node wallet paid to our keys: BIP352 derives the payment from the secrets of the inputs, which
+    /// the node wallet doesn't expose.
+    pub fn build_transaction(
+        &self,
+        funding: &SilentPaymentOutput,
+        recipient: &SilentPaymentAddress,
+        feerate_sat_vb: u32,
+    ) -> Result<SilentPaymentTransaction, String> {
+        self.spend(
+            vec![funding.clone()],
+            |secrets, outpoints| silent_payment_script(&self.secp, secrets, outpoints, recipient),
+            feerate_sat_vb,
+        )
+    }
+
+    /// Builds and signs the transaction sweeping `outputs` to `destination`, a taproot output of the node wallet.
+    pub fn sweep_transaction(
+        &self,
+        outputs: &[SilentPaymentOutput],
+        destination: ScriptBuf,
+        feerate_sat_vb: u32,
+    ) -> Result<SilentPaymentTransaction, String> {
+        self.spend(outputs.to_vec(), |_, _| Ok(destination), feerate_sat_vb)
+    }
+
+    /// Signs a transaction spending all of `spent` but the fee to the taproot output built by `script` from the
+    /// input secrets and outpoints.
+    fn spend(
+        &self,
+        spent: Vec<SilentPaymentOutput>,
+        script: impl FnOnce(&[SecretKey], &[OutPoint]) -> Result<ScriptBuf, String>,
+        feerate_sat_vb: u32,
+    ) -> Result<SilentPaymentTransaction, String> {
+        let total_sat: u64 = spent.iter().map(|spent| spent.output.amount_sat).sum();
+        let fee_sat = silent_payment_fee(spent.len(), feerate_sat_vb);
+        let amount_sat = total_sat
+            .checked_sub(fee_sat)
+            .filter(|amount_sat| *amount_sat >= SILENT_PAYMENT_DUST_LIMIT_SAT)
+            .ok_or_else(|| "Insufficient silent payment funds to cover the fee.".to_string())?;
+
+        let mut input_secrets = Vec::with_capacity(spent.len());
+        let mut prevouts = Vec::with_capacity(spent.len());
+        let mut inputs = Vec::with_capacity(spent.len());
+        for spent in &spent {
+            let txid: Txid = spent.output.txid.parse().map_err(|e| format!("Invalid txid: {e}"))?;
+            let secret = self.output_secret(&spent.tweak)?;
+
+            prevouts.push(TxOut {
+                value: Amount::from_sat(spent.output.amount_sat),
+                script_pubkey: taproot_script(secret.x_only_public_key(&self.secp).0),
+            });
+            inputs.push(TxIn {
+                previous_output: OutPoint::new(txid, spent.output.output_index),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                ..Default::default()
+            });
+            input_secrets.push(secret);
+        }
+
+        let outpoints: Vec<OutPoint> = inputs.iter().map(|input| input.previous_output).collect();
+        let mut transaction = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs,
+            output: vec![TxOut {
+                value: Amount::from_sat(amount_sat),
+                script_pubkey: script(&input_secrets, &outpoints)?,
+            }],
+        };
+
+        let mut witnesses = Vec::with_capacity(input_secrets.len());
+        let mut sighashes = SighashCache::new(&transaction);
+        for (index, secret) in input_secrets.iter().enumerate() {
+            let sighash = sighashes
+                .taproot_key_spend_signature_hash(index, &Prevouts::All(&prevouts), TapSighashType::Default)
+                .map_err(|e| e.to_string())?;
+            let keypair = Keypair::from_secret_key(&self.secp, secret);
+            let signature = self
+                .secp
+                .sign_schnorr_no_aux_rand(&Message::from_digest(sighash.to_byte_array()), &keypair);
+
+            witnesses.push(Witness::p2tr_key_spend(&taproot::Signature {
+                signature,
+                sighash_type: TapSighashType::Default,
+            }));
+        }
+        for (input, witness) in transaction.input.iter_mut().zip(witnesses) {
+            input.witness = witness;
+        }
+
+        Ok(SilentPaymentTransaction {
+            transaction,
+            fee_sat,
+            spent,
+        })
+    }
+}
+
+/// Fee of a transaction spending `inputs` of our taproot outputs to a single taproot output.
+pub fn silent_payment_fee(inputs: usize, feerate_sat_vb: u32) -> u64 {
+    let dummy = Transaction {
+        version: transaction::Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![
+            TxIn {
+                witness: Witness::from_slice(&[[0u8; 64]]),
+                ..Default::default()
+            };
+            inputs
+        ],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: taproot_script(XOnlyPublicKey::from_slice(&NUMS_INTERNAL_KEY).expect("valid x-only key")),
+        }],
+    };
+
+    dummy.vsize() as u64 * feerate_sat_vb as u64
+}
+
+/// Whether `address` looks like a silent payment address, of any network.
+pub fn is_silent_payment_address(address: &str) -> bool {
+    let address = address.to_ascii_lowercase();
+    ["sp1", "tsp1", "sprt1"]
+        .iter()
+        .any(|prefix| address.starts_with(prefix))
+}
+
+pub fn decode_silent_payment_address(address: &str) -> Result<SilentPaymentAddress, String> {
+    let mut checked = CheckedHrpstring::new::<Bech32m>(address).map_err(|e| e.to_string())?;
+    let network = match checked.hrp().to_lowercase().as_str() {
+        "sp" => BtcNetwork::Bitcoin,
+        "tsp" => BtcNetwork::Testnet,
+        "sprt" => BtcNetwork::Regtest,
+        hrp => return Err(format!("Unknown silent payment address prefix: {hrp}")),
+    };
+    let version = checked
+        .remove_witness_version()
+        .ok_or_else(|| "Missing silent payment address version".to_string())?;
+    let payload: Vec<u8> = checked.byte_iter().collect();
+
+    // Future versions must remain readable by version 0 senders, using only the first 66 bytes.
+    let valid_length = match version.to_u8() {
+        0 => payload.len() == SILENT_PAYMENT_PAYLOAD_LEN,
+        31 => false,
+        _ => payload.len() >= SILENT_PAYMENT_PAYLOAD_LEN,
+    };
+    if !valid_length {
+        return Err(format!(
+            "Unsupported silent payment address version {}",
+            version.to_u8()
+        ));
+    }
+
+    Ok(SilentPaymentAddress {
+        scan_public_key: PublicKey::from_slice(&payload[..33]).map_err(|e| e.to_string())?,
+        spend_public_key: PublicKey::from_slice(&payload[33..SILENT_PAYMENT_PAYLOAD_LEN]).map_err(|e| e.to_string())?,
+        network,
+    })
+}
+
+pub fn encode_silent_payment_address(address: &SilentPaymentAddress) -> String {
+    let hrp = match address.network {
+        BtcNetwork::Bitcoin => "sp",
+        BtcNetwork::Regtest | BtcNetwork::Simnet => "sprt",
+        BtcNetwork::Testnet | BtcNetwork::Testnet4 | BtcNetwork::Signet => "tsp",
+    };
+    let hrp = Hrp::parse_unchecked(hrp);
+    let mut payload = address.scan_public_key.serialize().to_vec();
+    payload.extend_from_slice(&address.spend_public_key.serialize());
+
+    payload
+        .iter()
+        .copied()
+        .bytes_to_fes()
+        .with_checksum::<Bech32m>(&hrp)
+        .with_witness_version(SILENT_PAYMENT_VERSION)
+        .chars()
+        .collect()
+}
+
+/// Taproot script of the output of `tx` paying `recipient`, given the secrets of the inputs spending `outpoints`.
+fn silent_payment_script(
+    secp: &Secp256k1<All>,
+    input_secrets: &[SecretKey],
+    outpoints: &[OutPoint],
+    recipient: &SilentPaymentAddress,
+) -> Result<ScriptBuf, String> {
+    let (first, rest) = input_secrets
+        .split_first()
+        .ok_or_else(|| "A silent payment needs at least one input".to_string())?;
+    let input_secret = rest.iter().try_fold(*first, |sum, secret| {
+        sum.add_tweak(&Scalar::from(*secret)).map_err(|e| e.to_string())
+    })?;
+    let smallest_outpoint = outpoints
+        .iter()
+        .map(serialize)
+        .min()
+        .ok_or_else(|| "A silent payment needs at least one input".to_string())?;
+
+    let input_hash = input_hash_of(&smallest_outpoint, &input_secret.public_key(secp))
+        .ok_or_else(|| "Invalid silent payment input hash".to_string())?;
+    let shared_secret = input_secret
+        .mul_tweak(&Scalar::from(input_hash))
+        .and_then(|tweak| recipient.scan_public_key.mul_tweak(secp, &Scalar::from(tweak)))
+        .map_err(|e| e.to_string())?;
+    let output_key = recipient
+        .spend_public_key
+        .add_exp_tweak(secp, &Scalar::from(shared_secret_tweak(&shared_secret, 0)))
+        .map_err(|e| e.to_string())?;
+
+    Ok(taproot_script(output_key.x_only_public_key().0))
+}
+
+/// Input hash and sum of the input public keys of `tx`, or `None` if `tx` cannot contain silent payments.
+fn input_hash(tx: &Transaction, prevouts: &[TxOut]) -> Option<(SecretKey, PublicKey)> {
+    if tx.is_coinbase() || tx.input.len() != prevouts.len() {
+        return None;
+    }
+    if !tx.output.iter().any(|output| output.script_pubkey.is_p2tr()) {
+        return None;
+    }
+    // Spends of future segwit versions could change how their keys are found, so those transactions are skipped.
+    if prevouts
+        .iter()
+        .filter_map(|prevout| prevout.script_pubkey.witness_version())
+        .any(|version| version.to_num() > 1)
+    {
+        return None;
+    }
+
+    let public_keys: Vec<PublicKey> = tx
+        .input
+        .iter()
+        .zip(prevouts)
+        .filter_map(|(input, prevout)| input_public_key(input, &prevout.script_pubkey))
+        .collect();
+    let input_public_key = PublicKey::combine_keys(&public_keys.iter().collect::<Vec<_>>()).ok()?;
+    let smallest_outpoint = tx.input.iter().map(|input| serialize(&input.previous_output)).min()?;
+
+    Some((input_hash_of(&smallest_outpoint, &input_public_key)?, input_public_key))
+}
+
+fn input_hash_of(smallest_outpoint: &[u8], input_public_key: &PublicKey) -> Option<SecretKey> {
+    let hash = tagged_hash("BIP0352/Inputs", &[smallest_outpoint, &input_public_key.serialize()]);
+    SecretKey::from_slice(&hash).ok()
+}
+
+/// Public key an input contributes to the shared secret, if any.
+fn input_public_key(input: &TxIn, script_pubkey: &Script) -> Option<PublicKey> {
+    if script_pubkey.is_p2tr() {
+        let mut witness: Vec<&[u8]> = input.witness.iter().collect();
+        if witness.len() > 1 && witness.last()?.first() == Some(&TAPROOT_ANNEX_PREFIX) {
+            witness.pop();
+        }
+        if witness.len() > 1 && witness.last()?.get(1..33) == Some(&NUMS_INTERNAL_KEY[..]) {
+            return None;
+        }
+
+        let output_key = taproot_output_key(script_pubkey)?;
+        return Some(PublicKey::from_x_only_public_key(output_key, Parity::Even));
+    }
+
+    if script_pubkey.is_p2wpkh() {
+        return compressed_public_key(input.witness.last()?);
+    }
+
+    if script_pubkey.is_p2sh() {
+        let redeem_script = input.script_sig.redeem_script()?;
+        return redeem_script
+            .is_p2wpkh()
+            .then(|| compressed_public_key(input.witness.last()?))
+            .flatten();
+    }
+
+    if script_pubkey.is_p2pkh() {
+        let pubkey_hash = script_pubkey.as_bytes().get(3..23)?;
+        let pushes: Vec<_> = input.script_sig.instructions().collect::<Result<_, _>>().ok()?;
+        return pushes.iter().rev().find_map(|instruction| match instruction {
+            Instruction::PushBytes(bytes)
+                if bytes.len() == 33 && hash160::Hash::hash(bytes.as_bytes()).as_byte_array()[..] == *pubkey_hash =>
+            {
+                compressed_public_key(bytes.as_bytes())
+            }
+            _ => None,
+        });
+    }
+
+    None
+}
+
+fn compressed_public_key(bytes: &[u8]) -> Option<PublicKey> {
+    (bytes.len() == 33).then(|| PublicKey::from_slice(bytes).ok()).flatten()
+}
+
+fn taproot_output_key(script_pubkey: &Script) -> Option<XOnlyPublicKey> {
+    if !script_pubkey.is_p2tr() {
+        return None;
+    }
+    XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..34]).ok()
+}
+
+fn taproot_script(output_key: XOnlyPublicKey) -> ScriptBuf {
+    ScriptBuf::new_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(output_key))
+}
+
+fn shared_secret_tweak(shared_secret: &PublicKey, k: u32) -> SecretKey {
+    let hash = tagged_hash("BIP0352/SharedSecret", &[&shared_secret.serialize(), &k.to_be_bytes()]);
+    SecretKey::from_slice(&hash).expect("hash is a valid secret key with overwhelming probability")
+}
+
+fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag_hash.as_ref());
+    engine.input(tag_hash.as_ref());
+    for chunk in data {
+        engine.input(chunk);
+    }
+
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWEAK: &str = "2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a";
+
+    fn receiver() -> SilentPaymentKeys {
+        SilentPaymentKeys::from_hex(&"11".repeat(32), &"22".repeat(32)).unwrap()
+    }
+
+    fn sender() -> SilentPaymentKeys {
+        SilentPaymentKeys::from_hex(&"33".repeat(32), &"44".repeat(32)).unwrap()
+    }
+
+    fn sender_output(amount_sat: u64, output_index: u32) -> SilentPaymentOutput {
+        SilentPaymentOutput {
+            output: BtcOutput {
+                outpoint: format!("{}:{output_index}", "ab".repeat(32)),
+                txid: "ab".repeat(32),
+                output_index,
+                amount_sat,
+                ..Default::default()
+            },
+            tweak: TWEAK.to_string(),
+        }
+    }
+
+    fn prevouts(keys: &SilentPaymentKeys, spent: &[SilentPaymentOutput]) -> Vec<TxOut> {
+        spent
+            .iter()
+            .map(|spent| TxOut {
+                value: Amount::from_sat(spent.output.amount_sat),
+                script_pubkey: keys.output_script(&spent.tweak).unwrap(),
+            })
+            .collect()
+    }
+
+    mod address {
+        use super::*;
+
+        #[test]
+        fn round_trips_through_decoding() {
+            let keys = receiver();
+            let address = keys.address(3, BtcNetwork::Bitcoin);
+
+            assert!(address.starts_with("sp1q"));
+            assert!(is_silent_payment_address(&address));
+
+            let decoded = decode_silent_payment_address(&address).unwrap();
+            assert_eq!(decoded.scan_public_key, keys.scan_public_key());
+            assert_eq!(decoded.network, BtcNetwork::Bitcoin);
+            assert_ne!(decoded.spend_public_key, keys.spend_public_key());
+        }
+
+        #[test]
+        fn uses_the_prefix_of_the_network() {
+            let keys = receiver();
+
+            assert!(keys.address(1, BtcNetwork::Signet).starts_with("tsp1q"));
+            assert!(keys.address(1, BtcNetwork::Regtest).starts_with("sprt1q"));
+        }
+
+        #[test]
+        fn rejects_segwit_addresses() {
+            let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+            assert!(!is_silent_payment_address(address));
+            assert!(decode_silent_payment_address(address).is_err());
+        }
+    }
+
+    mod scan_transaction {
+        use super::*;
+
+        #[test]
+        fn finds_a_payment_to_a_labeled_address() {
+            let sender = sender();
+            let receiver = receiver();
+            let recipient = decode_silent_payment_address(&receiver.address(7, BtcNetwork::Regtest)).unwrap();
+
+            let built = sender
+                .build_transaction(&sender_output(50_000, 0), &recipient, 2)
+                .unwrap();
+            let labels = HashMap::from([(receiver.label_point(7), 7)]);
+            let found = receiver.scan_transaction(&built.transaction, &prevouts(&sender, &built.spent), &labels);
+
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].output_index, 0);
+            assert_eq!(found[0].label, Some(7));
+
+            // The tweak found is the one spending the output.
+            let tweak = hex::encode(found[0].tweak.secret_bytes());
+            assert_eq!(
+                receiver.output_script(&tweak).unwrap(),
+                built.transaction.output[0].script_pubkey
+            );
+        }
+
+        #[test]
+        fn ignores_payments_to_unknown_labels() {
+            let sender = sender();
+            let receiver = receiver();
+            let recipient = decode_silent_payment_address(&receiver.address(7, BtcNetwork::Regtest)).unwrap();
+
+            let built = sender
+                .build_transaction(&sender_output(50_000, 0), &recipient, 2)
+                .unwrap();
+            let labels = HashMap::from([(receiver.label_point(8), 8)]);
+
+            assert!(receiver
+                .scan_transaction(&built.transaction, &prevouts(&sender, &built.spent), &labels)
+                .is_empty());
+        }
+
+        #[test]
+        fn ignores_transactions_without_prevouts() {
+            let sender = sender();
+            let receiver = receiver();
+            let recipient = decode_silent_payment_address(&receiver.address(7, BtcNetwork::Regtest)).unwrap();
+            let built = sender
+                .build_transaction(&sender_output(50_000, 0), &recipient, 2)
+                .unwrap();
+            let labels = HashMap::from([(receiver.label_point(7), 7)]);
+
+            assert!(receiver.scan_transaction(&built.transaction, &[], &labels).is_empty());
+        }
+    }
+
+    mod build_transaction {
+        use super::*;
+
+        #[test]
+        fn pays_the_funding_output_to_the_recipient_minus_the_fee() {
+            let sender = sender();
+            let recipient = decode_silent_payment_address(&receiver().address(1, BtcNetwork::Regtest)).unwrap();
+
+            let built = sender
+                .build_transaction(&sender_output(50_000, 0), &recipient, 2)
+                .unwrap();
+
+            assert_eq!(built.spent.len(), 1);
+            assert_eq!(built.transaction.input.len(), 1);
+            assert_eq!(built.transaction.output.len(), 1);
+            assert_eq!(built.fee_sat, silent_payment_fee(1, 2));
+            assert_eq!(built.fee_sat, built.transaction.vsize() as u64 * 2);
+            assert_eq!(built.transaction.output[0].value.to_sat(), 50_000 - built.fee_sat);
+        }
+
+        #[test]
+        fn fails_when_the_fee_leaves_dust() {
+            let sender = sender();
+            let recipient = decode_silent_payment_address(&receiver().address(1, BtcNetwork::Regtest)).unwrap();
+
+            let err = sender
+                .build_transaction(&sender_output(600, 0), &recipient, 2)
+                .unwrap_err();
+
+            assert!(err.contains("Insufficient"));
+        }
+    }
+
+    mod sweep_transaction {
+        use super::*;
+
+        #[test]
+        fn sweeps_every_output_to_the_destination() {
+            let keys = receiver();
+            let destination = sender().output_script(TWEAK).unwrap();
+            let outputs = vec![sender_output(10_000, 0), sender_output(30_000, 1)];
+
+            let built = receiver().sweep_transaction(&outputs, destination.clone(), 3).unwrap();
+
+            assert_eq!(built.spent.len(), 2);
+            assert_eq!(built.transaction.input.len(), 2);
+            assert_eq!(built.transaction.output.len(), 1);
+            assert_eq!(built.transaction.output[0].script_pubkey, destination);
+            assert_eq!(built.fee_sat, silent_payment_fee(2, 3));
+            assert_eq!(built.fee_sat, built.transaction.vsize() as u64 * 3);
+            assert_eq!(built.transaction.output[0].value.to_sat(), 40_000 - built.fee_sat);
+
+            // Every input is signed by the key of the output it spends.
+            let prevouts = prevouts(&keys, &built.spent);
+            let sighashes = &mut SighashCache::new(&built.transaction);
+            for (index, input) in built.transaction.input.iter().enumerate() {
+                let sighash = sighashes
+                    .taproot_key_spend_signature_hash(index, &Prevouts::All(&prevouts), TapSighashType::Default)
+                    .unwrap();
+                let signature = bitcoin::secp256k1::schnorr::Signature::from_slice(&input.witness[0]).unwrap();
+                let output_key = taproot_output_key(&prevouts[index].script_pubkey).unwrap();
+                keys.secp
+                    .verify_schnorr(&signature, &Message::from_digest(sighash.to_byte_array()), &output_key)
+                    .unwrap();
+            }
+        }
+
+        #[test]
+        fn fails_when_the_fee_leaves_dust() {
+            let err = receiver()
+                .sweep_transaction(&[sender_output(700, 0)], sender().output_script(TWEAK).unwrap(), 2)
+                .unwrap_err();
+
+            assert!(err.contains("Insufficient"));
+        }
+    }
+
+    /// Cases of the BIP352 `send_and_receive_test_vectors.json`, all paying the same recipient from the same two
+    /// outpoints with P2PKH and P2TR key path inputs.
+    mod bip352_vectors {
+        use super::*;
+
+        const SCAN_SECRET: &str = "0f694e068028a717f8af6b9411f9a133dd3565258714cc226594b34db90c1f2c";
+        const SPEND_SECRET: &str = "9d6ad855ce3417ef84e836892e5a56392bfba05fa5d97ccea30e266f540e08b3";
+        const ADDRESS: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+        const OUTPOINTS: [(&str, u32); 2] = [
+            ("f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16", 0),
+            ("a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d", 0),
+        ];
+
+        struct Vector {
+            comment: &'static str,
+            /// Private key of each input, and whether it is spent as a P2TR key path (otherwise P2PKH)
+            inputs: [(&'static str, bool); 2],
+            output: &'static str,
+        }
+
+        const VECTORS: [Vector; 5] = [
+            Vector {
+                comment: "Simple send: two inputs",
+                inputs: [
+                    (
+                        "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+                        false,
+                    ),
+                    (
+                        "93f5ed907ad5b2bdbbdcb5d9116ebc0a4e1f92f910d5260237fa45a9408aad16",
+                        false,
+                    ),
+                ],
+                output: "3e9fce73d4e77a4809908e3c3a2e54ee147b9312dc5044a193d1fc85de46e3c1",
+            },
+            Vector {
+                comment: "Single recipient: taproot only inputs with even y-values",
+                inputs: [
+                    ("eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1", true),
+                    ("fc8716a97a48ba9a05a98ae47b5cd201a25a7fd5d8b73c203c5f7b6b6b3b6ad7", true),
+                ],
+                output: "de88bea8e7ffc9ce1af30d1132f910323c505185aec8eae361670421e749a1fb",
+            },
+            Vector {
+                comment: "Single recipient: taproot only with mixed even/odd y-values",
+                inputs: [
+                    ("eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1", true),
+                    ("1d37787c2b7116ee983e9f9c13269df29091b391c04db94239e0d2bc2182c3bf", true),
+                ],
+                output: "77cab7dd12b10259ee82c6ea4b509774e33e7078e7138f568092241bf26b99f1",
+            },
+            Vector {
+                comment: "Single recipient: taproot input with even y-value and non-taproot input",
+                inputs: [
+                    ("eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1", true),
+                    (
+                        "8d4751f6e8a3586880fb66c19ae277969bd5aa06f61c4ee2f1e2486efdf666d3",
+                        false,
+                    ),
+                ],
+                output: "30523cca96b2a9ae3c98beb5e60f7d190ec5bc79b2d11a0b2d4d09a608c448f0",
+            },
+            Vector {
+                comment: "Single recipient: taproot input with odd y-value and non-taproot input",
+                inputs: [
+                    ("1d37787c2b7116ee983e9f9c13269df29091b391c04db94239e0d2bc2182c3bf", true),
+                    (
+                        "8d4751f6e8a3586880fb66c19ae277969bd5aa06f61c4ee2f1e2486efdf666d3",
+                        false,
+                    ),
+                ],
+                output: "359358f59ee9e9eec3f00bdf4882570fd5c182e451aa2650b788544aff012a3a",
+            },
+        ];
+
+        fn secret(hex: &str) -> SecretKey {
+            SecretKey::from_slice(&hex::decode(hex).unwrap()).unwrap()
+        }
+
+        fn outpoints() -> Vec<OutPoint> {
+            OUTPOINTS
+                .iter()
+                .map(|(txid, vout)| OutPoint::new(txid.parse().unwrap(), *vout))
+                .collect()
+        }
+
+        fn output_script(output: &str) -> ScriptBuf {
+            taproot_script(XOnlyPublicKey::from_slice(&hex::decode(output).unwrap()).unwrap())
+        }
+
+        /// Transaction spending the vector inputs to its output, with the prevouts those inputs spend. Signatures
+        /// are dummies: only the public keys they reveal matter to scanning.
+        fn transaction(vector: &Vector) -> (Transaction, Vec<TxOut>) {
+            let secp = Secp256k1::new();
+            let mut inputs = Vec::new();
+            let mut prevouts = Vec::new();
+            for ((key, taproot), outpoint) in vector.inputs.iter().zip(outpoints()) {
+                let public_key = secret(key).public_key(&secp);
+                let mut input = TxIn {
+                    previous_output: outpoint,
+                    ..Default::default()
+                };
+                let script_pubkey = if *taproot {
+                    input.witness = Witness::from_slice(&[[1u8; 64]]);
+                    taproot_script(public_key.x_only_public_key().0)
+                } else {
+                    input.script_sig = bitcoin::script::Builder::new()
+                        .push_slice([1u8; 71])
+                        .push_slice(public_key.serialize())
+                        .into_script();
+                    ScriptBuf::new_p2pkh(&bitcoin::PublicKey::new(public_key).pubkey_hash())
+                };
+                inputs.push(input);
+                prevouts.push(TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey,
+                });
+            }
+
+            let tx = Transaction {
+                version: transaction::Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: inputs,
+                output: vec![TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey: output_script(vector.output),
+                }],
+            };
+            (tx, prevouts)
+        }
+
+        #[test]
+        fn the_recipient_address_encodes_the_receiver_keys() {
+            let keys = SilentPaymentKeys::from_hex(SCAN_SECRET, SPEND_SECRET).unwrap();
+
+            assert_eq!(
+                encode_silent_payment_address(&SilentPaymentAddress {
+                    scan_public_key: keys.scan_public_key(),
+                    spend_public_key: keys.spend_public_key(),
+                    network: BtcNetwork::Bitcoin,
+                }),
+                ADDRESS
+            );
+        }
+
+        #[test]
+        fn sending_derives_the_expected_output() {
+            let secp = Secp256k1::new();
+            let recipient = decode_silent_payment_address(ADDRESS).unwrap();
+
+            for vector in &VECTORS {
+                // Taproot inputs are signed with the key of their even y-value output key.
+                let secrets: Vec<SecretKey> = vector
+                    .inputs
+                    .iter()
+                    .map(|(key, taproot)| match (secret(key), taproot) {
+                        (key, true) if key.x_only_public_key(&secp).1 == Parity::Odd => key.negate(),
+                        (key, _) => key,
+                    })
+                    .collect();
+
+                let mut outpoints = outpoints();
+                let script = silent_payment_script(&secp, &secrets, &outpoints, &recipient).unwrap();
+                assert_eq!(script, output_script(vector.output), "{}", vector.comment);
+
+                // The smallest outpoint, not the input order, determines the output.
+                outpoints.reverse();
+                let reversed: Vec<SecretKey> = secrets.into_iter().rev().collect();
+                let script = silent_payment_script(&secp, &reversed, &outpoints, &recipient).unwrap();
+                assert_eq!(
+                    script,
+                    output_script(vector.output),
+                    "{} (order reversed)",
+                    vector.comment
+                );
+            }
+        }
+
+        #[test]
+        fn receiving_finds_the_output_and_its_spending_tweak() {
+            let keys = SilentPaymentKeys::from_hex(SCAN_SECRET, SPEND_SECRET).unwrap();
+
+            for vector in &VECTORS {
+                let (tx, prevouts) = transaction(vector);
+
+                let found = keys.scan_transaction(&tx, &prevouts, &HashMap::new());
+
+                assert_eq!(found.len(), 1, "{}", vector.comment);
+                assert_eq!(found[0].output_index, 0);
+                assert_eq!(found[0].label, None);
+                let tweak = found[0].tweak.display_secret().to_string();
+                assert_eq!(keys.output_script(&tweak).unwrap(), tx.output[0].script_pubkey);
+            }
+
+            let (tx, prevouts) = transaction(&VECTORS[0]);
+            assert_eq!(
+                keys.scan_transaction(&tx, &prevouts, &HashMap::new())[0]
+                    .tweak
+                    .display_secret()
+                    .to_string(),
+                "f438b40179a3c4262de12986c0e6cce0634007cdc79c1dcd3e20b9ebc2e7eef6"
+            );
+        }
+    }
+}
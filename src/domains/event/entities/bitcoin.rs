@@ -7,6 +7,8 @@ pub struct OnchainDepositEvent {
     pub address: String,
     pub amount_sat: u64,
     pub block_height: Option<u32>,
+    /// Chain tip to count confirmations against, when the caller already knows it
+    pub tip_height: Option<u32>,
 }
 
 impl From<BtcOutput> for OnchainDepositEvent {
@@ -17,6 +19,7 @@ impl From<BtcOutput> for OnchainDepositEvent {
             address: output.address,
             amount_sat: output.amount_sat,
             block_height: output.block_height,
+            tip_height: None,
         }
     }
 }
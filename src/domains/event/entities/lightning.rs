@@ -6,6 +6,7 @@ pub struct LnInvoicePaidEvent {
     pub amount_received_msat: u64,
     pub fee_msat: u64,
     pub payment_time: DateTime<Utc>,
+    pub payment_preimage: Option<String>,
 }
 
 #[derive(Debug, Clone)]
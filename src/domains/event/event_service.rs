@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use chrono::Utc;
-use tracing::{debug, info, trace};
+use tracing::{debug, info, trace, warn};
+use uuid::Uuid;
 
 use crate::{
     application::{
@@ -8,34 +11,191 @@ use crate::{
         errors::ApplicationError,
     },
     domains::{
-        bitcoin::{BtcOutput, BtcOutputStatus},
+        bitcoin::{confirmations, BitcoinWallet, BtcOutput, BtcOutputStatus, DepositConfirmationPolicy},
         event::{
             EventUseCases, LnInvoicePaidEvent, LnPayFailureEvent, LnPaySuccessEvent, OnchainDepositEvent,
             OnchainWithdrawalEvent,
         },
         invoice::{Invoice, InvoiceStatus},
+        ln_address::split_amounts,
         lnurl::process_success_action,
-        payment::{Payment, PaymentStatus},
+        payment::{InternalPayment, Payment, PaymentStatus},
     },
+    infra::lightning::LnClient,
 };
 
 const DEFAULT_DEPOSIT_DESCRIPTION: &str = "Bitcoin On-chain deposit";
+const SPLIT_DESCRIPTION: &str = "Lightning Address payment split";
 
 #[derive(Clone)]
 pub struct EventService {
     store: AppStore,
+    ln_client: Arc<dyn LnClient>,
+    bitcoin_wallet: Arc<dyn BitcoinWallet>,
+    confirmation_policy: DepositConfirmationPolicy,
 }
 
 impl EventService {
-    pub fn new(store: AppStore) -> Self {
-        EventService { store }
+    pub fn new(
+        store: AppStore,
+        ln_client: Arc<dyn LnClient>,
+        bitcoin_wallet: Arc<dyn BitcoinWallet>,
+        confirmation_policy: DepositConfirmationPolicy,
+    ) -> Self {
+        EventService {
+            store,
+            ln_client,
+            bitcoin_wallet,
+            confirmation_policy,
+        }
+    }
+
+    fn output_status(confirmations: u32, required_confirmations: u32) -> BtcOutputStatus {
+        if confirmations > 0 && confirmations >= required_confirmations {
+            BtcOutputStatus::Confirmed
+        } else {
+            BtcOutputStatus::Unconfirmed
+        }
+    }
+
+    /// Counts the confirmations of a deposit and the threshold its wallet asset and amount require.
+    async fn deposit_confirmations(
+        &self,
+        wallet_id: Uuid,
+        block_height: Option<u32>,
+        tip_height: Option<u32>,
+        amount_sat: u64,
+    ) -> Result<(u32, u32), ApplicationError> {
+        if !matches!(block_height, Some(height) if height > 0) {
+            return Ok((0, 0));
+        }
+
+        let tip_height = match tip_height {
+            Some(tip_height) => tip_height,
+            None => self.bitcoin_wallet.block_height().await?,
+        };
+        let asset_id = self
+            .store
+            .wallet
+            .find(wallet_id)
+            .await?
+            .map(|wallet| wallet.asset_id)
+            .unwrap_or_default();
+
+        Ok((
+            confirmations(block_height, tip_height),
+            self.confirmation_policy.required_confirmations(asset_id, amount_sat),
+        ))
     }
 
-    fn output_status(block_height: Option<u32>) -> BtcOutputStatus {
-        match block_height {
-            Some(height) if height > 0 => BtcOutputStatus::Confirmed,
-            _ => BtcOutputStatus::Unconfirmed,
+    /// Settles the Lightning leg of a payment request paid on-chain with `amount_sat`. The invoice is cancelled on
+    /// the node first, so it is only marked settled once it can no longer be paid. An underpaid request stays open,
+    /// its deposit credited on its own.
+    async fn settle_payment_request_invoice(&self, invoice_id: Uuid, amount_sat: u64) -> Result<(), ApplicationError> {
+        let Some(mut invoice) = self.store.invoice.find(invoice_id).await? else {
+            return Ok(());
+        };
+        if invoice.status == InvoiceStatus::Settled {
+            return Ok(());
+        }
+        let Some(payment_hash) = invoice
+            .ln_invoice
+            .as_ref()
+            .map(|ln_invoice| ln_invoice.payment_hash.clone())
+        else {
+            return Ok(());
+        };
+
+        if let Some(amount_msat) = invoice
+            .amount_msat
+            .filter(|amount_msat| amount_sat * 1000 < *amount_msat)
+        {
+            info!(%invoice_id, amount_sat, amount_msat, "Payment request underpaid on-chain, left open");
+            return Ok(());
         }
+
+        if let Err(err) = self
+            .ln_client
+            .cancel_invoice(payment_hash.clone(), invoice_id.to_string())
+            .await
+        {
+            // Cancelling fails if the invoice was paid meanwhile, whose own event settles the request, or is
+            // already cancelled or deleted. While it can still be paid, the deposit is processed again with the
+            // next sync.
+            let ln_invoice = self.ln_client.invoice_by_hash(payment_hash.clone()).await?;
+            match ln_invoice.map(|ln_invoice| ln_invoice.status) {
+                Some(InvoiceStatus::Settled) => {
+                    warn!(%invoice_id, %payment_hash, "Payment request paid both on-chain and over Lightning");
+                    return Ok(());
+                }
+                Some(InvoiceStatus::Pending) => {
+                    warn!(%invoice_id, %payment_hash, %err, "Failed to cancel invoice of payment request paid on-chain");
+                    return Err(err.into());
+                }
+                Some(InvoiceStatus::Expired) | None => {}
+            }
+        }
+
+        invoice.payment_time = Some(Utc::now());
+        if self.store.invoice.settle(&invoice).await? {
+            info!(%invoice_id, "Payment request settled on-chain, invoice cancelled");
+        }
+
+        Ok(())
+    }
+
+    /// Internal transfers passing the configured shares of a Lightning Address payment on to their wallets.
+    async fn split_transfers(&self, invoice: &Invoice) -> Result<Vec<(Payment, Invoice)>, ApplicationError> {
+        let (Some(ln_address_id), Some(received_msat)) = (invoice.ln_address_id, invoice.amount_received_msat) else {
+            return Ok(vec![]);
+        };
+        let Some(ln_address) = self.store.ln_address.find(ln_address_id).await? else {
+            return Ok(vec![]);
+        };
+
+        let payment_time = invoice.payment_time.unwrap_or_else(Utc::now);
+        let mut transfers = vec![];
+        for (wallet_id, amount_msat) in split_amounts(received_msat, &ln_address.splits) {
+            // The share stays with the receiver when its wallet was deleted or now receives the address itself.
+            if wallet_id == invoice.wallet_id || self.store.wallet.find(wallet_id).await?.is_none() {
+                warn!(%wallet_id, invoice_id = %invoice.id, "Skipping payment split to an unavailable wallet");
+                continue;
+            }
+
+            let payment = Payment {
+                wallet_id: invoice.wallet_id,
+                amount_msat,
+                status: PaymentStatus::Settled,
+                description: Some(SPLIT_DESCRIPTION.to_string()),
+                fee_msat: Some(0),
+                payment_time: Some(payment_time),
+                ledger: Ledger::Internal,
+                internal: Some(InternalPayment {
+                    ln_address: None,
+                    btc_address: None,
+                    payment_hash: None,
+                    split_invoice_id: Some(invoice.id),
+                    transfer_id: None,
+                }),
+                ..Default::default()
+            };
+            let split_invoice = Invoice {
+                wallet_id,
+                split_invoice_id: Some(invoice.id),
+                ledger: Ledger::Internal,
+                description: Some(SPLIT_DESCRIPTION.to_string()),
+                amount_msat: Some(amount_msat),
+                amount_received_msat: Some(amount_msat),
+                timestamp: payment_time,
+                status: InvoiceStatus::Settled,
+                fee_msat: Some(0),
+                payment_time: Some(payment_time),
+                ..Default::default()
+            };
+            transfers.push((payment, split_invoice));
+        }
+
+        Ok(transfers)
     }
 
     fn project_lightning_settlement(payment: &mut Payment, event: &LnPaySuccessEvent) {
@@ -60,12 +220,21 @@ impl EventUseCases for EventService {
         let invoice_option = self.store.invoice.find_by_payment_hash(&event.payment_hash).await?;
 
         if let Some(mut invoice) = invoice_option {
+            let already_settled = invoice.status == InvoiceStatus::Settled;
             invoice.status = InvoiceStatus::Settled;
             invoice.fee_msat = Some(event.fee_msat);
             invoice.payment_time = Some(event.payment_time);
             invoice.amount_received_msat = Some(event.amount_received_msat);
+            if let Some(ln_invoice) = invoice.ln_invoice.as_mut() {
+                ln_invoice.payment_preimage.clone_from(&event.payment_preimage);
+            }
 
-            invoice = self.store.event_uow.settle_incoming_invoice(invoice).await?;
+            let splits = if already_settled {
+                vec![]
+            } else {
+                self.split_transfers(&invoice).await?
+            };
+            invoice = self.store.event_uow.settle_incoming_invoice(invoice, splits).await?;
 
             info!(id = %invoice.id, "Incoming Lightning payment processed successfully");
             return Ok(());
@@ -152,8 +321,23 @@ impl EventUseCases for EventService {
         trace!(%outpoint, "Processing onchain deposit event");
 
         let address = event.address;
-        let status = Self::output_status(event.block_height);
+        let Some(btc_address) = self.store.btc_address.find_by_address(&address).await? else {
+            trace!(%address, %outpoint, "Ignoring bitcoin output not matching any known wallet address");
+            return Ok(false);
+        };
+
+        let (confirmations, required_confirmations) = self
+            .deposit_confirmations(
+                btc_address.wallet_id,
+                event.block_height,
+                event.tip_height,
+                event.amount_sat,
+            )
+            .await?;
+        let status = Self::output_status(confirmations, required_confirmations);
         let is_confirmed = status == BtcOutputStatus::Confirmed;
+        trace!(%outpoint, confirmations, required_confirmations, "Onchain deposit confirmations counted");
+
         let output = BtcOutput {
             outpoint: outpoint.clone(),
             txid: event.txid.clone(),
@@ -165,11 +349,6 @@ impl EventUseCases for EventService {
             ..Default::default()
         };
 
-        let Some(btc_address) = self.store.btc_address.find_by_address(&address).await? else {
-            trace!(%address, %outpoint, "Ignoring bitcoin output not matching any known wallet address");
-            return Ok(false);
-        };
-
         let amount_msat = event.amount_sat.saturating_mul(1000);
         let now = Utc::now();
         let deposit_invoice = Invoice {
@@ -184,17 +363,38 @@ impl EventUseCases for EventService {
         };
 
         // Output upsert, address mark-used, and invoice settle/insert (+ balance credit when
-        // confirmed) are applied atomically; idempotent if the event is replayed.
+        // confirmed, or reversal when a credited output was reorged out) are applied atomically;
+        // idempotent if the event is replayed.
+        let payment_request_invoice_id = btc_address.invoice_id;
         let invoice = self
             .store
             .event_uow
             .project_onchain_deposit(output, btc_address, deposit_invoice)
             .await?;
 
+        if let Some(invoice_id) = payment_request_invoice_id.filter(|_| is_confirmed) {
+            self.settle_payment_request_invoice(invoice_id, event.amount_sat)
+                .await?;
+        }
+
         info!(invoice_id = %invoice.id, %outpoint, %address, "Onchain deposit processed");
         Ok(true)
     }
 
+    async fn onchain_deposit_dropped(&self, output: BtcOutput) -> Result<bool, ApplicationError> {
+        let outpoint = output.outpoint.clone();
+        trace!(%outpoint, "Processing dropped onchain deposit");
+
+        let invoice = self.store.event_uow.drop_onchain_deposit(output).await?;
+
+        warn!(
+            invoice_id = ?invoice.map(|invoice| invoice.id),
+            %outpoint,
+            "Onchain deposit dropped from the chain; credit reversed"
+        );
+        Ok(true)
+    }
+
     async fn onchain_withdrawal(&self, event: OnchainWithdrawalEvent) -> Result<bool, ApplicationError> {
         trace!(txid = %event.txid, block_height = event.block_height, "Processing onchain withdrawal event");
 
@@ -230,18 +430,52 @@ mod tests {
     use uuid::Uuid;
 
     use crate::{
-        application::composition::MockAppStoreBuilder,
+        application::{composition::MockAppStoreBuilder, errors::LightningError},
         domains::{
-            bitcoin::BtcAddress,
+            bitcoin::{BtcAddress, ConfirmationTier, MockBitcoinWallet},
+            invoice::LnInvoice,
             lnurl::LnUrlPaySuccessAction,
             payment::{LnPayment, Payment},
+            wallet::Wallet,
         },
+        infra::lightning::MockLnClient,
     };
 
     use super::*;
 
     fn service(store: MockAppStoreBuilder) -> EventService {
-        EventService::new(store.build())
+        service_with(store, MockBitcoinWallet::new(), DepositConfirmationPolicy::default())
+    }
+
+    fn service_with(
+        store: MockAppStoreBuilder,
+        bitcoin_wallet: MockBitcoinWallet,
+        confirmation_policy: DepositConfirmationPolicy,
+    ) -> EventService {
+        EventService::new(
+            store.build(),
+            Arc::new(MockLnClient::new()),
+            Arc::new(bitcoin_wallet),
+            confirmation_policy,
+        )
+    }
+
+    fn chain_tip(height: u32) -> MockBitcoinWallet {
+        let mut bitcoin_wallet = MockBitcoinWallet::new();
+        bitcoin_wallet
+            .expect_block_height()
+            .times(1)
+            .returning(move || Ok(height));
+        bitcoin_wallet
+    }
+
+    fn expect_wallet_lookup(store: &mut MockAppStoreBuilder) {
+        store.wallet.expect_find().times(1).returning(|id| {
+            Ok(Some(Wallet {
+                id,
+                ..Default::default()
+            }))
+        });
     }
 
     fn btc_address(used: bool) -> BtcAddress {
@@ -251,6 +485,8 @@ mod tests {
             address: "bc1qknown".to_string(),
             used,
             address_type: crate::domains::bitcoin::BtcAddressType::P2wpkh,
+            payjoin_uri: None,
+            invoice_id: None,
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -274,23 +510,145 @@ mod tests {
                 store
                     .event_uow
                     .expect_settle_incoming_invoice()
-                    .withf(|invoice| {
-                        invoice.status == InvoiceStatus::Settled && invoice.amount_received_msat == Some(2_000)
+                    .withf(|invoice, splits| {
+                        invoice.status == InvoiceStatus::Settled
+                            && invoice.amount_received_msat == Some(2_000)
+                            && splits.is_empty()
                     })
                     .times(1)
-                    .returning(Ok);
+                    .returning(|invoice, _| Ok(invoice));
 
                 let event = LnInvoicePaidEvent {
                     payment_hash: "ph".to_string(),
                     amount_received_msat: 2_000,
                     fee_msat: 1,
                     payment_time: Utc::now(),
+                    payment_preimage: None,
                 };
 
                 assert!(service(store).invoice_paid(event).await.is_ok());
             }
         }
 
+        mod when_the_address_splits_payments {
+            use super::*;
+
+            use crate::domains::ln_address::{LnAddress, LnAddressSplit};
+
+            fn event() -> LnInvoicePaidEvent {
+                LnInvoicePaidEvent {
+                    payment_hash: "ph".to_string(),
+                    amount_received_msat: 2_005,
+                    fee_msat: 0,
+                    payment_time: Utc::now(),
+                    payment_preimage: None,
+                }
+            }
+
+            fn store(status: InvoiceStatus, invoice_id: Uuid, receiver: Uuid) -> MockAppStoreBuilder {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .invoice
+                    .expect_find_by_payment_hash()
+                    .times(1)
+                    .returning(move |_| {
+                        Ok(Some(Invoice {
+                            id: invoice_id,
+                            wallet_id: receiver,
+                            ln_address_id: Some(Uuid::new_v4()),
+                            status: status.clone(),
+                            ..Default::default()
+                        }))
+                    });
+                store
+            }
+
+            #[tokio::test]
+            async fn passes_the_shares_on_with_the_settlement() {
+                let invoice_id = Uuid::new_v4();
+                let receiver = Uuid::new_v4();
+                let platform = Uuid::new_v4();
+
+                let mut store = store(InvoiceStatus::Pending, invoice_id, receiver);
+                store.ln_address.expect_find().times(1).returning(move |id| {
+                    Ok(Some(LnAddress {
+                        id,
+                        wallet_id: receiver,
+                        splits: vec![LnAddressSplit {
+                            wallet_id: platform,
+                            share_bps: 1_000,
+                        }],
+                        ..Default::default()
+                    }))
+                });
+                store.wallet.expect_find().times(1).returning(|id| {
+                    Ok(Some(Wallet {
+                        id,
+                        ..Default::default()
+                    }))
+                });
+                store
+                    .event_uow
+                    .expect_settle_incoming_invoice()
+                    .withf(move |invoice, splits| {
+                        let [(payment, split_invoice)] = splits.as_slice() else {
+                            return false;
+                        };
+                        invoice.amount_received_msat == Some(2_005)
+                            && payment.wallet_id == receiver
+                            && payment.amount_msat == 200
+                            && payment.ledger == Ledger::Internal
+                            && payment.internal.as_ref().and_then(|internal| internal.split_invoice_id)
+                                == Some(invoice_id)
+                            && split_invoice.wallet_id == platform
+                            && split_invoice.amount_received_msat == Some(200)
+                            && split_invoice.split_invoice_id == Some(invoice_id)
+                    })
+                    .times(1)
+                    .returning(|invoice, _| Ok(invoice));
+
+                assert!(service(store).invoice_paid(event()).await.is_ok());
+            }
+
+            #[tokio::test]
+            async fn skips_wallets_deleted_since() {
+                let mut store = store(InvoiceStatus::Pending, Uuid::new_v4(), Uuid::new_v4());
+                store.ln_address.expect_find().times(1).returning(|id| {
+                    Ok(Some(LnAddress {
+                        id,
+                        splits: vec![LnAddressSplit {
+                            wallet_id: Uuid::new_v4(),
+                            share_bps: 1_000,
+                        }],
+                        ..Default::default()
+                    }))
+                });
+                store.wallet.expect_find().times(1).returning(|_| Ok(None));
+                store
+                    .event_uow
+                    .expect_settle_incoming_invoice()
+                    .withf(|_, splits| splits.is_empty())
+                    .times(1)
+                    .returning(|invoice, _| Ok(invoice));
+
+                assert!(service(store).invoice_paid(event()).await.is_ok());
+            }
+
+            #[tokio::test]
+            async fn does_not_split_a_replayed_settlement() {
+                let mut store = store(InvoiceStatus::Settled, Uuid::new_v4(), Uuid::new_v4());
+                store.ln_address.expect_find().never();
+                store
+                    .event_uow
+                    .expect_settle_incoming_invoice()
+                    .withf(|_, splits| splits.is_empty())
+                    .times(1)
+                    .returning(|invoice, _| Ok(invoice));
+
+                assert!(service(store).invoice_paid(event()).await.is_ok());
+            }
+        }
+
         mod when_invoice_is_missing {
             use super::*;
 
@@ -308,6 +666,7 @@ mod tests {
                     amount_received_msat: 2_000,
                     fee_msat: 1,
                     payment_time: Utc::now(),
+                    payment_preimage: None,
                 };
 
                 assert!(service(store).invoice_paid(event).await.is_ok());
@@ -562,6 +921,7 @@ mod tests {
                     .expect_find_by_address()
                     .times(1)
                     .returning(|_| Ok(Some(btc_address(false))));
+                expect_wallet_lookup(&mut store);
                 store
                     .event_uow
                     .expect_project_onchain_deposit()
@@ -579,9 +939,101 @@ mod tests {
                     address: "bc1qknown".to_string(),
                     amount_sat: 1_000,
                     block_height: Some(800_000),
+                    tip_height: None,
                 };
 
-                let processed = service(store).onchain_deposit(event).await.unwrap();
+                let processed = service_with(store, chain_tip(800_000), DepositConfirmationPolicy::default())
+                    .onchain_deposit(event)
+                    .await
+                    .unwrap();
+
+                assert!(processed);
+            }
+        }
+
+        mod when_confirmations_are_below_the_threshold {
+            use super::*;
+
+            #[tokio::test]
+            async fn keeps_the_deposit_pending() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(Some(btc_address(false))));
+                expect_wallet_lookup(&mut store);
+                store
+                    .event_uow
+                    .expect_project_onchain_deposit()
+                    .withf(|output, _address, invoice| {
+                        output.status == BtcOutputStatus::Unconfirmed
+                            && output.block_height == Some(800_000)
+                            && invoice.amount_received_msat.is_none()
+                            && invoice.payment_time.is_none()
+                    })
+                    .times(1)
+                    .returning(|_, _, invoice| Ok(invoice));
+
+                let policy = DepositConfirmationPolicy {
+                    confirmations: 3,
+                    ..Default::default()
+                };
+                let event = OnchainDepositEvent {
+                    txid: "txid".to_string(),
+                    output_index: 0,
+                    address: "bc1qknown".to_string(),
+                    amount_sat: 1_000,
+                    block_height: Some(800_000),
+                    tip_height: None,
+                };
+
+                let processed = service_with(store, chain_tip(800_001), policy)
+                    .onchain_deposit(event)
+                    .await
+                    .unwrap();
+
+                assert!(processed);
+            }
+
+            #[tokio::test]
+            async fn applies_the_amount_tier() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(Some(btc_address(false))));
+                expect_wallet_lookup(&mut store);
+                store
+                    .event_uow
+                    .expect_project_onchain_deposit()
+                    .withf(|output, _address, invoice| {
+                        output.status == BtcOutputStatus::Unconfirmed && invoice.payment_time.is_none()
+                    })
+                    .times(1)
+                    .returning(|_, _, invoice| Ok(invoice));
+
+                let policy = DepositConfirmationPolicy {
+                    tiers: vec![ConfirmationTier {
+                        min_amount_sat: 1_000_000,
+                        confirmations: 6,
+                    }],
+                    ..Default::default()
+                };
+                let event = OnchainDepositEvent {
+                    txid: "txid".to_string(),
+                    output_index: 0,
+                    address: "bc1qknown".to_string(),
+                    amount_sat: 2_000_000,
+                    block_height: Some(800_000),
+                    tip_height: None,
+                };
+
+                let processed = service_with(store, chain_tip(800_004), policy)
+                    .onchain_deposit(event)
+                    .await
+                    .unwrap();
 
                 assert!(processed);
             }
@@ -615,6 +1067,7 @@ mod tests {
                     address: "bc1qknown".to_string(),
                     amount_sat: 1_000,
                     block_height: None,
+                    tip_height: None,
                 };
 
                 let processed = service(store).onchain_deposit(event).await.unwrap();
@@ -622,6 +1075,213 @@ mod tests {
                 assert!(processed);
             }
         }
+
+        mod when_address_belongs_to_a_payment_request {
+            use super::*;
+
+            fn pending_invoice(id: Uuid, amount_msat: u64) -> Invoice {
+                Invoice {
+                    id,
+                    amount_msat: Some(amount_msat),
+                    status: InvoiceStatus::Pending,
+                    ledger: Ledger::Lightning,
+                    ln_invoice: Some(LnInvoice {
+                        payment_hash: "ph".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            }
+
+            /// Store of a deposit of 1,000 sats to the address of a payment request of `amount_msat`.
+            fn store(invoice_id: Uuid, amount_msat: u64) -> MockAppStoreBuilder {
+                let mut store = MockAppStoreBuilder::new();
+                store.btc_address.expect_find_by_address().times(1).returning(move |_| {
+                    Ok(Some(BtcAddress {
+                        invoice_id: Some(invoice_id),
+                        ..btc_address(false)
+                    }))
+                });
+                expect_wallet_lookup(&mut store);
+                store
+                    .event_uow
+                    .expect_project_onchain_deposit()
+                    .times(1)
+                    .returning(|_, _, invoice| Ok(invoice));
+                store
+                    .invoice
+                    .expect_find()
+                    .times(1)
+                    .returning(move |id| Ok(Some(pending_invoice(id, amount_msat))));
+                store
+            }
+
+            fn event() -> OnchainDepositEvent {
+                OnchainDepositEvent {
+                    txid: "txid".to_string(),
+                    output_index: 0,
+                    address: "bc1qknown".to_string(),
+                    amount_sat: 1_000,
+                    block_height: Some(800_000),
+                    tip_height: None,
+                }
+            }
+
+            fn service(store: MockAppStoreBuilder, ln_client: MockLnClient) -> EventService {
+                EventService::new(
+                    store.build(),
+                    Arc::new(ln_client),
+                    Arc::new(chain_tip(800_000)),
+                    DepositConfirmationPolicy::default(),
+                )
+            }
+
+            #[tokio::test]
+            async fn cancels_and_settles_the_invoice() {
+                let invoice_id = Uuid::new_v4();
+                let mut store = store(invoice_id, 1_000_000);
+                store
+                    .invoice
+                    .expect_settle()
+                    .withf(move |invoice| invoice.id == invoice_id && invoice.payment_time.is_some())
+                    .times(1)
+                    .returning(|_| Ok(true));
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_cancel_invoice()
+                    .withf(move |payment_hash, label| payment_hash == "ph" && *label == invoice_id.to_string())
+                    .times(1)
+                    .returning(|_, _| Ok(()));
+
+                assert!(service(store, ln_client).onchain_deposit(event()).await.unwrap());
+            }
+
+            #[tokio::test]
+            async fn leaves_an_underpaid_request_open() {
+                let mut store = store(Uuid::new_v4(), 1_000_001);
+                store.invoice.expect_settle().never();
+
+                let mut ln_client = MockLnClient::new();
+                ln_client.expect_cancel_invoice().never();
+
+                assert!(service(store, ln_client).onchain_deposit(event()).await.unwrap());
+            }
+
+            #[tokio::test]
+            async fn fails_the_deposit_to_retry_it_when_the_invoice_cannot_be_cancelled() {
+                let mut store = store(Uuid::new_v4(), 1_000_000);
+                store.invoice.expect_settle().never();
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_cancel_invoice()
+                    .times(1)
+                    .returning(|_, _| Err(LightningError::CancelInvoice("node unavailable".to_string())));
+                ln_client
+                    .expect_invoice_by_hash()
+                    .withf(|payment_hash| payment_hash == "ph")
+                    .times(1)
+                    .returning(|_| Ok(Some(pending_invoice(Uuid::nil(), 1_000_000))));
+
+                let err = service(store, ln_client).onchain_deposit(event()).await.unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Lightning(LightningError::CancelInvoice(_))
+                ));
+            }
+
+            #[tokio::test]
+            async fn leaves_the_invoice_to_its_own_settlement_when_paid_over_lightning() {
+                let mut store = store(Uuid::new_v4(), 1_000_000);
+                store.invoice.expect_settle().never();
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_cancel_invoice()
+                    .times(1)
+                    .returning(|_, _| Err(LightningError::CancelInvoice("already paid".to_string())));
+                ln_client.expect_invoice_by_hash().times(1).returning(|_| {
+                    Ok(Some(Invoice {
+                        status: InvoiceStatus::Settled,
+                        ..pending_invoice(Uuid::nil(), 1_000_000)
+                    }))
+                });
+
+                assert!(service(store, ln_client).onchain_deposit(event()).await.unwrap());
+            }
+
+            #[tokio::test]
+            async fn settles_the_invoice_already_removed_from_the_node() {
+                let mut store = store(Uuid::new_v4(), 1_000_000);
+                store.invoice.expect_settle().times(1).returning(|_| Ok(true));
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_cancel_invoice()
+                    .times(1)
+                    .returning(|_, _| Err(LightningError::CancelInvoice("unknown invoice".to_string())));
+                ln_client.expect_invoice_by_hash().times(1).returning(|_| Ok(None));
+
+                assert!(service(store, ln_client).onchain_deposit(event()).await.unwrap());
+            }
+        }
+
+        mod when_the_chain_tip_is_known {
+            use super::*;
+
+            #[tokio::test]
+            async fn counts_confirmations_without_asking_the_wallet() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(Some(btc_address(false))));
+                expect_wallet_lookup(&mut store);
+                store
+                    .event_uow
+                    .expect_project_onchain_deposit()
+                    .withf(|output, _, _| output.status == BtcOutputStatus::Confirmed)
+                    .times(1)
+                    .returning(|_, _, invoice| Ok(invoice));
+
+                let event = OnchainDepositEvent {
+                    txid: "txid".to_string(),
+                    address: "bc1qknown".to_string(),
+                    amount_sat: 1_000,
+                    block_height: Some(800_000),
+                    tip_height: Some(800_005),
+                    ..Default::default()
+                };
+
+                // block_height is intentionally not expected on the wallet.
+                assert!(service(store).onchain_deposit(event).await.unwrap());
+            }
+        }
+    }
+
+    mod onchain_deposit_dropped {
+        use super::*;
+
+        #[tokio::test]
+        async fn reverses_the_deposit_through_the_unit_of_work() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .event_uow
+                .expect_drop_onchain_deposit()
+                .withf(|output| output.outpoint == "txid:0")
+                .times(1)
+                .returning(|_| Ok(Some(Invoice::default())));
+
+            let output = BtcOutput {
+                outpoint: "txid:0".to_string(),
+                ..Default::default()
+            };
+
+            assert!(service(store).onchain_deposit_dropped(output).await.unwrap());
+        }
     }
 
     mod onchain_withdrawal {
@@ -5,24 +5,38 @@ use crate::{
     domains::{
         bitcoin::{BtcAddress, BtcOutput},
         invoice::Invoice,
+        payment::Payment,
     },
 };
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait EventProjectionUnitOfWork: Send + Sync {
-    /// Settle an incoming invoice and credit the receiver's wallet balance in one transaction.
-    /// Idempotent: a replayed settle event credits the wallet at most once.
-    async fn settle_incoming_invoice(&self, invoice: Invoice) -> Result<Invoice, ApplicationError>;
+    /// Settle an incoming invoice and credit the receiver's wallet balance in one transaction, marking the
+    /// deposit address of its payment request used. `splits` are settled internal transfers out of the received
+    /// amount, each a payment from the receiver and its counterpart invoice, applied with the credit. Idempotent:
+    /// a replayed settle event credits the wallet and passes the splits on at most once.
+    async fn settle_incoming_invoice(
+        &self,
+        invoice: Invoice,
+        splits: Vec<(Payment, Invoice)>,
+    ) -> Result<Invoice, ApplicationError>;
 
     /// Project an on-chain deposit in one transaction: upsert the output, mark the receiving
     /// address used, and settle-or-insert the linked invoice (crediting the receiver when
     /// confirmed). `deposit_invoice` is the desired invoice for a first sighting; when an invoice
-    /// already exists for the output it is settled idempotently instead.
+    /// already exists for the output it is settled idempotently instead. If a credited output
+    /// is seen again unconfirmed in a different block (or none), it was reorged out: the invoice
+    /// returns to pending and the credit is reversed exactly once.
     async fn project_onchain_deposit(
         &self,
         output: BtcOutput,
         address: BtcAddress,
         deposit_invoice: Invoice,
     ) -> Result<Invoice, ApplicationError>;
+
+    /// Mark an on-chain deposit dropped from the chain and the mempool (reorged out or double spent) as failed, in
+    /// one transaction with the reversal of its credit. The balance goes negative when the funds were already spent.
+    /// Idempotent: a replayed drop reverses the credit at most once.
+    async fn drop_onchain_deposit(&self, output: BtcOutput) -> Result<Option<Invoice>, ApplicationError>;
 }
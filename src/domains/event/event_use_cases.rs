@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 
 use crate::application::errors::ApplicationError;
-use crate::domains::event::OnchainWithdrawalEvent;
+use crate::domains::{bitcoin::BtcOutput, event::OnchainWithdrawalEvent};
 
 use super::{LnInvoicePaidEvent, LnPayFailureEvent, LnPaySuccessEvent, OnchainDepositEvent};
 
@@ -12,5 +12,6 @@ pub trait EventUseCases: Send + Sync {
     async fn outgoing_payment(&self, event: LnPaySuccessEvent) -> Result<(), ApplicationError>;
     async fn failed_payment(&self, event: LnPayFailureEvent) -> Result<(), ApplicationError>;
     async fn onchain_deposit(&self, event: OnchainDepositEvent) -> Result<bool, ApplicationError>;
+    async fn onchain_deposit_dropped(&self, output: BtcOutput) -> Result<bool, ApplicationError>;
     async fn onchain_withdrawal(&self, event: OnchainWithdrawalEvent) -> Result<bool, ApplicationError>;
 }
@@ -8,7 +8,7 @@ use axum::{
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use swissknife_types::{ErrorResponse, NewInvoiceRequest};
+use swissknife_types::{ErrorResponse, NewInvoiceRequest, NewPaymentRequest};
 
 use crate::{
     application::{
@@ -26,13 +26,13 @@ use crate::{
     infra::axum::{Json, Path, Query},
 };
 
-use super::{Invoice, InvoiceFilter, InvoiceOrderBy, InvoiceStatus, LnInvoice};
+use super::{Invoice, InvoiceFilter, InvoiceOrderBy, InvoiceStatus, LnInvoice, PaymentRequest};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(generate_invoice, list_invoices, get_invoice, delete_invoice, delete_invoices),
-    components(schemas(Invoice, NewInvoiceRequest, InvoiceStatus, LnInvoice, InvoiceOrderBy, BtcOutput,
-        BtcOutputStatus, BtcNetwork, BtcAddress)),
+    paths(generate_invoice, generate_payment_request, list_invoices, get_invoice, delete_invoice, delete_invoices),
+    components(schemas(Invoice, NewInvoiceRequest, NewPaymentRequest, PaymentRequest, InvoiceStatus, LnInvoice,
+        InvoiceOrderBy, BtcOutput, BtcOutputStatus, BtcNetwork, BtcAddress)),
     tags(
         (name = "Invoices", description = "Invoice management endpoints. Require `read:transaction` or `write:transaction` permissions.")
     ),
@@ -43,6 +43,7 @@ pub const CONTEXT_PATH: &str = "/v1/invoices";
 pub fn router() -> Router<Arc<AppServices>> {
     Router::new()
         .route("/", post(generate_invoice))
+        .route("/unified", post(generate_payment_request))
         .route("/", get(list_invoices))
         .route("/{id}", get(get_invoice))
         .route("/{id}", delete(delete_invoice))
@@ -84,6 +85,48 @@ async fn generate_invoice(
     Ok(Json(invoice))
 }
 
+/// Generate a unified payment request
+///
+/// Returns an invoice and a fresh deposit address for the selected wallet, combined in a BIP21 URI. Whichever is
+/// paid first settles both: an on-chain payment cancels the invoice.
+#[utoipa::path(
+    post,
+    path = "/unified",
+    tag = "Invoices",
+    context_path = CONTEXT_PATH,
+    request_body = NewPaymentRequest,
+    responses(
+        (status = 200, description = "Payment Request Created", body = PaymentRequest),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn generate_payment_request(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<NewPaymentRequest>,
+) -> Result<Json<PaymentRequest>, ApplicationError> {
+    user.check_permission(Permission::WriteTransaction)?;
+    let wallet_id = payload
+        .wallet_id
+        .ok_or_else(|| DataError::Malformed("wallet_id is required.".to_string()))?;
+
+    let payment_request = services
+        .invoice
+        .payment_request(
+            wallet_id,
+            payload.amount_msat,
+            payload.description,
+            payload.expiry,
+            payload.address_type,
+        )
+        .await?;
+    Ok(Json(payment_request))
+}
+
 /// Find an invoice
 ///
 /// Returns the invoice by its ID.
@@ -268,6 +311,48 @@ mod tests {
         }
     }
 
+    mod generate_payment_request {
+        use super::*;
+
+        fn payload(wallet_id: Option<Uuid>) -> NewPaymentRequest {
+            NewPaymentRequest {
+                wallet_id,
+                amount_msat: 1_000,
+                description: None,
+                expiry: None,
+                address_type: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn is_forbidden_without_the_write_permission() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = generate_payment_request(
+                State(Arc::new(services)),
+                user(vec![]),
+                Json(payload(Some(Uuid::new_v4()))),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn requires_a_wallet_id() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = generate_payment_request(
+                State(Arc::new(services)),
+                user(vec![Permission::WriteTransaction]),
+                Json(payload(None)),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::Malformed(_)))));
+        }
+    }
+
     mod get_invoice {
         use super::*;
 
@@ -17,5 +17,10 @@ pub trait InvoiceRepository: Send + Sync {
     /// Conditionally settle a still-pending invoice (sets payment_time/fee/received once).
     /// Returns `false` if the invoice was already settled, so callers stay idempotent.
     async fn settle(&self, invoice: &Invoice) -> Result<bool, DatabaseError>;
+    /// Conditionally revert a settled invoice back to pending (clears payment_time/received).
+    /// Returns `false` if the invoice was not settled, so a reversal is applied at most once.
+    async fn unsettle(&self, id: Uuid) -> Result<bool, DatabaseError>;
+    /// Expire a pending invoice now, such as the deposit invoice of a dropped output.
+    async fn expire(&self, id: Uuid) -> Result<(), DatabaseError>;
     async fn delete_many(&self, filter: InvoiceFilter) -> Result<u64, DatabaseError>;
 }
@@ -1,9 +1,11 @@
 use async_trait::async_trait;
+use bip21::{ser::SerializeParams, Uri};
+use bitcoin::{address::NetworkUnchecked, Address, Amount};
 use chrono::Utc;
 use tracing::{debug, info, trace};
 use uuid::Uuid;
 
-use std::sync::Arc;
+use std::{iter, sync::Arc};
 
 use crate::{
     application::{
@@ -12,13 +14,13 @@ use crate::{
     },
     domains::{
         asset::{Protocol, NATIVE_ASSET_REF},
-        bitcoin::BtcNetwork,
+        bitcoin::{BitcoinWallet, BtcAddressType, BtcNetwork},
         event::{EventUseCases, LnInvoicePaidEvent},
     },
     infra::lightning::LnClient,
 };
 
-use super::{Invoice, InvoiceFilter, InvoiceStatus, InvoiceUseCases};
+use super::{Invoice, InvoiceFilter, InvoiceStatus, InvoiceUseCases, PaymentRequest};
 
 const DEFAULT_INVOICE_DESCRIPTION: &str = "Numeraire Invoice";
 
@@ -28,6 +30,8 @@ pub struct InvoiceService {
     invoice_expiry: u32,
     events: Arc<dyn EventUseCases>,
     network: BtcNetwork,
+    bitcoin_wallet: Arc<dyn BitcoinWallet>,
+    address_type: BtcAddressType,
 }
 
 impl InvoiceService {
@@ -37,6 +41,8 @@ impl InvoiceService {
         invoice_expiry: u32,
         events: Arc<dyn EventUseCases>,
         network: BtcNetwork,
+        bitcoin_wallet: Arc<dyn BitcoinWallet>,
+        address_type: BtcAddressType,
     ) -> Self {
         InvoiceService {
             store,
@@ -44,6 +50,8 @@ impl InvoiceService {
             invoice_expiry,
             events,
             network,
+            bitcoin_wallet,
+            address_type,
         }
     }
 
@@ -93,6 +101,7 @@ impl InvoiceUseCases for InvoiceService {
                 invoice_id.to_string(),
                 expiry.unwrap_or(self.invoice_expiry),
                 false,
+                None,
             )
             .await?;
         invoice.id = invoice_id;
@@ -104,6 +113,47 @@ impl InvoiceUseCases for InvoiceService {
         Ok(invoice)
     }
 
+    async fn payment_request(
+        &self,
+        wallet_id: Uuid,
+        amount: u64,
+        description: Option<String>,
+        expiry: Option<u32>,
+        address_type: Option<BtcAddressType>,
+    ) -> Result<PaymentRequest, ApplicationError> {
+        debug!(%wallet_id, "Generating unified payment request");
+
+        if amount == 0 || !amount.is_multiple_of(1000) {
+            return Err(DataError::Validation(
+                "Amount must be a positive whole number of satoshis to be payable on-chain.".to_string(),
+            )
+            .into());
+        }
+
+        // A silent payment address is static and shared, so a payment to it can't be tied to the request.
+        let address_type = address_type.unwrap_or(self.address_type);
+        if address_type == BtcAddressType::SilentPayment {
+            return Err(DataError::Validation(
+                "Silent payment addresses cannot be used in a payment request.".to_string(),
+            )
+            .into());
+        }
+
+        let invoice = self.invoice(wallet_id, amount, description, expiry).await?;
+
+        let address = self.bitcoin_wallet.new_address(address_type).await?;
+        let address = self
+            .store
+            .btc_address
+            .insert_for_invoice(wallet_id, &address, address_type, invoice.id)
+            .await?;
+
+        let uri = unified_uri(&address.address, amount / 1000, &invoice)?;
+
+        info!(id = %invoice.id, address = %address.address, "Unified payment request generated successfully");
+        Ok(PaymentRequest { uri, invoice, address })
+    }
+
     async fn get(&self, id: Uuid) -> Result<Invoice, ApplicationError> {
         trace!(%id, "Fetching invoice");
 
@@ -204,6 +254,9 @@ impl InvoiceUseCases for InvoiceService {
                 amount_received_msat: node_invoice.amount_received_msat.unwrap_or_default(),
                 fee_msat: node_invoice.fee_msat.unwrap_or_default(),
                 payment_time,
+                payment_preimage: node_invoice
+                    .ln_invoice
+                    .and_then(|ln_invoice| ln_invoice.payment_preimage),
             };
 
             self.events.invoice_paid(event).await?;
@@ -215,6 +268,38 @@ impl InvoiceUseCases for InvoiceService {
     }
 }
 
+/// `lightning=` parameter of a unified BIP21 URI.
+struct LightningParam(String);
+
+impl<'a> SerializeParams for &'a LightningParam {
+    type Key = &'static str;
+    type Value = &'a str;
+    type Iterator = iter::Once<(&'static str, &'a str)>;
+
+    fn serialize_params(self) -> Self::Iterator {
+        iter::once(("lightning", self.0.as_str()))
+    }
+}
+
+/// BIP21 URI paying `address` on-chain or the invoice over Lightning.
+fn unified_uri(address: &str, amount_sat: u64, invoice: &Invoice) -> Result<String, ApplicationError> {
+    let bolt11 = invoice
+        .ln_invoice
+        .as_ref()
+        .map(|ln_invoice| ln_invoice.bolt11.clone())
+        .ok_or_else(|| DataError::Inconsistency(format!("Invoice {} has no Lightning details", invoice.id)))?;
+    let address = address
+        .parse::<Address<NetworkUnchecked>>()
+        .map_err(|e| DataError::Inconsistency(format!("Invalid deposit address {address}: {e}")))?
+        .assume_checked();
+
+    let mut uri = Uri::with_extras(address, LightningParam(bolt11));
+    uri.amount = Some(Amount::from_sat(amount_sat));
+    uri.message = invoice.description.as_deref().map(Into::into);
+
+    Ok(uri.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -222,7 +307,13 @@ mod tests {
             composition::MockAppStoreBuilder,
             errors::{DatabaseError, LightningError},
         },
-        domains::{asset::Asset, event::MockEventUseCases, invoice::LnInvoice, wallet::Wallet},
+        domains::{
+            asset::Asset,
+            bitcoin::{BtcAddress, MockBitcoinWallet},
+            event::MockEventUseCases,
+            invoice::LnInvoice,
+            wallet::Wallet,
+        },
         infra::lightning::MockLnClient,
     };
 
@@ -248,6 +339,8 @@ mod tests {
             EXPIRY,
             Arc::new(events),
             BtcNetwork::Regtest,
+            Arc::new(MockBitcoinWallet::new()),
+            BtcAddressType::P2wpkh,
         )
     }
 
@@ -292,14 +385,15 @@ mod tests {
                 let mut ln_client = MockLnClient::new();
                 ln_client
                     .expect_invoice()
-                    .withf(|amount, description, _label, expiry, deschashonly| {
+                    .withf(|amount, description, _label, expiry, deschashonly, preimage| {
                         *amount == 1_000
                             && description == DEFAULT_INVOICE_DESCRIPTION
                             && *expiry == EXPIRY
                             && !deschashonly
+                            && preimage.is_none()
                     })
                     .times(1)
-                    .returning(|amount, _, _, _, _| {
+                    .returning(|amount, _, _, _, _, _| {
                         Ok(Invoice {
                             amount_msat: Some(amount),
                             ..Default::default()
@@ -331,7 +425,7 @@ mod tests {
                 ln_client
                     .expect_invoice()
                     .times(1)
-                    .returning(|_, _, _, _, _| Err(LightningError::Invoice("node down".to_string())));
+                    .returning(|_, _, _, _, _, _| Err(LightningError::Invoice("node down".to_string())));
 
                 let service = service(MockAppStoreBuilder::new(), ln_client, MockEventUseCases::new());
 
@@ -350,7 +444,7 @@ mod tests {
                 ln_client
                     .expect_invoice()
                     .times(1)
-                    .returning(|_, _, _, _, _| Ok(Invoice::default()));
+                    .returning(|_, _, _, _, _, _| Ok(Invoice::default()));
 
                 let mut store = MockAppStoreBuilder::new();
                 store
@@ -391,6 +485,121 @@ mod tests {
         }
     }
 
+    mod payment_request {
+        use super::*;
+
+        const ADDRESS: &str = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+        const BOLT11: &str = "lnbcrt100u1pexample";
+
+        fn payment_request_service(store: MockAppStoreBuilder, ln_client: MockLnClient) -> InvoiceService {
+            let mut wallet = MockBitcoinWallet::new();
+            wallet
+                .expect_new_address()
+                .withf(|address_type| *address_type == BtcAddressType::P2tr)
+                .returning(|_| Ok(ADDRESS.to_string()));
+
+            let mut store = store;
+            store.wallet.expect_find().returning(|wallet_id| {
+                Ok(Some(Wallet {
+                    id: wallet_id,
+                    asset: Some(native_btc_asset(BtcNetwork::Regtest)),
+                    ..Default::default()
+                }))
+            });
+
+            InvoiceService::new(
+                store.build(),
+                Arc::new(ln_client),
+                EXPIRY,
+                Arc::new(MockEventUseCases::new()),
+                BtcNetwork::Regtest,
+                Arc::new(wallet),
+                BtcAddressType::P2tr,
+            )
+        }
+
+        #[tokio::test]
+        async fn links_a_fresh_address_to_the_invoice_in_a_bip21_uri() {
+            let wallet_id = Uuid::new_v4();
+
+            let mut ln_client = MockLnClient::new();
+            ln_client
+                .expect_invoice()
+                .times(1)
+                .returning(|amount, description, _, _, _, _| {
+                    Ok(Invoice {
+                        amount_msat: Some(amount),
+                        description: Some(description),
+                        ln_invoice: Some(LnInvoice {
+                            bolt11: BOLT11.to_string(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })
+                });
+
+            let mut store = MockAppStoreBuilder::new();
+            store.invoice.expect_insert().times(1).returning(Ok);
+            store
+                .btc_address
+                .expect_insert_for_invoice()
+                .withf(move |id, address, address_type, _| {
+                    *id == wallet_id && address == ADDRESS && *address_type == BtcAddressType::P2tr
+                })
+                .times(1)
+                .returning(|wallet_id, address, address_type, invoice_id| {
+                    Ok(BtcAddress {
+                        id: Uuid::new_v4(),
+                        wallet_id,
+                        address: address.to_string(),
+                        used: false,
+                        address_type,
+                        payjoin_uri: None,
+                        invoice_id: Some(invoice_id),
+                        created_at: Utc::now(),
+                        updated_at: None,
+                    })
+                });
+
+            let service = payment_request_service(store, ln_client);
+
+            let payment_request = service
+                .payment_request(wallet_id, 250_000, Some("Coffee & cake".to_string()), None, None)
+                .await
+                .unwrap();
+
+            assert_eq!(payment_request.address.invoice_id, Some(payment_request.invoice.id));
+            assert_eq!(
+                payment_request.uri,
+                format!("bitcoin:{ADDRESS}?amount=0.0000025&message=Coffee%20%26%20cake&lightning={BOLT11}")
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_amounts_with_millisatoshis() {
+            let service = payment_request_service(MockAppStoreBuilder::new(), MockLnClient::new());
+
+            let err = service
+                .payment_request(Uuid::new_v4(), 1_500, None, None, None)
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_silent_payment_addresses() {
+            let service = payment_request_service(MockAppStoreBuilder::new(), MockLnClient::new());
+
+            let err = service
+                .payment_request(Uuid::new_v4(), 1_000, None, None, Some(BtcAddressType::SilentPayment))
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+    }
+
     mod get {
         use super::*;
 
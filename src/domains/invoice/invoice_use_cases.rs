@@ -2,9 +2,9 @@ use async_trait::async_trait;
 
 use uuid::Uuid;
 
-use crate::application::errors::ApplicationError;
+use crate::{application::errors::ApplicationError, domains::bitcoin::BtcAddressType};
 
-use super::{Invoice, InvoiceFilter};
+use super::{Invoice, InvoiceFilter, PaymentRequest};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -16,6 +16,16 @@ pub trait InvoiceUseCases: Send + Sync {
         description: Option<String>,
         expiry: Option<u32>,
     ) -> Result<Invoice, ApplicationError>;
+    /// Generates an invoice and a dedicated deposit address, combined in a BIP21 URI. Whichever is paid first
+    /// settles both: an on-chain payment cancels the invoice.
+    async fn payment_request(
+        &self,
+        wallet_id: Uuid,
+        amount: u64,
+        description: Option<String>,
+        expiry: Option<u32>,
+        address_type: Option<BtcAddressType>,
+    ) -> Result<PaymentRequest, ApplicationError>;
     async fn get(&self, id: Uuid) -> Result<Invoice, ApplicationError>;
     async fn list(&self, filter: InvoiceFilter) -> Result<Vec<Invoice>, ApplicationError>;
     async fn delete(&self, id: Uuid) -> Result<(), ApplicationError>;
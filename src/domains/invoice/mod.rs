@@ -7,4 +7,4 @@ pub use invoice_handler::*;
 pub use invoice_repository::*;
 pub use invoice_service::*;
 pub use invoice_use_cases::*;
-pub use swissknife_types::{Invoice, InvoiceFilter, InvoiceOrderBy, InvoiceStatus, LnInvoice};
+pub use swissknife_types::{Invoice, InvoiceFilter, InvoiceOrderBy, InvoiceStatus, LnInvoice, PaymentRequest};
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{delete, get, post},
+    Router,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use swissknife_types::{ErrorResponse, RegisterLnAddressDomainRequest};
+
+use crate::{
+    application::{
+        composition::AppServices,
+        docs::{
+            BAD_REQUEST_EXAMPLE, CONFLICT_EXAMPLE, FORBIDDEN_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE,
+            UNAUTHORIZED_EXAMPLE, UNPROCESSABLE_EXAMPLE,
+        },
+        errors::ApplicationError,
+    },
+    domains::account::{Permission, User},
+    infra::axum::{Json, Path},
+};
+
+use super::LnAddressDomain;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(register_domain, list_domains, delete_domain),
+    components(schemas(LnAddressDomain, RegisterLnAddressDomainRequest)),
+    tags(
+        (name = "Lightning Address Domains", description = "Domains served next to the configured domain. Lightning Addresses are unique per domain. Require `read:ln_address` or `write:ln_address` permissions.")
+    )
+)]
+pub struct LnAddressDomainHandler;
+pub const CONTEXT_PATH: &str = "/v1/lightning-address-domains";
+
+pub fn domain_router() -> Router<Arc<AppServices>> {
+    Router::new()
+        .route("/", get(list_domains))
+        .route("/", post(register_domain))
+        .route("/{id}", delete(delete_domain))
+}
+
+/// Register a new domain
+///
+/// Registers a domain for Lightning Addresses. Point the domain's `/.well-known` routes to this instance and its callback host to the `/lnurlp` routes.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Lightning Address Domains",
+    context_path = CONTEXT_PATH,
+    request_body = RegisterLnAddressDomainRequest,
+    responses(
+        (status = 200, description = "Domain Registered", body = LnAddressDomain),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn register_domain(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<RegisterLnAddressDomainRequest>,
+) -> Result<Json<LnAddressDomain>, ApplicationError> {
+    user.check_permission(Permission::WriteLnAddress)?;
+
+    let domain = services.ln_address.register_domain(payload.name, payload.host).await?;
+    Ok(domain.into())
+}
+
+/// List domains
+///
+/// Returns all the managed domains. The configured domain of the instance is not listed.
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "Lightning Address Domains",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<LnAddressDomain>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_domains(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+) -> Result<Json<Vec<LnAddressDomain>>, ApplicationError> {
+    user.check_permission(Permission::ReadLnAddress)?;
+
+    let domains = services.ln_address.list_domains().await?;
+    Ok(domains.into())
+}
+
+/// Delete a domain
+///
+/// Deletes a domain by ID. Domains with Lightning Addresses cannot be deleted. Returns an empty body
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "Lightning Address Domains",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 409, description = "Domain In Use", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_domain(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    user.check_permission(Permission::WriteLnAddress)?;
+
+    services.ln_address.delete_domain(id).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::application::composition::MockAppServicesBuilder;
+
+    use super::*;
+
+    fn user(permissions: Vec<Permission>) -> User {
+        User {
+            account_id: Uuid::new_v4(),
+            permissions,
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: None,
+        }
+    }
+
+    fn register_request() -> RegisterLnAddressDomainRequest {
+        RegisterLnAddressDomainRequest {
+            name: "brand.example".to_string(),
+            host: "https://pay.brand.example".to_string(),
+        }
+    }
+
+    mod register_domain {
+        use super::*;
+
+        #[tokio::test]
+        async fn is_forbidden_without_the_write_permission() {
+            let result = register_domain(
+                State(Arc::new(MockAppServicesBuilder::new().build())),
+                user(vec![Permission::ReadLnAddress]),
+                Json(register_request()),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn forwards_the_name_and_host_to_the_service() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .ln_address
+                .expect_register_domain()
+                .withf(|name, host| name == "brand.example" && host == "https://pay.brand.example")
+                .times(1)
+                .returning(|name, host| {
+                    Ok(LnAddressDomain {
+                        id: Uuid::new_v4(),
+                        name,
+                        host,
+                        created_at: Utc::now(),
+                        updated_at: None,
+                    })
+                });
+
+            let result = register_domain(
+                State(Arc::new(builder.build())),
+                user(vec![Permission::WriteLnAddress]),
+                Json(register_request()),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(result.0.name, "brand.example");
+        }
+    }
+
+    mod delete_domain {
+        use super::*;
+
+        #[tokio::test]
+        async fn is_forbidden_without_the_write_permission() {
+            let result = delete_domain(
+                State(Arc::new(MockAppServicesBuilder::new().build())),
+                user(vec![Permission::ReadLnAddress]),
+                Path(Uuid::new_v4()),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+    }
+}
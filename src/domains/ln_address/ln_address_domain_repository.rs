@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{application::errors::DatabaseError, domains::ln_address::LnAddressDomain};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LnAddressDomainRepository: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<LnAddressDomain>, DatabaseError>;
+    async fn find_by_name(&self, name: &str) -> Result<Option<LnAddressDomain>, DatabaseError>;
+    async fn find_all(&self) -> Result<Vec<LnAddressDomain>, DatabaseError>;
+    async fn insert(&self, name: &str, host: &str) -> Result<LnAddressDomain, DatabaseError>;
+    async fn delete(&self, id: Uuid) -> Result<u64, DatabaseError>;
+}
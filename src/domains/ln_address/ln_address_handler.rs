@@ -24,12 +24,22 @@ use crate::{
     infra::axum::{Json, Path},
 };
 
-use super::{LnAddress, LnAddressFilter};
+use super::{
+    LnAddress, LnAddressBip353Record, LnAddressFilter, LnAddressPayProfile, LnAddressSplit, LnAddressSuccessAction,
+};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(register_address, get_address, list_addresses, update_address, delete_address, delete_addresses),
-    components(schemas(LnAddress, RegisterLnAddressRequest, UpdateLnAddressRequest)),
+    paths(register_address, get_address, list_addresses, update_address, delete_address, delete_addresses, get_bip353_record),
+    components(schemas(
+        LnAddress,
+        LnAddressBip353Record,
+        RegisterLnAddressRequest,
+        UpdateLnAddressRequest,
+        LnAddressPayProfile,
+        LnAddressSplit,
+        LnAddressSuccessAction
+    )),
     tags(
         (name = "Lightning Addresses", description = "LN Address management endpoints as defined in the [protocol specification](https://lightningaddress.com/). Require `read:ln_address` or `write:ln_address` permissions.")
     )
@@ -42,6 +52,7 @@ pub fn router() -> Router<Arc<AppServices>> {
         .route("/", get(list_addresses))
         .route("/", post(register_address))
         .route("/{id}", get(get_address))
+        .route("/{id}/bip353", get(get_bip353_record))
         .route("/{id}", put(update_address))
         .route("/{id}", delete(delete_address))
         .route("/", delete(delete_addresses))
@@ -72,17 +83,10 @@ async fn register_address(
 ) -> Result<Json<LnAddress>, ApplicationError> {
     user.check_permission(Permission::WriteLnAddress)?;
 
-    let ln_address = services
-        .ln_address
-        .register(
-            payload
-                .account_id
-                .ok_or_else(|| DataError::Malformed("account_id is required.".to_string()))?,
-            payload.username,
-            payload.allows_nostr,
-            payload.nostr_pubkey,
-        )
-        .await?;
+    let account_id = payload
+        .account_id
+        .ok_or_else(|| DataError::Malformed("account_id is required.".to_string()))?;
+    let ln_address = services.ln_address.register(account_id, payload).await?;
     Ok(ln_address.into())
 }
 
@@ -114,6 +118,34 @@ async fn get_address(
     Ok(ln_address.into())
 }
 
+/// Get the BIP353 record of a LN Address
+///
+/// Returns the DNS TXT record to publish for the address to be paid as `₿username@domain`. The record points to the silent payment address of the wallet, so silent payments must be enabled. The zone must be signed with DNSSEC.
+#[utoipa::path(
+    get,
+    path = "/{id}/bip353",
+    tag = "Lightning Addresses",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Found", body = LnAddressBip353Record),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn get_bip353_record(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<Json<LnAddressBip353Record>, ApplicationError> {
+    user.check_permission(Permission::ReadLnAddress)?;
+
+    let record = services.ln_address.bip353_record(id).await?;
+    Ok(record.into())
+}
+
 /// List LN Addresses
 ///
 /// Returns all the addresses given a filter
@@ -246,6 +278,11 @@ mod tests {
         User {
             account_id: Uuid::new_v4(),
             permissions,
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: None,
         }
     }
 
@@ -255,9 +292,12 @@ mod tests {
             account_id,
             wallet_id,
             username: "alice".to_string(),
+            domain_id: None,
             active: true,
             allows_nostr: false,
             nostr_pubkey: None,
+            pay_profile: Default::default(),
+            splits: Vec::new(),
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -267,8 +307,12 @@ mod tests {
         RegisterLnAddressRequest {
             account_id,
             username: "alice".to_string(),
+            domain: None,
             allows_nostr: false,
             nostr_pubkey: None,
+            wallet_id: None,
+            pay_profile: None,
+            splits: Vec::new(),
         }
     }
 
@@ -323,9 +367,11 @@ mod tests {
                 builder
                     .ln_address
                     .expect_register()
-                    .withf(move |account, username, _, _| *account == account_id && username == "alice")
+                    .withf(move |account, request| {
+                        *account == account_id && request.username == "alice" && request.domain.is_none()
+                    })
                     .times(1)
-                    .returning(move |account, _, _, _| Ok(ln_address(account, wallet_id)));
+                    .returning(move |account, _| Ok(ln_address(account, wallet_id)));
 
                 let result = register_address(
                     State(Arc::new(builder.build())),
@@ -355,4 +401,21 @@ mod tests {
             }
         }
     }
+
+    mod get_bip353_record {
+        use super::*;
+
+        mod without_the_read_permission {
+            use super::*;
+
+            #[tokio::test]
+            async fn is_forbidden() {
+                let services = MockAppServicesBuilder::new().build();
+
+                let result = get_bip353_record(State(Arc::new(services)), user(vec![]), Path(Uuid::new_v4())).await;
+
+                assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+            }
+        }
+    }
 }
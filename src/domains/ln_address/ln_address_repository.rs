@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use nostr_sdk::prelude::PublicKey;
 use uuid::Uuid;
 
 use crate::{
@@ -11,17 +10,13 @@ use crate::{
 #[async_trait]
 pub trait LnAddressRepository: Send + Sync {
     async fn find(&self, id: Uuid) -> Result<Option<LnAddress>, DatabaseError>;
-    async fn find_by_username(&self, username: &str) -> Result<Option<LnAddress>, DatabaseError>;
-    async fn find_by_account_id(&self, account_id: Uuid) -> Result<Option<LnAddress>, DatabaseError>;
-    async fn find_many(&self, filter: LnAddressFilter) -> Result<Vec<LnAddress>, DatabaseError>;
-    async fn insert(
+    async fn find_by_username(
         &self,
-        account_id: Uuid,
-        wallet_id: Uuid,
+        domain_id: Option<Uuid>,
         username: &str,
-        allows_nostr: bool,
-        nostr_pubkey: Option<PublicKey>,
-    ) -> Result<LnAddress, DatabaseError>;
+    ) -> Result<Option<LnAddress>, DatabaseError>;
+    async fn find_many(&self, filter: LnAddressFilter) -> Result<Vec<LnAddress>, DatabaseError>;
+    async fn insert(&self, ln_address: LnAddress) -> Result<LnAddress, DatabaseError>;
     async fn update(&self, ln_address: LnAddress) -> Result<LnAddress, DatabaseError>;
     async fn delete_many(&self, filter: LnAddressFilter) -> Result<u64, DatabaseError>;
 }
@@ -1,19 +1,27 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use nostr_sdk::prelude::PublicKey;
+use base64::{prelude::BASE64_STANDARD, Engine};
 use regex::Regex;
+use reqwest::Url;
 use tracing::{debug, info, trace};
 use uuid::Uuid;
 
-use swissknife_types::UpdateLnAddressRequest;
+use swissknife_types::{LnAddressBip353Record, RegisterLnAddressRequest, UpdateLnAddressRequest};
 
 use crate::{
     application::{
         composition::AppStore,
-        errors::{ApplicationError, DataError},
+        errors::{ApplicationError, DataError, DatabaseError},
     },
     domains::{
-        bitcoin::BtcNetwork,
-        ln_address::{LnAddress, LnAddressFilter},
+        audit::{record_created, record_deleted, record_updated},
+        bitcoin::{BitcoinUseCases, BtcAddressType, BtcNetwork},
+        ln_address::{
+            LnAddress, LnAddressDomain, LnAddressFilter, LnAddressPayProfile, LnAddressSplit, LnAddressSuccessAction,
+        },
+        lnurl::{MAX_SENDABLE, MIN_SENDABLE},
+        payment::{bip353_record_name, is_dns_label, txt_record_value, BIP353_PREFIX, BIP353_RECORD_TTL},
     },
 };
 
@@ -21,66 +29,293 @@ use super::LnAddressUseCases;
 
 const MIN_USERNAME_LENGTH: usize = 1;
 const MAX_USERNAME_LENGTH: usize = 64;
+const SUB_ADDRESS_SEPARATOR: char = '+';
+// LUD-09 caps success action texts at 144 characters.
+const MAX_LUD09_TEXT_LENGTH: usize = 144;
+const MAX_DESCRIPTION_LENGTH: usize = 255;
+const MAX_IMAGE_BYTES: usize = 128 * 1024;
+// Keeps the encrypted payload under the 4096 characters accepted by LUD-10 wallets.
+const MAX_AES_SECRET_LENGTH: usize = 2048;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const MAX_SPLITS: usize = 10;
+const TOTAL_SHARE_BPS: u64 = 10_000;
 
 pub struct LnAddressService {
     store: AppStore,
     network: BtcNetwork,
+    domain: String,
+    host: String,
+    bitcoin: Arc<dyn BitcoinUseCases>,
 }
 
 impl LnAddressService {
-    pub fn new(store: AppStore, network: BtcNetwork) -> Self {
-        LnAddressService { store, network }
+    pub fn new(
+        store: AppStore,
+        network: BtcNetwork,
+        domain: String,
+        host: String,
+        bitcoin: Arc<dyn BitcoinUseCases>,
+    ) -> Self {
+        LnAddressService {
+            store,
+            network,
+            domain,
+            host,
+            bitcoin,
+        }
     }
-}
 
-#[async_trait]
-impl LnAddressUseCases for LnAddressService {
-    async fn register(
-        &self,
-        account_id: Uuid,
-        mut username: String,
-        allows_nostr: bool,
-        nostr_pubkey: Option<PublicKey>,
-    ) -> Result<LnAddress, ApplicationError> {
-        debug!(%account_id, username, network = %self.network, "Registering lightning address");
+    /// Resolves a domain name to its managed domain ID. `None` stands for the configured domain.
+    async fn domain_id(&self, name: Option<&str>) -> Result<Option<Uuid>, ApplicationError> {
+        let Some(name) = name.filter(|name| !name.eq_ignore_ascii_case(&self.domain)) else {
+            return Ok(None);
+        };
 
-        username = username.to_lowercase();
-        validate_username(username.as_str())?;
+        let domain = self
+            .store
+            .ln_address_domain
+            .find_by_name(&name.to_lowercase())
+            .await?
+            .ok_or_else(|| DataError::Validation("Unknown lightning address domain.".to_string()))?;
+
+        Ok(Some(domain.id))
+    }
+
+    async fn managed_domain(&self, domain_id: Uuid) -> Result<LnAddressDomain, ApplicationError> {
+        let domain = self
+            .store
+            .ln_address_domain
+            .find(domain_id)
+            .await?
+            .ok_or_else(|| DataError::Inconsistency(format!("Lightning address domain {domain_id} not found.")))?;
+
+        Ok(domain)
+    }
 
-        if self.store.ln_address.find_by_account_id(account_id).await?.is_some() {
-            return Err(DataError::Conflict("Account already has a lightning address.".to_string()).into());
+    /// Public URL serving the LNURL callbacks of the addresses on the given domain.
+    async fn callback_host(&self, domain_id: Option<Uuid>) -> Result<String, ApplicationError> {
+        match domain_id {
+            Some(domain_id) => Ok(self.managed_domain(domain_id).await?.host),
+            None => Ok(self.host.clone()),
         }
+    }
 
-        if self.store.ln_address.find_by_username(&username).await?.is_some() {
-            return Err(DataError::Conflict("Duplicate username.".to_string()).into());
+    async fn domain_name(&self, domain_id: Option<Uuid>) -> Result<String, ApplicationError> {
+        match domain_id {
+            Some(domain_id) => Ok(self.managed_domain(domain_id).await?.name),
+            None => Ok(self.domain.clone()),
         }
+    }
 
+    async fn native_btc_asset_id(&self) -> Result<Uuid, ApplicationError> {
         let asset = self
             .store
             .asset
             .find_native_btc_by_network(self.network)
             .await?
             .ok_or_else(|| DataError::Inconsistency("Native BTC asset is not configured.".to_string()))?;
+
+        Ok(asset.id)
+    }
+
+    /// Wallet receiving the invoices of an address: the requested one, or the account's native BTC wallet.
+    async fn receiving_wallet(&self, account_id: Uuid, wallet_id: Option<Uuid>) -> Result<Uuid, ApplicationError> {
+        let asset_id = self.native_btc_asset_id().await?;
+
+        let Some(wallet_id) = wallet_id else {
+            let wallet = self
+                .store
+                .wallet
+                .find_by_account_and_asset(account_id, asset_id)
+                .await?
+                .ok_or_else(|| {
+                    DataError::Validation("Account has no native BTC wallet for the active network.".to_string())
+                })?;
+            return Ok(wallet.id);
+        };
+
         let wallet = self
             .store
             .wallet
-            .find_by_account_and_asset(account_id, asset.id)
+            .find(wallet_id)
+            .await?
+            .filter(|wallet| wallet.account_id == account_id)
+            .ok_or_else(|| DataError::NotFound("Wallet not found.".to_string()))?;
+        if wallet.asset_id != asset_id {
+            return Err(DataError::Validation(
+                "Lightning addresses can only receive into a native BTC wallet of the active network.".to_string(),
+            )
+            .into());
+        }
+
+        Ok(wallet.id)
+    }
+
+    /// Splits may target wallets of any account, as long as they hold the same asset as the receiving wallet.
+    async fn validate_splits(&self, wallet_id: Uuid, splits: &[LnAddressSplit]) -> Result<(), ApplicationError> {
+        if splits.is_empty() {
+            return Ok(());
+        }
+
+        if splits.len() > MAX_SPLITS {
+            return Err(DataError::Validation(format!("At most {MAX_SPLITS} payment splits are allowed.")).into());
+        }
+
+        if splits.iter().any(|split| split.share_bps == 0) {
+            return Err(DataError::Validation("Split shares must be greater than zero.".to_string()).into());
+        }
+
+        let total_bps: u64 = splits.iter().map(|split| u64::from(split.share_bps)).sum();
+        if total_bps > TOTAL_SHARE_BPS {
+            return Err(DataError::Validation("Split shares cannot exceed 10000 basis points.".to_string()).into());
+        }
+
+        let asset_id = self.native_btc_asset_id().await?;
+        for (i, split) in splits.iter().enumerate() {
+            if split.wallet_id == wallet_id {
+                return Err(DataError::Validation("Splits cannot target the receiving wallet.".to_string()).into());
+            }
+
+            if splits[..i].iter().any(|other| other.wallet_id == split.wallet_id) {
+                return Err(DataError::Validation("Duplicate split wallet.".to_string()).into());
+            }
+
+            let wallet = self
+                .store
+                .wallet
+                .find(split.wallet_id)
+                .await?
+                .ok_or_else(|| DataError::NotFound("Split wallet not found.".to_string()))?;
+            if wallet.asset_id != asset_id {
+                return Err(DataError::Validation(
+                    "Splits can only target native BTC wallets of the active network.".to_string(),
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_pay_profile(&self, profile: &LnAddressPayProfile, host: &str) -> Result<(), DataError> {
+        let min_sendable = profile.min_sendable.unwrap_or(MIN_SENDABLE);
+        let max_sendable = profile.max_sendable.unwrap_or(MAX_SENDABLE);
+        if min_sendable == 0 {
+            return Err(DataError::Validation(
+                "Min sendable must be at least 1 msat.".to_string(),
+            ));
+        }
+        if min_sendable > max_sendable {
+            return Err(DataError::Validation(
+                "Min sendable cannot be greater than max sendable.".to_string(),
+            ));
+        }
+
+        if let Some(description) = &profile.description {
+            if description.is_empty() || description.chars().count() > MAX_DESCRIPTION_LENGTH {
+                return Err(DataError::Validation("Invalid description length.".to_string()));
+            }
+        }
+
+        if let Some(image) = &profile.image {
+            let bytes = BASE64_STANDARD
+                .decode(image)
+                .map_err(|_| DataError::Validation("Image must be base64 encoded.".to_string()))?;
+
+            if bytes.len() > MAX_IMAGE_BYTES {
+                return Err(DataError::Validation("Image is too large.".to_string()));
+            }
+
+            if !bytes.starts_with(&PNG_SIGNATURE) {
+                return Err(DataError::Validation("Image must be a PNG.".to_string()));
+            }
+        }
+
+        match &profile.success_action {
+            Some(LnAddressSuccessAction::Message { message }) => {
+                validate_lud09_text("Success action message", message)?
+            }
+            Some(LnAddressSuccessAction::Url { description, url }) => {
+                validate_lud09_text("Success action description", description)?;
+
+                // LUD-09: the URL must share the domain of the callback, served from our host.
+                let url =
+                    Url::parse(url).map_err(|_| DataError::Validation("Invalid success action URL.".to_string()))?;
+                let host = Url::parse(host).ok();
+                if url.domain().is_none() || url.domain() != host.as_ref().and_then(|host| host.domain()) {
+                    return Err(DataError::Validation(
+                        "Success action URL must be on the same domain as the LNURL callback.".to_string(),
+                    ));
+                }
+            }
+            Some(LnAddressSuccessAction::Aes { description, secret }) => {
+                validate_lud09_text("Success action description", description)?;
+
+                if secret.is_empty() || secret.len() > MAX_AES_SECRET_LENGTH {
+                    return Err(DataError::Validation(
+                        "Invalid success action secret length.".to_string(),
+                    ));
+                }
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LnAddressUseCases for LnAddressService {
+    async fn register(
+        &self,
+        account_id: Uuid,
+        request: RegisterLnAddressRequest,
+    ) -> Result<LnAddress, ApplicationError> {
+        debug!(%account_id, ?request, network = %self.network, "Registering lightning address");
+
+        let username = request.username.to_lowercase();
+        validate_username(username.as_str())?;
+
+        let domain_id = self.domain_id(request.domain.as_deref()).await?;
+        if self
+            .store
+            .ln_address
+            .find_by_username(domain_id, &username)
             .await?
-            .ok_or_else(|| {
-                DataError::Validation("Account has no native BTC wallet for the active network.".to_string())
-            })?;
-        let wallet_id = wallet.id;
+            .is_some()
+        {
+            return Err(DataError::Conflict("Duplicate username.".to_string()).into());
+        }
+
+        let pay_profile = request.pay_profile.unwrap_or_default();
+        let host = self.callback_host(domain_id).await?;
+        self.validate_pay_profile(&pay_profile, &host)?;
+
+        let wallet_id = self.receiving_wallet(account_id, request.wallet_id).await?;
+        self.validate_splits(wallet_id, &request.splits).await?;
 
         let ln_address = self
             .store
             .ln_address
-            .insert(account_id, wallet_id, &username, allows_nostr, nostr_pubkey)
+            .insert(LnAddress {
+                account_id,
+                wallet_id,
+                domain_id,
+                username,
+                active: true,
+                allows_nostr: request.allows_nostr,
+                nostr_pubkey: request.nostr_pubkey,
+                pay_profile,
+                splits: request.splits,
+                ..Default::default()
+            })
             .await?;
+        record_created(&ln_address);
 
         info!(
             %account_id,
             %wallet_id,
-            username, "Lightning address registered successfully"
+            username = ln_address.username, "Lightning address registered successfully"
         );
         Ok(ln_address)
     }
@@ -119,6 +354,7 @@ impl LnAddressUseCases for LnAddressService {
             .find(id)
             .await?
             .ok_or_else(|| DataError::NotFound("Lightning address not found.".to_string()))?;
+        let before = ln_address.clone();
 
         if let Some(mut username) = request.username {
             username = username.to_lowercase();
@@ -126,7 +362,13 @@ impl LnAddressUseCases for LnAddressService {
             if username != ln_address.username {
                 validate_username(username.as_str())?;
 
-                if self.store.ln_address.find_by_username(&username).await?.is_some() {
+                if self
+                    .store
+                    .ln_address
+                    .find_by_username(ln_address.domain_id, &username)
+                    .await?
+                    .is_some()
+                {
                     return Err(DataError::Conflict("Duplicate username.".to_string()).into());
                 }
 
@@ -134,6 +376,21 @@ impl LnAddressUseCases for LnAddressService {
             }
         }
 
+        let rerouted = match request.wallet_id.filter(|wallet_id| *wallet_id != ln_address.wallet_id) {
+            Some(wallet_id) => {
+                ln_address.wallet_id = self.receiving_wallet(ln_address.account_id, Some(wallet_id)).await?;
+                true
+            }
+            None => false,
+        };
+
+        if let Some(splits) = request.splits {
+            self.validate_splits(ln_address.wallet_id, &splits).await?;
+            ln_address.splits = splits;
+        } else if rerouted {
+            self.validate_splits(ln_address.wallet_id, &ln_address.splits).await?;
+        }
+
         if let Some(active) = request.active {
             ln_address.active = active;
         }
@@ -146,7 +403,14 @@ impl LnAddressUseCases for LnAddressService {
             ln_address.nostr_pubkey = Some(nostr_pubkey);
         }
 
+        if let Some(pay_profile) = request.pay_profile {
+            let host = self.callback_host(ln_address.domain_id).await?;
+            self.validate_pay_profile(&pay_profile, &host)?;
+            ln_address.pay_profile = pay_profile;
+        }
+
         let ln_address = self.store.ln_address.update(ln_address).await?;
+        record_updated(&before, &ln_address);
 
         info!(%id, "Lightning address updated successfully");
         Ok(ln_address)
@@ -155,6 +419,12 @@ impl LnAddressUseCases for LnAddressService {
     async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
         debug!(%id, "Deleting lightning address");
 
+        let ln_address = self
+            .store
+            .ln_address
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Lightning address not found.".to_string()))?;
         let n_deleted = self
             .store
             .ln_address
@@ -167,6 +437,7 @@ impl LnAddressUseCases for LnAddressService {
         if n_deleted == 0 {
             return Err(DataError::NotFound("Lightning address not found.".to_string()).into());
         }
+        record_deleted(&ln_address);
 
         info!(%id, "Lightning address deleted successfully");
         Ok(())
@@ -180,6 +451,169 @@ impl LnAddressUseCases for LnAddressService {
         info!(?filter, n_deleted, "Lightning addresses deleted successfully");
         Ok(n_deleted)
     }
+
+    async fn bip353_record(&self, id: Uuid) -> Result<LnAddressBip353Record, ApplicationError> {
+        trace!(%id, "Generating BIP353 record of lightning address");
+
+        let ln_address = self.get(id).await?;
+        if !is_dns_label(&ln_address.username) {
+            return Err(DataError::Validation("Username cannot be published as a BIP353 name.".to_string()).into());
+        }
+        let domain = self.domain_name(ln_address.domain_id).await?;
+
+        // A silent payment address is the only static instruction we can publish: invoices expire and reusing an
+        // on-chain address would link every payment.
+        let address = self
+            .bitcoin
+            .new_deposit_address(ln_address.wallet_id, Some(BtcAddressType::SilentPayment))
+            .await?;
+
+        let name = bip353_record_name(&ln_address.username, &domain);
+        let value = format!("bitcoin:?sp={}", address.address);
+        let record = LnAddressBip353Record {
+            bip353_name: format!("{BIP353_PREFIX}{}@{domain}", ln_address.username),
+            zone_record: format!("{name} {BIP353_RECORD_TTL} IN TXT {}", txt_record_value(&value)),
+            name,
+            ttl: BIP353_RECORD_TTL,
+            value,
+        };
+
+        debug!(%id, name = record.name, "BIP353 record of lightning address generated successfully");
+        Ok(record)
+    }
+
+    async fn register_domain(&self, name: String, host: String) -> Result<LnAddressDomain, ApplicationError> {
+        debug!(name, host, "Registering lightning address domain");
+
+        let name = name.to_lowercase();
+        validate_domain_name(&name)?;
+        validate_domain_host(&host)?;
+
+        if name.eq_ignore_ascii_case(&self.domain) {
+            return Err(DataError::Conflict("Domain is already served as the configured domain.".to_string()).into());
+        }
+
+        if self.store.ln_address_domain.find_by_name(&name).await?.is_some() {
+            return Err(DataError::Conflict("Duplicate domain.".to_string()).into());
+        }
+
+        let domain = self
+            .store
+            .ln_address_domain
+            .insert(&name, host.trim_end_matches('/'))
+            .await?;
+
+        info!(id = %domain.id, name, "Lightning address domain registered successfully");
+        Ok(domain)
+    }
+
+    async fn list_domains(&self) -> Result<Vec<LnAddressDomain>, ApplicationError> {
+        trace!("Listing lightning address domains");
+
+        let domains = self.store.ln_address_domain.find_all().await?;
+
+        debug!("Lightning address domains listed successfully");
+        Ok(domains)
+    }
+
+    async fn delete_domain(&self, id: Uuid) -> Result<(), ApplicationError> {
+        debug!(%id, "Deleting lightning address domain");
+
+        let ln_addresses = self
+            .store
+            .ln_address
+            .find_many(LnAddressFilter {
+                domain_id: Some(id),
+                limit: Some(1),
+                ..Default::default()
+            })
+            .await?;
+        if !ln_addresses.is_empty() {
+            return Err(DataError::Conflict("Domain still has lightning addresses.".to_string()).into());
+        }
+
+        let n_deleted = self.store.ln_address_domain.delete(id).await?;
+        if n_deleted == 0 {
+            return Err(DataError::NotFound("Lightning address domain not found.".to_string()).into());
+        }
+
+        info!(%id, "Lightning address domain deleted successfully");
+        Ok(())
+    }
+}
+
+/// Splits a `username+tag` sub-address into its username and tag.
+pub fn split_sub_address(username: &str) -> (&str, Option<&str>) {
+    match username.split_once(SUB_ADDRESS_SEPARATOR) {
+        Some((username, tag)) if !username.is_empty() && !tag.is_empty() => (username, Some(tag)),
+        _ => (username, None),
+    }
+}
+
+/// Amounts passed on to each split wallet, rounded down so that the receiving wallet keeps the remainder.
+pub fn split_amounts(amount_msat: u64, splits: &[LnAddressSplit]) -> Vec<(Uuid, u64)> {
+    splits
+        .iter()
+        .map(|split| {
+            let share_msat = u128::from(amount_msat) * u128::from(split.share_bps) / u128::from(TOTAL_SHARE_BPS);
+            (split.wallet_id, share_msat as u64)
+        })
+        .filter(|(_, share_msat)| *share_msat > 0)
+        .collect()
+}
+
+/// Finds the address receiving payments sent to `username`. Sub-addresses are received by their base address.
+pub async fn find_receiving_ln_address(
+    store: &AppStore,
+    domain_id: Option<Uuid>,
+    username: &str,
+) -> Result<Option<LnAddress>, DatabaseError> {
+    // Addresses registered before sub-addresses existed may contain the separator.
+    if let Some(ln_address) = store.ln_address.find_by_username(domain_id, username).await? {
+        return Ok(Some(ln_address));
+    }
+
+    match split_sub_address(username) {
+        (username, Some(_)) => store.ln_address.find_by_username(domain_id, username).await,
+        _ => Ok(None),
+    }
+}
+
+fn validate_domain_name(name: &str) -> Result<(), DataError> {
+    // The name must survive as the host of a URL untouched: no scheme, port, path or IP address.
+    let url = Url::parse(&format!("https://{name}"))
+        .map_err(|_| DataError::Validation("Invalid domain name.".to_string()))?;
+    if url.domain() != Some(name) || url.port().is_some() || !name.contains('.') {
+        return Err(DataError::Validation("Invalid domain name.".to_string()));
+    }
+
+    Ok(())
+}
+
+fn validate_domain_host(host: &str) -> Result<(), DataError> {
+    let url = Url::parse(host).map_err(|_| DataError::Validation("Invalid domain host.".to_string()))?;
+    if !matches!(url.scheme(), "http" | "https")
+        || url.host_str().is_none()
+        || url.path() != "/"
+        || url.query().is_some()
+    {
+        return Err(DataError::Validation(
+            "Domain host must be an HTTP(S) base URL.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_lud09_text(field: &str, value: &str) -> Result<(), DataError> {
+    if value.is_empty() || value.chars().count() > MAX_LUD09_TEXT_LENGTH {
+        return Err(DataError::Validation(format!(
+            "Invalid {} length.",
+            field.to_lowercase()
+        )));
+    }
+
+    Ok(())
 }
 
 fn validate_username(username: &str) -> Result<(), DataError> {
@@ -187,8 +621,8 @@ fn validate_username(username: &str) -> Result<(), DataError> {
         return Err(DataError::Validation("Invalid username length.".to_string()));
     }
 
-    // Regex validation for allowed characters in username
-    let email_username_re = Regex::new(r"^[a-z0-9.!#$%&'*+/=?^_`{|}~-]+$").expect("should not fail as a constant");
+    // Regex validation for allowed characters in username. `+` is reserved for sub-addresses.
+    let email_username_re = Regex::new(r"^[a-z0-9.!#$%&'*/=?^_`{|}~-]+$").expect("should not fail as a constant");
     if !email_username_re.is_match(username) {
         return Err(DataError::Validation("Invalid username format.".to_string()));
     }
@@ -204,13 +638,20 @@ mod tests {
         application::{composition::MockAppStoreBuilder, errors::DatabaseError},
         domains::{
             asset::{Asset, Protocol, NATIVE_ASSET_REF},
-            bitcoin::BtcNetwork,
+            bitcoin::{BtcAddress, BtcNetwork, MockBitcoinUseCases},
             wallet::Wallet,
         },
     };
 
     use super::*;
 
+    const DOMAIN: &str = "numeraire.tech";
+    const HOST: &str = "https://numeraire.tech";
+
+    fn bitcoin() -> Arc<dyn BitcoinUseCases> {
+        Arc::new(MockBitcoinUseCases::new())
+    }
+
     fn native_btc_asset() -> Asset {
         Asset {
             id: Uuid::new_v4(),
@@ -243,20 +684,39 @@ mod tests {
             account_id,
             wallet_id,
             username: username.to_string(),
+            domain_id: None,
             active: true,
             allows_nostr: false,
             nostr_pubkey: None,
+            pay_profile: LnAddressPayProfile::default(),
+            splits: Vec::new(),
             created_at: Utc::now(),
             updated_at: None,
         }
     }
 
+    fn register_request(username: &str) -> RegisterLnAddressRequest {
+        RegisterLnAddressRequest {
+            account_id: None,
+            username: username.to_string(),
+            domain: None,
+            allows_nostr: false,
+            nostr_pubkey: None,
+            wallet_id: None,
+            pay_profile: None,
+            splits: Vec::new(),
+        }
+    }
+
     fn update_request(username: Option<&str>) -> UpdateLnAddressRequest {
         UpdateLnAddressRequest {
             username: username.map(str::to_string),
+            wallet_id: None,
             active: None,
             allows_nostr: None,
             nostr_pubkey: None,
+            pay_profile: None,
+            splits: None,
         }
     }
 
@@ -266,7 +726,7 @@ mod tests {
         #[test]
         fn accepts_supported_email_local_part_characters() {
             assert!(validate_username("alice").is_ok());
-            assert!(validate_username("alice.123_+-").is_ok());
+            assert!(validate_username("alice.123_-").is_ok());
             assert!(validate_username("a".repeat(MAX_USERNAME_LENGTH).as_str()).is_ok());
         }
 
@@ -284,7 +744,7 @@ mod tests {
 
         #[test]
         fn rejects_unsupported_characters() {
-            for username in ["Alice", "alice bob", "alice@example", "alice:123"] {
+            for username in ["Alice", "alice bob", "alice@example", "alice:123", "alice+tips"] {
                 let err = validate_username(username).unwrap_err();
                 assert!(matches!(err, DataError::Validation(_)));
                 assert!(err.to_string().contains("Invalid username format"));
@@ -292,6 +752,49 @@ mod tests {
         }
     }
 
+    mod split_sub_address {
+        use super::*;
+
+        #[test]
+        fn separates_the_tag_from_the_username() {
+            assert_eq!(split_sub_address("alice+tips"), ("alice", Some("tips")));
+            assert_eq!(split_sub_address("alice+tips+2026"), ("alice", Some("tips+2026")));
+        }
+
+        #[test]
+        fn leaves_plain_and_incomplete_usernames_untouched() {
+            assert_eq!(split_sub_address("alice"), ("alice", None));
+            assert_eq!(split_sub_address("alice+"), ("alice+", None));
+            assert_eq!(split_sub_address("+tips"), ("+tips", None));
+        }
+    }
+
+    mod split_amounts {
+        use super::*;
+
+        #[test]
+        fn rounds_shares_down_and_drops_empty_ones() {
+            let platform = Uuid::new_v4();
+            let collaborator = Uuid::new_v4();
+            let splits = vec![
+                LnAddressSplit {
+                    wallet_id: platform,
+                    share_bps: 1_000,
+                },
+                LnAddressSplit {
+                    wallet_id: collaborator,
+                    share_bps: 1,
+                },
+            ];
+
+            assert_eq!(
+                split_amounts(12_345, &splits),
+                vec![(platform, 1_234), (collaborator, 1)]
+            );
+            assert_eq!(split_amounts(9_999, &splits), vec![(platform, 999)]);
+        }
+    }
+
     mod register {
         use super::*;
 
@@ -306,18 +809,12 @@ mod tests {
                 let asset_id = asset.id;
 
                 let mut store = MockAppStoreBuilder::new();
-                store
-                    .ln_address
-                    .expect_find_by_account_id()
-                    .withf(move |id| *id == account_id)
-                    .times(1)
-                    .returning(|_| Ok(None));
                 store
                     .ln_address
                     .expect_find_by_username()
-                    .withf(|username| username == "alice")
+                    .withf(|domain_id, username| domain_id.is_none() && username == "alice")
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
                 store
                     .asset
                     .expect_find_native_btc_by_network()
@@ -333,20 +830,30 @@ mod tests {
                 store
                     .ln_address
                     .expect_insert()
-                    .withf(move |account, wallet, username, _, _| {
-                        *account == account_id && *wallet == wallet_id && username == "alice"
+                    .withf(move |ln_address| {
+                        ln_address.account_id == account_id
+                            && ln_address.wallet_id == wallet_id
+                            && ln_address.domain_id.is_none()
+                            && ln_address.username == "alice"
+                            && ln_address.active
                     })
                     .times(1)
-                    .returning(|account_id, wallet_id, username, _, _| {
-                        Ok(ln_address_fixture(Uuid::new_v4(), account_id, wallet_id, username))
+                    .returning(|ln_address| {
+                        Ok(LnAddress {
+                            id: Uuid::new_v4(),
+                            ..ln_address
+                        })
                     });
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
-                let ln_address = service
-                    .register(account_id, "Alice".to_string(), false, None)
-                    .await
-                    .unwrap();
+                let ln_address = service.register(account_id, register_request("Alice")).await.unwrap();
 
                 assert_eq!(ln_address.username, "alice");
                 assert_eq!(ln_address.account_id, account_id);
@@ -360,10 +867,16 @@ mod tests {
             #[tokio::test]
             async fn rejects_without_touching_the_store() {
                 // No store expectations are installed, so any repository call panics.
-                let service = LnAddressService::new(MockAppStoreBuilder::new().build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    MockAppStoreBuilder::new().build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
-                    .register(Uuid::new_v4(), "invalid username".to_string(), false, None)
+                    .register(Uuid::new_v4(), register_request("invalid username"))
                     .await
                     .unwrap_err();
 
@@ -371,111 +884,338 @@ mod tests {
             }
         }
 
-        mod when_account_already_has_an_address {
+        mod with_a_chosen_wallet {
             use super::*;
 
+            fn store_with_wallet(mut wallet: Wallet) -> MockAppStoreBuilder {
+                let asset = native_btc_asset();
+                let wallet_id = wallet.id;
+                wallet.asset_id = asset.id;
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .times(1)
+                    .returning(move |_| Ok(Some(asset.clone())));
+                store
+                    .wallet
+                    .expect_find()
+                    .withf(move |id| *id == wallet_id)
+                    .times(1)
+                    .returning(move |_| Ok(Some(wallet.clone())));
+                store.wallet.expect_find_by_account_and_asset().never();
+                store
+            }
+
             #[tokio::test]
-            async fn returns_conflict() {
+            async fn routes_the_address_to_it() {
                 let account_id = Uuid::new_v4();
                 let wallet_id = Uuid::new_v4();
 
-                let mut store = MockAppStoreBuilder::new();
+                let mut store = store_with_wallet(wallet(wallet_id, account_id));
                 store
                     .ln_address
-                    .expect_find_by_account_id()
+                    .expect_insert()
+                    .withf(move |ln_address| ln_address.wallet_id == wallet_id)
                     .times(1)
-                    .returning(move |_| {
-                        Ok(Some(ln_address_fixture(
-                            Uuid::new_v4(),
-                            account_id,
-                            wallet_id,
-                            "existing",
-                        )))
-                    });
+                    .returning(Ok);
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
-                let err = service
-                    .register(account_id, "alice".to_string(), false, None)
+                let ln_address = service
+                    .register(
+                        account_id,
+                        RegisterLnAddressRequest {
+                            wallet_id: Some(wallet_id),
+                            ..register_request("tips")
+                        },
+                    )
                     .await
-                    .unwrap_err();
+                    .unwrap();
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
-                assert!(err.to_string().contains("Account already has a lightning address"));
+                assert_eq!(ln_address.wallet_id, wallet_id);
             }
-        }
-
-        mod when_username_is_taken {
-            use super::*;
 
             #[tokio::test]
-            async fn returns_conflict() {
-                let mut store = MockAppStoreBuilder::new();
-                store
-                    .ln_address
-                    .expect_find_by_account_id()
-                    .times(1)
-                    .returning(|_| Ok(None));
-                store.ln_address.expect_find_by_username().times(1).returning(|_| {
-                    Ok(Some(ln_address_fixture(
-                        Uuid::new_v4(),
-                        Uuid::new_v4(),
-                        Uuid::new_v4(),
-                        "alice",
-                    )))
-                });
+            async fn rejects_wallets_of_other_accounts() {
+                let account_id = Uuid::new_v4();
+                let wallet_id = Uuid::new_v4();
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let mut store = store_with_wallet(wallet(wallet_id, Uuid::new_v4()));
+                store.ln_address.expect_insert().never();
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
-                    .register(Uuid::new_v4(), "alice".to_string(), false, None)
+                    .register(
+                        account_id,
+                        RegisterLnAddressRequest {
+                            wallet_id: Some(wallet_id),
+                            ..register_request("tips")
+                        },
+                    )
                     .await
                     .unwrap_err();
 
-                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
-                assert!(err.to_string().contains("Duplicate username"));
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
             }
         }
 
-        mod when_lookup_fails {
+        mod with_splits {
             use super::*;
 
-            #[tokio::test]
-            async fn propagates_database_error() {
+            fn store_with_default_wallet(account_id: Uuid, wallet_id: Uuid, asset: Asset) -> MockAppStoreBuilder {
                 let mut store = MockAppStoreBuilder::new();
                 store
                     .ln_address
-                    .expect_find_by_account_id()
+                    .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Err(DatabaseError::FindOne("boom".to_string())));
+                    .returning(|_, _| Ok(None));
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .returning(move |_| Ok(Some(asset.clone())));
+                store
+                    .wallet
+                    .expect_find_by_account_and_asset()
+                    .times(1)
+                    .returning(move |_, _| Ok(Some(wallet(wallet_id, account_id))));
+                store
+            }
+
+            fn split(wallet_id: Uuid, share_bps: u16) -> LnAddressSplit {
+                LnAddressSplit { wallet_id, share_bps }
+            }
+
+            #[tokio::test]
+            async fn stores_shares_for_wallets_of_other_accounts() {
+                let account_id = Uuid::new_v4();
+                let asset = native_btc_asset();
+                let platform_wallet = Wallet {
+                    asset_id: asset.id,
+                    ..wallet(Uuid::new_v4(), Uuid::new_v4())
+                };
+                let platform_id = platform_wallet.id;
+
+                let mut store = store_with_default_wallet(account_id, Uuid::new_v4(), asset);
+                store
+                    .wallet
+                    .expect_find()
+                    .withf(move |id| *id == platform_id)
+                    .times(1)
+                    .returning(move |_| Ok(Some(platform_wallet.clone())));
+                store
+                    .ln_address
+                    .expect_insert()
+                    .withf(move |ln_address| ln_address.splits == vec![split(platform_id, 1_000)])
+                    .times(1)
+                    .returning(Ok);
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let ln_address = service
+                    .register(
+                        account_id,
+                        RegisterLnAddressRequest {
+                            splits: vec![split(platform_id, 1_000)],
+                            ..register_request("creator")
+                        },
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(ln_address.splits.len(), 1);
+            }
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+            #[tokio::test]
+            async fn rejects_shares_above_the_whole_amount() {
+                let mut store = store_with_default_wallet(Uuid::new_v4(), Uuid::new_v4(), native_btc_asset());
+                store.wallet.expect_find().never();
+                store.ln_address.expect_insert().never();
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
-                    .register(Uuid::new_v4(), "alice".to_string(), false, None)
+                    .register(
+                        Uuid::new_v4(),
+                        RegisterLnAddressRequest {
+                            splits: vec![split(Uuid::new_v4(), 6_000), split(Uuid::new_v4(), 4_001)],
+                            ..register_request("creator")
+                        },
+                    )
                     .await
                     .unwrap_err();
 
-                assert!(matches!(err, ApplicationError::Database(DatabaseError::FindOne(_))));
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_the_receiving_wallet() {
+                let wallet_id = Uuid::new_v4();
+                let mut store = store_with_default_wallet(Uuid::new_v4(), wallet_id, native_btc_asset());
+                store.ln_address.expect_insert().never();
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .register(
+                        Uuid::new_v4(),
+                        RegisterLnAddressRequest {
+                            splits: vec![split(wallet_id, 1_000)],
+                            ..register_request("creator")
+                        },
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_wallets_of_other_assets() {
+                let other_wallet = wallet(Uuid::new_v4(), Uuid::new_v4());
+                let other_id = other_wallet.id;
+
+                let mut store = store_with_default_wallet(Uuid::new_v4(), Uuid::new_v4(), native_btc_asset());
+                store
+                    .wallet
+                    .expect_find()
+                    .times(1)
+                    .returning(move |_| Ok(Some(other_wallet.clone())));
+                store.ln_address.expect_insert().never();
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .register(
+                        Uuid::new_v4(),
+                        RegisterLnAddressRequest {
+                            splits: vec![split(other_id, 1_000)],
+                            ..register_request("creator")
+                        },
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
             }
         }
 
-        mod when_active_network_asset_is_missing {
+        mod when_username_is_taken {
             use super::*;
 
             #[tokio::test]
-            async fn returns_inconsistency() {
+            async fn returns_conflict() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
+                    Ok(Some(ln_address_fixture(
+                        Uuid::new_v4(),
+                        Uuid::new_v4(),
+                        Uuid::new_v4(),
+                        "alice",
+                    )))
+                });
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .register(Uuid::new_v4(), register_request("alice"))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+                assert!(err.to_string().contains("Duplicate username"));
+            }
+        }
+
+        mod when_lookup_fails {
+            use super::*;
+
+            #[tokio::test]
+            async fn propagates_database_error() {
                 let mut store = MockAppStoreBuilder::new();
                 store
                     .ln_address
-                    .expect_find_by_account_id()
+                    .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Err(DatabaseError::FindOne("boom".to_string())));
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .register(Uuid::new_v4(), register_request("alice"))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Database(DatabaseError::FindOne(_))));
+            }
+        }
+
+        mod when_active_network_asset_is_missing {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_inconsistency() {
+                let mut store = MockAppStoreBuilder::new();
                 store
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
                 store
                     .asset
                     .expect_find_native_btc_by_network()
@@ -483,10 +1223,16 @@ mod tests {
                     .times(1)
                     .returning(|_| Ok(None));
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
-                    .register(Uuid::new_v4(), "alice".to_string(), false, None)
+                    .register(Uuid::new_v4(), register_request("alice"))
                     .await
                     .unwrap_err();
 
@@ -505,16 +1251,11 @@ mod tests {
                 let asset_id = asset.id;
 
                 let mut store = MockAppStoreBuilder::new();
-                store
-                    .ln_address
-                    .expect_find_by_account_id()
-                    .times(1)
-                    .returning(|_| Ok(None));
                 store
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
                 store
                     .asset
                     .expect_find_native_btc_by_network()
@@ -528,10 +1269,16 @@ mod tests {
                     .times(1)
                     .returning(|_, _| Ok(None));
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
-                    .register(account_id, "alice".to_string(), false, None)
+                    .register(account_id, register_request("alice"))
                     .await
                     .unwrap_err();
 
@@ -559,7 +1306,13 @@ mod tests {
                     .times(1)
                     .returning(move |id| Ok(Some(ln_address_fixture(id, Uuid::new_v4(), Uuid::new_v4(), "alice"))));
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let ln_address = service.get(id).await.unwrap();
 
@@ -575,7 +1328,13 @@ mod tests {
                 let mut store = MockAppStoreBuilder::new();
                 store.ln_address.expect_find().times(1).returning(|_| Ok(None));
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service.get(Uuid::new_v4()).await.unwrap_err();
 
@@ -599,7 +1358,13 @@ mod tests {
                 )])
             });
 
-            let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
 
             let addresses = service.list(LnAddressFilter::default()).await.unwrap();
 
@@ -618,7 +1383,13 @@ mod tests {
                 let mut store = MockAppStoreBuilder::new();
                 store.ln_address.expect_find().times(1).returning(|_| Ok(None));
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
                     .update(Uuid::new_v4(), update_request(Some("bob")))
@@ -645,9 +1416,9 @@ mod tests {
                 store
                     .ln_address
                     .expect_find_by_username()
-                    .withf(|username| username == "bob")
+                    .withf(|domain_id, username| domain_id.is_none() && username == "bob")
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
                 store
                     .ln_address
                     .expect_update()
@@ -655,7 +1426,13 @@ mod tests {
                     .times(1)
                     .returning(Ok);
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let updated = service.update(id, update_request(Some("Bob"))).await.unwrap();
 
@@ -678,7 +1455,13 @@ mod tests {
                 // username must not trigger a uniqueness lookup.
                 store.ln_address.expect_update().times(1).returning(Ok);
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let updated = service
                     .update(Uuid::new_v4(), update_request(Some("alice")))
@@ -700,7 +1483,7 @@ mod tests {
                     .expect_find()
                     .times(1)
                     .returning(|id| Ok(Some(ln_address_fixture(id, Uuid::new_v4(), Uuid::new_v4(), "alice"))));
-                store.ln_address.expect_find_by_username().times(1).returning(|_| {
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
                     Ok(Some(ln_address_fixture(
                         Uuid::new_v4(),
                         Uuid::new_v4(),
@@ -709,7 +1492,13 @@ mod tests {
                     )))
                 });
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service
                     .update(Uuid::new_v4(), update_request(Some("bob")))
@@ -719,6 +1508,311 @@ mod tests {
                 assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
             }
         }
+
+        mod with_a_new_wallet {
+            use super::*;
+
+            #[tokio::test]
+            async fn reroutes_the_address_within_the_account() {
+                let account_id = Uuid::new_v4();
+                let wallet_id = Uuid::new_v4();
+                let asset = native_btc_asset();
+                let asset_id = asset.id;
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find()
+                    .times(1)
+                    .returning(move |id| Ok(Some(ln_address_fixture(id, account_id, Uuid::new_v4(), "alice"))));
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .times(1)
+                    .returning(move |_| Ok(Some(asset.clone())));
+                store.wallet.expect_find().times(1).returning(move |id| {
+                    Ok(Some(Wallet {
+                        asset_id,
+                        ..wallet(id, account_id)
+                    }))
+                });
+                store
+                    .ln_address
+                    .expect_update()
+                    .withf(move |ln_address| ln_address.wallet_id == wallet_id)
+                    .times(1)
+                    .returning(Ok);
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let updated = service
+                    .update(
+                        Uuid::new_v4(),
+                        UpdateLnAddressRequest {
+                            wallet_id: Some(wallet_id),
+                            ..update_request(None)
+                        },
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(updated.wallet_id, wallet_id);
+            }
+
+            #[tokio::test]
+            async fn rejects_a_wallet_receiving_a_split() {
+                let account_id = Uuid::new_v4();
+                let wallet_id = Uuid::new_v4();
+                let asset = native_btc_asset();
+                let asset_id = asset.id;
+
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find().times(1).returning(move |id| {
+                    Ok(Some(LnAddress {
+                        splits: vec![LnAddressSplit {
+                            wallet_id,
+                            share_bps: 1_000,
+                        }],
+                        ..ln_address_fixture(id, account_id, Uuid::new_v4(), "alice")
+                    }))
+                });
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .returning(move |_| Ok(Some(asset.clone())));
+                store.wallet.expect_find().times(1).returning(move |id| {
+                    Ok(Some(Wallet {
+                        asset_id,
+                        ..wallet(id, account_id)
+                    }))
+                });
+                store.ln_address.expect_update().never();
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .update(
+                        Uuid::new_v4(),
+                        UpdateLnAddressRequest {
+                            wallet_id: Some(wallet_id),
+                            ..update_request(None)
+                        },
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_wallets_of_other_assets() {
+                let account_id = Uuid::new_v4();
+                let asset = native_btc_asset();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find()
+                    .times(1)
+                    .returning(move |id| Ok(Some(ln_address_fixture(id, account_id, Uuid::new_v4(), "alice"))));
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .times(1)
+                    .returning(move |_| Ok(Some(asset.clone())));
+                // The fixture wallet holds another instance of the asset, with its own ID.
+                store
+                    .wallet
+                    .expect_find()
+                    .times(1)
+                    .returning(move |id| Ok(Some(wallet(id, account_id))));
+                store.ln_address.expect_update().never();
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .update(
+                        Uuid::new_v4(),
+                        UpdateLnAddressRequest {
+                            wallet_id: Some(Uuid::new_v4()),
+                            ..update_request(None)
+                        },
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+        }
+
+        mod with_a_valid_pay_profile {
+            use super::*;
+
+            #[tokio::test]
+            async fn replaces_the_profile() {
+                let profile = LnAddressPayProfile {
+                    min_sendable: Some(10_000),
+                    max_sendable: Some(50_000),
+                    comment_allowed: Some(32),
+                    description: Some("Coffee at Alice's".to_string()),
+                    image: Some(BASE64_STANDARD.encode(PNG_SIGNATURE)),
+                    success_action: Some(LnAddressSuccessAction::Url {
+                        description: "Your receipt".to_string(),
+                        url: "https://numeraire.tech/receipts".to_string(),
+                    }),
+                    payer_data: None,
+                };
+                let expected = profile.clone();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find()
+                    .times(1)
+                    .returning(|id| Ok(Some(ln_address_fixture(id, Uuid::new_v4(), Uuid::new_v4(), "alice"))));
+                store
+                    .ln_address
+                    .expect_update()
+                    .withf(move |ln_address| ln_address.pay_profile == expected)
+                    .times(1)
+                    .returning(Ok);
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let updated = service
+                    .update(
+                        Uuid::new_v4(),
+                        UpdateLnAddressRequest {
+                            pay_profile: Some(profile),
+                            ..update_request(None)
+                        },
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(updated.pay_profile.min_sendable, Some(10_000));
+            }
+        }
+
+        mod with_an_invalid_pay_profile {
+            use super::*;
+
+            async fn assert_rejected(profile: LnAddressPayProfile) {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find()
+                    .times(1)
+                    .returning(|id| Ok(Some(ln_address_fixture(id, Uuid::new_v4(), Uuid::new_v4(), "alice"))));
+                // update is intentionally not expected.
+
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
+
+                let err = service
+                    .update(
+                        Uuid::new_v4(),
+                        UpdateLnAddressRequest {
+                            pay_profile: Some(profile),
+                            ..update_request(None)
+                        },
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_inverted_or_empty_amount_ranges() {
+                assert_rejected(LnAddressPayProfile {
+                    min_sendable: Some(50_000),
+                    max_sendable: Some(10_000),
+                    ..Default::default()
+                })
+                .await;
+                assert_rejected(LnAddressPayProfile {
+                    min_sendable: Some(MAX_SENDABLE + 1),
+                    ..Default::default()
+                })
+                .await;
+                assert_rejected(LnAddressPayProfile {
+                    min_sendable: Some(0),
+                    ..Default::default()
+                })
+                .await;
+            }
+
+            #[tokio::test]
+            async fn rejects_images_that_are_not_base64_png() {
+                assert_rejected(LnAddressPayProfile {
+                    image: Some("not base64!".to_string()),
+                    ..Default::default()
+                })
+                .await;
+                assert_rejected(LnAddressPayProfile {
+                    image: Some(BASE64_STANDARD.encode(b"GIF89a")),
+                    ..Default::default()
+                })
+                .await;
+            }
+
+            #[tokio::test]
+            async fn rejects_success_actions_breaking_lud09() {
+                assert_rejected(LnAddressPayProfile {
+                    success_action: Some(LnAddressSuccessAction::Message {
+                        message: "a".repeat(145),
+                    }),
+                    ..Default::default()
+                })
+                .await;
+                assert_rejected(LnAddressPayProfile {
+                    success_action: Some(LnAddressSuccessAction::Url {
+                        description: "Your receipt".to_string(),
+                        url: "https://evil.example/receipts".to_string(),
+                    }),
+                    ..Default::default()
+                })
+                .await;
+                assert_rejected(LnAddressPayProfile {
+                    success_action: Some(LnAddressSuccessAction::Aes {
+                        description: "Your voucher".to_string(),
+                        secret: "a".repeat(MAX_AES_SECRET_LENGTH + 1),
+                    }),
+                    ..Default::default()
+                })
+                .await;
+            }
+        }
     }
 
     mod delete {
@@ -730,23 +1824,41 @@ mod tests {
             #[tokio::test]
             async fn succeeds() {
                 let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find()
+                    .times(1)
+                    .returning(|_| Ok(Some(LnAddress::default())));
                 store.ln_address.expect_delete_many().times(1).returning(|_| Ok(1));
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 assert!(service.delete(Uuid::new_v4()).await.is_ok());
             }
         }
 
-        mod when_nothing_is_removed {
+        mod when_the_address_is_missing {
             use super::*;
 
             #[tokio::test]
             async fn returns_not_found() {
                 let mut store = MockAppStoreBuilder::new();
-                store.ln_address.expect_delete_many().times(1).returning(|_| Ok(0));
+                store.ln_address.expect_find().times(1).returning(|_| Ok(None));
+                store.ln_address.expect_delete_many().never();
 
-                let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+                let service = LnAddressService::new(
+                    store.build(),
+                    BtcNetwork::Regtest,
+                    DOMAIN.to_string(),
+                    HOST.to_string(),
+                    bitcoin(),
+                );
 
                 let err = service.delete(Uuid::new_v4()).await.unwrap_err();
 
@@ -763,11 +1875,284 @@ mod tests {
             let mut store = MockAppStoreBuilder::new();
             store.ln_address.expect_delete_many().times(1).returning(|_| Ok(3));
 
-            let service = LnAddressService::new(store.build(), BtcNetwork::Regtest);
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
 
             let deleted = service.delete_many(LnAddressFilter::default()).await.unwrap();
 
             assert_eq!(deleted, 3);
         }
     }
+
+    fn ln_address_domain(name: &str) -> LnAddressDomain {
+        LnAddressDomain {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            host: "https://pay.brand.example".to_string(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    mod register_domain {
+        use super::*;
+
+        #[tokio::test]
+        async fn lowercases_the_name_and_trims_the_host() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .ln_address_domain
+                .expect_find_by_name()
+                .withf(|name| name == "brand.example")
+                .times(1)
+                .returning(|_| Ok(None));
+            store
+                .ln_address_domain
+                .expect_insert()
+                .withf(|name, host| name == "brand.example" && host == "https://pay.brand.example")
+                .times(1)
+                .returning(|name, _| Ok(ln_address_domain(name)));
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            let domain = service
+                .register_domain("Brand.Example".to_string(), "https://pay.brand.example/".to_string())
+                .await
+                .unwrap();
+
+            assert_eq!(domain.name, "brand.example");
+        }
+
+        #[tokio::test]
+        async fn rejects_the_configured_domain() {
+            let service = LnAddressService::new(
+                MockAppStoreBuilder::new().build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            let err = service
+                .register_domain(DOMAIN.to_uppercase(), HOST.to_string())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_duplicates() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .ln_address_domain
+                .expect_find_by_name()
+                .times(1)
+                .returning(|name| Ok(Some(ln_address_domain(name))));
+            store.ln_address_domain.expect_insert().never();
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            let err = service
+                .register_domain("brand.example".to_string(), "https://pay.brand.example".to_string())
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_names_that_are_not_hostnames() {
+            let service = LnAddressService::new(
+                MockAppStoreBuilder::new().build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            for name in ["https://brand.example", "brand.example:443", "brand.example/pay"] {
+                let err = service
+                    .register_domain(name.to_string(), "https://pay.brand.example".to_string())
+                    .await
+                    .unwrap_err();
+
+                assert!(
+                    matches!(err, ApplicationError::Data(DataError::Validation(_))),
+                    "{name}"
+                );
+            }
+        }
+    }
+
+    mod delete_domain {
+        use super::*;
+
+        #[tokio::test]
+        async fn refuses_domains_with_addresses() {
+            let mut store = MockAppStoreBuilder::new();
+            store.ln_address.expect_find_many().times(1).returning(|_| {
+                Ok(vec![ln_address_fixture(
+                    Uuid::new_v4(),
+                    Uuid::new_v4(),
+                    Uuid::new_v4(),
+                    "alice",
+                )])
+            });
+            store.ln_address_domain.expect_delete().never();
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            let err = service.delete_domain(Uuid::new_v4()).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+        }
+
+        #[tokio::test]
+        async fn returns_not_found_when_nothing_is_removed() {
+            let mut store = MockAppStoreBuilder::new();
+            store.ln_address.expect_find_many().times(1).returning(|_| Ok(vec![]));
+            store.ln_address_domain.expect_delete().times(1).returning(|_| Ok(0));
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            let err = service.delete_domain(Uuid::new_v4()).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+        }
+    }
+
+    mod bip353_record {
+        use super::*;
+
+        fn found(store: &mut MockAppStoreBuilder, username: &'static str, domain_id: Option<Uuid>) -> LnAddress {
+            let mut ln_address = ln_address_fixture(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), username);
+            ln_address.domain_id = domain_id;
+            let returned = ln_address.clone();
+            store
+                .ln_address
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(returned.clone())));
+            ln_address
+        }
+
+        fn silent_payment_address(wallet_id: Uuid) -> MockBitcoinUseCases {
+            let mut bitcoin = MockBitcoinUseCases::new();
+            bitcoin
+                .expect_new_deposit_address()
+                .withf(move |id, address_type| *id == wallet_id && *address_type == Some(BtcAddressType::SilentPayment))
+                .times(1)
+                .returning(|wallet_id, _| {
+                    Ok(BtcAddress {
+                        id: Uuid::new_v4(),
+                        wallet_id,
+                        address: "sprt1qexample".to_string(),
+                        used: false,
+                        address_type: BtcAddressType::SilentPayment,
+                        payjoin_uri: None,
+                        invoice_id: None,
+                        created_at: Utc::now(),
+                        updated_at: None,
+                    })
+                });
+            bitcoin
+        }
+
+        #[tokio::test]
+        async fn publishes_the_silent_payment_address_of_the_wallet() {
+            let mut store = MockAppStoreBuilder::new();
+            let ln_address = found(&mut store, "alice", None);
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                Arc::new(silent_payment_address(ln_address.wallet_id)),
+            );
+
+            let record = service.bip353_record(ln_address.id).await.unwrap();
+
+            assert_eq!(record.bip353_name, "₿alice@numeraire.tech");
+            assert_eq!(record.name, "alice.user._bitcoin-payment.numeraire.tech.");
+            assert_eq!(record.value, "bitcoin:?sp=sprt1qexample");
+            assert_eq!(
+                record.zone_record,
+                "alice.user._bitcoin-payment.numeraire.tech. 3600 IN TXT \"bitcoin:?sp=sprt1qexample\""
+            );
+        }
+
+        #[tokio::test]
+        async fn uses_the_name_of_the_managed_domain() {
+            let mut store = MockAppStoreBuilder::new();
+            let domain_id = Uuid::new_v4();
+            let ln_address = found(&mut store, "alice", Some(domain_id));
+            store.ln_address_domain.expect_find().times(1).returning(move |_| {
+                Ok(Some(LnAddressDomain {
+                    id: domain_id,
+                    ..ln_address_domain("brand.example")
+                }))
+            });
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                Arc::new(silent_payment_address(ln_address.wallet_id)),
+            );
+
+            let record = service.bip353_record(ln_address.id).await.unwrap();
+
+            assert_eq!(record.name, "alice.user._bitcoin-payment.brand.example.");
+        }
+
+        #[tokio::test]
+        async fn rejects_usernames_that_are_not_dns_labels() {
+            let mut store = MockAppStoreBuilder::new();
+            let ln_address = found(&mut store, "alice.bob", None);
+
+            let service = LnAddressService::new(
+                store.build(),
+                BtcNetwork::Regtest,
+                DOMAIN.to_string(),
+                HOST.to_string(),
+                bitcoin(),
+            );
+
+            let err = service.bip353_record(ln_address.id).await.unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+    }
 }
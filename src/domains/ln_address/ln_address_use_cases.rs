@@ -1,13 +1,12 @@
 use async_trait::async_trait;
 
-use nostr_sdk::prelude::PublicKey;
 use uuid::Uuid;
 
-use swissknife_types::UpdateLnAddressRequest;
+use swissknife_types::{LnAddressBip353Record, RegisterLnAddressRequest, UpdateLnAddressRequest};
 
 use crate::application::errors::ApplicationError;
 
-use super::{LnAddress, LnAddressFilter};
+use super::{LnAddress, LnAddressDomain, LnAddressFilter};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -15,13 +14,16 @@ pub trait LnAddressUseCases: Send + Sync {
     async fn register(
         &self,
         account_id: Uuid,
-        username: String,
-        allows_nostr: bool,
-        nostr_pubkey: Option<PublicKey>,
+        request: RegisterLnAddressRequest,
     ) -> Result<LnAddress, ApplicationError>;
     async fn get(&self, id: Uuid) -> Result<LnAddress, ApplicationError>;
     async fn list(&self, filter: LnAddressFilter) -> Result<Vec<LnAddress>, ApplicationError>;
     async fn update(&self, id: Uuid, request: UpdateLnAddressRequest) -> Result<LnAddress, ApplicationError>;
     async fn delete(&self, id: Uuid) -> Result<(), ApplicationError>;
     async fn delete_many(&self, filter: LnAddressFilter) -> Result<u64, ApplicationError>;
+    /// DNS record publishing the address as a BIP353 name, `₿username@domain`.
+    async fn bip353_record(&self, id: Uuid) -> Result<LnAddressBip353Record, ApplicationError>;
+    async fn register_domain(&self, name: String, host: String) -> Result<LnAddressDomain, ApplicationError>;
+    async fn list_domains(&self) -> Result<Vec<LnAddressDomain>, ApplicationError>;
+    async fn delete_domain(&self, id: Uuid) -> Result<(), ApplicationError>;
 }
@@ -1,10 +1,17 @@
+mod ln_address_domain_handler;
+mod ln_address_domain_repository;
 mod ln_address_handler;
 mod ln_address_repository;
 mod ln_address_service;
 mod ln_address_use_cases;
 
+pub use ln_address_domain_handler::*;
+pub use ln_address_domain_repository::*;
 pub use ln_address_handler::*;
 pub use ln_address_repository::*;
 pub use ln_address_service::*;
 pub use ln_address_use_cases::*;
-pub use swissknife_types::{LnAddress, LnAddressFilter};
+pub use swissknife_types::{
+    LnAddress, LnAddressBip353Record, LnAddressDomain, LnAddressFilter, LnAddressPayProfile, LnAddressPayerData,
+    LnAddressSplit, LnAddressSuccessAction,
+};
@@ -1,6 +1,9 @@
 use serde::Deserialize;
 
-pub use swissknife_types::{LnURLPayRequest, LnUrlCallback, LnUrlPaySuccessAction, LnUrlSuccessAction};
+pub use swissknife_types::{
+    LnURLPayRequest, LnUrlCallback, LnUrlPayPreview, LnUrlPaySuccessAction, LnUrlPayerData, LnUrlPayerDataField,
+    LnUrlPayerDataRequest, LnUrlSuccessAction, LnUrlVerifyResponse,
+};
 
 /// Parsed `payRequest` from a remote LNURL service, used when paying out.
 #[derive(Clone, Debug, Deserialize)]
@@ -14,6 +17,8 @@ pub struct LnUrlPayRequestData {
     pub comment_allowed: u16,
     #[serde(default)]
     pub ln_address: Option<String>,
+    #[serde(default)]
+    pub payer_data: Option<LnUrlPayerDataRequest>,
 }
 
 /// Parsed callback response from a remote LNURL service.
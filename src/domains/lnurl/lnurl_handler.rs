@@ -1,25 +1,29 @@
 use std::sync::Arc;
 
 use axum::{extract::State, routing::get, Router};
+use axum_extra::{headers::Host, TypedHeader};
 use utoipa::OpenApi;
+use uuid::Uuid;
 
-use swissknife_types::{ErrorResponse, LNUrlpInvoiceQueryParams};
+use swissknife_types::{ErrorResponse, LNUrlpInvoiceQueryParams, PayLinkCallbackQueryParams, PayLinkQueryParams};
 
 use crate::{
     application::{
         composition::AppServices,
-        docs::{BAD_REQUEST_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE, UNPROCESSABLE_EXAMPLE},
+        docs::{
+            BAD_REQUEST_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE, TOO_MANY_REQUESTS_EXAMPLE, UNPROCESSABLE_EXAMPLE,
+        },
         errors::ApplicationError,
     },
     infra::axum::{Json, Path, Query},
 };
 
-use super::{LnURLPayRequest, LnUrlCallback};
+use super::{LnURLPayRequest, LnUrlCallback, LnUrlVerifyResponse};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(well_known, callback),
-    components(schemas(LnURLPayRequest, LnUrlCallback)),
+    paths(well_known, callback, verify, pay_link, pay_link_callback, pay_link_verify),
+    components(schemas(LnURLPayRequest, LnUrlCallback, LnUrlVerifyResponse)),
     tags(
         (name = "LNURL", description = "Public LNURL endpoints as defined in the [protocol specification](https://github.com/lnurl/luds). Allows any active Lightning Address to receive payments")
     ),
@@ -27,12 +31,21 @@ use super::{LnURLPayRequest, LnUrlCallback};
 pub struct LnURLHandler;
 
 pub fn router() -> Router<Arc<AppServices>> {
-    Router::new().route("/{username}/callback", get(callback))
+    Router::new()
+        .route("/{username}/callback", get(callback))
+        .route("/{username}/verify/{payment_hash}", get(verify))
+}
+
+pub fn pay_link_lnurl_router() -> Router<Arc<AppServices>> {
+    Router::new()
+        .route("/{id}", get(pay_link))
+        .route("/{id}/callback", get(pay_link_callback))
+        .route("/{id}/verify/{payment_hash}", get(pay_link_verify))
 }
 
 /// Well-known endpoint
 ///
-/// Returns the LNURL payRequest for this LN Address (username). The returned payload contains information allowing the payer to generate an invoice. See [LUDS-06](https://github.com/lnurl/luds/blob/luds/06.md)
+/// Returns the LNURL payRequest for this LN Address (username). The address is looked up on the domain named by the `Host` header, falling back to the configured domain. The returned payload contains information allowing the payer to generate an invoice. See [LUDS-06](https://github.com/lnurl/luds/blob/luds/06.md)
 #[utoipa::path(
     get,
     path = "/{username}",
@@ -46,15 +59,17 @@ pub fn router() -> Router<Arc<AppServices>> {
 )]
 pub async fn well_known(
     Path(username): Path<String>,
+    host: Option<TypedHeader<Host>>,
     State(services): State<Arc<AppServices>>,
 ) -> Result<Json<LnURLPayRequest>, ApplicationError> {
-    let lnurlp = services.lnurl.lnurlp(username).await?;
+    let domain = host.map(|TypedHeader(host)| host.hostname().to_string());
+    let lnurlp = services.lnurl.lnurlp(domain, username).await?;
     Ok(lnurlp.into())
 }
 
 /// LNURL callback endpoint
 ///
-/// Returns the callback response for this LN Address (username). Containing an invoice and information on how to behave upon success. See [LUDS-06](https://github.com/lnurl/luds/blob/luds/06.md)
+/// Returns the callback response for this LN Address (username). Containing an invoice and information on how to behave upon success. Invoices are rate limited per client IP and per LN Address. See [LUDS-06](https://github.com/lnurl/luds/blob/luds/06.md)
 #[utoipa::path(
     get,
     path = "/{username}/callback",
@@ -66,6 +81,7 @@ pub async fn well_known(
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
         (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
@@ -76,13 +92,119 @@ async fn callback(
 ) -> Result<Json<LnUrlCallback>, ApplicationError> {
     let callback = services
         .lnurl
-        .lnurlp_callback(username, query_params.amount, query_params.comment)
+        .lnurlp_callback(
+            username,
+            query_params.amount,
+            query_params.comment,
+            query_params.payerdata,
+        )
         .await?;
     Ok(Json(callback))
 }
 
+/// LNURL verify endpoint
+///
+/// Returns whether an invoice issued by the callback of this LN Address (username) has been paid, along with its preimage once settled. See [LUDS-21](https://github.com/lnurl/luds/blob/luds/21.md)
+#[utoipa::path(
+    get,
+    path = "/{username}/verify/{payment_hash}",
+    tag = "LNURL",
+    context_path = "/lnurlp",
+    responses(
+        (status = 200, description = "Found", body = LnUrlVerifyResponse),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn verify(
+    Path((username, payment_hash)): Path<(String, String)>,
+    State(services): State<Arc<AppServices>>,
+) -> Result<Json<LnUrlVerifyResponse>, ApplicationError> {
+    let verify = services.lnurl.lnurlp_verify(username, payment_hash).await?;
+    Ok(Json(verify))
+}
+
+/// Pay link endpoint
+///
+/// Returns the LNURL payRequest of a pay link, the URL encoded in its static QR code. A `reference` query parameter, such as an order ID, is carried to the callback and attached to the invoice. See [LUDS-06](https://github.com/lnurl/luds/blob/luds/06.md)
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "LNURL",
+    context_path = "/lnurl/pay-links",
+    params(PayLinkQueryParams),
+    responses(
+        (status = 200, description = "Found", body = LnURLPayRequest),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn pay_link(
+    Path(id): Path<Uuid>,
+    Query(query_params): Query<PayLinkQueryParams>,
+    State(services): State<Arc<AppServices>>,
+) -> Result<Json<LnURLPayRequest>, ApplicationError> {
+    let lnurlp = services.lnurl.pay_link_request(id, query_params.reference).await?;
+    Ok(Json(lnurlp))
+}
+
+/// Pay link callback endpoint
+///
+/// Returns an invoice for a pay link, paid into the wallet of the link and tagged with the reference. Invoices are rate limited per client IP and per pay link. See [LUDS-06](https://github.com/lnurl/luds/blob/luds/06.md)
+#[utoipa::path(
+    get,
+    path = "/{id}/callback",
+    tag = "LNURL",
+    context_path = "/lnurl/pay-links",
+    params(PayLinkCallbackQueryParams),
+    responses(
+        (status = 200, description = "Found", body = LnUrlCallback),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 429, description = "Too Many Requests", body = ErrorResponse, example = json!(TOO_MANY_REQUESTS_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn pay_link_callback(
+    Path(id): Path<Uuid>,
+    Query(query_params): Query<PayLinkCallbackQueryParams>,
+    State(services): State<Arc<AppServices>>,
+) -> Result<Json<LnUrlCallback>, ApplicationError> {
+    let callback = services
+        .lnurl
+        .pay_link_callback(id, query_params.amount, query_params.comment, query_params.reference)
+        .await?;
+    Ok(Json(callback))
+}
+
+/// Pay link verify endpoint
+///
+/// Returns whether an invoice issued by a pay link has been paid, along with its preimage once settled. See [LUDS-21](https://github.com/lnurl/luds/blob/luds/21.md)
+#[utoipa::path(
+    get,
+    path = "/{id}/verify/{payment_hash}",
+    tag = "LNURL",
+    context_path = "/lnurl/pay-links",
+    responses(
+        (status = 200, description = "Found", body = LnUrlVerifyResponse),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn pay_link_verify(
+    Path((id, payment_hash)): Path<(Uuid, String)>,
+    State(services): State<Arc<AppServices>>,
+) -> Result<Json<LnUrlVerifyResponse>, ApplicationError> {
+    let verify = services.lnurl.pay_link_verify(id, payment_hash).await?;
+    Ok(Json(verify))
+}
+
 #[cfg(test)]
 mod tests {
+    use axum::http::uri::Authority;
+
     use crate::application::{composition::MockAppServicesBuilder, errors::DataError};
 
     use super::*;
@@ -96,11 +218,31 @@ mod tests {
             builder
                 .lnurl
                 .expect_lnurlp()
-                .withf(|username| username == "alice")
+                .withf(|domain, username| domain.is_none() && username == "alice")
                 .times(1)
-                .returning(|_| Err(DataError::NotFound("missing".to_string()).into()));
+                .returning(|_, _| Err(DataError::NotFound("missing".to_string()).into()));
 
-            let result = well_known(Path("alice".to_string()), State(Arc::new(builder.build()))).await;
+            let result = well_known(Path("alice".to_string()), None, State(Arc::new(builder.build()))).await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+
+        #[tokio::test]
+        async fn forwards_the_host_without_its_port() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .lnurl
+                .expect_lnurlp()
+                .withf(|domain, username| domain.as_deref() == Some("brand.example") && username == "alice")
+                .times(1)
+                .returning(|_, _| Err(DataError::NotFound("missing".to_string()).into()));
+
+            let result = well_known(
+                Path("alice".to_string()),
+                Some(TypedHeader(Host::from(Authority::from_static("brand.example:8080")))),
+                State(Arc::new(builder.build())),
+            )
+            .await;
 
             assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
         }
@@ -109,23 +251,83 @@ mod tests {
     mod callback {
         use super::*;
 
+        const PAYER_DATA: &str = r#"{"name":"Dario"}"#;
+
         #[tokio::test]
         async fn forwards_amount_and_comment_to_the_service() {
             let mut builder = MockAppServicesBuilder::new();
             builder
                 .lnurl
                 .expect_lnurlp_callback()
-                .withf(|username, amount, comment| {
-                    username == "alice" && *amount == 2_000 && comment.as_deref() == Some("thanks")
+                .withf(|username, amount, comment, payerdata| {
+                    username == "alice"
+                        && *amount == 2_000
+                        && comment.as_deref() == Some("thanks")
+                        && payerdata.as_deref() == Some(PAYER_DATA)
                 })
                 .times(1)
-                .returning(|_, _, _| Err(DataError::NotFound("missing".to_string()).into()));
+                .returning(|_, _, _, _| Err(DataError::NotFound("missing".to_string()).into()));
 
             let result = callback(
                 Path("alice".to_string()),
                 Query(LNUrlpInvoiceQueryParams {
                     amount: 2_000,
                     comment: Some("thanks".to_string()),
+                    payerdata: Some(PAYER_DATA.to_string()),
+                }),
+                State(Arc::new(builder.build())),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+    }
+
+    mod verify {
+        use super::*;
+
+        #[tokio::test]
+        async fn forwards_the_username_and_payment_hash() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .lnurl
+                .expect_lnurlp_verify()
+                .withf(|username, payment_hash| username == "alice" && payment_hash == "ph")
+                .times(1)
+                .returning(|_, _| Err(DataError::NotFound("missing".to_string()).into()));
+
+            let result = verify(
+                Path(("alice".to_string(), "ph".to_string())),
+                State(Arc::new(builder.build())),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+    }
+
+    mod pay_link_callback {
+        use super::*;
+
+        #[tokio::test]
+        async fn forwards_the_reference_to_the_service() {
+            let id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .lnurl
+                .expect_pay_link_callback()
+                .withf(move |link_id, amount, comment, reference| {
+                    *link_id == id && *amount == 2_000 && comment.is_none() && reference.as_deref() == Some("order-42")
+                })
+                .times(1)
+                .returning(|_, _, _, _| Err(DataError::NotFound("missing".to_string()).into()));
+
+            let result = pay_link_callback(
+                Path(id),
+                Query(PayLinkCallbackQueryParams {
+                    amount: 2_000,
+                    comment: None,
+                    reference: Some("order-42".to_string()),
                 }),
                 State(Arc::new(builder.build())),
             )
@@ -1,7 +1,10 @@
 use std::{sync::Arc, vec};
 
 use async_trait::async_trait;
-use tracing::{debug, info};
+use lnurl::{lnurl::LnUrl, pay::AesParams};
+use reqwest::Url;
+use swissknife_types::{RegisterPayLinkRequest, UpdatePayLinkRequest};
+use tracing::{debug, info, trace};
 use uuid::Uuid;
 
 use crate::{
@@ -9,14 +12,26 @@ use crate::{
         composition::AppStore,
         errors::{ApplicationError, DataError},
     },
+    domains::{
+        bitcoin::BtcNetwork,
+        invoice::InvoiceStatus,
+        ln_address::{find_receiving_ln_address, LnAddress, LnAddressSuccessAction},
+    },
     infra::lightning::LnClient,
 };
 
-use super::{LnURLPayRequest, LnUrlCallback, LnUrlSuccessAction, LnUrlUseCases};
+use super::{
+    payer_data_request, validate_payer_data, LnURLPayRequest, LnUrlCallback, LnUrlSuccessAction, LnUrlUseCases,
+    LnUrlVerifyResponse, PayLink, PayLinkFilter,
+};
 
-const MIN_SENDABLE: u64 = 1000;
-const MAX_SENDABLE: u64 = 250000000;
-const COMMENT_ALLOWED: u16 = 255;
+/// Defaults served for Lightning Addresses without a custom pay profile.
+pub const MIN_SENDABLE: u64 = 1000;
+pub const MAX_SENDABLE: u64 = 250000000;
+pub const COMMENT_ALLOWED: u16 = 255;
+const SUCCESS_MESSAGE: &str = "Thanks for the sats!";
+/// Maximum length of the names, descriptions and references of LNURL-pay links.
+const MAX_PAY_LINK_FIELD_LENGTH: usize = 255;
 
 pub struct LnUrlService {
     domain: String,
@@ -24,6 +39,7 @@ pub struct LnUrlService {
     store: AppStore,
     invoice_expiry: u32,
     ln_client: Arc<dyn LnClient>,
+    network: BtcNetwork,
 }
 
 impl LnUrlService {
@@ -33,6 +49,7 @@ impl LnUrlService {
         invoice_expiry: u32,
         domain: String,
         host: String,
+        network: BtcNetwork,
     ) -> Self {
         LnUrlService {
             store,
@@ -40,27 +57,69 @@ impl LnUrlService {
             invoice_expiry,
             domain,
             host,
+            network,
         }
     }
 
-    fn metadata(&self, username: &str) -> String {
-        serde_json::to_string(&[
-            ["text/identifier".to_string(), format!("{}@{}", username, self.domain)],
-            ["text/plain".to_string(), format!("{} never refuses sats", username)],
-        ])
-        .expect("should not fail as a constant")
+    /// Resolves the domain named by a request. Names that are not a managed domain are served as the configured one.
+    async fn resolve_domain(&self, name: Option<&str>) -> Result<ServedDomain, ApplicationError> {
+        if let Some(name) = name.filter(|name| !name.eq_ignore_ascii_case(&self.domain)) {
+            if let Some(domain) = self.store.ln_address_domain.find_by_name(&name.to_lowercase()).await? {
+                return Ok(ServedDomain {
+                    id: Some(domain.id),
+                    name: domain.name,
+                    host: domain.host,
+                });
+            }
+        }
+
+        Ok(ServedDomain {
+            id: None,
+            name: self.domain.clone(),
+            host: self.host.clone(),
+        })
     }
-}
 
-#[async_trait]
-impl LnUrlUseCases for LnUrlService {
-    async fn lnurlp(&self, username: String) -> Result<LnURLPayRequest, ApplicationError> {
-        debug!(username, "Generating LNURLp");
+    /// Resolves the `username` or `username@domain` path segment of the callback routes.
+    async fn resolve_address(&self, identifier: &str) -> Result<(ServedDomain, LnAddress, String), ApplicationError> {
+        let (username, domain) = match identifier.split_once('@') {
+            Some((username, domain)) => (username, Some(domain)),
+            None => (identifier, None),
+        };
 
-        let ln_address = self
-            .store
-            .ln_address
-            .find_by_username(&username)
+        let domain = self.resolve_domain(domain).await?;
+        let (ln_address, alias) = self.active_ln_address(domain.id, username).await?;
+
+        Ok((domain, ln_address, alias))
+    }
+
+    /// `alias` is the username the address is paid through, such as `username+tag` for sub-addresses.
+    fn metadata(&self, domain: &ServedDomain, ln_address: &LnAddress, alias: &str) -> String {
+        let profile = &ln_address.pay_profile;
+        let description = profile
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("{} never refuses sats", ln_address.username));
+
+        let mut metadata = vec![
+            ["text/identifier".to_string(), format!("{}@{}", alias, domain.name)],
+            ["text/plain".to_string(), description],
+        ];
+        if let Some(image) = &profile.image {
+            metadata.push(["image/png;base64".to_string(), image.clone()]);
+        }
+
+        serde_json::to_string(&metadata).expect("should not fail as a constant")
+    }
+
+    /// Finds the active address receiving payments sent to `username`, along with the lowercased alias paid.
+    async fn active_ln_address(
+        &self,
+        domain_id: Option<Uuid>,
+        username: &str,
+    ) -> Result<(LnAddress, String), ApplicationError> {
+        let alias = username.to_lowercase();
+        let ln_address = find_receiving_ln_address(&self.store, domain_id, &alias)
             .await?
             .ok_or_else(|| DataError::NotFound("Lightning address not found.".to_string()))?;
 
@@ -68,18 +127,207 @@ impl LnUrlUseCases for LnUrlService {
             return Err(DataError::NotFound("Lightning address not found.".to_string()).into());
         }
 
+        Ok((ln_address, alias))
+    }
+
+    /// Public URL of a pay link, encoded as the bech32 LNURL printed on QR codes.
+    fn pay_link_url(&self, id: Uuid) -> String {
+        format!("{}/lnurl/pay-links/{}", self.host, id)
+    }
+
+    fn with_lnurl(&self, mut pay_link: PayLink) -> PayLink {
+        pay_link.lnurl = Some(LnUrl::from_url(self.pay_link_url(pay_link.id)).encode());
+        pay_link
+    }
+
+    async fn find_pay_link(&self, id: Uuid) -> Result<PayLink, ApplicationError> {
+        let pay_link = self
+            .store
+            .pay_link
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Pay link not found.".to_string()))?;
+
+        Ok(pay_link)
+    }
+
+    async fn active_pay_link(&self, id: Uuid) -> Result<PayLink, ApplicationError> {
+        let pay_link = self.find_pay_link(id).await?;
+        if !pay_link.active {
+            return Err(DataError::NotFound("Pay link not found.".to_string()).into());
+        }
+
+        Ok(pay_link)
+    }
+
+    /// Pay links receive Lightning payments, so only native BTC wallets of the active network can hold them.
+    async fn validate_receiving_wallet(&self, wallet_id: Uuid) -> Result<(), ApplicationError> {
+        let wallet = self
+            .store
+            .wallet
+            .find(wallet_id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Wallet not found.".to_string()))?;
+        let asset = self
+            .store
+            .asset
+            .find_native_btc_by_network(self.network)
+            .await?
+            .ok_or_else(|| DataError::Inconsistency("Native BTC asset is not configured.".to_string()))?;
+
+        if wallet.asset_id != asset.id {
+            return Err(DataError::Validation(
+                "Pay links can only receive into a native BTC wallet of the active network.".to_string(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    async fn validate_pay_link_name(&self, wallet_id: Uuid, name: &str) -> Result<(), ApplicationError> {
+        if name.trim().is_empty() || name.chars().count() > MAX_PAY_LINK_FIELD_LENGTH {
+            return Err(DataError::Validation("Invalid name length.".to_string()).into());
+        }
+
+        let pay_links = self
+            .store
+            .pay_link
+            .find_many(PayLinkFilter {
+                wallet_id: Some(wallet_id),
+                ..Default::default()
+            })
+            .await?;
+        if pay_links.iter().any(|pay_link| pay_link.name == name) {
+            return Err(DataError::Conflict("Duplicate pay link name.".to_string()).into());
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_pay_link(pay_link: &PayLink) -> Result<(), DataError> {
+    if let Some(description) = &pay_link.description {
+        if description.chars().count() > MAX_PAY_LINK_FIELD_LENGTH {
+            return Err(DataError::Validation("Invalid description length.".to_string()));
+        }
+    }
+
+    validate_reference(pay_link.reference.as_deref())?;
+
+    if let Some(amount_msat) = pay_link.amount_msat {
+        if !(MIN_SENDABLE..=MAX_SENDABLE).contains(&amount_msat) {
+            return Err(DataError::Validation(format!(
+                "Amount must be between {} and {} msats.",
+                MIN_SENDABLE, MAX_SENDABLE
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_reference(reference: Option<&str>) -> Result<(), DataError> {
+    if let Some(reference) = reference {
+        if reference.is_empty() || reference.chars().count() > MAX_PAY_LINK_FIELD_LENGTH {
+            return Err(DataError::Validation("Invalid reference length.".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Fixed-amount links pin both bounds to their amount.
+fn pay_link_bounds(pay_link: &PayLink) -> (u64, u64) {
+    match pay_link.amount_msat {
+        Some(amount_msat) => (amount_msat, amount_msat),
+        None => (MIN_SENDABLE, MAX_SENDABLE),
+    }
+}
+
+fn pay_link_metadata(pay_link: &PayLink) -> String {
+    let description = pay_link.description.clone().unwrap_or_else(|| pay_link.name.clone());
+    let metadata = vec![["text/plain".to_string(), description]];
+
+    serde_json::to_string(&metadata).expect("should not fail as a constant")
+}
+
+/// Empty strings clear optional fields on update.
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// Domain serving a Lightning Address, either managed or the configured one.
+struct ServedDomain {
+    id: Option<Uuid>,
+    name: String,
+    host: String,
+}
+
+impl ServedDomain {
+    /// Base URL of the callback routes. Managed domains carry their name so callbacks resolve without the `Host`.
+    fn lnurlp_url(&self, username: &str) -> String {
+        match self.id {
+            Some(_) => format!("{}/lnurlp/{}@{}", self.host, username, self.name),
+            None => format!("{}/lnurlp/{}", self.host, username),
+        }
+    }
+}
+
+fn success_action(action: Option<&LnAddressSuccessAction>, preimage: Option<&[u8; 32]>) -> LnUrlSuccessAction {
+    match (action, preimage) {
+        (Some(LnAddressSuccessAction::Url { description, url }), _) => LnUrlSuccessAction {
+            tag: "url".to_string(),
+            description: Some(description.clone()),
+            url: Some(url.clone()),
+            ..Default::default()
+        },
+        (Some(LnAddressSuccessAction::Aes { description, secret }), Some(preimage)) => {
+            let params =
+                AesParams::new(description.clone(), secret, preimage).expect("should not fail with a 32 byte key");
+            LnUrlSuccessAction {
+                tag: "aes".to_string(),
+                description: Some(params.description),
+                ciphertext: Some(params.ciphertext),
+                iv: Some(params.iv),
+                ..Default::default()
+            }
+        }
+        (Some(LnAddressSuccessAction::Message { message }), _) => LnUrlSuccessAction {
+            tag: "message".to_string(),
+            message: Some(message.clone()),
+            ..Default::default()
+        },
+        _ => LnUrlSuccessAction {
+            tag: "message".to_string(),
+            message: Some(SUCCESS_MESSAGE.to_string()),
+            ..Default::default()
+        },
+    }
+}
+
+#[async_trait]
+impl LnUrlUseCases for LnUrlService {
+    async fn lnurlp(&self, domain: Option<String>, username: String) -> Result<LnURLPayRequest, ApplicationError> {
+        debug!(username, ?domain, "Generating LNURLp");
+
+        let domain = self.resolve_domain(domain.as_deref()).await?;
+        let (ln_address, alias) = self.active_ln_address(domain.id, &username).await?;
+        let profile = &ln_address.pay_profile;
+
         let lnurlp = LnURLPayRequest {
-            callback: format!("{}/lnurlp/{}/callback", self.host, username),
-            max_sendable: MAX_SENDABLE,
-            min_sendable: MIN_SENDABLE,
-            metadata: self.metadata(&username),
-            comment_allowed: COMMENT_ALLOWED,
+            callback: format!("{}/callback", domain.lnurlp_url(&alias)),
+            max_sendable: profile.max_sendable.unwrap_or(MAX_SENDABLE),
+            min_sendable: profile.min_sendable.unwrap_or(MIN_SENDABLE),
+            metadata: self.metadata(&domain, &ln_address, &alias),
+            comment_allowed: profile.comment_allowed.unwrap_or(COMMENT_ALLOWED),
             tag: "payRequest".to_string(),
             allows_nostr: ln_address.allows_nostr,
             nostr_pubkey: ln_address.nostr_pubkey,
+            payer_data: Some(payer_data_request(profile.payer_data.as_ref())),
         };
 
-        info!(username, "LNURLp returned successfully");
+        info!(username, domain = domain.name, "LNURLp returned successfully");
         Ok(lnurlp)
     }
 
@@ -88,52 +336,326 @@ impl LnUrlUseCases for LnUrlService {
         username: String,
         amount: u64,
         comment: Option<String>,
+        payerdata: Option<String>,
     ) -> Result<LnUrlCallback, ApplicationError> {
-        debug!(username, amount, comment, "Generating LNURLp invoice");
+        debug!(username, amount, comment, payerdata, "Generating LNURLp invoice");
 
-        let ln_address = self
-            .store
-            .ln_address
-            .find_by_username(&username)
-            .await?
-            .ok_or_else(|| DataError::NotFound("Lightning address not found.".to_string()))?;
+        let (domain, ln_address, alias) = self.resolve_address(&username).await?;
+        let profile = &ln_address.pay_profile;
 
-        if !ln_address.active {
-            return Err(DataError::NotFound("Lightning address not found.".to_string()).into());
+        let min_sendable = profile.min_sendable.unwrap_or(MIN_SENDABLE);
+        let max_sendable = profile.max_sendable.unwrap_or(MAX_SENDABLE);
+        if amount < min_sendable || amount > max_sendable {
+            return Err(DataError::Validation(format!(
+                "Amount must be between {} and {} msats.",
+                min_sendable, max_sendable
+            ))
+            .into());
+        }
+
+        let comment_allowed = profile.comment_allowed.unwrap_or(COMMENT_ALLOWED);
+        if let Some(comment) = &comment {
+            if comment.chars().count() > comment_allowed as usize {
+                return Err(DataError::Validation(format!(
+                    "Comment cannot be longer than {} characters.",
+                    comment_allowed
+                ))
+                .into());
+            }
+        }
+
+        let payer_data = validate_payer_data(payerdata.as_deref(), profile.payer_data.as_ref())?;
+
+        // LUD-18: the description hash commits to the metadata followed by the raw payer data.
+        let mut description = self.metadata(&domain, &ln_address, &alias);
+        if payer_data.is_some() {
+            description.push_str(payerdata.as_deref().unwrap_or_default());
         }
 
+        // LUD-10: AES secrets are encrypted with the preimage, so we pick it ourselves.
+        let preimage =
+            matches!(profile.success_action, Some(LnAddressSuccessAction::Aes { .. })).then(rand::random::<[u8; 32]>);
+
         let invoice_id = Uuid::new_v4();
         let mut invoice = self
             .ln_client
             .invoice(
                 amount,
-                self.metadata(&username),
+                description,
                 invoice_id.to_string(),
                 self.invoice_expiry,
                 true,
+                preimage,
             )
             .await?;
         invoice.id = invoice_id;
         invoice.wallet_id.clone_from(&ln_address.wallet_id);
         invoice.ln_address_id = Some(ln_address.id);
-        invoice.description = Some(comment.unwrap_or(format!("Payment to {}@{}", username, self.domain)));
+        invoice.ln_address_alias = Some(alias.clone());
+        invoice.payer_data = payer_data;
+        invoice.description = Some(comment.unwrap_or(format!("Payment to {}@{}", alias, domain.name)));
 
-        // TODO: Get or add more information to make this a LNURLp invoice (like fetching a success action specific to the user)
         let invoice = self.store.invoice.insert(invoice).await?;
+        let ln_invoice = invoice.ln_invoice.expect("should exist for ledger Lightning");
         let lnurlp_invoice = LnUrlCallback {
-            pr: invoice.ln_invoice.expect("should exist for ledger Lightning").bolt11,
-            success_action: Some(LnUrlSuccessAction {
-                tag: "message".to_string(),
-                message: Some("Thanks for the sats!".to_string()),
-                ..Default::default()
-            }),
+            pr: ln_invoice.bolt11,
+            success_action: Some(success_action(
+                ln_address.pay_profile.success_action.as_ref(),
+                preimage.as_ref(),
+            )),
             disposable: None,
             routes: vec![],
+            verify: Some(format!(
+                "{}/verify/{}",
+                domain.lnurlp_url(&alias),
+                ln_invoice.payment_hash
+            )),
         };
 
         info!(username, "Lightning invoice generated successfully");
         Ok(lnurlp_invoice)
     }
+
+    async fn lnurlp_verify(
+        &self,
+        username: String,
+        payment_hash: String,
+    ) -> Result<LnUrlVerifyResponse, ApplicationError> {
+        debug!(username, payment_hash, "Verifying LNURLp invoice");
+
+        let (_, ln_address, _) = self.resolve_address(&username).await?;
+
+        // Only invoices issued for this address are visible, so the endpoint cannot probe other wallets.
+        let invoice = self
+            .store
+            .invoice
+            .find_by_payment_hash(&payment_hash)
+            .await?
+            .filter(|invoice| invoice.ln_address_id == Some(ln_address.id))
+            .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?;
+        let ln_invoice = invoice.ln_invoice.expect("should exist for ledger Lightning");
+
+        let settled = invoice.status == InvoiceStatus::Settled;
+        let verify = LnUrlVerifyResponse {
+            status: "OK".to_string(),
+            settled,
+            preimage: ln_invoice.payment_preimage.filter(|_| settled),
+            pr: ln_invoice.bolt11,
+        };
+
+        debug!(username, payment_hash, settled, "LNURLp invoice verified successfully");
+        Ok(verify)
+    }
+
+    async fn register_pay_link(
+        &self,
+        wallet_id: Uuid,
+        request: RegisterPayLinkRequest,
+    ) -> Result<PayLink, ApplicationError> {
+        debug!(%wallet_id, ?request, "Registering pay link");
+
+        let pay_link = PayLink {
+            wallet_id,
+            name: request.name,
+            description: request.description.and_then(non_empty),
+            amount_msat: request.amount_msat,
+            reference: request.reference.and_then(non_empty),
+            active: true,
+            ..Default::default()
+        };
+        validate_pay_link(&pay_link)?;
+        self.validate_receiving_wallet(wallet_id).await?;
+        self.validate_pay_link_name(wallet_id, &pay_link.name).await?;
+
+        let pay_link = self.store.pay_link.insert(pay_link).await?;
+
+        info!(%wallet_id, id = %pay_link.id, "Pay link registered successfully");
+        Ok(self.with_lnurl(pay_link))
+    }
+
+    async fn get_pay_link(&self, id: Uuid) -> Result<PayLink, ApplicationError> {
+        trace!(%id, "Fetching pay link");
+
+        let pay_link = self.find_pay_link(id).await?;
+
+        debug!(%id, "Pay link fetched successfully");
+        Ok(self.with_lnurl(pay_link))
+    }
+
+    async fn list_pay_links(&self, filter: PayLinkFilter) -> Result<Vec<PayLink>, ApplicationError> {
+        trace!(?filter, "Listing pay links");
+
+        let pay_links = self.store.pay_link.find_many(filter.clone()).await?;
+
+        debug!(?filter, "Pay links listed successfully");
+        Ok(pay_links
+            .into_iter()
+            .map(|pay_link| self.with_lnurl(pay_link))
+            .collect())
+    }
+
+    async fn update_pay_link(&self, id: Uuid, request: UpdatePayLinkRequest) -> Result<PayLink, ApplicationError> {
+        debug!(%id, ?request, "Updating pay link");
+
+        let mut pay_link = self.find_pay_link(id).await?;
+
+        if let Some(name) = request.name.filter(|name| *name != pay_link.name) {
+            self.validate_pay_link_name(pay_link.wallet_id, &name).await?;
+            pay_link.name = name;
+        }
+
+        if let Some(description) = request.description {
+            pay_link.description = non_empty(description);
+        }
+
+        if let Some(amount_msat) = request.amount_msat {
+            pay_link.amount_msat = (amount_msat > 0).then_some(amount_msat);
+        }
+
+        if let Some(reference) = request.reference {
+            pay_link.reference = non_empty(reference);
+        }
+
+        if let Some(active) = request.active {
+            pay_link.active = active;
+        }
+
+        validate_pay_link(&pay_link)?;
+        let pay_link = self.store.pay_link.update(pay_link).await?;
+
+        info!(%id, "Pay link updated successfully");
+        Ok(self.with_lnurl(pay_link))
+    }
+
+    async fn delete_pay_link(&self, id: Uuid) -> Result<(), ApplicationError> {
+        debug!(%id, "Deleting pay link");
+
+        let n_deleted = self.store.pay_link.delete(id).await?;
+        if n_deleted == 0 {
+            return Err(DataError::NotFound("Pay link not found.".to_string()).into());
+        }
+
+        info!(%id, "Pay link deleted successfully");
+        Ok(())
+    }
+
+    async fn pay_link_request(&self, id: Uuid, reference: Option<String>) -> Result<LnURLPayRequest, ApplicationError> {
+        debug!(%id, reference, "Generating pay link LNURLp");
+
+        validate_reference(reference.as_deref())?;
+        let pay_link = self.active_pay_link(id).await?;
+        let (min_sendable, max_sendable) = pay_link_bounds(&pay_link);
+
+        let mut callback =
+            Url::parse(&format!("{}/callback", self.pay_link_url(id))).expect("should parse from the configured host");
+        if let Some(reference) = &reference {
+            callback.query_pairs_mut().append_pair("reference", reference);
+        }
+
+        let lnurlp = LnURLPayRequest {
+            callback: callback.to_string(),
+            max_sendable,
+            min_sendable,
+            metadata: pay_link_metadata(&pay_link),
+            comment_allowed: COMMENT_ALLOWED,
+            tag: "payRequest".to_string(),
+            allows_nostr: false,
+            nostr_pubkey: None,
+            payer_data: None,
+        };
+
+        info!(%id, "Pay link LNURLp returned successfully");
+        Ok(lnurlp)
+    }
+
+    async fn pay_link_callback(
+        &self,
+        id: Uuid,
+        amount: u64,
+        comment: Option<String>,
+        reference: Option<String>,
+    ) -> Result<LnUrlCallback, ApplicationError> {
+        debug!(%id, amount, comment, reference, "Generating pay link invoice");
+
+        validate_reference(reference.as_deref())?;
+        let pay_link = self.active_pay_link(id).await?;
+
+        let (min_sendable, max_sendable) = pay_link_bounds(&pay_link);
+        if amount < min_sendable || amount > max_sendable {
+            return Err(DataError::Validation(format!(
+                "Amount must be between {} and {} msats.",
+                min_sendable, max_sendable
+            ))
+            .into());
+        }
+
+        if let Some(comment) = &comment {
+            if comment.chars().count() > COMMENT_ALLOWED as usize {
+                return Err(DataError::Validation(format!(
+                    "Comment cannot be longer than {} characters.",
+                    COMMENT_ALLOWED
+                ))
+                .into());
+            }
+        }
+
+        let invoice_id = Uuid::new_v4();
+        let mut invoice = self
+            .ln_client
+            .invoice(
+                amount,
+                pay_link_metadata(&pay_link),
+                invoice_id.to_string(),
+                self.invoice_expiry,
+                true,
+                None,
+            )
+            .await?;
+        invoice.id = invoice_id;
+        invoice.wallet_id = pay_link.wallet_id;
+        invoice.pay_link_id = Some(pay_link.id);
+        invoice.reference = reference.or(pay_link.reference);
+        invoice.description = Some(comment.unwrap_or(format!("Payment to {}", pay_link.name)));
+
+        let invoice = self.store.invoice.insert(invoice).await?;
+        let ln_invoice = invoice.ln_invoice.expect("should exist for ledger Lightning");
+        let callback = LnUrlCallback {
+            pr: ln_invoice.bolt11,
+            success_action: Some(success_action(None, None)),
+            disposable: None,
+            routes: vec![],
+            verify: Some(format!("{}/verify/{}", self.pay_link_url(id), ln_invoice.payment_hash)),
+        };
+
+        info!(%id, reference = invoice.reference, "Pay link invoice generated successfully");
+        Ok(callback)
+    }
+
+    async fn pay_link_verify(&self, id: Uuid, payment_hash: String) -> Result<LnUrlVerifyResponse, ApplicationError> {
+        debug!(%id, payment_hash, "Verifying pay link invoice");
+
+        let pay_link = self.find_pay_link(id).await?;
+
+        let invoice = self
+            .store
+            .invoice
+            .find_by_payment_hash(&payment_hash)
+            .await?
+            .filter(|invoice| invoice.pay_link_id == Some(pay_link.id))
+            .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?;
+        let ln_invoice = invoice.ln_invoice.expect("should exist for ledger Lightning");
+
+        let settled = invoice.status == InvoiceStatus::Settled;
+        let verify = LnUrlVerifyResponse {
+            status: "OK".to_string(),
+            settled,
+            preimage: ln_invoice.payment_preimage.filter(|_| settled),
+            pr: ln_invoice.bolt11,
+        };
+
+        debug!(%id, payment_hash, settled, "Pay link invoice verified successfully");
+        Ok(verify)
+    }
 }
 
 #[cfg(test)]
@@ -143,8 +665,10 @@ mod tests {
     use crate::{
         application::composition::MockAppStoreBuilder,
         domains::{
+            asset::{Asset, Protocol, NATIVE_ASSET_REF},
             invoice::{Invoice, LnInvoice},
-            ln_address::LnAddress,
+            ln_address::{LnAddressPayProfile, LnAddressPayerData},
+            wallet::Wallet,
         },
         infra::lightning::MockLnClient,
     };
@@ -158,23 +682,42 @@ mod tests {
             3_600,
             "numeraire.tech".to_string(),
             "https://numeraire.tech".to_string(),
+            BtcNetwork::Regtest,
         )
     }
 
     fn ln_address(active: bool) -> LnAddress {
+        ln_address_with_profile(active, LnAddressPayProfile::default())
+    }
+
+    fn ln_address_with_profile(active: bool, pay_profile: LnAddressPayProfile) -> LnAddress {
         LnAddress {
             id: Uuid::new_v4(),
             account_id: Uuid::new_v4(),
             wallet_id: Uuid::new_v4(),
             username: "alice".to_string(),
+            domain_id: None,
             active,
             allows_nostr: false,
             nostr_pubkey: None,
+            pay_profile,
+            splits: Vec::new(),
             created_at: Utc::now(),
             updated_at: None,
         }
     }
 
+    fn node_invoice() -> Invoice {
+        Invoice {
+            ln_invoice: Some(LnInvoice {
+                bolt11: "lnbc1example".to_string(),
+                payment_hash: "ph".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
     mod lnurlp {
         use super::*;
 
@@ -187,19 +730,89 @@ mod tests {
                 store
                     .ln_address
                     .expect_find_by_username()
-                    .withf(|username| username == "alice")
+                    .withf(|domain_id, username| domain_id.is_none() && username == "alice")
                     .times(1)
-                    .returning(|_| Ok(Some(ln_address(true))));
+                    .returning(|_, _| Ok(Some(ln_address(true))));
 
                 let request = service(store, MockLnClient::new())
-                    .lnurlp("alice".to_string())
+                    .lnurlp(None, "alice".to_string())
                     .await
                     .unwrap();
 
                 assert_eq!(request.tag, "payRequest");
                 assert_eq!(request.min_sendable, MIN_SENDABLE);
                 assert_eq!(request.max_sendable, MAX_SENDABLE);
+                assert_eq!(request.comment_allowed, COMMENT_ALLOWED);
                 assert!(request.callback.contains("alice"));
+                assert!(request.metadata.contains("alice never refuses sats"));
+                assert!(request.payer_data.unwrap().name.is_some());
+            }
+        }
+
+        mod when_address_has_a_pay_profile {
+            use super::*;
+
+            #[tokio::test]
+            async fn serves_the_profile() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
+                    Ok(Some(ln_address_with_profile(
+                        true,
+                        LnAddressPayProfile {
+                            min_sendable: Some(10_000),
+                            max_sendable: Some(50_000),
+                            comment_allowed: Some(0),
+                            description: Some("Coffee at Alice's".to_string()),
+                            image: Some("iVBORw0KGgo=".to_string()),
+                            success_action: None,
+                            payer_data: None,
+                        },
+                    )))
+                });
+
+                let request = service(store, MockLnClient::new())
+                    .lnurlp(None, "alice".to_string())
+                    .await
+                    .unwrap();
+
+                assert_eq!(request.min_sendable, 10_000);
+                assert_eq!(request.max_sendable, 50_000);
+                assert_eq!(request.comment_allowed, 0);
+                assert_eq!(
+                    request.metadata,
+                    r#"[["text/identifier","alice@numeraire.tech"],["text/plain","Coffee at Alice's"],["image/png;base64","iVBORw0KGgo="]]"#
+                );
+            }
+        }
+
+        mod when_a_sub_address_is_requested {
+            use super::*;
+
+            #[tokio::test]
+            async fn serves_the_base_address_under_the_alias() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .withf(|_, username| username == "alice+tips")
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .withf(|_, username| username == "alice")
+                    .times(1)
+                    .returning(|_, _| Ok(Some(ln_address(true))));
+
+                let request = service(store, MockLnClient::new())
+                    .lnurlp(None, "Alice+Tips".to_string())
+                    .await
+                    .unwrap();
+
+                assert_eq!(request.callback, "https://numeraire.tech/lnurlp/alice+tips/callback");
+                assert!(request
+                    .metadata
+                    .contains(r#"["text/identifier","alice+tips@numeraire.tech"]"#));
             }
         }
 
@@ -213,10 +826,10 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(Some(ln_address(false))));
+                    .returning(|_, _| Ok(Some(ln_address(false))));
 
                 let err = service(store, MockLnClient::new())
-                    .lnurlp("alice".to_string())
+                    .lnurlp(None, "alice".to_string())
                     .await
                     .unwrap_err();
 
@@ -234,10 +847,10 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
 
                 let err = service(store, MockLnClient::new())
-                    .lnurlp("alice".to_string())
+                    .lnurlp(None, "alice".to_string())
                     .await
                     .unwrap_err();
 
@@ -259,49 +872,722 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(Some(ln_address(true))));
+                    .returning(|_, _| Ok(Some(ln_address(true))));
                 store.invoice.expect_insert().times(1).returning(Ok);
 
                 let mut ln_client = MockLnClient::new();
                 ln_client
                     .expect_invoice()
-                    .withf(|_, _, _, _, deschashonly| *deschashonly)
+                    .withf(|_, _, _, _, deschashonly, preimage| *deschashonly && preimage.is_none())
                     .times(1)
-                    .returning(|_, _, _, _, _| {
-                        Ok(Invoice {
-                            ln_invoice: Some(LnInvoice {
-                                bolt11: "lnbc1example".to_string(),
-                                ..Default::default()
-                            }),
-                            ..Default::default()
-                        })
-                    });
+                    .returning(|_, _, _, _, _, _| Ok(node_invoice()));
 
                 let callback = service(store, ln_client)
-                    .lnurlp_callback("alice".to_string(), 2_000, None)
+                    .lnurlp_callback("alice".to_string(), 2_000, None, None)
                     .await
                     .unwrap();
 
                 assert_eq!(callback.pr, "lnbc1example");
-                assert!(callback.success_action.is_some());
+                assert_eq!(
+                    callback.verify.as_deref(),
+                    Some("https://numeraire.tech/lnurlp/alice/verify/ph")
+                );
+                let success_action = callback.success_action.unwrap();
+                assert_eq!(success_action.tag, "message");
+                assert_eq!(success_action.message.as_deref(), Some(SUCCESS_MESSAGE));
             }
         }
 
-        mod when_address_is_inactive {
+        mod when_a_sub_address_is_paid {
             use super::*;
 
             #[tokio::test]
-            async fn returns_not_found_without_calling_the_node() {
+            async fn tags_the_invoice_with_the_alias() {
                 let mut store = MockAppStoreBuilder::new();
                 store
                     .ln_address
                     .expect_find_by_username()
+                    .withf(|_, username| username == "alice+tips")
                     .times(1)
-                    .returning(|_| Ok(Some(ln_address(false))));
-
+                    .returning(|_, _| Ok(None));
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .withf(|_, username| username == "alice")
+                    .times(1)
+                    .returning(|_, _| Ok(Some(ln_address(true))));
+                store
+                    .invoice
+                    .expect_insert()
+                    .withf(|invoice| {
+                        invoice.ln_address_alias.as_deref() == Some("alice+tips")
+                            && invoice.description.as_deref() == Some("Payment to alice+tips@numeraire.tech")
+                    })
+                    .times(1)
+                    .returning(Ok);
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_invoice()
+                    .withf(|_, description, _, _, _, _| description.contains("alice+tips@numeraire.tech"))
+                    .times(1)
+                    .returning(|_, _, _, _, _, _| Ok(node_invoice()));
+
+                let callback = service(store, ln_client)
+                    .lnurlp_callback("alice+tips".to_string(), 2_000, None, None)
+                    .await
+                    .unwrap();
+
+                assert_eq!(
+                    callback.verify.as_deref(),
+                    Some("https://numeraire.tech/lnurlp/alice+tips/verify/ph")
+                );
+            }
+        }
+
+        mod when_payer_data_is_sent {
+            use super::*;
+
+            const PAYER_DATA: &str = r#"{"name":"Dario","identifier":"dario@numeraire.tech"}"#;
+
+            #[tokio::test]
+            async fn commits_it_to_the_description_hash_and_stores_it() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .times(1)
+                    .returning(|_, _| Ok(Some(ln_address(true))));
+                store
+                    .invoice
+                    .expect_insert()
+                    .withf(|invoice| {
+                        invoice
+                            .payer_data
+                            .as_ref()
+                            .is_some_and(|payer_data| payer_data.name.as_deref() == Some("Dario"))
+                    })
+                    .times(1)
+                    .returning(Ok);
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_invoice()
+                    .withf(|_, description, _, _, deschashonly, _| {
+                        *deschashonly && description.starts_with("[[") && description.ends_with(PAYER_DATA)
+                    })
+                    .times(1)
+                    .returning(|_, _, _, _, _, _| Ok(node_invoice()));
+
+                service(store, ln_client)
+                    .lnurlp_callback("alice".to_string(), 2_000, None, Some(PAYER_DATA.to_string()))
+                    .await
+                    .unwrap();
+            }
+
+            #[tokio::test]
+            async fn rejects_missing_mandatory_fields_without_calling_the_node() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
+                    Ok(Some(ln_address_with_profile(
+                        true,
+                        LnAddressPayProfile {
+                            payer_data: Some(LnAddressPayerData {
+                                email: Some(true),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                    )))
+                });
+
+                let err = service(store, MockLnClient::new())
+                    .lnurlp_callback("alice".to_string(), 2_000, None, Some(PAYER_DATA.to_string()))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+        }
+
+        mod when_amount_is_outside_the_profile_range {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_validation_error_without_calling_the_node() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(2).returning(|_, _| {
+                    Ok(Some(ln_address_with_profile(
+                        true,
+                        LnAddressPayProfile {
+                            min_sendable: Some(10_000),
+                            max_sendable: Some(50_000),
+                            ..Default::default()
+                        },
+                    )))
+                });
+                let service = service(store, MockLnClient::new());
+
+                for amount in [9_999, 50_001] {
+                    let err = service
+                        .lnurlp_callback("alice".to_string(), amount, None, None)
+                        .await
+                        .unwrap_err();
+
+                    assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+                }
+            }
+        }
+
+        mod when_comment_is_too_long {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_validation_error_without_calling_the_node() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
+                    Ok(Some(ln_address_with_profile(
+                        true,
+                        LnAddressPayProfile {
+                            comment_allowed: Some(5),
+                            ..Default::default()
+                        },
+                    )))
+                });
+
+                let err = service(store, MockLnClient::new())
+                    .lnurlp_callback("alice".to_string(), 2_000, Some("too long".to_string()), None)
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+        }
+
+        mod when_profile_has_a_url_success_action {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_the_url_action() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
+                    Ok(Some(ln_address_with_profile(
+                        true,
+                        LnAddressPayProfile {
+                            success_action: Some(LnAddressSuccessAction::Url {
+                                description: "Your receipt".to_string(),
+                                url: "https://numeraire.tech/receipts".to_string(),
+                            }),
+                            ..Default::default()
+                        },
+                    )))
+                });
+                store.invoice.expect_insert().times(1).returning(Ok);
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_invoice()
+                    .times(1)
+                    .returning(|_, _, _, _, _, _| Ok(node_invoice()));
+
+                let callback = service(store, ln_client)
+                    .lnurlp_callback("alice".to_string(), 2_000, None, None)
+                    .await
+                    .unwrap();
+
+                let success_action = callback.success_action.unwrap();
+                assert_eq!(success_action.tag, "url");
+                assert_eq!(success_action.description.as_deref(), Some("Your receipt"));
+                assert_eq!(success_action.url.as_deref(), Some("https://numeraire.tech/receipts"));
+            }
+        }
+
+        mod when_profile_has_an_aes_success_action {
+            use std::sync::Mutex;
+
+            use super::*;
+
+            #[tokio::test]
+            async fn encrypts_the_secret_with_the_invoice_preimage() {
+                let mut store = MockAppStoreBuilder::new();
+                store.ln_address.expect_find_by_username().times(1).returning(|_, _| {
+                    Ok(Some(ln_address_with_profile(
+                        true,
+                        LnAddressPayProfile {
+                            success_action: Some(LnAddressSuccessAction::Aes {
+                                description: "Your voucher".to_string(),
+                                secret: "VOUCHER-42".to_string(),
+                            }),
+                            ..Default::default()
+                        },
+                    )))
+                });
+                store.invoice.expect_insert().times(1).returning(Ok);
+
+                let preimage = Arc::new(Mutex::new(None));
+                let captured = preimage.clone();
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_invoice()
+                    .withf(|_, _, _, _, _, preimage| preimage.is_some())
+                    .times(1)
+                    .returning(move |_, _, _, _, _, preimage| {
+                        *captured.lock().unwrap() = preimage;
+                        Ok(node_invoice())
+                    });
+
+                let callback = service(store, ln_client)
+                    .lnurlp_callback("alice".to_string(), 2_000, None, None)
+                    .await
+                    .unwrap();
+
+                let success_action = callback.success_action.unwrap();
+                assert_eq!(success_action.tag, "aes");
+                let params = AesParams {
+                    description: success_action.description.unwrap(),
+                    ciphertext: success_action.ciphertext.unwrap(),
+                    iv: success_action.iv.unwrap(),
+                };
+                let preimage = preimage.lock().unwrap().unwrap();
+                assert_eq!(params.description, "Your voucher");
+                assert_eq!(params.decrypt(&preimage).unwrap(), "VOUCHER-42");
+            }
+        }
+
+        mod when_address_is_inactive {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_not_found_without_calling_the_node() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .times(1)
+                    .returning(|_, _| Ok(Some(ln_address(false))));
+
                 // ln_client.invoice is intentionally not expected.
                 let err = service(store, MockLnClient::new())
-                    .lnurlp_callback("alice".to_string(), 2_000, None)
+                    .lnurlp_callback("alice".to_string(), 2_000, None, None)
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+    }
+
+    mod lnurlp_verify {
+        use super::*;
+
+        fn issued_invoice(ln_address_id: Uuid, status: InvoiceStatus) -> Invoice {
+            let mut invoice = node_invoice();
+            invoice.ln_address_id = Some(ln_address_id);
+            invoice.status = status;
+            if let Some(ln_invoice) = invoice.ln_invoice.as_mut() {
+                ln_invoice.payment_preimage = Some("preimage".to_string());
+            }
+            invoice
+        }
+
+        fn store(ln_address: LnAddress, invoice: Invoice) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .ln_address
+                .expect_find_by_username()
+                .times(1)
+                .returning(move |_, _| Ok(Some(ln_address.clone())));
+            store
+                .invoice
+                .expect_find_by_payment_hash()
+                .withf(|payment_hash| payment_hash == "ph")
+                .times(1)
+                .returning(move |_| Ok(Some(invoice.clone())));
+            store
+        }
+
+        mod when_invoice_is_settled {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_the_preimage() {
+                let ln_address = ln_address(true);
+                let invoice = issued_invoice(ln_address.id, InvoiceStatus::Settled);
+
+                let verify = service(store(ln_address, invoice), MockLnClient::new())
+                    .lnurlp_verify("alice".to_string(), "ph".to_string())
+                    .await
+                    .unwrap();
+
+                assert_eq!(verify.status, "OK");
+                assert!(verify.settled);
+                assert_eq!(verify.preimage.as_deref(), Some("preimage"));
+                assert_eq!(verify.pr, "lnbc1example");
+            }
+        }
+
+        mod when_invoice_is_pending {
+            use super::*;
+
+            #[tokio::test]
+            async fn withholds_the_preimage() {
+                let ln_address = ln_address(true);
+                let invoice = issued_invoice(ln_address.id, InvoiceStatus::Pending);
+
+                let verify = service(store(ln_address, invoice), MockLnClient::new())
+                    .lnurlp_verify("alice".to_string(), "ph".to_string())
+                    .await
+                    .unwrap();
+
+                assert!(!verify.settled);
+                assert!(verify.preimage.is_none());
+                assert_eq!(verify.pr, "lnbc1example");
+            }
+        }
+
+        mod when_invoice_belongs_to_another_address {
+            use super::*;
+
+            #[tokio::test]
+            async fn returns_not_found() {
+                let invoice = issued_invoice(Uuid::new_v4(), InvoiceStatus::Settled);
+
+                let err = service(store(ln_address(true), invoice), MockLnClient::new())
+                    .lnurlp_verify("alice".to_string(), "ph".to_string())
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+    }
+
+    mod pay_links {
+        use super::*;
+
+        fn native_btc_asset() -> Asset {
+            Asset {
+                id: Uuid::new_v4(),
+                code: "BTC".to_string(),
+                name: Some("Bitcoin".to_string()),
+                protocol: Protocol::Bitcoin,
+                network: BtcNetwork::Regtest,
+                asset_ref: NATIVE_ASSET_REF.to_string(),
+                display_ticker: "rBTC".to_string(),
+                decimals: 11,
+                created_at: Utc::now(),
+                updated_at: None,
+            }
+        }
+
+        fn pay_link(amount_msat: Option<u64>, active: bool) -> PayLink {
+            PayLink {
+                id: Uuid::new_v4(),
+                wallet_id: Uuid::new_v4(),
+                name: "Register 3".to_string(),
+                amount_msat,
+                reference: Some("register-3".to_string()),
+                active,
+                created_at: Utc::now(),
+                ..Default::default()
+            }
+        }
+
+        fn register_request(amount_msat: Option<u64>) -> RegisterPayLinkRequest {
+            RegisterPayLinkRequest {
+                wallet_id: None,
+                name: "Register 3".to_string(),
+                description: Some(String::new()),
+                amount_msat,
+                reference: None,
+            }
+        }
+
+        fn store_with_link(pay_link: PayLink) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .pay_link
+                .expect_find()
+                .withf(move |id| *id == pay_link.id)
+                .times(1)
+                .returning(move |_| Ok(Some(pay_link.clone())));
+            store
+        }
+
+        mod register {
+            use super::*;
+
+            fn store_with_wallet(wallet_asset_id: Option<Uuid>, existing: Vec<PayLink>) -> MockAppStoreBuilder {
+                let asset = native_btc_asset();
+                let asset_id = wallet_asset_id.unwrap_or(asset.id);
+
+                let mut store = MockAppStoreBuilder::new();
+                store.wallet.expect_find().times(1).returning(move |id| {
+                    Ok(Some(Wallet {
+                        id,
+                        asset_id,
+                        ..Default::default()
+                    }))
+                });
+                store
+                    .asset
+                    .expect_find_native_btc_by_network()
+                    .withf(|network| *network == BtcNetwork::Regtest)
+                    .times(1)
+                    .returning(move |_| Ok(Some(asset.clone())));
+                store
+                    .pay_link
+                    .expect_find_many()
+                    .returning(move |_| Ok(existing.clone()));
+                store
+            }
+
+            #[tokio::test]
+            async fn stores_the_link_and_returns_its_lnurl() {
+                let wallet_id = Uuid::new_v4();
+                let mut store = store_with_wallet(None, Vec::new());
+                store
+                    .pay_link
+                    .expect_insert()
+                    .withf(move |pay_link| {
+                        pay_link.wallet_id == wallet_id
+                            && pay_link.name == "Register 3"
+                            && pay_link.description.is_none()
+                            && pay_link.amount_msat == Some(21_000)
+                            && pay_link.active
+                    })
+                    .times(1)
+                    .returning(|mut pay_link| {
+                        pay_link.id = Uuid::new_v4();
+                        Ok(pay_link)
+                    });
+
+                let pay_link = service(store, MockLnClient::new())
+                    .register_pay_link(wallet_id, register_request(Some(21_000)))
+                    .await
+                    .unwrap();
+
+                let lnurl = LnUrl::decode(pay_link.lnurl.unwrap()).unwrap();
+                assert_eq!(
+                    lnurl.url,
+                    format!("https://numeraire.tech/lnurl/pay-links/{}", pay_link.id)
+                );
+            }
+
+            #[tokio::test]
+            async fn rejects_a_duplicate_name() {
+                let store = store_with_wallet(None, vec![pay_link(None, true)]);
+
+                let err = service(store, MockLnClient::new())
+                    .register_pay_link(Uuid::new_v4(), register_request(None))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Conflict(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_a_wallet_of_another_asset() {
+                let store = store_with_wallet(Some(Uuid::new_v4()), Vec::new());
+
+                let err = service(store, MockLnClient::new())
+                    .register_pay_link(Uuid::new_v4(), register_request(None))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_an_amount_out_of_bounds() {
+                let err = service(MockAppStoreBuilder::new(), MockLnClient::new())
+                    .register_pay_link(Uuid::new_v4(), register_request(Some(MAX_SENDABLE + 1)))
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+        }
+
+        mod update {
+            use super::*;
+
+            #[tokio::test]
+            async fn clears_the_amount_and_reference() {
+                let link = pay_link(Some(21_000), true);
+                let mut store = store_with_link(link.clone());
+                store
+                    .pay_link
+                    .expect_update()
+                    .withf(|pay_link| {
+                        pay_link.amount_msat.is_none() && pay_link.reference.is_none() && !pay_link.active
+                    })
+                    .times(1)
+                    .returning(Ok);
+
+                let pay_link = service(store, MockLnClient::new())
+                    .update_pay_link(
+                        link.id,
+                        UpdatePayLinkRequest {
+                            amount_msat: Some(0),
+                            reference: Some(String::new()),
+                            active: Some(false),
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                    .unwrap();
+
+                assert!(pay_link.lnurl.is_some());
+            }
+        }
+
+        mod request {
+            use super::*;
+
+            #[tokio::test]
+            async fn pins_fixed_amounts_and_carries_the_reference() {
+                let link = pay_link(Some(21_000), true);
+                let id = link.id;
+
+                let request = service(store_with_link(link), MockLnClient::new())
+                    .pay_link_request(id, Some("order 42".to_string()))
+                    .await
+                    .unwrap();
+
+                assert_eq!(request.min_sendable, 21_000);
+                assert_eq!(request.max_sendable, 21_000);
+                assert_eq!(
+                    request.callback,
+                    format!("https://numeraire.tech/lnurl/pay-links/{id}/callback?reference=order+42")
+                );
+                assert!(request.metadata.contains("Register 3"));
+            }
+
+            #[tokio::test]
+            async fn serves_open_amounts() {
+                let link = pay_link(None, true);
+                let id = link.id;
+
+                let request = service(store_with_link(link), MockLnClient::new())
+                    .pay_link_request(id, None)
+                    .await
+                    .unwrap();
+
+                assert_eq!(request.min_sendable, MIN_SENDABLE);
+                assert_eq!(request.max_sendable, MAX_SENDABLE);
+                assert!(!request.callback.contains('?'));
+            }
+
+            #[tokio::test]
+            async fn hides_inactive_links() {
+                let link = pay_link(None, false);
+                let id = link.id;
+
+                let err = service(store_with_link(link), MockLnClient::new())
+                    .pay_link_request(id, None)
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+
+        mod callback {
+            use super::*;
+
+            #[tokio::test]
+            async fn tags_the_invoice_with_the_payment_reference() {
+                let link = pay_link(None, true);
+                let (id, wallet_id) = (link.id, link.wallet_id);
+                let mut store = store_with_link(link);
+                store
+                    .invoice
+                    .expect_insert()
+                    .withf(move |invoice| {
+                        invoice.wallet_id == wallet_id
+                            && invoice.pay_link_id == Some(id)
+                            && invoice.reference.as_deref() == Some("order-42")
+                            && invoice.description.as_deref() == Some("Payment to Register 3")
+                    })
+                    .times(1)
+                    .returning(Ok);
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_invoice()
+                    .withf(|amount, description, _, _, deschashonly, _| {
+                        *amount == 5_000 && description.contains("Register 3") && *deschashonly
+                    })
+                    .times(1)
+                    .returning(|_, _, _, _, _, _| Ok(node_invoice()));
+
+                let callback = service(store, ln_client)
+                    .pay_link_callback(id, 5_000, None, Some("order-42".to_string()))
+                    .await
+                    .unwrap();
+
+                assert_eq!(callback.pr, "lnbc1example");
+                assert_eq!(
+                    callback.verify,
+                    Some(format!("https://numeraire.tech/lnurl/pay-links/{id}/verify/ph"))
+                );
+            }
+
+            #[tokio::test]
+            async fn falls_back_to_the_link_reference() {
+                let link = pay_link(None, true);
+                let id = link.id;
+                let mut store = store_with_link(link);
+                store
+                    .invoice
+                    .expect_insert()
+                    .withf(|invoice| invoice.reference.as_deref() == Some("register-3"))
+                    .times(1)
+                    .returning(Ok);
+
+                let mut ln_client = MockLnClient::new();
+                ln_client
+                    .expect_invoice()
+                    .times(1)
+                    .returning(|_, _, _, _, _, _| Ok(node_invoice()));
+
+                service(store, ln_client)
+                    .pay_link_callback(id, 5_000, None, None)
+                    .await
+                    .unwrap();
+            }
+
+            #[tokio::test]
+            async fn rejects_another_amount_for_fixed_links() {
+                let link = pay_link(Some(21_000), true);
+                let id = link.id;
+
+                let err = service(store_with_link(link), MockLnClient::new())
+                    .pay_link_callback(id, 20_000, None, None)
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+        }
+
+        mod verify {
+            use super::*;
+
+            #[tokio::test]
+            async fn ignores_invoices_of_other_links() {
+                let link = pay_link(None, true);
+                let id = link.id;
+                let mut store = store_with_link(link);
+                store.invoice.expect_find_by_payment_hash().times(1).returning(|_| {
+                    let mut invoice = node_invoice();
+                    invoice.pay_link_id = Some(Uuid::new_v4());
+                    Ok(Some(invoice))
+                });
+
+                let err = service(store, MockLnClient::new())
+                    .pay_link_verify(id, "ph".to_string())
                     .await
                     .unwrap_err();
 
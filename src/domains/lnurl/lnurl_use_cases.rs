@@ -1,17 +1,45 @@
 use async_trait::async_trait;
+use uuid::Uuid;
+
+use swissknife_types::{RegisterPayLinkRequest, UpdatePayLinkRequest};
 
 use crate::application::errors::ApplicationError;
 
-use super::{LnURLPayRequest, LnUrlCallback};
+use super::{LnURLPayRequest, LnUrlCallback, LnUrlVerifyResponse, PayLink, PayLinkFilter};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait LnUrlUseCases: Send + Sync {
-    async fn lnurlp(&self, username: String) -> Result<LnURLPayRequest, ApplicationError>;
+    async fn lnurlp(&self, domain: Option<String>, username: String) -> Result<LnURLPayRequest, ApplicationError>;
     async fn lnurlp_callback(
         &self,
         username: String,
         amount: u64,
         comment: Option<String>,
+        payerdata: Option<String>,
+    ) -> Result<LnUrlCallback, ApplicationError>;
+    async fn lnurlp_verify(
+        &self,
+        username: String,
+        payment_hash: String,
+    ) -> Result<LnUrlVerifyResponse, ApplicationError>;
+    async fn register_pay_link(
+        &self,
+        wallet_id: Uuid,
+        request: RegisterPayLinkRequest,
+    ) -> Result<PayLink, ApplicationError>;
+    async fn get_pay_link(&self, id: Uuid) -> Result<PayLink, ApplicationError>;
+    async fn list_pay_links(&self, filter: PayLinkFilter) -> Result<Vec<PayLink>, ApplicationError>;
+    async fn update_pay_link(&self, id: Uuid, request: UpdatePayLinkRequest) -> Result<PayLink, ApplicationError>;
+    async fn delete_pay_link(&self, id: Uuid) -> Result<(), ApplicationError>;
+    /// LNURL-pay request of a link. `reference` is carried to the callback and attached to the invoice.
+    async fn pay_link_request(&self, id: Uuid, reference: Option<String>) -> Result<LnURLPayRequest, ApplicationError>;
+    async fn pay_link_callback(
+        &self,
+        id: Uuid,
+        amount: u64,
+        comment: Option<String>,
+        reference: Option<String>,
     ) -> Result<LnUrlCallback, ApplicationError>;
+    async fn pay_link_verify(&self, id: Uuid, payment_hash: String) -> Result<LnUrlVerifyResponse, ApplicationError>;
 }
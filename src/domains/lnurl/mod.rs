@@ -2,10 +2,17 @@ mod entities;
 mod lnurl_handler;
 mod lnurl_service;
 mod lnurl_use_cases;
+mod pay_link_handler;
+mod pay_link_repository;
+mod payer_data;
 mod utils;
 
 pub use entities::*;
 pub use lnurl_handler::*;
 pub use lnurl_service::*;
 pub use lnurl_use_cases::*;
+pub use pay_link_handler::*;
+pub use pay_link_repository::*;
+pub use payer_data::*;
+pub use swissknife_types::{PayLink, PayLinkFilter};
 pub use utils::*;
@@ -0,0 +1,280 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{delete, get, post, put},
+    Router,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use swissknife_types::{ErrorResponse, RegisterPayLinkRequest, UpdatePayLinkRequest};
+
+use crate::{
+    application::{
+        composition::AppServices,
+        docs::{
+            BAD_REQUEST_EXAMPLE, CONFLICT_EXAMPLE, FORBIDDEN_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE,
+            UNAUTHORIZED_EXAMPLE, UNPROCESSABLE_EXAMPLE,
+        },
+        errors::{ApplicationError, DataError},
+    },
+    domains::account::{Permission, User},
+    infra::axum::{Json, Path, Query},
+};
+
+use super::{PayLink, PayLinkFilter};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(register_pay_link, get_pay_link, list_pay_links, update_pay_link, delete_pay_link),
+    components(schemas(PayLink, RegisterPayLinkRequest, UpdatePayLinkRequest)),
+    tags(
+        (name = "Pay Links", description = "Reusable LNURL-pay endpoints of a wallet, printable as static QR codes for points of sale. Require `read:ln_address` or `write:ln_address` permissions.")
+    )
+)]
+pub struct PayLinkHandler;
+pub const CONTEXT_PATH: &str = "/v1/pay-links";
+
+pub fn pay_link_router() -> Router<Arc<AppServices>> {
+    Router::new()
+        .route("/", post(register_pay_link))
+        .route("/", get(list_pay_links))
+        .route("/{id}", get(get_pay_link))
+        .route("/{id}", put(update_pay_link))
+        .route("/{id}", delete(delete_pay_link))
+}
+
+/// Register a new pay link
+///
+/// Registers a named LNURL-pay link for a wallet, with a fixed or open amount. The returned `lnurl` can be printed as a QR code.
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "Pay Links",
+    context_path = CONTEXT_PATH,
+    request_body = RegisterPayLinkRequest,
+    responses(
+        (status = 200, description = "Pay Link Registered", body = PayLink),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn register_pay_link(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<RegisterPayLinkRequest>,
+) -> Result<Json<PayLink>, ApplicationError> {
+    user.check_permission(Permission::WriteLnAddress)?;
+
+    let wallet_id = payload
+        .wallet_id
+        .ok_or_else(|| DataError::Malformed("wallet_id is required.".to_string()))?;
+    let pay_link = services.lnurl.register_pay_link(wallet_id, payload).await?;
+    Ok(pay_link.into())
+}
+
+/// Find a pay link
+///
+/// Returns the pay link by its ID.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "Pay Links",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Found", body = PayLink),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn get_pay_link(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PayLink>, ApplicationError> {
+    user.check_permission(Permission::ReadLnAddress)?;
+
+    let pay_link = services.lnurl.get_pay_link(id).await?;
+    Ok(pay_link.into())
+}
+
+/// List pay links
+///
+/// Returns all the pay links given a filter
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "Pay Links",
+    context_path = CONTEXT_PATH,
+    params(PayLinkFilter),
+    responses(
+        (status = 200, description = "Success", body = Vec<PayLink>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_pay_links(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Query(query_params): Query<PayLinkFilter>,
+) -> Result<Json<Vec<PayLink>>, ApplicationError> {
+    user.check_permission(Permission::ReadLnAddress)?;
+
+    let pay_links = services.lnurl.list_pay_links(query_params).await?;
+    Ok(pay_links.into())
+}
+
+/// Update a pay link
+///
+/// Updates the name, description, amount, reference or status of a pay link. Its URL and QR code stay the same.
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    tag = "Pay Links",
+    context_path = CONTEXT_PATH,
+    request_body = UpdatePayLinkRequest,
+    responses(
+        (status = 200, description = "Pay Link Updated", body = PayLink),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn update_pay_link(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdatePayLinkRequest>,
+) -> Result<Json<PayLink>, ApplicationError> {
+    user.check_permission(Permission::WriteLnAddress)?;
+
+    let pay_link = services.lnurl.update_pay_link(id, payload).await?;
+    Ok(pay_link.into())
+}
+
+/// Delete a pay link
+///
+/// Deletes a pay link by ID. Invoices it issued keep their reference. Returns an empty body
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "Pay Links",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_pay_link(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    user.check_permission(Permission::WriteLnAddress)?;
+
+    services.lnurl.delete_pay_link(id).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::application::composition::MockAppServicesBuilder;
+
+    use super::*;
+
+    fn user(permissions: Vec<Permission>) -> User {
+        User {
+            account_id: Uuid::new_v4(),
+            permissions,
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: None,
+        }
+    }
+
+    fn register_request(wallet_id: Option<Uuid>) -> RegisterPayLinkRequest {
+        RegisterPayLinkRequest {
+            wallet_id,
+            name: "Register 3".to_string(),
+            description: None,
+            amount_msat: None,
+            reference: None,
+        }
+    }
+
+    mod register_pay_link {
+        use super::*;
+
+        #[tokio::test]
+        async fn is_forbidden_without_the_write_permission() {
+            let result = register_pay_link(
+                State(Arc::new(MockAppServicesBuilder::new().build())),
+                user(vec![Permission::ReadLnAddress]),
+                Json(register_request(Some(Uuid::new_v4()))),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn requires_a_wallet() {
+            let result = register_pay_link(
+                State(Arc::new(MockAppServicesBuilder::new().build())),
+                user(vec![Permission::WriteLnAddress]),
+                Json(register_request(None)),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::Malformed(_)))));
+        }
+
+        #[tokio::test]
+        async fn forwards_the_wallet_to_the_service() {
+            let wallet_id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .lnurl
+                .expect_register_pay_link()
+                .withf(move |id, request| *id == wallet_id && request.name == "Register 3")
+                .times(1)
+                .returning(|wallet_id, request| {
+                    Ok(PayLink {
+                        wallet_id,
+                        name: request.name,
+                        ..Default::default()
+                    })
+                });
+
+            let result = register_pay_link(
+                State(Arc::new(builder.build())),
+                user(vec![Permission::WriteLnAddress]),
+                Json(register_request(Some(wallet_id))),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(result.0.wallet_id, wallet_id);
+        }
+    }
+}
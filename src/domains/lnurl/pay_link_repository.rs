@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::lnurl::{PayLink, PayLinkFilter},
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PayLinkRepository: Send + Sync {
+    async fn find(&self, id: Uuid) -> Result<Option<PayLink>, DatabaseError>;
+    async fn find_many(&self, filter: PayLinkFilter) -> Result<Vec<PayLink>, DatabaseError>;
+    async fn insert(&self, pay_link: PayLink) -> Result<PayLink, DatabaseError>;
+    async fn update(&self, pay_link: PayLink) -> Result<PayLink, DatabaseError>;
+    async fn delete(&self, id: Uuid) -> Result<u64, DatabaseError>;
+}
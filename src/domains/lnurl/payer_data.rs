@@ -0,0 +1,264 @@
+use std::str::FromStr;
+
+use bitcoin::secp256k1::PublicKey;
+
+use crate::{application::errors::DataError, domains::ln_address::LnAddressPayerData};
+
+use super::{LnUrlPayerData, LnUrlPayerDataField, LnUrlPayerDataRequest};
+
+const MAX_PAYER_DATA_LENGTH: usize = 2048;
+const MAX_FIELD_LENGTH: usize = 255;
+
+/// Payer data requested from addresses that do not configure their own.
+fn default_payer_data() -> LnAddressPayerData {
+    LnAddressPayerData {
+        name: Some(false),
+        pubkey: Some(false),
+        identifier: Some(false),
+        email: Some(false),
+    }
+}
+
+/// Builds the `payerData` advertised in a `payRequest`. LNURL-auth is never requested, as we don't track the `k1`
+/// challenges that would make its proofs fresh.
+pub fn payer_data_request(requested: Option<&LnAddressPayerData>) -> LnUrlPayerDataRequest {
+    let requested = requested.cloned().unwrap_or_else(default_payer_data);
+    let field = |mandatory: Option<bool>| mandatory.map(|mandatory| LnUrlPayerDataField { mandatory });
+
+    LnUrlPayerDataRequest {
+        name: field(requested.name),
+        pubkey: field(requested.pubkey),
+        identifier: field(requested.identifier),
+        email: field(requested.email),
+        auth: None,
+    }
+}
+
+/// Validates the `payerdata` received on a callback against what the address requests.
+pub fn validate_payer_data(
+    raw: Option<&str>,
+    requested: Option<&LnAddressPayerData>,
+) -> Result<Option<LnUrlPayerData>, DataError> {
+    let requested = requested.cloned().unwrap_or_else(default_payer_data);
+
+    let payer_data = match raw {
+        Some(raw) => {
+            if raw.len() > MAX_PAYER_DATA_LENGTH {
+                return Err(DataError::Validation("Payer data is too large.".to_string()));
+            }
+            serde_json::from_str::<LnUrlPayerData>(raw)
+                .map_err(|_| DataError::Validation("Invalid payer data.".to_string()))?
+        }
+        None => LnUrlPayerData::default(),
+    };
+
+    check_field("name", payer_data.name.is_some(), requested.name)?;
+    check_field("pubkey", payer_data.pubkey.is_some(), requested.pubkey)?;
+    check_field("identifier", payer_data.identifier.is_some(), requested.identifier)?;
+    check_field("email", payer_data.email.is_some(), requested.email)?;
+    check_field("auth", payer_data.auth.is_some(), None)?;
+
+    for (field, value) in [
+        ("name", &payer_data.name),
+        ("identifier", &payer_data.identifier),
+        ("email", &payer_data.email),
+    ] {
+        if value
+            .as_ref()
+            .is_some_and(|value| value.chars().count() > MAX_FIELD_LENGTH)
+        {
+            return Err(DataError::Validation(format!("Payer {} is too long.", field)));
+        }
+    }
+
+    if payer_data.email.as_ref().is_some_and(|email| !email.contains('@')) {
+        return Err(DataError::Validation("Invalid payer email.".to_string()));
+    }
+
+    if let Some(pubkey) = &payer_data.pubkey {
+        PublicKey::from_str(pubkey).map_err(|_| DataError::Validation("Invalid payer pubkey.".to_string()))?;
+    }
+
+    Ok(raw.map(|_| payer_data))
+}
+
+fn check_field(field: &str, present: bool, requested: Option<bool>) -> Result<(), DataError> {
+    match (present, requested) {
+        (true, None) => Err(DataError::Validation(format!("Payer {} was not requested.", field))),
+        (false, Some(true)) => Err(DataError::Validation(format!("Payer {} is required.", field))),
+        _ => Ok(()),
+    }
+}
+
+/// Builds the payer data we attach when paying an external LNURL, limited to the fields it makes mandatory.
+pub fn outgoing_payer_data(
+    request: &LnUrlPayerDataRequest,
+    name: Option<String>,
+    identifier: Option<String>,
+) -> Result<Option<LnUrlPayerData>, String> {
+    let mandatory = |field: &Option<LnUrlPayerDataField>| field.as_ref().is_some_and(|field| field.mandatory);
+
+    let payer_data = LnUrlPayerData {
+        name: name.filter(|_| mandatory(&request.name)),
+        identifier: identifier.filter(|_| mandatory(&request.identifier)),
+        ..Default::default()
+    };
+
+    for (field, missing) in [
+        ("name", mandatory(&request.name) && payer_data.name.is_none()),
+        (
+            "identifier",
+            mandatory(&request.identifier) && payer_data.identifier.is_none(),
+        ),
+        ("pubkey", mandatory(&request.pubkey)),
+        ("email", mandatory(&request.email)),
+        ("auth", request.auth.as_ref().is_some_and(|auth| auth.mandatory)),
+    ] {
+        if missing {
+            return Err(format!(
+                "LNURL service requires payer {} which cannot be provided",
+                field
+            ));
+        }
+    }
+
+    Ok((payer_data != LnUrlPayerData::default()).then_some(payer_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use swissknife_types::LnUrlPayerDataAuth;
+
+    use super::*;
+
+    mod payer_data_request {
+        use super::*;
+
+        #[test]
+        fn requests_optional_fields_by_default() {
+            let request = payer_data_request(None);
+
+            assert_eq!(request.name, Some(LnUrlPayerDataField { mandatory: false }));
+            assert_eq!(request.email, Some(LnUrlPayerDataField { mandatory: false }));
+            assert!(request.auth.is_none());
+        }
+    }
+
+    mod validate_payer_data {
+        use super::*;
+
+        #[test]
+        fn accepts_requested_fields() {
+            let payer_data = validate_payer_data(
+                Some(r#"{"name":"Dario","identifier":"dario@numeraire.tech","email":"dario@numeraire.tech"}"#),
+                None,
+            )
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(payer_data.name.as_deref(), Some("Dario"));
+            assert_eq!(payer_data.identifier.as_deref(), Some("dario@numeraire.tech"));
+        }
+
+        #[test]
+        fn accepts_missing_payer_data_when_nothing_is_mandatory() {
+            assert_eq!(validate_payer_data(None, None).unwrap(), None);
+        }
+
+        #[test]
+        fn rejects_missing_mandatory_fields() {
+            let requested = LnAddressPayerData {
+                email: Some(true),
+                ..Default::default()
+            };
+
+            assert!(validate_payer_data(None, Some(&requested)).is_err());
+            assert!(validate_payer_data(Some("{}"), Some(&requested)).is_err());
+        }
+
+        #[test]
+        fn rejects_fields_that_were_not_requested() {
+            let requested = LnAddressPayerData {
+                name: Some(false),
+                ..Default::default()
+            };
+
+            assert!(validate_payer_data(Some(r#"{"email":"dario@numeraire.tech"}"#), Some(&requested)).is_err());
+        }
+
+        #[test]
+        fn rejects_malformed_values() {
+            assert!(validate_payer_data(Some("not json"), None).is_err());
+            assert!(validate_payer_data(Some(r#"{"email":"dario"}"#), None).is_err());
+            assert!(validate_payer_data(Some(r#"{"pubkey":"02ab"}"#), None).is_err());
+        }
+
+        #[test]
+        fn rejects_auth_proofs() {
+            let raw = serde_json::to_string(&LnUrlPayerData {
+                auth: Some(LnUrlPayerDataAuth {
+                    key: "02".repeat(33),
+                    k1: "01".repeat(32),
+                    sig: "30".to_string(),
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+            assert!(validate_payer_data(Some(&raw), None).is_err());
+        }
+    }
+
+    mod outgoing_payer_data {
+        use super::*;
+
+        #[test]
+        fn sends_only_mandatory_fields() {
+            let request = LnUrlPayerDataRequest {
+                name: Some(LnUrlPayerDataField { mandatory: false }),
+                identifier: Some(LnUrlPayerDataField { mandatory: true }),
+                ..Default::default()
+            };
+
+            let payer_data = outgoing_payer_data(
+                &request,
+                Some("alice".to_string()),
+                Some("alice@numeraire.tech".to_string()),
+            )
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(payer_data.name, None);
+            assert_eq!(payer_data.identifier.as_deref(), Some("alice@numeraire.tech"));
+        }
+
+        #[test]
+        fn sends_nothing_when_nothing_is_mandatory() {
+            let request = LnUrlPayerDataRequest {
+                name: Some(LnUrlPayerDataField { mandatory: false }),
+                identifier: Some(LnUrlPayerDataField { mandatory: false }),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                outgoing_payer_data(
+                    &request,
+                    Some("alice".to_string()),
+                    Some("alice@numeraire.tech".to_string())
+                )
+                .unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn fails_when_a_mandatory_field_cannot_be_provided() {
+            let request = LnUrlPayerDataRequest {
+                identifier: Some(LnUrlPayerDataField { mandatory: true }),
+                ..Default::default()
+            };
+
+            assert!(outgoing_payer_data(&request, Some("alice".to_string()), None).is_err());
+        }
+    }
+}
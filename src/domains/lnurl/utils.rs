@@ -14,20 +14,95 @@ use serde::Deserialize;
 use serde_bolt::bitcoin::hashes::{sha256, Hash};
 use tracing::{trace, warn};
 
-use crate::domains::lnurl::{LnUrlPayCallbackResponse, LnUrlPayRequestData, LnUrlPaySuccessAction};
+use crate::domains::lnurl::{
+    LnUrlPayCallbackResponse, LnUrlPayPreview, LnUrlPayRequestData, LnUrlPaySuccessAction, LnUrlPayerData,
+};
 
 use super::LnUrlSuccessAction;
 
+/// Checks an amount and comment against the terms of a `payRequest`, before calling the remote callback.
+pub fn validate_lnurl_pay_terms(
+    amount_msat: u64,
+    comment: Option<&str>,
+    req: &LnUrlPayRequestData,
+) -> std::result::Result<(), String> {
+    if amount_msat < req.min_sendable || amount_msat > req.max_sendable {
+        return Err(format!(
+            "Amount must be between {} and {} msats.",
+            req.min_sendable, req.max_sendable
+        ));
+    }
+
+    // LUD-12: services that do not set `commentAllowed` do not accept comments.
+    if let Some(comment) = comment.filter(|comment| !comment.is_empty()) {
+        if req.comment_allowed == 0 {
+            return Err("Recipient does not accept comments.".to_string());
+        }
+        if comment.chars().count() > req.comment_allowed as usize {
+            return Err(format!(
+                "Comment cannot be longer than {} characters.",
+                req.comment_allowed
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Terms of a `payRequest` as shown to the payer, with its metadata unpacked (LUD-06).
+pub fn lnurl_pay_preview(req: &LnUrlPayRequestData) -> LnUrlPayPreview {
+    let mut preview = LnUrlPayPreview {
+        ln_address: req.ln_address.clone(),
+        min_sendable: req.min_sendable,
+        max_sendable: req.max_sendable,
+        comment_allowed: req.comment_allowed,
+        metadata: req.metadata.clone(),
+        payer_data: req.payer_data.clone(),
+        ..Default::default()
+    };
+
+    // Malformed metadata is left raw: the callback still commits to it, so paying stays possible.
+    let entries = serde_json::from_str::<Vec<Vec<serde_json::Value>>>(&req.metadata).unwrap_or_default();
+    for entry in entries {
+        let (Some(mime), Some(content)) = (
+            entry.first().and_then(|v| v.as_str()),
+            entry.get(1).and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+
+        match mime {
+            "text/plain" => preview.description = Some(content.to_string()),
+            "text/long-desc" => preview.long_description = Some(content.to_string()),
+            "image/png;base64" | "image/jpeg;base64" => {
+                let media_type = mime.trim_end_matches(";base64");
+                preview.image = Some(format!("data:{media_type};base64,{content}"));
+            }
+            _ => {}
+        }
+    }
+
+    preview
+}
+
 pub async fn validate_lnurl_pay(
     user_amount_msat: u64,
     comment: &Option<String>,
+    payer_data: Option<&LnUrlPayerData>,
     req: &LnUrlPayRequestData,
 ) -> Result<LnUrlPayCallbackResponse> {
-    trace!(?req, "Validating LNURL pay request");
+    trace!(?req, ?payer_data, "Validating LNURL pay request");
 
     let pay = lnurl_pay_response_from_request(req);
-    let callback_resp =
-        fetch_lnurl_pay_invoice(reqwest::Client::new(), &pay, user_amount_msat, comment.as_deref()).await?;
+    let payer_data = payer_data.map(serde_json::to_string).transpose()?;
+    let callback_resp = fetch_lnurl_pay_invoice(
+        reqwest::Client::new(),
+        &pay,
+        user_amount_msat,
+        comment.as_deref(),
+        payer_data.as_deref(),
+    )
+    .await?;
 
     validate_invoice(user_amount_msat, &callback_resp.pr)?;
 
@@ -60,19 +135,25 @@ async fn fetch_lnurl_pay_invoice(
     pay: &LnurlPayResponse,
     msats: u64,
     comment: Option<&str>,
+    payer_data: Option<&str>,
 ) -> Result<LnURLPayInvoice> {
-    let url = lnurl_pay_callback_url(pay, msats, comment)?;
+    let url = lnurl_pay_callback_url(pay, msats, comment, payer_data)?;
     let response_text = client.get(url).send().await?.error_for_status()?.text().await?;
     parse_lnurl_pay_invoice_response(&response_text)
 }
 
-fn lnurl_pay_callback_url(pay: &LnurlPayResponse, msats: u64, comment: Option<&str>) -> Result<Url> {
+fn lnurl_pay_callback_url(
+    pay: &LnurlPayResponse,
+    msats: u64,
+    comment: Option<&str>,
+    payer_data: Option<&str>,
+) -> Result<Url> {
     if msats < pay.min_sendable || msats > pay.max_sendable {
         return Err(anyhow!("Invalid LNURL payment amount"));
     }
 
     if let (Some(comment), Some(max_length)) = (comment, pay.comment_allowed) {
-        if comment.len() > max_length as usize {
+        if comment.chars().count() > max_length as usize {
             return Err(anyhow!("Invalid LNURL comment"));
         }
     }
@@ -85,6 +166,9 @@ fn lnurl_pay_callback_url(pay: &LnurlPayResponse, msats: u64, comment: Option<&s
         if let Some(comment) = comment {
             query.append_pair("comment", comment);
         }
+        if let Some(payer_data) = payer_data {
+            query.append_pair("payerdata", payer_data);
+        }
     }
 
     Ok(url)
@@ -264,6 +348,7 @@ mod tests {
             &pay_response("https://example.com/lnurl/callback?existing=1"),
             2_000,
             Some("thanks & sats + #freedom"),
+            None,
         )
         .unwrap();
 
@@ -279,9 +364,29 @@ mod tests {
         assert!(url.as_str().contains("comment=thanks+%26+sats+%2B+%23freedom"));
     }
 
+    #[test]
+    fn lnurl_pay_callback_url_appends_payer_data() {
+        let url = lnurl_pay_callback_url(
+            &pay_response("https://example.com/callback"),
+            2_000,
+            None,
+            Some(r#"{"identifier":"alice@numeraire.tech"}"#),
+        )
+        .unwrap();
+
+        let query_pairs = url.query_pairs().into_owned().collect::<Vec<_>>();
+        assert_eq!(
+            query_pairs[1],
+            (
+                "payerdata".to_string(),
+                r#"{"identifier":"alice@numeraire.tech"}"#.to_string()
+            )
+        );
+    }
+
     #[test]
     fn lnurl_pay_callback_url_rejects_invalid_amount() {
-        let err = lnurl_pay_callback_url(&pay_response("https://example.com/callback"), 999, None).unwrap_err();
+        let err = lnurl_pay_callback_url(&pay_response("https://example.com/callback"), 999, None, None).unwrap_err();
         assert!(err.to_string().contains("Invalid LNURL payment amount"));
     }
 
@@ -290,7 +395,7 @@ mod tests {
         let mut pay = pay_response("https://example.com/callback");
         pay.comment_allowed = Some(3);
 
-        let err = lnurl_pay_callback_url(&pay, 2_000, Some("four")).unwrap_err();
+        let err = lnurl_pay_callback_url(&pay, 2_000, Some("four"), None).unwrap_err();
         assert!(err.to_string().contains("Invalid LNURL comment"));
     }
 
@@ -429,4 +534,58 @@ mod tests {
     fn decrypt_success_action_returns_none_for_invalid_preimage() {
         assert!(decrypt_success_action("ciphertext".to_string(), "iv".to_string(), "not-hex").is_none());
     }
+
+    fn pay_request(metadata: &str, comment_allowed: u16) -> LnUrlPayRequestData {
+        LnUrlPayRequestData {
+            callback: "https://example.com/callback".to_string(),
+            min_sendable: 1_000,
+            max_sendable: 10_000,
+            metadata: metadata.to_string(),
+            comment_allowed,
+            ln_address: Some("alice@example.com".to_string()),
+            payer_data: None,
+        }
+    }
+
+    #[test]
+    fn validate_lnurl_pay_terms_reports_the_amount_range() {
+        let err = validate_lnurl_pay_terms(10_001, None, &pay_request("[]", 0)).unwrap_err();
+        assert_eq!(err, "Amount must be between 1000 and 10000 msats.");
+        assert!(validate_lnurl_pay_terms(10_000, None, &pay_request("[]", 0)).is_ok());
+    }
+
+    #[test]
+    fn validate_lnurl_pay_terms_enforces_the_comment_allowance() {
+        let err = validate_lnurl_pay_terms(2_000, Some("hi"), &pay_request("[]", 0)).unwrap_err();
+        assert_eq!(err, "Recipient does not accept comments.");
+
+        let err = validate_lnurl_pay_terms(2_000, Some("héllo"), &pay_request("[]", 4)).unwrap_err();
+        assert_eq!(err, "Comment cannot be longer than 4 characters.");
+        assert!(validate_lnurl_pay_terms(2_000, Some("héll"), &pay_request("[]", 4)).is_ok());
+    }
+
+    #[test]
+    fn lnurl_pay_preview_unpacks_the_metadata() {
+        let metadata =
+            r#"[["text/plain","Coffee"],["text/long-desc","Coffee at Alice's"],["image/png;base64","iVBORw0KGgo="]]"#;
+
+        let preview = lnurl_pay_preview(&pay_request(metadata, 144));
+
+        assert_eq!(preview.ln_address.as_deref(), Some("alice@example.com"));
+        assert_eq!(preview.min_sendable, 1_000);
+        assert_eq!(preview.max_sendable, 10_000);
+        assert_eq!(preview.comment_allowed, 144);
+        assert_eq!(preview.description.as_deref(), Some("Coffee"));
+        assert_eq!(preview.long_description.as_deref(), Some("Coffee at Alice's"));
+        assert_eq!(preview.image.as_deref(), Some("data:image/png;base64,iVBORw0KGgo="));
+        assert_eq!(preview.metadata, metadata);
+    }
+
+    #[test]
+    fn lnurl_pay_preview_keeps_malformed_metadata_raw() {
+        let preview = lnurl_pay_preview(&pay_request("not json", 0));
+
+        assert!(preview.description.is_none());
+        assert_eq!(preview.metadata, "not json");
+    }
 }
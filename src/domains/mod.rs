@@ -1,5 +1,6 @@
 pub mod account;
 pub mod asset;
+pub mod audit;
 pub mod bitcoin;
 pub mod event;
 pub mod invoice;
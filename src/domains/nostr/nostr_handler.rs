@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use axum::extract::State;
+use axum_extra::{headers::Host, TypedHeader};
 use utoipa::OpenApi;
 
 use swissknife_types::{ErrorResponse, NostrNIP05QueryParams, NostrNIP05Response};
@@ -26,7 +27,7 @@ pub struct NostrHandler;
 
 /// Well-known endpoint
 ///
-/// Returns the names known by this service given username. The returned payload contains public keys in hex format. See [NIP-05](https://github.com/nostr-protocol/nips/blob/master/05.md)
+/// Returns the names known by this service given username. Names are looked up on the domain named by the `Host` header, falling back to the configured domain. The returned payload contains public keys in hex format. See [NIP-05](https://github.com/nostr-protocol/nips/blob/master/05.md)
 #[utoipa::path(
     get,
     path = "/nostr.json",
@@ -41,14 +42,17 @@ pub struct NostrHandler;
 )]
 pub async fn well_known_nostr(
     Query(query_params): Query<NostrNIP05QueryParams>,
+    host: Option<TypedHeader<Host>>,
     State(services): State<Arc<AppServices>>,
 ) -> Result<Json<NostrNIP05Response>, ApplicationError> {
-    let pubkey = services.nostr.get_pubkey(query_params.name.clone()).await?;
+    let domain = host.map(|TypedHeader(host)| host.hostname().to_string());
+    let pubkey = services.nostr.get_pubkey(domain, query_params.name.clone()).await?;
     Ok(NostrNIP05Response::new(query_params.name, pubkey).into())
 }
 
 #[cfg(test)]
 mod tests {
+    use axum::http::uri::Authority;
     use nostr_sdk::prelude::PublicKey;
 
     use crate::application::{composition::MockAppServicesBuilder, errors::DataError};
@@ -66,18 +70,23 @@ mod tests {
         use super::*;
 
         #[tokio::test]
-        async fn forwards_the_name_and_returns_the_pubkey() {
+        async fn forwards_the_host_and_name_and_returns_the_pubkey() {
             let pubkey = PublicKey::from_hex(VALID_PUBKEY_HEX).unwrap();
 
             let mut builder = MockAppServicesBuilder::new();
             builder
                 .nostr
                 .expect_get_pubkey()
-                .withf(|name| name == "alice")
+                .withf(|domain, name| domain.as_deref() == Some("brand.example") && name == "alice")
                 .times(1)
-                .returning(move |_| Ok(pubkey));
+                .returning(move |_, _| Ok(pubkey));
 
-            let result = well_known_nostr(Query(query("alice")), State(Arc::new(builder.build()))).await;
+            let result = well_known_nostr(
+                Query(query("alice")),
+                Some(TypedHeader(Host::from(Authority::from_static("brand.example:8080")))),
+                State(Arc::new(builder.build())),
+            )
+            .await;
 
             assert!(result.is_ok());
         }
@@ -89,9 +98,9 @@ mod tests {
                 .nostr
                 .expect_get_pubkey()
                 .times(1)
-                .returning(|_| Err(DataError::NotFound("missing".to_string()).into()));
+                .returning(|_, _| Err(DataError::NotFound("missing".to_string()).into()));
 
-            let result = well_known_nostr(Query(query("alice")), State(Arc::new(builder.build()))).await;
+            let result = well_known_nostr(Query(query("alice")), None, State(Arc::new(builder.build()))).await;
 
             assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
         }
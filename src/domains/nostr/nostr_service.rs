@@ -21,13 +21,24 @@ impl NostrService {
 
 #[async_trait]
 impl NostrUseCases for NostrService {
-    async fn get_pubkey(&self, username: String) -> Result<PublicKey, ApplicationError> {
-        trace!(username, "Fetching Nostr identifier");
+    async fn get_pubkey(&self, domain: Option<String>, username: String) -> Result<PublicKey, ApplicationError> {
+        trace!(username, ?domain, "Fetching Nostr identifier");
+
+        // Names that are not a managed domain are served as the configured domain.
+        let domain_id = match domain {
+            Some(domain) => self
+                .store
+                .ln_address_domain
+                .find_by_name(&domain.to_lowercase())
+                .await?
+                .map(|domain| domain.id),
+            None => None,
+        };
 
         let ln_address = self
             .store
             .ln_address
-            .find_by_username(&username)
+            .find_by_username(domain_id, &username)
             .await?
             .ok_or_else(|| DataError::NotFound("Nostr ID not found.".to_string()))?;
 
@@ -62,9 +73,12 @@ mod tests {
             account_id: Uuid::new_v4(),
             wallet_id: Uuid::new_v4(),
             username: "alice".to_string(),
+            domain_id: None,
             active: true,
             allows_nostr,
             nostr_pubkey,
+            pay_profile: Default::default(),
+            splits: Vec::new(),
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -84,13 +98,13 @@ mod tests {
                 store
                     .ln_address
                     .expect_find_by_username()
-                    .withf(|username| username == "alice")
+                    .withf(|domain_id, username| domain_id.is_none() && username == "alice")
                     .times(1)
-                    .returning(move |_| Ok(Some(ln_address_fixture(true, Some(pubkey)))));
+                    .returning(move |_, _| Ok(Some(ln_address_fixture(true, Some(pubkey)))));
 
                 let service = NostrService::new(store.build());
 
-                let result = service.get_pubkey("alice".to_string()).await.unwrap();
+                let result = service.get_pubkey(None, "alice".to_string()).await.unwrap();
 
                 assert_eq!(result, pubkey);
             }
@@ -106,11 +120,11 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
 
                 let service = NostrService::new(store.build());
 
-                let err = service.get_pubkey("alice".to_string()).await.unwrap_err();
+                let err = service.get_pubkey(None, "alice".to_string()).await.unwrap_err();
 
                 assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
             }
@@ -128,11 +142,11 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(move |_| Ok(Some(ln_address_fixture(false, Some(pubkey)))));
+                    .returning(move |_, _| Ok(Some(ln_address_fixture(false, Some(pubkey)))));
 
                 let service = NostrService::new(store.build());
 
-                let err = service.get_pubkey("alice".to_string()).await.unwrap_err();
+                let err = service.get_pubkey(None, "alice".to_string()).await.unwrap_err();
 
                 assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
             }
@@ -148,11 +162,11 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(Some(ln_address_fixture(true, None))));
+                    .returning(|_, _| Ok(Some(ln_address_fixture(true, None))));
 
                 let service = NostrService::new(store.build());
 
-                let err = service.get_pubkey("alice".to_string()).await.unwrap_err();
+                let err = service.get_pubkey(None, "alice".to_string()).await.unwrap_err();
 
                 assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
             }
@@ -168,11 +182,11 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Err(DatabaseError::FindOne("boom".to_string())));
+                    .returning(|_, _| Err(DatabaseError::FindOne("boom".to_string())));
 
                 let service = NostrService::new(store.build());
 
-                let err = service.get_pubkey("alice".to_string()).await.unwrap_err();
+                let err = service.get_pubkey(None, "alice".to_string()).await.unwrap_err();
 
                 assert!(matches!(err, ApplicationError::Database(DatabaseError::FindOne(_))));
             }
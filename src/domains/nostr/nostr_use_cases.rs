@@ -6,5 +6,5 @@ use crate::application::errors::ApplicationError;
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait NostrUseCases: Send + Sync {
-    async fn get_pubkey(&self, username: String) -> Result<PublicKey, ApplicationError>;
+    async fn get_pubkey(&self, domain: Option<String>, username: String) -> Result<PublicKey, ApplicationError>;
 }
@@ -0,0 +1,200 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+/// Prefix BIP353 recommends displaying human-readable names with.
+pub const BIP353_PREFIX: char = '₿';
+
+/// TTL of the published records. Payment instructions are static, so they can be cached for long.
+pub const BIP353_RECORD_TTL: u32 = 3600;
+
+/// Longest character-string of a TXT record.
+const TXT_STRING_MAX_LEN: usize = 255;
+
+#[derive(Debug, Error)]
+pub enum DnsResolverError {
+    #[error("DNS query failed: {0}")]
+    Query(String),
+
+    #[error("DNS answer for {0} is not DNSSEC-validated")]
+    Insecure(String),
+}
+
+/// Resolves TXT records through a DNSSEC-validating resolver.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait DnsResolver: Send + Sync {
+    /// TXT records of `name`, each with its character-strings concatenated. A name that doesn't exist has no
+    /// records. Answers the resolver could not authenticate with DNSSEC are rejected.
+    async fn resolve_txt(&self, name: &str) -> Result<Vec<String>, DnsResolverError>;
+}
+
+/// A BIP353 human-readable name, `₿user@domain`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bip353Name {
+    pub user: String,
+    pub domain: String,
+    /// Whether the name was written with the `₿` prefix, which rules out it being a Lightning Address.
+    pub explicit: bool,
+}
+
+impl Bip353Name {
+    pub fn parse(input: &str) -> Option<Self> {
+        let (name, explicit) = match input.strip_prefix(BIP353_PREFIX) {
+            Some(name) => (name, true),
+            None => (input, false),
+        };
+        let (user, domain) = name.split_once('@')?;
+        let domain = domain.trim_end_matches('.');
+
+        if !is_dns_label(user) || domain.split('.').count() < 2 || !domain.split('.').all(is_dns_label) {
+            return None;
+        }
+
+        Some(Self {
+            user: user.to_lowercase(),
+            domain: domain.to_lowercase(),
+            explicit,
+        })
+    }
+
+    /// Name of the TXT record holding the payment instructions.
+    pub fn record_name(&self) -> String {
+        bip353_record_name(&self.user, &self.domain)
+    }
+}
+
+impl std::fmt::Display for Bip353Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{BIP353_PREFIX}{}@{}", self.user, self.domain)
+    }
+}
+
+pub fn bip353_record_name(user: &str, domain: &str) -> String {
+    format!("{user}.user._bitcoin-payment.{domain}.")
+}
+
+pub fn strip_bip353_prefix(input: &str) -> &str {
+    input.strip_prefix(BIP353_PREFIX).unwrap_or(input)
+}
+
+/// Whether `label` can be published as a single DNS label. Only ASCII is supported; internationalized names
+/// would need to be punycode-encoded first.
+pub fn is_dns_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The payment instructions among the TXT records of a name: the only record starting with `bitcoin:`.
+pub fn payment_instructions(records: Vec<String>) -> Result<Option<String>, String> {
+    let mut instructions = records.into_iter().filter(|record| {
+        record
+            .get(..8)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("bitcoin:"))
+    });
+
+    let Some(first) = instructions.next() else {
+        return Ok(None);
+    };
+    if instructions.next().is_some() {
+        return Err("Multiple BIP353 payment instructions".to_string());
+    }
+
+    Ok(Some(first))
+}
+
+/// Zone file value of a TXT record, split in character-strings of at most 255 bytes.
+pub fn txt_record_value(value: &str) -> String {
+    value
+        .as_bytes()
+        .chunks(TXT_STRING_MAX_LEN)
+        .map(|chunk| {
+            format!(
+                "\"{}\"",
+                String::from_utf8_lossy(chunk)
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn accepts_names_with_and_without_the_prefix() {
+            assert_eq!(
+                Bip353Name::parse("₿Alice@Example.com"),
+                Some(Bip353Name {
+                    user: "alice".to_string(),
+                    domain: "example.com".to_string(),
+                    explicit: true,
+                })
+            );
+            assert_eq!(
+                Bip353Name::parse("alice@example.com").map(|name| name.explicit),
+                Some(false)
+            );
+        }
+
+        #[test]
+        fn rejects_names_that_are_not_dns_labels() {
+            assert_eq!(Bip353Name::parse("₿alice"), None);
+            assert_eq!(Bip353Name::parse("₿al ice@example.com"), None);
+            assert_eq!(Bip353Name::parse("₿alice.bob@example.com"), None);
+            assert_eq!(Bip353Name::parse("₿alice@localhost"), None);
+            assert_eq!(Bip353Name::parse("₿alice@example..com"), None);
+        }
+
+        #[test]
+        fn builds_the_record_name() {
+            let name = Bip353Name::parse("₿alice@example.com").unwrap();
+
+            assert_eq!(name.record_name(), "alice.user._bitcoin-payment.example.com.");
+            assert_eq!(name.to_string(), "₿alice@example.com");
+        }
+    }
+
+    mod payment_instructions {
+        use super::*;
+
+        #[test]
+        fn picks_the_bitcoin_uri_among_other_records() {
+            let instructions =
+                payment_instructions(vec!["v=spf1 -all".to_string(), "BITCOIN:?sp=sp1qexample".to_string()]).unwrap();
+
+            assert_eq!(instructions, Some("BITCOIN:?sp=sp1qexample".to_string()));
+        }
+
+        #[test]
+        fn is_none_without_a_bitcoin_uri() {
+            assert_eq!(payment_instructions(vec!["v=spf1 -all".to_string()]).unwrap(), None);
+            assert_eq!(payment_instructions(vec![]).unwrap(), None);
+        }
+
+        #[test]
+        fn rejects_several_bitcoin_uris() {
+            assert!(payment_instructions(vec!["bitcoin:?sp=a".to_string(), "bitcoin:?sp=b".to_string()]).is_err());
+        }
+    }
+
+    #[test]
+    fn txt_record_value_splits_long_values() {
+        let value = format!("bitcoin:?sp={}", "q".repeat(300));
+
+        let record = txt_record_value(&value);
+
+        assert_eq!(
+            record,
+            format!("\"bitcoin:?sp={}\" \"{}\"", "q".repeat(243), "q".repeat(57))
+        );
+    }
+}
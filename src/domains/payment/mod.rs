@@ -1,3 +1,4 @@
+mod bip353;
 mod payment_handler;
 mod payment_input;
 mod payment_repository;
@@ -5,6 +6,7 @@ mod payment_service;
 mod payment_unit_of_work;
 mod payment_use_cases;
 
+pub use bip353::*;
 pub use payment_handler::*;
 pub(crate) use payment_input::LnPaymentTarget;
 pub use payment_repository::*;
@@ -12,5 +14,5 @@ pub use payment_service::*;
 pub use payment_unit_of_work::*;
 pub use payment_use_cases::*;
 pub use swissknife_types::{
-    BtcPayment, InternalPayment, LnPayment, Payment, PaymentFeeEstimate, PaymentFilter, PaymentStatus,
+    BtcPayment, InternalPayment, LnPayment, Payment, PaymentFeeEstimate, PaymentFilter, PaymentStatus, TransferRequest,
 };
@@ -9,7 +9,7 @@ use axum_extra::extract::Query;
 use utoipa::OpenApi;
 use uuid::Uuid;
 
-use swissknife_types::{ErrorResponse, SendPaymentRequest};
+use swissknife_types::{ErrorResponse, PreviewPaymentRequest, SendPaymentRequest, TransferRequest};
 
 use crate::{
     application::{
@@ -22,7 +22,8 @@ use crate::{
     },
     domains::{
         account::{Permission, User},
-        lnurl::LnUrlSuccessAction,
+        bitcoin::BtcFeeSelection,
+        lnurl::{LnUrlPayPreview, LnUrlSuccessAction},
     },
     infra::axum::{Json, Path},
 };
@@ -31,7 +32,7 @@ use super::{BtcPayment, InternalPayment, LnPayment, Payment, PaymentFeeEstimate,
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(estimate_payment_fee, pay, get_payment, list_payments, delete_payment, delete_payments),
+    paths(estimate_payment_fee, preview_lnurl_pay, pay, transfer, get_payment, list_payments, delete_payment, delete_payments),
     components(schemas(
         Payment,
         PaymentFeeEstimate,
@@ -39,6 +40,9 @@ use super::{BtcPayment, InternalPayment, LnPayment, Payment, PaymentFeeEstimate,
         BtcPayment,
         InternalPayment,
         SendPaymentRequest,
+        PreviewPaymentRequest,
+        LnUrlPayPreview,
+        TransferRequest,
         PaymentStatus,
         LnUrlSuccessAction
     )),
@@ -52,7 +56,9 @@ pub const CONTEXT_PATH: &str = "/v1/payments";
 pub fn router() -> Router<Arc<AppServices>> {
     Router::new()
         .route("/fee-estimate", post(estimate_payment_fee))
+        .route("/lnurl-preview", post(preview_lnurl_pay))
         .route("/", post(pay))
+        .route("/transfers", post(transfer))
         .route("/", get(list_payments))
         .route("/{id}", get(get_payment))
         .route("/{id}", delete(delete_payment))
@@ -61,7 +67,9 @@ pub fn router() -> Router<Arc<AppServices>> {
 
 /// Estimate an outgoing payment fee
 ///
-/// Returns the provider-derived expected fee and the hard maximum used during payment execution.
+/// Returns the provider-derived expected fee and the hard maximum used during payment execution. LNURL and Lightning
+/// Address inputs also return the terms of the recipient, without a fee while the amount or comment do not satisfy
+/// them.
 #[utoipa::path(
     post,
     path = "/fee-estimate",
@@ -86,14 +94,48 @@ async fn estimate_payment_fee(
     let wallet_id = payload
         .wallet_id
         .ok_or_else(|| DataError::Malformed("wallet_id is required.".to_string()))?;
+    let fee = BtcFeeSelection::new(payload.conf_target, payload.feerate_sat_vb)?;
     let estimate = services
         .payment
-        .estimate_fee(payload.input, payload.amount_msat, payload.comment, wallet_id)
+        .estimate_fee(payload.input, payload.amount_msat, payload.comment, wallet_id, fee)
         .await?;
 
     Ok(Json(estimate))
 }
 
+/// Preview an LNURL-pay request
+///
+/// Fetches the remote payRequest of an LNURL or Lightning Address and returns its sendable range, comment allowance,
+/// description and image so a confirmation screen can be rendered before paying.
+#[utoipa::path(
+    post,
+    path = "/lnurl-preview",
+    tag = "Payments",
+    context_path = CONTEXT_PATH,
+    request_body = PreviewPaymentRequest,
+    responses(
+        (status = 200, description = "LNURL-pay request fetched", body = LnUrlPayPreview),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn preview_lnurl_pay(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<PreviewPaymentRequest>,
+) -> Result<Json<LnUrlPayPreview>, ApplicationError> {
+    user.check_permission(Permission::WriteTransaction)?;
+    let wallet_id = payload
+        .wallet_id
+        .ok_or_else(|| DataError::Malformed("wallet_id is required.".to_string()))?;
+    let preview = services.payment.preview_lnurl_pay(payload.input, wallet_id).await?;
+
+    Ok(Json(preview))
+}
+
 /// Send a payment
 ///
 /// Pay a Lightning invoice, LNURL, Lightning Address, on-chain address, or another account on this instance.
@@ -122,14 +164,59 @@ async fn pay(
         .wallet_id
         .ok_or_else(|| DataError::Malformed("wallet_id is required.".to_string()))?;
 
+    let fee = BtcFeeSelection::new(payload.conf_target, payload.feerate_sat_vb)?;
+    let max_amount_msat = services.auth.payment_step_up_limit(&user).await?;
     let payment = services
         .payment
-        .pay(payload.input, payload.amount_msat, payload.comment, wallet_id)
+        .pay(
+            payload.input,
+            payload.amount_msat,
+            payload.comment,
+            wallet_id,
+            fee,
+            max_amount_msat,
+        )
         .await?;
 
     Ok(Json(payment))
 }
 
+/// Transfer between wallets
+///
+/// Move funds to another wallet on this instance, selected by wallet ID, account or Lightning Address, without a Lightning invoice.
+/// Both wallets must hold the same asset.
+#[utoipa::path(
+    post,
+    path = "/transfers",
+    tag = "Payments",
+    context_path = CONTEXT_PATH,
+    request_body = TransferRequest,
+    responses(
+        (status = 200, description = "Transfer Settled", body = Payment),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 403, description = "Forbidden", body = ErrorResponse, example = json!(FORBIDDEN_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn transfer(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<TransferRequest>,
+) -> Result<Json<Payment>, ApplicationError> {
+    user.check_permission(Permission::WriteTransaction)?;
+    let wallet_id = payload
+        .wallet_id
+        .ok_or_else(|| DataError::Malformed("wallet_id is required.".to_string()))?;
+
+    let max_amount_msat = services.auth.payment_step_up_limit(&user).await?;
+    let payment = services.payment.transfer(wallet_id, payload, max_amount_msat).await?;
+
+    Ok(Json(payment))
+}
+
 /// Find a payment
 ///
 /// Returns the payment by its ID.
@@ -245,7 +332,10 @@ async fn delete_payments(
 
 #[cfg(test)]
 mod tests {
-    use crate::{application::composition::MockAppServicesBuilder, domains::payment::Payment};
+    use crate::{
+        application::{composition::MockAppServicesBuilder, errors::AuthorizationError},
+        domains::payment::Payment,
+    };
 
     use super::*;
 
@@ -262,6 +352,8 @@ mod tests {
             input: "bob@numeraire.tech".to_string(),
             amount_msat: Some(1_000),
             comment: None,
+            conf_target: None,
+            feerate_sat_vb: None,
         }
     }
 
@@ -273,6 +365,8 @@ mod tests {
             maximum_fee_msat: 100,
             estimated_total_msat: Some(1_010),
             maximum_total_msat: 1_100,
+            onchain_fees: vec![],
+            lnurl_pay: None,
         }
     }
 
@@ -300,9 +394,9 @@ mod tests {
             builder
                 .payment
                 .expect_estimate_fee()
-                .withf(move |_, _, _, selected_wallet_id| *selected_wallet_id == wallet_id)
+                .withf(move |_, _, _, selected_wallet_id, _| *selected_wallet_id == wallet_id)
                 .times(1)
-                .returning(|_, _, _, _| Ok(fee_estimate()));
+                .returning(|_, _, _, _, _| Ok(fee_estimate()));
 
             let result = estimate_payment_fee(
                 State(Arc::new(builder.build())),
@@ -316,6 +410,74 @@ mod tests {
         }
     }
 
+    mod preview_lnurl_pay {
+        use super::*;
+
+        #[tokio::test]
+        async fn requires_write_transaction_permission() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = preview_lnurl_pay(
+                State(Arc::new(services)),
+                user(vec![]),
+                Json(PreviewPaymentRequest {
+                    wallet_id: Some(Uuid::new_v4()),
+                    input: "bob@example.com".to_string(),
+                }),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn requires_a_wallet_id() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = preview_lnurl_pay(
+                State(Arc::new(services)),
+                user(vec![Permission::WriteTransaction]),
+                Json(PreviewPaymentRequest {
+                    wallet_id: None,
+                    input: "bob@example.com".to_string(),
+                }),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::Malformed(_)))));
+        }
+
+        #[tokio::test]
+        async fn previews_for_the_explicit_wallet() {
+            let wallet_id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .payment
+                .expect_preview_lnurl_pay()
+                .withf(move |_, selected_wallet_id| *selected_wallet_id == wallet_id)
+                .times(1)
+                .returning(|_, _| {
+                    Ok(LnUrlPayPreview {
+                        comment_allowed: 140,
+                        ..Default::default()
+                    })
+                });
+
+            let result = preview_lnurl_pay(
+                State(Arc::new(builder.build())),
+                user(vec![Permission::WriteTransaction]),
+                Json(PreviewPaymentRequest {
+                    wallet_id: Some(wallet_id),
+                    input: "bob@example.com".to_string(),
+                }),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(result.0.comment_allowed, 140);
+        }
+    }
+
     mod pay {
         use super::*;
 
@@ -346,12 +508,13 @@ mod tests {
                 let explicit = Uuid::new_v4();
 
                 let mut builder = MockAppServicesBuilder::new();
+                builder.auth.expect_payment_step_up_limit().returning(|_| Ok(None));
                 builder
                     .payment
                     .expect_pay()
-                    .withf(move |_, _, _, wallet_id| *wallet_id == explicit)
+                    .withf(move |_, _, _, wallet_id, _, _| *wallet_id == explicit)
                     .times(1)
-                    .returning(|_, _, _, _| Ok(Payment::default()));
+                    .returning(|_, _, _, _, _, _| Ok(Payment::default()));
 
                 let result = pay(
                     State(Arc::new(builder.build())),
@@ -365,6 +528,99 @@ mod tests {
         }
     }
 
+    mod transfer {
+        use super::*;
+
+        fn transfer_request(wallet_id: Option<Uuid>) -> TransferRequest {
+            TransferRequest {
+                wallet_id,
+                to_wallet_id: Some(Uuid::new_v4()),
+                to_account_id: None,
+                to_ln_address: None,
+                amount_msat: 1_000,
+                comment: None,
+            }
+        }
+
+        #[tokio::test]
+        async fn requires_write_transaction_permission() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = transfer(
+                State(Arc::new(services)),
+                user(vec![]),
+                Json(transfer_request(Some(Uuid::new_v4()))),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Authorization(_))));
+        }
+
+        #[tokio::test]
+        async fn requires_the_wallet_id() {
+            let services = MockAppServicesBuilder::new().build();
+
+            let result = transfer(
+                State(Arc::new(services)),
+                user(vec![Permission::WriteTransaction]),
+                Json(transfer_request(None)),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::Malformed(_)))));
+        }
+
+        #[tokio::test]
+        async fn transfers_from_the_explicit_wallet() {
+            let wallet_id = Uuid::new_v4();
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_payment_step_up_limit().returning(|_| Ok(None));
+            builder
+                .payment
+                .expect_transfer()
+                .withf(move |selected_wallet_id, _, _| *selected_wallet_id == wallet_id)
+                .times(1)
+                .returning(|_, _, _| Ok(Payment::default()));
+
+            let result = transfer(
+                State(Arc::new(builder.build())),
+                user(vec![Permission::WriteTransaction]),
+                Json(transfer_request(Some(wallet_id))),
+            )
+            .await;
+
+            assert!(result.is_ok());
+        }
+
+        #[tokio::test]
+        async fn passes_the_step_up_limit_of_the_caller() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .auth
+                .expect_payment_step_up_limit()
+                .times(1)
+                .returning(|_| Ok(Some(500)));
+            builder
+                .payment
+                .expect_transfer()
+                .withf(|_, _, max_amount_msat| *max_amount_msat == Some(500))
+                .times(1)
+                .returning(|_, _, _| Err(AuthorizationError::StepUpRequired.into()));
+
+            let result = transfer(
+                State(Arc::new(builder.build())),
+                user(vec![Permission::WriteTransaction]),
+                Json(transfer_request(Some(Uuid::new_v4()))),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::StepUpRequired))
+            ));
+        }
+    }
+
     mod get_payment {
         use super::*;
 
@@ -1,7 +1,7 @@
 use std::{borrow::Cow, str::FromStr};
 
 use ::lnurl::{
-    lightning_address::LightningAddress as LnurlLightningAddress, lnurl::LnUrl, AsyncClient as LnurlClient,
+    decode_ln_url_response_from_json, lightning_address::LightningAddress as LnurlLightningAddress, lnurl::LnUrl,
     LnUrlResponse,
 };
 use bip21::{
@@ -12,12 +12,18 @@ use bitcoin::{address::NetworkUnchecked, Address, Network as BitcoinNetwork};
 use lightning_invoice::{Bolt11Invoice, Bolt11InvoiceDescriptionRef, Currency as Bolt11Currency, ParseOrSemanticError};
 use reqwest::Url;
 use thiserror::Error;
+use tracing::debug;
 
 use crate::{
     application::composition::Currency,
-    domains::{bitcoin::BtcNetwork, lnurl::LnUrlPayRequestData},
+    domains::{
+        bitcoin::{decode_silent_payment_address, is_silent_payment_address, valid_payjoin_endpoint, BtcNetwork},
+        lnurl::{LnUrlPayRequestData, LnUrlPayerDataRequest},
+    },
 };
 
+use super::bip353::{payment_instructions, Bip353Name, DnsResolver};
+
 #[derive(Debug)]
 pub enum PaymentInput {
     BitcoinAddress(BitcoinAddressData),
@@ -31,6 +37,8 @@ pub struct BitcoinAddressData {
     pub amount_sat: Option<u64>,
     pub message: Option<String>,
     pub network: BtcNetwork,
+    /// BIP78 payjoin endpoint of the receiver, from the `pj` parameter of a BIP21 URI.
+    pub payjoin: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -54,6 +62,7 @@ pub struct LnPaymentTarget {
 #[derive(Clone, Debug, Default)]
 struct PaymentUriExtras {
     lightning: Option<Bolt11Invoice>,
+    pj: Option<String>,
 }
 
 #[derive(Clone, Debug, Error)]
@@ -62,6 +71,10 @@ enum PaymentUriExtrasError {
     MultipleLightning,
     #[error("invalid lightning invoice")]
     InvalidLightningInvoice,
+    #[error("multiple pj parameters")]
+    MultiplePayjoin,
+    #[error("invalid pj parameter")]
+    InvalidPayjoin,
 }
 
 impl From<ParseOrSemanticError> for PaymentUriExtrasError {
@@ -82,7 +95,7 @@ impl<'de> DeserializationState<'de> for PaymentUriExtras {
     type Value = PaymentUriExtras;
 
     fn is_param_known(&self, key: &str) -> bool {
-        key == "lightning"
+        matches!(key, "lightning" | "pj")
     }
 
     fn deserialize_temp(&mut self, key: &str, value: Param<'_>) -> Result<ParamKind, PaymentUriExtrasError> {
@@ -95,6 +108,12 @@ impl<'de> DeserializationState<'de> for PaymentUriExtras {
                 Ok(ParamKind::Known)
             }
             "lightning" => Err(PaymentUriExtrasError::MultipleLightning),
+            "pj" if self.pj.is_none() => {
+                let endpoint: Cow<'_, str> = value.try_into().map_err(|_| PaymentUriExtrasError::InvalidPayjoin)?;
+                self.pj = Some(endpoint.into_owned());
+                Ok(ParamKind::Known)
+            }
+            "pj" => Err(PaymentUriExtrasError::MultiplePayjoin),
             _ => Ok(ParamKind::Unknown),
         }
     }
@@ -104,7 +123,7 @@ impl<'de> DeserializationState<'de> for PaymentUriExtras {
     }
 }
 
-pub async fn parse_payment_input(input: &str) -> Result<PaymentInput, String> {
+pub async fn parse_payment_input(input: &str, dns_resolver: &dyn DnsResolver) -> Result<PaymentInput, String> {
     let input = input.trim();
     if input.is_empty() {
         return Err("Payment input cannot be empty".to_string());
@@ -114,10 +133,25 @@ pub async fn parse_payment_input(input: &str) -> Result<PaymentInput, String> {
         return Ok(PaymentInput::Bolt11(invoice));
     }
 
+    if is_silent_payment_address(input) {
+        return parse_silent_payment_input(input);
+    }
+
     if let Ok(bitcoin_payment) = parse_bitcoin_payment_input(input) {
         return Ok(bitcoin_payment);
     }
 
+    if let Some(name) = Bip353Name::parse(input) {
+        match resolve_bip353(&name, dns_resolver).await {
+            Ok(Some(payment_input)) => return Ok(payment_input),
+            Ok(None) if name.explicit => return Err(format!("No payment instructions published for {name}")),
+            Err(err) if name.explicit => return Err(err),
+            // Without the `₿` prefix, the name can still be a Lightning Address.
+            Ok(None) => {}
+            Err(err) => debug!(%name, %err, "BIP353 resolution failed, falling back to LNURL"),
+        }
+    }
+
     if looks_like_lnurl(input) {
         let data = resolve_lnurl_pay(input).await?;
         return Ok(PaymentInput::LnUrlPay(data));
@@ -181,13 +215,69 @@ fn parse_bitcoin_payment_input(input: &str) -> Result<PaymentInput, String> {
         })
         .transpose()?;
 
-    bitcoin_address_data_from_unchecked(uri.address, amount_sat, message).map(PaymentInput::BitcoinAddress)
+    bitcoin_address_data_from_unchecked(uri.address, amount_sat, message, uri.extras.pj)
+        .map(PaymentInput::BitcoinAddress)
+}
+
+/// Silent payment addresses (BIP352) are only accepted on their own, as BIP21 doesn't define them yet.
+fn parse_silent_payment_input(input: &str) -> Result<PaymentInput, String> {
+    let address =
+        decode_silent_payment_address(input).map_err(|err| format!("Invalid silent payment address: {err}"))?;
+
+    Ok(PaymentInput::BitcoinAddress(BitcoinAddressData {
+        address: input.to_ascii_lowercase(),
+        amount_sat: None,
+        message: None,
+        network: address.network,
+        payjoin: None,
+    }))
+}
+
+async fn resolve_bip353(name: &Bip353Name, dns_resolver: &dyn DnsResolver) -> Result<Option<PaymentInput>, String> {
+    let records = dns_resolver
+        .resolve_txt(&name.record_name())
+        .await
+        .map_err(|err| err.to_string())?;
+
+    payment_instructions(records)?
+        .map(|instructions| parse_bip353_instructions(&instructions))
+        .transpose()
+}
+
+/// Parses BIP353 payment instructions, a BIP21 URI whose address may be empty. A Lightning invoice is preferred,
+/// then a silent payment address, then the on-chain address. BOLT12 offers (`lno`) are not supported.
+fn parse_bip353_instructions(instructions: &str) -> Result<PaymentInput, String> {
+    let uri = Url::parse(instructions).map_err(|err| format!("Invalid BIP353 payment instructions: {err}"))?;
+    let param = |key: &str| {
+        uri.query_pairs()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.into_owned())
+    };
+
+    if let Some(invoice) = param("lightning") {
+        return parse_bolt11(&invoice).map(PaymentInput::Bolt11);
+    }
+
+    if let Some(address) = param("sp") {
+        return parse_silent_payment_input(&address);
+    }
+
+    if !uri.path().is_empty() {
+        return parse_bitcoin_payment_input(instructions);
+    }
+
+    if param("lno").is_some() {
+        return Err("BOLT12 offers are not supported".to_string());
+    }
+
+    Err("Unsupported BIP353 payment instructions".to_string())
 }
 
 fn bitcoin_address_data_from_unchecked(
     unchecked: Address<NetworkUnchecked>,
     amount_sat: Option<u64>,
     message: Option<String>,
+    payjoin: Option<String>,
 ) -> Result<BitcoinAddressData, String> {
     let candidates = [
         (BitcoinNetwork::Bitcoin, BtcNetwork::Bitcoin),
@@ -199,11 +289,15 @@ fn bitcoin_address_data_from_unchecked(
 
     for (bitcoin_network, network) in candidates {
         if let Ok(checked) = unchecked.clone().require_network(bitcoin_network) {
+            // An endpoint we would not send the original PSBT to is ignored and the payment made without payjoin.
+            let payjoin = payjoin.filter(|endpoint| valid_payjoin_endpoint(endpoint, network));
+
             return Ok(BitcoinAddressData {
                 address: checked.to_string(),
                 amount_sat,
                 message,
                 network,
+                payjoin,
             });
         }
     }
@@ -222,14 +316,25 @@ fn looks_like_lnurl(input: &str) -> bool {
 
 async fn resolve_lnurl_pay(input: &str) -> Result<LnUrlPayRequestData, String> {
     let (url, ln_address) = lnurl_endpoint(input)?;
-    let client = LnurlClient::from_client(reqwest::Client::new());
-    let response = client.make_request(&url).await.map_err(|err| err.to_string())?;
+    let json: serde_json::Value = reqwest::get(&url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?
+        .json()
+        .await
+        .map_err(|err| err.to_string())?;
 
-    let LnUrlResponse::LnUrlPayResponse(pay) = response else {
+    // `lnurl-rs` does not model LUD-18, so `payerData` is read from the raw response.
+    let payer_data = json
+        .get("payerData")
+        .and_then(|payer_data| serde_json::from_value(payer_data.clone()).ok());
+
+    let LnUrlResponse::LnUrlPayResponse(pay) = decode_ln_url_response_from_json(json).map_err(|err| err.to_string())?
+    else {
         return Err("Unsupported LNURL response type".to_string());
     };
 
-    lnurl_pay_request_data_from_response(pay, ln_address)
+    lnurl_pay_request_data_from_response(pay, ln_address, payer_data)
 }
 
 fn lnurl_endpoint(input: &str) -> Result<(String, Option<String>), String> {
@@ -260,6 +365,7 @@ fn validate_lnurl_endpoint(endpoint: &str) -> Result<(), String> {
 fn lnurl_pay_request_data_from_response(
     pay: ::lnurl::pay::PayResponse,
     ln_address: Option<String>,
+    payer_data: Option<LnUrlPayerDataRequest>,
 ) -> Result<LnUrlPayRequestData, String> {
     let comment_allowed = match pay.comment_allowed {
         Some(comment_allowed) => {
@@ -275,6 +381,7 @@ fn lnurl_pay_request_data_from_response(
         metadata: pay.metadata,
         comment_allowed,
         ln_address,
+        payer_data,
     })
 }
 
@@ -297,6 +404,11 @@ fn currency_from_bolt11(currency: Bolt11Currency) -> Currency {
 
 #[cfg(test)]
 mod tests {
+    use crate::domains::{
+        bitcoin::SilentPaymentKeys,
+        payment::bip353::{DnsResolverError, MockDnsResolver},
+    };
+
     use super::*;
 
     const MAINNET_ADDRESS: &str = "1BoatSLRHtKNngkdXEeobR76b53LETtpyT";
@@ -346,6 +458,46 @@ mod tests {
         assert_eq!(data.message, Some("hello there".to_string()));
     }
 
+    #[test]
+    fn parse_bitcoin_uri_extracts_the_payjoin_endpoint() {
+        let input = format!("bitcoin:{MAINNET_ADDRESS}?amount=0.001&pj=https://example.com/v1/payjoin%3Fid%3D1");
+
+        let PaymentInput::BitcoinAddress(data) = parse_bitcoin_payment_input(&input).unwrap() else {
+            panic!("expected bitcoin address payment input");
+        };
+
+        assert_eq!(data.amount_sat, Some(100_000));
+        assert_eq!(data.payjoin, Some("https://example.com/v1/payjoin?id=1".to_string()));
+    }
+
+    #[test]
+    fn parse_bitcoin_uri_ignores_an_insecure_payjoin_endpoint_on_mainnet() {
+        let input = format!("bitcoin:{MAINNET_ADDRESS}?pj=http://example.com/v1/payjoin");
+
+        let PaymentInput::BitcoinAddress(data) = parse_bitcoin_payment_input(&input).unwrap() else {
+            panic!("expected bitcoin address payment input");
+        };
+
+        assert_eq!(data.payjoin, None);
+    }
+
+    #[tokio::test]
+    async fn parse_payment_input_detects_a_silent_payment_address() {
+        let keys = SilentPaymentKeys::from_hex(&"11".repeat(32), &"22".repeat(32)).unwrap();
+        let address = keys.address(1, BtcNetwork::Bitcoin);
+
+        let PaymentInput::BitcoinAddress(data) = parse_payment_input(&address.to_uppercase(), &MockDnsResolver::new())
+            .await
+            .unwrap()
+        else {
+            panic!("expected a bitcoin address input");
+        };
+
+        assert_eq!(data.address, address);
+        assert_eq!(data.network, BtcNetwork::Bitcoin);
+        assert_eq!(data.payjoin, None);
+    }
+
     #[test]
     fn currency_from_bolt11_maps_every_network() {
         assert_eq!(currency_from_bolt11(Bolt11Currency::Bitcoin), Currency::Bitcoin);
@@ -398,13 +550,16 @@ mod tests {
 
     #[tokio::test]
     async fn parse_payment_input_rejects_empty_input() {
-        let err = parse_payment_input("   ").await.unwrap_err();
+        let err = parse_payment_input("   ", &MockDnsResolver::new()).await.unwrap_err();
         assert!(err.contains("cannot be empty"));
     }
 
     #[tokio::test]
     async fn parse_payment_input_detects_a_bitcoin_address() {
-        let PaymentInput::BitcoinAddress(data) = parse_payment_input(MAINNET_ADDRESS).await.unwrap() else {
+        let PaymentInput::BitcoinAddress(data) = parse_payment_input(MAINNET_ADDRESS, &MockDnsResolver::new())
+            .await
+            .unwrap()
+        else {
             panic!("expected bitcoin address payment input");
         };
         assert_eq!(data.network, BtcNetwork::Bitcoin);
@@ -414,7 +569,101 @@ mod tests {
     async fn parse_payment_input_rejects_unsupported_input() {
         // Spaces ensure this is neither a bolt11, a bitcoin address, nor a
         // lightning address, so no network resolution is attempted.
-        let err = parse_payment_input("*** not a payment ***").await.unwrap_err();
+        let err = parse_payment_input("*** not a payment ***", &MockDnsResolver::new())
+            .await
+            .unwrap_err();
         assert!(err.contains("Unsupported payment input"));
     }
+
+    mod bip353 {
+        use super::*;
+
+        fn resolver(records: Vec<String>) -> MockDnsResolver {
+            let mut resolver = MockDnsResolver::new();
+            resolver
+                .expect_resolve_txt()
+                .withf(|name| name == "alice.user._bitcoin-payment.example.com.")
+                .times(1)
+                .returning(move |_| Ok(records.clone()));
+            resolver
+        }
+
+        fn silent_payment_address() -> String {
+            SilentPaymentKeys::from_hex(&"11".repeat(32), &"22".repeat(32))
+                .unwrap()
+                .address(1, BtcNetwork::Bitcoin)
+        }
+
+        #[tokio::test]
+        async fn pays_the_silent_payment_address_of_the_instructions() {
+            let address = silent_payment_address();
+            let record = format!("bitcoin:?sp={address}");
+
+            let PaymentInput::BitcoinAddress(data) = parse_payment_input("₿alice@example.com", &resolver(vec![record]))
+                .await
+                .unwrap()
+            else {
+                panic!("expected a bitcoin address input");
+            };
+
+            assert_eq!(data.address, address);
+        }
+
+        #[tokio::test]
+        async fn pays_the_on_chain_address_of_the_instructions() {
+            let record = format!("bitcoin:{MAINNET_ADDRESS}?amount=0.001");
+
+            let PaymentInput::BitcoinAddress(data) = parse_payment_input("₿alice@example.com", &resolver(vec![record]))
+                .await
+                .unwrap()
+            else {
+                panic!("expected a bitcoin address input");
+            };
+
+            assert_eq!(data.address, MAINNET_ADDRESS);
+            assert_eq!(data.amount_sat, Some(100_000));
+        }
+
+        #[tokio::test]
+        async fn rejects_offers_only_instructions() {
+            let err = parse_payment_input(
+                "₿alice@example.com",
+                &resolver(vec!["bitcoin:?lno=lno1example".to_string()]),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(err.contains("BOLT12"));
+        }
+
+        #[tokio::test]
+        async fn fails_for_a_prefixed_name_without_instructions() {
+            let err = parse_payment_input("₿alice@example.com", &resolver(vec!["v=spf1 -all".to_string()]))
+                .await
+                .unwrap_err();
+
+            assert!(err.contains("No payment instructions"));
+        }
+
+        #[tokio::test]
+        async fn fails_for_a_prefixed_name_on_an_insecure_answer() {
+            let mut resolver = MockDnsResolver::new();
+            resolver
+                .expect_resolve_txt()
+                .returning(|name| Err(DnsResolverError::Insecure(name.to_string())));
+
+            let err = parse_payment_input("₿alice@example.com", &resolver).await.unwrap_err();
+
+            assert!(err.contains("DNSSEC"));
+        }
+
+        #[test]
+        fn prefers_the_lightning_invoice_of_the_instructions() {
+            let err =
+                parse_bip353_instructions(&format!("bitcoin:{MAINNET_ADDRESS}?lightning=lnbc1invalid")).unwrap_err();
+
+            // The invoice is picked over the address, and rejected as it doesn't parse.
+            assert!(!err.contains("Unsupported"));
+        }
+    }
 }
@@ -1,35 +1,53 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bitcoin::{consensus::encode::serialize_hex, secp256k1::SecretKey};
 use chrono::Utc;
+use swissknife_types::OrderDirection;
 use tracing::{debug, info, trace, warn};
 use uuid::Uuid;
 
 use crate::{
     application::{
         composition::{AppStore, Ledger},
-        errors::{ApplicationError, DataError, LightningError},
+        errors::{ApplicationError, AuthorizationError, BitcoinError, DataError, LightningError, PayjoinError},
     },
     domains::{
         asset::{Protocol, NATIVE_ASSET_REF},
-        bitcoin::{BitcoinWallet, BtcNetwork},
+        audit::{record_created, record_deleted},
+        bitcoin::{
+            address_script, bitcoin_network, check_payjoin_proposal, check_signed_payjoin, decode_psbt,
+            decode_silent_payment_address, encode_psbt, is_silent_payment_address, prepare_original_psbt,
+            request_payjoin_proposal, script_address, signed_vsize, silent_payment_fee, BitcoinWallet, BtcFeeQuote,
+            BtcFeeSelection, BtcNetwork, BtcOutput, BtcOutputStatus, BtcPreparedTransaction, SilentPaymentOutput,
+            SilentPaymentTransaction, SilentPayments, FEE_ESTIMATE_CONF_TARGETS,
+        },
         event::{EventUseCases, LnPayFailureEvent, LnPaySuccessEvent},
         invoice::{Invoice, InvoiceStatus},
-        lnurl::{process_success_action, validate_lnurl_pay, LnUrlPayRequestData},
+        ln_address::{find_receiving_ln_address, LnAddress, LnAddressFilter},
+        lnurl::{
+            lnurl_pay_preview, outgoing_payer_data, process_success_action, validate_lnurl_pay,
+            validate_lnurl_pay_terms, LnUrlPayPreview, LnUrlPayRequestData, LnUrlPayerData,
+        },
     },
     infra::lightning::LnClient,
 };
 
 use super::{
+    bip353::{strip_bip353_prefix, DnsResolver},
     payment_input::{
         parse_bolt11, parse_payment_input, BitcoinAddressData, LnPaymentTarget, ParsedBolt11Invoice, PaymentInput,
     },
     BtcPayment, InternalPayment, LnPayment, Payment, PaymentFeeEstimate, PaymentFilter, PaymentStatus,
-    PaymentsUseCases,
+    PaymentsUseCases, TransferRequest,
 };
 
 const DEFAULT_INTERNAL_INVOICE_DESCRIPTION: &str = "Numeraire Invoice";
 const DEFAULT_INTERNAL_PAYMENT_DESCRIPTION: &str = "Payment to Numeraire";
+const DEFAULT_TRANSFER_DESCRIPTION: &str = "Numeraire Transfer";
+
+/// Confirmation target of the feerate silent payments are sent at when none is selected.
+const SILENT_PAYMENT_CONF_TARGET: u32 = 6;
 
 pub struct PaymentService {
     domain: String,
@@ -37,6 +55,8 @@ pub struct PaymentService {
     ln_client: Arc<dyn LnClient>,
     bitcoin_wallet: Arc<dyn BitcoinWallet>,
     events: Arc<dyn EventUseCases>,
+    silent_payments: Option<Arc<SilentPayments>>,
+    dns_resolver: Arc<dyn DnsResolver>,
 }
 
 impl PaymentService {
@@ -46,6 +66,8 @@ impl PaymentService {
         bitcoin_wallet: Arc<dyn BitcoinWallet>,
         domain: String,
         events: Arc<dyn EventUseCases>,
+        silent_payments: Option<Arc<SilentPayments>>,
+        dns_resolver: Arc<dyn DnsResolver>,
     ) -> Self {
         PaymentService {
             store,
@@ -53,6 +75,8 @@ impl PaymentService {
             bitcoin_wallet,
             domain,
             events,
+            silent_payments,
+            dns_resolver,
         }
     }
 }
@@ -67,6 +91,47 @@ impl PaymentService {
         Ok(amount)
     }
 
+    /// Rejects payments above the step-up limit of the caller. Payments of unknown amount count as above it.
+    fn check_step_up_limit(amount_msat: Option<u64>, max_amount_msat: Option<u64>) -> Result<(), ApplicationError> {
+        if let Some(max_amount_msat) = max_amount_msat {
+            if amount_msat.is_none_or(|amount| amount > max_amount_msat) {
+                return Err(AuthorizationError::StepUpRequired.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Negotiates a BIP78 payjoin with the receiver for the prepared transaction and broadcasts the resulting
+    /// transaction. No additional fee is offered and output substitution is disabled, so the amount and fee
+    /// paid by the wallet are those of the prepared transaction. Returns the txid of the payjoin transaction.
+    async fn send_payjoin(
+        &self,
+        prepared_tx: &BtcPreparedTransaction,
+        endpoint: &str,
+        address: &str,
+        network: BtcNetwork,
+    ) -> Result<String, ApplicationError> {
+        let payee_script = address_script(address, bitcoin_network(network))
+            .ok_or_else(|| PayjoinError::Request("Invalid payee address.".to_string()))?;
+
+        let original = self.bitcoin_wallet.sign_psbt(&prepared_tx.psbt).await?;
+        let original = decode_psbt(&original).map_err(BitcoinError::ParsePsbt)?;
+        let original = prepare_original_psbt(original);
+
+        let proposal = request_payjoin_proposal(endpoint, &encode_psbt(&original)).await?;
+        let proposal = decode_psbt(&proposal).map_err(PayjoinError::ProposalRejected)?;
+        let proposal = check_payjoin_proposal(&original, proposal, &payee_script)?;
+
+        let signed = self.bitcoin_wallet.sign_psbt(&encode_psbt(&proposal)).await?;
+        let signed_psbt = decode_psbt(&signed).map_err(BitcoinError::ParsePsbt)?;
+        check_signed_payjoin(&proposal, &signed_psbt, &original)?;
+
+        let txid = self.bitcoin_wallet.broadcast_psbt(&signed).await?;
+
+        Ok(txid)
+    }
+
     fn reserve_amount_msat(amount_msat: u64, fee_msat: u64) -> Result<u64, ApplicationError> {
         amount_msat
             .checked_add(fee_msat)
@@ -91,9 +156,271 @@ impl PaymentService {
             maximum_fee_msat,
             estimated_total_msat,
             maximum_total_msat,
+            onchain_fees: Vec::new(),
+            lnurl_pay: None,
+        })
+    }
+
+    async fn resolve_feerate(&self, fee: BtcFeeSelection) -> Result<Option<u32>, ApplicationError> {
+        match fee {
+            BtcFeeSelection::NodeDefault => Ok(None),
+            BtcFeeSelection::ConfTarget(conf_target) => {
+                Ok(Some(self.bitcoin_wallet.estimate_feerate(conf_target).await?))
+            }
+            BtcFeeSelection::Feerate(feerate_sat_vb) => Ok(Some(feerate_sat_vb)),
+        }
+    }
+
+    fn silent_payments(&self) -> Result<&SilentPayments, ApplicationError> {
+        self.silent_payments.as_deref().ok_or_else(|| {
+            DataError::Validation("Sending to silent payment addresses is not enabled.".to_string()).into()
         })
     }
 
+    async fn silent_payment_feerate(&self, fee: BtcFeeSelection) -> Result<u32, ApplicationError> {
+        match self.resolve_feerate(fee).await? {
+            Some(feerate_sat_vb) => Ok(feerate_sat_vb),
+            None => Ok(self.bitcoin_wallet.estimate_feerate(SILENT_PAYMENT_CONF_TARGET).await?),
+        }
+    }
+
+    /// Prepares the node wallet transaction funding a payment of `amount_sat` to a silent payment address. It pays
+    /// a single use output of our silent payment keys, which then pays the recipient, since the payment is derived
+    /// from the secrets of the inputs and those of the node wallet cannot be read.
+    async fn prepare_silent_payment_funding(
+        &self,
+        silent_payments: &SilentPayments,
+        amount_sat: u64,
+        feerate_sat_vb: u32,
+    ) -> Result<(BtcPreparedTransaction, SilentPaymentOutput), ApplicationError> {
+        let tweak = SecretKey::from_slice(&rand::random::<[u8; 32]>())
+            .map_err(|e| BitcoinError::PrepareTransaction(e.to_string()))?
+            .display_secret()
+            .to_string();
+        let script = silent_payments
+            .keys
+            .output_script(&tweak)
+            .map_err(BitcoinError::PrepareTransaction)?;
+        let address = script_address(&script, bitcoin_network(self.bitcoin_wallet.network()))
+            .ok_or_else(|| BitcoinError::Address("Invalid silent payment funding script".to_string()))?;
+        let funding_sat = amount_sat
+            .checked_add(silent_payment_fee(1, feerate_sat_vb))
+            .ok_or_else(|| DataError::Validation("Payment amount overflows".to_string()))?;
+
+        let prepared = self
+            .bitcoin_wallet
+            .prepare_transaction(address.clone(), funding_sat, Some(feerate_sat_vb))
+            .await?;
+        let funding = decode_psbt(&prepared.psbt)
+            .map_err(BitcoinError::ParsePsbt)
+            .and_then(|psbt| {
+                let output_index = psbt
+                    .unsigned_tx
+                    .output
+                    .iter()
+                    .position(|output| output.script_pubkey == script)
+                    .ok_or_else(|| BitcoinError::PrepareTransaction("Funding output not found".to_string()))?;
+                let txid = psbt.unsigned_tx.compute_txid().to_string();
+
+                Ok(SilentPaymentOutput {
+                    output: BtcOutput {
+                        outpoint: format!("{txid}:{output_index}"),
+                        txid,
+                        output_index: output_index as u32,
+                        address,
+                        amount_sat: funding_sat,
+                        status: BtcOutputStatus::Confirmed,
+                        ..Default::default()
+                    },
+                    tweak,
+                })
+            });
+
+        match funding {
+            Ok(funding) => Ok((prepared, funding)),
+            Err(error) => {
+                if let Err(err) = self.bitcoin_wallet.release_prepared_transaction(&prepared).await {
+                    warn!(txid = prepared.txid, %err, "Failed to release the silent payment funding transaction");
+                }
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Quotes the fee of a payment to a silent payment address: the fee of the funding transaction plus that of
+    /// the payment spending it.
+    async fn silent_payment_fee(
+        &self,
+        address: &str,
+        amount_sat: u64,
+        fee: BtcFeeSelection,
+    ) -> Result<u64, ApplicationError> {
+        let silent_payments = self.silent_payments()?;
+        decode_silent_payment_address(address).map_err(DataError::Validation)?;
+        let feerate_sat_vb = self.silent_payment_feerate(fee).await?;
+
+        let (prepared, _) = self
+            .prepare_silent_payment_funding(silent_payments, amount_sat, feerate_sat_vb)
+            .await?;
+        self.bitcoin_wallet.release_prepared_transaction(&prepared).await?;
+
+        Ok(prepared.fee_sat + silent_payment_fee(1, feerate_sat_vb))
+    }
+
+    /// Pays a silent payment address from the node wallet, through a single use output of our silent payment keys.
+    /// The funding transaction is broadcast by the node and the payment spending it through the full node.
+    async fn send_silent_payment(
+        &self,
+        address: String,
+        amount_sat: u64,
+        description: Option<String>,
+        wallet_id: Uuid,
+        fee: BtcFeeSelection,
+    ) -> Result<Payment, ApplicationError> {
+        let silent_payments = self.silent_payments()?;
+        let recipient = decode_silent_payment_address(&address).map_err(DataError::Validation)?;
+        let feerate_sat_vb = self.silent_payment_feerate(fee).await?;
+
+        let (prepared, funding) = self
+            .prepare_silent_payment_funding(silent_payments, amount_sat, feerate_sat_vb)
+            .await?;
+        let transaction = match silent_payments
+            .keys
+            .build_transaction(&funding, &recipient, feerate_sat_vb)
+        {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                if let Err(err) = self.bitcoin_wallet.release_prepared_transaction(&prepared).await {
+                    warn!(txid = prepared.txid, %err, "Failed to release the silent payment funding transaction");
+                }
+                return Err(DataError::Validation(error).into());
+            }
+        };
+
+        let amount_msat = amount_sat.saturating_mul(1000);
+        let fee_msat = (prepared.fee_sat + transaction.fee_sat).saturating_mul(1000);
+        let txid = transaction.transaction.compute_txid().to_string();
+
+        let pending_payment = match Self::reserve_amount_msat(amount_msat, fee_msat) {
+            Ok(reserve_amount) => {
+                self.store
+                    .payment_uow
+                    .reserve(
+                        Payment {
+                            wallet_id,
+                            amount_msat,
+                            fee_msat: Some(fee_msat),
+                            status: PaymentStatus::Pending,
+                            ledger: Ledger::Onchain,
+                            description,
+                            bitcoin: Some(BtcPayment {
+                                address,
+                                txid: txid.clone(),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                        reserve_amount,
+                    )
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+        let pending_payment = match pending_payment {
+            Ok(payment) => payment,
+            Err(error) => {
+                if let Err(err) = self.bitcoin_wallet.release_prepared_transaction(&prepared).await {
+                    warn!(txid = prepared.txid, %err, "Failed to release the silent payment funding transaction");
+                }
+                return Err(error);
+            }
+        };
+
+        if let Err(error) = self
+            .broadcast_silent_payment(silent_payments, &prepared, &funding, wallet_id, &transaction)
+            .await
+        {
+            let mut failed_payment = pending_payment.clone();
+            failed_payment.status = PaymentStatus::Failed;
+            failed_payment.error = Some(error.to_string());
+            self.store.payment_uow.fail(failed_payment).await?;
+
+            return Err(error);
+        }
+
+        info!(%txid, %wallet_id, "Silent payment broadcast");
+        Ok(pending_payment)
+    }
+
+    /// Broadcasts the funding transaction, then the payment spending it. The funding output is recorded first, as
+    /// an output of our keys, so if the payment cannot be broadcast it is swept back to the node wallet.
+    async fn broadcast_silent_payment(
+        &self,
+        silent_payments: &SilentPayments,
+        prepared: &BtcPreparedTransaction,
+        funding: &SilentPaymentOutput,
+        wallet_id: Uuid,
+        transaction: &SilentPaymentTransaction,
+    ) -> Result<(), ApplicationError> {
+        self.store
+            .btc_address
+            .insert_silent_payment_output(wallet_id, &funding.output.address, &funding.tweak)
+            .await?;
+        self.store.btc_output.upsert(funding.output.clone()).await?;
+
+        if let Err(error) = self.bitcoin_wallet.sign_send_transaction(prepared).await {
+            if let Err(err) = self.bitcoin_wallet.release_prepared_transaction(prepared).await {
+                warn!(txid = prepared.txid, %err, "Failed to release the silent payment funding transaction");
+            }
+            self.store
+                .btc_output
+                .upsert(BtcOutput {
+                    status: BtcOutputStatus::Failed,
+                    ..funding.output.clone()
+                })
+                .await?;
+            return Err(error.into());
+        }
+
+        silent_payments
+            .chain
+            .broadcast_transaction(&serialize_hex(&transaction.transaction))
+            .await?;
+        self.store
+            .btc_output
+            .mark_spent(vec![funding.output.outpoint.clone()])
+            .await?;
+
+        Ok(())
+    }
+
+    /// Quotes the on-chain fee of a withdrawal at each standard confirmation target, pricing the signed size of
+    /// the already prepared transaction at the node's feerate for that target. No further UTXOs are reserved.
+    async fn onchain_fee_quotes(
+        &self,
+        prepared: &BtcPreparedTransaction,
+    ) -> Result<Vec<BtcFeeQuote>, ApplicationError> {
+        let psbt = decode_psbt(&prepared.psbt).map_err(BitcoinError::ParsePsbt)?;
+        let vsize = signed_vsize(&psbt).map_err(BitcoinError::EstimateFee)?;
+
+        let mut quotes = Vec::with_capacity(FEE_ESTIMATE_CONF_TARGETS.len());
+        for conf_target in FEE_ESTIMATE_CONF_TARGETS {
+            let feerate_sat_vb = self.bitcoin_wallet.estimate_feerate(conf_target).await?;
+            let fee_msat = vsize
+                .checked_mul(u64::from(feerate_sat_vb))
+                .and_then(|fee_sat| fee_sat.checked_mul(1000))
+                .ok_or_else(|| DataError::Validation("On-chain fee overflows".to_string()))?;
+
+            quotes.push(BtcFeeQuote {
+                conf_target,
+                feerate_sat_vb,
+                fee_msat,
+            });
+        }
+
+        Ok(quotes)
+    }
+
     fn ln_payment_target(
         invoice: &ParsedBolt11Invoice,
         amount_msat: Option<u64>,
@@ -131,6 +458,7 @@ impl PaymentService {
     async fn send_internal(
         &self,
         input: String,
+        domain_id: Option<Uuid>,
         amount_msat: Option<u64>,
         comment: Option<String>,
         wallet_id: Uuid,
@@ -138,9 +466,12 @@ impl PaymentService {
         let amount = Self::validate_amount(amount_msat)?;
         debug!(%wallet_id, %amount, ledger="Internal", "Sending internal payment");
 
-        let (username, _) = input.split_once('@').expect("should not fail or malformed LN address");
+        let (username, _) = strip_bip353_prefix(&input)
+            .split_once('@')
+            .expect("should not fail or malformed LN address");
 
-        let address_opt = self.store.ln_address.find_by_username(username).await?;
+        let alias = username.to_lowercase();
+        let address_opt = find_receiving_ln_address(&self.store, domain_id, &alias).await?;
         match address_opt {
             Some(retrieved_address) => {
                 if !retrieved_address.active {
@@ -155,6 +486,7 @@ impl PaymentService {
                 let invoice = Invoice {
                     wallet_id: retrieved_address.wallet_id,
                     ln_address_id: Some(retrieved_address.id),
+                    ln_address_alias: Some(alias),
                     ledger: Ledger::Internal,
                     description: comment
                         .clone()
@@ -180,6 +512,8 @@ impl PaymentService {
                         ln_address: Some(input),
                         btc_address: None,
                         payment_hash: None,
+                        split_invoice_id: None,
+                        transfer_id: None,
                     }),
                     ..Default::default()
                 };
@@ -198,6 +532,7 @@ impl PaymentService {
         amount_sat: Option<u64>,
         comment: Option<String>,
         wallet_id: Uuid,
+        fee: BtcFeeSelection,
     ) -> Result<Payment, ApplicationError> {
         let specified_amount = data.amount_sat.or(amount_sat);
         if specified_amount == Some(0) {
@@ -240,6 +575,8 @@ impl PaymentService {
                         ln_address: None,
                         btc_address: Some(data.address),
                         payment_hash: None,
+                        split_invoice_id: None,
+                        transfer_id: None,
                     }),
                     fee_msat: Some(0),
                     payment_time: Some(timestamp),
@@ -251,9 +588,16 @@ impl PaymentService {
                 return Ok(internal_payment);
             }
 
+            if is_silent_payment_address(&data.address) {
+                return self
+                    .send_silent_payment(data.address, amount, description, wallet_id, fee)
+                    .await;
+            }
+
+            let feerate_sat_vb = self.resolve_feerate(fee).await?;
             let prepared_tx = self
                 .bitcoin_wallet
-                .prepare_transaction(data.address.clone(), amount, None)
+                .prepare_transaction(data.address.clone(), amount, feerate_sat_vb)
                 .await?;
 
             let fee_msat = prepared_tx.fee_sat.saturating_mul(1000);
@@ -271,7 +615,7 @@ impl PaymentService {
                         ledger: Ledger::Onchain,
                         description,
                         bitcoin: Some(BtcPayment {
-                            address: data.address,
+                            address: data.address.clone(),
                             txid: prepared_tx.txid.clone(),
                             ..Default::default()
                         }),
@@ -291,7 +635,26 @@ impl PaymentService {
                 }
             };
 
-            match self.bitcoin_wallet.sign_send_transaction(&prepared_tx).await {
+            let payjoin_txid = match &data.payjoin {
+                Some(endpoint) => match self
+                    .send_payjoin(&prepared_tx, endpoint, &data.address, data.network)
+                    .await
+                {
+                    Ok(txid) => Some(txid),
+                    Err(err) => {
+                        warn!(%err, endpoint, "Payjoin failed. Broadcasting the original transaction instead.");
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            let sent = match payjoin_txid {
+                Some(txid) => Ok(Some(txid)),
+                None => self.bitcoin_wallet.sign_send_transaction(&prepared_tx).await,
+            };
+
+            match sent {
                 Ok(resolved_txid) => {
                     // If sign_send returned a resolved txid, update the payment
                     // record so withdrawal events can be matched by this identifier.
@@ -375,6 +738,8 @@ impl PaymentService {
                                 ln_address: None,
                                 btc_address: None,
                                 payment_hash: Some(payment_hash.clone()),
+                                split_invoice_id: None,
+                                transfer_id: None,
                             }),
                             ..Default::default()
                         };
@@ -450,6 +815,49 @@ impl PaymentService {
         }
     }
 
+    /// Payer data (LUD-18) attached to outgoing LNURL payments when the service requires it, identifying the wallet
+    /// by its oldest active Lightning Address.
+    async fn lnurl_payer_data(
+        &self,
+        wallet_id: Uuid,
+        data: &LnUrlPayRequestData,
+    ) -> Result<Option<LnUrlPayerData>, ApplicationError> {
+        let Some(request) = &data.payer_data else {
+            return Ok(None);
+        };
+
+        let ln_address = self
+            .store
+            .ln_address
+            .find_many(LnAddressFilter {
+                wallet_id: Some(wallet_id),
+                active: Some(true),
+                limit: Some(1),
+                order_direction: OrderDirection::Asc,
+                ..Default::default()
+            })
+            .await?
+            .into_iter()
+            .next();
+        let identifier = match &ln_address {
+            Some(LnAddress {
+                username,
+                domain_id: Some(domain_id),
+                ..
+            }) => self
+                .store
+                .ln_address_domain
+                .find(*domain_id)
+                .await?
+                .map(|domain| format!("{}@{}", username, domain.name)),
+            Some(ln_address) => Some(format!("{}@{}", ln_address.username, self.domain)),
+            None => None,
+        };
+
+        outgoing_payer_data(request, ln_address.map(|ln_address| ln_address.username), identifier)
+            .map_err(|err| DataError::Validation(err).into())
+    }
+
     async fn send_lnurl_pay(
         &self,
         data: LnUrlPayRequestData,
@@ -460,7 +868,9 @@ impl PaymentService {
         let amount = Self::validate_amount(amount_msat)?;
         debug!(%wallet_id, %amount, ledger="Lightning", "Sending LNURL payment");
 
-        let cb = validate_lnurl_pay(amount, &comment, &data)
+        validate_lnurl_pay_terms(amount, comment.as_deref(), &data).map_err(DataError::Validation)?;
+        let payer_data = self.lnurl_payer_data(wallet_id, &data).await?;
+        let cb = validate_lnurl_pay(amount, &comment, payer_data.as_ref(), &data)
             .await
             .map_err(|e| DataError::Validation(e.to_string()))?;
         let invoice = parse_bolt11(&cb.pr).map_err(DataError::Validation)?;
@@ -562,11 +972,27 @@ impl PaymentService {
         }
     }
 
-    fn is_internal_payment(&self, input: &str) -> bool {
-        if let Some((_, input_domain)) = input.split_once('@') {
-            return input_domain == self.domain;
+    /// Resolves a Lightning Address on one of our domains to its domain ID and username.
+    /// The domain ID is `None` for the configured domain.
+    async fn internal_recipient<'a>(
+        &self,
+        input: &'a str,
+    ) -> Result<Option<(Option<Uuid>, &'a str)>, ApplicationError> {
+        // A BIP353 name of one of our addresses is paid internally like the Lightning Address.
+        let Some((username, input_domain)) = strip_bip353_prefix(input).split_once('@') else {
+            return Ok(None);
+        };
+
+        if input_domain.eq_ignore_ascii_case(&self.domain) {
+            return Ok(Some((None, username)));
         }
-        false
+
+        let domain = self
+            .store
+            .ln_address_domain
+            .find_by_name(&input_domain.to_lowercase())
+            .await?;
+        Ok(domain.map(|domain| (Some(domain.id), username)))
     }
 
     fn bolt11_network(&self, invoice: &ParsedBolt11Invoice) -> BtcNetwork {
@@ -600,6 +1026,21 @@ impl PaymentService {
         ))
         .into())
     }
+
+    /// Resolves the active Lightning Address on this instance a transfer is sent to, with the alias paid.
+    async fn transfer_ln_address(&self, input: &str) -> Result<(LnAddress, String), ApplicationError> {
+        let Some((domain_id, username)) = self.internal_recipient(input).await? else {
+            return Err(DataError::NotFound("Recipient not found.".to_string()).into());
+        };
+
+        let alias = username.to_lowercase();
+        let ln_address = find_receiving_ln_address(&self.store, domain_id, &alias)
+            .await?
+            .filter(|ln_address| ln_address.active)
+            .ok_or_else(|| DataError::NotFound("Recipient not found.".to_string()))?;
+
+        Ok((ln_address, alias))
+    }
 }
 
 #[async_trait]
@@ -610,20 +1051,15 @@ impl PaymentsUseCases for PaymentService {
         amount_msat: Option<u64>,
         comment: Option<String>,
         wallet_id: Uuid,
+        fee: BtcFeeSelection,
     ) -> Result<PaymentFeeEstimate, ApplicationError> {
-        debug!(%input, %wallet_id, "Received fee estimate request");
+        debug!(%input, %wallet_id, ?fee, "Received fee estimate request");
 
-        if self.is_internal_payment(&input) {
+        if let Some((domain_id, username)) = self.internal_recipient(&input).await? {
             let amount = Self::validate_amount(amount_msat)?;
             self.ensure_wallet_network(wallet_id, self.bitcoin_wallet.network())
                 .await?;
-            let (username, _) = input
-                .split_once('@')
-                .ok_or_else(|| DataError::Validation("Invalid internal Lightning address".to_string()))?;
-            let address = self
-                .store
-                .ln_address
-                .find_by_username(username)
+            let address = find_receiving_ln_address(&self.store, domain_id, &username.to_lowercase())
                 .await?
                 .filter(|address| address.active)
                 .ok_or_else(|| DataError::NotFound("Recipient not found.".to_string()))?;
@@ -634,7 +1070,9 @@ impl PaymentsUseCases for PaymentService {
             return Self::fee_estimate(Ledger::Internal, amount, Some(0), 0);
         }
 
-        let input_type = parse_payment_input(&input).await.map_err(DataError::Validation)?;
+        let input_type = parse_payment_input(&input, self.dns_resolver.as_ref())
+            .await
+            .map_err(DataError::Validation)?;
         let expected_network = match &input_type {
             PaymentInput::BitcoinAddress(address) => address.network,
             PaymentInput::Bolt11(invoice) => self.bolt11_network(invoice),
@@ -664,9 +1102,18 @@ impl PaymentsUseCases for PaymentService {
                     return Self::fee_estimate(Ledger::Internal, amount_msat, Some(0), 0);
                 }
 
+                if is_silent_payment_address(&data.address) {
+                    let fee_msat = self
+                        .silent_payment_fee(&data.address, amount_sat, fee)
+                        .await?
+                        .saturating_mul(1000);
+                    return Self::fee_estimate(Ledger::Onchain, amount_msat, Some(fee_msat), fee_msat);
+                }
+
+                let feerate_sat_vb = self.resolve_feerate(fee).await?;
                 let prepared = self
                     .bitcoin_wallet
-                    .prepare_transaction(data.address, amount_sat, None)
+                    .prepare_transaction(data.address.clone(), amount_sat, feerate_sat_vb)
                     .await?;
                 self.bitcoin_wallet.release_prepared_transaction(&prepared).await?;
                 let fee_msat = prepared
@@ -674,7 +1121,14 @@ impl PaymentsUseCases for PaymentService {
                     .checked_mul(1000)
                     .ok_or_else(|| DataError::Validation("On-chain fee overflows".to_string()))?;
 
-                Self::fee_estimate(Ledger::Onchain, amount_msat, Some(fee_msat), fee_msat)
+                let mut estimate = Self::fee_estimate(Ledger::Onchain, amount_msat, Some(fee_msat), fee_msat)?;
+                // Quotes are informational: a node without fee data must not block the estimate itself.
+                match self.onchain_fee_quotes(&prepared).await {
+                    Ok(quotes) => estimate.onchain_fees = quotes,
+                    Err(err) => warn!(%err, "Failed to quote on-chain fees per confirmation target"),
+                }
+
+                Ok(estimate)
             }
             PaymentInput::Bolt11(invoice) => {
                 let amount = invoice
@@ -696,17 +1150,49 @@ impl PaymentsUseCases for PaymentService {
                 self.lightning_fee_estimate(target).await
             }
             PaymentInput::LnUrlPay(data) => {
-                let amount = Self::validate_amount(amount_msat)?;
-                let callback = validate_lnurl_pay(amount, &comment, &data)
+                let preview = lnurl_pay_preview(&data);
+
+                // The terms are returned without a fee until the amount and comment satisfy them, so that the payer
+                // can pick an amount before the recipient is asked for an invoice.
+                let amount = amount_msat.unwrap_or_default();
+                if amount == 0 || validate_lnurl_pay_terms(amount, comment.as_deref(), &data).is_err() {
+                    let mut estimate = Self::fee_estimate(Ledger::Lightning, amount, None, 0)?;
+                    estimate.lnurl_pay = Some(preview);
+                    return Ok(estimate);
+                }
+
+                // Payer data identifies the wallet, which an estimate does not disclose.
+                let callback = validate_lnurl_pay(amount, &comment, None, &data)
                     .await
                     .map_err(|err| DataError::Validation(err.to_string()))?;
                 let invoice = parse_bolt11(&callback.pr).map_err(DataError::Validation)?;
                 let target = Self::ln_payment_target(&invoice, None)?;
-                self.lightning_fee_estimate(target).await
+
+                let mut estimate = self.lightning_fee_estimate(target).await?;
+                estimate.lnurl_pay = Some(preview);
+                Ok(estimate)
             }
         }
     }
 
+    async fn preview_lnurl_pay(&self, input: String, wallet_id: Uuid) -> Result<LnUrlPayPreview, ApplicationError> {
+        debug!(%input, %wallet_id, "Received LNURL-pay preview request");
+
+        let input_type = parse_payment_input(&input, self.dns_resolver.as_ref())
+            .await
+            .map_err(DataError::Validation)?;
+        let PaymentInput::LnUrlPay(data) = input_type else {
+            return Err(DataError::Validation("Input is not a LNURL or Lightning Address.".to_string()).into());
+        };
+        self.ensure_wallet_network(wallet_id, self.bitcoin_wallet.network())
+            .await?;
+
+        let preview = lnurl_pay_preview(&data);
+
+        debug!(%input, %wallet_id, "LNURL-pay preview fetched successfully");
+        Ok(preview)
+    }
+
     async fn get(&self, id: Uuid) -> Result<Payment, ApplicationError> {
         trace!(%id, "Fetching payment");
 
@@ -736,15 +1222,21 @@ impl PaymentsUseCases for PaymentService {
         amount_msat: Option<u64>,
         comment: Option<String>,
         wallet_id: Uuid,
+        fee: BtcFeeSelection,
+        max_amount_msat: Option<u64>,
     ) -> Result<Payment, ApplicationError> {
-        debug!(%input, %wallet_id, "Received pay request");
+        debug!(%input, %wallet_id, ?fee, "Received pay request");
 
-        let payment = if self.is_internal_payment(&input) {
+        let payment = if let Some((domain_id, _)) = self.internal_recipient(&input).await? {
             self.ensure_wallet_network(wallet_id, self.bitcoin_wallet.network())
                 .await?;
-            self.send_internal(input, amount_msat, comment, wallet_id).await
+            Self::check_step_up_limit(amount_msat, max_amount_msat)?;
+            self.send_internal(input, domain_id, amount_msat, comment, wallet_id)
+                .await
         } else {
-            let input_type = parse_payment_input(&input).await.map_err(DataError::Validation)?;
+            let input_type = parse_payment_input(&input, self.dns_resolver.as_ref())
+                .await
+                .map_err(DataError::Validation)?;
             let expected_network = match &input_type {
                 PaymentInput::BitcoinAddress(address) => address.network,
                 PaymentInput::Bolt11(invoice) => self.bolt11_network(invoice),
@@ -752,20 +1244,144 @@ impl PaymentsUseCases for PaymentService {
             };
             self.ensure_wallet_network(wallet_id, expected_network).await?;
 
+            let known_amount_msat = match &input_type {
+                PaymentInput::BitcoinAddress(address) => address
+                    .amount_sat
+                    .map(|amount| amount.saturating_mul(1000))
+                    .or(amount_msat),
+                PaymentInput::Bolt11(invoice) => invoice.amount_msat.or(amount_msat),
+                PaymentInput::LnUrlPay(_) => amount_msat,
+            };
+            Self::check_step_up_limit(known_amount_msat, max_amount_msat)?;
+
             match input_type {
                 PaymentInput::BitcoinAddress(address) => {
                     let amount_sat = amount_msat.map(|amount| amount / 1000);
-                    self.send_bitcoin(address, amount_sat, comment, wallet_id).await
+                    self.send_bitcoin(address, amount_sat, comment, wallet_id, fee).await
                 }
                 PaymentInput::Bolt11(invoice) => self.send_bolt11(invoice, amount_msat, comment, wallet_id).await,
                 PaymentInput::LnUrlPay(data) => self.send_lnurl_pay(data, amount_msat, comment, wallet_id).await,
             }
         }?;
+        record_created(&payment);
 
         info!(id = %payment.id, "Payment processed successfully");
         Ok(payment)
     }
 
+    async fn transfer(
+        &self,
+        wallet_id: Uuid,
+        request: TransferRequest,
+        max_amount_msat: Option<u64>,
+    ) -> Result<Payment, ApplicationError> {
+        debug!(%wallet_id, ?request, "Received transfer request");
+
+        let amount = Self::validate_amount(Some(request.amount_msat))?;
+        Self::check_step_up_limit(Some(amount), max_amount_msat)?;
+        let sender = self
+            .store
+            .wallet
+            .find(wallet_id)
+            .await?
+            .ok_or_else(|| DataError::NotFound(format!("Wallet {wallet_id} not found")))?;
+
+        let (recipient_id, ln_address) = match (request.to_wallet_id, request.to_account_id, request.to_ln_address) {
+            (Some(to_wallet_id), None, None) => (to_wallet_id, None),
+            (None, Some(to_account_id), None) => {
+                let recipient = self
+                    .store
+                    .wallet
+                    .find_by_account_and_asset(to_account_id, sender.asset_id)
+                    .await?
+                    .ok_or_else(|| DataError::NotFound("Recipient not found.".to_string()))?;
+                (recipient.id, None)
+            }
+            (None, None, Some(to_ln_address)) => {
+                // A bare username is an address on the default domain.
+                let to_ln_address = if to_ln_address.contains('@') {
+                    to_ln_address
+                } else {
+                    format!("{to_ln_address}@{}", self.domain)
+                };
+                let (recipient, alias) = self.transfer_ln_address(&to_ln_address).await?;
+                (recipient.wallet_id, Some((to_ln_address, recipient.id, alias)))
+            }
+            _ => {
+                return Err(DataError::Validation(
+                    "Exactly one of to_wallet_id, to_account_id or to_ln_address is required.".to_string(),
+                )
+                .into())
+            }
+        };
+
+        if recipient_id == wallet_id {
+            return Err(DataError::Validation("Cannot transfer to the same wallet.".to_string()).into());
+        }
+
+        let recipient = self
+            .store
+            .wallet
+            .find(recipient_id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Recipient not found.".to_string()))?;
+        if recipient.asset_id != sender.asset_id {
+            return Err(
+                DataError::Validation("Cannot transfer between wallets holding different assets.".to_string()).into(),
+            );
+        }
+
+        let transfer_id = Uuid::new_v4();
+        let curr_time = Utc::now();
+        let description = request
+            .comment
+            .or_else(|| Some(DEFAULT_TRANSFER_DESCRIPTION.to_string()));
+        let (ln_address, ln_address_id, ln_address_alias) = match ln_address {
+            Some((ln_address, ln_address_id, alias)) => (Some(ln_address), Some(ln_address_id), Some(alias)),
+            None => (None, None, None),
+        };
+
+        let invoice = Invoice {
+            wallet_id: recipient_id,
+            ln_address_id,
+            ln_address_alias,
+            transfer_id: Some(transfer_id),
+            ledger: Ledger::Internal,
+            description: description.clone(),
+            amount_msat: Some(amount),
+            amount_received_msat: Some(amount),
+            timestamp: curr_time,
+            status: InvoiceStatus::Settled,
+            fee_msat: Some(0),
+            payment_time: Some(curr_time),
+            ..Default::default()
+        };
+
+        let payment = Payment {
+            wallet_id,
+            amount_msat: amount,
+            status: PaymentStatus::Settled,
+            description,
+            fee_msat: Some(0),
+            payment_time: Some(curr_time),
+            ledger: Ledger::Internal,
+            internal: Some(InternalPayment {
+                ln_address,
+                btc_address: None,
+                payment_hash: None,
+                split_invoice_id: None,
+                transfer_id: Some(transfer_id),
+            }),
+            ..Default::default()
+        };
+
+        let payment = self.store.payment_uow.transfer(payment, invoice).await?;
+        record_created(&payment);
+
+        info!(id = %payment.id, %transfer_id, "Transfer processed successfully");
+        Ok(payment)
+    }
+
     async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
         debug!(%id, "Deleting payment");
 
@@ -787,6 +1403,7 @@ impl PaymentsUseCases for PaymentService {
                 ..Default::default()
             })
             .await?;
+        record_deleted(&payment);
 
         info!(%id, "Payment deleted successfully");
         Ok(())
@@ -887,10 +1504,14 @@ mod tests {
         },
         domains::{
             asset::{Asset, Protocol},
-            bitcoin::{BtcAddress, BtcAddressType, BtcNetwork, BtcPreparedTransaction, MockBitcoinWallet},
+            bitcoin::{
+                BtcAddress, BtcAddressType, BtcNetwork, BtcPreparedTransaction, MockBitcoinChain, MockBitcoinWallet,
+                SilentPaymentKeys,
+            },
             event::MockEventUseCases,
-            ln_address::LnAddress,
+            ln_address::LnAddressDomain,
             lnurl::LnUrlPaySuccessAction,
+            payment::MockDnsResolver,
             wallet::Wallet,
         },
         infra::lightning::MockLnClient,
@@ -911,6 +1532,8 @@ mod tests {
             Arc::new(bitcoin_wallet),
             DOMAIN.to_string(),
             Arc::new(events),
+            None,
+            Arc::new(MockDnsResolver::new()),
         )
     }
 
@@ -920,9 +1543,22 @@ mod tests {
             account_id: Uuid::new_v4(),
             wallet_id,
             username: "bob".to_string(),
+            domain_id: None,
             active,
             allows_nostr: false,
             nostr_pubkey: None,
+            pay_profile: Default::default(),
+            splits: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    fn ln_address_domain(name: &str) -> LnAddressDomain {
+        LnAddressDomain {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            host: format!("https://pay.{name}"),
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -935,6 +1571,8 @@ mod tests {
             address: "bcrt1qrecipient".to_string(),
             used: false,
             address_type: BtcAddressType::P2wpkh,
+            payjoin_uri: None,
+            invoice_id: None,
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -986,6 +1624,7 @@ mod tests {
             amount_sat,
             message: None,
             network: BtcNetwork::Regtest,
+            payjoin: None,
         }
     }
 
@@ -1005,11 +1644,40 @@ mod tests {
         PaymentService::ln_payment_target(&bolt11(Some(amount_msat)), None).unwrap()
     }
 
+    /// Unsigned node withdrawal: one P2WPKH input, the payment and change outputs. 141 vB once signed.
+    fn prepared_psbt() -> String {
+        use bitcoin::{hashes::Hash, psbt::Psbt, Amount, ScriptBuf, Transaction, TxIn, TxOut, WPubkeyHash};
+
+        let script = |byte: u8| ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([byte; 20]));
+        let tx = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(1_000),
+                    script_pubkey: script(1),
+                },
+                TxOut {
+                    value: Amount::from_sat(8_990),
+                    script_pubkey: script(2),
+                },
+            ],
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: script(3),
+        });
+
+        encode_psbt(&psbt)
+    }
+
     fn prepared_tx() -> BtcPreparedTransaction {
         BtcPreparedTransaction {
             txid: "txid".to_string(),
             fee_sat: 10,
-            psbt: "psbt".to_string(),
+            psbt: prepared_psbt(),
             locked_utxos: vec![],
         }
     }
@@ -1155,21 +1823,67 @@ mod tests {
         }
     }
 
-    mod is_internal_payment {
+    mod internal_recipient {
         use super::*;
 
-        #[test]
-        fn matches_the_configured_domain() {
-            let service = service(
-                MockAppStoreBuilder::new(),
+        fn service_with(store: MockAppStoreBuilder) -> PaymentService {
+            service(
+                store,
                 MockLnClient::new(),
                 MockBitcoinWallet::new(),
                 MockEventUseCases::new(),
-            );
+            )
+        }
+
+        #[tokio::test]
+        async fn matches_the_configured_domain_without_a_lookup() {
+            let service = service_with(MockAppStoreBuilder::new());
+
+            let recipient = service.internal_recipient("bob@Numeraire.tech").await.unwrap();
+
+            assert_eq!(recipient, Some((None, "bob")));
+        }
+
+        #[tokio::test]
+        async fn matches_a_bip353_name_of_the_configured_domain() {
+            let service = service_with(MockAppStoreBuilder::new());
+
+            let recipient = service.internal_recipient("₿bob@numeraire.tech").await.unwrap();
+
+            assert_eq!(recipient, Some((None, "bob")));
+        }
+
+        #[tokio::test]
+        async fn matches_a_managed_domain() {
+            let domain = ln_address_domain("brand.example");
+            let domain_id = domain.id;
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .ln_address_domain
+                .expect_find_by_name()
+                .withf(|name| name == "brand.example")
+                .times(1)
+                .returning(move |_| Ok(Some(domain.clone())));
+            let service = service_with(store);
+
+            let recipient = service.internal_recipient("bob@Brand.example").await.unwrap();
+
+            assert_eq!(recipient, Some((Some(domain_id), "bob")));
+        }
+
+        #[tokio::test]
+        async fn ignores_foreign_domains_and_other_inputs() {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .ln_address_domain
+                .expect_find_by_name()
+                .times(1)
+                .returning(|_| Ok(None));
+            let service = service_with(store);
 
-            assert!(service.is_internal_payment("bob@numeraire.tech"));
-            assert!(!service.is_internal_payment("bob@example.com"));
-            assert!(!service.is_internal_payment("not-an-address"));
+            assert_eq!(service.internal_recipient("bob@example.com").await.unwrap(), None);
+            assert_eq!(service.internal_recipient("not-an-address").await.unwrap(), None);
         }
     }
 
@@ -1349,6 +2063,11 @@ mod tests {
                 .withf(|prepared| prepared.txid == "txid")
                 .times(1)
                 .returning(|_| Ok(()));
+            // Nodes without fee data still get an estimate, just without per-target quotes.
+            bitcoin_wallet
+                .expect_estimate_feerate()
+                .times(1)
+                .returning(|_| Err(BitcoinError::EstimateFee("no fee data".to_string())));
 
             let service = service(store, MockLnClient::new(), bitcoin_wallet, MockEventUseCases::new());
             let estimate = service
@@ -1357,6 +2076,7 @@ mod tests {
                     Some(1_000_000),
                     None,
                     wallet_id,
+                    BtcFeeSelection::NodeDefault,
                 )
                 .await
                 .unwrap();
@@ -1364,27 +2084,94 @@ mod tests {
             assert_eq!(estimate.ledger, Ledger::Onchain);
             assert_eq!(estimate.estimated_fee_msat, Some(10_000));
             assert_eq!(estimate.maximum_total_msat, 1_010_000);
+            assert!(estimate.onchain_fees.is_empty());
         }
-    }
-
-    mod send_internal {
-        use super::*;
-
-        mod when_recipient_is_active_and_distinct {
-            use super::*;
-
-            #[tokio::test]
-            async fn writes_counterpart_invoice_and_settled_payment() {
-                let sender = Uuid::new_v4();
-                let recipient = Uuid::new_v4();
 
-                let mut store = MockAppStoreBuilder::new();
-                store
+        #[tokio::test]
+        async fn quotes_each_confirmation_target_without_preparing_more_transactions() {
+            let wallet_id = Uuid::new_v4();
+            let mut store = MockAppStoreBuilder::new();
+            store.wallet.expect_find().times(1).returning(move |_| {
+                Ok(Some(wallet_with_asset(
+                    wallet_id,
+                    native_btc_asset(BtcNetwork::Bitcoin),
+                )))
+            });
+            store
+                .btc_address
+                .expect_find_by_address()
+                .times(1)
+                .returning(|_| Ok(None));
+
+            let mut bitcoin_wallet = MockBitcoinWallet::new();
+            bitcoin_wallet
+                .expect_estimate_feerate()
+                .times(4)
+                .returning(|conf_target| Ok(if conf_target <= 3 { 20 } else { 2 }));
+            // Only the transaction at the selected feerate is prepared, the quotes reuse its size.
+            bitcoin_wallet
+                .expect_prepare_transaction()
+                .withf(|_, _, fee_rate| *fee_rate == Some(7))
+                .times(1)
+                .returning(|_, _, _| {
+                    Ok(BtcPreparedTransaction {
+                        fee_sat: 7 * 141,
+                        ..prepared_tx()
+                    })
+                });
+            bitcoin_wallet
+                .expect_release_prepared_transaction()
+                .times(1)
+                .returning(|_| Ok(()));
+
+            let service = service(store, MockLnClient::new(), bitcoin_wallet, MockEventUseCases::new());
+            let estimate = service
+                .estimate_fee(
+                    "1BoatSLRHtKNngkdXEeobR76b53LETtpyT".to_string(),
+                    Some(1_000_000),
+                    None,
+                    wallet_id,
+                    BtcFeeSelection::Feerate(7),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(estimate.estimated_fee_msat, Some(987_000));
+            let quotes: Vec<(u32, u32, u64)> = estimate
+                .onchain_fees
+                .iter()
+                .map(|quote| (quote.conf_target, quote.feerate_sat_vb, quote.fee_msat))
+                .collect();
+            assert_eq!(
+                quotes,
+                vec![
+                    (1, 20, 2_820_000),
+                    (3, 20, 2_820_000),
+                    (6, 2, 282_000),
+                    (144, 2, 282_000)
+                ]
+            );
+        }
+    }
+
+    mod send_internal {
+        use super::*;
+
+        mod when_recipient_is_active_and_distinct {
+            use super::*;
+
+            #[tokio::test]
+            async fn writes_counterpart_invoice_and_settled_payment() {
+                let sender = Uuid::new_v4();
+                let recipient = Uuid::new_v4();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
                     .ln_address
                     .expect_find_by_username()
-                    .withf(|username| username == "bob")
+                    .withf(|domain_id, username| domain_id.is_none() && username == "bob")
                     .times(1)
-                    .returning(move |_| Ok(Some(ln_address(recipient, true))));
+                    .returning(move |_, _| Ok(Some(ln_address(recipient, true))));
                 store
                     .payment_uow
                     .expect_settle_internal()
@@ -1403,7 +2190,7 @@ mod tests {
                 let service = service(store, MockLnClient::new(), bitcoin_wallet, MockEventUseCases::new());
 
                 let payment = service
-                    .send_internal("bob@numeraire.tech".to_string(), Some(1_000), None, sender)
+                    .send_internal("bob@numeraire.tech".to_string(), None, Some(1_000), None, sender)
                     .await
                     .unwrap();
 
@@ -1412,6 +2199,55 @@ mod tests {
             }
         }
 
+        mod when_recipient_is_a_sub_address {
+            use super::*;
+
+            #[tokio::test]
+            async fn credits_the_base_address_and_tags_the_alias() {
+                let recipient = Uuid::new_v4();
+
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .withf(|_, username| username == "bob+rent")
+                    .times(1)
+                    .returning(|_, _| Ok(None));
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .withf(|_, username| username == "bob")
+                    .times(1)
+                    .returning(move |_, _| Ok(Some(ln_address(recipient, true))));
+                store
+                    .payment_uow
+                    .expect_settle_internal()
+                    .withf(move |_, invoice| {
+                        invoice.wallet_id == recipient && invoice.ln_address_alias.as_deref() == Some("bob+rent")
+                    })
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+
+                let mut bitcoin_wallet = MockBitcoinWallet::new();
+                bitcoin_wallet.expect_network().returning(|| BtcNetwork::Regtest);
+
+                let service = service(store, MockLnClient::new(), bitcoin_wallet, MockEventUseCases::new());
+
+                let payment = service
+                    .send_internal(
+                        "Bob+Rent@numeraire.tech".to_string(),
+                        None,
+                        Some(1_000),
+                        None,
+                        Uuid::new_v4(),
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(payment.status, PaymentStatus::Settled);
+            }
+        }
+
         mod with_zero_amount {
             use super::*;
 
@@ -1425,7 +2261,7 @@ mod tests {
                 );
 
                 let err = service
-                    .send_internal("bob@numeraire.tech".to_string(), Some(0), None, Uuid::new_v4())
+                    .send_internal("bob@numeraire.tech".to_string(), None, Some(0), None, Uuid::new_v4())
                     .await
                     .unwrap_err();
 
@@ -1443,7 +2279,7 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(None));
+                    .returning(|_, _| Ok(None));
 
                 let service = service(
                     store,
@@ -1453,7 +2289,13 @@ mod tests {
                 );
 
                 let err = service
-                    .send_internal("bob@numeraire.tech".to_string(), Some(1_000), None, Uuid::new_v4())
+                    .send_internal(
+                        "bob@numeraire.tech".to_string(),
+                        None,
+                        Some(1_000),
+                        None,
+                        Uuid::new_v4(),
+                    )
                     .await
                     .unwrap_err();
 
@@ -1471,7 +2313,7 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(|_| Ok(Some(ln_address(Uuid::new_v4(), false))));
+                    .returning(|_, _| Ok(Some(ln_address(Uuid::new_v4(), false))));
 
                 let service = service(
                     store,
@@ -1481,7 +2323,13 @@ mod tests {
                 );
 
                 let err = service
-                    .send_internal("bob@numeraire.tech".to_string(), Some(1_000), None, Uuid::new_v4())
+                    .send_internal(
+                        "bob@numeraire.tech".to_string(),
+                        None,
+                        Some(1_000),
+                        None,
+                        Uuid::new_v4(),
+                    )
                     .await
                     .unwrap_err();
 
@@ -1501,7 +2349,7 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(move |_| Ok(Some(ln_address(wallet_id, true))));
+                    .returning(move |_, _| Ok(Some(ln_address(wallet_id, true))));
 
                 let service = service(
                     store,
@@ -1511,7 +2359,7 @@ mod tests {
                 );
 
                 let err = service
-                    .send_internal("bob@numeraire.tech".to_string(), Some(1_000), None, wallet_id)
+                    .send_internal("bob@numeraire.tech".to_string(), None, Some(1_000), None, wallet_id)
                     .await
                     .unwrap_err();
 
@@ -1543,7 +2391,7 @@ mod tests {
                     .ln_address
                     .expect_find_by_username()
                     .times(1)
-                    .returning(move |_| Ok(Some(ln_address(recipient, true))));
+                    .returning(move |_, _| Ok(Some(ln_address(recipient, true))));
                 store
                     .payment_uow
                     .expect_settle_internal()
@@ -1557,7 +2405,14 @@ mod tests {
                 let service = service(store, MockLnClient::new(), bitcoin_wallet, MockEventUseCases::new());
 
                 let payment = service
-                    .pay("bob@numeraire.tech".to_string(), Some(1_000), None, sender)
+                    .pay(
+                        "bob@numeraire.tech".to_string(),
+                        Some(1_000),
+                        None,
+                        sender,
+                        BtcFeeSelection::NodeDefault,
+                        None,
+                    )
                     .await
                     .unwrap();
 
@@ -1566,6 +2421,403 @@ mod tests {
         }
     }
 
+    mod transfer {
+        use super::*;
+
+        fn request(amount_msat: u64) -> TransferRequest {
+            TransferRequest {
+                wallet_id: None,
+                to_wallet_id: None,
+                to_account_id: None,
+                to_ln_address: None,
+                amount_msat,
+                comment: None,
+            }
+        }
+
+        fn store_with_wallets(wallets: Vec<Wallet>) -> MockAppStoreBuilder {
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .wallet
+                .expect_find()
+                .returning(move |id| Ok(wallets.iter().find(|wallet| wallet.id == id).cloned()));
+            store
+        }
+
+        mod to_a_wallet {
+            use super::*;
+
+            #[tokio::test]
+            async fn settles_both_legs_under_one_transfer_id() {
+                let asset = native_btc_asset(BtcNetwork::Regtest);
+                let sender = Uuid::new_v4();
+                let recipient = Uuid::new_v4();
+
+                let mut store = store_with_wallets(vec![
+                    wallet_with_asset(sender, asset.clone()),
+                    wallet_with_asset(recipient, asset),
+                ]);
+                store
+                    .payment_uow
+                    .expect_transfer()
+                    .withf(move |payment, invoice| {
+                        let transfer_id = payment.internal.as_ref().and_then(|internal| internal.transfer_id);
+                        payment.wallet_id == sender
+                            && payment.amount_msat == 1_000
+                            && payment.ledger == Ledger::Internal
+                            && invoice.wallet_id == recipient
+                            && invoice.amount_received_msat == Some(1_000)
+                            && invoice.status == InvoiceStatus::Settled
+                            && transfer_id.is_some()
+                            && invoice.transfer_id == transfer_id
+                    })
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+
+                let service = service(
+                    store,
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let payment = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_wallet_id: Some(recipient),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(payment.status, PaymentStatus::Settled);
+                assert_eq!(payment.description.as_deref(), Some(DEFAULT_TRANSFER_DESCRIPTION));
+            }
+
+            #[tokio::test]
+            async fn rejects_the_same_wallet() {
+                let asset = native_btc_asset(BtcNetwork::Regtest);
+                let sender = Uuid::new_v4();
+
+                let service = service(
+                    store_with_wallets(vec![wallet_with_asset(sender, asset)]),
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let err = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_wallet_id: Some(sender),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn rejects_a_wallet_of_another_asset() {
+                let sender = Uuid::new_v4();
+                let recipient = Uuid::new_v4();
+
+                let service = service(
+                    store_with_wallets(vec![
+                        wallet_with_asset(sender, native_btc_asset(BtcNetwork::Regtest)),
+                        wallet_with_asset(recipient, taproot_asset(BtcNetwork::Regtest)),
+                    ]),
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let err = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_wallet_id: Some(recipient),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            #[tokio::test]
+            async fn fails_when_the_wallet_does_not_exist() {
+                let asset = native_btc_asset(BtcNetwork::Regtest);
+                let sender = Uuid::new_v4();
+
+                let service = service(
+                    store_with_wallets(vec![wallet_with_asset(sender, asset)]),
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let err = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_wallet_id: Some(Uuid::new_v4()),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+
+        mod to_an_account {
+            use super::*;
+
+            #[tokio::test]
+            async fn credits_its_wallet_of_the_same_asset() {
+                let asset = native_btc_asset(BtcNetwork::Regtest);
+                let asset_id = asset.id;
+                let sender = Uuid::new_v4();
+                let recipient = wallet_with_asset(Uuid::new_v4(), asset.clone());
+                let (recipient_id, account_id) = (recipient.id, recipient.account_id);
+
+                let mut store = store_with_wallets(vec![wallet_with_asset(sender, asset), recipient.clone()]);
+                store
+                    .wallet
+                    .expect_find_by_account_and_asset()
+                    .withf(move |account, asset| *account == account_id && *asset == asset_id)
+                    .times(1)
+                    .returning(move |_, _| Ok(Some(recipient.clone())));
+                store
+                    .payment_uow
+                    .expect_transfer()
+                    .withf(move |_, invoice| invoice.wallet_id == recipient_id)
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+
+                let service = service(
+                    store,
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let result = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_account_id: Some(account_id),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await;
+
+                assert!(result.is_ok());
+            }
+        }
+
+        mod to_a_lightning_address {
+            use super::*;
+
+            #[tokio::test]
+            async fn resolves_a_bare_username_on_the_default_domain() {
+                let asset = native_btc_asset(BtcNetwork::Regtest);
+                let sender = Uuid::new_v4();
+                let recipient = Uuid::new_v4();
+                let address = ln_address(recipient, true);
+                let address_id = address.id;
+
+                let mut store = store_with_wallets(vec![
+                    wallet_with_asset(sender, asset.clone()),
+                    wallet_with_asset(recipient, asset),
+                ]);
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .withf(|domain_id, username| domain_id.is_none() && username == "bob")
+                    .times(1)
+                    .returning(move |_, _| Ok(Some(address.clone())));
+                store
+                    .payment_uow
+                    .expect_transfer()
+                    .withf(move |payment, invoice| {
+                        payment
+                            .internal
+                            .as_ref()
+                            .and_then(|internal| internal.ln_address.as_deref())
+                            == Some("bob@numeraire.tech")
+                            && invoice.wallet_id == recipient
+                            && invoice.ln_address_id == Some(address_id)
+                            && invoice.ln_address_alias.as_deref() == Some("bob")
+                    })
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+
+                let service = service(
+                    store,
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let result = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_ln_address: Some("bob".to_string()),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await;
+
+                assert!(result.is_ok());
+            }
+
+            #[tokio::test]
+            async fn rejects_an_inactive_address() {
+                let asset = native_btc_asset(BtcNetwork::Regtest);
+                let sender = Uuid::new_v4();
+
+                let mut store = store_with_wallets(vec![wallet_with_asset(sender, asset)]);
+                store
+                    .ln_address
+                    .expect_find_by_username()
+                    .returning(|_, _| Ok(Some(ln_address(Uuid::new_v4(), false))));
+
+                let service = service(
+                    store,
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let err = service
+                    .transfer(
+                        sender,
+                        TransferRequest {
+                            to_ln_address: Some("bob@numeraire.tech".to_string()),
+                            ..request(1_000)
+                        },
+                        None,
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::NotFound(_))));
+            }
+        }
+
+        #[tokio::test]
+        async fn requires_exactly_one_recipient() {
+            let asset = native_btc_asset(BtcNetwork::Regtest);
+            let sender = Uuid::new_v4();
+
+            let service = service(
+                store_with_wallets(vec![wallet_with_asset(sender, asset)]),
+                MockLnClient::new(),
+                MockBitcoinWallet::new(),
+                MockEventUseCases::new(),
+            );
+
+            let err = service
+                .transfer(
+                    sender,
+                    TransferRequest {
+                        to_wallet_id: Some(Uuid::new_v4()),
+                        to_account_id: Some(Uuid::new_v4()),
+                        ..request(1_000)
+                    },
+                    None,
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn rejects_a_zero_amount() {
+            let service = service(
+                MockAppStoreBuilder::new(),
+                MockLnClient::new(),
+                MockBitcoinWallet::new(),
+                MockEventUseCases::new(),
+            );
+
+            let err = service
+                .transfer(
+                    Uuid::new_v4(),
+                    TransferRequest {
+                        to_wallet_id: Some(Uuid::new_v4()),
+                        ..request(0)
+                    },
+                    None,
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+        }
+
+        #[tokio::test]
+        async fn requires_a_step_up_above_the_limit_of_the_caller() {
+            let service = service(
+                MockAppStoreBuilder::new(),
+                MockLnClient::new(),
+                MockBitcoinWallet::new(),
+                MockEventUseCases::new(),
+            );
+
+            let err = service
+                .transfer(
+                    Uuid::new_v4(),
+                    TransferRequest {
+                        to_wallet_id: Some(Uuid::new_v4()),
+                        ..request(1_001)
+                    },
+                    Some(1_000),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(
+                err,
+                ApplicationError::Authorization(AuthorizationError::StepUpRequired)
+            ));
+        }
+    }
+
+    mod check_step_up_limit {
+        use super::*;
+
+        #[test]
+        fn allows_any_amount_without_a_limit() {
+            assert!(PaymentService::check_step_up_limit(None, None).is_ok());
+            assert!(PaymentService::check_step_up_limit(Some(u64::MAX), None).is_ok());
+        }
+
+        #[test]
+        fn rejects_amounts_above_the_limit_and_unknown_amounts() {
+            assert!(PaymentService::check_step_up_limit(Some(1_000), Some(1_000)).is_ok());
+            assert!(PaymentService::check_step_up_limit(Some(1_001), Some(1_000)).is_err());
+            assert!(PaymentService::check_step_up_limit(None, Some(1_000)).is_err());
+        }
+    }
+
     mod send_bitcoin {
         use super::*;
 
@@ -1582,7 +2834,13 @@ mod tests {
                 );
 
                 let err = service
-                    .send_bitcoin(bitcoin_data(Some(0)), None, None, Uuid::new_v4())
+                    .send_bitcoin(
+                        bitcoin_data(Some(0)),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
                     .await
                     .unwrap_err();
 
@@ -1603,7 +2861,13 @@ mod tests {
                 );
 
                 let err = service
-                    .send_bitcoin(bitcoin_data(None), None, None, Uuid::new_v4())
+                    .send_bitcoin(
+                        bitcoin_data(None),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
                     .await
                     .unwrap_err();
 
@@ -1644,7 +2908,13 @@ mod tests {
                 );
 
                 let payment = service
-                    .send_bitcoin(bitcoin_data(Some(1_000)), None, None, Uuid::new_v4())
+                    .send_bitcoin(
+                        bitcoin_data(Some(1_000)),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
                     .await
                     .unwrap();
 
@@ -1675,7 +2945,13 @@ mod tests {
                 );
 
                 let err = service
-                    .send_bitcoin(bitcoin_data(Some(1_000)), None, None, wallet_id)
+                    .send_bitcoin(
+                        bitcoin_data(Some(1_000)),
+                        None,
+                        None,
+                        wallet_id,
+                        BtcFeeSelection::NodeDefault,
+                    )
                     .await
                     .unwrap_err();
 
@@ -1716,7 +2992,316 @@ mod tests {
                 let service = service(store, MockLnClient::new(), wallet, MockEventUseCases::new());
 
                 let payment = service
-                    .send_bitcoin(bitcoin_data(Some(1_000)), None, None, Uuid::new_v4())
+                    .send_bitcoin(
+                        bitcoin_data(Some(1_000)),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(payment.status, PaymentStatus::Pending);
+            }
+        }
+
+        mod with_a_payjoin_endpoint {
+            use super::*;
+
+            #[tokio::test]
+            async fn falls_back_to_the_original_transaction_when_the_payjoin_fails() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(None));
+                store
+                    .payment_uow
+                    .expect_reserve()
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet
+                    .expect_prepare_transaction()
+                    .times(1)
+                    .returning(|_, _, _| Ok(prepared_tx()));
+                wallet
+                    .expect_sign_psbt()
+                    .times(1)
+                    .returning(|_| Err(BitcoinError::SignPsbt("not signed".to_string())));
+                wallet.expect_broadcast_psbt().never();
+                wallet.expect_sign_send_transaction().times(1).returning(|_| Ok(None));
+
+                let service = service(store, MockLnClient::new(), wallet, MockEventUseCases::new());
+
+                let data = BitcoinAddressData {
+                    address: "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080".to_string(),
+                    payjoin: Some("https://example.com/payjoin".to_string()),
+                    ..bitcoin_data(Some(1_000))
+                };
+                let payment = service
+                    .send_bitcoin(data, None, None, Uuid::new_v4(), BtcFeeSelection::NodeDefault)
+                    .await
+                    .unwrap();
+
+                assert_eq!(payment.status, PaymentStatus::Pending);
+            }
+        }
+
+        mod to_a_silent_payment_address {
+            use super::*;
+
+            fn silent_payment_data(amount_sat: u64) -> BitcoinAddressData {
+                let recipient = SilentPaymentKeys::from_hex(&"11".repeat(32), &"22".repeat(32)).unwrap();
+                BitcoinAddressData {
+                    address: recipient.address(1, BtcNetwork::Regtest),
+                    ..bitcoin_data(Some(amount_sat))
+                }
+            }
+
+            #[tokio::test]
+            async fn fails_when_silent_payments_are_disabled() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(None));
+
+                let service = service(
+                    store,
+                    MockLnClient::new(),
+                    MockBitcoinWallet::new(),
+                    MockEventUseCases::new(),
+                );
+
+                let err = service
+                    .send_bitcoin(
+                        silent_payment_data(1_000),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(err, ApplicationError::Data(DataError::Validation(_))));
+            }
+
+            /// Node wallet preparing the funding transaction, paying `address` from a single input.
+            fn funding_wallet() -> MockBitcoinWallet {
+                let mut wallet = MockBitcoinWallet::new();
+                wallet.expect_network().returning(|| BtcNetwork::Regtest);
+                wallet
+                    .expect_estimate_feerate()
+                    .withf(|conf_target| *conf_target == SILENT_PAYMENT_CONF_TARGET)
+                    .times(1)
+                    .returning(|_| Ok(2));
+                wallet
+                    .expect_prepare_transaction()
+                    .withf(|_, amount_sat, feerate| {
+                        *amount_sat == 40_000 + silent_payment_fee(1, 2) && *feerate == Some(2)
+                    })
+                    .times(1)
+                    .returning(|address, amount_sat, _| {
+                        let script_pubkey = address_script(&address, bitcoin::Network::Regtest).unwrap();
+                        let tx = bitcoin::Transaction {
+                            version: bitcoin::transaction::Version::TWO,
+                            lock_time: bitcoin::absolute::LockTime::ZERO,
+                            input: vec![bitcoin::TxIn::default()],
+                            output: vec![bitcoin::TxOut {
+                                value: bitcoin::Amount::from_sat(amount_sat),
+                                script_pubkey,
+                            }],
+                        };
+                        Ok(BtcPreparedTransaction {
+                            txid: tx.compute_txid().to_string(),
+                            fee_sat: 300,
+                            psbt: encode_psbt(&bitcoin::Psbt::from_unsigned_tx(tx).unwrap()),
+                            locked_utxos: vec![],
+                        })
+                    });
+                wallet
+            }
+
+            fn funding_store() -> MockAppStoreBuilder {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(None));
+                store.btc_output.expect_find_silent_payment_outputs().never();
+                store
+                    .payment_uow
+                    .expect_reserve()
+                    .withf(|payment, _| {
+                        payment.amount_msat == 40_000_000
+                            && payment.fee_msat == Some((300 + silent_payment_fee(1, 2)) * 1000)
+                            && payment
+                                .bitcoin
+                                .as_ref()
+                                .is_some_and(|bitcoin| bitcoin.address.starts_with("sprt1q"))
+                    })
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+                store
+                    .btc_address
+                    .expect_insert_silent_payment_output()
+                    .times(1)
+                    .returning(|wallet_id, address, _| {
+                        Ok(BtcAddress {
+                            address: address.to_string(),
+                            ..btc_address(wallet_id)
+                        })
+                    });
+                store
+                    .btc_output
+                    .expect_upsert()
+                    .withf(|output| output.status == BtcOutputStatus::Confirmed && output.amount_sat > 40_000)
+                    .times(1)
+                    .returning(Ok);
+                store
+            }
+
+            fn silent_payment_service(
+                store: MockAppStoreBuilder,
+                wallet: MockBitcoinWallet,
+                chain: MockBitcoinChain,
+            ) -> PaymentService {
+                let keys = SilentPaymentKeys::from_hex(&"33".repeat(32), &"44".repeat(32)).unwrap();
+                PaymentService::new(
+                    store.build(),
+                    Arc::new(MockLnClient::new()),
+                    Arc::new(wallet),
+                    DOMAIN.to_string(),
+                    Arc::new(MockEventUseCases::new()),
+                    Some(Arc::new(SilentPayments::new(keys, Arc::new(chain), 0))),
+                    Arc::new(MockDnsResolver::new()),
+                )
+            }
+
+            #[tokio::test]
+            async fn funds_it_from_the_node_wallet_and_broadcasts_it_through_the_full_node() {
+                let mut store = funding_store();
+                store
+                    .btc_output
+                    .expect_mark_spent()
+                    .withf(|outpoints| outpoints.len() == 1)
+                    .times(1)
+                    .returning(|_| Ok(()));
+
+                let mut wallet = funding_wallet();
+                let mut sequence = mockall::Sequence::new();
+                wallet
+                    .expect_sign_send_transaction()
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|_| Ok(None));
+
+                let mut chain = MockBitcoinChain::new();
+                chain
+                    .expect_broadcast_transaction()
+                    .times(1)
+                    .in_sequence(&mut sequence)
+                    .returning(|_| Ok("txid".to_string()));
+
+                let payment = silent_payment_service(store, wallet, chain)
+                    .send_bitcoin(
+                        silent_payment_data(40_000),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
+                    .await
+                    .unwrap();
+
+                assert_eq!(payment.status, PaymentStatus::Pending);
+            }
+
+            #[tokio::test]
+            async fn fails_the_payment_and_leaves_the_funding_output_to_be_swept_when_it_cannot_be_broadcast() {
+                let mut store = funding_store();
+                store.btc_output.expect_mark_spent().never();
+                store
+                    .payment_uow
+                    .expect_fail()
+                    .withf(|payment| payment.status == PaymentStatus::Failed)
+                    .times(1)
+                    .returning(Ok);
+
+                let mut wallet = funding_wallet();
+                wallet.expect_sign_send_transaction().times(1).returning(|_| Ok(None));
+
+                let mut chain = MockBitcoinChain::new();
+                chain
+                    .expect_broadcast_transaction()
+                    .times(1)
+                    .returning(|_| Err(BitcoinError::BroadcastTransaction("rejected".to_string())));
+
+                let err = silent_payment_service(store, wallet, chain)
+                    .send_bitcoin(
+                        silent_payment_data(40_000),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
+                    .await
+                    .unwrap_err();
+
+                assert!(matches!(
+                    err,
+                    ApplicationError::Bitcoin(BitcoinError::BroadcastTransaction(_))
+                ));
+            }
+        }
+
+        mod with_a_confirmation_target {
+            use super::*;
+
+            #[tokio::test]
+            async fn prepares_at_the_node_estimate_for_that_target() {
+                let mut store = MockAppStoreBuilder::new();
+                store
+                    .btc_address
+                    .expect_find_by_address()
+                    .times(1)
+                    .returning(|_| Ok(None));
+                store
+                    .payment_uow
+                    .expect_reserve()
+                    .times(1)
+                    .returning(|payment, _| Ok(payment));
+
+                let mut wallet = MockBitcoinWallet::new();
+                wallet
+                    .expect_estimate_feerate()
+                    .withf(|conf_target| *conf_target == 6)
+                    .times(1)
+                    .returning(|_| Ok(12));
+                wallet
+                    .expect_prepare_transaction()
+                    .withf(|_, _, fee_rate| *fee_rate == Some(12))
+                    .times(1)
+                    .returning(|_, _, _| Ok(prepared_tx()));
+                wallet.expect_sign_send_transaction().times(1).returning(|_| Ok(None));
+
+                let service = service(store, MockLnClient::new(), wallet, MockEventUseCases::new());
+
+                let payment = service
+                    .send_bitcoin(
+                        bitcoin_data(Some(1_000)),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::ConfTarget(6),
+                    )
                     .await
                     .unwrap();
 
@@ -1755,7 +3340,13 @@ mod tests {
                 let service = service(store, MockLnClient::new(), wallet, MockEventUseCases::new());
 
                 let err = service
-                    .send_bitcoin(bitcoin_data(Some(1_000)), None, None, Uuid::new_v4())
+                    .send_bitcoin(
+                        bitcoin_data(Some(1_000)),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
                     .await
                     .unwrap_err();
 
@@ -1803,7 +3394,13 @@ mod tests {
                 let service = service(store, MockLnClient::new(), wallet, MockEventUseCases::new());
 
                 let err = service
-                    .send_bitcoin(bitcoin_data(Some(1_000)), None, None, Uuid::new_v4())
+                    .send_bitcoin(
+                        bitcoin_data(Some(1_000)),
+                        None,
+                        None,
+                        Uuid::new_v4(),
+                        BtcFeeSelection::NodeDefault,
+                    )
                     .await
                     .unwrap_err();
 
@@ -2417,4 +4014,28 @@ mod tests {
             }
         }
     }
+
+    mod preview_lnurl_pay {
+        use super::*;
+
+        #[tokio::test]
+        async fn rejects_inputs_that_are_not_lnurl_pay_requests() {
+            // No store expectation: a non-LNURL input is refused before the wallet is read.
+            let service = service(
+                MockAppStoreBuilder::new(),
+                MockLnClient::new(),
+                MockBitcoinWallet::new(),
+                MockEventUseCases::new(),
+            );
+
+            let result = service
+                .preview_lnurl_pay("1BoatSLRHtKNngkdXEeobR76b53LETtpyT".to_string(), Uuid::new_v4())
+                .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Data(DataError::Validation(message))) if message.contains("not a LNURL")
+            ));
+        }
+    }
 }
@@ -19,4 +19,8 @@ pub trait PaymentUnitOfWork: Send + Sync {
     /// Settle an internal payment: debit the sender, credit the receiver, and insert the payment
     /// and its counterpart invoice, atomically.
     async fn settle_internal(&self, payment: Payment, invoice: Invoice) -> Result<Payment, ApplicationError>;
+
+    /// Settle a transfer between two wallets: debit the sender, credit the receiver, and insert the payment and
+    /// its counterpart invoice sharing the transfer ID, atomically.
+    async fn transfer(&self, payment: Payment, invoice: Invoice) -> Result<Payment, ApplicationError>;
 }
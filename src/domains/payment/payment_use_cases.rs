@@ -1,9 +1,12 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use crate::application::errors::ApplicationError;
+use crate::{
+    application::errors::ApplicationError,
+    domains::{bitcoin::BtcFeeSelection, lnurl::LnUrlPayPreview},
+};
 
-use super::{Payment, PaymentFeeEstimate, PaymentFilter};
+use super::{Payment, PaymentFeeEstimate, PaymentFilter, TransferRequest};
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -14,13 +17,26 @@ pub trait PaymentsUseCases: Send + Sync {
         amount_msat: Option<u64>,
         comment: Option<String>,
         wallet_id: Uuid,
+        fee: BtcFeeSelection,
     ) -> Result<PaymentFeeEstimate, ApplicationError>;
+    /// Fetches the `payRequest` of a LNURL or Lightning Address without paying it.
+    async fn preview_lnurl_pay(&self, input: String, wallet_id: Uuid) -> Result<LnUrlPayPreview, ApplicationError>;
+    /// Sends a payment. Payments above `max_amount_msat`, or of an unknown amount when it is set, require a
+    /// step-up of the caller.
     async fn pay(
         &self,
         input: String,
         amount_msat: Option<u64>,
         comment: Option<String>,
         wallet_id: Uuid,
+        fee: BtcFeeSelection,
+        max_amount_msat: Option<u64>,
+    ) -> Result<Payment, ApplicationError>;
+    async fn transfer(
+        &self,
+        wallet_id: Uuid,
+        request: TransferRequest,
+        max_amount_msat: Option<u64>,
     ) -> Result<Payment, ApplicationError>;
     async fn get(&self, id: Uuid) -> Result<Payment, ApplicationError>;
     async fn list(&self, filter: PaymentFilter) -> Result<Vec<Payment>, ApplicationError>;
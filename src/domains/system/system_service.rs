@@ -8,7 +8,10 @@ use crate::{
         composition::AppStore,
         errors::{ApplicationError, DataError},
     },
-    domains::{account::PASSWORD_HASH_KEY, bitcoin::OnchainSyncCursor},
+    domains::{
+        account::PASSWORD_HASH_KEY,
+        bitcoin::{OnchainSyncCursor, SilentPaymentScanCursor},
+    },
     infra::lightning::LnClient,
 };
 
@@ -16,6 +19,7 @@ use super::{HealthCheck, HealthStatus, SetupInfo, SystemUseCases, VersionInfo};
 
 const WELCOME_COMPLETE_KEY: &str = "welcome_complete";
 const ONCHAIN_CURSOR_KEY: &str = "onchain_sync_cursor";
+const SILENT_PAYMENT_CURSOR_KEY: &str = "silent_payment_scan_cursor";
 
 pub struct SystemService {
     store: AppStore,
@@ -109,6 +113,29 @@ impl SystemUseCases for SystemService {
         debug!(?cursor, "Onchain sync cursor updated successfully");
         Ok(())
     }
+
+    async fn get_silent_payment_cursor(&self) -> Result<Option<SilentPaymentScanCursor>, ApplicationError> {
+        trace!("Retrieving silent payment scan cursor");
+
+        let Some(value) = self.store.config.find(SILENT_PAYMENT_CURSOR_KEY).await? else {
+            return Ok(None);
+        };
+
+        let cursor = from_value(value).map_err(|e| DataError::Malformed(e.to_string()))?;
+
+        debug!(?cursor, "Silent payment scan cursor retrieved successfully");
+        Ok(Some(cursor))
+    }
+
+    async fn set_silent_payment_cursor(&self, cursor: SilentPaymentScanCursor) -> Result<(), ApplicationError> {
+        trace!("Setting silent payment scan cursor");
+
+        let value = to_value(cursor.clone()).map_err(|e| DataError::Malformed(e.to_string()))?;
+        self.store.config.upsert(SILENT_PAYMENT_CURSOR_KEY, value).await?;
+
+        debug!(?cursor, "Silent payment scan cursor updated successfully");
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -349,6 +376,31 @@ mod tests {
         }
     }
 
+    mod get_silent_payment_cursor {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_the_stored_cursor() {
+            let cursor = SilentPaymentScanCursor {
+                height: 840_000,
+                hash: "00000000000000000000".to_string(),
+            };
+            let stored = serde_json::to_value(cursor.clone()).unwrap();
+
+            let mut store = MockAppStoreBuilder::new();
+            store
+                .config
+                .expect_find()
+                .withf(|key| key == SILENT_PAYMENT_CURSOR_KEY)
+                .times(1)
+                .returning(move |_| Ok(Some(stored.clone())));
+
+            let service = service(store, MockLnClient::new());
+
+            assert_eq!(service.get_silent_payment_cursor().await.unwrap(), Some(cursor));
+        }
+    }
+
     mod version {
         use super::*;
 
@@ -1,6 +1,9 @@
 use async_trait::async_trait;
 
-use crate::{application::errors::ApplicationError, domains::bitcoin::OnchainSyncCursor};
+use crate::{
+    application::errors::ApplicationError,
+    domains::bitcoin::{OnchainSyncCursor, SilentPaymentScanCursor},
+};
 
 use super::{HealthCheck, SetupInfo, VersionInfo};
 
@@ -13,4 +16,6 @@ pub trait SystemUseCases: Send + Sync {
     async fn mark_welcome_complete(&self) -> Result<(), ApplicationError>;
     async fn get_onchain_cursor(&self) -> Result<Option<OnchainSyncCursor>, ApplicationError>;
     async fn set_onchain_cursor(&self, cursor: OnchainSyncCursor) -> Result<(), ApplicationError>;
+    async fn get_silent_payment_cursor(&self) -> Result<Option<SilentPaymentScanCursor>, ApplicationError>;
+    async fn set_silent_payment_cursor(&self, cursor: SilentPaymentScanCursor) -> Result<(), ApplicationError>;
 }
@@ -10,21 +10,26 @@ use uuid::Uuid;
 
 use swissknife_types::{
     Account, AccountPreferences, CreateApiKeyRequest, CreateWalletRequest, ErrorResponse, NewBtcAddressRequest,
-    NewInvoiceRequest, PaymentFeeEstimate, RegisterLnAddressRequest, SendPaymentRequest,
-    UpdateAccountPreferencesRequest, UpdateAccountRequest, UpdateLnAddressRequest,
+    NewInvoiceRequest, NewPaymentRequest, OrderDirection, PaymentFeeEstimate, PreviewPaymentRequest,
+    RegisterLnAddressRequest, RegisterPayLinkRequest, RotateApiKeyRequest, SendPaymentRequest, TransferRequest,
+    UpdateAccountPreferencesRequest, UpdateAccountRequest, UpdateLnAddressRequest, UpdatePayLinkRequest,
 };
 
 use crate::{
     application::{
         composition::AppServices,
-        docs::{BAD_REQUEST_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE, UNAUTHORIZED_EXAMPLE, UNPROCESSABLE_EXAMPLE},
+        docs::{
+            BAD_REQUEST_EXAMPLE, CONFLICT_EXAMPLE, INTERNAL_EXAMPLE, NOT_FOUND_EXAMPLE, UNAUTHORIZED_EXAMPLE,
+            UNPROCESSABLE_EXAMPLE,
+        },
         errors::{ApplicationError, DataError},
     },
     domains::{
-        account::{ApiKey, ApiKeyFilter, User},
-        bitcoin::{BtcAddress, BtcAddressFilter},
-        invoice::{Invoice, InvoiceFilter, InvoiceStatus},
+        account::{ApiKey, ApiKeyFilter, Permission, User},
+        bitcoin::{BtcAddress, BtcAddressFilter, BtcFeeSelection},
+        invoice::{Invoice, InvoiceFilter, InvoiceStatus, PaymentRequest},
         ln_address::{LnAddress, LnAddressFilter},
+        lnurl::{LnUrlPayPreview, PayLink, PayLinkFilter},
         payment::{Payment, PaymentFilter, PaymentStatus},
     },
     infra::axum::{Json, Path, Query},
@@ -43,11 +48,17 @@ use super::{Balance, Contact, Wallet, WalletFilter};
         register_account_address,
         update_account_address,
         delete_account_address,
+        list_account_addresses,
+        register_account_addresses,
+        get_account_address_by_id,
+        update_account_address_by_id,
+        delete_account_address_by_id,
         create_account_api_key,
         list_account_api_keys,
         get_account_api_key,
         revoke_account_api_key,
         revoke_account_api_keys,
+        rotate_account_api_key,
         list_account_wallets,
         create_account_wallet,
         get_account_wallet,
@@ -55,14 +66,22 @@ use super::{Balance, Contact, Wallet, WalletFilter};
         new_wallet_btc_address,
         list_wallet_btc_addresses,
         new_wallet_invoice,
+        new_wallet_payment_request,
         list_wallet_invoices,
         get_wallet_invoice,
         delete_expired_invoices,
         wallet_pay,
         estimate_wallet_payment_fee,
+        preview_wallet_lnurl_pay,
+        wallet_transfer,
         list_wallet_payments,
         get_wallet_payment,
         delete_failed_payments,
+        list_wallet_pay_links,
+        register_wallet_pay_link,
+        get_wallet_pay_link,
+        update_wallet_pay_link,
+        delete_wallet_pay_link,
         list_contacts,
     ),
     components(schemas(
@@ -78,9 +97,18 @@ use super::{Balance, Contact, Wallet, WalletFilter};
         BtcAddress,
         SendPaymentRequest,
         PaymentFeeEstimate,
+        PreviewPaymentRequest,
+        LnUrlPayPreview,
+        TransferRequest,
+        PayLink,
+        RegisterPayLinkRequest,
+        UpdatePayLinkRequest,
         NewInvoiceRequest,
+        NewPaymentRequest,
+        PaymentRequest,
         NewBtcAddressRequest,
         CreateApiKeyRequest,
+        RotateApiKeyRequest,
         ApiKey
     )),
     tags(
@@ -100,11 +128,17 @@ pub fn account_router() -> Router<Arc<AppServices>> {
         .route("/lightning-address", post(register_account_address))
         .route("/lightning-address", put(update_account_address))
         .route("/lightning-address", delete(delete_account_address))
+        .route("/lightning-addresses", get(list_account_addresses))
+        .route("/lightning-addresses", post(register_account_addresses))
+        .route("/lightning-addresses/{id}", get(get_account_address_by_id))
+        .route("/lightning-addresses/{id}", put(update_account_address_by_id))
+        .route("/lightning-addresses/{id}", delete(delete_account_address_by_id))
         .route("/api-keys", post(create_account_api_key))
         .route("/api-keys", get(list_account_api_keys))
         .route("/api-keys/{id}", get(get_account_api_key))
         .route("/api-keys/{id}", delete(revoke_account_api_key))
         .route("/api-keys", delete(revoke_account_api_keys))
+        .route("/api-keys/{id}/rotate", post(rotate_account_api_key))
         .route("/wallets", get(list_account_wallets))
         .route("/wallets", post(create_account_wallet))
         .route("/wallets/{wallet_id}", get(get_account_wallet))
@@ -112,6 +146,10 @@ pub fn account_router() -> Router<Arc<AppServices>> {
         .route("/wallets/{wallet_id}/bitcoin/addresses", get(list_wallet_btc_addresses))
         .route("/wallets/{wallet_id}/bitcoin/addresses", post(new_wallet_btc_address))
         .route("/wallets/{wallet_id}/invoices", post(new_wallet_invoice))
+        .route(
+            "/wallets/{wallet_id}/invoices/unified",
+            post(new_wallet_payment_request),
+        )
         .route("/wallets/{wallet_id}/invoices", get(list_wallet_invoices))
         .route("/wallets/{wallet_id}/invoices/{id}", get(get_wallet_invoice))
         .route("/wallets/{wallet_id}/invoices", delete(delete_expired_invoices))
@@ -120,9 +158,19 @@ pub fn account_router() -> Router<Arc<AppServices>> {
             "/wallets/{wallet_id}/payments/fee-estimate",
             post(estimate_wallet_payment_fee),
         )
+        .route(
+            "/wallets/{wallet_id}/payments/lnurl-preview",
+            post(preview_wallet_lnurl_pay),
+        )
         .route("/wallets/{wallet_id}/payments", get(list_wallet_payments))
         .route("/wallets/{wallet_id}/payments/{id}", get(get_wallet_payment))
         .route("/wallets/{wallet_id}/payments", delete(delete_failed_payments))
+        .route("/wallets/{wallet_id}/transfers", post(wallet_transfer))
+        .route("/wallets/{wallet_id}/pay-links", get(list_wallet_pay_links))
+        .route("/wallets/{wallet_id}/pay-links", post(register_wallet_pay_link))
+        .route("/wallets/{wallet_id}/pay-links/{id}", get(get_wallet_pay_link))
+        .route("/wallets/{wallet_id}/pay-links/{id}", put(update_wallet_pay_link))
+        .route("/wallets/{wallet_id}/pay-links/{id}", delete(delete_wallet_pay_link))
         .route("/wallets/{wallet_id}/contacts", get(list_contacts))
 }
 
@@ -140,6 +188,7 @@ pub fn account_router() -> Router<Arc<AppServices>> {
     )
 )]
 async fn get_account(State(services): State<Arc<AppServices>>, user: User) -> Result<Json<Account>, ApplicationError> {
+    user.check_role_permission(Permission::ReadAccount)?;
     let mut account = services.account.get(user.account_id).await?;
     account.permissions = Some(user.permissions);
     Ok(Json(account))
@@ -166,6 +215,8 @@ async fn update_current_account(
     user: User,
     Json(UpdateAccountRequest { display_name }): Json<UpdateAccountRequest>,
 ) -> Result<Json<Account>, ApplicationError> {
+    user.check_role_permission(Permission::WriteAccount)?;
+    user.check_account_scope()?;
     let mut account = services.account.update(user.account_id, display_name).await?;
     account.permissions = Some(user.permissions);
     Ok(Json(account))
@@ -188,6 +239,7 @@ async fn get_account_preferences(
     State(services): State<Arc<AppServices>>,
     user: User,
 ) -> Result<Json<AccountPreferences>, ApplicationError> {
+    user.check_role_permission(Permission::ReadAccount)?;
     let account = services.account.get(user.account_id).await?;
     let preferences = account
         .preferences
@@ -215,6 +267,8 @@ async fn update_account_preferences(
     user: User,
     Json(payload): Json<UpdateAccountPreferencesRequest>,
 ) -> Result<Json<AccountPreferences>, ApplicationError> {
+    user.check_role_permission(Permission::WriteAccount)?;
+    user.check_account_scope()?;
     Ok(Json(
         services
             .account
@@ -242,7 +296,15 @@ async fn list_account_wallets(
     user: User,
     Query(mut filter): Query<WalletFilter>,
 ) -> Result<Json<Vec<Wallet>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadWallet)?;
     filter.account_id = Some(user.account_id);
+    if let Some(wallet_ids) = user.wallet_ids {
+        let ids = match filter.ids {
+            Some(ids) => ids.into_iter().filter(|id| wallet_ids.contains(id)).collect(),
+            None => wallet_ids,
+        };
+        filter.ids = Some(ids);
+    }
     Ok(Json(services.wallet.list(filter).await?))
 }
 
@@ -266,6 +328,8 @@ async fn create_account_wallet(
     user: User,
     Json(payload): Json<CreateWalletRequest>,
 ) -> Result<Json<Wallet>, ApplicationError> {
+    user.check_role_permission(Permission::WriteWallet)?;
+    user.check_account_scope()?;
     Ok(Json(services.wallet.create(user.account_id, payload.asset_id).await?))
 }
 
@@ -287,6 +351,8 @@ async fn get_account_wallet(
     user: User,
     Path(wallet_id): Path<Uuid>,
 ) -> Result<Json<Wallet>, ApplicationError> {
+    user.check_role_permission(Permission::ReadWallet)?;
+    user.check_wallet(wallet_id)?;
     Ok(Json(
         services.wallet.get_by_account_id(user.account_id, wallet_id).await?,
     ))
@@ -314,6 +380,8 @@ async fn new_wallet_btc_address(
     Path(wallet_id): Path<Uuid>,
     Json(payload): Json<NewBtcAddressRequest>,
 ) -> Result<Json<BtcAddress>, ApplicationError> {
+    user.check_role_permission(Permission::WriteBtcAddress)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
     let address = services
@@ -344,6 +412,8 @@ async fn list_wallet_btc_addresses(
     Path(wallet_id): Path<Uuid>,
     Query(mut filter): Query<BtcAddressFilter>,
 ) -> Result<Json<Vec<BtcAddress>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadBtcAddress)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
     filter.wallet_id = Some(wallet_id);
@@ -372,11 +442,22 @@ async fn wallet_pay(
     Path(wallet_id): Path<Uuid>,
     Json(payload): Json<SendPaymentRequest>,
 ) -> Result<Json<Payment>, ApplicationError> {
+    user.check_role_permission(Permission::WriteTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
+    let fee = BtcFeeSelection::new(payload.conf_target, payload.feerate_sat_vb)?;
+    let max_amount_msat = services.auth.payment_step_up_limit(&user).await?;
     let payment = services
         .payment
-        .pay(payload.input, payload.amount_msat, payload.comment, wallet_id)
+        .pay(
+            payload.input,
+            payload.amount_msat,
+            payload.comment,
+            wallet_id,
+            fee,
+            max_amount_msat,
+        )
         .await?;
 
     Ok(Json(payment))
@@ -404,47 +485,57 @@ async fn estimate_wallet_payment_fee(
     Path(wallet_id): Path<Uuid>,
     Json(payload): Json<SendPaymentRequest>,
 ) -> Result<Json<PaymentFeeEstimate>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+    let fee = BtcFeeSelection::new(payload.conf_target, payload.feerate_sat_vb)?;
     let estimate = services
         .payment
-        .estimate_fee(payload.input, payload.amount_msat, payload.comment, wallet_id)
+        .estimate_fee(payload.input, payload.amount_msat, payload.comment, wallet_id, fee)
         .await?;
 
     Ok(Json(estimate))
 }
 
-/// Get wallet balance.
+/// Preview an LNURL-pay request before paying it from an account-owned wallet.
 #[utoipa::path(
-    get,
-    path = "/wallets/{wallet_id}/balance",
+    post,
+    path = "/wallets/{wallet_id}/payments/lnurl-preview",
     tag = "Me",
     context_path = CONTEXT_PATH,
+    request_body = PreviewPaymentRequest,
     responses(
-        (status = 200, description = "Found", body = Balance),
+        (status = 200, description = "LNURL-pay request fetched", body = LnUrlPayPreview),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn get_wallet_balance(
+async fn preview_wallet_lnurl_pay(
     State(services): State<Arc<AppServices>>,
     user: User,
     Path(wallet_id): Path<Uuid>,
-) -> Result<Json<Balance>, ApplicationError> {
+    Json(payload): Json<PreviewPaymentRequest>,
+) -> Result<Json<LnUrlPayPreview>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+    let preview = services.payment.preview_lnurl_pay(payload.input, wallet_id).await?;
 
-    Ok(Json(services.wallet.get_balance(wallet_id).await?))
+    Ok(Json(preview))
 }
 
-/// Generate a new invoice for a wallet.
+/// Transfer from a wallet to another wallet on this instance, without a Lightning invoice.
 #[utoipa::path(
     post,
-    path = "/wallets/{wallet_id}/invoices",
+    path = "/wallets/{wallet_id}/transfers",
     tag = "Me",
     context_path = CONTEXT_PATH,
-    request_body = NewInvoiceRequest,
+    request_body = TransferRequest,
     responses(
-        (status = 200, description = "Invoice Created", body = Invoice),
+        (status = 200, description = "Transfer Settled", body = Payment),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
@@ -452,352 +543,764 @@ async fn get_wallet_balance(
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn new_wallet_invoice(
+async fn wallet_transfer(
     State(services): State<Arc<AppServices>>,
     user: User,
     Path(wallet_id): Path<Uuid>,
-    Json(payload): Json<NewInvoiceRequest>,
-) -> Result<Json<Invoice>, ApplicationError> {
+    Json(payload): Json<TransferRequest>,
+) -> Result<Json<Payment>, ApplicationError> {
+    user.check_role_permission(Permission::WriteTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
-    let invoice = services
-        .invoice
-        .invoice(wallet_id, payload.amount_msat, payload.description, payload.expiry)
-        .await?;
+    let max_amount_msat = services.auth.payment_step_up_limit(&user).await?;
+    let payment = services.payment.transfer(wallet_id, payload, max_amount_msat).await?;
 
-    Ok(Json(invoice))
+    Ok(Json(payment))
 }
 
-/// Get account Lightning Address.
-#[utoipa::path(
-    get,
-    path = "/lightning-address",
-    tag = "Me",
-    context_path = CONTEXT_PATH,
-    responses(
-        (status = 200, description = "Found", body = Option<LnAddress>),
-        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
-        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
-        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
-    )
-)]
-async fn get_account_address(
-    State(services): State<Arc<AppServices>>,
-    user: User,
-) -> Result<Json<Option<LnAddress>>, ApplicationError> {
-    let ln_addresses = services
-        .ln_address
-        .list(LnAddressFilter {
-            account_id: Some(user.account_id),
-            ..Default::default()
-        })
-        .await?;
+/// Finds a pay link of the wallet. Links of other wallets are reported as not found.
+async fn find_wallet_pay_link(services: &AppServices, wallet_id: Uuid, id: Uuid) -> Result<PayLink, ApplicationError> {
+    let pay_link = services.lnurl.get_pay_link(id).await?;
+    if pay_link.wallet_id != wallet_id {
+        return Err(DataError::NotFound("Pay link not found.".to_string()).into());
+    }
 
-    Ok(Json(ln_addresses.into_iter().next()))
+    Ok(pay_link)
 }
 
-/// Register account Lightning Address.
+/// List the pay links of a wallet.
 #[utoipa::path(
-    post,
-    path = "/lightning-address",
+    get,
+    path = "/wallets/{wallet_id}/pay-links",
     tag = "Me",
     context_path = CONTEXT_PATH,
-    request_body = RegisterLnAddressRequest,
+    params(PayLinkFilter),
     responses(
-        (status = 200, description = "LN Address Registered", body = LnAddress),
+        (status = 200, description = "Success", body = Vec<PayLink>),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
-        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn register_account_address(
+async fn list_wallet_pay_links(
     State(services): State<Arc<AppServices>>,
     user: User,
-    Json(payload): Json<RegisterLnAddressRequest>,
-) -> Result<Json<LnAddress>, ApplicationError> {
-    let ln_address = services
-        .ln_address
-        .register(
-            user.account_id,
-            payload.username,
-            payload.allows_nostr,
-            payload.nostr_pubkey,
-        )
-        .await?;
-    Ok(Json(ln_address))
+    Path(wallet_id): Path<Uuid>,
+    Query(mut query_params): Query<PayLinkFilter>,
+) -> Result<Json<Vec<PayLink>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadLnAddress)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    query_params.wallet_id = Some(wallet_id);
+    Ok(Json(services.lnurl.list_pay_links(query_params).await?))
 }
 
-/// Update account Lightning Address.
+/// Register a reusable LNURL-pay link for a wallet, printable as a static QR code.
 #[utoipa::path(
-    put,
-    path = "/lightning-address",
+    post,
+    path = "/wallets/{wallet_id}/pay-links",
     tag = "Me",
     context_path = CONTEXT_PATH,
-    request_body = UpdateLnAddressRequest,
+    request_body = RegisterPayLinkRequest,
     responses(
-        (status = 200, description = "LN Address Updated", body = LnAddress),
+        (status = 200, description = "Pay Link Registered", body = PayLink),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
-        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn update_account_address(
+async fn register_wallet_pay_link(
     State(services): State<Arc<AppServices>>,
     user: User,
-    Json(payload): Json<UpdateLnAddressRequest>,
-) -> Result<Json<LnAddress>, ApplicationError> {
-    let ln_addresses = services
-        .ln_address
-        .list(LnAddressFilter {
-            account_id: Some(user.account_id),
-            ..Default::default()
-        })
-        .await?;
+    Path(wallet_id): Path<Uuid>,
+    Json(payload): Json<RegisterPayLinkRequest>,
+) -> Result<Json<PayLink>, ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
-    let ln_address = ln_addresses
-        .first()
-        .cloned()
-        .ok_or_else(|| DataError::NotFound("LN Address not found.".to_string()))?;
+    let pay_link = services.lnurl.register_pay_link(wallet_id, payload).await?;
 
-    Ok(Json(services.ln_address.update(ln_address.id, payload).await?))
+    Ok(Json(pay_link))
 }
 
-/// Delete account Lightning Address.
+/// Get a wallet pay link.
 #[utoipa::path(
-    delete,
-    path = "/lightning-address",
+    get,
+    path = "/wallets/{wallet_id}/pay-links/{id}",
     tag = "Me",
     context_path = CONTEXT_PATH,
     responses(
-        (status = 200, description = "Deleted"),
+        (status = 200, description = "Found", body = PayLink),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn delete_account_address(State(services): State<Arc<AppServices>>, user: User) -> Result<(), ApplicationError> {
-    let n_deleted = services
-        .ln_address
-        .delete_many(LnAddressFilter {
-            account_id: Some(user.account_id),
-            ..Default::default()
-        })
-        .await?;
+async fn get_wallet_pay_link(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path((wallet_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<PayLink>, ApplicationError> {
+    user.check_role_permission(Permission::ReadLnAddress)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
-    if n_deleted == 0 {
-        return Err(DataError::NotFound("Lightning address not found.".to_string()).into());
-    }
+    let pay_link = find_wallet_pay_link(&services, wallet_id, id).await?;
 
-    Ok(())
+    Ok(Json(pay_link))
 }
 
-/// List payments for a wallet.
+/// Update a wallet pay link.
 #[utoipa::path(
-    get,
-    path = "/wallets/{wallet_id}/payments",
+    put,
+    path = "/wallets/{wallet_id}/pay-links/{id}",
     tag = "Me",
     context_path = CONTEXT_PATH,
-    params(PaymentFilter),
+    request_body = UpdatePayLinkRequest,
     responses(
-        (status = 200, description = "Success", body = Vec<Payment>),
+        (status = 200, description = "Pay Link Updated", body = PayLink),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 409, description = "Duplicate", body = ErrorResponse, example = json!(CONFLICT_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn list_wallet_payments(
+async fn update_wallet_pay_link(
     State(services): State<Arc<AppServices>>,
     user: User,
-    Path(wallet_id): Path<Uuid>,
-    Query(mut query_params): Query<PaymentFilter>,
-) -> Result<Json<Vec<Payment>>, ApplicationError> {
+    Path((wallet_id, id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdatePayLinkRequest>,
+) -> Result<Json<PayLink>, ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+    find_wallet_pay_link(&services, wallet_id, id).await?;
 
-    query_params.wallet_id = Some(wallet_id);
-    Ok(Json(services.payment.list(query_params).await?))
+    let pay_link = services.lnurl.update_pay_link(id, payload).await?;
+
+    Ok(Json(pay_link))
 }
 
-/// Get a wallet payment.
+/// Delete a wallet pay link. Returns an empty body.
 #[utoipa::path(
-    get,
-    path = "/wallets/{wallet_id}/payments/{id}",
+    delete,
+    path = "/wallets/{wallet_id}/pay-links/{id}",
     tag = "Me",
     context_path = CONTEXT_PATH,
     responses(
-        (status = 200, description = "Found", body = Payment),
+        (status = 200, description = "Deleted"),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn get_wallet_payment(
+async fn delete_wallet_pay_link(
     State(services): State<Arc<AppServices>>,
     user: User,
     Path((wallet_id, id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<Payment>, ApplicationError> {
+) -> Result<(), ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+    find_wallet_pay_link(&services, wallet_id, id).await?;
 
-    let payments = services
-        .payment
-        .list(PaymentFilter {
-            wallet_id: Some(wallet_id),
-            ids: Some(vec![id]),
-            ..Default::default()
-        })
-        .await?;
-
-    let payment = payments
-        .first()
-        .cloned()
-        .ok_or_else(|| DataError::NotFound("Payment not found.".to_string()))?;
+    services.lnurl.delete_pay_link(id).await?;
 
-    Ok(Json(payment))
+    Ok(())
 }
 
-/// List invoices for a wallet.
+/// Get wallet balance.
 #[utoipa::path(
     get,
-    path = "/wallets/{wallet_id}/invoices",
+    path = "/wallets/{wallet_id}/balance",
     tag = "Me",
     context_path = CONTEXT_PATH,
-    params(InvoiceFilter),
     responses(
-        (status = 200, description = "Success", body = Vec<Invoice>),
-        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 200, description = "Found", body = Balance),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn list_wallet_invoices(
+async fn get_wallet_balance(
     State(services): State<Arc<AppServices>>,
     user: User,
     Path(wallet_id): Path<Uuid>,
-    Query(mut query_params): Query<InvoiceFilter>,
-) -> Result<Json<Vec<Invoice>>, ApplicationError> {
+) -> Result<Json<Balance>, ApplicationError> {
+    user.check_role_permission(Permission::ReadWallet)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
-    query_params.wallet_id = Some(wallet_id);
-    Ok(Json(services.invoice.list(query_params).await?))
+    Ok(Json(services.wallet.get_balance(wallet_id).await?))
 }
 
-/// Get a wallet invoice.
+/// Generate a new invoice for a wallet.
 #[utoipa::path(
-    get,
-    path = "/wallets/{wallet_id}/invoices/{id}",
+    post,
+    path = "/wallets/{wallet_id}/invoices",
     tag = "Me",
     context_path = CONTEXT_PATH,
+    request_body = NewInvoiceRequest,
     responses(
-        (status = 200, description = "Found", body = Invoice),
+        (status = 200, description = "Invoice Created", body = Invoice),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn get_wallet_invoice(
+async fn new_wallet_invoice(
     State(services): State<Arc<AppServices>>,
     user: User,
-    Path((wallet_id, id)): Path<(Uuid, Uuid)>,
+    Path(wallet_id): Path<Uuid>,
+    Json(payload): Json<NewInvoiceRequest>,
 ) -> Result<Json<Invoice>, ApplicationError> {
+    user.check_role_permission(Permission::WriteTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
-    let invoices = services
+    let invoice = services
         .invoice
-        .list(InvoiceFilter {
-            wallet_id: Some(wallet_id),
-            ids: Some(vec![id]),
-            ..Default::default()
-        })
+        .invoice(wallet_id, payload.amount_msat, payload.description, payload.expiry)
         .await?;
 
-    let invoice = invoices
-        .first()
-        .cloned()
-        .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?;
-
     Ok(Json(invoice))
 }
 
-/// List contacts for a wallet.
+/// Generate a unified payment request for a wallet, payable over Lightning or on-chain.
 #[utoipa::path(
-    get,
-    path = "/wallets/{wallet_id}/contacts",
+    post,
+    path = "/wallets/{wallet_id}/invoices/unified",
     tag = "Me",
     context_path = CONTEXT_PATH,
+    request_body = NewPaymentRequest,
     responses(
-        (status = 200, description = "Success", body = Vec<Contact>),
+        (status = 200, description = "Payment Request Created", body = PaymentRequest),
         (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn list_contacts(
+async fn new_wallet_payment_request(
     State(services): State<Arc<AppServices>>,
     user: User,
     Path(wallet_id): Path<Uuid>,
-) -> Result<Json<Vec<Contact>>, ApplicationError> {
+    Json(payload): Json<NewPaymentRequest>,
+) -> Result<Json<PaymentRequest>, ApplicationError> {
+    user.check_role_permission(Permission::WriteTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
-    Ok(Json(services.wallet.list_contacts(wallet_id).await?))
+    let payment_request = services
+        .invoice
+        .payment_request(
+            wallet_id,
+            payload.amount_msat,
+            payload.description,
+            payload.expiry,
+            payload.address_type,
+        )
+        .await?;
+
+    Ok(Json(payment_request))
 }
 
-/// Delete expired invoices for a wallet.
+/// Get account Lightning Address.
+///
+/// Returns the oldest address of the account. Use `/lightning-addresses` for accounts holding several.
 #[utoipa::path(
-    delete,
-    path = "/wallets/{wallet_id}/invoices",
+    get,
+    path = "/lightning-address",
     tag = "Me",
     context_path = CONTEXT_PATH,
     responses(
-        (status = 200, description = "Success", body = u64),
+        (status = 200, description = "Found", body = Option<LnAddress>),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
         (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn delete_expired_invoices(
+async fn get_account_address(
     State(services): State<Arc<AppServices>>,
     user: User,
-    Path(wallet_id): Path<Uuid>,
-) -> Result<Json<u64>, ApplicationError> {
-    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
-
-    let n_deleted = services
-        .invoice
-        .delete_many(InvoiceFilter {
-            wallet_id: Some(wallet_id),
-            status: Some(InvoiceStatus::Expired),
-            ..Default::default()
-        })
-        .await?;
-    Ok(Json(n_deleted))
+) -> Result<Json<Option<LnAddress>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadLnAddress)?;
+    Ok(Json(oldest_account_address(&services, user.account_id).await?))
 }
 
-/// Delete failed payments for a wallet.
+/// Register account Lightning Address.
 #[utoipa::path(
-    delete,
-    path = "/wallets/{wallet_id}/payments",
+    post,
+    path = "/lightning-address",
     tag = "Me",
     context_path = CONTEXT_PATH,
+    request_body = RegisterLnAddressRequest,
     responses(
-        (status = 200, description = "Success", body = u64),
+        (status = 200, description = "LN Address Registered", body = LnAddress),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
         (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
-        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
         (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
     )
 )]
-async fn delete_failed_payments(
+async fn register_account_address(
     State(services): State<Arc<AppServices>>,
     user: User,
-    Path(wallet_id): Path<Uuid>,
-) -> Result<Json<u64>, ApplicationError> {
+    Json(payload): Json<RegisterLnAddressRequest>,
+) -> Result<Json<LnAddress>, ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_account_scope()?;
+    let ln_address = services.ln_address.register(user.account_id, payload).await?;
+    Ok(Json(ln_address))
+}
+
+/// Update account Lightning Address.
+///
+/// Updates the oldest address of the account.
+#[utoipa::path(
+    put,
+    path = "/lightning-address",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    request_body = UpdateLnAddressRequest,
+    responses(
+        (status = 200, description = "LN Address Updated", body = LnAddress),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn update_account_address(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<UpdateLnAddressRequest>,
+) -> Result<Json<LnAddress>, ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_account_scope()?;
+    let ln_address = oldest_account_address(&services, user.account_id)
+        .await?
+        .ok_or_else(|| DataError::NotFound("LN Address not found.".to_string()))?;
+
+    Ok(Json(services.ln_address.update(ln_address.id, payload).await?))
+}
+
+/// Delete account Lightning Address.
+#[utoipa::path(
+    delete,
+    path = "/lightning-address",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_account_address(State(services): State<Arc<AppServices>>, user: User) -> Result<(), ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_account_scope()?;
+    let ln_address = oldest_account_address(&services, user.account_id)
+        .await?
+        .ok_or_else(|| DataError::NotFound("Lightning address not found.".to_string()))?;
+
+    services.ln_address.delete(ln_address.id).await
+}
+
+/// List account Lightning Addresses.
+#[utoipa::path(
+    get,
+    path = "/lightning-addresses",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    params(LnAddressFilter),
+    responses(
+        (status = 200, description = "Success", body = Vec<LnAddress>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_account_addresses(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Query(mut query_params): Query<LnAddressFilter>,
+) -> Result<Json<Vec<LnAddress>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadLnAddress)?;
+    query_params.account_id = Some(user.account_id);
+    Ok(Json(services.ln_address.list(query_params).await?))
+}
+
+/// Register an additional account Lightning Address.
+///
+/// The address receives into `wallet_id`, or into the native BTC wallet of the account when omitted.
+#[utoipa::path(
+    post,
+    path = "/lightning-addresses",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    request_body = RegisterLnAddressRequest,
+    responses(
+        (status = 200, description = "LN Address Registered", body = LnAddress),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn register_account_addresses(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Json(payload): Json<RegisterLnAddressRequest>,
+) -> Result<Json<LnAddress>, ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_account_scope()?;
+    let ln_address = services.ln_address.register(user.account_id, payload).await?;
+    Ok(Json(ln_address))
+}
+
+/// Get an account Lightning Address.
+#[utoipa::path(
+    get,
+    path = "/lightning-addresses/{id}",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Found", body = LnAddress),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn get_account_address_by_id(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<Json<LnAddress>, ApplicationError> {
+    user.check_role_permission(Permission::ReadLnAddress)?;
+    Ok(Json(owned_account_address(&services, user.account_id, id).await?))
+}
+
+/// Update an account Lightning Address.
+#[utoipa::path(
+    put,
+    path = "/lightning-addresses/{id}",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    request_body = UpdateLnAddressRequest,
+    responses(
+        (status = 200, description = "LN Address Updated", body = LnAddress),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn update_account_address_by_id(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateLnAddressRequest>,
+) -> Result<Json<LnAddress>, ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_account_scope()?;
+    let ln_address = owned_account_address(&services, user.account_id, id).await?;
+    Ok(Json(services.ln_address.update(ln_address.id, payload).await?))
+}
+
+/// Delete an account Lightning Address.
+#[utoipa::path(
+    delete,
+    path = "/lightning-addresses/{id}",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Deleted"),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_account_address_by_id(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+) -> Result<(), ApplicationError> {
+    user.check_role_permission(Permission::WriteLnAddress)?;
+    user.check_account_scope()?;
+    let ln_address = owned_account_address(&services, user.account_id, id).await?;
+    services.ln_address.delete(ln_address.id).await
+}
+
+async fn oldest_account_address(
+    services: &AppServices,
+    account_id: Uuid,
+) -> Result<Option<LnAddress>, ApplicationError> {
+    let ln_addresses = services
+        .ln_address
+        .list(LnAddressFilter {
+            account_id: Some(account_id),
+            limit: Some(1),
+            order_direction: OrderDirection::Asc,
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(ln_addresses.into_iter().next())
+}
+
+/// Addresses of other accounts are reported as missing rather than forbidden.
+async fn owned_account_address(
+    services: &AppServices,
+    account_id: Uuid,
+    id: Uuid,
+) -> Result<LnAddress, ApplicationError> {
+    let ln_address = services.ln_address.get(id).await?;
+    if ln_address.account_id != account_id {
+        return Err(DataError::NotFound("LN Address not found.".to_string()).into());
+    }
+
+    Ok(ln_address)
+}
+
+/// List payments for a wallet.
+#[utoipa::path(
+    get,
+    path = "/wallets/{wallet_id}/payments",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    params(PaymentFilter),
+    responses(
+        (status = 200, description = "Success", body = Vec<Payment>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_wallet_payments(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(wallet_id): Path<Uuid>,
+    Query(mut query_params): Query<PaymentFilter>,
+) -> Result<Json<Vec<Payment>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    query_params.wallet_id = Some(wallet_id);
+    Ok(Json(services.payment.list(query_params).await?))
+}
+
+/// Get a wallet payment.
+#[utoipa::path(
+    get,
+    path = "/wallets/{wallet_id}/payments/{id}",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Found", body = Payment),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn get_wallet_payment(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path((wallet_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Payment>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    let payments = services
+        .payment
+        .list(PaymentFilter {
+            wallet_id: Some(wallet_id),
+            ids: Some(vec![id]),
+            ..Default::default()
+        })
+        .await?;
+
+    let payment = payments
+        .first()
+        .cloned()
+        .ok_or_else(|| DataError::NotFound("Payment not found.".to_string()))?;
+
+    Ok(Json(payment))
+}
+
+/// List invoices for a wallet.
+#[utoipa::path(
+    get,
+    path = "/wallets/{wallet_id}/invoices",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    params(InvoiceFilter),
+    responses(
+        (status = 200, description = "Success", body = Vec<Invoice>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_wallet_invoices(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(wallet_id): Path<Uuid>,
+    Query(mut query_params): Query<InvoiceFilter>,
+) -> Result<Json<Vec<Invoice>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    query_params.wallet_id = Some(wallet_id);
+    Ok(Json(services.invoice.list(query_params).await?))
+}
+
+/// Get a wallet invoice.
+#[utoipa::path(
+    get,
+    path = "/wallets/{wallet_id}/invoices/{id}",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Found", body = Invoice),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn get_wallet_invoice(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path((wallet_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Invoice>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    let invoices = services
+        .invoice
+        .list(InvoiceFilter {
+            wallet_id: Some(wallet_id),
+            ids: Some(vec![id]),
+            ..Default::default()
+        })
+        .await?;
+
+    let invoice = invoices
+        .first()
+        .cloned()
+        .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?;
+
+    Ok(Json(invoice))
+}
+
+/// List contacts for a wallet.
+#[utoipa::path(
+    get,
+    path = "/wallets/{wallet_id}/contacts",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = Vec<Contact>),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn list_contacts(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(wallet_id): Path<Uuid>,
+) -> Result<Json<Vec<Contact>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadTransaction)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    Ok(Json(services.wallet.list_contacts(wallet_id).await?))
+}
+
+/// Delete expired invoices for a wallet.
+#[utoipa::path(
+    delete,
+    path = "/wallets/{wallet_id}/invoices",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = u64),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_expired_invoices(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(wallet_id): Path<Uuid>,
+) -> Result<Json<u64>, ApplicationError> {
+    user.check_role_permission(Permission::WriteTransaction)?;
+    user.check_wallet(wallet_id)?;
+    services.wallet.verify_ownership(user.account_id, wallet_id).await?;
+
+    let n_deleted = services
+        .invoice
+        .delete_many(InvoiceFilter {
+            wallet_id: Some(wallet_id),
+            status: Some(InvoiceStatus::Expired),
+            ..Default::default()
+        })
+        .await?;
+    Ok(Json(n_deleted))
+}
+
+/// Delete failed payments for a wallet.
+#[utoipa::path(
+    delete,
+    path = "/wallets/{wallet_id}/payments",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    responses(
+        (status = 200, description = "Success", body = u64),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn delete_failed_payments(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(wallet_id): Path<Uuid>,
+) -> Result<Json<u64>, ApplicationError> {
+    user.check_role_permission(Permission::WriteTransaction)?;
+    user.check_wallet(wallet_id)?;
     services.wallet.verify_ownership(user.account_id, wallet_id).await?;
 
     let n_deleted = services
@@ -834,6 +1337,9 @@ async fn create_account_api_key(
     user: User,
     Json(mut payload): Json<CreateApiKeyRequest>,
 ) -> Result<Json<ApiKey>, ApplicationError> {
+    user.check_role_permission(Permission::WriteApiKey)?;
+    user.check_account_scope()?;
+    services.auth.check_step_up(&user).await?;
     payload.account_id = Some(user.account_id);
     let api_key = services.api_key.generate(user, payload).await?;
     Ok(Json(api_key))
@@ -858,6 +1364,8 @@ async fn get_account_api_key(
     user: User,
     Path(id): Path<Uuid>,
 ) -> Result<Json<ApiKey>, ApplicationError> {
+    user.check_role_permission(Permission::ReadApiKey)?;
+    user.check_account_scope()?;
     let api_keys = services
         .api_key
         .list(ApiKeyFilter {
@@ -894,6 +1402,8 @@ async fn list_account_api_keys(
     user: User,
     Query(mut filter): Query<ApiKeyFilter>,
 ) -> Result<Json<Vec<ApiKey>>, ApplicationError> {
+    user.check_role_permission(Permission::ReadApiKey)?;
+    user.check_account_scope()?;
     filter.account_id = Some(user.account_id);
     let api_keys = services.api_key.list(filter).await?;
 
@@ -919,6 +1429,8 @@ async fn revoke_account_api_key(
     user: User,
     Path(id): Path<Uuid>,
 ) -> Result<(), ApplicationError> {
+    user.check_role_permission(Permission::WriteApiKey)?;
+    user.check_account_scope()?;
     let n_revoked = services
         .api_key
         .revoke_many(ApiKeyFilter {
@@ -954,18 +1466,68 @@ async fn revoke_account_api_keys(
     user: User,
     Query(mut filter): Query<ApiKeyFilter>,
 ) -> Result<Json<u64>, ApplicationError> {
+    user.check_role_permission(Permission::WriteApiKey)?;
+    user.check_account_scope()?;
     filter.account_id = Some(user.account_id);
     let n_revoked = services.api_key.revoke_many(filter).await?;
     Ok(n_revoked.into())
 }
 
+/// Rotate an account API key.
+///
+/// Issues a replacement with the same name, permissions and wallets. The rotated key keeps working for the
+/// requested grace period.
+#[utoipa::path(
+    post,
+    path = "/api-keys/{id}/rotate",
+    tag = "Me",
+    context_path = CONTEXT_PATH,
+    request_body = RotateApiKeyRequest,
+    responses(
+        (status = 200, description = "API Key Rotated", body = ApiKey),
+        (status = 400, description = "Bad Request", body = ErrorResponse, example = json!(BAD_REQUEST_EXAMPLE)),
+        (status = 401, description = "Unauthorized", body = ErrorResponse, example = json!(UNAUTHORIZED_EXAMPLE)),
+        (status = 404, description = "Not Found", body = ErrorResponse, example = json!(NOT_FOUND_EXAMPLE)),
+        (status = 422, description = "Unprocessable Entity", body = ErrorResponse, example = json!(UNPROCESSABLE_EXAMPLE)),
+        (status = 500, description = "Internal Server Error", body = ErrorResponse, example = json!(INTERNAL_EXAMPLE))
+    )
+)]
+async fn rotate_account_api_key(
+    State(services): State<Arc<AppServices>>,
+    user: User,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RotateApiKeyRequest>,
+) -> Result<Json<ApiKey>, ApplicationError> {
+    user.check_role_permission(Permission::WriteApiKey)?;
+    user.check_account_scope()?;
+    let owned = services
+        .api_key
+        .list(ApiKeyFilter {
+            account_id: Some(user.account_id),
+            ids: Some(vec![id]),
+            ..Default::default()
+        })
+        .await?;
+    if owned.is_empty() {
+        return Err(DataError::NotFound("API Key not found.".to_string()).into());
+    }
+    services.auth.check_step_up(&user).await?;
+
+    let api_key = services.api_key.rotate(user, id, payload).await?;
+    Ok(Json(api_key))
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
 
     use crate::{
-        application::composition::MockAppServicesBuilder,
+        application::{
+            composition::{MockAppServicesBuilder, MockAppStoreBuilder},
+            errors::AuthorizationError,
+        },
         domains::{
+            account::{ApiKeyService, OrganizationMember, OrganizationRole},
             bitcoin::{BtcAddress, BtcAddressType},
             payment::Payment,
         },
@@ -977,6 +1539,11 @@ mod tests {
         User {
             account_id: Uuid::new_v4(),
             permissions: vec![],
+            wallet_ids: None,
+            session_id: None,
+            membership: None,
+            api_key_id: None,
+            subject: None,
         }
     }
 
@@ -987,6 +1554,8 @@ mod tests {
             address: "bcrt1qexample".to_string(),
             used: false,
             address_type: BtcAddressType::P2wpkh,
+            payjoin_uri: None,
+            invoice_id: None,
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -1000,6 +1569,8 @@ mod tests {
             maximum_fee_msat: 100,
             estimated_total_msat: Some(1_010),
             maximum_total_msat: 1_100,
+            onchain_fees: vec![],
+            lnurl_pay: None,
         }
     }
 
@@ -1038,6 +1609,67 @@ mod tests {
         }
     }
 
+    mod account_addresses {
+        use super::*;
+
+        fn ln_address(account_id: Uuid) -> LnAddress {
+            LnAddress {
+                id: Uuid::new_v4(),
+                account_id,
+                username: "alice".to_string(),
+                ..Default::default()
+            }
+        }
+
+        #[tokio::test]
+        async fn hides_addresses_of_other_accounts() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .ln_address
+                .expect_get()
+                .times(1)
+                .returning(|_| Ok(ln_address(Uuid::new_v4())));
+            builder.ln_address.expect_delete().never();
+
+            let result =
+                super::delete_account_address_by_id(State(Arc::new(builder.build())), user(), Path(Uuid::new_v4()))
+                    .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+
+        #[tokio::test]
+        async fn singular_route_deletes_only_the_oldest_address() {
+            let caller = user();
+            let account_id = caller.account_id;
+            let oldest = ln_address(account_id);
+            let oldest_id = oldest.id;
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .ln_address
+                .expect_list()
+                .withf(move |filter| {
+                    filter.account_id == Some(account_id)
+                        && filter.limit == Some(1)
+                        && filter.order_direction == OrderDirection::Asc
+                })
+                .times(1)
+                .returning(move |_| Ok(vec![oldest.clone()]));
+            builder
+                .ln_address
+                .expect_delete()
+                .withf(move |id| *id == oldest_id)
+                .times(1)
+                .returning(|_| Ok(()));
+            builder.ln_address.expect_delete_many().never();
+
+            let result = super::delete_account_address(State(Arc::new(builder.build())), caller).await;
+
+            assert!(result.is_ok());
+        }
+    }
+
     mod get_account {
         use super::*;
 
@@ -1083,18 +1715,25 @@ mod tests {
                 .withf(move |account, id| *account == account_id && *id == wallet_id)
                 .times(1)
                 .returning(|_, _| Ok(()));
+            builder
+                .auth
+                .expect_payment_step_up_limit()
+                .times(1)
+                .returning(|_| Ok(Some(5_000)));
             builder
                 .payment
                 .expect_pay()
-                .withf(move |_, _, _, id| *id == wallet_id)
+                .withf(move |_, _, _, id, _, max_amount_msat| *id == wallet_id && *max_amount_msat == Some(5_000))
                 .times(1)
-                .returning(|_, _, _, _| Ok(Payment::default()));
+                .returning(|_, _, _, _, _, _| Ok(Payment::default()));
 
             let payload = SendPaymentRequest {
                 wallet_id: None,
                 input: "bob@numeraire.tech".to_string(),
                 amount_msat: Some(1_000),
                 comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
             };
 
             let result =
@@ -1122,6 +1761,8 @@ mod tests {
                 input: "bob@numeraire.tech".to_string(),
                 amount_msat: Some(1_000),
                 comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
             };
 
             let result =
@@ -1129,6 +1770,229 @@ mod tests {
 
             assert!(matches!(result, Err(ApplicationError::Data(_))));
         }
+
+        #[tokio::test]
+        async fn rejects_wallets_outside_the_credentials_scope() {
+            let caller = User {
+                wallet_ids: Some(vec![Uuid::new_v4()]),
+                ..user()
+            };
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder.wallet.expect_verify_ownership().never();
+
+            let payload = SendPaymentRequest {
+                wallet_id: None,
+                input: "bob@numeraire.tech".to_string(),
+                amount_msat: Some(1_000),
+                comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
+            };
+
+            let result = super::wallet_pay(
+                State(Arc::new(builder.build())),
+                caller,
+                Path(Uuid::new_v4()),
+                Json(payload),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::WalletNotAllowed(_)))
+            ));
+        }
+
+        #[tokio::test]
+        async fn rejects_organization_viewers() {
+            let caller = user();
+            let caller = User {
+                membership: Some(OrganizationMember {
+                    organization_id: caller.account_id,
+                    account_id: Uuid::new_v4(),
+                    role: OrganizationRole::Viewer,
+                    created_at: Utc::now(),
+                    updated_at: None,
+                }),
+                ..caller
+            };
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder.wallet.expect_verify_ownership().never();
+            builder.payment.expect_pay().never();
+
+            let payload = SendPaymentRequest {
+                wallet_id: None,
+                input: "bob@numeraire.tech".to_string(),
+                amount_msat: Some(1_000),
+                comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
+            };
+
+            let result = super::wallet_pay(
+                State(Arc::new(builder.build())),
+                caller,
+                Path(Uuid::new_v4()),
+                Json(payload),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::MissingPermission(
+                    Permission::WriteTransaction
+                )))
+            ));
+        }
+    }
+
+    mod wallet_scoped_credentials {
+        use super::*;
+
+        #[tokio::test]
+        async fn only_list_their_wallets() {
+            let allowed = Uuid::new_v4();
+            let caller = User {
+                wallet_ids: Some(vec![allowed]),
+                ..user()
+            };
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .wallet
+                .expect_list()
+                .withf(move |filter| filter.ids == Some(vec![allowed]))
+                .times(1)
+                .returning(|_| Ok(vec![]));
+
+            let result = super::list_account_wallets(
+                State(Arc::new(builder.build())),
+                caller,
+                Query(WalletFilter {
+                    ids: Some(vec![allowed, Uuid::new_v4()]),
+                    ..Default::default()
+                }),
+            )
+            .await;
+
+            assert!(result.is_ok());
+        }
+
+        #[tokio::test]
+        async fn cannot_create_wallets() {
+            let caller = User {
+                wallet_ids: Some(vec![Uuid::new_v4()]),
+                ..user()
+            };
+
+            let result = super::create_account_wallet(
+                State(Arc::new(MockAppServicesBuilder::new().build())),
+                caller,
+                Json(CreateWalletRequest {
+                    account_id: None,
+                    asset_id: Uuid::new_v4(),
+                }),
+            )
+            .await;
+
+            assert!(matches!(
+                result,
+                Err(ApplicationError::Authorization(AuthorizationError::WalletScoped))
+            ));
+        }
+    }
+
+    mod rotate_account_api_key {
+        use super::*;
+
+        #[tokio::test]
+        async fn returns_not_found_for_keys_of_other_accounts() {
+            let caller = user();
+            let account_id = caller.account_id;
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .api_key
+                .expect_list()
+                .withf(move |filter| filter.account_id == Some(account_id))
+                .times(1)
+                .returning(|_| Ok(vec![]));
+            builder.api_key.expect_rotate().never();
+
+            let result = super::rotate_account_api_key(
+                State(Arc::new(builder.build())),
+                caller,
+                Path(Uuid::new_v4()),
+                Json(RotateApiKeyRequest::default()),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+
+        #[tokio::test]
+        async fn binds_keys_an_admin_rotates_to_the_admin_rather_than_their_owner_issuer() {
+            let caller = user();
+            let organization_id = caller.account_id;
+            let admin_account_id = Uuid::new_v4();
+            let caller = User {
+                permissions: vec![Permission::ReadWallet],
+                membership: Some(OrganizationMember {
+                    organization_id,
+                    account_id: admin_account_id,
+                    role: OrganizationRole::Admin,
+                    created_at: Utc::now(),
+                    updated_at: None,
+                }),
+                ..caller
+            };
+            let owner_issued = ApiKey {
+                id: Uuid::new_v4(),
+                account_id: organization_id,
+                name: "treasury".to_string(),
+                permissions: vec![Permission::ReadWallet],
+                member_account_id: Some(Uuid::new_v4()),
+                created_at: Utc::now(),
+                ..Default::default()
+            };
+            let id = owner_issued.id;
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder.auth.expect_check_step_up().times(1).returning(|_| Ok(()));
+            let mut store = MockAppStoreBuilder::new();
+            let found = owner_issued.clone();
+            store
+                .api_key
+                .expect_find_many()
+                .times(1)
+                .returning(move |_| Ok(vec![found.clone()]));
+            let found = owner_issued.clone();
+            store
+                .api_key
+                .expect_find()
+                .times(1)
+                .returning(move |_| Ok(Some(found.clone())));
+            store
+                .api_key
+                .expect_rotate()
+                .times(1)
+                .returning(|_, replacement, _| Ok(Some(replacement)));
+            let mut services = builder.build();
+            services.api_key = Box::new(ApiKeyService::new(store.build()));
+
+            let Json(replacement) = super::rotate_account_api_key(
+                State(Arc::new(services)),
+                caller,
+                Path(id),
+                Json(RotateApiKeyRequest::default()),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(replacement.member_account_id, Some(admin_account_id));
+        }
     }
 
     mod estimate_wallet_payment_fee {
@@ -1150,15 +2014,17 @@ mod tests {
             builder
                 .payment
                 .expect_estimate_fee()
-                .withf(move |_, _, _, id| *id == wallet_id)
+                .withf(move |_, _, _, id, _| *id == wallet_id)
                 .times(1)
-                .returning(|_, _, _, _| Ok(fee_estimate()));
+                .returning(|_, _, _, _, _| Ok(fee_estimate()));
 
             let payload = SendPaymentRequest {
                 wallet_id: Some(Uuid::new_v4()),
                 input: "bob@numeraire.tech".to_string(),
                 amount_msat: Some(1_000),
                 comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
             };
 
             let result = super::estimate_wallet_payment_fee(
@@ -1189,6 +2055,8 @@ mod tests {
                 input: "bob@numeraire.tech".to_string(),
                 amount_msat: Some(1_000),
                 comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
             };
 
             let result = super::estimate_wallet_payment_fee(
@@ -1203,6 +2071,78 @@ mod tests {
         }
     }
 
+    mod preview_wallet_lnurl_pay {
+        use super::*;
+
+        #[tokio::test]
+        async fn previews_for_the_path_wallet_after_ownership_check() {
+            let caller = user();
+            let account_id = caller.account_id;
+            let wallet_id = Uuid::new_v4();
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .wallet
+                .expect_verify_ownership()
+                .withf(move |account, id| *account == account_id && *id == wallet_id)
+                .times(1)
+                .returning(|_, _| Ok(()));
+            builder
+                .payment
+                .expect_preview_lnurl_pay()
+                .withf(move |input, id| input == "bob@example.com" && *id == wallet_id)
+                .times(1)
+                .returning(|_, _| {
+                    Ok(LnUrlPayPreview {
+                        min_sendable: 1_000,
+                        max_sendable: 5_000,
+                        ..Default::default()
+                    })
+                });
+
+            let payload = PreviewPaymentRequest {
+                wallet_id: Some(Uuid::new_v4()),
+                input: "bob@example.com".to_string(),
+            };
+
+            let result = super::preview_wallet_lnurl_pay(
+                State(Arc::new(builder.build())),
+                caller,
+                Path(wallet_id),
+                Json(payload),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(result.0.max_sendable, 5_000);
+        }
+
+        #[tokio::test]
+        async fn rejects_wallets_outside_the_account_scope() {
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .wallet
+                .expect_verify_ownership()
+                .times(1)
+                .returning(|_, _| Err(DataError::NotFound("Wallet not found.".to_string()).into()));
+
+            let payload = PreviewPaymentRequest {
+                wallet_id: None,
+                input: "bob@example.com".to_string(),
+            };
+
+            let result = super::preview_wallet_lnurl_pay(
+                State(Arc::new(builder.build())),
+                user(),
+                Path(Uuid::new_v4()),
+                Json(payload),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(_))));
+        }
+    }
+
     mod new_wallet_btc_address {
         use super::*;
 
@@ -1238,4 +2178,82 @@ mod tests {
             assert!(result.is_ok());
         }
     }
+
+    mod wallet_pay_links {
+        use super::*;
+
+        #[tokio::test]
+        async fn hides_links_of_other_wallets() {
+            let caller = user();
+            let wallet_id = Uuid::new_v4();
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .wallet
+                .expect_verify_ownership()
+                .times(1)
+                .returning(|_, _| Ok(()));
+            builder.lnurl.expect_get_pay_link().times(1).returning(|id| {
+                Ok(PayLink {
+                    id,
+                    wallet_id: Uuid::new_v4(),
+                    ..Default::default()
+                })
+            });
+            builder.lnurl.expect_delete_pay_link().never();
+
+            let result = super::delete_wallet_pay_link(
+                State(Arc::new(builder.build())),
+                caller,
+                Path((wallet_id, Uuid::new_v4())),
+            )
+            .await;
+
+            assert!(matches!(result, Err(ApplicationError::Data(DataError::NotFound(_)))));
+        }
+
+        #[tokio::test]
+        async fn registers_links_on_the_path_wallet() {
+            let caller = user();
+            let wallet_id = Uuid::new_v4();
+
+            let mut builder = MockAppServicesBuilder::new();
+            builder
+                .wallet
+                .expect_verify_ownership()
+                .withf(move |_, id| *id == wallet_id)
+                .times(1)
+                .returning(|_, _| Ok(()));
+            builder
+                .lnurl
+                .expect_register_pay_link()
+                .withf(move |id, _| *id == wallet_id)
+                .times(1)
+                .returning(|wallet_id, _| {
+                    Ok(PayLink {
+                        wallet_id,
+                        ..Default::default()
+                    })
+                });
+
+            let payload = RegisterPayLinkRequest {
+                wallet_id: None,
+                name: "Register 3".to_string(),
+                description: None,
+                amount_msat: None,
+                reference: None,
+            };
+
+            let result = super::register_wallet_pay_link(
+                State(Arc::new(builder.build())),
+                caller,
+                Path(wallet_id),
+                Json(payload),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(result.0.wallet_id, wallet_id);
+        }
+    }
 }
@@ -1,6 +1,9 @@
-use crate::application::{
-    composition::AppStore,
-    errors::{ApplicationError, DataError},
+use crate::{
+    application::{
+        composition::AppStore,
+        errors::{ApplicationError, DataError},
+    },
+    domains::audit::record_deleted,
 };
 use async_trait::async_trait;
 use tracing::{debug, info, trace};
@@ -125,6 +128,12 @@ impl WalletUseCases for WalletService {
     async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
         debug!(%id, "Deleting wallet");
 
+        let wallet = self
+            .store
+            .wallet
+            .find(id)
+            .await?
+            .ok_or_else(|| DataError::NotFound("Wallet not found.".to_string()))?;
         let n_deleted = self
             .store
             .wallet
@@ -137,6 +146,7 @@ impl WalletUseCases for WalletService {
         if n_deleted == 0 {
             return Err(DataError::NotFound("Wallet not found.".to_string()).into());
         }
+        record_deleted(&wallet);
 
         info!(%id, "Wallet deleted successfully");
         Ok(())
@@ -477,6 +487,11 @@ mod tests {
             #[tokio::test]
             async fn succeeds() {
                 let mut store = MockAppStoreBuilder::new();
+                store
+                    .wallet
+                    .expect_find()
+                    .times(1)
+                    .returning(|_| Ok(Some(Wallet::default())));
                 store.wallet.expect_delete_many().times(1).returning(|_| Ok(1));
 
                 let service = WalletService::new(store.build());
@@ -485,13 +500,14 @@ mod tests {
             }
         }
 
-        mod when_nothing_is_removed {
+        mod when_the_wallet_is_missing {
             use super::*;
 
             #[tokio::test]
             async fn returns_not_found() {
                 let mut store = MockAppStoreBuilder::new();
-                store.wallet.expect_delete_many().times(1).returning(|_| Ok(0));
+                store.wallet.expect_find().times(1).returning(|_| Ok(None));
+                store.wallet.expect_delete_many().never();
 
                 let service = WalletService::new(store.build());
 
@@ -2,7 +2,7 @@ use std::{sync::Arc, time::Duration};
 
 use tokio::{
     task::yield_now,
-    time::{sleep, Instant},
+    time::{interval_at, sleep, Instant, MissedTickBehavior},
 };
 use tracing::{error, warn};
 
@@ -32,6 +32,7 @@ const LISTENER_STABLE_THRESHOLD: Duration = Duration::from_secs(60);
 pub struct EventListener {
     listener: Arc<dyn EventsListener>,
     services: Arc<AppServices>,
+    onchain_sync_interval: Duration,
 }
 
 impl EventListener {
@@ -83,7 +84,11 @@ impl EventListener {
             }
         };
 
-        Ok(Self { listener, services })
+        Ok(Self {
+            listener,
+            services,
+            onchain_sync_interval: config.onchain_sync_interval,
+        })
     }
 
     pub async fn start(&self) -> Result<(), ApplicationError> {
@@ -124,6 +129,22 @@ impl EventListener {
             }
         });
 
+        // Deposits mature and reorgs happen without a wallet event for the output, so re-sync
+        // on-chain state periodically rather than only when the listener (re)connects.
+        let services = self.services.clone();
+        let period = self.onchain_sync_interval;
+        tokio::spawn(async move {
+            let mut ticker = interval_at(Instant::now() + period, period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(err) = services.bitcoin.sync().await {
+                    warn!(%err, "Periodic on-chain sync failed");
+                }
+            }
+        });
+
         yield_now().await;
         Self::sync_offchain_state(&self.services).await?;
 
@@ -185,6 +206,7 @@ mod tests {
         let event_listener = EventListener {
             listener: Arc::new(listener),
             services,
+            onchain_sync_interval: Duration::from_secs(3600),
         };
 
         event_listener.start().await.unwrap();
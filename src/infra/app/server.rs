@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use crate::{
     application::{
@@ -6,9 +6,10 @@ use crate::{
         docs::merged_openapi,
         errors::WebServerError,
     },
-    domains::{account, bitcoin, invoice, ln_address, lnurl, nostr, payment, system, wallet},
+    domains::{account, audit, bitcoin, invoice, ln_address, lnurl, nostr, payment, system, wallet},
+    infra::axum::{client_ip_middleware, rate_limit_middleware},
 };
-use axum::{routing::get, Router};
+use axum::{middleware, routing::get, Router};
 use std::future::Future;
 use tokio::net::TcpListener;
 use tower_http::{
@@ -29,6 +30,7 @@ impl Server {
             .nest("/.well-known", Self::well_known_router())
             .nest("/v1/system", system::router())
             .nest("/lnurlp", lnurl::router())
+            .nest("/lnurl/pay-links", lnurl::pay_link_lnurl_router())
             .nest("/v1/invoices", invoice::router())
             .nest("/v1/payments", payment::router())
             .nest("/v1/me", wallet::account_router())
@@ -36,8 +38,14 @@ impl Server {
             .nest("/v1/accounts", account::router())
             .nest("/v1/auth", account::auth_router())
             .nest("/v1/api-keys", account::api_key_router())
+            .nest("/v1/organizations", account::organization_router())
+            .nest("/v1/audit", audit::router())
             .nest("/v1/lightning-addresses", ln_address::router())
+            .nest("/v1/lightning-address-domains", ln_address::domain_router())
+            .nest("/v1/pay-links", lnurl::pay_link_router())
             .nest("/v1/bitcoin/addresses", bitcoin::router())
+            .nest("/v1/bitcoin/fees", bitcoin::fee_router())
+            .nest("/v1/payjoin", bitcoin::payjoin_router())
             .merge(Scalar::with_url("/docs", merged_openapi()));
 
         let router = match dashboard_dir {
@@ -46,6 +54,25 @@ impl Server {
             None => router,
         };
 
+        let router = router
+            .layer(middleware::from_fn(account::nostr_payload_middleware))
+            .layer(middleware::from_fn_with_state(
+                services.clone(),
+                audit::audit_middleware,
+            ));
+
+        // Requests over their limits are rejected before being audited.
+        let router = match adapters.rate_limiter {
+            Some(rate_limiter) => router.layer(middleware::from_fn_with_state(rate_limiter, rate_limit_middleware)),
+            None => router,
+        };
+
+        // The client IP is resolved for the rate limits, audit entries and API keys, whether or not limits are enabled.
+        let router = router.layer(middleware::from_fn_with_state(
+            adapters.client_ip_resolver,
+            client_ip_middleware,
+        ));
+
         let router = router
             .layer(TraceLayer::new_for_http())
             .layer(adapters.timeout_layer)
@@ -66,10 +93,13 @@ impl Server {
 
         info!(addr, "Listening on");
 
-        axum::serve(listener, self.router.clone())
-            .with_graceful_shutdown(shutdown_signal)
-            .await
-            .map_err(|e| WebServerError::Serve(e.to_string()))?;
+        axum::serve(
+            listener,
+            self.router.clone().into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown_signal)
+        .await
+        .map_err(|e| WebServerError::Serve(e.to_string()))?;
 
         Ok(())
     }
@@ -0,0 +1,111 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::Extensions,
+    middleware::Next,
+    response::Response,
+};
+
+/// Client IP of a request, resolved by [`client_ip_middleware`].
+#[derive(Clone, Copy, Debug)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    /// The client IP of the request, if resolved.
+    pub fn of(extensions: &Extensions) -> Option<IpAddr> {
+        extensions.get::<ClientIp>().map(|ClientIp(ip)| *ip)
+    }
+}
+
+/// Resolves the client IP of requests, for rate limits, audit entries and API key usage.
+#[derive(Clone, Debug, Default)]
+pub struct ClientIpResolver {
+    header: Option<String>,
+}
+
+impl ClientIpResolver {
+    pub fn new(header: Option<String>) -> Self {
+        Self { header }
+    }
+
+    /// The client IP, read from the configured header when deployed behind a reverse proxy, or else the peer address.
+    pub fn resolve(&self, request: &Request) -> Option<IpAddr> {
+        let forwarded = self.header.as_ref().and_then(|header| {
+            request
+                .headers()
+                .get(header)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .and_then(|value| value.trim().parse().ok())
+        });
+
+        forwarded.or_else(|| peer_ip(request.extensions()))
+    }
+}
+
+/// Keeps the client IP of every request as a [`ClientIp`] extension.
+pub async fn client_ip_middleware(
+    State(resolver): State<ClientIpResolver>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(ip) = resolver.resolve(&request) {
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+
+    next.run(request).await
+}
+
+/// The peer address, which is the reverse proxy's when deployed behind one.
+fn peer_ip(extensions: &Extensions) -> Option<IpAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+
+    use super::*;
+
+    fn request(forwarded_for: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri("/v1/me");
+        if let Some(forwarded_for) = forwarded_for {
+            builder = builder.header("X-Forwarded-For", forwarded_for);
+        }
+        let mut request = builder.body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 443))));
+
+        request
+    }
+
+    #[test]
+    fn resolves_the_peer_address_without_header() {
+        let resolver = ClientIpResolver::default();
+
+        assert_eq!(
+            resolver.resolve(&request(Some("203.0.113.1"))),
+            Some("10.0.0.1".parse().unwrap()),
+            "the header is not trusted unless configured"
+        );
+    }
+
+    #[test]
+    fn resolves_the_last_value_of_the_configured_header() {
+        let resolver = ClientIpResolver::new(Some("X-Forwarded-For".to_string()));
+
+        assert_eq!(
+            resolver.resolve(&request(Some("198.51.100.9, 203.0.113.1"))),
+            Some("203.0.113.1".parse().unwrap())
+        );
+        assert_eq!(
+            resolver.resolve(&request(Some("not an ip"))),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(resolver.resolve(&request(None)), Some("10.0.0.1".parse().unwrap()));
+    }
+}
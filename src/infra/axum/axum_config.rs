@@ -8,4 +8,135 @@ pub struct AxumServerConfig {
     pub addr: String,
     #[serde(deserialize_with = "deserialize_duration")]
     pub request_timeout: Duration,
+    /// Header holding the client IP, such as `X-Forwarded-For`, when deployed behind a reverse proxy. Its last
+    /// value, the one added by the proxy, is used for rate limits, audit entries and API key usage. The peer address
+    /// is used when not set.
+    #[serde(default)]
+    pub client_ip_header: Option<String>,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Token-bucket rate limits. A limit of zero requests disables it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    /// Where token buckets are kept. Instances behind a load balancer share them through the database.
+    #[serde(default)]
+    pub backend: RateLimitBackend,
+    /// Requests per client IP, to any route
+    #[serde(default = "default_global_rate_limit")]
+    pub global: RateLimit,
+    /// Requests per client IP to the routes starting with a prefix, such as `/v1/payments`
+    #[serde(default)]
+    pub routes: Vec<RouteRateLimit>,
+    /// Requests per authenticated account, whatever its credentials
+    #[serde(default = "default_account_rate_limit")]
+    pub account: RateLimit,
+    /// Requests per API key
+    #[serde(default = "default_api_key_rate_limit")]
+    pub api_key: RateLimit,
+    /// Sign-in, step-up and TOTP attempts per client IP, against password and code guessing
+    #[serde(default = "default_sign_in_rate_limit")]
+    pub sign_in: RateLimit,
+    /// Failed sign-ins per login or passkey, failed TOTP codes per account and failed password resets per client IP,
+    /// against distributed guessing. Exhausted logins, passkeys and accounts are rejected until a failure is refilled.
+    #[serde(default = "default_auth_failures_rate_limit")]
+    pub auth_failures: RateLimit,
+    /// Invoices generated by LNURL callbacks per client IP
+    #[serde(default = "default_lnurl_callback_rate_limit")]
+    pub lnurl_callback: RateLimit,
+    /// Invoices generated by LNURL callbacks per Lightning address or pay link, whatever the client
+    #[serde(default = "default_lnurl_callback_recipient_rate_limit")]
+    pub lnurl_callback_recipient: RateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_rate_limit_enabled(),
+            backend: RateLimitBackend::default(),
+            global: default_global_rate_limit(),
+            routes: vec![],
+            account: default_account_rate_limit(),
+            api_key: default_api_key_rate_limit(),
+            sign_in: default_sign_in_rate_limit(),
+            auth_failures: default_auth_failures_rate_limit(),
+            lnurl_callback: default_lnurl_callback_rate_limit(),
+            lnurl_callback_recipient: default_lnurl_callback_recipient_rate_limit(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitBackend {
+    #[default]
+    Memory,
+    Database,
+}
+
+/// Bucket of `requests` tokens, refilled at `requests` per `period`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub period: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, period: Duration) -> Self {
+        Self { requests, period }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.requests > 0 && !self.period.is_zero()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RouteRateLimit {
+    pub prefix: String,
+    pub requests: u32,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub period: Duration,
+}
+
+impl RouteRateLimit {
+    pub fn limit(&self) -> RateLimit {
+        RateLimit::new(self.requests, self.period)
+    }
+}
+
+fn default_rate_limit_enabled() -> bool {
+    true
+}
+
+fn default_global_rate_limit() -> RateLimit {
+    RateLimit::new(600, Duration::from_secs(60))
+}
+
+fn default_account_rate_limit() -> RateLimit {
+    RateLimit::new(300, Duration::from_secs(60))
+}
+
+fn default_api_key_rate_limit() -> RateLimit {
+    RateLimit::new(300, Duration::from_secs(60))
+}
+
+fn default_sign_in_rate_limit() -> RateLimit {
+    RateLimit::new(10, Duration::from_secs(5 * 60))
+}
+
+fn default_auth_failures_rate_limit() -> RateLimit {
+    RateLimit::new(5, Duration::from_secs(15 * 60))
+}
+
+fn default_lnurl_callback_rate_limit() -> RateLimit {
+    RateLimit::new(30, Duration::from_secs(60))
+}
+
+fn default_lnurl_callback_recipient_rate_limit() -> RateLimit {
+    RateLimit::new(120, Duration::from_secs(60))
 }
@@ -0,0 +1,520 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use tracing::{trace, warn};
+use uuid::Uuid;
+
+use crate::application::errors::{DatabaseError, RateLimitError};
+
+use super::{ClientIp, RateLimit, RateLimitConfig};
+
+const SIGN_IN_PATH: &str = "/v1/auth/sign-in";
+/// Routes guessing credentials, limited per client IP with sign-ins.
+const CREDENTIAL_PATHS: [&str; 5] = [
+    SIGN_IN_PATH,
+    "/v1/auth/passkeys/sign-in",
+    "/v1/auth/step-up",
+    "/v1/auth/totp/confirm",
+    "/v1/auth/totp/disable",
+];
+/// Idle buckets are swept every this many checks.
+const SWEEP_INTERVAL: u64 = 10_000;
+
+/// Tokens left in a bucket, refilled continuously since it was last updated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBucket {
+    pub tokens: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TokenBucket {
+    pub fn full(limit: &RateLimit, now: DateTime<Utc>) -> Self {
+        Self {
+            tokens: f64::from(limit.requests),
+            updated_at: now,
+        }
+    }
+
+    /// Refills the bucket for the time elapsed and takes a token, or returns the time until one is available.
+    pub fn take(&mut self, limit: &RateLimit, now: DateTime<Utc>) -> Option<Duration> {
+        self.refill(limit, now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return None;
+        }
+
+        Some(self.time_to_token(limit))
+    }
+
+    /// Returns the time until a token is available if the bucket is empty, without taking one.
+    pub fn peek(&self, limit: &RateLimit, now: DateTime<Utc>) -> Option<Duration> {
+        let mut bucket = self.clone();
+        bucket.refill(limit, now);
+
+        (bucket.tokens < 1.0).then(|| bucket.time_to_token(limit))
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: DateTime<Utc>) {
+        let elapsed = (now - self.updated_at).to_std().unwrap_or_default();
+
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * refill_rate(limit)).min(f64::from(limit.requests));
+        self.updated_at = self.updated_at.max(now);
+    }
+
+    fn time_to_token(&self, limit: &RateLimit) -> Duration {
+        Duration::from_secs_f64((1.0 - self.tokens) / refill_rate(limit))
+    }
+}
+
+fn refill_rate(limit: &RateLimit) -> f64 {
+    f64::from(limit.requests) / limit.period.as_secs_f64()
+}
+
+/// Token buckets of the rate limiter, by key.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Takes a token from the bucket of the key, created full if missing. Returns the time until a token is
+    /// available if the bucket is empty.
+    async fn acquire(&self, key: &str, limit: &RateLimit) -> Result<Option<Duration>, DatabaseError>;
+    /// Returns the time until a token is available if the bucket of the key is empty, without taking one.
+    async fn peek(&self, key: &str, limit: &RateLimit) -> Result<Option<Duration>, DatabaseError>;
+    /// Deletes the buckets untouched since the given time, which are full again.
+    async fn sweep(&self, idle_since: DateTime<Utc>) -> Result<u64, DatabaseError>;
+}
+
+/// Buckets of a single instance.
+#[derive(Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn acquire(&self, key: &str, limit: &RateLimit) -> Result<Option<Duration>, DatabaseError> {
+        let now = Utc::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::full(limit, now));
+
+        Ok(bucket.take(limit, now))
+    }
+
+    async fn peek(&self, key: &str, limit: &RateLimit) -> Result<Option<Duration>, DatabaseError> {
+        let buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+
+        Ok(buckets.get(key).and_then(|bucket| bucket.peek(limit, Utc::now())))
+    }
+
+    async fn sweep(&self, idle_since: DateTime<Utc>) -> Result<u64, DatabaseError> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        let count = buckets.len();
+        buckets.retain(|_, bucket| bucket.updated_at >= idle_since);
+
+        Ok((count - buckets.len()) as u64)
+    }
+}
+
+/// Limits requests by client IP, route, account and API key, and failed authentications by login and account.
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    store: Arc<dyn RateLimitStore>,
+    // Buckets untouched for the longest period are full, and as good as missing.
+    idle_after: TimeDelta,
+    checks: Arc<AtomicU64>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        let longest_period = [
+            &config.global,
+            &config.account,
+            &config.api_key,
+            &config.sign_in,
+            &config.auth_failures,
+            &config.lnurl_callback,
+            &config.lnurl_callback_recipient,
+        ]
+        .into_iter()
+        .map(|limit| limit.period)
+        .chain(config.routes.iter().map(|route| route.period))
+        .max()
+        .unwrap_or_default();
+
+        Self {
+            config: Arc::new(config),
+            store,
+            idle_after: TimeDelta::from_std(longest_period).unwrap_or(TimeDelta::MAX),
+            checks: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Checks the limits of the client IP and of the route of a request.
+    pub async fn check_request(
+        &self,
+        client_ip: Option<IpAddr>,
+        method: &Method,
+        path: &str,
+    ) -> Result<(), RateLimitError> {
+        let mut buckets = vec![];
+
+        if let Some(ip) = client_ip {
+            buckets.push((format!("ip:{ip}"), self.config.global.clone()));
+
+            for route in self
+                .config
+                .routes
+                .iter()
+                .filter(|route| path.starts_with(&route.prefix))
+            {
+                buckets.push((format!("route:{}:ip:{ip}", route.prefix), route.limit()));
+            }
+
+            if *method == Method::POST && CREDENTIAL_PATHS.contains(&path) {
+                buckets.push((format!("sign-in:ip:{ip}"), self.config.sign_in.clone()));
+            }
+        }
+
+        if let Some(recipient) = lnurl_callback_recipient(path) {
+            if let Some(ip) = client_ip {
+                buckets.push((format!("lnurl-callback:ip:{ip}"), self.config.lnurl_callback.clone()));
+            }
+            buckets.push((
+                format!("lnurl-callback:{}", recipient.to_lowercase()),
+                self.config.lnurl_callback_recipient.clone(),
+            ));
+        }
+
+        self.acquire(buckets).await
+    }
+
+    /// Checks the limits of an authenticated account and of the API key it authenticated with, if any.
+    pub async fn check_principal(&self, account_id: Uuid, api_key_id: Option<Uuid>) -> Result<(), RateLimitError> {
+        let mut buckets = vec![(format!("account:{account_id}"), self.config.account.clone())];
+        if let Some(api_key_id) = api_key_id {
+            buckets.push((format!("api-key:{api_key_id}"), self.config.api_key.clone()));
+        }
+
+        self.acquire(buckets).await
+    }
+
+    /// Rejects authentications of `subject`, such as `login:alice`, once its failures are exhausted.
+    pub async fn check_failures(&self, subject: &str) -> Result<(), RateLimitError> {
+        let limit = &self.config.auth_failures;
+        if !limit.is_enabled() {
+            return Ok(());
+        }
+
+        let key = failures_key(subject);
+        match self.store.peek(&key, limit).await {
+            Ok(None) => Ok(()),
+            Ok(Some(retry_after)) => Err(RateLimitError::Exceeded(key, retry_after)),
+            // Authentications go through rather than fail when their limits cannot be checked.
+            Err(e) => {
+                warn!(error = %e, key, "Failed to check authentication failures");
+                Ok(())
+            }
+        }
+    }
+
+    /// Counts a failed authentication of `subject` against its limit.
+    pub async fn record_failure(&self, subject: &str) {
+        let buckets = vec![(failures_key(subject), self.config.auth_failures.clone())];
+        if let Err(e) = self.acquire(buckets).await {
+            trace!(error = %e, "Authentication failures exhausted");
+        }
+    }
+
+    async fn acquire(&self, buckets: Vec<(String, RateLimit)>) -> Result<(), RateLimitError> {
+        self.sweep_idle_buckets().await;
+
+        for (key, limit) in buckets.into_iter().filter(|(_, limit)| limit.is_enabled()) {
+            match self.store.acquire(&key, &limit).await {
+                Ok(None) => {}
+                Ok(Some(retry_after)) => return Err(RateLimitError::Exceeded(key, retry_after)),
+                // Requests go through rather than fail when their limits cannot be checked.
+                Err(e) => warn!(error = %e, key, "Failed to check rate limit"),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn sweep_idle_buckets(&self) {
+        if !self
+            .checks
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(SWEEP_INTERVAL)
+        {
+            return;
+        }
+
+        let idle_since = Utc::now()
+            .checked_sub_signed(self.idle_after)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        match self.store.sweep(idle_since).await {
+            Ok(count) => trace!(count, "Idle rate limit buckets swept"),
+            Err(e) => warn!(error = %e, "Failed to sweep idle rate limit buckets"),
+        }
+    }
+}
+
+/// Rejects requests over the limits of their client IP and route, and hands the limiter to the `User` extractor,
+/// which checks the limits of the authenticated account.
+pub async fn rate_limit_middleware(State(limiter): State<RateLimiter>, mut request: Request, next: Next) -> Response {
+    let client_ip = ClientIp::of(request.extensions());
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    if let Err(e) = limiter.check_request(client_ip, &method, &path).await {
+        return e.into_response();
+    }
+
+    request.extensions_mut().insert(limiter);
+    next.run(request).await
+}
+
+fn failures_key(subject: &str) -> String {
+    format!("auth-failures:{subject}")
+}
+
+/// The Lightning address or pay link of an LNURL callback, which generates an invoice without authentication.
+fn lnurl_callback_recipient(path: &str) -> Option<&str> {
+    let recipient = path
+        .strip_prefix("/lnurlp/")
+        .or_else(|| path.strip_prefix("/lnurl/pay-links/"))?
+        .strip_suffix("/callback")?;
+
+    (!recipient.is_empty() && !recipient.contains('/')).then_some(recipient)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use crate::infra::axum::RouteRateLimit;
+
+    use super::*;
+
+    fn limit(requests: u32) -> RateLimit {
+        RateLimit::new(requests, Duration::from_secs(60))
+    }
+
+    fn limiter(config: RateLimitConfig) -> RateLimiter {
+        RateLimiter::new(config, Arc::new(InMemoryRateLimitStore::default()))
+    }
+
+    fn ip(last: u8) -> Option<IpAddr> {
+        Some(IpAddr::V4(Ipv4Addr::new(203, 0, 113, last)))
+    }
+
+    mod token_bucket {
+        use super::*;
+
+        // Refilled at a token per second.
+        fn per_second(requests: u32) -> RateLimit {
+            RateLimit::new(requests, Duration::from_secs(requests.into()))
+        }
+
+        #[test]
+        fn takes_tokens_until_empty() {
+            let now = Utc::now();
+            let limit = per_second(2);
+            let mut bucket = TokenBucket::full(&limit, now);
+
+            assert_eq!(bucket.take(&limit, now), None);
+            assert_eq!(bucket.take(&limit, now), None);
+            assert_eq!(bucket.take(&limit, now), Some(Duration::from_secs(1)));
+        }
+
+        #[test]
+        fn refills_over_time_up_to_its_capacity() {
+            let now = Utc::now();
+            let limit = per_second(2);
+            let mut bucket = TokenBucket {
+                tokens: 0.0,
+                updated_at: now,
+            };
+
+            assert_eq!(bucket.take(&limit, now + TimeDelta::seconds(1)), None);
+            assert!(bucket.take(&limit, now + TimeDelta::seconds(1)).is_some());
+
+            bucket.take(&limit, now + TimeDelta::hours(1));
+            assert_eq!(bucket.tokens, 1.0);
+        }
+    }
+
+    mod check_request {
+        use super::*;
+
+        #[tokio::test]
+        async fn limits_sign_in_attempts_per_ip() {
+            let limiter = limiter(RateLimitConfig {
+                sign_in: limit(2),
+                ..Default::default()
+            });
+
+            for _ in 0..2 {
+                assert!(limiter.check_request(ip(1), &Method::POST, SIGN_IN_PATH).await.is_ok());
+            }
+            let result = limiter.check_request(ip(1), &Method::POST, SIGN_IN_PATH).await;
+
+            assert!(matches!(result, Err(RateLimitError::Exceeded(key, _)) if key == "sign-in:ip:203.0.113.1"));
+            assert!(limiter.check_request(ip(2), &Method::POST, SIGN_IN_PATH).await.is_ok());
+            assert!(limiter.check_request(ip(1), &Method::GET, "/v1/me").await.is_ok());
+        }
+
+        #[tokio::test]
+        async fn limits_lnurl_callbacks_per_recipient_across_ips() {
+            let limiter = limiter(RateLimitConfig {
+                lnurl_callback: limit(10),
+                lnurl_callback_recipient: limit(2),
+                ..Default::default()
+            });
+
+            assert!(limiter
+                .check_request(ip(1), &Method::GET, "/lnurlp/alice/callback")
+                .await
+                .is_ok());
+            assert!(limiter
+                .check_request(ip(2), &Method::GET, "/lnurlp/Alice/callback")
+                .await
+                .is_ok());
+            assert!(limiter
+                .check_request(ip(3), &Method::GET, "/lnurlp/alice/callback")
+                .await
+                .is_err());
+            assert!(limiter
+                .check_request(ip(3), &Method::GET, "/lnurlp/bob/callback")
+                .await
+                .is_ok());
+        }
+
+        #[tokio::test]
+        async fn limits_route_prefixes_and_all_routes_per_ip() {
+            let limiter = limiter(RateLimitConfig {
+                global: limit(3),
+                routes: vec![RouteRateLimit {
+                    prefix: "/v1/payments".to_string(),
+                    requests: 1,
+                    period: Duration::from_secs(60),
+                }],
+                ..Default::default()
+            });
+
+            assert!(limiter
+                .check_request(ip(1), &Method::POST, "/v1/payments")
+                .await
+                .is_ok());
+            assert!(limiter
+                .check_request(ip(1), &Method::POST, "/v1/payments")
+                .await
+                .is_err());
+            assert!(limiter.check_request(ip(1), &Method::GET, "/v1/me").await.is_ok());
+            assert!(limiter.check_request(ip(1), &Method::GET, "/v1/me").await.is_err());
+        }
+
+        #[tokio::test]
+        async fn ignores_disabled_limits() {
+            let limiter = limiter(RateLimitConfig {
+                global: limit(0),
+                ..Default::default()
+            });
+
+            for _ in 0..1000 {
+                assert!(limiter.check_request(ip(1), &Method::GET, "/v1/me").await.is_ok());
+            }
+        }
+
+        #[tokio::test]
+        async fn lets_requests_through_when_the_store_fails() {
+            let mut store = MockRateLimitStore::new();
+            store.expect_sweep().returning(|_| Ok(0));
+            store
+                .expect_acquire()
+                .returning(|_, _| Err(DatabaseError::Transaction("unavailable".to_string())));
+            let limiter = RateLimiter::new(RateLimitConfig::default(), Arc::new(store));
+
+            let result = limiter.check_request(ip(1), &Method::POST, SIGN_IN_PATH).await;
+
+            assert!(result.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn check_principal_limits_accounts_and_api_keys() {
+        let limiter = limiter(RateLimitConfig {
+            account: limit(3),
+            api_key: limit(1),
+            ..Default::default()
+        });
+        let account_id = Uuid::new_v4();
+        let api_key_id = Uuid::new_v4();
+
+        assert!(limiter.check_principal(account_id, Some(api_key_id)).await.is_ok());
+        assert!(limiter.check_principal(account_id, Some(api_key_id)).await.is_err());
+        assert!(limiter.check_principal(account_id, None).await.is_ok());
+        assert!(limiter.check_principal(account_id, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failures_are_only_taken_when_recorded() {
+        let limiter = limiter(RateLimitConfig {
+            auth_failures: limit(2),
+            ..Default::default()
+        });
+
+        for _ in 0..5 {
+            assert!(limiter.check_failures("login:alice").await.is_ok());
+        }
+        limiter.record_failure("login:alice").await;
+        assert!(limiter.check_failures("login:alice").await.is_ok());
+        limiter.record_failure("login:alice").await;
+
+        let result = limiter.check_failures("login:alice").await;
+
+        assert!(matches!(result, Err(RateLimitError::Exceeded(key, _)) if key == "auth-failures:login:alice"));
+        assert!(limiter.check_failures("login:bob").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn sweep_drops_idle_buckets() {
+        let store = InMemoryRateLimitStore::default();
+        store.acquire("ip:203.0.113.1", &limit(1)).await.unwrap();
+
+        assert_eq!(store.sweep(Utc::now() - TimeDelta::minutes(1)).await.unwrap(), 0);
+        assert_eq!(store.sweep(Utc::now() + TimeDelta::minutes(1)).await.unwrap(), 1);
+        assert_eq!(
+            store.acquire("ip:203.0.113.1", &limit(1)).await.unwrap(),
+            None,
+            "swept buckets start full again"
+        );
+    }
+
+    #[test]
+    fn lnurl_callback_recipient_is_the_address_or_pay_link() {
+        assert_eq!(lnurl_callback_recipient("/lnurlp/alice/callback"), Some("alice"));
+        assert_eq!(lnurl_callback_recipient("/lnurl/pay-links/6f1c/callback"), Some("6f1c"));
+        assert_eq!(lnurl_callback_recipient("/lnurlp/alice"), None);
+        assert_eq!(lnurl_callback_recipient("/lnurlp/alice/verify/callback"), None);
+        assert_eq!(lnurl_callback_recipient("/v1/me/callback"), None);
+    }
+}
@@ -1,14 +1,18 @@
 use axum::{
-    http::{header::WWW_AUTHENTICATE, HeaderValue, StatusCode},
+    http::{
+        header::{RETRY_AFTER, WWW_AUTHENTICATE},
+        HeaderValue, StatusCode,
+    },
     response::{IntoResponse, Response},
     Json,
 };
 use tracing::{debug, error, trace, warn};
 
-use swissknife_types::ErrorResponse;
+use swissknife_types::{ErrorResponse, PayjoinErrorResponse};
 
 use crate::application::errors::{
-    ApplicationError, AuthenticationError, AuthorizationError, BitcoinError, DataError, LightningError,
+    ApplicationError, AuthenticationError, AuthorizationError, BitcoinError, DataError, LightningError, PayjoinError,
+    RateLimitError,
 };
 
 const INTERNAL_SERVER_ERROR_MSG: &str = "Internal server error, Please contact your administrator or try later";
@@ -21,6 +25,8 @@ impl IntoResponse for ApplicationError {
             ApplicationError::Data(error) => error.into_response(),
             ApplicationError::Lightning(error) => error.into_response(),
             ApplicationError::Bitcoin(error) => error.into_response(),
+            ApplicationError::Payjoin(error) => error.into_response(),
+            ApplicationError::RateLimit(error) => error.into_response(),
             _ => {
                 error!("{}", self);
 
@@ -36,6 +42,14 @@ impl IntoResponse for AuthorizationError {
     fn into_response(self) -> Response {
         let error_message = match self {
             AuthorizationError::MissingPermission(_) => "Access denied due to insufficient permissions",
+            AuthorizationError::WalletNotAllowed(_) | AuthorizationError::WalletScoped => {
+                "Access denied due to the wallet scope of the credentials"
+            }
+            AuthorizationError::StepUpRequired => "Two-factor verification required for this operation",
+            AuthorizationError::SessionRequired => "Only signed-in sessions can perform this operation",
+            AuthorizationError::NotMember(_) => "Access denied to the organization",
+            AuthorizationError::RoleRequired(_) => "Access denied due to the role in the organization",
+            AuthorizationError::BootstrapAdminRequired => "Access denied to the bootstrap admin account",
         };
 
         warn!("{}", self);
@@ -50,6 +64,7 @@ impl IntoResponse for AuthenticationError {
     fn into_response(self) -> Response {
         let (error_message, header_message) = match self {
             AuthenticationError::InvalidCredentials => ("Invalid credentials", ""),
+            AuthenticationError::OtpRequired => ("Two-factor authentication code required", ""),
             AuthenticationError::MissingAuthorizationHeader => (
                 "Missing authentication token",
                 "Bearer realm=\"swissknife\", error=\"invalid_request\"",
@@ -153,6 +168,47 @@ impl IntoResponse for BitcoinError {
     }
 }
 
+/// Payjoin errors use the BIP78 body so any payjoin sender can interpret them.
+impl IntoResponse for PayjoinError {
+    fn into_response(self) -> Response {
+        let (error_code, status) = match self {
+            PayjoinError::Unavailable(_) => ("unavailable", StatusCode::SERVICE_UNAVAILABLE),
+            PayjoinError::VersionUnsupported(_) => ("version-unsupported", StatusCode::BAD_REQUEST),
+            PayjoinError::OriginalPsbtRejected(_) => ("original-psbt-rejected", StatusCode::BAD_REQUEST),
+            PayjoinError::ProposalRejected(_) | PayjoinError::Request(_) => {
+                ("unavailable", StatusCode::UNPROCESSABLE_ENTITY)
+            }
+        };
+
+        warn!("{}", self);
+
+        let body = Json(PayjoinErrorResponse {
+            error_code: error_code.to_string(),
+            message: self.to_string(),
+        });
+        (status, body).into_response()
+    }
+}
+
+impl IntoResponse for RateLimitError {
+    fn into_response(self) -> Response {
+        let RateLimitError::Exceeded(_, retry_after) = &self;
+        // Whole seconds, rounded up so that retrying after them succeeds.
+        let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+        warn!("{}", self);
+
+        let status = StatusCode::TOO_MANY_REQUESTS;
+        let body = generate_body(status, "Too many requests, please retry later".to_string());
+        let mut response = (status, body).into_response();
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+
+        response
+    }
+}
+
 fn generate_body(status: StatusCode, reason: String) -> Json<ErrorResponse> {
     ErrorResponse {
         status: status.to_string(),
@@ -160,3 +216,19 @@ fn generate_body(status: StatusCode, reason: String) -> Json<ErrorResponse> {
     }
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn rate_limit_errors_tell_when_to_retry_in_whole_seconds() {
+        let response =
+            RateLimitError::Exceeded("ip:203.0.113.1".to_string(), Duration::from_millis(1500)).into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[RETRY_AFTER], "2");
+    }
+}
@@ -1,6 +1,10 @@
+mod axum_client_ip;
 mod axum_config;
+mod axum_rate_limit;
 mod axum_response;
 mod axum_types;
 
-pub use axum_config::AxumServerConfig;
+pub use axum_client_ip::*;
+pub use axum_config::*;
+pub use axum_rate_limit::*;
 pub use axum_types::*;
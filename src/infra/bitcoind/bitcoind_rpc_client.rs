@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bitcoin::{consensus::encode::deserialize_hex, Amount, ScriptBuf, Transaction, TxOut};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{
+    application::errors::BitcoinError,
+    domains::bitcoin::{BitcoinChain, ChainBlock, ChainTransaction},
+    infra::config::config_rs::deserialize_duration,
+};
+
+/// `RPC_INVALID_ADDRESS_OR_KEY`, returned when a transaction is not part of the given block.
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+/// `RPC_VERIFY_ERROR`, returned among others when the inputs of a broadcast transaction are missing or spent.
+const RPC_VERIFY_ERROR: i64 = -25;
+/// `RPC_VERIFY_ALREADY_IN_CHAIN`, returned when a broadcast transaction is already confirmed.
+const RPC_VERIFY_ALREADY_IN_CHAIN: i64 = -27;
+
+const USER_AGENT: &str = "Numeraire Swissknife/1.0";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BitcoindRpcConfig {
+    pub endpoint: String,
+    pub user: String,
+    pub password: String,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+}
+
+/// JSON-RPC client of Bitcoin Core, version 23 or later (`getblock` verbosity 3).
+pub struct BitcoindRpcClient {
+    client: Client,
+    config: BitcoindRpcConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Block {
+    hash: String,
+    tx: Vec<BlockTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockTransaction {
+    hex: String,
+    vin: Vec<BlockInput>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockInput {
+    prevout: Option<Prevout>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Prevout {
+    value: f64,
+    #[serde(rename = "scriptPubKey")]
+    script_pubkey: PrevoutScript,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrevoutScript {
+    hex: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MempoolAcceptResult {
+    allowed: bool,
+    #[serde(rename = "reject-reason")]
+    reject_reason: Option<String>,
+}
+
+impl BitcoindRpcClient {
+    pub async fn new(config: BitcoindRpcConfig) -> Result<Self, BitcoinError> {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| BitcoinError::BlockHeight(e.to_string()))?;
+
+        let rpc_client = Self { client, config };
+        rpc_client.block_height().await?;
+
+        Ok(rpc_client)
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, RpcError> {
+        let response = self
+            .client
+            .post(&self.config.endpoint)
+            .basic_auth(&self.config.user, Some(&self.config.password))
+            .json(&json!({ "jsonrpc": "1.0", "id": "swissknife", "method": method, "params": params }))
+            .send()
+            .await
+            .map_err(|e| RpcError::transport(e.to_string()))?;
+
+        // bitcoind answers RPC errors with a non-2xx status and the error in the body.
+        let body = response.text().await.map_err(|e| RpcError::transport(e.to_string()))?;
+        let response: RpcResponse<T> = serde_json::from_str(&body).map_err(|_| RpcError::transport(body))?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(error),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(RpcError::transport(format!("Empty response to {method}"))),
+        }
+    }
+}
+
+impl RpcError {
+    fn transport(message: String) -> Self {
+        Self { code: 0, message }
+    }
+}
+
+#[async_trait]
+impl BitcoinChain for BitcoindRpcClient {
+    async fn block_height(&self) -> Result<u32, BitcoinError> {
+        self.call("getblockcount", json!([]))
+            .await
+            .map_err(|e| BitcoinError::BlockHeight(e.message))
+    }
+
+    async fn block_hash(&self, height: u32) -> Result<String, BitcoinError> {
+        self.call("getblockhash", json!([height]))
+            .await
+            .map_err(|e| BitcoinError::GetBlock(e.message))
+    }
+
+    async fn block(&self, height: u32) -> Result<ChainBlock, BitcoinError> {
+        let hash = self.block_hash(height).await?;
+        let block: Block = self
+            .call("getblock", json!([hash, 3]))
+            .await
+            .map_err(|e| BitcoinError::GetBlock(e.message))?;
+
+        let transactions = block
+            .tx
+            .into_iter()
+            .map(|tx| {
+                let transaction: Transaction =
+                    deserialize_hex(&tx.hex).map_err(|e| BitcoinError::GetBlock(e.to_string()))?;
+                let prevouts = tx
+                    .vin
+                    .into_iter()
+                    .filter_map(|input| input.prevout)
+                    .map(|prevout| {
+                        Ok(TxOut {
+                            value: Amount::from_btc(prevout.value)
+                                .map_err(|e| BitcoinError::GetBlock(e.to_string()))?,
+                            script_pubkey: ScriptBuf::from_hex(&prevout.script_pubkey.hex)
+                                .map_err(|e| BitcoinError::GetBlock(e.to_string()))?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, BitcoinError>>()?;
+
+                Ok(ChainTransaction { transaction, prevouts })
+            })
+            .collect::<Result<Vec<_>, BitcoinError>>()?;
+
+        Ok(ChainBlock {
+            hash: block.hash,
+            transactions,
+        })
+    }
+
+    async fn is_in_block(&self, txid: &str, height: u32) -> Result<bool, BitcoinError> {
+        let hash = self.block_hash(height).await?;
+
+        match self
+            .call::<String>("getrawtransaction", json!([txid, false, hash]))
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.code == RPC_INVALID_ADDRESS_OR_KEY => Ok(false),
+            Err(e) => Err(BitcoinError::GetTransaction(e.message)),
+        }
+    }
+
+    async fn test_mempool_accept(&self, tx_hex: &str) -> Result<Option<String>, BitcoinError> {
+        let results: Vec<MempoolAcceptResult> = self
+            .call("testmempoolaccept", json!([[tx_hex]]))
+            .await
+            .map_err(|e| BitcoinError::TestMempoolAccept(e.message))?;
+        let result = results
+            .into_iter()
+            .next()
+            .ok_or_else(|| BitcoinError::TestMempoolAccept("Empty result".to_string()))?;
+
+        if result.allowed {
+            return Ok(None);
+        }
+        Ok(Some(result.reject_reason.unwrap_or_else(|| "rejected".to_string())))
+    }
+
+    async fn broadcast_transaction(&self, tx_hex: &str) -> Result<String, BitcoinError> {
+        match self.call("sendrawtransaction", json!([tx_hex])).await {
+            Ok(txid) => Ok(txid),
+            Err(e) if e.code == RPC_VERIFY_ALREADY_IN_CHAIN => {
+                let transaction: Transaction =
+                    deserialize_hex(tx_hex).map_err(|err| BitcoinError::BroadcastTransaction(err.to_string()))?;
+                Ok(transaction.compute_txid().to_string())
+            }
+            Err(e) if e.code == RPC_VERIFY_ERROR && e.message.contains("missingorspent") => {
+                Err(BitcoinError::InputsSpent(e.message))
+            }
+            Err(e) => Err(BitcoinError::BroadcastTransaction(e.message)),
+        }
+    }
+}
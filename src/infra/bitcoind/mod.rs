@@ -0,0 +1,3 @@
+mod bitcoind_rpc_client;
+
+pub use bitcoind_rpc_client::*;
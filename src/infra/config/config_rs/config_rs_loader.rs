@@ -37,3 +37,16 @@ where
     let s = String::deserialize(deserializer)?;
     parse_duration(&s).map_err(serde::de::Error::custom)
 }
+
+/// Like [`deserialize_duration`], rejecting zero for periods of timers.
+pub fn deserialize_period<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let duration = deserialize_duration(deserializer)?;
+    if duration.is_zero() {
+        return Err(serde::de::Error::custom("period must be greater than zero"));
+    }
+
+    Ok(duration)
+}
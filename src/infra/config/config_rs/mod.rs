@@ -1,4 +1,4 @@
 mod config_rs_loader;
 
-pub use config_rs_loader::deserialize_duration;
 pub use config_rs_loader::load_config;
+pub use config_rs_loader::{deserialize_duration, deserialize_period};
@@ -22,8 +22,24 @@ pub enum Relation {
     ApiKey,
     #[sea_orm(has_many = "super::auth_identity::Entity")]
     AuthIdentity,
-    #[sea_orm(has_one = "super::ln_address::Entity")]
+    #[sea_orm(has_many = "super::auth_session::Entity")]
+    AuthSession,
+    #[sea_orm(has_many = "super::invitation::Entity")]
+    Invitation,
+    #[sea_orm(has_many = "super::ln_address::Entity")]
     LnAddress,
+    #[sea_orm(has_one = "super::organization::Entity")]
+    Organization,
+    #[sea_orm(has_many = "super::organization_member::Entity")]
+    OrganizationMember,
+    #[sea_orm(has_many = "super::passkey_challenge::Entity")]
+    PasskeyChallenge,
+    #[sea_orm(has_many = "super::password_reset::Entity")]
+    PasswordReset,
+    #[sea_orm(has_one = "super::totp_factor::Entity")]
+    TotpFactor,
+    #[sea_orm(has_many = "super::totp_recovery_code::Entity")]
+    TotpRecoveryCode,
     #[sea_orm(has_many = "super::wallet::Entity")]
     Wallet,
 }
@@ -46,12 +62,60 @@ impl Related<super::auth_identity::Entity> for Entity {
     }
 }
 
+impl Related<super::auth_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AuthSession.def()
+    }
+}
+
+impl Related<super::invitation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Invitation.def()
+    }
+}
+
 impl Related<super::ln_address::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::LnAddress.def()
     }
 }
 
+impl Related<super::organization::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organization.def()
+    }
+}
+
+impl Related<super::organization_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OrganizationMember.def()
+    }
+}
+
+impl Related<super::passkey_challenge::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasskeyChallenge.def()
+    }
+}
+
+impl Related<super::password_reset::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordReset.def()
+    }
+}
+
+impl Related<super::totp_factor::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TotpFactor.def()
+    }
+}
+
+impl Related<super::totp_recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TotpRecoveryCode.def()
+    }
+}
+
 impl Related<super::wallet::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Wallet.def()
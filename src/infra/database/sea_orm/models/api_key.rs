@@ -16,6 +16,10 @@ pub struct Model {
     pub expires_at: Option<DateTime>,
     pub permissions: Json,
     pub account_id: Uuid,
+    pub last_used_at: Option<DateTime>,
+    pub last_used_ip: Option<String>,
+    pub wallet_ids: Option<Json>,
+    pub member_account_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,6 +25,10 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Account,
+    #[sea_orm(has_one = "super::local_credential::Entity")]
+    LocalCredential,
+    #[sea_orm(has_many = "super::passkey_credential::Entity")]
+    PasskeyCredential,
 }
 
 impl Related<super::account::Entity> for Entity {
@@ -33,4 +37,16 @@ impl Related<super::account::Entity> for Entity {
     }
 }
 
+impl Related<super::local_credential::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LocalCredential.def()
+    }
+}
+
+impl Related<super::passkey_credential::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasskeyCredential.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
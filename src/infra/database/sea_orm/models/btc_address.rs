@@ -14,6 +14,10 @@ pub struct Model {
     pub used: bool,
     pub created_at: DateTime,
     pub updated_at: Option<DateTime>,
+    #[sea_orm(unique)]
+    pub silent_payment_label: Option<i32>,
+    pub silent_payment_tweak: Option<String>,
+    pub invoice_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
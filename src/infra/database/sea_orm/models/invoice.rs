@@ -11,6 +11,7 @@ pub struct Model {
     #[sea_orm(unique)]
     pub payment_hash: Option<String>,
     pub ln_address_id: Option<Uuid>,
+    pub ln_address_alias: Option<String>,
     #[sea_orm(unique)]
     pub bolt11: Option<String>,
     pub ledger: String,
@@ -30,6 +31,13 @@ pub struct Model {
     pub expires_at: Option<DateTime>,
     #[sea_orm(unique)]
     pub btc_output_id: Option<Uuid>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub payer_data: Option<Json>,
+    pub payment_preimage: Option<String>,
+    pub split_invoice_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
+    pub pay_link_id: Option<Uuid>,
+    pub reference: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
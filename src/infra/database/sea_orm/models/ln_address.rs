@@ -7,17 +7,19 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
-    #[sea_orm(unique)]
     pub wallet_id: Uuid,
-    #[sea_orm(unique)]
+    pub domain_id: Option<Uuid>,
     pub username: String,
     pub active: bool,
     pub created_at: DateTime,
     pub updated_at: Option<DateTime>,
     pub allows_nostr: bool,
     pub nostr_pubkey: Option<String>,
-    #[sea_orm(unique)]
     pub account_id: Uuid,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub pay_profile: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub splits: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -32,6 +34,14 @@ pub enum Relation {
     Account,
     #[sea_orm(has_many = "super::invoice::Entity")]
     Invoice,
+    #[sea_orm(
+        belongs_to = "super::ln_address_domain::Entity",
+        from = "Column::DomainId",
+        to = "super::ln_address_domain::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Restrict"
+    )]
+    LnAddressDomain,
     #[sea_orm(
         belongs_to = "super::wallet::Entity",
         from = "(Column::AccountId, Column::WalletId)",
@@ -54,6 +64,12 @@ impl Related<super::invoice::Entity> for Entity {
     }
 }
 
+impl Related<super::ln_address_domain::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LnAddressDomain.def()
+    }
+}
+
 impl Related<super::wallet::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Wallet.def()
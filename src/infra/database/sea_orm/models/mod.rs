@@ -6,12 +6,31 @@ pub mod account;
 pub mod account_preference;
 pub mod api_key;
 pub mod asset;
+pub mod audit_chain_head;
+pub mod audit_entry;
 pub mod auth_identity;
+pub mod auth_session;
 pub mod btc_address;
 pub mod btc_output;
 pub mod config;
 pub mod contact;
+pub mod invitation;
 pub mod invoice;
 pub mod ln_address;
+pub mod ln_address_domain;
+pub mod local_credential;
+pub mod nostr_auth_event;
+pub mod organization;
+pub mod organization_member;
+pub mod passkey_challenge;
+pub mod passkey_credential;
+pub mod password_reset;
+pub mod pay_link;
+pub mod payjoin_contribution;
+pub mod payjoin_fallback;
+pub mod payjoin_input;
 pub mod payment;
+pub mod rate_limit_bucket;
+pub mod totp_factor;
+pub mod totp_recovery_code;
 pub mod wallet;
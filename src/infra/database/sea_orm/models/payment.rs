@@ -30,6 +30,8 @@ pub struct Model {
     pub reserved_amount: i64,
     #[sea_orm(column_type = "JsonBinary", nullable)]
     pub raw_success_action: Option<Json>,
+    pub split_invoice_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
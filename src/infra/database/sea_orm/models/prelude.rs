@@ -4,11 +4,30 @@ pub use super::account::Entity as Account;
 pub use super::account_preference::Entity as AccountPreference;
 pub use super::api_key::Entity as ApiKey;
 pub use super::asset::Entity as Asset;
+pub use super::audit_chain_head::Entity as AuditChainHead;
+pub use super::audit_entry::Entity as AuditEntry;
 pub use super::auth_identity::Entity as AuthIdentity;
+pub use super::auth_session::Entity as AuthSession;
 pub use super::btc_address::Entity as BtcAddress;
 pub use super::btc_output::Entity as BtcOutput;
 pub use super::config::Entity as Config;
+pub use super::invitation::Entity as Invitation;
 pub use super::invoice::Entity as Invoice;
 pub use super::ln_address::Entity as LnAddress;
+pub use super::ln_address_domain::Entity as LnAddressDomain;
+pub use super::local_credential::Entity as LocalCredential;
+pub use super::nostr_auth_event::Entity as NostrAuthEvent;
+pub use super::organization::Entity as Organization;
+pub use super::organization_member::Entity as OrganizationMember;
+pub use super::passkey_challenge::Entity as PasskeyChallenge;
+pub use super::passkey_credential::Entity as PasskeyCredential;
+pub use super::password_reset::Entity as PasswordReset;
+pub use super::pay_link::Entity as PayLink;
+pub use super::payjoin_contribution::Entity as PayjoinContribution;
+pub use super::payjoin_fallback::Entity as PayjoinFallback;
+pub use super::payjoin_input::Entity as PayjoinInput;
 pub use super::payment::Entity as Payment;
+pub use super::rate_limit_bucket::Entity as RateLimitBucket;
+pub use super::totp_factor::Entity as TotpFactor;
+pub use super::totp_recovery_code::Entity as TotpRecoveryCode;
 pub use super::wallet::Entity as Wallet;
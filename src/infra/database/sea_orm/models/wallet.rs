@@ -41,8 +41,10 @@ pub enum Relation {
     BtcAddress,
     #[sea_orm(has_many = "super::invoice::Entity")]
     Invoice,
-    #[sea_orm(has_one = "super::ln_address::Entity")]
+    #[sea_orm(has_many = "super::ln_address::Entity")]
     LnAddress,
+    #[sea_orm(has_many = "super::pay_link::Entity")]
+    PayLink,
     #[sea_orm(has_many = "super::payment::Entity")]
     Payment,
 }
@@ -77,6 +79,12 @@ impl Related<super::ln_address::Entity> for Entity {
     }
 }
 
+impl Related<super::pay_link::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PayLink.def()
+    }
+}
+
 impl Related<super::payment::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Payment.def()
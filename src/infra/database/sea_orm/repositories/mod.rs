@@ -2,22 +2,46 @@ mod connection;
 mod sea_orm_account_repository;
 mod sea_orm_api_key_repository;
 mod sea_orm_asset_repository;
+mod sea_orm_audit_repository;
 mod sea_orm_btc_address_repository;
 mod sea_orm_btc_output_repository;
 mod sea_orm_config_repository;
+mod sea_orm_credential_repository;
+mod sea_orm_invitation_repository;
 mod sea_orm_invoice_repository;
+mod sea_orm_ln_address_domain_repository;
 mod sea_orm_ln_address_repository;
+mod sea_orm_nostr_event_repository;
+mod sea_orm_organization_repository;
+mod sea_orm_passkey_repository;
+mod sea_orm_pay_link_repository;
+mod sea_orm_payjoin_repository;
 mod sea_orm_payment_repository;
+mod sea_orm_rate_limit_store;
+mod sea_orm_session_repository;
+mod sea_orm_totp_repository;
 mod sea_orm_wallet_repository;
 
 pub(crate) use connection::SeaOrmConnection;
 pub use sea_orm_account_repository::*;
 pub use sea_orm_api_key_repository::*;
 pub use sea_orm_asset_repository::*;
+pub use sea_orm_audit_repository::*;
 pub use sea_orm_btc_address_repository::*;
 pub use sea_orm_btc_output_repository::*;
 pub use sea_orm_config_repository::*;
+pub use sea_orm_credential_repository::*;
+pub use sea_orm_invitation_repository::*;
 pub use sea_orm_invoice_repository::*;
+pub use sea_orm_ln_address_domain_repository::*;
 pub use sea_orm_ln_address_repository::*;
+pub use sea_orm_nostr_event_repository::*;
+pub use sea_orm_organization_repository::*;
+pub use sea_orm_passkey_repository::*;
+pub use sea_orm_pay_link_repository::*;
+pub use sea_orm_payjoin_repository::*;
 pub use sea_orm_payment_repository::*;
+pub use sea_orm_rate_limit_store::*;
+pub use sea_orm_session_repository::*;
+pub use sea_orm_totp_repository::*;
 pub use sea_orm_wallet_repository::*;
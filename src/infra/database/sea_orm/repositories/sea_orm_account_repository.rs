@@ -12,13 +12,17 @@ use uuid::Uuid;
 use crate::{
     application::errors::DatabaseError,
     domains::{
-        account::{Account, AccountFilter, AccountPreferences, AccountRepository, AuthProvider, Permission},
+        account::{
+            Account, AccountFilter, AccountPreferences, AccountRepository, AuthIdentity as AccountIdentity,
+            AuthProvider, Permission,
+        },
         payment::PaymentStatus,
         wallet::Wallet as AccountWallet,
     },
     infra::database::sea_orm::models::{
         account, account_preference, auth_identity,
         invoice::Column as InvoiceColumn,
+        ln_address,
         payment::Column as PaymentColumn,
         prelude::{
             Account as AccountEntity, AccountPreference, Asset as AssetEntity, AuthIdentity, Invoice as InvoiceEntity,
@@ -53,8 +57,12 @@ impl SeaOrmAccountRepository {
             .load_one(AssetEntity, &self.db)
             .await
             .map_err(|e| DatabaseError::FindRelated(e.to_string()))?;
+        // Wallets receiving through several addresses expose the oldest one.
         let ln_addresses = models
-            .load_one(LnAddressEntity, &self.db)
+            .load_many(
+                LnAddressEntity::find().order_by_asc(ln_address::Column::CreatedAt),
+                &self.db,
+            )
             .await
             .map_err(|e| DatabaseError::FindRelated(e.to_string()))?;
 
@@ -91,7 +99,7 @@ impl SeaOrmAccountRepository {
             .collect::<HashMap<_, _>>();
 
         let mut wallets = Vec::with_capacity(models.len());
-        for ((wallet_model, asset_model), ln_address_model) in models.into_iter().zip(assets).zip(ln_addresses) {
+        for ((wallet_model, asset_model), ln_address_models) in models.into_iter().zip(assets).zip(ln_addresses) {
             let mut wallet: AccountWallet = wallet_model.into();
             let received_msat = received_by_wallet.remove(&wallet.id).flatten().unwrap_or(0);
             let (sent_msat, fees_paid_msat) = spent_by_wallet.remove(&wallet.id).unwrap_or((None, None));
@@ -99,7 +107,7 @@ impl SeaOrmAccountRepository {
             wallet.balance.sent_msat = sent_msat.unwrap_or(0) as u64;
             wallet.balance.fees_paid_msat = fees_paid_msat.unwrap_or(0) as u64;
             wallet.asset = asset_model.map(Into::into);
-            wallet.ln_address = ln_address_model.map(Into::into);
+            wallet.ln_address = ln_address_models.into_iter().next().map(Into::into);
             wallets.push(wallet);
         }
 
@@ -443,4 +451,52 @@ impl AccountRepository for SeaOrmAccountRepository {
 
         Ok(result.rows_affected)
     }
+
+    async fn find_identities(
+        &self,
+        account_id: Uuid,
+        provider: AuthProvider,
+    ) -> Result<Vec<AccountIdentity>, DatabaseError> {
+        let models = AuthIdentity::find()
+            .filter(auth_identity::Column::AccountId.eq(account_id))
+            .filter(auth_identity::Column::Provider.eq(provider.to_string()))
+            .order_by_asc(auth_identity::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert_identity(
+        &self,
+        account_id: Uuid,
+        provider: AuthProvider,
+        subject: &str,
+    ) -> Result<AccountIdentity, DatabaseError> {
+        let model = auth_identity::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            provider: Set(provider.to_string()),
+            subject: Set(subject.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn delete_identity(&self, account_id: Uuid, provider: AuthProvider, id: Uuid) -> Result<u64, DatabaseError> {
+        let result = AuthIdentity::delete_many()
+            .filter(auth_identity::Column::Id.eq(id))
+            .filter(auth_identity::Column::AccountId.eq(account_id))
+            .filter(auth_identity::Column::Provider.eq(provider.to_string()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
 }
@@ -7,10 +7,10 @@ use crate::{
     },
 };
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sea_orm::{
     sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, ExprTrait, QueryFilter,
-    QueryOrder, QuerySelect, QueryTrait, Set,
+    QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
 };
 use uuid::Uuid;
 
@@ -27,6 +27,28 @@ impl SeaOrmApiKeyRepository {
     }
 }
 
+fn active_model(api_key: ApiKey) -> Result<ActiveModel, DatabaseError> {
+    let permissions_json =
+        serde_json::to_value(&api_key.permissions).map_err(|e| DatabaseError::Insert(e.to_string()))?;
+    let wallet_ids_json = api_key
+        .wallet_ids
+        .map(|ids| serde_json::to_value(ids).map_err(|e| DatabaseError::Insert(e.to_string())))
+        .transpose()?;
+
+    Ok(ActiveModel {
+        id: Set(Uuid::new_v4()),
+        account_id: Set(api_key.account_id),
+        name: Set(api_key.name),
+        key_hash: Set(api_key.key_hash),
+        permissions: Set(permissions_json),
+        description: Set(api_key.description),
+        expires_at: Set(api_key.expires_at.map(|t| t.naive_utc())),
+        wallet_ids: Set(wallet_ids_json),
+        member_account_id: Set(api_key.member_account_id),
+        ..Default::default()
+    })
+}
+
 #[async_trait]
 impl ApiKeyRepository for SeaOrmApiKeyRepository {
     async fn find(&self, id: Uuid) -> Result<Option<ApiKey>, DatabaseError> {
@@ -43,7 +65,7 @@ impl ApiKeyRepository for SeaOrmApiKeyRepository {
             .filter(Column::KeyHash.eq(key_hash))
             .filter(
                 Condition::any()
-                    .add(Expr::col(Column::ExpiresAt).gt(Utc::now()))
+                    .add(Expr::col(Column::ExpiresAt).gt(Utc::now().naive_utc()))
                     .add(Expr::col(Column::ExpiresAt).is_null()),
             )
             .one(&self.db)
@@ -70,21 +92,7 @@ impl ApiKeyRepository for SeaOrmApiKeyRepository {
     }
 
     async fn insert(&self, api_key: ApiKey) -> Result<ApiKey, DatabaseError> {
-        let permissions_json =
-            serde_json::to_value(&api_key.permissions).map_err(|e| DatabaseError::Insert(e.to_string()))?;
-
-        let model = ActiveModel {
-            id: Set(Uuid::new_v4()),
-            account_id: Set(api_key.account_id),
-            name: Set(api_key.name),
-            key_hash: Set(api_key.key_hash),
-            permissions: Set(permissions_json),
-            description: Set(api_key.description),
-            expires_at: Set(api_key.expires_at.map(|t| t.naive_utc())),
-            ..Default::default()
-        };
-
-        let model = model
+        let model = active_model(api_key)?
             .insert(&self.db)
             .await
             .map_err(|e| DatabaseError::Insert(e.to_string()))?;
@@ -92,6 +100,56 @@ impl ApiKeyRepository for SeaOrmApiKeyRepository {
         Ok(model.into())
     }
 
+    async fn rotate(
+        &self,
+        id: Uuid,
+        replacement: ApiKey,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<ApiKey>, DatabaseError> {
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let result = ApiKeyEntity::update_many()
+            .col_expr(Column::ExpiresAt, Expr::value(Some(expires_at.naive_utc())))
+            .filter(Column::Id.eq(id))
+            .exec(&tx)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            tx.rollback()
+                .await
+                .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+            return Ok(None);
+        }
+
+        let model = active_model(replacement)?
+            .insert(&tx)
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(Some(model.into()))
+    }
+
+    async fn record_usage(&self, id: Uuid, ip: Option<String>) -> Result<(), DatabaseError> {
+        ApiKeyEntity::update_many()
+            .col_expr(Column::LastUsedAt, Expr::value(Some(Utc::now().naive_utc())))
+            .col_expr(Column::LastUsedIp, Expr::value(ip))
+            .filter(Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(())
+    }
+
     async fn delete_many(&self, filter: ApiKeyFilter) -> Result<u64, DatabaseError> {
         let result = ApiKeyEntity::delete_many()
             .apply_if(filter.account_id, |q, account_id| {
@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::audit::{chain_entry, AuditEntry, AuditFilter, AuditRepository},
+    infra::database::sea_orm::{
+        models::{
+            audit_chain_head::{ActiveModel as HeadActiveModel, Column as HeadColumn},
+            audit_entry::{ActiveModel, Column},
+            prelude::{AuditChainHead, AuditEntry as AuditEntryEntity},
+        },
+        sea_order,
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmAuditRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmAuditRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AuditRepository for SeaOrmAuditRepository {
+    async fn find_many(&self, filter: AuditFilter) -> Result<Vec<AuditEntry>, DatabaseError> {
+        let models = AuditEntryEntity::find()
+            .apply_if(filter.account_id, |q, account_id| {
+                q.filter(Column::AccountId.eq(account_id))
+            })
+            .apply_if(filter.member_account_id, |q, member_account_id| {
+                q.filter(Column::MemberAccountId.eq(member_account_id))
+            })
+            .apply_if(filter.api_key_id, |q, api_key_id| {
+                q.filter(Column::ApiKeyId.eq(api_key_id))
+            })
+            .apply_if(filter.subject, |q, subject| q.filter(Column::Subject.eq(subject)))
+            .apply_if(filter.resource_id, |q, resource_id| {
+                q.filter(Column::ResourceId.eq(resource_id))
+            })
+            .apply_if(filter.path, |q, path| q.filter(Column::Path.starts_with(path)))
+            .apply_if(filter.method, |q, method| {
+                q.filter(Column::Method.eq(method.to_uppercase()))
+            })
+            .apply_if(filter.created_after, |q, created_after| {
+                q.filter(Column::CreatedAt.gte(created_after.naive_utc()))
+            })
+            .apply_if(filter.created_before, |q, created_before| {
+                q.filter(Column::CreatedAt.lt(created_before.naive_utc()))
+            })
+            .order_by(Column::CreatedAt, sea_order(&filter.order_direction))
+            .offset(filter.offset)
+            .limit(filter.limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert(&self, entry: AuditEntry) -> Result<AuditEntry, DatabaseError> {
+        insert(&self.db, entry).await
+    }
+
+    async fn insert_chained(&self, entry: AuditEntry) -> Result<AuditEntry, DatabaseError> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        // The head is inserted with the first entry, which concurrent writers must not fork either.
+        AuditChainHead::insert(HeadActiveModel {
+            id: Set(CHAIN_HEAD_ID),
+            entry_id: Set(None),
+        })
+        .on_conflict(OnConflict::column(HeadColumn::Id).do_nothing().to_owned())
+        .exec_without_returning(&txn)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        // Locked until committed so that concurrent writers link to the last entry one at a time. SQLite ignores the
+        // lock but the insert above already took its write lock.
+        let head = AuditChainHead::find_by_id(CHAIN_HEAD_ID)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+            .ok_or_else(|| DatabaseError::FindOne("audit chain head not found".to_string()))?;
+
+        let last = match head.entry_id {
+            Some(entry_id) => AuditEntryEntity::find_by_id(entry_id)
+                .one(&txn)
+                .await
+                .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+                .map(AuditEntry::from),
+            None => None,
+        };
+        let entry = insert(&txn, chain_entry(entry, last.as_ref())).await?;
+
+        let mut head: HeadActiveModel = head.into();
+        head.entry_id = Set(Some(entry.id));
+        head.update(&txn)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(entry)
+    }
+}
+
+/// The chain has a single head.
+const CHAIN_HEAD_ID: i32 = 1;
+
+async fn insert(db: &impl ConnectionTrait, entry: AuditEntry) -> Result<AuditEntry, DatabaseError> {
+    let diff = entry
+        .diff
+        .map(|diff| serde_json::to_value(diff).map_err(|e| DatabaseError::Insert(e.to_string())))
+        .transpose()?;
+
+    let model = ActiveModel {
+        id: Set(entry.id),
+        account_id: Set(entry.account_id),
+        member_account_id: Set(entry.member_account_id),
+        api_key_id: Set(entry.api_key_id),
+        subject: Set(entry.subject),
+        source_ip: Set(entry.source_ip),
+        method: Set(entry.method),
+        path: Set(entry.path),
+        resource_id: Set(entry.resource_id),
+        status: Set(entry.status.into()),
+        diff: Set(diff),
+        previous_hash: Set(entry.previous_hash),
+        hash: Set(entry.hash),
+        created_at: Set(entry.created_at.naive_utc()),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+    Ok(model.into())
+}
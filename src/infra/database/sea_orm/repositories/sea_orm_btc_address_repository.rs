@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
-    Set, Unchanged,
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, Unchanged,
 };
 use uuid::Uuid;
 
@@ -51,6 +51,7 @@ where
         let model = BtcAddressEntity::find()
             .filter(Column::WalletId.eq(wallet_id))
             .filter(Column::Used.eq(false))
+            .filter(Column::InvoiceId.is_null())
             .filter(Column::AddressType.eq(address_type.to_string()))
             .order_by_desc(Column::CreatedAt)
             .one(self.db.connection())
@@ -112,6 +113,41 @@ where
         Ok(model.into())
     }
 
+    async fn insert_for_invoice(
+        &self,
+        wallet_id: Uuid,
+        address: &str,
+        address_type: BtcAddressType,
+        invoice_id: Uuid,
+    ) -> Result<BtcAddress, DatabaseError> {
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            wallet_id: Set(wallet_id),
+            address: Set(address.to_owned()),
+            address_type: Set(address_type.to_string()),
+            used: Set(false),
+            invoice_id: Set(Some(invoice_id)),
+            ..Default::default()
+        };
+
+        let model = model
+            .insert(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn find_by_invoice_id(&self, invoice_id: Uuid) -> Result<Option<BtcAddress>, DatabaseError> {
+        let model = BtcAddressEntity::find()
+            .filter(Column::InvoiceId.eq(invoice_id))
+            .one(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
     async fn mark_used(&self, id: Uuid) -> Result<(), DatabaseError> {
         let active_model = ActiveModel {
             id: Unchanged(id),
@@ -139,4 +175,88 @@ where
 
         Ok(result.rows_affected)
     }
+
+    async fn max_silent_payment_label(&self) -> Result<Option<u32>, DatabaseError> {
+        #[derive(FromQueryResult)]
+        struct MaxLabel {
+            max: Option<i32>,
+        }
+
+        let result = BtcAddressEntity::find()
+            .select_only()
+            .column_as(Column::SilentPaymentLabel.max(), "max")
+            .into_model::<MaxLabel>()
+            .one(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(result.and_then(|row| row.max).map(|value| value as u32))
+    }
+
+    async fn insert_silent_payment(
+        &self,
+        wallet_id: Uuid,
+        address: &str,
+        label: u32,
+    ) -> Result<Option<BtcAddress>, DatabaseError> {
+        let id = Uuid::new_v4();
+        let model = ActiveModel {
+            id: Set(id),
+            wallet_id: Set(wallet_id),
+            address: Set(address.to_owned()),
+            address_type: Set(BtcAddressType::SilentPayment.to_string()),
+            used: Set(false),
+            silent_payment_label: Set(Some(label as i32)),
+            ..Default::default()
+        };
+
+        let rows_affected = BtcAddressEntity::insert(model)
+            .on_conflict(OnConflict::column(Column::SilentPaymentLabel).do_nothing().to_owned())
+            .exec_without_returning(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        if rows_affected == 0 {
+            return Ok(None);
+        }
+
+        self.find(id).await
+    }
+
+    async fn find_silent_payment_labels(&self) -> Result<Vec<(u32, Uuid)>, DatabaseError> {
+        let models = BtcAddressEntity::find()
+            .filter(Column::SilentPaymentLabel.is_not_null())
+            .all(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models
+            .into_iter()
+            .filter_map(|model| Some((model.silent_payment_label? as u32, model.wallet_id)))
+            .collect())
+    }
+
+    async fn insert_silent_payment_output(
+        &self,
+        wallet_id: Uuid,
+        address: &str,
+        tweak: &str,
+    ) -> Result<BtcAddress, DatabaseError> {
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            wallet_id: Set(wallet_id),
+            address: Set(address.to_owned()),
+            address_type: Set(BtcAddressType::P2tr.to_string()),
+            used: Set(true),
+            silent_payment_tweak: Set(Some(tweak.to_owned())),
+            ..Default::default()
+        };
+
+        let model = model
+            .insert(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
 }
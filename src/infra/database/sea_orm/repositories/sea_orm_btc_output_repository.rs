@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, QueryFilter, QuerySelect, Set,
-    Unchanged,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, FromQueryResult,
+    QueryFilter, QuerySelect, QueryTrait, Set, Unchanged,
 };
 use uuid::Uuid;
 
@@ -10,10 +12,11 @@ use super::SeaOrmConnection;
 
 use crate::{
     application::errors::DatabaseError,
-    domains::bitcoin::{BtcOutput, BtcOutputRepository},
+    domains::bitcoin::{BtcOutput, BtcOutputRepository, BtcOutputStatus, SilentPaymentOutput},
     infra::database::sea_orm::models::{
+        btc_address,
         btc_output::{ActiveModel, Column},
-        prelude::BtcOutput as BtcOutputEntity,
+        prelude::{BtcAddress as BtcAddressEntity, BtcOutput as BtcOutputEntity},
     },
 };
 
@@ -101,4 +104,71 @@ where
 
         Ok(result.and_then(|row| row.max).map(|value| value as u32))
     }
+
+    async fn find_unconfirmed_or_since(&self, min_block_height: u32) -> Result<Vec<BtcOutput>, DatabaseError> {
+        let models = BtcOutputEntity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::Status.eq(BtcOutputStatus::Unconfirmed.to_string()))
+                    .add(Column::BlockHeight.gte(i64::from(min_block_height))),
+            )
+            .all(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_silent_payment_outputs(
+        &self,
+        status: BtcOutputStatus,
+    ) -> Result<Vec<SilentPaymentOutput>, DatabaseError> {
+        let models = BtcOutputEntity::find()
+            .filter(Column::Status.eq(status.to_string()))
+            .filter(
+                Column::Address.in_subquery(
+                    BtcAddressEntity::find()
+                        .select_only()
+                        .column(btc_address::Column::Address)
+                        .filter(btc_address::Column::SilentPaymentTweak.is_not_null())
+                        .into_query(),
+                ),
+            )
+            .all(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        let addresses: Vec<String> = models.iter().map(|model| model.address.clone()).collect();
+        let tweaks: HashMap<String, String> = BtcAddressEntity::find()
+            .filter(btc_address::Column::Address.is_in(addresses))
+            .all(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?
+            .into_iter()
+            .filter_map(|model| Some((model.address, model.silent_payment_tweak?)))
+            .collect();
+
+        Ok(models
+            .into_iter()
+            .filter_map(|model| {
+                let tweak = tweaks.get(&model.address)?.clone();
+                Some(SilentPaymentOutput {
+                    output: model.into(),
+                    tweak,
+                })
+            })
+            .collect())
+    }
+
+    async fn mark_spent(&self, outpoints: Vec<String>) -> Result<(), DatabaseError> {
+        BtcOutputEntity::update_many()
+            .col_expr(Column::Status, Expr::value(BtcOutputStatus::Spent.to_string()))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Outpoint.is_in(outpoints))
+            .exec(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(())
+    }
 }
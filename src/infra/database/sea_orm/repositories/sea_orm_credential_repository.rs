@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::{Account, AuthProvider, CredentialRepository, LocalCredential, LocalRegistration},
+    infra::database::sea_orm::models::{
+        account, account_preference, auth_identity, invitation, local_credential, password_reset,
+        prelude::{
+            AuthIdentity, Invitation as InvitationEntity, LocalCredential as LocalCredentialEntity,
+            PasswordReset as PasswordResetEntity,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmCredentialRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmCredentialRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+fn local_credential(identity: auth_identity::Model, credential: local_credential::Model) -> LocalCredential {
+    LocalCredential {
+        identity_id: identity.id,
+        account_id: identity.account_id,
+        username: identity.subject,
+        password_hash: credential.password_hash,
+    }
+}
+
+#[async_trait]
+impl CredentialRepository for SeaOrmCredentialRepository {
+    async fn find_by_login(&self, login: &str) -> Result<Option<LocalCredential>, DatabaseError> {
+        let by_username = AuthIdentity::find()
+            .filter(auth_identity::Column::Provider.eq(AuthProvider::Jwt.to_string()))
+            .filter(auth_identity::Column::Subject.eq(login))
+            .find_also_related(LocalCredentialEntity)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        if let Some((identity, Some(credential))) = by_username {
+            return Ok(Some(local_credential(identity, credential)));
+        }
+
+        let by_email = LocalCredentialEntity::find()
+            .filter(local_credential::Column::Email.eq(login))
+            .find_also_related(AuthIdentity)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        match by_email {
+            Some((credential, Some(identity))) => Ok(Some(local_credential(identity, credential))),
+            Some((_, None)) => Err(DatabaseError::FindRelated(
+                "local credential does not reference an auth identity".to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_by_account(&self, account_id: Uuid) -> Result<Option<LocalCredential>, DatabaseError> {
+        let identity_with_credential = AuthIdentity::find()
+            .filter(auth_identity::Column::AccountId.eq(account_id))
+            .filter(auth_identity::Column::Provider.eq(AuthProvider::Jwt.to_string()))
+            .find_also_related(LocalCredentialEntity)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(match identity_with_credential {
+            Some((identity, Some(credential))) => Some(local_credential(identity, credential)),
+            _ => None,
+        })
+    }
+
+    async fn register(&self, registration: LocalRegistration) -> Result<Option<Account>, DatabaseError> {
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let account_id = Uuid::new_v4();
+        let identity_id = Uuid::new_v4();
+        let now = Utc::now().naive_utc();
+        let permissions_json =
+            serde_json::to_value(&registration.permissions).map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        let account_model = account::ActiveModel {
+            id: Set(account_id),
+            display_name: Set(registration.display_name),
+            permissions: Set(permissions_json),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        let preference_model = account_preference::ActiveModel {
+            account_id: Set(account_id),
+            dashboard_settings: Set(json!({})),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        let identity_model = auth_identity::ActiveModel {
+            id: Set(identity_id),
+            account_id: Set(account_id),
+            provider: Set(AuthProvider::Jwt.to_string()),
+            subject: Set(registration.username),
+            created_at: Set(now),
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        local_credential::ActiveModel {
+            identity_id: Set(identity_id),
+            email: Set(registration.email),
+            password_hash: Set(registration.password_hash),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        if let Some(invitation_id) = registration.invitation_id {
+            // Consumed in the same transaction so concurrent registrations cannot share an invitation.
+            let result = InvitationEntity::update_many()
+                .col_expr(invitation::Column::UsedAt, Expr::value(Some(now)))
+                .col_expr(invitation::Column::AccountId, Expr::value(Some(account_id)))
+                .filter(invitation::Column::Id.eq(invitation_id))
+                .filter(invitation::Column::UsedAt.is_null())
+                .filter(invitation::Column::ExpiresAt.gt(now))
+                .exec(&tx)
+                .await
+                .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+            if result.rows_affected == 0 {
+                tx.rollback()
+                    .await
+                    .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+                return Ok(None);
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let mut account: Account = account_model.into();
+        account.identity = Some(identity_model.into());
+        account.preferences = Some(preference_model.into());
+        Ok(Some(account))
+    }
+
+    async fn update_password(&self, identity_id: Uuid, password_hash: String) -> Result<(), DatabaseError> {
+        LocalCredentialEntity::update_many()
+            .col_expr(local_credential::Column::PasswordHash, Expr::value(password_hash))
+            .col_expr(
+                local_credential::Column::UpdatedAt,
+                Expr::value(Some(Utc::now().naive_utc())),
+            )
+            .filter(local_credential::Column::IdentityId.eq(identity_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn insert_password_reset(
+        &self,
+        account_id: Uuid,
+        token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        password_reset::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            token_hash: Set(token_hash),
+            expires_at: Set(expires_at.naive_utc()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn consume_password_reset(&self, token_hash: Vec<u8>) -> Result<Option<Uuid>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let Some(model) = PasswordResetEntity::find()
+            .filter(password_reset::Column::TokenHash.eq(token_hash))
+            .filter(password_reset::Column::UsedAt.is_null())
+            .filter(password_reset::Column::ExpiresAt.gt(now))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        // Only the request that flips `used_at` may use the token.
+        let result = PasswordResetEntity::update_many()
+            .col_expr(password_reset::Column::UsedAt, Expr::value(Some(now)))
+            .filter(password_reset::Column::Id.eq(model.id))
+            .filter(password_reset::Column::UsedAt.is_null())
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok((result.rows_affected == 1).then_some(model.account_id))
+    }
+}
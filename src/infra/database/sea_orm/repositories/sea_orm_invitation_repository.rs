@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+    Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::{Invitation, InvitationFilter, InvitationRepository},
+    infra::database::sea_orm::models::{
+        invitation::{ActiveModel, Column},
+        prelude::Invitation as InvitationEntity,
+    },
+};
+
+use crate::infra::database::sea_orm::sea_order;
+
+#[derive(Clone)]
+pub struct SeaOrmInvitationRepository {
+    pub db: DatabaseConnection,
+}
+
+impl SeaOrmInvitationRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for SeaOrmInvitationRepository {
+    async fn find_by_token_hash(&self, token_hash: Vec<u8>) -> Result<Option<Invitation>, DatabaseError> {
+        let model = InvitationEntity::find()
+            .filter(Column::TokenHash.eq(token_hash))
+            .filter(Column::UsedAt.is_null())
+            .filter(Column::ExpiresAt.gt(Utc::now().naive_utc()))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn find_many(&self, filter: InvitationFilter) -> Result<Vec<Invitation>, DatabaseError> {
+        let models = InvitationEntity::find()
+            .apply_if(filter.ids, |q, ids| q.filter(Column::Id.is_in(ids)))
+            .apply_if(filter.used, |q, used| {
+                if used {
+                    q.filter(Column::UsedAt.is_not_null())
+                } else {
+                    q.filter(Column::UsedAt.is_null())
+                }
+            })
+            .order_by(Column::CreatedAt, sea_order(&filter.order_direction))
+            .offset(filter.offset)
+            .limit(filter.limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert(&self, invitation: Invitation) -> Result<Invitation, DatabaseError> {
+        let permissions_json =
+            serde_json::to_value(&invitation.permissions).map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            token_hash: Set(invitation.token_hash),
+            email: Set(invitation.email),
+            permissions: Set(permissions_json),
+            expires_at: Set(invitation.expires_at.naive_utc()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        let model = model
+            .insert(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn delete_many(&self, filter: InvitationFilter) -> Result<u64, DatabaseError> {
+        let result = InvitationEntity::delete_many()
+            .apply_if(filter.ids, |q, ids| q.filter(Column::Id.is_in(ids)))
+            .apply_if(filter.used, |q, used| {
+                if used {
+                    q.filter(Column::UsedAt.is_not_null())
+                } else {
+                    q.filter(Column::UsedAt.is_null())
+                }
+            })
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
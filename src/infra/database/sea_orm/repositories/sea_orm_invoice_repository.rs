@@ -8,7 +8,7 @@ use crate::{
     },
 };
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
 use sea_orm::{
     sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, ExprTrait, QueryFilter,
     QueryOrder, QuerySelect, QueryTrait, Set, Unchanged,
@@ -106,6 +106,14 @@ where
                 ),
             })
             .apply_if(filter.ledger, |q, l| q.filter(Column::Ledger.eq(l.to_string())))
+            .apply_if(filter.ln_address_id, |q, id| q.filter(Column::LnAddressId.eq(id)))
+            .apply_if(filter.ln_address_alias, |q, alias| {
+                q.filter(Column::LnAddressAlias.eq(alias.to_lowercase()))
+            })
+            .apply_if(filter.pay_link_id, |q, id| q.filter(Column::PayLinkId.eq(id)))
+            .apply_if(filter.reference, |q, reference| {
+                q.filter(Column::Reference.eq(reference))
+            })
             .order_by(order_by_column, sea_order(&filter.order_direction))
             .offset(filter.offset)
             .limit(filter.limit)
@@ -131,6 +139,10 @@ where
             id: Set(id),
             wallet_id: Set(invoice.wallet_id),
             ln_address_id: Set(invoice.ln_address_id),
+            ln_address_alias: Set(invoice.ln_address_alias),
+            payer_data: Set(invoice
+                .payer_data
+                .and_then(|payer_data| serde_json::to_value(payer_data).ok())),
             description: Set(invoice.description),
             amount_msat: Set(invoice.amount_msat.map(|v| v as i64)),
             amount_received_msat: Set(invoice.amount_received_msat.map(|v| v as i64)),
@@ -138,6 +150,10 @@ where
             payment_time: Set(invoice.payment_time.map(|t| t.naive_utc())),
             ledger: Set(invoice.ledger.to_string()),
             btc_output_id: Set(invoice.btc_output_id),
+            split_invoice_id: Set(invoice.split_invoice_id),
+            transfer_id: Set(invoice.transfer_id),
+            pay_link_id: Set(invoice.pay_link_id),
+            reference: Set(invoice.reference),
             ..Default::default()
         };
 
@@ -151,6 +167,7 @@ where
             model.min_final_cltv_expiry_delta = Set((ln_invoice.min_final_cltv_expiry_delta as i64).into());
             model.expiry = Set((ln_invoice.expiry.as_secs() as i64).into());
             model.expires_at = Set(Some(ln_invoice.expires_at.naive_utc()));
+            model.payment_preimage = Set(ln_invoice.payment_preimage);
         }
 
         let result = model
@@ -194,6 +211,15 @@ where
                 Expr::value(invoice.amount_received_msat.map(|v| v as i64)),
             )
             .col_expr(Column::Ledger, Expr::value(invoice.ledger.to_string()))
+            .col_expr(
+                Column::PaymentPreimage,
+                Expr::value(
+                    invoice
+                        .ln_invoice
+                        .as_ref()
+                        .and_then(|ln_invoice| ln_invoice.payment_preimage.clone()),
+                ),
+            )
             .col_expr(Column::UpdatedAt, Expr::value(Some(Utc::now().naive_utc())))
             .filter(Column::Id.eq(invoice.id))
             .filter(Column::PaymentTime.is_null())
@@ -204,6 +230,33 @@ where
         Ok(result.rows_affected == 1)
     }
 
+    async fn unsettle(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = InvoiceEntity::update_many()
+            .col_expr(Column::PaymentTime, Expr::value(Option::<NaiveDateTime>::None))
+            .col_expr(Column::AmountReceivedMsat, Expr::value(Option::<i64>::None))
+            .col_expr(Column::UpdatedAt, Expr::value(Some(Utc::now().naive_utc())))
+            .filter(Column::Id.eq(id))
+            .filter(Column::PaymentTime.is_not_null())
+            .exec(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn expire(&self, id: Uuid) -> Result<(), DatabaseError> {
+        InvoiceEntity::update_many()
+            .col_expr(Column::ExpiresAt, Expr::value(Some(Utc::now().naive_utc())))
+            .col_expr(Column::UpdatedAt, Expr::value(Some(Utc::now().naive_utc())))
+            .filter(Column::Id.eq(id))
+            .filter(Column::PaymentTime.is_null())
+            .exec(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(())
+    }
+
     async fn delete_many(&self, filter: InvoiceFilter) -> Result<u64, DatabaseError> {
         let now = Utc::now().naive_utc();
         let result = InvoiceEntity::delete_many()
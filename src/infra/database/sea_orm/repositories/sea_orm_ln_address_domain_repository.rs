@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+use crate::domains::ln_address::{LnAddressDomain, LnAddressDomainRepository};
+use crate::infra::database::sea_orm::models::{
+    ln_address_domain::{ActiveModel, Column},
+    prelude::LnAddressDomain as LnAddressDomainEntity,
+};
+
+#[derive(Clone)]
+pub struct SeaOrmLnAddressDomainRepository {
+    pub db: DatabaseConnection,
+}
+
+impl SeaOrmLnAddressDomainRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl LnAddressDomainRepository for SeaOrmLnAddressDomainRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<LnAddressDomain>, DatabaseError> {
+        let model = LnAddressDomainEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<LnAddressDomain>, DatabaseError> {
+        let model = LnAddressDomainEntity::find()
+            .filter(Column::Name.eq(name))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn find_all(&self) -> Result<Vec<LnAddressDomain>, DatabaseError> {
+        let models = LnAddressDomainEntity::find()
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert(&self, name: &str, host: &str) -> Result<LnAddressDomain, DatabaseError> {
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(name.to_owned()),
+            host: Set(host.to_owned()),
+            ..Default::default()
+        };
+
+        let model = model
+            .insert(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<u64, DatabaseError> {
+        let result = LnAddressDomainEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -1,6 +1,5 @@
 use async_trait::async_trait;
 use chrono::Utc;
-use nostr_sdk::prelude::PublicKey;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
     Set, Unchanged,
@@ -8,7 +7,9 @@ use sea_orm::{
 use uuid::Uuid;
 
 use crate::application::errors::DatabaseError;
-use crate::domains::ln_address::{LnAddress, LnAddressFilter, LnAddressRepository};
+use crate::domains::ln_address::{
+    LnAddress, LnAddressFilter, LnAddressPayProfile, LnAddressRepository, LnAddressSplit,
+};
 use crate::infra::database::sea_orm::models::{
     ln_address::{ActiveModel, Column},
     prelude::LnAddress as LnAddressEntity,
@@ -37,18 +38,18 @@ impl LnAddressRepository for SeaOrmLnAddressRepository {
         Ok(model.map(Into::into))
     }
 
-    async fn find_by_account_id(&self, account_id: Uuid) -> Result<Option<LnAddress>, DatabaseError> {
-        let model = LnAddressEntity::find()
-            .filter(Column::AccountId.eq(account_id))
-            .one(&self.db)
-            .await
-            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
-
-        Ok(model.map(Into::into))
-    }
+    async fn find_by_username(
+        &self,
+        domain_id: Option<Uuid>,
+        username: &str,
+    ) -> Result<Option<LnAddress>, DatabaseError> {
+        let domain = match domain_id {
+            Some(domain_id) => Column::DomainId.eq(domain_id),
+            None => Column::DomainId.is_null(),
+        };
 
-    async fn find_by_username(&self, username: &str) -> Result<Option<LnAddress>, DatabaseError> {
         let model = LnAddressEntity::find()
+            .filter(domain)
             .filter(Column::Username.eq(username))
             .one(&self.db)
             .await
@@ -62,6 +63,7 @@ impl LnAddressRepository for SeaOrmLnAddressRepository {
             .apply_if(filter.account_id, |q, id| q.filter(Column::AccountId.eq(id)))
             .apply_if(filter.wallet_id, |q, id| q.filter(Column::WalletId.eq(id)))
             .apply_if(filter.username, |q, username| q.filter(Column::Username.eq(username)))
+            .apply_if(filter.domain_id, |q, id| q.filter(Column::DomainId.eq(id)))
             .apply_if(filter.ids, |q, ids| q.filter(Column::Id.is_in(ids)))
             .apply_if(filter.active, |q, active| q.filter(Column::Active.eq(active)))
             .order_by(Column::CreatedAt, sea_order(&filter.order_direction))
@@ -74,22 +76,18 @@ impl LnAddressRepository for SeaOrmLnAddressRepository {
         Ok(models.into_iter().map(Into::into).collect())
     }
 
-    async fn insert(
-        &self,
-        account_id: Uuid,
-        wallet_id: Uuid,
-        username: &str,
-        allows_nostr: bool,
-        nostr_pubkey: Option<PublicKey>,
-    ) -> Result<LnAddress, DatabaseError> {
+    async fn insert(&self, ln_address: LnAddress) -> Result<LnAddress, DatabaseError> {
         let model = ActiveModel {
             id: Set(Uuid::new_v4()),
-            account_id: Set(account_id),
-            wallet_id: Set(wallet_id),
-            username: Set(username.to_owned()),
-            allows_nostr: Set(allows_nostr),
-            nostr_pubkey: Set(nostr_pubkey.map(|k| k.to_string())),
-            active: Set(true),
+            account_id: Set(ln_address.account_id),
+            wallet_id: Set(ln_address.wallet_id),
+            domain_id: Set(ln_address.domain_id),
+            username: Set(ln_address.username),
+            allows_nostr: Set(ln_address.allows_nostr),
+            nostr_pubkey: Set(ln_address.nostr_pubkey.map(|k| k.to_string())),
+            active: Set(ln_address.active),
+            pay_profile: Set(pay_profile_value(&ln_address.pay_profile)),
+            splits: Set(splits_value(&ln_address.splits)),
             ..Default::default()
         };
 
@@ -105,11 +103,14 @@ impl LnAddressRepository for SeaOrmLnAddressRepository {
         let model = ActiveModel {
             id: Unchanged(ln_address.id),
             account_id: Unchanged(ln_address.account_id),
-            wallet_id: Unchanged(ln_address.wallet_id),
+            wallet_id: Set(ln_address.wallet_id),
+            domain_id: Unchanged(ln_address.domain_id),
             username: Set(ln_address.username),
             allows_nostr: Set(ln_address.allows_nostr),
             nostr_pubkey: Set(ln_address.nostr_pubkey.map(|k| k.to_string())),
             active: Set(ln_address.active),
+            pay_profile: Set(pay_profile_value(&ln_address.pay_profile)),
+            splits: Set(splits_value(&ln_address.splits)),
             updated_at: Set(Some(Utc::now().naive_utc())),
             ..Default::default()
         };
@@ -128,6 +129,7 @@ impl LnAddressRepository for SeaOrmLnAddressRepository {
             .apply_if(filter.wallet_id, |q, id| q.filter(Column::WalletId.eq(id)))
             .apply_if(filter.ids, |q, ids| q.filter(Column::Id.is_in(ids)))
             .apply_if(filter.username, |q, username| q.filter(Column::Username.eq(username)))
+            .apply_if(filter.domain_id, |q, id| q.filter(Column::DomainId.eq(id)))
             .exec(&self.db)
             .await
             .map_err(|e| DatabaseError::Delete(e.to_string()))?;
@@ -135,3 +137,16 @@ impl LnAddressRepository for SeaOrmLnAddressRepository {
         Ok(result.rows_affected)
     }
 }
+
+/// Default profiles are stored as `NULL` so that addresses follow the server defaults.
+fn pay_profile_value(pay_profile: &LnAddressPayProfile) -> Option<serde_json::Value> {
+    (*pay_profile != LnAddressPayProfile::default())
+        .then(|| serde_json::to_value(pay_profile).ok())
+        .flatten()
+}
+
+fn splits_value(splits: &[LnAddressSplit]) -> Option<serde_json::Value> {
+    (!splits.is_empty())
+        .then(|| serde_json::to_value(splits).ok())
+        .flatten()
+}
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::OnConflict, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::NostrEventRepository,
+    infra::database::sea_orm::models::{
+        nostr_auth_event::{ActiveModel, Column},
+        prelude::NostrAuthEvent,
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmNostrEventRepository {
+    pub db: DatabaseConnection,
+}
+
+impl SeaOrmNostrEventRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl NostrEventRepository for SeaOrmNostrEventRepository {
+    async fn insert(&self, id: String, expires_at: DateTime<Utc>) -> Result<bool, DatabaseError> {
+        NostrAuthEvent::delete_many()
+            .filter(Column::ExpiresAt.lt(Utc::now().naive_utc()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        // The primary key lets a single instance accept the event, however many receive it concurrently.
+        let n_inserted = NostrAuthEvent::insert(ActiveModel {
+            id: Set(id),
+            expires_at: Set(expires_at.naive_utc()),
+        })
+        .on_conflict(OnConflict::column(Column::Id).do_nothing().to_owned())
+        .exec_without_returning(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(n_inserted == 1)
+    }
+}
@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::{Organization, OrganizationMember, OrganizationRepository, OrganizationRole},
+    infra::database::sea_orm::models::{
+        account, account_preference, organization, organization_member,
+        prelude::{Organization as OrganizationEntity, OrganizationMember as OrganizationMemberEntity},
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmOrganizationRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmOrganizationRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for SeaOrmOrganizationRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<Organization>, DatabaseError> {
+        let model = OrganizationEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn find_many(&self, account_id: Uuid) -> Result<Vec<Organization>, DatabaseError> {
+        let memberships = OrganizationMemberEntity::find()
+            .filter(organization_member::Column::AccountId.eq(account_id))
+            .find_also_related(OrganizationEntity)
+            .order_by_asc(organization_member::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        memberships
+            .into_iter()
+            .map(|(member_model, organization_model)| {
+                let organization_model = organization_model.ok_or_else(|| {
+                    DatabaseError::FindRelated("organization member does not reference an organization".to_string())
+                })?;
+                let member: OrganizationMember = member_model.into();
+                let mut organization: Organization = organization_model.into();
+                organization.role = Some(member.role);
+                Ok(organization)
+            })
+            .collect()
+    }
+
+    async fn insert(&self, name: String, owner_id: Uuid) -> Result<Organization, DatabaseError> {
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let id = Uuid::new_v4();
+        let now = Utc::now().naive_utc();
+
+        // The account owning the organization's resources. Members act for it, so it holds no permissions.
+        account::ActiveModel {
+            id: Set(id),
+            display_name: Set(Some(name.clone())),
+            permissions: Set(json!([])),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        account_preference::ActiveModel {
+            account_id: Set(id),
+            dashboard_settings: Set(json!({})),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        let model = organization::ActiveModel {
+            id: Set(id),
+            name: Set(name),
+            created_at: Set(now),
+            updated_at: Set(None),
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        organization_member::ActiveModel {
+            organization_id: Set(id),
+            account_id: Set(owner_id),
+            role: Set(OrganizationRole::Owner.to_string()),
+            created_at: Set(now),
+            updated_at: Set(None),
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let mut organization: Organization = model.into();
+        organization.role = Some(OrganizationRole::Owner);
+        Ok(organization)
+    }
+
+    async fn update(&self, id: Uuid, name: String) -> Result<Option<Organization>, DatabaseError> {
+        let result = OrganizationEntity::update_many()
+            .col_expr(organization::Column::Name, Expr::value(name))
+            .col_expr(
+                organization::Column::UpdatedAt,
+                Expr::value(Some(Utc::now().naive_utc())),
+            )
+            .filter(organization::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        self.find(id).await
+    }
+
+    async fn find_member(
+        &self,
+        organization_id: Uuid,
+        account_id: Uuid,
+    ) -> Result<Option<OrganizationMember>, DatabaseError> {
+        let model = OrganizationMemberEntity::find_by_id((organization_id, account_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn find_members(&self, organization_id: Uuid) -> Result<Vec<OrganizationMember>, DatabaseError> {
+        let models = OrganizationMemberEntity::find()
+            .filter(organization_member::Column::OrganizationId.eq(organization_id))
+            .order_by_asc(organization_member::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert_member(&self, member: OrganizationMember) -> Result<OrganizationMember, DatabaseError> {
+        let model = organization_member::ActiveModel {
+            organization_id: Set(member.organization_id),
+            account_id: Set(member.account_id),
+            role: Set(member.role.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            updated_at: Set(None),
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn update_member(
+        &self,
+        organization_id: Uuid,
+        account_id: Uuid,
+        role: OrganizationRole,
+    ) -> Result<Option<OrganizationMember>, DatabaseError> {
+        let mut update = OrganizationMemberEntity::update_many()
+            .col_expr(organization_member::Column::Role, Expr::value(role.to_string()))
+            .col_expr(
+                organization_member::Column::UpdatedAt,
+                Expr::value(Some(Utc::now().naive_utc())),
+            )
+            .filter(organization_member::Column::OrganizationId.eq(organization_id))
+            .filter(organization_member::Column::AccountId.eq(account_id));
+        if role != OrganizationRole::Owner {
+            update = update.filter(keeps_an_owner(organization_id, account_id));
+        }
+
+        let result = update
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        self.find_member(organization_id, account_id).await
+    }
+
+    async fn delete_member(&self, organization_id: Uuid, account_id: Uuid) -> Result<u64, DatabaseError> {
+        let result = OrganizationMemberEntity::delete_many()
+            .filter(organization_member::Column::OrganizationId.eq(organization_id))
+            .filter(organization_member::Column::AccountId.eq(account_id))
+            .filter(keeps_an_owner(organization_id, account_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+/// Matches members who are not owners, or owners of organizations with another owner, so that a single
+/// statement never leaves an organization without owners.
+fn keeps_an_owner(organization_id: Uuid, account_id: Uuid) -> Condition {
+    let other_owners = OrganizationMemberEntity::find()
+        .select_only()
+        .column(organization_member::Column::OrganizationId)
+        .filter(organization_member::Column::OrganizationId.eq(organization_id))
+        .filter(organization_member::Column::Role.eq(OrganizationRole::Owner.to_string()))
+        .filter(organization_member::Column::AccountId.ne(account_id))
+        .into_query();
+
+    Condition::any()
+        .add(organization_member::Column::Role.ne(OrganizationRole::Owner.to_string()))
+        .add(organization_member::Column::OrganizationId.in_subquery(other_owners))
+}
@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::{Passkey, PasskeyChallenge, PasskeyRepository},
+    infra::database::sea_orm::models::{
+        auth_identity, passkey_challenge, passkey_credential,
+        prelude::{
+            AuthIdentity, PasskeyChallenge as PasskeyChallengeEntity, PasskeyCredential as PasskeyCredentialEntity,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmPasskeyRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmPasskeyRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+fn passkey(model: passkey_credential::Model, account_id: Uuid) -> Passkey {
+    Passkey {
+        id: model.id,
+        account_id,
+        name: model.name,
+        identity_id: model.identity_id,
+        credential_id: model.credential_id,
+        public_key: model.public_key,
+        sign_count: model.sign_count as u32,
+        created_at: model.created_at.and_utc(),
+        last_used_at: model.last_used_at.map(|t| t.and_utc()),
+    }
+}
+
+fn passkey_challenge(model: passkey_challenge::Model) -> PasskeyChallenge {
+    PasskeyChallenge {
+        id: model.id,
+        challenge: model.challenge,
+        account_id: model.account_id,
+        expires_at: model.expires_at.and_utc(),
+        created_at: model.created_at.and_utc(),
+    }
+}
+
+#[async_trait]
+impl PasskeyRepository for SeaOrmPasskeyRepository {
+    async fn find_by_credential_id(&self, credential_id: Vec<u8>) -> Result<Option<Passkey>, DatabaseError> {
+        let credential_with_identity = PasskeyCredentialEntity::find()
+            .filter(passkey_credential::Column::CredentialId.eq(credential_id))
+            .find_also_related(AuthIdentity)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        match credential_with_identity {
+            Some((model, Some(identity))) => Ok(Some(passkey(model, identity.account_id))),
+            Some((_, None)) => Err(DatabaseError::FindRelated(
+                "passkey credential does not reference an auth identity".to_string(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_many(&self, account_id: Uuid) -> Result<Vec<Passkey>, DatabaseError> {
+        let models = PasskeyCredentialEntity::find()
+            .filter(passkey_credential::Column::IdentityId.in_subquery(identities_of(account_id)))
+            .order_by_asc(passkey_credential::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(|model| passkey(model, account_id)).collect())
+    }
+
+    async fn insert(&self, new_passkey: Passkey) -> Result<Passkey, DatabaseError> {
+        let model = passkey_credential::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            identity_id: Set(new_passkey.identity_id),
+            credential_id: Set(new_passkey.credential_id),
+            public_key: Set(new_passkey.public_key),
+            sign_count: Set(new_passkey.sign_count.into()),
+            name: Set(new_passkey.name),
+            created_at: Set(Utc::now().naive_utc()),
+            last_used_at: Set(None),
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(passkey(model, new_passkey.account_id))
+    }
+
+    async fn record_use(&self, id: Uuid, sign_count: u32) -> Result<bool, DatabaseError> {
+        // Conditional on the stored counter so that a cloned authenticator cannot replay an older counter.
+        let counter_filter = if sign_count == 0 {
+            passkey_credential::Column::SignCount.eq(0)
+        } else {
+            passkey_credential::Column::SignCount.lt(i64::from(sign_count))
+        };
+
+        let result = PasskeyCredentialEntity::update_many()
+            .col_expr(
+                passkey_credential::Column::SignCount,
+                Expr::value(i64::from(sign_count)),
+            )
+            .col_expr(
+                passkey_credential::Column::LastUsedAt,
+                Expr::value(Some(Utc::now().naive_utc())),
+            )
+            .filter(passkey_credential::Column::Id.eq(id))
+            .filter(counter_filter)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn delete(&self, account_id: Uuid, id: Uuid) -> Result<u64, DatabaseError> {
+        let result = PasskeyCredentialEntity::delete_many()
+            .filter(passkey_credential::Column::Id.eq(id))
+            .filter(passkey_credential::Column::IdentityId.in_subquery(identities_of(account_id)))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn insert_challenge(&self, challenge: PasskeyChallenge) -> Result<(), DatabaseError> {
+        PasskeyChallengeEntity::delete_many()
+            .filter(passkey_challenge::Column::ExpiresAt.lte(Utc::now().naive_utc()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        passkey_challenge::ActiveModel {
+            id: Set(challenge.id),
+            challenge: Set(challenge.challenge),
+            account_id: Set(challenge.account_id),
+            expires_at: Set(challenge.expires_at.naive_utc()),
+            created_at: Set(challenge.created_at.naive_utc()),
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn consume_challenge(&self, id: Uuid) -> Result<Option<PasskeyChallenge>, DatabaseError> {
+        let Some(model) = PasskeyChallengeEntity::find_by_id(id)
+            .filter(passkey_challenge::Column::ExpiresAt.gt(Utc::now().naive_utc()))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        // Only the request that deletes the challenge may answer it.
+        let result = PasskeyChallengeEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(passkey_challenge(model)))
+    }
+}
+
+fn identities_of(account_id: Uuid) -> sea_orm::sea_query::SelectStatement {
+    AuthIdentity::find()
+        .select_only()
+        .column(auth_identity::Column::Id)
+        .filter(auth_identity::Column::AccountId.eq(account_id))
+        .into_query()
+}
@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+    Set, Unchanged,
+};
+use uuid::Uuid;
+
+use crate::application::errors::DatabaseError;
+use crate::domains::lnurl::{PayLink, PayLinkFilter, PayLinkRepository};
+use crate::infra::database::sea_orm::models::{
+    pay_link::{ActiveModel, Column},
+    prelude::PayLink as PayLinkEntity,
+};
+use crate::infra::database::sea_orm::sea_order;
+
+#[derive(Clone)]
+pub struct SeaOrmPayLinkRepository {
+    pub db: DatabaseConnection,
+}
+
+impl SeaOrmPayLinkRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PayLinkRepository for SeaOrmPayLinkRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<PayLink>, DatabaseError> {
+        let model = PayLinkEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn find_many(&self, filter: PayLinkFilter) -> Result<Vec<PayLink>, DatabaseError> {
+        let models = PayLinkEntity::find()
+            .apply_if(filter.wallet_id, |q, id| q.filter(Column::WalletId.eq(id)))
+            .apply_if(filter.ids, |q, ids| q.filter(Column::Id.is_in(ids)))
+            .apply_if(filter.active, |q, active| q.filter(Column::Active.eq(active)))
+            .order_by(Column::CreatedAt, sea_order(&filter.order_direction))
+            .offset(filter.offset)
+            .limit(filter.limit)
+            .all(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn insert(&self, pay_link: PayLink) -> Result<PayLink, DatabaseError> {
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            wallet_id: Set(pay_link.wallet_id),
+            name: Set(pay_link.name),
+            description: Set(pay_link.description),
+            amount_msat: Set(pay_link.amount_msat.map(|v| v as i64)),
+            reference: Set(pay_link.reference),
+            active: Set(pay_link.active),
+            ..Default::default()
+        };
+
+        let model = model
+            .insert(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn update(&self, pay_link: PayLink) -> Result<PayLink, DatabaseError> {
+        let model = ActiveModel {
+            id: Unchanged(pay_link.id),
+            wallet_id: Unchanged(pay_link.wallet_id),
+            name: Set(pay_link.name),
+            description: Set(pay_link.description),
+            amount_msat: Set(pay_link.amount_msat.map(|v| v as i64)),
+            reference: Set(pay_link.reference),
+            active: Set(pay_link.active),
+            updated_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        };
+
+        let model = model
+            .update(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<u64, DatabaseError> {
+        let result = PayLinkEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
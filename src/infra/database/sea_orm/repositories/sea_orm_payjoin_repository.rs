@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use uuid::Uuid;
+
+use super::SeaOrmConnection;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::bitcoin::{PayjoinFallback, PayjoinRepository},
+    infra::database::sea_orm::models::{
+        payjoin_contribution, payjoin_fallback, payjoin_input,
+        prelude::{
+            PayjoinContribution as PayjoinContributionEntity, PayjoinFallback as PayjoinFallbackEntity,
+            PayjoinInput as PayjoinInputEntity,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmPayjoinRepository<C = DatabaseConnection> {
+    db: C,
+}
+
+impl<C> SeaOrmPayjoinRepository<C> {
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl<C> PayjoinRepository for SeaOrmPayjoinRepository<C>
+where
+    C: SeaOrmConnection,
+{
+    async fn insert_inputs(&self, outpoints: Vec<String>) -> Result<bool, DatabaseError> {
+        if outpoints.is_empty() {
+            return Ok(true);
+        }
+
+        let count = outpoints.len() as u64;
+        let models = outpoints.into_iter().map(|outpoint| payjoin_input::ActiveModel {
+            outpoint: Set(outpoint),
+            created_at: Set(Utc::now().naive_utc()),
+        });
+
+        let rows_affected = PayjoinInputEntity::insert_many(models)
+            .on_conflict(
+                OnConflict::column(payjoin_input::Column::Outpoint)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(rows_affected == count)
+    }
+
+    async fn insert_fallback(&self, fallback: PayjoinFallback) -> Result<(), DatabaseError> {
+        let model = payjoin_fallback::ActiveModel {
+            id: Set(fallback.id),
+            txid: Set(fallback.txid),
+            tx_hex: Set(fallback.tx_hex),
+            lock_id: Set(fallback.locked_utxo.id),
+            locked_txid: Set(fallback.locked_utxo.txid),
+            locked_output_index: Set(i64::from(fallback.locked_utxo.output_index)),
+            broadcast_after: Set(fallback.broadcast_after.naive_utc()),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        model
+            .insert(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_due_fallbacks(&self) -> Result<Vec<PayjoinFallback>, DatabaseError> {
+        let models = PayjoinFallbackEntity::find()
+            .filter(payjoin_fallback::Column::BroadcastAfter.lte(Utc::now().naive_utc()))
+            .order_by_asc(payjoin_fallback::Column::BroadcastAfter)
+            .all(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn delete_fallback(&self, id: Uuid) -> Result<(), DatabaseError> {
+        PayjoinFallbackEntity::delete_by_id(id)
+            .exec(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn insert_contribution(&self, outpoint: String, amount_sat: u64) -> Result<(), DatabaseError> {
+        let model = payjoin_contribution::ActiveModel {
+            outpoint: Set(outpoint),
+            amount_sat: Set(amount_sat as i64),
+            created_at: Set(Utc::now().naive_utc()),
+        };
+
+        model
+            .insert(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_contribution(&self, outpoint: &str) -> Result<Option<u64>, DatabaseError> {
+        let model = PayjoinContributionEntity::find_by_id(outpoint)
+            .one(self.db.connection())
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(|model| model.amount_sat as u64))
+    }
+}
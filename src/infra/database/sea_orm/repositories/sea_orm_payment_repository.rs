@@ -109,7 +109,7 @@ where
             })
             .unwrap_or((None, None, None));
 
-        let (internal_ln_address, internal_btc_address, internal_payment_hash) = payment
+        let (internal_ln_address, internal_btc_address, internal_payment_hash, split_invoice_id, transfer_id) = payment
             .internal
             .as_ref()
             .map(|internal| {
@@ -117,9 +117,11 @@ where
                     internal.ln_address.clone(),
                     internal.btc_address.clone(),
                     internal.payment_hash.clone(),
+                    internal.split_invoice_id,
+                    internal.transfer_id,
                 )
             })
-            .unwrap_or((None, None, None));
+            .unwrap_or((None, None, None, None, None));
 
         let model = ActiveModel {
             id: Set(Uuid::new_v4()),
@@ -139,6 +141,8 @@ where
             raw_success_action: Set(raw_success_action),
             payment_preimage: Set(payment_preimage),
             btc_block_height: Set(block_height.map(i64::from)),
+            split_invoice_id: Set(split_invoice_id),
+            transfer_id: Set(transfer_id),
             ..Default::default()
         }
         .insert(self.db.connection())
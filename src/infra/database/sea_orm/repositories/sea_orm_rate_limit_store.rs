@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect,
+    Set, TransactionTrait,
+};
+
+use crate::{
+    application::errors::DatabaseError,
+    infra::{
+        axum::{RateLimit, RateLimitStore, TokenBucket},
+        database::sea_orm::models::{
+            prelude::RateLimitBucket,
+            rate_limit_bucket::{ActiveModel, Column},
+        },
+    },
+};
+
+/// Buckets shared by every instance using the database.
+#[derive(Clone)]
+pub struct SeaOrmRateLimitStore {
+    pub db: DatabaseConnection,
+}
+
+impl SeaOrmRateLimitStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for SeaOrmRateLimitStore {
+    async fn acquire(&self, key: &str, limit: &RateLimit) -> Result<Option<Duration>, DatabaseError> {
+        let now = Utc::now();
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let bucket = TokenBucket::full(limit, now);
+        RateLimitBucket::insert(ActiveModel {
+            key: Set(key.to_string()),
+            tokens: Set(bucket.tokens),
+            updated_at: Set(bucket.updated_at.naive_utc()),
+        })
+        .on_conflict(OnConflict::column(Column::Key).do_nothing().to_owned())
+        .exec_without_returning(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        // Locked until committed so that concurrent instances take tokens one at a time.
+        let model = RateLimitBucket::find_by_id(key)
+            .lock_exclusive()
+            .one(&tx)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+            .ok_or_else(|| DatabaseError::FindOne(format!("rate limit bucket {key} not found")))?;
+
+        let mut bucket = TokenBucket {
+            tokens: model.tokens,
+            updated_at: model.updated_at.and_utc(),
+        };
+        let retry_after = bucket.take(limit, now);
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.tokens = Set(bucket.tokens);
+        active_model.updated_at = Set(bucket.updated_at.naive_utc());
+        active_model
+            .update(&tx)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(retry_after)
+    }
+
+    async fn peek(&self, key: &str, limit: &RateLimit) -> Result<Option<Duration>, DatabaseError> {
+        let model = RateLimitBucket::find_by_id(key)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.and_then(|model| {
+            let bucket = TokenBucket {
+                tokens: model.tokens,
+                updated_at: model.updated_at.and_utc(),
+            };
+            bucket.peek(limit, Utc::now())
+        }))
+    }
+
+    async fn sweep(&self, idle_since: DateTime<Utc>) -> Result<u64, DatabaseError> {
+        let result = RateLimitBucket::delete_many()
+            .filter(Column::UpdatedAt.lt(idle_since.naive_utc()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
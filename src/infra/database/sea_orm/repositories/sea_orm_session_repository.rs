@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::{Session, SessionRepository},
+    infra::database::sea_orm::models::{
+        auth_session::{ActiveModel, Column},
+        prelude::AuthSession as AuthSessionEntity,
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmSessionRepository {
+    pub db: DatabaseConnection,
+}
+
+impl SeaOrmSessionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SeaOrmSessionRepository {
+    async fn find(&self, id: Uuid) -> Result<Option<Session>, DatabaseError> {
+        let model = AuthSessionEntity::find_by_id(id)
+            .filter(Column::ExpiresAt.gt(Utc::now().naive_utc()))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn insert(
+        &self,
+        account_id: Uuid,
+        refresh_token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<Session, DatabaseError> {
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            refresh_token_hash: Set(refresh_token_hash),
+            expires_at: Set(expires_at.naive_utc()),
+            created_at: Set(Utc::now().naive_utc()),
+            verified_at: Set(verified_at.map(|t| t.naive_utc())),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        Ok(model.into())
+    }
+
+    async fn refresh(
+        &self,
+        refresh_token_hash: Vec<u8>,
+        new_refresh_token_hash: Vec<u8>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Option<Session>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let Some(model) = AuthSessionEntity::find()
+            .filter(Column::RefreshTokenHash.eq(refresh_token_hash.clone()))
+            .filter(Column::ExpiresAt.gt(now))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        // Only the request that replaces the current refresh token may use it.
+        let result = AuthSessionEntity::update_many()
+            .col_expr(Column::RefreshTokenHash, Expr::value(new_refresh_token_hash))
+            .col_expr(Column::ExpiresAt, Expr::value(expires_at.naive_utc()))
+            .col_expr(Column::RefreshedAt, Expr::value(Some(now)))
+            .filter(Column::Id.eq(model.id))
+            .filter(Column::RefreshTokenHash.eq(refresh_token_hash))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        if result.rows_affected != 1 {
+            return Ok(None);
+        }
+
+        Ok(Some(model.into()))
+    }
+
+    async fn verify(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = AuthSessionEntity::update_many()
+            .col_expr(Column::VerifiedAt, Expr::value(Some(Utc::now().naive_utc())))
+            .filter(Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn delete_by_refresh_token(&self, refresh_token_hash: Vec<u8>) -> Result<Option<Session>, DatabaseError> {
+        let Some(model) = AuthSessionEntity::find()
+            .filter(Column::RefreshTokenHash.eq(refresh_token_hash))
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let result = AuthSessionEntity::delete_by_id(model.id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok((result.rows_affected == 1).then(|| model.into()))
+    }
+
+    async fn delete_by_account(&self, account_id: Uuid) -> Result<u64, DatabaseError> {
+        let result = AuthSessionEntity::delete_many()
+            .filter(Column::AccountId.eq(account_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
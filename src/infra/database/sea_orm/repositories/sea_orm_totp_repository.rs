@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    application::errors::DatabaseError,
+    domains::account::{TotpFactor, TotpRepository},
+    infra::database::sea_orm::models::{
+        prelude::{TotpFactor as TotpFactorEntity, TotpRecoveryCode as TotpRecoveryCodeEntity},
+        totp_factor, totp_recovery_code,
+    },
+};
+
+#[derive(Clone)]
+pub struct SeaOrmTotpRepository {
+    db: DatabaseConnection,
+}
+
+impl SeaOrmTotpRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl TotpRepository for SeaOrmTotpRepository {
+    async fn find(&self, account_id: Uuid) -> Result<Option<TotpFactor>, DatabaseError> {
+        let model = TotpFactorEntity::find_by_id(account_id)
+            .one(&self.db)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+
+        Ok(model.map(Into::into))
+    }
+
+    async fn insert_pending(&self, account_id: Uuid, secret: Vec<u8>) -> Result<Option<TotpFactor>, DatabaseError> {
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let existing = TotpFactorEntity::find_by_id(account_id)
+            .one(&tx)
+            .await
+            .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
+        if existing.as_ref().is_some_and(|factor| factor.confirmed_at.is_some()) {
+            tx.rollback()
+                .await
+                .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+            return Ok(None);
+        }
+
+        TotpFactorEntity::delete_many()
+            .filter(totp_factor::Column::AccountId.eq(account_id))
+            .filter(totp_factor::Column::ConfirmedAt.is_null())
+            .exec(&tx)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        let model = totp_factor::ActiveModel {
+            account_id: Set(account_id),
+            secret: Set(secret),
+            last_used_step: Set(0),
+            confirmed_at: Set(None),
+            created_at: Set(Utc::now().naive_utc()),
+        }
+        .insert(&tx)
+        .await
+        .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(Some(model.into()))
+    }
+
+    async fn confirm(
+        &self,
+        account_id: Uuid,
+        step: i64,
+        recovery_code_hashes: Vec<Vec<u8>>,
+    ) -> Result<bool, DatabaseError> {
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+        let now = Utc::now().naive_utc();
+
+        let result = TotpFactorEntity::update_many()
+            .col_expr(totp_factor::Column::ConfirmedAt, Expr::value(Some(now)))
+            .col_expr(totp_factor::Column::LastUsedStep, Expr::value(step))
+            .filter(totp_factor::Column::AccountId.eq(account_id))
+            .filter(totp_factor::Column::ConfirmedAt.is_null())
+            .exec(&tx)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        if result.rows_affected == 0 {
+            tx.rollback()
+                .await
+                .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+            return Ok(false);
+        }
+
+        TotpRecoveryCodeEntity::delete_many()
+            .filter(totp_recovery_code::Column::AccountId.eq(account_id))
+            .exec(&tx)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        if !recovery_code_hashes.is_empty() {
+            let models = recovery_code_hashes
+                .into_iter()
+                .map(|code_hash| totp_recovery_code::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    account_id: Set(account_id),
+                    code_hash: Set(code_hash),
+                    used_at: Set(None),
+                    created_at: Set(now),
+                });
+
+            TotpRecoveryCodeEntity::insert_many(models)
+                .exec_without_returning(&tx)
+                .await
+                .map_err(|e| DatabaseError::Insert(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    async fn use_step(&self, account_id: Uuid, step: i64) -> Result<bool, DatabaseError> {
+        // Conditional on the last used step so that concurrent requests cannot both use the same code.
+        let result = TotpFactorEntity::update_many()
+            .col_expr(totp_factor::Column::LastUsedStep, Expr::value(step))
+            .filter(totp_factor::Column::AccountId.eq(account_id))
+            .filter(totp_factor::Column::LastUsedStep.lt(step))
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn use_recovery_code(&self, account_id: Uuid, code_hash: Vec<u8>) -> Result<bool, DatabaseError> {
+        let result = TotpRecoveryCodeEntity::update_many()
+            .col_expr(
+                totp_recovery_code::Column::UsedAt,
+                Expr::value(Some(Utc::now().naive_utc())),
+            )
+            .filter(totp_recovery_code::Column::AccountId.eq(account_id))
+            .filter(totp_recovery_code::Column::CodeHash.eq(code_hash))
+            .filter(totp_recovery_code::Column::UsedAt.is_null())
+            .exec(&self.db)
+            .await
+            .map_err(|e| DatabaseError::Update(e.to_string()))?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn delete(&self, account_id: Uuid) -> Result<u64, DatabaseError> {
+        let tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        TotpRecoveryCodeEntity::delete_many()
+            .filter(totp_recovery_code::Column::AccountId.eq(account_id))
+            .exec(&tx)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        let result = TotpFactorEntity::delete_many()
+            .filter(totp_factor::Column::AccountId.eq(account_id))
+            .exec(&tx)
+            .await
+            .map_err(|e| DatabaseError::Delete(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -2,8 +2,8 @@ use async_trait::async_trait;
 use chrono::Utc;
 use sea_orm::{
     sea_query::{Expr, OnConflict},
-    ColumnTrait, DatabaseConnection, EntityTrait, ExprTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect,
-    QueryTrait, Set,
+    ColumnTrait, DatabaseConnection, EntityTrait, ExprTrait, LoaderTrait, ModelTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, Set,
 };
 use uuid::Uuid;
 
@@ -11,20 +11,34 @@ use super::SeaOrmConnection;
 use crate::infra::database::sea_orm::sea_order;
 
 use crate::{
-    application::errors::DatabaseError,
+    application::{composition::Ledger, errors::DatabaseError},
     domains::{
+        bitcoin::BtcOutputStatus,
         payment::PaymentStatus,
         wallet::{Balance, Contact, Wallet, WalletFilter, WalletOverview, WalletRepository},
     },
     infra::database::sea_orm::models::{
         contact::ContactModel,
         invoice::Column as InvoiceColumn,
+        ln_address::Column as LnAddressColumn,
         payment::Column as PaymentColumn,
         prelude::{Asset as AssetEntity, BtcAddress, BtcOutput, Invoice, LnAddress, Payment, Wallet as WalletEntity},
         wallet::{ActiveModel, Column},
     },
 };
 
+/// Sum of on-chain deposits seen but not yet credited (still awaiting confirmations), excluding dropped outputs.
+fn pending_onchain_sum() -> String {
+    format!(
+        "CAST(SUM(CASE WHEN invoice.ledger = '{ledger}' AND invoice.payment_time IS NULL \
+         AND (invoice.btc_output_id IS NULL \
+         OR invoice.btc_output_id NOT IN (SELECT id FROM btc_output WHERE status = '{failed}')) \
+         THEN invoice.amount_msat ELSE 0 END) AS BIGINT)",
+        ledger = Ledger::Onchain,
+        failed = BtcOutputStatus::Failed
+    )
+}
+
 #[derive(Clone)]
 pub struct SeaOrmWalletRepository<C = DatabaseConnection> {
     db: C,
@@ -63,6 +77,7 @@ where
             .map_err(|e| DatabaseError::FindRelated(e.to_string()))?;
         let ln_address = model
             .find_related(LnAddress)
+            .order_by_asc(LnAddressColumn::CreatedAt)
             .one(self.db.connection())
             .await
             .map_err(|e| DatabaseError::FindRelated(e.to_string()))?;
@@ -166,11 +181,18 @@ where
     }
 
     async fn find_many_overview(&self) -> Result<Vec<WalletOverview>, DatabaseError> {
-        let wallets_with_ln = WalletEntity::find()
-            .find_also_related(LnAddress)
+        let wallet_models = WalletEntity::find()
             .all(self.db.connection())
             .await
             .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
+        // Wallets receiving through several addresses expose the oldest one.
+        let ln_addresses = wallet_models
+            .load_many(
+                LnAddress::find().order_by_asc(LnAddressColumn::CreatedAt),
+                self.db.connection(),
+            )
+            .await
+            .map_err(|e| DatabaseError::FindRelated(e.to_string()))?;
 
         let invoice_aggs = Invoice::find()
             .select_only()
@@ -179,9 +201,10 @@ where
                 Expr::cust("CAST(SUM(invoice.amount_received_msat) AS BIGINT)"),
                 "received_msat",
             )
+            .column_as(Expr::cust(pending_onchain_sum()), "pending_msat")
             .column_as(InvoiceColumn::Id.count(), "n_invoices")
             .group_by(InvoiceColumn::WalletId)
-            .into_tuple::<(Uuid, Option<i64>, i64)>()
+            .into_tuple::<(Uuid, Option<i64>, Option<i64>, i64)>()
             .all(self.db.connection())
             .await
             .map_err(|e| DatabaseError::FindMany(e.to_string()))?;
@@ -215,21 +238,24 @@ where
 
         let invoice_map: std::collections::HashMap<_, _> = invoice_aggs
             .into_iter()
-            .map(|(id, received, count)| (id, (received, count)))
+            .map(|(id, received, pending, count)| (id, (received, pending, count)))
             .collect();
         let payment_map: std::collections::HashMap<_, _> = payment_aggs
             .into_iter()
             .map(|(id, sent, fees, count, contacts)| (id, (sent, fees, count, contacts)))
             .collect();
 
-        let mut overviews = Vec::with_capacity(wallets_with_ln.len());
-        for (wallet_model, ln_address_model) in wallets_with_ln {
+        let mut overviews = Vec::with_capacity(wallet_models.len());
+        for (wallet_model, ln_address_models) in wallet_models.into_iter().zip(ln_addresses) {
             let wallet_id = wallet_model.id;
             let asset_model = AssetEntity::find_by_id(wallet_model.asset_id)
                 .one(self.db.connection())
                 .await
                 .map_err(|e| DatabaseError::FindOne(e.to_string()))?;
-            let (received_msat, n_invoices) = invoice_map.get(&wallet_id).map(|(r, n)| (*r, *n)).unwrap_or((None, 0));
+            let (received_msat, pending_msat, n_invoices) = invoice_map
+                .get(&wallet_id)
+                .map(|(r, p, n)| (*r, *p, *n))
+                .unwrap_or((None, None, 0));
             let (sent_msat, fees_paid_msat, n_payments, n_contacts) = payment_map
                 .get(&wallet_id)
                 .map(|(s, f, np, nc)| (*s, *f, *np, *nc))
@@ -247,11 +273,12 @@ where
                     fees_paid_msat: fees_paid_msat.unwrap_or(0) as u64,
                     reserved_msat: wallet_model.reserved_amount as u64,
                     available_msat: wallet_model.available_amount,
+                    pending_msat: pending_msat.unwrap_or(0) as u64,
                 },
                 n_payments: n_payments as u32,
                 n_invoices: n_invoices as u32,
                 n_contacts: n_contacts as u32,
-                ln_address: ln_address_model.map(Into::into),
+                ln_address: ln_address_models.into_iter().next().map(Into::into),
                 created_at: wallet_model.created_at.and_utc(),
                 updated_at: wallet_model.updated_at.map(|t| t.and_utc()),
             });
@@ -299,18 +326,19 @@ where
 
         let (available_msat, reserved_msat) = wallet_amounts.unwrap_or((0, 0));
 
-        let received = Invoice::find()
+        let (received, pending) = Invoice::find()
             .filter(InvoiceColumn::WalletId.eq(id))
             .select_only()
             .column_as(
                 Expr::cust("CAST(SUM(invoice.amount_received_msat) AS BIGINT)"),
                 "received_msat",
             )
-            .into_tuple::<Option<i64>>()
+            .column_as(Expr::cust(pending_onchain_sum()), "pending_msat")
+            .into_tuple::<(Option<i64>, Option<i64>)>()
             .one(self.db.connection())
             .await
             .map_err(|e| DatabaseError::FindOne(e.to_string()))?
-            .unwrap_or(None);
+            .unwrap_or((None, None));
 
         let (sent_msat, fees_paid_msat) = Payment::find()
             .filter(PaymentColumn::WalletId.eq(id))
@@ -330,6 +358,7 @@ where
             fees_paid_msat: fees_paid_msat.unwrap_or(0) as u64,
             reserved_msat: reserved_msat as u64,
             available_msat,
+            pending_msat: pending.unwrap_or(0) as u64,
         })
     }
 
@@ -17,10 +17,13 @@ use crate::{
 };
 
 use super::{
-    SeaOrmAccountRepository, SeaOrmApiKeyRepository, SeaOrmAssetRepository, SeaOrmBitcoinAddressRepository,
-    SeaOrmBitcoinOutputRepository, SeaOrmConfig, SeaOrmConfigRepository, SeaOrmEventProjectionUnitOfWork,
-    SeaOrmInvoiceRepository, SeaOrmLnAddressRepository, SeaOrmPaymentRepository, SeaOrmPaymentUnitOfWork,
-    SeaOrmWalletRepository,
+    SeaOrmAccountRepository, SeaOrmApiKeyRepository, SeaOrmAssetRepository, SeaOrmAuditRepository,
+    SeaOrmBitcoinAddressRepository, SeaOrmBitcoinOutputRepository, SeaOrmConfig, SeaOrmConfigRepository,
+    SeaOrmCredentialRepository, SeaOrmEventProjectionUnitOfWork, SeaOrmInvitationRepository, SeaOrmInvoiceRepository,
+    SeaOrmLnAddressDomainRepository, SeaOrmLnAddressRepository, SeaOrmNostrEventRepository,
+    SeaOrmOrganizationRepository, SeaOrmPasskeyRepository, SeaOrmPayLinkRepository, SeaOrmPayjoinRepository,
+    SeaOrmPaymentRepository, SeaOrmPaymentUnitOfWork, SeaOrmRateLimitStore, SeaOrmSessionRepository,
+    SeaOrmTotpRepository, SeaOrmWalletRepository,
 };
 
 pub struct SeaOrmStore;
@@ -34,15 +37,27 @@ impl SeaOrmStore {
     pub fn from_connection(db_conn: DatabaseConnection) -> AppStore {
         AppStore::new(
             Arc::new(SeaOrmLnAddressRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmLnAddressDomainRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmPayLinkRepository::new(db_conn.clone())),
             Arc::new(SeaOrmPaymentRepository::new(db_conn.clone())),
             Arc::new(SeaOrmInvoiceRepository::new(db_conn.clone())),
             Arc::new(SeaOrmWalletRepository::new(db_conn.clone())),
             Arc::new(SeaOrmAccountRepository::new(db_conn.clone())),
             Arc::new(SeaOrmAssetRepository::new(db_conn.clone())),
             Arc::new(SeaOrmApiKeyRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmCredentialRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmInvitationRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmSessionRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmTotpRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmPasskeyRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmNostrEventRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmOrganizationRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmAuditRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmRateLimitStore::new(db_conn.clone())),
             Arc::new(SeaOrmConfigRepository::new(db_conn.clone())),
             Arc::new(SeaOrmBitcoinAddressRepository::new(db_conn.clone())),
             Arc::new(SeaOrmBitcoinOutputRepository::new(db_conn.clone())),
+            Arc::new(SeaOrmPayjoinRepository::new(db_conn.clone())),
             Arc::new(SeaOrmHealthProbe::new(db_conn.clone())),
             Arc::new(SeaOrmPaymentUnitOfWork::new(db_conn.clone())),
             Arc::new(SeaOrmEventProjectionUnitOfWork::new(db_conn)),
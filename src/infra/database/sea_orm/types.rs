@@ -5,11 +5,16 @@ use chrono::Utc;
 use crate::{
     application::composition::Ledger,
     domains::{
-        account::{Account, AccountPreferences, ApiKey, AuthIdentity},
+        account::{
+            Account, AccountPreferences, ApiKey, AuthIdentity, Invitation, Organization, OrganizationMember, Session,
+            TotpFactor,
+        },
         asset::Asset,
-        bitcoin::{BtcAddress, BtcOutput},
+        audit::AuditEntry,
+        bitcoin::{BtcAddress, BtcLockedUtxo, BtcOutput, PayjoinFallback},
         invoice::{Invoice, InvoiceStatus, LnInvoice},
-        ln_address::LnAddress,
+        ln_address::{LnAddress, LnAddressDomain},
+        lnurl::PayLink,
         payment::{BtcPayment, InternalPayment, LnPayment, Payment},
         wallet::{Balance, Contact, Wallet},
     },
@@ -20,9 +25,14 @@ use swissknife_types::OrderDirection;
 
 use super::models::{
     account::Model as AccountModel, account_preference::Model as AccountPreferenceModel, api_key::Model as ApiKeyModel,
-    asset::Model as AssetModel, auth_identity::Model as AuthIdentityModel, btc_address::Model as BitcoinAddressModel,
-    btc_output::Model as BitcoinOutputModel, contact::ContactModel, invoice::Model as InvoiceModel,
-    ln_address::Model as LnAddressModel, payment::Model as PaymentModel, wallet::Model as WalletModel,
+    asset::Model as AssetModel, audit_entry::Model as AuditEntryModel, auth_identity::Model as AuthIdentityModel,
+    auth_session::Model as AuthSessionModel, btc_address::Model as BitcoinAddressModel,
+    btc_output::Model as BitcoinOutputModel, contact::ContactModel, invitation::Model as InvitationModel,
+    invoice::Model as InvoiceModel, ln_address::Model as LnAddressModel,
+    ln_address_domain::Model as LnAddressDomainModel, organization::Model as OrganizationModel,
+    organization_member::Model as OrganizationMemberModel, pay_link::Model as PayLinkModel,
+    payjoin_fallback::Model as PayjoinFallbackModel, payment::Model as PaymentModel,
+    totp_factor::Model as TotpFactorModel, wallet::Model as WalletModel,
 };
 
 const ASSERTION_MSG: &str = "should parse successfully by assertion";
@@ -109,6 +119,7 @@ impl From<InvoiceModel> for Invoice {
                 payment_secret: model.payment_secret.expect(ASSERTION_MSG),
                 expiry: Duration::from_secs(model.expiry.expect(ASSERTION_MSG) as u64),
                 expires_at: model.expires_at.expect(ASSERTION_MSG).and_utc(),
+                payment_preimage: model.payment_preimage,
             }),
             _ => None,
         };
@@ -117,6 +128,14 @@ impl From<InvoiceModel> for Invoice {
             id: model.id,
             wallet_id: model.wallet_id,
             ln_address_id: model.ln_address_id,
+            ln_address_alias: model.ln_address_alias,
+            payer_data: model
+                .payer_data
+                .and_then(|payer_data| serde_json::from_value(payer_data).ok()),
+            split_invoice_id: model.split_invoice_id,
+            transfer_id: model.transfer_id,
+            pay_link_id: model.pay_link_id,
+            reference: model.reference,
             description: model.description,
             amount_msat: model.amount_msat.map(|v| v as u64),
             amount_received_msat: model.amount_received_msat.map(|v| v as u64),
@@ -166,6 +185,8 @@ impl From<PaymentModel> for Payment {
             ln_address: model.ln_address.clone(),
             btc_address: model.btc_address.clone(),
             payment_hash: model.payment_hash.clone(),
+            split_invoice_id: model.split_invoice_id,
+            transfer_id: model.transfer_id,
         });
 
         Payment {
@@ -204,9 +225,47 @@ impl From<LnAddressModel> for LnAddress {
             account_id: model.account_id,
             wallet_id: model.wallet_id,
             username: model.username,
+            domain_id: model.domain_id,
             active: model.active,
             allows_nostr: model.allows_nostr,
             nostr_pubkey: model.nostr_pubkey.map(|k| k.parse().expect(ASSERTION_MSG)),
+            pay_profile: model
+                .pay_profile
+                .and_then(|profile| serde_json::from_value(profile).ok())
+                .unwrap_or_default(),
+            splits: model
+                .splits
+                .and_then(|splits| serde_json::from_value(splits).ok())
+                .unwrap_or_default(),
+            created_at: model.created_at.and_utc(),
+            updated_at: model.updated_at.map(|t| t.and_utc()),
+        }
+    }
+}
+
+impl From<LnAddressDomainModel> for LnAddressDomain {
+    fn from(model: LnAddressDomainModel) -> Self {
+        LnAddressDomain {
+            id: model.id,
+            name: model.name,
+            host: model.host,
+            created_at: model.created_at.and_utc(),
+            updated_at: model.updated_at.map(|t| t.and_utc()),
+        }
+    }
+}
+
+impl From<PayLinkModel> for PayLink {
+    fn from(model: PayLinkModel) -> Self {
+        PayLink {
+            id: model.id,
+            wallet_id: model.wallet_id,
+            name: model.name,
+            description: model.description,
+            amount_msat: model.amount_msat.map(|v| v as u64),
+            reference: model.reference,
+            active: model.active,
+            lnurl: None,
             created_at: model.created_at.and_utc(),
             updated_at: model.updated_at.map(|t| t.and_utc()),
         }
@@ -232,6 +291,88 @@ impl From<WalletModel> for Wallet {
     }
 }
 
+impl From<InvitationModel> for Invitation {
+    fn from(model: InvitationModel) -> Self {
+        Invitation {
+            id: model.id,
+            token: None,
+            token_hash: model.token_hash,
+            email: model.email,
+            permissions: serde_json::from_value(model.permissions).expect(ASSERTION_MSG),
+            account_id: model.account_id,
+            expires_at: model.expires_at.and_utc(),
+            used_at: model.used_at.map(|t| t.and_utc()),
+            created_at: model.created_at.and_utc(),
+        }
+    }
+}
+
+impl From<AuthSessionModel> for Session {
+    fn from(model: AuthSessionModel) -> Self {
+        Session {
+            id: model.id,
+            account_id: model.account_id,
+            verified_at: model.verified_at.map(|t| t.and_utc()),
+        }
+    }
+}
+
+impl From<TotpFactorModel> for TotpFactor {
+    fn from(model: TotpFactorModel) -> Self {
+        TotpFactor {
+            account_id: model.account_id,
+            secret: model.secret,
+            last_used_step: model.last_used_step,
+            confirmed_at: model.confirmed_at.map(|t| t.and_utc()),
+        }
+    }
+}
+
+impl From<OrganizationModel> for Organization {
+    fn from(model: OrganizationModel) -> Self {
+        Organization {
+            id: model.id,
+            name: model.name,
+            role: None,
+            created_at: model.created_at.and_utc(),
+            updated_at: model.updated_at.map(|t| t.and_utc()),
+        }
+    }
+}
+
+impl From<OrganizationMemberModel> for OrganizationMember {
+    fn from(model: OrganizationMemberModel) -> Self {
+        OrganizationMember {
+            organization_id: model.organization_id,
+            account_id: model.account_id,
+            role: model.role.parse().expect(ASSERTION_MSG),
+            created_at: model.created_at.and_utc(),
+            updated_at: model.updated_at.map(|t| t.and_utc()),
+        }
+    }
+}
+
+impl From<AuditEntryModel> for AuditEntry {
+    fn from(model: AuditEntryModel) -> Self {
+        AuditEntry {
+            id: model.id,
+            account_id: model.account_id,
+            member_account_id: model.member_account_id,
+            api_key_id: model.api_key_id,
+            subject: model.subject,
+            source_ip: model.source_ip,
+            method: model.method,
+            path: model.path,
+            resource_id: model.resource_id,
+            status: model.status as u16,
+            diff: model.diff.and_then(|diff| serde_json::from_value(diff).ok()),
+            previous_hash: model.previous_hash,
+            hash: model.hash,
+            created_at: model.created_at.and_utc(),
+        }
+    }
+}
+
 impl From<ApiKeyModel> for ApiKey {
     fn from(model: ApiKeyModel) -> Self {
         ApiKey {
@@ -244,6 +385,12 @@ impl From<ApiKeyModel> for ApiKey {
             description: model.description,
             created_at: model.created_at.and_utc(),
             expires_at: model.expires_at.map(|t| t.and_utc()),
+            wallet_ids: model
+                .wallet_ids
+                .map(|ids| serde_json::from_value(ids).expect(ASSERTION_MSG)),
+            last_used_at: model.last_used_at.map(|t| t.and_utc()),
+            last_used_ip: model.last_used_ip,
+            member_account_id: model.member_account_id,
         }
     }
 }
@@ -265,6 +412,22 @@ impl From<BitcoinOutputModel> for BtcOutput {
     }
 }
 
+impl From<PayjoinFallbackModel> for PayjoinFallback {
+    fn from(model: PayjoinFallbackModel) -> Self {
+        PayjoinFallback {
+            id: model.id,
+            txid: model.txid,
+            tx_hex: model.tx_hex,
+            locked_utxo: BtcLockedUtxo {
+                id: model.lock_id,
+                txid: model.locked_txid,
+                output_index: model.locked_output_index as u32,
+            },
+            broadcast_after: model.broadcast_after.and_utc(),
+        }
+    }
+}
+
 impl From<BitcoinAddressModel> for BtcAddress {
     fn from(model: BitcoinAddressModel) -> Self {
         BtcAddress {
@@ -273,6 +436,8 @@ impl From<BitcoinAddressModel> for BtcAddress {
             address: model.address,
             address_type: model.address_type.parse().expect(ASSERTION_MSG),
             used: model.used,
+            payjoin_uri: None,
+            invoice_id: model.invoice_id,
             created_at: model.created_at.and_utc(),
             updated_at: model.updated_at.map(|t| t.and_utc()),
         }
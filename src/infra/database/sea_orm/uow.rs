@@ -1,10 +1,11 @@
 use async_trait::async_trait;
 use sea_orm::{DatabaseConnection, TransactionTrait};
+use uuid::Uuid;
 
 use crate::{
     application::errors::{ApplicationError, DataError, DatabaseError},
     domains::{
-        bitcoin::{BtcAddress, BtcAddressRepository, BtcOutput, BtcOutputRepository},
+        bitcoin::{BtcAddress, BtcAddressRepository, BtcOutput, BtcOutputRepository, BtcOutputStatus},
         event::EventProjectionUnitOfWork,
         invoice::{Invoice, InvoiceRepository},
         payment::{Payment, PaymentRepository, PaymentStatus, PaymentUnitOfWork},
@@ -206,6 +207,38 @@ impl PaymentUnitOfWork for SeaOrmPaymentUnitOfWork {
 
         Ok(payment)
     }
+
+    async fn transfer(&self, mut payment: Payment, invoice: Invoice) -> Result<Payment, ApplicationError> {
+        let transfer_id = payment.internal.as_ref().and_then(|internal| internal.transfer_id);
+        if transfer_id.is_none() || transfer_id != invoice.transfer_id {
+            return Err(DataError::Inconsistency("Transfer legs must share a transfer ID.".to_string()).into());
+        }
+
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let wallet_repo = SeaOrmWalletRepository::new(&txn);
+
+        if !wallet_repo.debit(payment.wallet_id, payment.amount_msat).await? {
+            return Err(DataError::InsufficientFunds(payment.amount_msat as f64).into());
+        }
+        payment.reserved_amount = 0;
+        let payment = SeaOrmPaymentRepository::new(&txn).insert(payment).await?;
+
+        if let Some(received_msat) = invoice.amount_received_msat {
+            wallet_repo.credit(invoice.wallet_id, received_msat).await?;
+        }
+        SeaOrmInvoiceRepository::new(&txn).insert(invoice).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(payment)
+    }
 }
 
 #[derive(Clone)]
@@ -221,7 +254,11 @@ impl SeaOrmEventProjectionUnitOfWork {
 
 #[async_trait]
 impl EventProjectionUnitOfWork for SeaOrmEventProjectionUnitOfWork {
-    async fn settle_incoming_invoice(&self, invoice: Invoice) -> Result<Invoice, ApplicationError> {
+    async fn settle_incoming_invoice(
+        &self,
+        invoice: Invoice,
+        splits: Vec<(Payment, Invoice)>,
+    ) -> Result<Invoice, ApplicationError> {
         let txn = self
             .db
             .begin()
@@ -230,30 +267,60 @@ impl EventProjectionUnitOfWork for SeaOrmEventProjectionUnitOfWork {
 
         let invoice_repo = SeaOrmInvoiceRepository::new(&txn);
         let wallet_repo = SeaOrmWalletRepository::new(&txn);
+        let address_repo = SeaOrmBitcoinAddressRepository::new(&txn);
 
-        let settled = if invoice.id.is_nil() {
+        let (settled, credited) = if invoice.id.is_nil() {
             // New, already-settled incoming invoice (e.g. an on-chain deposit first seen confirmed).
             if let Some(received_msat) = invoice.amount_received_msat {
                 wallet_repo.credit(invoice.wallet_id, received_msat).await?;
             }
-            invoice_repo.insert(invoice).await?
+            (invoice_repo.insert(invoice).await?, true)
         } else if invoice_repo.settle(&invoice).await? {
             // Pending invoice settled now: credit the receiver exactly once.
             if let Some(received_msat) = invoice.amount_received_msat {
                 wallet_repo.credit(invoice.wallet_id, received_msat).await?;
             }
-            invoice_repo
+            let settled = invoice_repo
                 .find(invoice.id)
                 .await?
-                .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?
+                .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?;
+            (settled, true)
         } else {
             // Already settled: idempotent replay, no credit.
-            invoice_repo
+            let settled = invoice_repo
                 .find(invoice.id)
                 .await?
-                .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?
+                .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?;
+            (settled, false)
         };
 
+        // Shares are passed on along with the credit they are taken from, so a replay never pays them twice.
+        if credited {
+            let payment_repo = SeaOrmPaymentRepository::new(&txn);
+            for (payment, split_invoice) in splits {
+                if !wallet_repo.debit(payment.wallet_id, payment.amount_msat).await? {
+                    return Err(DataError::Inconsistency(format!(
+                        "Wallet balance missing for the split of invoice {}",
+                        settled.id
+                    ))
+                    .into());
+                }
+                payment_repo.insert(payment).await?;
+
+                if let Some(received_msat) = split_invoice.amount_received_msat {
+                    wallet_repo.credit(split_invoice.wallet_id, received_msat).await?;
+                }
+                invoice_repo.insert(split_invoice).await?;
+            }
+        }
+
+        // The on-chain leg of a payment request is settled along with its invoice.
+        if let Some(address) = address_repo.find_by_invoice_id(settled.id).await? {
+            if !address.used {
+                address_repo.mark_used(address.id).await?;
+            }
+        }
+
         txn.commit()
             .await
             .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
@@ -278,6 +345,12 @@ impl EventProjectionUnitOfWork for SeaOrmEventProjectionUnitOfWork {
         let invoice_repo = SeaOrmInvoiceRepository::new(&txn);
         let wallet_repo = SeaOrmWalletRepository::new(&txn);
 
+        // A change of block for an output we had already seen mined means it was reorged out.
+        let reorged = output_repo
+            .find_by_outpoint(&output.outpoint)
+            .await?
+            .is_some_and(|previous| previous.block_height.is_some() && previous.block_height != output.block_height);
+
         let stored_output = output_repo.upsert(output).await?;
 
         if !address.used {
@@ -303,6 +376,14 @@ impl EventProjectionUnitOfWork for SeaOrmEventProjectionUnitOfWork {
                         .await?
                         .ok_or_else(|| DataError::NotFound("Invoice not found.".to_string()))?
                 } else {
+                    if reorged && invoice_repo.unsettle(existing.id).await? {
+                        if let Some(received_msat) = existing.amount_received_msat {
+                            reverse_deposit_credit(&wallet_repo, existing.wallet_id, existing.id, received_msat)
+                                .await?;
+                        }
+                        existing.payment_time = None;
+                        existing.amount_received_msat = None;
+                    }
                     // Still unconfirmed: keep the invoice linked to the (re-)seen output.
                     existing.btc_output_id = Some(stored_output.id);
                     invoice_repo.update(existing).await?
@@ -325,4 +406,60 @@ impl EventProjectionUnitOfWork for SeaOrmEventProjectionUnitOfWork {
 
         Ok(invoice)
     }
+    async fn drop_onchain_deposit(&self, output: BtcOutput) -> Result<Option<Invoice>, ApplicationError> {
+        let txn = self
+            .db
+            .begin()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        let output_repo = SeaOrmBitcoinOutputRepository::new(&txn);
+        let invoice_repo = SeaOrmInvoiceRepository::new(&txn);
+        let wallet_repo = SeaOrmWalletRepository::new(&txn);
+
+        let stored_output = output_repo
+            .upsert(BtcOutput {
+                status: BtcOutputStatus::Failed,
+                block_height: None,
+                ..output
+            })
+            .await?;
+
+        let invoice = match invoice_repo.find_by_btc_output_id(stored_output.id).await? {
+            Some(existing) => {
+                // Reverse the credit exactly once, whether or not the deposit was confirmed yet.
+                if invoice_repo.unsettle(existing.id).await? {
+                    if let Some(received_msat) = existing.amount_received_msat {
+                        reverse_deposit_credit(&wallet_repo, existing.wallet_id, existing.id, received_msat).await?;
+                    }
+                }
+                invoice_repo.expire(existing.id).await?;
+                invoice_repo.find(existing.id).await?
+            }
+            None => None,
+        };
+
+        txn.commit()
+            .await
+            .map_err(|e| DatabaseError::Transaction(e.to_string()))?;
+
+        Ok(invoice)
+    }
+}
+
+/// Reverses the credit of a deposit that no longer stands. Funds the user already spent cannot be taken back, so
+/// the balance goes negative by the shortfall and stays owed until the wallet is funded again.
+async fn reverse_deposit_credit(
+    wallet_repo: &impl WalletRepository,
+    wallet_id: Uuid,
+    invoice_id: Uuid,
+    received_msat: u64,
+) -> Result<(), ApplicationError> {
+    if !wallet_repo.debit_confirmed(wallet_id, received_msat).await? {
+        return Err(
+            DataError::Inconsistency(format!("Wallet balance missing for reversed deposit {invoice_id}")).into(),
+        );
+    }
+
+    Ok(())
 }
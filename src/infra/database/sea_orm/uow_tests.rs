@@ -7,7 +7,7 @@
 
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use chrono::Utc;
+use chrono::{SubsecRound, Utc};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{
     ColumnTrait, ConnectOptions, ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, EntityTrait,
@@ -17,18 +17,41 @@ use uuid::Uuid;
 
 use crate::application::composition::Ledger;
 use crate::application::errors::{ApplicationError, DataError};
-use crate::domains::account::{AccountFilter, AccountRepository, ApiKey, ApiKeyRepository, AuthProvider, Permission};
+use crate::domains::account::{
+    AccountFilter, AccountRepository, ApiKey, ApiKeyRepository, AuthProvider, CredentialRepository, Invitation,
+    InvitationFilter, InvitationRepository, LocalRegistration, NostrEventRepository, OrganizationMember,
+    OrganizationRepository, OrganizationRole, Passkey, PasskeyChallenge, PasskeyRepository, Permission,
+    SessionRepository, TotpRepository,
+};
+use crate::domains::audit::{audit_hash, AuditDiff, AuditEntry, AuditFilter, AuditRepository};
 use crate::domains::event::EventProjectionUnitOfWork;
-use crate::domains::invoice::{Invoice, InvoiceRepository};
-use crate::domains::ln_address::LnAddressRepository;
-use crate::domains::payment::{LnPayment, Payment, PaymentRepository, PaymentStatus, PaymentUnitOfWork};
-use crate::domains::{asset::AssetRepository, bitcoin::BtcNetwork, wallet::WalletRepository};
+use crate::domains::invoice::{Invoice, InvoiceFilter, InvoiceRepository, InvoiceStatus};
+use crate::domains::ln_address::{
+    LnAddress, LnAddressDomainRepository, LnAddressFilter, LnAddressRepository, LnAddressSplit,
+};
+use crate::domains::lnurl::{PayLink, PayLinkFilter, PayLinkRepository};
+use crate::domains::payment::{
+    InternalPayment, LnPayment, Payment, PaymentFilter, PaymentRepository, PaymentStatus, PaymentUnitOfWork,
+};
+use crate::domains::{
+    asset::AssetRepository,
+    bitcoin::{
+        BtcAddressRepository, BtcAddressType, BtcLockedUtxo, BtcNetwork, BtcOutput, BtcOutputRepository,
+        BtcOutputStatus, PayjoinFallback, PayjoinRepository,
+    },
+    wallet::WalletRepository,
+};
+use crate::infra::axum::{RateLimit, RateLimitStore};
 
 use super::models::{prelude::Wallet, wallet};
 use super::{
-    SeaOrmAccountRepository, SeaOrmApiKeyRepository, SeaOrmAssetRepository, SeaOrmEventProjectionUnitOfWork,
-    SeaOrmInvoiceRepository, SeaOrmLnAddressRepository, SeaOrmPaymentRepository, SeaOrmPaymentUnitOfWork,
-    SeaOrmWalletRepository,
+    SeaOrmAccountRepository, SeaOrmApiKeyRepository, SeaOrmAssetRepository, SeaOrmAuditRepository,
+    SeaOrmBitcoinAddressRepository, SeaOrmBitcoinOutputRepository, SeaOrmCredentialRepository,
+    SeaOrmEventProjectionUnitOfWork, SeaOrmInvitationRepository, SeaOrmInvoiceRepository,
+    SeaOrmLnAddressDomainRepository, SeaOrmLnAddressRepository, SeaOrmNostrEventRepository,
+    SeaOrmOrganizationRepository, SeaOrmPasskeyRepository, SeaOrmPayLinkRepository, SeaOrmPayjoinRepository,
+    SeaOrmPaymentRepository, SeaOrmPaymentUnitOfWork, SeaOrmRateLimitStore, SeaOrmSessionRepository,
+    SeaOrmTotpRepository, SeaOrmWalletRepository,
 };
 
 static COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -258,7 +281,7 @@ async fn postgres_migrates_legacy_oauth2_wallet_data() {
                 'idx_invoice_btc_output_id',
                 'idx_invoice_ln_address_id',
                 'idx_invoice_wallet_created_at',
-                'idx_ln_address_account',
+                'idx_ln_address_account_id',
                 'idx_payment_wallet_created_at',
                 'idx_wallet_account_asset',
                 'uq_wallet_account_id',
@@ -403,7 +426,7 @@ async fn account_repository_crud_keeps_the_aggregate_consistent() {
     assert!(wallet_repo.exists_for_account(account.id, wallet.id).await.unwrap());
     assert!(!wallet_repo.exists_for_account(Uuid::new_v4(), wallet.id).await.unwrap());
     let ln_address = SeaOrmLnAddressRepository::new(conn.clone())
-        .insert(account.id, wallet.id, "operator", false, None)
+        .insert(ln_address(account.id, wallet.id, None, "operator"))
         .await
         .unwrap();
     let aggregate = repo.find(account.id).await.unwrap().unwrap();
@@ -670,6 +693,81 @@ async fn settle_internal_is_atomic() {
     assert_eq!(balance(&conn, payee).await, (50_000, 0), "payee credited");
 }
 
+/// An internal transfer leg pair sharing a fresh transfer id.
+fn transfer(payer: Uuid, payee: Uuid, amount_msat: u64) -> (Payment, Invoice) {
+    let transfer_id = Uuid::new_v4();
+    let payment = Payment {
+        wallet_id: payer,
+        amount_msat,
+        fee_msat: Some(0),
+        status: PaymentStatus::Settled,
+        ledger: Ledger::Internal,
+        internal: Some(InternalPayment {
+            transfer_id: Some(transfer_id),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut invoice = pending_invoice(payee, amount_msat);
+    invoice.status = InvoiceStatus::Settled;
+    invoice.payment_time = Some(Utc::now());
+    invoice.transfer_id = Some(transfer_id);
+    (payment, invoice)
+}
+
+#[tokio::test]
+async fn transfer_moves_funds_and_links_both_legs() {
+    let conn = connect().await;
+    let payer = seed_wallet(&conn, 200_000).await;
+    let payee = seed_wallet(&conn, 0).await;
+
+    let (payment, invoice) = transfer(payer, payee, 50_000);
+    let transfer_id = invoice.transfer_id;
+    let transferred = uow(&conn).transfer(payment, invoice).await.expect("transfer");
+
+    assert_eq!(balance(&conn, payer).await, (150_000, 0), "payer debited");
+    assert_eq!(balance(&conn, payee).await, (50_000, 0), "payee credited");
+
+    let stored = SeaOrmPaymentRepository::new(conn.clone())
+        .find(transferred.id)
+        .await
+        .expect("find payment")
+        .expect("payment stored");
+    assert_eq!(stored.internal.and_then(|internal| internal.transfer_id), transfer_id);
+    let invoices = SeaOrmInvoiceRepository::new(conn.clone())
+        .find_many(InvoiceFilter {
+            wallet_id: Some(payee),
+            ..Default::default()
+        })
+        .await
+        .expect("list invoices");
+    assert_eq!(invoices.len(), 1);
+    assert_eq!(invoices[0].transfer_id, transfer_id);
+    assert_eq!(invoices[0].status, InvoiceStatus::Settled);
+}
+
+#[tokio::test]
+async fn transfer_without_funds_leaves_no_trace() {
+    let conn = connect().await;
+    let payer = seed_wallet(&conn, 10_000).await;
+    let payee = seed_wallet(&conn, 0).await;
+
+    let (payment, invoice) = transfer(payer, payee, 50_000);
+    let err = uow(&conn).transfer(payment, invoice).await.unwrap_err();
+
+    assert!(matches!(err, ApplicationError::Data(DataError::InsufficientFunds(_))));
+    assert_eq!(balance(&conn, payer).await, (10_000, 0));
+    assert_eq!(balance(&conn, payee).await, (0, 0));
+    let invoices = SeaOrmInvoiceRepository::new(conn.clone())
+        .find_many(InvoiceFilter {
+            wallet_id: Some(payee),
+            ..Default::default()
+        })
+        .await
+        .expect("list invoices");
+    assert!(invoices.is_empty(), "no counterpart invoice without the debit");
+}
+
 #[tokio::test]
 async fn concurrent_internal_payers_cannot_both_settle_one_invoice() {
     let conn = connect().await;
@@ -721,7 +819,7 @@ async fn settle_incoming_invoice_credits_a_new_invoice() {
     let mut invoice = pending_invoice(receiver, 30_000);
     invoice.payment_time = Some(Utc::now());
     SeaOrmEventProjectionUnitOfWork::new(conn.clone())
-        .settle_incoming_invoice(invoice)
+        .settle_incoming_invoice(invoice, Vec::new())
         .await
         .expect("settle incoming");
 
@@ -740,14 +838,14 @@ async fn settle_incoming_invoice_credits_once_under_replay() {
 
     let projection = SeaOrmEventProjectionUnitOfWork::new(conn.clone());
     projection
-        .settle_incoming_invoice(invoice.clone())
+        .settle_incoming_invoice(invoice.clone(), Vec::new())
         .await
         .expect("settle");
     assert_eq!(balance(&conn, receiver).await, (30_000, 0), "credited on settlement");
 
     // A duplicate success event must not credit twice.
     projection
-        .settle_incoming_invoice(invoice)
+        .settle_incoming_invoice(invoice, Vec::new())
         .await
         .expect("idempotent replay");
     assert_eq!(
@@ -756,3 +854,1318 @@ async fn settle_incoming_invoice_credits_once_under_replay() {
         "no double credit on replay"
     );
 }
+
+#[tokio::test]
+async fn settle_incoming_invoice_marks_the_payment_request_address_used() {
+    let conn = connect().await;
+    let receiver = seed_wallet(&conn, 0).await;
+    let mut invoice = SeaOrmInvoiceRepository::new(conn.clone())
+        .insert(pending_invoice(receiver, 30_000))
+        .await
+        .expect("insert pending invoice");
+    invoice.payment_time = Some(Utc::now());
+    let addresses = SeaOrmBitcoinAddressRepository::new(conn.clone());
+    let address = addresses
+        .insert_for_invoice(receiver, "bcrt1qrequest", BtcAddressType::P2wpkh, invoice.id)
+        .await
+        .expect("insert payment request address");
+
+    SeaOrmEventProjectionUnitOfWork::new(conn.clone())
+        .settle_incoming_invoice(invoice, Vec::new())
+        .await
+        .expect("settle");
+
+    let address = addresses.find(address.id).await.unwrap().unwrap();
+    assert!(address.used, "the on-chain leg is settled with the invoice");
+}
+
+#[tokio::test]
+async fn settle_incoming_invoice_passes_splits_on_once() {
+    let conn = connect().await;
+    let receiver = seed_wallet(&conn, 0).await;
+    let platform = seed_wallet(&conn, 0).await;
+    let mut invoice = SeaOrmInvoiceRepository::new(conn.clone())
+        .insert(pending_invoice(receiver, 30_000))
+        .await
+        .expect("insert pending invoice");
+    invoice.payment_time = Some(Utc::now());
+
+    let split = || {
+        let payment = Payment {
+            wallet_id: receiver,
+            amount_msat: 3_000,
+            status: PaymentStatus::Settled,
+            ledger: Ledger::Internal,
+            internal: Some(InternalPayment {
+                split_invoice_id: Some(invoice.id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut split_invoice = pending_invoice(platform, 3_000);
+        split_invoice.payment_time = Some(Utc::now());
+        split_invoice.split_invoice_id = Some(invoice.id);
+        (payment, split_invoice)
+    };
+
+    let projection = SeaOrmEventProjectionUnitOfWork::new(conn.clone());
+    projection
+        .settle_incoming_invoice(invoice.clone(), vec![split()])
+        .await
+        .expect("settle");
+    assert_eq!(
+        balance(&conn, receiver).await,
+        (27_000, 0),
+        "the receiver keeps the rest"
+    );
+    assert_eq!(balance(&conn, platform).await, (3_000, 0), "the share is passed on");
+
+    // A duplicate success event must not pass the share on twice.
+    projection
+        .settle_incoming_invoice(invoice.clone(), vec![split()])
+        .await
+        .expect("idempotent replay");
+    assert_eq!(balance(&conn, receiver).await, (27_000, 0));
+    assert_eq!(balance(&conn, platform).await, (3_000, 0));
+
+    let payments = SeaOrmPaymentRepository::new(conn.clone())
+        .find_many(PaymentFilter {
+            wallet_id: Some(receiver),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(
+        payments[0]
+            .internal
+            .as_ref()
+            .and_then(|internal| internal.split_invoice_id),
+        Some(invoice.id)
+    );
+    let received = SeaOrmInvoiceRepository::new(conn.clone())
+        .find_many(InvoiceFilter {
+            wallet_id: Some(platform),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].split_invoice_id, Some(invoice.id));
+}
+
+/// A deposit to `address` mined at `block_height` (`None` while in the mempool).
+fn deposit_output(address: &str, block_height: Option<u32>, status: BtcOutputStatus) -> BtcOutput {
+    BtcOutput {
+        outpoint: format!("{address}-txid:0"),
+        txid: format!("{address}-txid"),
+        address: address.to_string(),
+        amount_sat: 40,
+        status,
+        block_height,
+        ..Default::default()
+    }
+}
+
+/// The deposit invoice the event service builds; settled only once the output is confirmed.
+fn deposit_invoice(wallet_id: Uuid, confirmed: bool) -> Invoice {
+    Invoice {
+        wallet_id,
+        amount_msat: Some(40_000),
+        amount_received_msat: confirmed.then_some(40_000),
+        payment_time: confirmed.then(Utc::now),
+        ledger: Ledger::Onchain,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn project_onchain_deposit_credits_once_confirmed_and_reverses_on_reorg() {
+    let conn = connect().await;
+    let receiver = seed_wallet(&conn, 0).await;
+    let address_value = format!("bcrt1q-{}", Uuid::new_v4());
+    let address = SeaOrmBitcoinAddressRepository::new(conn.clone())
+        .insert(receiver, &address_value, BtcAddressType::P2wpkh)
+        .await
+        .expect("insert address");
+    let projection = SeaOrmEventProjectionUnitOfWork::new(conn.clone());
+    let wallets = SeaOrmWalletRepository::new(conn.clone());
+
+    // Mined but below the confirmation threshold: pending, not yet spendable.
+    projection
+        .project_onchain_deposit(
+            deposit_output(&address_value, Some(100), BtcOutputStatus::Unconfirmed),
+            address.clone(),
+            deposit_invoice(receiver, false),
+        )
+        .await
+        .expect("project pending deposit");
+    assert_eq!(balance(&conn, receiver).await, (0, 0));
+    assert_eq!(wallets.get_balance(receiver).await.unwrap().pending_msat, 40_000);
+
+    // Threshold reached in the same block: credited once, even under replay.
+    for _ in 0..2 {
+        projection
+            .project_onchain_deposit(
+                deposit_output(&address_value, Some(100), BtcOutputStatus::Confirmed),
+                address.clone(),
+                deposit_invoice(receiver, true),
+            )
+            .await
+            .expect("project confirmed deposit");
+    }
+    assert_eq!(balance(&conn, receiver).await, (40_000, 0));
+    assert_eq!(wallets.get_balance(receiver).await.unwrap().pending_msat, 0);
+
+    // The block is reorged out and the transaction is back in the mempool: credit reversed once.
+    for _ in 0..2 {
+        projection
+            .project_onchain_deposit(
+                deposit_output(&address_value, None, BtcOutputStatus::Unconfirmed),
+                address.clone(),
+                deposit_invoice(receiver, false),
+            )
+            .await
+            .expect("project reorged deposit");
+    }
+    assert_eq!(balance(&conn, receiver).await, (0, 0), "credit reversed exactly once");
+    assert_eq!(wallets.get_balance(receiver).await.unwrap().pending_msat, 40_000);
+
+    // Re-mined in another block and confirmed again: credited again.
+    projection
+        .project_onchain_deposit(
+            deposit_output(&address_value, Some(101), BtcOutputStatus::Confirmed),
+            address,
+            deposit_invoice(receiver, true),
+        )
+        .await
+        .expect("project re-mined deposit");
+    assert_eq!(balance(&conn, receiver).await, (40_000, 0));
+}
+
+#[tokio::test]
+async fn reorged_deposit_reversal_keeps_the_spent_shortfall_as_a_negative_balance() {
+    let conn = connect().await;
+    let receiver = seed_wallet(&conn, 0).await;
+    let address_value = format!("bcrt1q-{}", Uuid::new_v4());
+    let address = SeaOrmBitcoinAddressRepository::new(conn.clone())
+        .insert(receiver, &address_value, BtcAddressType::P2wpkh)
+        .await
+        .expect("insert address");
+    let projection = SeaOrmEventProjectionUnitOfWork::new(conn.clone());
+
+    projection
+        .project_onchain_deposit(
+            deposit_output(&address_value, Some(100), BtcOutputStatus::Confirmed),
+            address.clone(),
+            deposit_invoice(receiver, true),
+        )
+        .await
+        .expect("project confirmed deposit");
+
+    // Most of the deposit is spent before the reorg.
+    assert!(SeaOrmWalletRepository::new(conn.clone())
+        .debit(receiver, 30_000)
+        .await
+        .expect("spend"));
+
+    projection
+        .project_onchain_deposit(
+            deposit_output(&address_value, None, BtcOutputStatus::Unconfirmed),
+            address,
+            deposit_invoice(receiver, false),
+        )
+        .await
+        .expect("project reorged deposit");
+    assert_eq!(
+        balance(&conn, receiver).await,
+        (-30_000, 0),
+        "the spent part of the reversed deposit is owed"
+    );
+}
+
+#[tokio::test]
+async fn drop_onchain_deposit_reverses_the_credit_once_and_fails_the_output() {
+    let conn = connect().await;
+    let receiver = seed_wallet(&conn, 0).await;
+    let address_value = format!("bcrt1q-{}", Uuid::new_v4());
+    let address = SeaOrmBitcoinAddressRepository::new(conn.clone())
+        .insert(receiver, &address_value, BtcAddressType::P2wpkh)
+        .await
+        .expect("insert address");
+    let projection = SeaOrmEventProjectionUnitOfWork::new(conn.clone());
+    let wallets = SeaOrmWalletRepository::new(conn.clone());
+
+    projection
+        .project_onchain_deposit(
+            deposit_output(&address_value, Some(100), BtcOutputStatus::Confirmed),
+            address,
+            deposit_invoice(receiver, true),
+        )
+        .await
+        .expect("project confirmed deposit");
+    assert_eq!(balance(&conn, receiver).await, (40_000, 0));
+
+    // Double spent: the output is gone from the chain and the mempool.
+    for _ in 0..2 {
+        let invoice = projection
+            .drop_onchain_deposit(deposit_output(&address_value, Some(100), BtcOutputStatus::Confirmed))
+            .await
+            .expect("drop deposit")
+            .expect("deposit invoice");
+        assert_eq!(invoice.status, InvoiceStatus::Expired);
+    }
+    assert_eq!(balance(&conn, receiver).await, (0, 0), "credit reversed exactly once");
+    assert_eq!(
+        wallets.get_balance(receiver).await.unwrap().pending_msat,
+        0,
+        "a dropped deposit is no longer pending"
+    );
+
+    let output = SeaOrmBitcoinOutputRepository::new(conn.clone())
+        .find_by_outpoint(&format!("{address_value}-txid:0"))
+        .await
+        .expect("find output")
+        .expect("output");
+    assert_eq!(output.status, BtcOutputStatus::Failed);
+    assert_eq!(output.block_height, None);
+}
+
+#[tokio::test]
+async fn payjoin_inputs_are_accepted_once_and_fallbacks_are_due_after_their_delay() {
+    let conn = connect().await;
+    let payjoin = SeaOrmPayjoinRepository::new(conn.clone());
+    let outpoint = format!("{}:0", Uuid::new_v4());
+
+    assert!(payjoin.insert_inputs(vec![outpoint.clone()]).await.unwrap());
+    assert!(
+        !payjoin
+            .insert_inputs(vec![outpoint, format!("{}:1", Uuid::new_v4())])
+            .await
+            .unwrap(),
+        "an original reusing a seen input is a probe"
+    );
+
+    let fallback = |broadcast_after| PayjoinFallback {
+        id: Uuid::new_v4(),
+        txid: "original".to_string(),
+        tx_hex: "0200".to_string(),
+        locked_utxo: BtcLockedUtxo {
+            id: "lease".to_string(),
+            txid: "utxo".to_string(),
+            output_index: 1,
+        },
+        broadcast_after,
+    };
+    let due = fallback(Utc::now() - chrono::Duration::seconds(1));
+    payjoin.insert_fallback(due.clone()).await.unwrap();
+    payjoin
+        .insert_fallback(fallback(Utc::now() + chrono::Duration::hours(1)))
+        .await
+        .unwrap();
+
+    let found = payjoin.find_due_fallbacks().await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, due.id);
+    assert_eq!(found[0].locked_utxo.id, "lease");
+
+    payjoin.delete_fallback(due.id).await.unwrap();
+    assert!(payjoin.find_due_fallbacks().await.unwrap().is_empty());
+
+    let payment_outpoint = format!("{}:0", Uuid::new_v4());
+    payjoin
+        .insert_contribution(payment_outpoint.clone(), 19_500)
+        .await
+        .unwrap();
+    assert_eq!(
+        payjoin.find_contribution(&payment_outpoint).await.unwrap(),
+        Some(19_500)
+    );
+    assert_eq!(payjoin.find_contribution("unknown:0").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn silent_payment_labels_are_taken_once() {
+    let conn = connect().await;
+    let wallet_id = seed_wallet(&conn, 0).await;
+    let other_wallet_id = seed_wallet(&conn, 0).await;
+    let addresses = SeaOrmBitcoinAddressRepository::new(conn.clone());
+
+    let issued = addresses
+        .insert_silent_payment(wallet_id, "sprt1qlabel1", 1)
+        .await
+        .unwrap()
+        .expect("the label is free");
+    assert_eq!(issued.address_type, BtcAddressType::SilentPayment);
+
+    assert!(
+        addresses
+            .insert_silent_payment(other_wallet_id, "sprt1qlabel1", 1)
+            .await
+            .unwrap()
+            .is_none(),
+        "a concurrent allocation of the same label is reported, not raised"
+    );
+    assert_eq!(addresses.max_silent_payment_label().await.unwrap(), Some(1));
+}
+
+/// Register an account with a wallet and return `(account_id, wallet_id)`.
+async fn seed_account(conn: &DatabaseConnection) -> (Uuid, Uuid) {
+    let account = SeaOrmAccountRepository::new(conn.clone())
+        .insert(None, &[])
+        .await
+        .expect("create account");
+    let asset = SeaOrmAssetRepository::new(conn.clone())
+        .find_native_btc_by_network(BtcNetwork::Bitcoin)
+        .await
+        .expect("find native BTC asset")
+        .expect("native BTC asset");
+    let wallet = SeaOrmWalletRepository::new(conn.clone())
+        .upsert(account.id, asset.id)
+        .await
+        .expect("ensure wallet");
+    (account.id, wallet.id)
+}
+
+fn ln_address(account_id: Uuid, wallet_id: Uuid, domain_id: Option<Uuid>, username: &str) -> LnAddress {
+    LnAddress {
+        account_id,
+        wallet_id,
+        domain_id,
+        username: username.to_string(),
+        active: true,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn ln_address_usernames_are_unique_per_domain() {
+    let conn = connect().await;
+    let domain = SeaOrmLnAddressDomainRepository::new(conn.clone())
+        .insert("brand.example", "https://pay.brand.example")
+        .await
+        .unwrap();
+    let ln_addresses = SeaOrmLnAddressRepository::new(conn.clone());
+
+    let (account_id, wallet_id) = seed_account(&conn).await;
+    let default_address = ln_addresses
+        .insert(ln_address(account_id, wallet_id, None, "alice"))
+        .await
+        .unwrap();
+    let (account_id, wallet_id) = seed_account(&conn).await;
+    let managed_address = ln_addresses
+        .insert(ln_address(account_id, wallet_id, Some(domain.id), "alice"))
+        .await
+        .expect("the same username is free on another domain");
+
+    let (account_id, wallet_id) = seed_account(&conn).await;
+    assert!(
+        ln_addresses
+            .insert(ln_address(account_id, wallet_id, None, "alice"))
+            .await
+            .is_err(),
+        "usernames stay unique on the configured domain"
+    );
+    assert!(
+        ln_addresses
+            .insert(ln_address(account_id, wallet_id, Some(domain.id), "alice"))
+            .await
+            .is_err(),
+        "usernames stay unique on a managed domain"
+    );
+
+    let found = ln_addresses.find_by_username(None, "alice").await.unwrap().unwrap();
+    assert_eq!(found.id, default_address.id);
+    let found = ln_addresses
+        .find_by_username(Some(domain.id), "alice")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, managed_address.id);
+}
+
+#[tokio::test]
+async fn accounts_hold_several_ln_addresses_and_invoices_keep_the_alias_paid() {
+    let conn = connect().await;
+    let ln_addresses = SeaOrmLnAddressRepository::new(conn.clone());
+    let invoices = SeaOrmInvoiceRepository::new(conn.clone());
+
+    let (account_id, wallet_id) = seed_account(&conn).await;
+    let donations = ln_addresses
+        .insert(ln_address(
+            account_id,
+            wallet_id,
+            None,
+            &format!("donations-{account_id}"),
+        ))
+        .await
+        .unwrap();
+    ln_addresses
+        .insert(ln_address(account_id, wallet_id, None, &format!("sales-{account_id}")))
+        .await
+        .expect("an account and a wallet can receive through several addresses");
+
+    let listed = ln_addresses
+        .find_many(LnAddressFilter {
+            account_id: Some(account_id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 2);
+    let wallet = SeaOrmWalletRepository::new(conn.clone())
+        .find(wallet_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        wallet.ln_address.map(|address| address.id),
+        Some(donations.id),
+        "wallets expose their oldest address"
+    );
+
+    let alias = format!("{}+gala", donations.username);
+    let mut tagged = pending_invoice(wallet_id, 21_000);
+    tagged.ln_address_id = Some(donations.id);
+    tagged.ln_address_alias = Some(alias.clone());
+    invoices.insert(tagged).await.unwrap();
+    let mut untagged = pending_invoice(wallet_id, 21_000);
+    untagged.ln_address_id = Some(donations.id);
+    invoices.insert(untagged).await.unwrap();
+
+    let found = invoices
+        .find_many(InvoiceFilter {
+            ln_address_alias: Some(alias.to_uppercase()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].ln_address_alias.as_deref(), Some(alias.as_str()));
+    let found = invoices
+        .find_many(InvoiceFilter {
+            ln_address_id: Some(donations.id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+}
+
+#[tokio::test]
+async fn ln_address_splits_are_stored_with_the_address() {
+    let conn = connect().await;
+    let ln_addresses = SeaOrmLnAddressRepository::new(conn.clone());
+    let (account_id, wallet_id) = seed_account(&conn).await;
+    let platform = seed_wallet(&conn, 0).await;
+
+    let splits = vec![LnAddressSplit {
+        wallet_id: platform,
+        share_bps: 250,
+    }];
+    let inserted = ln_addresses
+        .insert(LnAddress {
+            splits: splits.clone(),
+            ..ln_address(account_id, wallet_id, None, &format!("creator-{account_id}"))
+        })
+        .await
+        .unwrap();
+    assert_eq!(inserted.splits, splits);
+
+    let updated = ln_addresses
+        .update(LnAddress {
+            splits: Vec::new(),
+            ..inserted
+        })
+        .await
+        .unwrap();
+    assert!(updated.splits.is_empty(), "clearing the splits stores none");
+}
+
+#[tokio::test]
+async fn pay_link_names_are_unique_per_wallet_and_invoices_keep_their_reference() {
+    let conn = connect().await;
+    let pay_links = SeaOrmPayLinkRepository::new(conn.clone());
+    let invoices = SeaOrmInvoiceRepository::new(conn.clone());
+    let (_, wallet_id) = seed_account(&conn).await;
+
+    let register = pay_links
+        .insert(PayLink {
+            wallet_id,
+            name: "Register 3".to_string(),
+            amount_msat: Some(21_000),
+            active: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(register.amount_msat, Some(21_000));
+    let err = pay_links
+        .insert(PayLink {
+            wallet_id,
+            name: "Register 3".to_string(),
+            active: true,
+            ..Default::default()
+        })
+        .await;
+    assert!(err.is_err(), "names are unique per wallet");
+
+    let updated = pay_links
+        .update(PayLink {
+            amount_msat: None,
+            reference: Some("register-3".to_string()),
+            ..register.clone()
+        })
+        .await
+        .unwrap();
+    assert_eq!(updated.amount_msat, None);
+    assert_eq!(updated.reference.as_deref(), Some("register-3"));
+
+    let reference = format!("order-{wallet_id}");
+    let mut invoice = pending_invoice(wallet_id, 21_000);
+    invoice.pay_link_id = Some(register.id);
+    invoice.reference = Some(reference.clone());
+    invoices.insert(invoice).await.unwrap();
+    invoices.insert(pending_invoice(wallet_id, 21_000)).await.unwrap();
+
+    let found = invoices
+        .find_many(InvoiceFilter {
+            reference: Some(reference.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].pay_link_id, Some(register.id));
+
+    assert_eq!(pay_links.delete(register.id).await.unwrap(), 1);
+    assert!(pay_links
+        .find_many(PayLinkFilter {
+            wallet_id: Some(wallet_id),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .is_empty());
+    let found = invoices
+        .find_many(InvoiceFilter {
+            pay_link_id: Some(register.id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1, "invoices outlive their pay link");
+}
+
+#[tokio::test]
+async fn registration_consumes_its_invitation_exactly_once() {
+    let conn = connect().await;
+    let credentials = SeaOrmCredentialRepository::new(conn.clone());
+    let invitations = SeaOrmInvitationRepository::new(conn.clone());
+
+    let invitation = invitations
+        .insert(Invitation {
+            token_hash: vec![7; 32],
+            permissions: vec![Permission::ReadWallet],
+            expires_at: Utc::now() + chrono::Duration::hours(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let registration = |username: &str, email: &str| LocalRegistration {
+        username: username.to_string(),
+        email: Some(email.to_string()),
+        password_hash: "hash".to_string(),
+        permissions: invitation.permissions.clone(),
+        invitation_id: Some(invitation.id),
+        ..Default::default()
+    };
+
+    let account = credentials
+        .register(registration("alice", "alice@example.com"))
+        .await
+        .unwrap()
+        .expect("the first registration consumes the invitation");
+    assert_eq!(account.permissions, Some(vec![Permission::ReadWallet]));
+
+    let second = credentials
+        .register(registration("bob", "bob@example.com"))
+        .await
+        .unwrap();
+    assert!(second.is_none(), "a used invitation cannot register a second account");
+    assert_eq!(count(&conn, "SELECT COUNT(*) AS count FROM account").await, 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) AS count FROM local_credential").await, 1);
+    assert!(invitations.find_by_token_hash(vec![7; 32]).await.unwrap().is_none());
+
+    let used = invitations
+        .find_many(InvitationFilter {
+            used: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(used.len(), 1);
+    assert_eq!(used[0].account_id, Some(account.id));
+
+    let by_username = credentials.find_by_login("alice").await.unwrap().unwrap();
+    let by_email = credentials.find_by_login("alice@example.com").await.unwrap().unwrap();
+    assert_eq!(by_username.identity_id, by_email.identity_id);
+    assert_eq!(by_username.account_id, account.id);
+
+    credentials
+        .update_password(by_username.identity_id, "new-hash".to_string())
+        .await
+        .unwrap();
+    let updated = credentials.find_by_account(account.id).await.unwrap().unwrap();
+    assert_eq!(updated.password_hash, "new-hash");
+}
+
+#[tokio::test]
+async fn password_reset_tokens_are_consumed_once() {
+    let conn = connect().await;
+    let credentials = SeaOrmCredentialRepository::new(conn.clone());
+    let account = credentials
+        .register(LocalRegistration {
+            username: "alice".to_string(),
+            password_hash: "hash".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    credentials
+        .insert_password_reset(account.id, vec![1; 32], Utc::now() + chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    credentials
+        .insert_password_reset(account.id, vec![2; 32], Utc::now() - chrono::Duration::hours(1))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        credentials.consume_password_reset(vec![1; 32]).await.unwrap(),
+        Some(account.id)
+    );
+    assert_eq!(credentials.consume_password_reset(vec![1; 32]).await.unwrap(), None);
+    assert_eq!(
+        credentials.consume_password_reset(vec![2; 32]).await.unwrap(),
+        None,
+        "expired tokens are rejected"
+    );
+}
+
+#[tokio::test]
+async fn api_key_rotation_retires_the_old_key_and_keeps_the_wallet_scope() {
+    let conn = connect().await;
+    let wallet_id = seed_wallet(&conn, 0).await;
+    let account_id = SeaOrmWalletRepository::new(conn.clone())
+        .find(wallet_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .account_id;
+    let repo = SeaOrmApiKeyRepository::new(conn.clone());
+    let original = repo
+        .insert(ApiKey {
+            account_id,
+            name: "pos".to_string(),
+            key_hash: vec![7; 32],
+            wallet_ids: Some(vec![wallet_id]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(original.wallet_ids, Some(vec![wallet_id]));
+
+    repo.record_usage(original.id, Some("203.0.113.7".to_string()))
+        .await
+        .unwrap();
+    let used = repo.find(original.id).await.unwrap().unwrap();
+    assert!(used.last_used_at.is_some());
+    assert_eq!(used.last_used_ip.as_deref(), Some("203.0.113.7"));
+
+    let replacement = repo
+        .rotate(
+            original.id,
+            ApiKey {
+                account_id,
+                name: "pos".to_string(),
+                key_hash: vec![8; 32],
+                wallet_ids: original.wallet_ids.clone(),
+                ..Default::default()
+            },
+            Utc::now() - chrono::Duration::seconds(1),
+        )
+        .await
+        .unwrap()
+        .expect("the rotated key exists");
+    assert_ne!(replacement.id, original.id);
+    assert_eq!(replacement.wallet_ids, Some(vec![wallet_id]));
+
+    assert!(
+        repo.find_by_key_hash(vec![7; 32]).await.unwrap().is_none(),
+        "the rotated key no longer authenticates"
+    );
+    assert_eq!(
+        repo.find_by_key_hash(vec![8; 32]).await.unwrap().map(|k| k.id),
+        Some(replacement.id)
+    );
+
+    let missing = repo
+        .rotate(
+            Uuid::new_v4(),
+            ApiKey {
+                account_id,
+                key_hash: vec![9; 32],
+                ..Default::default()
+            },
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+    assert!(repo.find_by_key_hash(vec![9; 32]).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn refresh_tokens_are_used_once_and_sessions_end_with_their_account() {
+    let conn = connect().await;
+    let accounts = SeaOrmAccountRepository::new(conn.clone());
+    let account = accounts.insert(None, &[]).await.unwrap();
+    let sessions = SeaOrmSessionRepository::new(conn.clone());
+    let expires_at = Utc::now() + chrono::Duration::days(1);
+
+    let session = sessions
+        .insert(account.id, vec![1; 32], expires_at, None)
+        .await
+        .unwrap();
+    let expired = sessions
+        .insert(account.id, vec![2; 32], Utc::now() - chrono::Duration::hours(1), None)
+        .await
+        .unwrap();
+
+    let refreshed = sessions
+        .refresh(vec![1; 32], vec![3; 32], expires_at + chrono::Duration::days(1))
+        .await
+        .unwrap()
+        .expect("the refresh token is valid");
+    assert_eq!(refreshed.id, session.id);
+    assert!(
+        sessions
+            .refresh(vec![1; 32], vec![4; 32], expires_at)
+            .await
+            .unwrap()
+            .is_none(),
+        "a replaced refresh token cannot be used again"
+    );
+    assert!(
+        sessions
+            .refresh(vec![2; 32], vec![5; 32], expires_at)
+            .await
+            .unwrap()
+            .is_none(),
+        "expired sessions cannot be refreshed"
+    );
+    assert!(
+        sessions.find(expired.id).await.unwrap().is_none(),
+        "expired sessions no longer authenticate"
+    );
+    assert!(sessions.find(session.id).await.unwrap().is_some());
+
+    assert_eq!(
+        sessions
+            .delete_by_refresh_token(vec![2; 32])
+            .await
+            .unwrap()
+            .map(|ended| ended.id),
+        Some(expired.id)
+    );
+    assert!(sessions.delete_by_refresh_token(vec![2; 32]).await.unwrap().is_none());
+
+    accounts
+        .delete_many(AccountFilter {
+            ids: Some(vec![account.id]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(
+        sessions.find(session.id).await.unwrap().is_none(),
+        "sessions are deleted with their account"
+    );
+}
+
+#[tokio::test]
+async fn totp_steps_and_recovery_codes_are_used_once() {
+    let conn = connect().await;
+    let accounts = SeaOrmAccountRepository::new(conn.clone());
+    let account = accounts.insert(None, &[]).await.unwrap();
+    let sessions = SeaOrmSessionRepository::new(conn.clone());
+    let totp = SeaOrmTotpRepository::new(conn.clone());
+
+    let session = sessions
+        .insert(account.id, vec![1; 32], Utc::now() + chrono::Duration::days(1), None)
+        .await
+        .unwrap();
+    assert!(sessions.verify(session.id).await.unwrap());
+    assert!(sessions.find(session.id).await.unwrap().unwrap().verified_at.is_some());
+
+    assert!(totp.insert_pending(account.id, vec![1; 20]).await.unwrap().is_some());
+    let pending = totp.insert_pending(account.id, vec![2; 20]).await.unwrap().unwrap();
+    assert!(!pending.is_confirmed());
+    assert_eq!(totp.find(account.id).await.unwrap().unwrap().secret, vec![2; 20]);
+
+    assert!(totp
+        .confirm(account.id, 10, vec![vec![7; 32], vec![8; 32]])
+        .await
+        .unwrap());
+    assert!(
+        !totp.confirm(account.id, 11, vec![]).await.unwrap(),
+        "a confirmed factor cannot be confirmed again"
+    );
+    assert!(
+        totp.insert_pending(account.id, vec![3; 20]).await.unwrap().is_none(),
+        "a confirmed factor is not replaced by a new enrollment"
+    );
+    let factor = totp.find(account.id).await.unwrap().unwrap();
+    assert!(factor.is_confirmed());
+    assert_eq!(factor.last_used_step, 10);
+
+    assert!(!totp.use_step(account.id, 10).await.unwrap(), "steps are used once");
+    assert!(totp.use_step(account.id, 11).await.unwrap());
+    assert!(!totp.use_step(account.id, 11).await.unwrap());
+
+    assert!(totp.use_recovery_code(account.id, vec![7; 32]).await.unwrap());
+    assert!(!totp.use_recovery_code(account.id, vec![7; 32]).await.unwrap());
+    assert!(!totp.use_recovery_code(account.id, vec![9; 32]).await.unwrap());
+
+    assert_eq!(totp.delete(account.id).await.unwrap(), 1);
+    assert!(totp.find(account.id).await.unwrap().is_none());
+    assert!(
+        !totp.use_recovery_code(account.id, vec![8; 32]).await.unwrap(),
+        "recovery codes are deleted with their factor"
+    );
+
+    assert!(totp.insert_pending(account.id, vec![4; 20]).await.unwrap().is_some());
+    assert!(totp.confirm(account.id, 1, vec![vec![6; 32]]).await.unwrap());
+    accounts
+        .delete_many(AccountFilter {
+            ids: Some(vec![account.id]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(
+        totp.find(account.id).await.unwrap().is_none(),
+        "factors are deleted with their account"
+    );
+}
+
+#[tokio::test]
+async fn passkey_counters_and_challenges_are_used_once() {
+    let conn = connect().await;
+    let accounts = SeaOrmAccountRepository::new(conn.clone());
+    let account = accounts.upsert(AuthProvider::Jwt, "alice", None, &[]).await.unwrap();
+    let other = accounts.upsert(AuthProvider::Jwt, "bob", None, &[]).await.unwrap();
+    let passkeys = SeaOrmPasskeyRepository::new(conn.clone());
+
+    let passkey = passkeys
+        .insert(Passkey {
+            account_id: account.id,
+            name: "YubiKey".to_string(),
+            identity_id: account.identity.as_ref().unwrap().id,
+            credential_id: vec![1; 16],
+            public_key: vec![2; 77],
+            sign_count: 5,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(passkeys
+        .insert(Passkey {
+            identity_id: other.identity.as_ref().unwrap().id,
+            credential_id: vec![1; 16],
+            ..passkey.clone()
+        })
+        .await
+        .is_err());
+
+    let found = passkeys.find_by_credential_id(vec![1; 16]).await.unwrap().unwrap();
+    assert_eq!(found.id, passkey.id);
+    assert_eq!(found.account_id, account.id);
+    assert_eq!(found.public_key, vec![2; 77]);
+    assert_eq!(passkeys.find_many(account.id).await.unwrap().len(), 1);
+    assert!(passkeys.find_many(other.id).await.unwrap().is_empty());
+
+    assert!(
+        !passkeys.record_use(passkey.id, 5).await.unwrap(),
+        "counters must increase"
+    );
+    assert!(!passkeys.record_use(passkey.id, 0).await.unwrap());
+    assert!(passkeys.record_use(passkey.id, 6).await.unwrap());
+    let used = passkeys.find_by_credential_id(vec![1; 16]).await.unwrap().unwrap();
+    assert_eq!(used.sign_count, 6);
+    assert!(used.last_used_at.is_some());
+
+    let challenge = PasskeyChallenge {
+        id: Uuid::new_v4(),
+        challenge: vec![3; 32],
+        account_id: Some(account.id),
+        expires_at: Utc::now() + chrono::Duration::minutes(5),
+        created_at: Utc::now(),
+    };
+    passkeys.insert_challenge(challenge.clone()).await.unwrap();
+    let expired = PasskeyChallenge {
+        id: Uuid::new_v4(),
+        account_id: None,
+        expires_at: Utc::now() - chrono::Duration::seconds(1),
+        ..challenge.clone()
+    };
+    passkeys.insert_challenge(expired.clone()).await.unwrap();
+
+    let consumed = passkeys.consume_challenge(challenge.id).await.unwrap().unwrap();
+    assert_eq!(consumed.challenge, vec![3; 32]);
+    assert_eq!(consumed.account_id, Some(account.id));
+    assert!(
+        passkeys.consume_challenge(challenge.id).await.unwrap().is_none(),
+        "challenges are answered once"
+    );
+    assert!(passkeys.consume_challenge(expired.id).await.unwrap().is_none());
+
+    assert_eq!(passkeys.delete(other.id, passkey.id).await.unwrap(), 0);
+    assert_eq!(passkeys.delete(account.id, passkey.id).await.unwrap(), 1);
+    assert!(passkeys.find_by_credential_id(vec![1; 16]).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn organizations_keep_at_least_one_owner() {
+    let conn = connect().await;
+    let accounts = SeaOrmAccountRepository::new(conn.clone());
+    let owner = accounts.upsert(AuthProvider::Jwt, "alice", None, &[]).await.unwrap();
+    let member = accounts.upsert(AuthProvider::Jwt, "bob", None, &[]).await.unwrap();
+    let organizations = SeaOrmOrganizationRepository::new(conn.clone());
+
+    let organization = organizations.insert("Numeraire".to_string(), owner.id).await.unwrap();
+    let account = accounts.find(organization.id).await.unwrap().unwrap();
+    assert_eq!(account.display_name.as_deref(), Some("Numeraire"));
+
+    organizations
+        .insert_member(OrganizationMember {
+            organization_id: organization.id,
+            account_id: member.id,
+            role: OrganizationRole::Viewer,
+            created_at: Utc::now(),
+            updated_at: None,
+        })
+        .await
+        .unwrap();
+    let listed = organizations.find_many(member.id).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].role, Some(OrganizationRole::Viewer));
+    assert_eq!(organizations.find_members(organization.id).await.unwrap().len(), 2);
+
+    assert!(
+        organizations
+            .update_member(organization.id, owner.id, OrganizationRole::Admin)
+            .await
+            .unwrap()
+            .is_none(),
+        "the last owner keeps the owner role"
+    );
+    assert_eq!(
+        organizations.delete_member(organization.id, owner.id).await.unwrap(),
+        0,
+        "the last owner stays a member"
+    );
+
+    let promoted = organizations
+        .update_member(organization.id, member.id, OrganizationRole::Owner)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(promoted.role, OrganizationRole::Owner);
+    assert_eq!(organizations.delete_member(organization.id, owner.id).await.unwrap(), 1);
+    assert!(organizations
+        .find_member(organization.id, owner.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    accounts
+        .delete_many(AccountFilter {
+            ids: Some(vec![organization.id]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(organizations.find(organization.id).await.unwrap().is_none());
+    assert!(
+        organizations.find_many(member.id).await.unwrap().is_empty(),
+        "memberships are deleted with their organization"
+    );
+}
+
+#[tokio::test]
+async fn audit_entries_are_filtered_and_keep_their_hash() {
+    let conn = connect().await;
+    let audit = SeaOrmAuditRepository::new(conn.clone());
+    let account_id = Uuid::new_v4();
+    let wallet_id = Uuid::new_v4();
+    let now = Utc::now().trunc_subsecs(6);
+
+    let first = AuditEntry {
+        id: Uuid::new_v4(),
+        account_id: Some(account_id),
+        method: "POST".to_string(),
+        path: format!("/v1/wallets/{wallet_id}/payments"),
+        resource_id: Some(wallet_id),
+        status: 200,
+        diff: Some(AuditDiff {
+            before: None,
+            after: Some(serde_json::json!({ "amount_msat": 1000, "status": "Settled" })),
+        }),
+        created_at: now,
+        ..Default::default()
+    };
+    let first = AuditEntry {
+        hash: Some(audit_hash(&first)),
+        ..first
+    };
+    audit.insert(first.clone()).await.unwrap();
+    audit
+        .insert(AuditEntry {
+            id: Uuid::new_v4(),
+            method: "POST".to_string(),
+            path: "/v1/auth/sign-in".to_string(),
+            status: 401,
+            created_at: now + chrono::Duration::seconds(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let found = audit
+        .find_many(AuditFilter {
+            account_id: Some(account_id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        found[0].hash,
+        Some(audit_hash(&found[0])),
+        "stored entries hash the same"
+    );
+
+    let by_path = audit
+        .find_many(AuditFilter {
+            path: Some(format!("/v1/wallets/{wallet_id}")),
+            method: Some("post".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(by_path.len(), 1);
+    assert_eq!(by_path[0].resource_id, Some(wallet_id));
+
+    let before = audit
+        .find_many(AuditFilter {
+            created_before: Some(now),
+            account_id: Some(account_id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(before.is_empty());
+
+    let second = audit
+        .insert_chained(AuditEntry {
+            id: Uuid::new_v4(),
+            method: "DELETE".to_string(),
+            path: "/v1/api-keys".to_string(),
+            status: 200,
+            created_at: now,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        second.previous_hash, first.hash,
+        "entries without hash are not part of the chain"
+    );
+    assert!(second.created_at > first.created_at);
+
+    let third = audit
+        .insert_chained(AuditEntry {
+            id: Uuid::new_v4(),
+            method: "DELETE".to_string(),
+            path: "/v1/api-keys".to_string(),
+            status: 200,
+            created_at: now,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(third.previous_hash, second.hash);
+    let stored = audit
+        .find_many(AuditFilter {
+            path: Some("/v1/api-keys".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(stored.iter().all(|entry| entry.hash == Some(audit_hash(entry))));
+}
+
+#[tokio::test]
+async fn chained_audit_entries_do_not_fork_across_instances() {
+    let conn = connect().await;
+    // Two instances sharing the database.
+    let first = SeaOrmAuditRepository::new(conn.clone());
+    let second = SeaOrmAuditRepository::new(conn);
+    let entry = || AuditEntry {
+        id: Uuid::new_v4(),
+        method: "POST".to_string(),
+        path: "/v1/api-keys".to_string(),
+        status: 200,
+        created_at: Utc::now().trunc_subsecs(6),
+        ..Default::default()
+    };
+
+    // The chain starts empty so that the first entries race for the genesis too.
+    let results = futures_util::future::join_all((0..4).map(|i| {
+        let audit = if i % 2 == 0 { &first } else { &second };
+        audit.insert_chained(entry())
+    }))
+    .await;
+
+    let mut chained: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+    chained.sort_by_key(|entry| entry.created_at);
+    let mut previous_hash = None;
+    for entry in chained {
+        assert_eq!(entry.previous_hash, previous_hash, "each entry links to the one before");
+        previous_hash = entry.hash;
+    }
+}
+
+#[tokio::test]
+async fn rate_limit_buckets_are_shared_through_the_database() {
+    let conn = connect().await;
+    let limit = RateLimit::new(3, std::time::Duration::from_secs(60));
+    // Two instances sharing the database.
+    let first = SeaOrmRateLimitStore::new(conn.clone());
+    let second = SeaOrmRateLimitStore::new(conn);
+
+    let results = futures_util::future::join_all((0..4).map(|i| {
+        let store = if i % 2 == 0 { &first } else { &second };
+        store.acquire("sign-in:ip:203.0.113.1", &limit)
+    }))
+    .await;
+
+    let rejected: Vec<_> = results.into_iter().flat_map(Result::unwrap).collect();
+    assert_eq!(rejected.len(), 1, "a bucket of 3 tokens lets 3 of 4 requests through");
+    assert!(rejected[0] <= std::time::Duration::from_secs(20));
+    assert_eq!(
+        second.acquire("sign-in:ip:203.0.113.2", &limit).await.unwrap(),
+        None,
+        "buckets are per key"
+    );
+
+    assert_eq!(first.sweep(Utc::now() - chrono::Duration::minutes(1)).await.unwrap(), 0);
+    assert_eq!(first.sweep(Utc::now() + chrono::Duration::minutes(1)).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn nostr_events_are_accepted_once_across_instances() {
+    let conn = connect().await;
+    // Two instances sharing the database.
+    let first = SeaOrmNostrEventRepository::new(conn.clone());
+    let second = SeaOrmNostrEventRepository::new(conn);
+    let expires_at = Utc::now() + chrono::Duration::minutes(1);
+
+    let results = futures_util::future::join_all((0..4).map(|i| {
+        let events = if i % 2 == 0 { &first } else { &second };
+        events.insert("a".repeat(64), expires_at)
+    }))
+    .await;
+
+    let accepted = results
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|accepted| *accepted)
+        .count();
+    assert_eq!(accepted, 1, "a replayed event is rejected by every instance");
+    assert!(second.insert("b".repeat(64), expires_at).await.unwrap());
+
+    // Expired events are forgotten.
+    assert!(first
+        .insert("c".repeat(64), Utc::now() - chrono::Duration::minutes(1))
+        .await
+        .unwrap());
+    assert!(second.insert("c".repeat(64), expires_at).await.unwrap());
+}
+
+#[tokio::test]
+async fn nostr_keys_are_linked_once_and_keep_the_login_identity() {
+    let conn = connect().await;
+    let repo = SeaOrmAccountRepository::new(conn);
+    let alice = repo.upsert(AuthProvider::Jwt, "alice", None, &[]).await.unwrap();
+    let bob = repo.upsert(AuthProvider::Jwt, "bob", None, &[]).await.unwrap();
+    let pubkey = "3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d";
+
+    let linked = repo
+        .insert_identity(alice.id, AuthProvider::Nostr, pubkey)
+        .await
+        .unwrap();
+    assert!(
+        repo.insert_identity(bob.id, AuthProvider::Nostr, pubkey).await.is_err(),
+        "a key authenticates a single account"
+    );
+
+    let by_key = repo
+        .find_by_identity(AuthProvider::Nostr, pubkey)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_key.id, alice.id);
+    let found = repo.find(alice.id).await.unwrap().unwrap();
+    assert_eq!(
+        found.identity.map(|identity| identity.subject),
+        Some("alice".to_string()),
+        "the login identity is the first one"
+    );
+
+    let keys = repo.find_identities(alice.id, AuthProvider::Nostr).await.unwrap();
+    assert_eq!(keys.iter().map(|key| key.id).collect::<Vec<_>>(), vec![linked.id]);
+    assert!(repo
+        .find_identities(bob.id, AuthProvider::Nostr)
+        .await
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(
+        repo.delete_identity(bob.id, AuthProvider::Nostr, linked.id)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        repo.delete_identity(alice.id, AuthProvider::Jwt, linked.id)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        repo.delete_identity(alice.id, AuthProvider::Nostr, linked.id)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(repo
+        .find_by_identity(AuthProvider::Nostr, pubkey)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{header::ACCEPT, Client};
+use serde::Deserialize;
+
+use crate::{
+    domains::payment::{DnsResolver, DnsResolverError},
+    infra::config::config_rs::deserialize_duration,
+};
+
+const USER_AGENT: &str = "Numeraire Swissknife/1.0";
+
+const RCODE_NOERROR: u32 = 0;
+const RCODE_NXDOMAIN: u32 = 3;
+const RR_TYPE_TXT: u16 = 16;
+
+/// DNS-over-HTTPS resolver answering with the JSON format of Cloudflare and Google. The resolver validates
+/// DNSSEC and is trusted for it, so it should be one you run or trust.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DohResolverConfig {
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    #[serde(default = "default_timeout", deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+}
+
+impl Default for DohResolverConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_endpoint(),
+            timeout: default_timeout(),
+        }
+    }
+}
+
+fn default_endpoint() -> String {
+    "https://cloudflare-dns.com/dns-query".to_string()
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+pub struct DohResolver {
+    client: Client,
+    endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DohResponse {
+    status: u32,
+    #[serde(rename = "AD", default)]
+    authenticated_data: bool,
+    #[serde(default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+impl DohResolver {
+    pub fn new(config: DohResolverConfig) -> Result<Self, DnsResolverError> {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| DnsResolverError::Query(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            endpoint: config.endpoint,
+        })
+    }
+}
+
+#[async_trait]
+impl DnsResolver for DohResolver {
+    async fn resolve_txt(&self, name: &str) -> Result<Vec<String>, DnsResolverError> {
+        let response: DohResponse = self
+            .client
+            .get(&self.endpoint)
+            .query(&[("name", name), ("type", "TXT"), ("do", "1")])
+            .header(ACCEPT, "application/dns-json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| DnsResolverError::Query(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| DnsResolverError::Query(e.to_string()))?;
+
+        match response.status {
+            RCODE_NOERROR => {}
+            RCODE_NXDOMAIN => return Ok(vec![]),
+            rcode => return Err(DnsResolverError::Query(format!("{name} answered with RCODE {rcode}"))),
+        }
+
+        let records: Vec<String> = response
+            .answer
+            .into_iter()
+            .filter(|answer| answer.record_type == RR_TYPE_TXT)
+            .map(|answer| txt_data(&answer.data))
+            .collect();
+
+        // Records are only trusted once authenticated; the absence of records has nothing to trust.
+        if !records.is_empty() && !response.authenticated_data {
+            return Err(DnsResolverError::Insecure(name.to_string()));
+        }
+
+        Ok(records)
+    }
+}
+
+/// Concatenates the character-strings of TXT record data in presentation format (`"abc" "def"`), undoing
+/// `\"`, `\\` and `\DDD` escapes. Resolvers answering with unquoted data have it returned as is.
+fn txt_data(data: &str) -> String {
+    if !data.starts_with('"') {
+        return data.to_string();
+    }
+
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut chars = data.chars();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => {
+                let Some(escaped) = chars.next() else {
+                    break;
+                };
+                match escaped.to_digit(10) {
+                    Some(hundreds) => {
+                        let tens = chars.next().and_then(|c| c.to_digit(10)).unwrap_or_default();
+                        let units = chars.next().and_then(|c| c.to_digit(10)).unwrap_or_default();
+                        bytes.push((hundreds * 100 + tens * 10 + units) as u8);
+                    }
+                    None => bytes.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes()),
+                }
+            }
+            c if quoted => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            _ => {}
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{
+        matchers::{header, method, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    const NAME: &str = "alice.user._bitcoin-payment.example.com.";
+
+    async fn resolver_answering(body: serde_json::Value) -> (MockServer, DohResolver) {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("name", NAME))
+            .and(query_param("type", "TXT"))
+            .and(header("accept", "application/dns-json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+
+        let resolver = DohResolver::new(DohResolverConfig {
+            endpoint: format!("{}/dns-query", server.uri()),
+            timeout: Duration::from_secs(5),
+        })
+        .unwrap();
+
+        (server, resolver)
+    }
+
+    #[tokio::test]
+    async fn returns_the_authenticated_txt_records() {
+        let (_server, resolver) = resolver_answering(json!({
+            "Status": 0,
+            "AD": true,
+            "Answer": [
+                { "name": NAME, "type": 5, "TTL": 300, "data": "alias.example.com." },
+                { "name": NAME, "type": 16, "TTL": 300, "data": "\"bitcoin:?sp=sp1q\" \"example\"" }
+            ]
+        }))
+        .await;
+
+        let records = resolver.resolve_txt(NAME).await.unwrap();
+
+        assert_eq!(records, vec!["bitcoin:?sp=sp1qexample".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn rejects_records_not_validated_with_dnssec() {
+        let (_server, resolver) = resolver_answering(json!({
+            "Status": 0,
+            "AD": false,
+            "Answer": [{ "name": NAME, "type": 16, "TTL": 300, "data": "\"bitcoin:?sp=sp1qexample\"" }]
+        }))
+        .await;
+
+        let err = resolver.resolve_txt(NAME).await.unwrap_err();
+
+        assert!(matches!(err, DnsResolverError::Insecure(_)));
+    }
+
+    #[tokio::test]
+    async fn returns_no_records_for_a_missing_name() {
+        let (_server, resolver) = resolver_answering(json!({ "Status": 3, "AD": false })).await;
+
+        assert!(resolver.resolve_txt(NAME).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_on_resolver_errors() {
+        let (_server, resolver) = resolver_answering(json!({ "Status": 2, "AD": false })).await;
+
+        let err = resolver.resolve_txt(NAME).await.unwrap_err();
+
+        assert!(matches!(err, DnsResolverError::Query(_)));
+    }
+
+    #[test]
+    fn txt_data_undoes_escapes() {
+        assert_eq!(txt_data(r#""a\"b\\c\032d""#), "a\"b\\c d");
+        assert_eq!(txt_data("unquoted"), "unquoted");
+    }
+}
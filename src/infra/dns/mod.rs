@@ -0,0 +1,3 @@
+mod doh_resolver;
+
+pub use doh_resolver::*;
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use uuid::Uuid;
 
 use crate::{
     application::errors::AuthenticationError,
@@ -8,6 +9,7 @@ use crate::{
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait JWTAuthenticator: Send + Sync {
-    fn encode(&self, account: Account) -> Result<String, AuthenticationError>;
+    /// Issues an access token for `account`, bound to its sign-in session.
+    fn encode(&self, account: Account, session_id: Uuid) -> Result<String, AuthenticationError>;
     async fn decode(&self, token: &str) -> Result<AuthClaims, AuthenticationError>;
 }
@@ -4,6 +4,7 @@ use crate::application::errors::AuthenticationError;
 use crate::domains::account::{Account, AuthClaims};
 use crate::infra::jwt::JWTAuthenticator;
 use async_trait::async_trait;
+use uuid::Uuid;
 
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::Deserialize;
@@ -36,7 +37,7 @@ impl LocalAuthenticator {
 
 #[async_trait]
 impl JWTAuthenticator for LocalAuthenticator {
-    fn encode(&self, account: Account) -> Result<String, AuthenticationError> {
+    fn encode(&self, account: Account, session_id: Uuid) -> Result<String, AuthenticationError> {
         let now = chrono::Utc::now().timestamp();
         let expiration = now + self.token_expiry.as_secs() as i64;
         let identity = account
@@ -48,6 +49,8 @@ impl JWTAuthenticator for LocalAuthenticator {
             exp: expiration as usize,
             iat: now as usize,
             permissions: account.permissions.unwrap_or_default(),
+            sid: Some(session_id),
+            issuer: None,
         };
 
         let token = encode(&Header::default(), &claims, &self.encoding_key)
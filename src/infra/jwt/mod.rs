@@ -1,8 +1,10 @@
 mod jwt_authenticator;
 pub mod local;
+mod multi_issuer_authenticator;
 pub mod oauth2;
 
 pub use jwt_authenticator::JWTAuthenticator;
 #[allow(unused_imports)]
 #[cfg(test)]
 pub use jwt_authenticator::MockJWTAuthenticator;
+pub use multi_issuer_authenticator::MultiIssuerAuthenticator;
@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    application::errors::AuthenticationError,
+    domains::account::{Account, AuthClaims},
+    infra::jwt::{local::LocalAuthenticator, oauth2::OAuth2Authenticator, JWTAuthenticator},
+};
+
+/// Verifies tokens of several trusted OAuth2 issuers alongside locally issued ones. Tokens are verified by the issuer
+/// named in their `iss` claim, tokens of other issuers by the local authenticator.
+pub struct MultiIssuerAuthenticator {
+    local: Option<LocalAuthenticator>,
+    issuers: Vec<OAuth2Authenticator>,
+}
+
+/// Issuer claim of a token, read before its signature is verified to select the authenticator verifying it.
+#[derive(Deserialize)]
+struct UnverifiedIssuer {
+    iss: Option<String>,
+}
+
+impl MultiIssuerAuthenticator {
+    pub fn new(local: Option<LocalAuthenticator>, issuers: Vec<OAuth2Authenticator>) -> Self {
+        Self { local, issuers }
+    }
+
+    fn issuer_of(&self, token: &str) -> Option<&OAuth2Authenticator> {
+        let payload = token.split('.').nth(1)?;
+        let json = BASE64_URL_SAFE_NO_PAD.decode(payload).ok()?;
+        let iss = serde_json::from_slice::<UnverifiedIssuer>(&json).ok()?.iss?;
+
+        self.issuers.iter().find(|issuer| issuer.issuer_url() == iss)
+    }
+}
+
+#[async_trait]
+impl JWTAuthenticator for MultiIssuerAuthenticator {
+    fn encode(&self, account: Account, session_id: Uuid) -> Result<String, AuthenticationError> {
+        match &self.local {
+            Some(local) => local.encode(account, session_id),
+            None => Err(AuthenticationError::UnsupportedOperation),
+        }
+    }
+
+    async fn decode(&self, token: &str) -> Result<AuthClaims, AuthenticationError> {
+        if let Some(issuer) = self.issuer_of(token) {
+            return issuer.decode(token).await;
+        }
+
+        match &self.local {
+            Some(local) => local.decode(token).await,
+            None => Err(AuthenticationError::DecodeJWT(
+                "Token of an untrusted issuer".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::json;
+
+    use crate::domains::account::{AuthIdentity, AuthProvider};
+
+    use super::*;
+
+    async fn local() -> LocalAuthenticator {
+        let config = serde_json::from_value(json!({ "token_expiry": "1h", "secret": "secret" })).unwrap();
+        LocalAuthenticator::new(config).await.unwrap()
+    }
+
+    fn account() -> Account {
+        Account {
+            id: Uuid::new_v4(),
+            display_name: None,
+            identity: Some(AuthIdentity {
+                id: Uuid::new_v4(),
+                provider: AuthProvider::Jwt,
+                subject: "alice".to_string(),
+                created_at: Utc::now(),
+            }),
+            permissions: Some(vec![]),
+            preferences: None,
+            wallets: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn verifies_local_tokens_alongside_issuers() {
+        let authenticator = MultiIssuerAuthenticator::new(Some(local().await), vec![]);
+        let session_id = Uuid::new_v4();
+
+        let token = authenticator.encode(account(), session_id).unwrap();
+        let claims = authenticator.decode(&token).await.unwrap();
+
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.sid, Some(session_id));
+        assert!(claims.issuer.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_of_untrusted_issuers_without_local_authentication() {
+        let token = local().await.encode(account(), Uuid::new_v4()).unwrap();
+        let authenticator = MultiIssuerAuthenticator::new(None, vec![]);
+
+        assert!(matches!(
+            authenticator.encode(account(), Uuid::new_v4()),
+            Err(AuthenticationError::UnsupportedOperation)
+        ));
+        assert!(matches!(
+            authenticator.decode(&token).await,
+            Err(AuthenticationError::DecodeJWT(_))
+        ));
+    }
+}
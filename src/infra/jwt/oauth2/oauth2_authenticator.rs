@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::domains::account::AuthClaims;
+use crate::domains::account::{AccountProvisioning, AuthClaims, Permission, TokenIssuer};
 use crate::infra::jwt::JWTAuthenticator;
 use crate::{application::errors::AuthenticationError, domains::account::Account};
 use async_trait::async_trait;
@@ -11,9 +11,11 @@ use jsonwebtoken::{
     Algorithm, DecodingKey, Validation,
 };
 use serde::Deserialize;
+use serde_json::{Map, Value};
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 use tracing::{error, trace};
+use uuid::Uuid;
 
 use crate::infra::config::config_rs::deserialize_duration;
 
@@ -31,9 +33,40 @@ pub struct OAuth2Config {
     audience: String,
     #[serde(deserialize_with = "deserialize_duration")]
     leeway: Duration,
+    /// Namespace of the subjects of the issuer in account identities, required for each of `[[oauth2_issuers]]`.
+    /// Subjects of an unnamed issuer are stored as is.
+    #[serde(default)]
+    name: Option<String>,
+    /// Claim listing the permissions of the subject, as an array or a space-separated string
+    #[serde(default = "default_permissions_claim")]
+    permissions_claim: String,
+    /// Permissions granted by values of the permissions claim, such as the roles or groups of the IdP. Without
+    /// mapping, values are permission names. Unknown values are ignored either way.
+    #[serde(default)]
+    permission_mapping: Vec<PermissionMapping>,
+    /// Permissions granted to every subject of the issuer
+    #[serde(default)]
+    default_permissions: Vec<Permission>,
+    #[serde(default)]
+    provisioning: AccountProvisioning,
+}
+
+/// Permissions granted by a value of the permissions claim.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PermissionMapping {
+    value: String,
+    permissions: Vec<Permission>,
+}
+
+fn default_permissions_claim() -> String {
+    "permissions".to_string()
 }
 
 impl OAuth2Config {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     /// The issuer base URL with a scheme guaranteed and any trailing slash
     /// stripped. A bare host defaults to `https`.
     fn issuer_base(&self) -> String {
@@ -70,10 +103,69 @@ fn verify_discovered_issuer(configured_base: &str, discovered: &str) -> Result<(
     }
 }
 
+/// Claims of OAuth2 access tokens. Permissions are read from the configured claim among the others.
+#[derive(Debug, Deserialize)]
+struct IssuerClaims {
+    exp: usize,
+    iat: usize,
+    sub: String,
+    #[serde(flatten)]
+    others: Map<String, Value>,
+}
+
+/// Maps the claims of tokens to the permissions of their subject.
+#[derive(Clone, Debug)]
+struct ClaimMapping {
+    permissions_claim: String,
+    permission_mapping: Vec<PermissionMapping>,
+    default_permissions: Vec<Permission>,
+}
+
+impl ClaimMapping {
+    fn permissions(&self, claims: &Map<String, Value>) -> Result<Vec<Permission>, AuthenticationError> {
+        let values = match claims.get(&self.permissions_claim) {
+            None | Some(Value::Null) => vec![],
+            Some(Value::String(values)) => values.split_whitespace().collect(),
+            Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+            Some(_) => {
+                return Err(AuthenticationError::DecodeJWT(format!(
+                    "Invalid `{}` claim",
+                    self.permissions_claim
+                )))
+            }
+        };
+
+        let mut permissions = self.default_permissions.clone();
+        for value in values {
+            let granted = if self.permission_mapping.is_empty() {
+                serde_json::from_value(Value::from(value)).into_iter().collect()
+            } else {
+                self.permission_mapping
+                    .iter()
+                    .filter(|mapping| mapping.value == value)
+                    .flat_map(|mapping| mapping.permissions.clone())
+                    .collect::<Vec<Permission>>()
+            };
+
+            for permission in granted {
+                if !permissions.contains(&permission) {
+                    permissions.push(permission);
+                }
+            }
+        }
+
+        Ok(permissions)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct OAuth2Authenticator {
     jwks: Arc<RwLock<JwkSet>>,
     validation: Validation,
+    /// Canonical issuer, matched against the `iss` claim of tokens
+    issuer_url: String,
+    issuer: TokenIssuer,
+    claim_mapping: ClaimMapping,
 }
 
 impl OAuth2Authenticator {
@@ -96,6 +188,7 @@ impl OAuth2Authenticator {
 
         let jwks = Arc::new(RwLock::new(initial_jwks));
         let jwks_clone = Arc::clone(&jwks);
+        let jwks_refresh_interval = config.jwks_refresh_interval;
 
         tokio::spawn(async move {
             loop {
@@ -109,7 +202,7 @@ impl OAuth2Authenticator {
                         error!(%err, jwks_uri, "Error refreshing jwks")
                     }
                 }
-                sleep(config.jwks_refresh_interval).await;
+                sleep(jwks_refresh_interval).await;
             }
         });
 
@@ -118,7 +211,25 @@ impl OAuth2Authenticator {
         validation.set_issuer(&[metadata.issuer.as_str()]);
         validation.leeway = config.leeway.as_secs();
 
-        Ok(Self { jwks, validation })
+        Ok(Self {
+            jwks,
+            validation,
+            issuer_url: metadata.issuer,
+            issuer: TokenIssuer {
+                name: config.name,
+                provisioning: config.provisioning,
+            },
+            claim_mapping: ClaimMapping {
+                permissions_claim: config.permissions_claim,
+                permission_mapping: config.permission_mapping,
+                default_permissions: config.default_permissions,
+            },
+        })
+    }
+
+    /// Canonical issuer of the tokens this authenticator verifies.
+    pub fn issuer_url(&self) -> &str {
+        &self.issuer_url
     }
 
     async fn fetch_discovery(discovery_url: &str) -> Result<OpenIdProviderMetadata, reqwest::Error> {
@@ -134,7 +245,7 @@ impl OAuth2Authenticator {
 
 #[async_trait]
 impl JWTAuthenticator for OAuth2Authenticator {
-    fn encode(&self, _: Account) -> Result<String, AuthenticationError> {
+    fn encode(&self, _: Account, _: Uuid) -> Result<String, AuthenticationError> {
         Err(AuthenticationError::UnsupportedOperation)
     }
 
@@ -156,10 +267,18 @@ impl JWTAuthenticator for OAuth2Authenticator {
                     let decoding_key = DecodingKey::from_rsa_components(&rsa.n, &rsa.e)
                         .map_err(|e| AuthenticationError::DecodeJWTKey(e.to_string()))?;
 
-                    let decoded_token = decode::<AuthClaims>(token, &decoding_key, &self.validation)
-                        .map_err(|e| AuthenticationError::DecodeJWT(e.to_string()))?;
-
-                    Ok(decoded_token.claims)
+                    let claims = decode::<IssuerClaims>(token, &decoding_key, &self.validation)
+                        .map_err(|e| AuthenticationError::DecodeJWT(e.to_string()))?
+                        .claims;
+
+                    Ok(AuthClaims {
+                        exp: claims.exp,
+                        iat: claims.iat,
+                        permissions: self.claim_mapping.permissions(&claims.others)?,
+                        sub: claims.sub,
+                        sid: None,
+                        issuer: Some(self.issuer.clone()),
+                    })
                 }
                 _ => unreachable!("Only RSA algorithm is supported as JWK. should be unreachable"),
             }
@@ -179,6 +298,11 @@ mod tests {
             jwks_refresh_interval: Duration::from_secs(3600),
             audience: "https://api.example.com".to_string(),
             leeway: Duration::from_secs(60),
+            name: None,
+            permissions_claim: default_permissions_claim(),
+            permission_mapping: vec![],
+            default_permissions: vec![],
+            provisioning: AccountProvisioning::Auto,
         }
     }
 
@@ -260,4 +384,85 @@ mod tests {
             assert_eq!(metadata.jwks_uri, "https://auth.example.com/.well-known/jwks.json");
         }
     }
+
+    mod claim_mapping {
+        use serde_json::json;
+
+        use super::*;
+
+        fn claims(value: Value) -> Map<String, Value> {
+            value.as_object().unwrap().clone()
+        }
+
+        fn mapping(permissions_claim: &str, permission_mapping: Vec<PermissionMapping>) -> ClaimMapping {
+            ClaimMapping {
+                permissions_claim: permissions_claim.to_string(),
+                permission_mapping,
+                default_permissions: vec![Permission::ReadWallet],
+            }
+        }
+
+        #[test]
+        fn reads_permission_names_without_mapping() {
+            let mapping = mapping("permissions", vec![]);
+
+            let permissions = mapping
+                .permissions(&claims(
+                    json!({ "permissions": ["write:wallet", "unknown", "read:wallet"] }),
+                ))
+                .unwrap();
+
+            assert_eq!(permissions, vec![Permission::ReadWallet, Permission::WriteWallet]);
+        }
+
+        #[test]
+        fn maps_claim_values_to_permissions() {
+            let mapping = mapping(
+                "scope",
+                vec![
+                    PermissionMapping {
+                        value: "treasury".to_string(),
+                        permissions: vec![Permission::ReadTransaction, Permission::WriteTransaction],
+                    },
+                    PermissionMapping {
+                        value: "auditor".to_string(),
+                        permissions: vec![Permission::ReadAudit],
+                    },
+                ],
+            );
+
+            let permissions = mapping
+                .permissions(&claims(json!({ "scope": "openid treasury read:account" })))
+                .unwrap();
+
+            assert_eq!(
+                permissions,
+                vec![
+                    Permission::ReadWallet,
+                    Permission::ReadTransaction,
+                    Permission::WriteTransaction
+                ]
+            );
+        }
+
+        #[test]
+        fn grants_the_default_permissions_without_claim() {
+            let mapping = mapping("roles", vec![]);
+
+            let permissions = mapping
+                .permissions(&claims(json!({ "permissions": ["read:account"] })))
+                .unwrap();
+
+            assert_eq!(permissions, vec![Permission::ReadWallet]);
+        }
+
+        #[test]
+        fn rejects_claims_of_another_type() {
+            let mapping = mapping("roles", vec![]);
+
+            let err = mapping.permissions(&claims(json!({ "roles": 42 }))).unwrap_err();
+
+            assert!(matches!(err, AuthenticationError::DecodeJWT(_)));
+        }
+    }
 }
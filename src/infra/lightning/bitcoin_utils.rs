@@ -1,5 +1,15 @@
 use base64::{engine::general_purpose::STANDARD, Engine};
-use bitcoin::psbt::Psbt;
+use std::{str::FromStr, time::Duration};
+
+use bitcoin::{
+    absolute::LockTime,
+    consensus::encode::serialize,
+    hashes::{sha256, Hash},
+    psbt::Psbt,
+    secp256k1::{Secp256k1, SecretKey},
+    transaction::Version,
+    OutPoint, Transaction, TxIn, Txid, Witness,
+};
 
 use crate::application::errors::BitcoinError;
 
@@ -10,3 +20,137 @@ pub fn parse_psbt(psbt_base64: &str) -> Result<Psbt, BitcoinError> {
 
     Psbt::deserialize(&psbt_bytes).map_err(|e| BitcoinError::ParsePsbt(e.to_string()))
 }
+
+pub fn encode_psbt(psbt: &Psbt) -> String {
+    STANDARD.encode(psbt.serialize())
+}
+
+/// PSBT spending only `txid:output_index`, for node RPCs that take the outputs to act on as a PSBT.
+pub fn outpoint_psbt(txid: &str, output_index: u32) -> Result<String, BitcoinError> {
+    let txid = Txid::from_str(txid).map_err(|e| BitcoinError::ParsePsbt(e.to_string()))?;
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid,
+                vout: output_index,
+            },
+            ..Default::default()
+        }],
+        output: vec![],
+    };
+    let psbt = Psbt::from_unsigned_tx(tx).map_err(|e| BitcoinError::ParsePsbt(e.to_string()))?;
+
+    Ok(encode_psbt(&psbt))
+}
+
+/// Blocks covering `duration` at one block every ten minutes, for nodes that reserve outputs by block count.
+pub fn duration_to_blocks(duration: Duration) -> u32 {
+    duration.as_secs().div_ceil(600).max(1) as u32
+}
+
+/// Public key of the secret hashing `label`, for nodes deriving label secrets by ECDH with their identity key.
+pub fn label_public_key(label: &str) -> Result<Vec<u8>, BitcoinError> {
+    let secp = Secp256k1::signing_only();
+    let secret = SecretKey::from_slice(sha256::Hash::hash(label.as_bytes()).as_byte_array())
+        .map_err(|e| BitcoinError::DeriveSecret(e.to_string()))?;
+
+    Ok(secret.public_key(&secp).serialize().to_vec())
+}
+
+/// Checks a secret returned by the node is 32 bytes long.
+pub fn node_secret(secret: &[u8]) -> Result<[u8; 32], BitcoinError> {
+    secret
+        .try_into()
+        .map_err(|_| BitcoinError::DeriveSecret(format!("Expected 32 bytes, got {}", secret.len())))
+}
+
+/// Serializes the fully signed transaction of `psbt`, ready to broadcast.
+pub fn extract_raw_tx(psbt: Psbt) -> Vec<u8> {
+    serialize(&psbt.extract_tx_unchecked_fee_rate())
+}
+
+/// Finalizes the P2WPKH and P2TR key-path inputs that carry a signature but no final witness,
+/// for nodes that return partially signed PSBTs. Other inputs are left untouched.
+pub fn finalize_signed_inputs(psbt: &mut Psbt) {
+    for input in psbt.inputs.iter_mut() {
+        if input.final_script_witness.is_some() || input.final_script_sig.is_some() {
+            continue;
+        }
+        let Some(script_pubkey) = input.witness_utxo.as_ref().map(|utxo| utxo.script_pubkey.clone()) else {
+            continue;
+        };
+
+        let witness = if script_pubkey.is_p2wpkh() {
+            input
+                .partial_sigs
+                .iter()
+                .next()
+                .map(|(pubkey, signature)| Witness::p2wpkh(signature, &pubkey.inner))
+        } else if script_pubkey.is_p2tr() {
+            input.tap_key_sig.as_ref().map(Witness::p2tr_key_spend)
+        } else {
+            None
+        };
+
+        if let Some(witness) = witness {
+            input.final_script_witness = Some(witness);
+            input.partial_sigs.clear();
+            input.sighash_type = None;
+            input.bip32_derivation.clear();
+            input.tap_key_sig = None;
+            input.tap_key_origins.clear();
+            input.tap_internal_key = None;
+        }
+    }
+}
+
+/// Converts a feerate in sat per 1000 weight units to sat/vB, rounding up to at least 1.
+pub fn sat_per_kw_to_sat_per_vb(sat_per_kw: u64) -> u32 {
+    u32::try_from(sat_per_kw.saturating_mul(4).div_ceil(1000).max(1)).unwrap_or(u32::MAX)
+}
+
+/// Converts a feerate in sat per 1000 virtual bytes to sat/vB, rounding up to at least 1.
+pub fn sat_per_kvb_to_sat_per_vb(sat_per_kvb: u64) -> u32 {
+    u32::try_from(sat_per_kvb.div_ceil(1000).max(1)).unwrap_or(u32::MAX)
+}
+
+/// Picks the feerate for `conf_target` from `(blockcount, feerate)` estimates: the slowest estimate
+/// that still confirms within the target, or the fastest one when the target is shorter than all.
+pub fn select_feerate_estimate(estimates: impl IntoIterator<Item = (u32, u32)>, conf_target: u32) -> Option<u32> {
+    let mut estimates: Vec<(u32, u32)> = estimates.into_iter().collect();
+    estimates.sort_unstable_by_key(|(blockcount, _)| *blockcount);
+
+    estimates
+        .iter()
+        .rev()
+        .find(|(blockcount, _)| *blockcount <= conf_target)
+        .or_else(|| estimates.first())
+        .map(|(_, feerate)| *feerate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_feerate_units_rounding_up() {
+        assert_eq!(sat_per_kw_to_sat_per_vb(253), 2);
+        assert_eq!(sat_per_kw_to_sat_per_vb(2_500), 10);
+        assert_eq!(sat_per_kw_to_sat_per_vb(0), 1);
+        assert_eq!(sat_per_kvb_to_sat_per_vb(1_012), 2);
+        assert_eq!(sat_per_kvb_to_sat_per_vb(12_000), 12);
+    }
+
+    #[test]
+    fn selects_the_slowest_estimate_within_the_target() {
+        let estimates = [(2, 20_000), (6, 12_000), (12, 8_000), (100, 1_000)];
+
+        assert_eq!(select_feerate_estimate(estimates, 1), Some(20_000));
+        assert_eq!(select_feerate_estimate(estimates, 3), Some(20_000));
+        assert_eq!(select_feerate_estimate(estimates, 6), Some(12_000));
+        assert_eq!(select_feerate_estimate(estimates, 144), Some(1_000));
+        assert_eq!(select_feerate_estimate([], 6), None);
+    }
+}
@@ -4,8 +4,10 @@ use async_trait::async_trait;
 use bitcoin::{Address, Network, ScriptBuf};
 use chrono::{TimeZone, Utc};
 use cln::{
-    node_client::NodeClient, Amount, Feerate, GetinfoRequest, GetroutesRequest, ListinvoicesRequest, NewaddrRequest,
-    OutputDesc, SetpsbtversionRequest, TxdiscardRequest, TxprepareRequest, TxsendRequest, XpayRequest,
+    feerates_request::FeeratesStyle, listfunds_outputs::ListfundsOutputsStatus, node_client::NodeClient, Amount,
+    Feerate, FeeratesRequest, GetinfoRequest, GetroutesRequest, ListinvoicesRequest, MakesecretRequest, NewaddrRequest,
+    OutputDesc, ReserveinputsRequest, SendpsbtRequest, SetpsbtversionRequest, SignpsbtRequest, TxdiscardRequest,
+    TxprepareRequest, TxsendRequest, UnreserveinputsRequest, XpayRequest,
 };
 use hex::decode;
 use lightning_invoice::Bolt11Invoice;
@@ -20,8 +22,8 @@ use crate::{
     },
     domains::{
         bitcoin::{
-            BitcoinWallet, BtcAddressType, BtcNetwork, BtcOutput, BtcPreparedTransaction, BtcTransaction,
-            BtcTransactionOutput, OnchainSyncBatch, OnchainSyncCursor, OnchainTransaction,
+            BitcoinWallet, BtcAddressType, BtcLockedUtxo, BtcNetwork, BtcOutput, BtcPreparedTransaction,
+            BtcTransaction, BtcTransactionOutput, BtcUtxo, OnchainSyncBatch, OnchainSyncCursor, OnchainTransaction,
         },
         event::OnchainWithdrawalEvent,
         invoice::Invoice,
@@ -31,7 +33,10 @@ use crate::{
     infra::{
         config::config_rs::deserialize_duration,
         lightning::{
-            bitcoin_utils::parse_psbt,
+            bitcoin_utils::{
+                duration_to_blocks, encode_psbt, finalize_signed_inputs, node_secret, outpoint_psbt, parse_psbt,
+                sat_per_kvb_to_sat_per_vb, select_feerate_estimate,
+            },
             cln::cln::{
                 delinvoice_request::DelinvoiceStatus, feerate,
                 listchainmoves_chainmoves::ListchainmovesChainmovesPrimaryTag,
@@ -46,6 +51,11 @@ use crate::{
 
 use self::cln::InvoiceRequest;
 
+/// Decrease applied when unreserving an output, larger than any reservation we take so it is fully released.
+pub(crate) const CLN_UNRESERVE_BLOCKS: u32 = 2016;
+/// Reservation taken while signing a PSBT, the default of lightningd, and released once signed.
+pub(crate) const CLN_SIGN_RESERVE_BLOCKS: u32 = 72;
+
 #[allow(dead_code, clippy::all)]
 pub mod cln {
     tonic::include_proto!("cln");
@@ -165,6 +175,7 @@ impl LnClient for ClnGrpcClient {
         label: String,
         expiry: u32,
         deschashonly: bool,
+        preimage: Option<[u8; 32]>,
     ) -> Result<Invoice, LightningError> {
         let mut client = self.client.clone();
 
@@ -174,6 +185,7 @@ impl LnClient for ClnGrpcClient {
                 expiry: Some(expiry as u64),
                 label,
                 deschashonly: Some(deschashonly),
+                preimage: preimage.map(|p| p.to_vec()),
                 amount_msat: Some(cln::AmountOrAny {
                     value: Some(cln::amount_or_any::Value::Amount(cln::Amount { msat: amount_msat })),
                 }),
@@ -454,6 +466,148 @@ impl BitcoinWallet for ClnGrpcClient {
         Ok(())
     }
 
+    async fn sign_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let mut client = self.client.clone();
+
+        // lightningd only signs reserved inputs. Inputs that are not ours are skipped by all three calls.
+        client
+            .reserve_inputs(ReserveinputsRequest {
+                psbt: psbt.to_string(),
+                exclusive: Some(false),
+                reserve: Some(CLN_SIGN_RESERVE_BLOCKS),
+            })
+            .await
+            .map_err(|e| BitcoinError::SignPsbt(e.message().to_string()))?;
+
+        let signed = async {
+            let signed = client
+                .sign_psbt(SignpsbtRequest {
+                    psbt: psbt.to_string(),
+                    signonly: vec![],
+                })
+                .await
+                .map_err(|e| BitcoinError::SignPsbt(e.message().to_string()))?
+                .into_inner();
+
+            let psbt_v0 = client
+                .set_psbt_version(SetpsbtversionRequest {
+                    psbt: signed.signed_psbt,
+                    version: 0,
+                })
+                .await
+                .map_err(|e| BitcoinError::SignPsbt(e.message().to_string()))?
+                .into_inner();
+
+            let mut psbt = parse_psbt(&psbt_v0.psbt)?;
+            finalize_signed_inputs(&mut psbt);
+
+            Ok::<_, BitcoinError>(encode_psbt(&psbt))
+        }
+        .await;
+
+        // Whether signing failed or the signed PSBT is never broadcast, such as when a payjoin falls back to the
+        // original transaction, the inputs are left as they were: free, or reserved by the prepared transaction or
+        // the lock of a payjoin contribution.
+        let unreserved = client
+            .unreserve_inputs(UnreserveinputsRequest {
+                psbt: psbt.to_string(),
+                reserve: Some(CLN_SIGN_RESERVE_BLOCKS),
+            })
+            .await
+            .map_err(|e| BitcoinError::SignPsbt(e.message().to_string()));
+
+        let signed = signed?;
+        unreserved?;
+        Ok(signed)
+    }
+
+    async fn broadcast_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let mut client = self.client.clone();
+
+        let response = client
+            .send_psbt(SendpsbtRequest {
+                psbt: psbt.to_string(),
+                reserve: None,
+            })
+            .await
+            .map_err(|e| BitcoinError::BroadcastTransaction(e.message().to_string()))?
+            .into_inner();
+
+        Ok(hex::encode(response.txid))
+    }
+
+    async fn lock_utxo(&self, utxo: &BtcUtxo, expiry: Duration) -> Result<BtcLockedUtxo, BitcoinError> {
+        let mut client = self.client.clone();
+
+        client
+            .reserve_inputs(ReserveinputsRequest {
+                psbt: outpoint_psbt(&utxo.txid, utxo.output_index)?,
+                exclusive: Some(true),
+                reserve: Some(duration_to_blocks(expiry)),
+            })
+            .await
+            .map_err(|e| BitcoinError::LockUtxo(e.message().to_string()))?;
+
+        Ok(BtcLockedUtxo {
+            id: String::new(),
+            txid: utxo.txid.clone(),
+            output_index: utxo.output_index,
+        })
+    }
+
+    async fn unlock_utxo(&self, utxo: &BtcLockedUtxo) -> Result<(), BitcoinError> {
+        let mut client = self.client.clone();
+
+        client
+            .unreserve_inputs(UnreserveinputsRequest {
+                psbt: outpoint_psbt(&utxo.txid, utxo.output_index)?,
+                reserve: Some(CLN_UNRESERVE_BLOCKS),
+            })
+            .await
+            .map_err(|e| BitcoinError::UnlockUtxo(e.message().to_string()))?;
+
+        Ok(())
+    }
+
+    async fn derive_secret(&self, label: &str) -> Result<[u8; 32], BitcoinError> {
+        let mut client = self.client.clone();
+
+        let response = client
+            .make_secret(MakesecretRequest {
+                hex: None,
+                string: Some(label.to_string()),
+            })
+            .await
+            .map_err(|e| BitcoinError::DeriveSecret(e.message().to_string()))?
+            .into_inner();
+
+        node_secret(&response.secret)
+    }
+
+    async fn list_unspent(&self) -> Result<Vec<BtcUtxo>, BitcoinError> {
+        let mut client = self.client.clone();
+
+        let response = client
+            .list_funds(cln::ListfundsRequest { spent: Some(false) })
+            .await
+            .map_err(|e| BitcoinError::ListUnspent(e.message().to_string()))?
+            .into_inner();
+
+        Ok(response
+            .outputs
+            .into_iter()
+            .filter(|output| output.status() == ListfundsOutputsStatus::Confirmed && !output.reserved)
+            .filter_map(|output| {
+                Some(BtcUtxo {
+                    txid: hex::encode(&output.txid),
+                    output_index: output.output,
+                    address: output.address?,
+                    amount_sat: output.amount_msat.map(|a| a.msat).unwrap_or_default() / 1000,
+                })
+            })
+            .collect())
+    }
+
     async fn get_transaction(&self, txid: &str) -> Result<Option<BtcTransaction>, BitcoinError> {
         let mut client = self.client.clone();
 
@@ -663,6 +817,39 @@ impl BitcoinWallet for ClnGrpcClient {
         }))
     }
 
+    async fn estimate_feerate(&self, conf_target: u32) -> Result<u32, BitcoinError> {
+        let mut client = self.client.clone();
+
+        let response = client
+            .feerates(FeeratesRequest {
+                style: FeeratesStyle::Perkb.into(),
+            })
+            .await
+            .map_err(|e| BitcoinError::EstimateFee(e.message().to_string()))?
+            .into_inner();
+
+        let estimates = response.perkb.map(|perkb| perkb.estimates).unwrap_or_default();
+        let feerate = select_feerate_estimate(
+            estimates.iter().map(|estimate| (estimate.blockcount, estimate.feerate)),
+            conf_target,
+        )
+        .ok_or_else(|| BitcoinError::EstimateFee("No feerate estimates returned by CLN".to_string()))?;
+
+        Ok(sat_per_kvb_to_sat_per_vb(feerate.into()))
+    }
+
+    async fn block_height(&self) -> Result<u32, BitcoinError> {
+        let mut client = self.client.clone();
+
+        let response = client
+            .getinfo(GetinfoRequest {})
+            .await
+            .map_err(|e| BitcoinError::BlockHeight(e.message().to_string()))?
+            .into_inner();
+
+        Ok(response.blockheight)
+    }
+
     fn network(&self) -> BtcNetwork {
         self.network
     }
@@ -54,6 +54,9 @@ impl From<ListinvoicesInvoices> for Invoice {
                 invoice.status = InvoiceStatus::Settled;
                 invoice.payment_time = Some(Utc.timestamp_opt(val.paid_at() as i64, 0).unwrap());
                 invoice.amount_msat = Some(val.amount_received_msat.unwrap().msat);
+                if let Some(ln_invoice) = invoice.ln_invoice.as_mut() {
+                    ln_invoice.payment_preimage = val.payment_preimage.as_ref().map(hex::encode);
+                }
             }
             ListinvoicesInvoicesStatus::Unpaid => {
                 invoice.status = InvoiceStatus::Pending;
@@ -74,6 +77,7 @@ impl From<WaitinvoiceResponse> for LnInvoicePaidEvent {
             amount_received_msat: val.amount_received_msat.as_ref().unwrap().msat,
             fee_msat: 0,
             payment_time: Utc.timestamp_opt(val.paid_at() as i64, 0).unwrap(),
+            payment_preimage: val.payment_preimage.as_ref().map(hex::encode),
         }
     }
 }
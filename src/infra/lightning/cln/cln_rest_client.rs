@@ -18,8 +18,9 @@ use crate::{
     },
     domains::{
         bitcoin::{
-            BitcoinWallet, BtcAddressType, BtcNetwork, BtcOutput, BtcOutputStatus, BtcPreparedTransaction,
-            BtcTransaction, BtcTransactionOutput, OnchainSyncBatch, OnchainSyncCursor, OnchainTransaction,
+            BitcoinWallet, BtcAddressType, BtcLockedUtxo, BtcNetwork, BtcOutput, BtcOutputStatus,
+            BtcPreparedTransaction, BtcTransaction, BtcTransactionOutput, BtcUtxo, OnchainSyncBatch, OnchainSyncCursor,
+            OnchainTransaction,
         },
         event::OnchainWithdrawalEvent,
         invoice::Invoice,
@@ -28,17 +29,27 @@ use crate::{
     },
     infra::{
         config::config_rs::deserialize_duration,
-        lightning::{bitcoin_utils::parse_psbt, cln::ListFundsResponse, types::parse_network, LnClient},
+        lightning::{
+            bitcoin_utils::{
+                duration_to_blocks, encode_psbt, finalize_signed_inputs, node_secret, outpoint_psbt, parse_psbt,
+                sat_per_kvb_to_sat_per_vb, select_feerate_estimate,
+            },
+            cln::{ListFundsResponse, CLN_SIGN_RESERVE_BLOCKS, CLN_UNRESERVE_BLOCKS},
+            types::parse_network,
+            LnClient,
+        },
     },
 };
 
 use super::{
-    DelInvoiceRequest, DelInvoiceResponse, ErrorResponse, GetRoutesRequest, GetRoutesResponse, GetinfoRequest,
-    GetinfoResponse, InvoiceRequest, InvoiceResponse, ListChainMovesRequest, ListChainMovesResponse, ListFundsRequest,
-    ListInvoicesRequest, ListInvoicesResponse, ListPaysRequest, ListPaysResponse, ListTransactionsRequest,
-    ListTransactionsResponse, NewAddrRequest, NewAddrResponse, SetPsbtVersionRequest, SetPsbtVersionResponse,
-    TxDiscardRequest, TxDiscardResponse, TxPrepareOutput, TxPrepareRequest, TxPrepareResponse, TxSendRequest,
-    TxSendResponse, XpayRequest, XpayResponse,
+    DelInvoiceRequest, DelInvoiceResponse, ErrorResponse, FeeratesRequest, FeeratesResponse, GetRoutesRequest,
+    GetRoutesResponse, GetinfoRequest, GetinfoResponse, InvoiceRequest, InvoiceResponse, ListChainMovesRequest,
+    ListChainMovesResponse, ListFundsRequest, ListInvoicesRequest, ListInvoicesResponse, ListPaysRequest,
+    ListPaysResponse, ListTransactionsRequest, ListTransactionsResponse, MakeSecretRequest, MakeSecretResponse,
+    NewAddrRequest, NewAddrResponse, ReserveInputsRequest, ReserveInputsResponse, SendPsbtRequest, SendPsbtResponse,
+    SetPsbtVersionRequest, SetPsbtVersionResponse, SignPsbtRequest, SignPsbtResponse, TxDiscardRequest,
+    TxDiscardResponse, TxPrepareOutput, TxPrepareRequest, TxPrepareResponse, TxSendRequest, TxSendResponse,
+    UnreserveInputsRequest, UnreserveInputsResponse, XpayRequest, XpayResponse,
 };
 
 #[derive(Clone, Debug, Deserialize)]
@@ -169,6 +180,7 @@ impl LnClient for ClnRestClient {
         label: String,
         expiry: u32,
         deschashonly: bool,
+        preimage: Option<[u8; 32]>,
     ) -> Result<Invoice, LightningError> {
         let response: InvoiceResponse = self
             .post_request(
@@ -179,6 +191,7 @@ impl LnClient for ClnRestClient {
                     label,
                     amount_msat,
                     deschashonly: Some(deschashonly),
+                    preimage: preimage.map(hex::encode),
                 },
             )
             .await
@@ -437,6 +450,143 @@ impl BitcoinWallet for ClnRestClient {
         Ok(())
     }
 
+    async fn sign_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        // lightningd only signs reserved inputs. Inputs that are not ours are skipped by all three calls.
+        let _reserved: ReserveInputsResponse = self
+            .post_request(
+                "reserveinputs",
+                &ReserveInputsRequest {
+                    psbt: psbt.to_string(),
+                    exclusive: false,
+                    reserve: Some(CLN_SIGN_RESERVE_BLOCKS),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::SignPsbt(e.to_string()))?;
+
+        let signed = async {
+            let signed: SignPsbtResponse = self
+                .post_request("signpsbt", &SignPsbtRequest { psbt: psbt.to_string() })
+                .await
+                .map_err(|e| BitcoinError::SignPsbt(e.to_string()))?;
+
+            let psbt_v0: SetPsbtVersionResponse = self
+                .post_request(
+                    "setpsbtversion",
+                    &SetPsbtVersionRequest {
+                        psbt: signed.signed_psbt,
+                        version: 0,
+                    },
+                )
+                .await
+                .map_err(|e| BitcoinError::SignPsbt(e.to_string()))?;
+
+            let mut psbt = parse_psbt(&psbt_v0.psbt)?;
+            finalize_signed_inputs(&mut psbt);
+
+            Ok::<_, BitcoinError>(encode_psbt(&psbt))
+        }
+        .await;
+
+        // Whether signing failed or the signed PSBT is never broadcast, such as when a payjoin falls back to the
+        // original transaction, the inputs are left as they were: free, or reserved by the prepared transaction or
+        // the lock of a payjoin contribution.
+        let unreserved: Result<UnreserveInputsResponse, _> = self
+            .post_request(
+                "unreserveinputs",
+                &UnreserveInputsRequest {
+                    psbt: psbt.to_string(),
+                    reserve: CLN_SIGN_RESERVE_BLOCKS,
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::SignPsbt(e.to_string()));
+
+        let signed = signed?;
+        unreserved?;
+        Ok(signed)
+    }
+
+    async fn broadcast_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let response: SendPsbtResponse = self
+            .post_request("sendpsbt", &SendPsbtRequest { psbt: psbt.to_string() })
+            .await
+            .map_err(|e| BitcoinError::BroadcastTransaction(e.to_string()))?;
+
+        Ok(response.txid)
+    }
+
+    async fn lock_utxo(&self, utxo: &BtcUtxo, expiry: Duration) -> Result<BtcLockedUtxo, BitcoinError> {
+        let _reserved: ReserveInputsResponse = self
+            .post_request(
+                "reserveinputs",
+                &ReserveInputsRequest {
+                    psbt: outpoint_psbt(&utxo.txid, utxo.output_index)?,
+                    exclusive: true,
+                    reserve: Some(duration_to_blocks(expiry)),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::LockUtxo(e.to_string()))?;
+
+        Ok(BtcLockedUtxo {
+            id: String::new(),
+            txid: utxo.txid.clone(),
+            output_index: utxo.output_index,
+        })
+    }
+
+    async fn unlock_utxo(&self, utxo: &BtcLockedUtxo) -> Result<(), BitcoinError> {
+        let _unreserved: UnreserveInputsResponse = self
+            .post_request(
+                "unreserveinputs",
+                &UnreserveInputsRequest {
+                    psbt: outpoint_psbt(&utxo.txid, utxo.output_index)?,
+                    reserve: CLN_UNRESERVE_BLOCKS,
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::UnlockUtxo(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn derive_secret(&self, label: &str) -> Result<[u8; 32], BitcoinError> {
+        let response: MakeSecretResponse = self
+            .post_request(
+                "makesecret",
+                &MakeSecretRequest {
+                    string: label.to_string(),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::DeriveSecret(e.to_string()))?;
+
+        let secret = hex::decode(response.secret).map_err(|e| BitcoinError::DeriveSecret(e.to_string()))?;
+        node_secret(&secret)
+    }
+
+    async fn list_unspent(&self) -> Result<Vec<BtcUtxo>, BitcoinError> {
+        let response: ListFundsResponse = self
+            .post_request("listfunds", &ListFundsRequest { spent: Some(false) })
+            .await
+            .map_err(|e| BitcoinError::ListUnspent(e.to_string()))?;
+
+        Ok(response
+            .outputs
+            .into_iter()
+            .filter(|output| output.status == "confirmed" && !output.reserved)
+            .filter_map(|output| {
+                Some(BtcUtxo {
+                    txid: output.txid,
+                    output_index: output.output,
+                    address: output.address?,
+                    amount_sat: output.amount_msat / 1000,
+                })
+            })
+            .collect())
+    }
+
     async fn get_transaction(&self, txid: &str) -> Result<Option<BtcTransaction>, BitcoinError> {
         let response: ListTransactionsResponse = self
             .post_request("listtransactions", &ListTransactionsRequest {})
@@ -643,6 +793,36 @@ impl BitcoinWallet for ClnRestClient {
         }))
     }
 
+    async fn estimate_feerate(&self, conf_target: u32) -> Result<u32, BitcoinError> {
+        let response = self
+            .post_request::<FeeratesResponse>(
+                "feerates",
+                &FeeratesRequest {
+                    style: "perkb".to_string(),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::EstimateFee(e.to_string()))?;
+
+        let estimates = response.perkb.map(|perkb| perkb.estimates).unwrap_or_default();
+        let feerate = select_feerate_estimate(
+            estimates.iter().map(|estimate| (estimate.blockcount, estimate.feerate)),
+            conf_target,
+        )
+        .ok_or_else(|| BitcoinError::EstimateFee("No feerate estimates returned by CLN".to_string()))?;
+
+        Ok(sat_per_kvb_to_sat_per_vb(feerate.into()))
+    }
+
+    async fn block_height(&self) -> Result<u32, BitcoinError> {
+        let response = self
+            .post_request::<GetinfoResponse>("getinfo", &GetinfoRequest {})
+            .await
+            .map_err(|e| BitcoinError::BlockHeight(e.to_string()))?;
+
+        Ok(response.blockheight)
+    }
+
     fn network(&self) -> BtcNetwork {
         self.network
     }
@@ -19,6 +19,8 @@ pub struct InvoiceRequest {
     pub expiry: u64,
     pub amount_msat: u64,
     pub deschashonly: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,15 +149,40 @@ pub struct ListFundsOutput {
     pub address: Option<String>,
     pub status: String,
     pub blockheight: Option<u32>,
+    #[serde(default)]
+    pub reserved: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct GetinfoRequest {}
 
+#[derive(Debug, Serialize)]
+pub struct FeeratesRequest {
+    pub style: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeratesResponse {
+    pub perkb: Option<FeeratesPerkb>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeratesPerkb {
+    #[serde(default)]
+    pub estimates: Vec<FeeratesEstimate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeratesEstimate {
+    pub blockcount: u32,
+    pub feerate: u32,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GetinfoResponse {
     pub id: String,
     pub network: String,
+    pub blockheight: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -237,6 +264,56 @@ pub struct SetPsbtVersionResponse {
     pub psbt: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ReserveInputsRequest {
+    pub psbt: String,
+    pub exclusive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserve: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReserveInputsResponse {}
+
+#[derive(Debug, Serialize)]
+pub struct UnreserveInputsRequest {
+    pub psbt: String,
+    pub reserve: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnreserveInputsResponse {}
+
+#[derive(Debug, Serialize)]
+pub struct MakeSecretRequest {
+    pub string: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MakeSecretResponse {
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignPsbtRequest {
+    pub psbt: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignPsbtResponse {
+    pub signed_psbt: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SendPsbtRequest {
+    pub psbt: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendPsbtResponse {
+    pub txid: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TxSendRequest {
     pub txid: String,
@@ -265,6 +342,7 @@ pub struct ListInvoicesInvoice {
     status: String,
     paid_at: Option<u64>,
     amount_received_msat: Option<u64>,
+    payment_preimage: Option<String>,
 }
 
 impl From<XpayResponse> for Payment {
@@ -306,6 +384,9 @@ impl From<ListInvoicesInvoice> for Invoice {
                 invoice.status = InvoiceStatus::Settled;
                 invoice.payment_time = Some(Utc.timestamp_opt(val.paid_at.unwrap() as i64, 0).unwrap());
                 invoice.amount_received_msat = val.amount_received_msat;
+                if let Some(ln_invoice) = invoice.ln_invoice.as_mut() {
+                    ln_invoice.payment_preimage = val.payment_preimage;
+                }
             }
             "unpaid" => {
                 invoice.status = InvoiceStatus::Pending;
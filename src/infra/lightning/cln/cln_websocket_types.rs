@@ -34,13 +34,14 @@ pub struct SendPayFailureData {
 
 impl From<InvoicePayment> for LnInvoicePaidEvent {
     fn from(val: InvoicePayment) -> Self {
-        let preimage = hex::decode(val.preimage.clone()).expect("should be hex string");
+        let preimage = hex::decode(&val.preimage).expect("should be hex string");
         let payment_hash = sha256::Hash::hash(&preimage).to_string();
         LnInvoicePaidEvent {
             payment_hash,
             amount_received_msat: val.msat,
             fee_msat: 0,
             payment_time: Utc::now(),
+            payment_preimage: Some(val.preimage),
         }
     }
 }
@@ -20,6 +20,7 @@ pub trait LnClient: Sync + Send {
         label: String,
         expiry: u32,
         deschashonly: bool,
+        preimage: Option<[u8; 32]>,
     ) -> Result<Invoice, LightningError>;
     /// Return a provider-derived route fee estimate.
     async fn estimate_fee(&self, target: LnPaymentTarget) -> Result<u64, LightningError>;
@@ -16,6 +16,7 @@ use tonic::{
 use invoicesrpc::invoices_client::InvoicesClient;
 use lnrpc::lightning_client::LightningClient;
 use routerrpc::router_client::RouterClient;
+use signrpc::signer_client::SignerClient;
 use walletrpc::wallet_kit_client::WalletKitClient;
 
 use crate::{
@@ -26,7 +27,7 @@ use crate::{
     domains::{
         bitcoin::{
             BitcoinWallet, BtcAddressType, BtcLockedUtxo, BtcNetwork, BtcOutput, BtcOutputStatus,
-            BtcPreparedTransaction, BtcTransaction, BtcTransactionOutput, OnchainSyncBatch, OnchainSyncCursor,
+            BtcPreparedTransaction, BtcTransaction, BtcTransactionOutput, BtcUtxo, OnchainSyncBatch, OnchainSyncCursor,
             OnchainTransaction,
         },
         invoice::{Invoice, InvoiceStatus},
@@ -36,14 +37,14 @@ use crate::{
     infra::{
         config::config_rs::deserialize_duration,
         lightning::{
-            bitcoin_utils::parse_psbt,
+            bitcoin_utils::{extract_raw_tx, label_public_key, node_secret, parse_psbt, sat_per_kw_to_sat_per_vb},
             lnd::{
                 lnrpc::{
                     invoice::InvoiceState, AddressType, GetTransactionsRequest, NewAddressRequest, PaymentFailureReason,
                 },
                 walletrpc::{
                     fund_psbt_request::{Fees, Template},
-                    GetTransactionRequest, TxTemplate,
+                    EstimateFeeRequest, GetTransactionRequest, TxTemplate,
                 },
             },
             types::parse_network,
@@ -109,6 +110,7 @@ pub struct LndGrpcClient {
     invoices: InvoicesClient<LndChannel>,
     router: RouterClient<LndChannel>,
     wallet: WalletKitClient<LndChannel>,
+    signer: SignerClient<LndChannel>,
     fee_limit_msat: u64,
     payment_timeout: Duration,
     reorg_buffer_blocks: u32,
@@ -123,7 +125,8 @@ impl LndGrpcClient {
             client: LightningClient::new(channel.clone()),
             invoices: InvoicesClient::new(channel.clone()),
             router: RouterClient::new(channel.clone()),
-            wallet: WalletKitClient::new(channel),
+            wallet: WalletKitClient::new(channel.clone()),
+            signer: SignerClient::new(channel),
             fee_limit_msat: config.fee_limit_msat,
             payment_timeout: config.payment_timeout,
             reorg_buffer_blocks: config.reorg_buffer_blocks,
@@ -219,6 +222,9 @@ impl LndGrpcClient {
                 if response.amt_paid_msat > 0 {
                     invoice.amount_received_msat = Some(response.amt_paid_msat as u64);
                 }
+                if let Some(ln_invoice) = invoice.ln_invoice.as_mut() {
+                    ln_invoice.payment_preimage = Some(hex::encode(&response.r_preimage));
+                }
             }
             InvoiceState::Open | InvoiceState::Accepted => {
                 invoice.status = InvoiceStatus::Pending;
@@ -306,11 +312,13 @@ impl LnClient for LndGrpcClient {
         _label: String,
         expiry: u32,
         deschashonly: bool,
+        preimage: Option<[u8; 32]>,
     ) -> Result<Invoice, LightningError> {
         let mut request = lnrpc::Invoice {
             memo: description.clone(),
             expiry: expiry as i64,
             value_msat: amount_msat as i64,
+            r_preimage: preimage.map(|p| p.to_vec()).unwrap_or_default(),
             ..Default::default()
         };
 
@@ -607,6 +615,132 @@ impl BitcoinWallet for LndGrpcClient {
         Ok(())
     }
 
+    async fn sign_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let mut wallet = self.wallet.clone();
+        let psbt_bytes = STANDARD
+            .decode(psbt)
+            .map_err(|e| BitcoinError::SignPsbt(e.to_string()))?;
+
+        let response = wallet
+            .finalize_psbt(walletrpc::FinalizePsbtRequest {
+                funded_psbt: psbt_bytes,
+                account: String::new(),
+            })
+            .await
+            .map_err(|e| BitcoinError::SignPsbt(e.message().to_string()))?
+            .into_inner();
+
+        Ok(STANDARD.encode(response.signed_psbt))
+    }
+
+    async fn broadcast_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let mut wallet = self.wallet.clone();
+        let psbt = parse_psbt(psbt)?;
+        let txid = psbt.unsigned_tx.compute_txid().to_string();
+
+        let response = wallet
+            .publish_transaction(walletrpc::Transaction {
+                tx_hex: extract_raw_tx(psbt),
+                label: String::new(),
+            })
+            .await
+            .map_err(|e| BitcoinError::BroadcastTransaction(e.message().to_string()))?
+            .into_inner();
+
+        if !response.publish_error.is_empty() {
+            return Err(BitcoinError::BroadcastTransaction(response.publish_error));
+        }
+
+        Ok(txid)
+    }
+
+    async fn lock_utxo(&self, utxo: &BtcUtxo, expiry: Duration) -> Result<BtcLockedUtxo, BitcoinError> {
+        let mut wallet = self.wallet.clone();
+        let id = rand::random::<[u8; 32]>();
+
+        wallet
+            .lease_output(walletrpc::LeaseOutputRequest {
+                id: id.to_vec(),
+                outpoint: Some(lnrpc::OutPoint {
+                    txid_bytes: Vec::new(),
+                    txid_str: utxo.txid.clone(),
+                    output_index: utxo.output_index,
+                }),
+                expiration_seconds: expiry.as_secs(),
+            })
+            .await
+            .map_err(|e| BitcoinError::LockUtxo(e.message().to_string()))?;
+
+        Ok(BtcLockedUtxo {
+            id: hex::encode(id),
+            txid: utxo.txid.clone(),
+            output_index: utxo.output_index,
+        })
+    }
+
+    async fn unlock_utxo(&self, utxo: &BtcLockedUtxo) -> Result<(), BitcoinError> {
+        let mut wallet = self.wallet.clone();
+        let id = hex::decode(&utxo.id).map_err(|e| BitcoinError::UnlockUtxo(e.to_string()))?;
+
+        wallet
+            .release_output(walletrpc::ReleaseOutputRequest {
+                id,
+                outpoint: Some(lnrpc::OutPoint {
+                    txid_bytes: Vec::new(),
+                    txid_str: utxo.txid.clone(),
+                    output_index: utxo.output_index,
+                }),
+            })
+            .await
+            .map_err(|e| BitcoinError::UnlockUtxo(e.message().to_string()))?;
+
+        Ok(())
+    }
+
+    async fn derive_secret(&self, label: &str) -> Result<[u8; 32], BitcoinError> {
+        let mut signer = self.signer.clone();
+
+        let response = signer
+            .derive_shared_key(signrpc::SharedKeyRequest {
+                ephemeral_pubkey: label_public_key(label)?,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| BitcoinError::DeriveSecret(e.message().to_string()))?
+            .into_inner();
+
+        node_secret(&response.shared_key)
+    }
+
+    async fn list_unspent(&self) -> Result<Vec<BtcUtxo>, BitcoinError> {
+        let mut wallet = self.wallet.clone();
+
+        let response = wallet
+            .list_unspent(walletrpc::ListUnspentRequest {
+                min_confs: 1,
+                max_confs: i32::MAX,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| BitcoinError::ListUnspent(e.message().to_string()))?
+            .into_inner();
+
+        Ok(response
+            .utxos
+            .into_iter()
+            .filter_map(|utxo| {
+                let outpoint = utxo.outpoint?;
+
+                Some(BtcUtxo {
+                    txid: outpoint.txid_str,
+                    output_index: outpoint.output_index,
+                    address: utxo.address,
+                    amount_sat: u64::try_from(utxo.amount_sat).ok()?,
+                })
+            })
+            .collect())
+    }
+
     async fn get_transaction(&self, txid: &str) -> Result<Option<BtcTransaction>, BitcoinError> {
         let mut wallet = self.wallet.clone();
         let response = wallet
@@ -719,6 +853,32 @@ impl BitcoinWallet for LndGrpcClient {
         }))
     }
 
+    async fn estimate_feerate(&self, conf_target: u32) -> Result<u32, BitcoinError> {
+        let mut wallet = self.wallet.clone();
+
+        let response = wallet
+            .estimate_fee(EstimateFeeRequest {
+                conf_target: conf_target as i32,
+            })
+            .await
+            .map_err(|e| BitcoinError::EstimateFee(e.message().to_string()))?
+            .into_inner();
+
+        Ok(sat_per_kw_to_sat_per_vb(response.sat_per_kw.max(0) as u64))
+    }
+
+    async fn block_height(&self) -> Result<u32, BitcoinError> {
+        let mut client = self.client.clone();
+
+        let response = client
+            .get_info(lnrpc::GetInfoRequest {})
+            .await
+            .map_err(|e| BitcoinError::BlockHeight(e.message().to_string()))?
+            .into_inner();
+
+        Ok(response.block_height)
+    }
+
     fn network(&self) -> BtcNetwork {
         self.network
     }
@@ -106,6 +106,7 @@ impl LndGrpcListener {
             amount_received_msat: invoice.amt_paid_msat as u64,
             fee_msat: 0,
             payment_time,
+            payment_preimage: (!invoice.r_preimage.is_empty()).then(|| hex::encode(&invoice.r_preimage)),
         };
 
         self.services
@@ -18,7 +18,7 @@ use crate::{
     domains::{
         bitcoin::{
             BitcoinWallet, BtcAddressType, BtcLockedUtxo, BtcNetwork, BtcOutput, BtcOutputStatus,
-            BtcPreparedTransaction, BtcTransaction, OnchainSyncBatch, OnchainSyncCursor, OnchainTransaction,
+            BtcPreparedTransaction, BtcTransaction, BtcUtxo, OnchainSyncBatch, OnchainSyncCursor, OnchainTransaction,
         },
         invoice::Invoice,
         payment::{LnPayment, LnPaymentTarget, Payment, PaymentStatus},
@@ -26,7 +26,11 @@ use crate::{
     },
     infra::{
         config::config_rs::deserialize_duration,
-        lightning::{bitcoin_utils::parse_psbt, types::parse_network, LnClient},
+        lightning::{
+            bitcoin_utils::{extract_raw_tx, label_public_key, node_secret, parse_psbt, sat_per_kw_to_sat_per_vb},
+            types::parse_network,
+            LnClient,
+        },
     },
 };
 use async_trait::async_trait;
@@ -244,11 +248,13 @@ impl LnClient for LndRestClient {
         _label: String,
         expiry: u32,
         deschashonly: bool,
+        preimage: Option<[u8; 32]>,
     ) -> Result<Invoice, LightningError> {
         let mut payload = InvoiceRequest {
             memo: description.clone(),
             expiry: expiry as u64,
             value_msat: amount_msat,
+            r_preimage: preimage.map(|p| STANDARD.encode(p)),
             ..Default::default()
         };
 
@@ -541,6 +547,125 @@ impl BitcoinWallet for LndRestClient {
         Ok(())
     }
 
+    async fn sign_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let response = self
+            .post_request::<FinalizePsbtResponse>(
+                "v2/wallet/psbt/finalize",
+                &FinalizePsbtRequest {
+                    funded_psbt: psbt.to_string(),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::SignPsbt(e.to_string()))?;
+
+        Ok(response.signed_psbt)
+    }
+
+    async fn broadcast_psbt(&self, psbt: &str) -> Result<String, BitcoinError> {
+        let psbt = parse_psbt(psbt)?;
+        let txid = psbt.unsigned_tx.compute_txid().to_string();
+
+        let response: PublishTransactionResponse = self
+            .post_request(
+                "v2/wallet/tx",
+                &PublishTransactionRequest {
+                    tx_hex: STANDARD.encode(extract_raw_tx(psbt)),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::BroadcastTransaction(e.to_string()))?;
+
+        if !response.publish_error.is_empty() {
+            return Err(BitcoinError::BroadcastTransaction(response.publish_error));
+        }
+
+        Ok(txid)
+    }
+
+    async fn lock_utxo(&self, utxo: &BtcUtxo, expiry: Duration) -> Result<BtcLockedUtxo, BitcoinError> {
+        let id = STANDARD.encode(rand::random::<[u8; 32]>());
+
+        self.post_request::<LeaseOutputResponse>(
+            "v2/wallet/utxos/lease",
+            &LeaseOutputRequest {
+                id: id.clone(),
+                outpoint: OutPoint {
+                    txid_str: Some(utxo.txid.clone()),
+                    output_index: Some(utxo.output_index as i64),
+                },
+                expiration_seconds: expiry.as_secs(),
+            },
+        )
+        .await
+        .map_err(|e| BitcoinError::LockUtxo(e.to_string()))?;
+
+        Ok(BtcLockedUtxo {
+            id,
+            txid: utxo.txid.clone(),
+            output_index: utxo.output_index,
+        })
+    }
+
+    async fn unlock_utxo(&self, utxo: &BtcLockedUtxo) -> Result<(), BitcoinError> {
+        self.post_request::<ReleaseOutputResponse>(
+            "v2/wallet/utxos/release",
+            &ReleaseOutputRequest {
+                id: utxo.id.clone(),
+                outpoint: OutPoint {
+                    txid_str: Some(utxo.txid.clone()),
+                    output_index: Some(utxo.output_index as i64),
+                },
+            },
+        )
+        .await
+        .map_err(|e| BitcoinError::UnlockUtxo(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn derive_secret(&self, label: &str) -> Result<[u8; 32], BitcoinError> {
+        let response: SharedKeyResponse = self
+            .post_request(
+                "v2/signer/sharedkey",
+                &SharedKeyRequest {
+                    ephemeral_pubkey: STANDARD.encode(label_public_key(label)?),
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::DeriveSecret(e.to_string()))?;
+
+        let secret = STANDARD
+            .decode(response.shared_key)
+            .map_err(|e| BitcoinError::DeriveSecret(e.to_string()))?;
+        node_secret(&secret)
+    }
+
+    async fn list_unspent(&self) -> Result<Vec<BtcUtxo>, BitcoinError> {
+        let response: ListUnspentResponse = self
+            .post_request(
+                "v2/wallet/utxos",
+                &ListUnspentRequest {
+                    min_confs: 1,
+                    max_confs: i32::MAX,
+                },
+            )
+            .await
+            .map_err(|e| BitcoinError::ListUnspent(e.to_string()))?;
+
+        Ok(response
+            .utxos
+            .into_iter()
+            .filter_map(|utxo| {
+                Some(BtcUtxo {
+                    txid: utxo.outpoint.txid_str?,
+                    output_index: u32::try_from(utxo.outpoint.output_index.unwrap_or_default()).ok()?,
+                    address: utxo.address,
+                    amount_sat: utxo.amount_sat,
+                })
+            })
+            .collect())
+    }
+
     async fn get_transaction(&self, txid: &str) -> Result<Option<BtcTransaction>, BitcoinError> {
         let endpoint = format!("v2/wallet/tx?txid={}", txid);
 
@@ -648,6 +773,27 @@ impl BitcoinWallet for LndRestClient {
         }))
     }
 
+    async fn estimate_feerate(&self, conf_target: u32) -> Result<u32, BitcoinError> {
+        let endpoint = format!("v2/wallet/estimatefee/{}", conf_target);
+        let response: EstimateFeeResponse = self
+            .get_request(&endpoint)
+            .await
+            .map_err(|e| BitcoinError::EstimateFee(e.to_string()))?;
+
+        Ok(sat_per_kw_to_sat_per_vb(response.sat_per_kw))
+    }
+
+    async fn block_height(&self) -> Result<u32, BitcoinError> {
+        let response: GetinfoResponse = self
+            .get_request("v1/getinfo")
+            .await
+            .map_err(|e| BitcoinError::BlockHeight(e.to_string()))?;
+
+        response
+            .block_height
+            .ok_or_else(|| BitcoinError::BlockHeight("No block height returned by LND".to_string()))
+    }
+
     fn network(&self) -> BtcNetwork {
         self.network
     }
@@ -26,6 +26,8 @@ pub struct InvoiceRequest {
     pub expiry: u64,
     pub value_msat: u64,
     pub description_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r_preimage: Option<String>,
 }
 
 #[serde_as]
@@ -39,6 +41,8 @@ pub struct AddInvoiceResponse {
 pub struct InvoiceResponse {
     pub payment_request: String,
     pub r_hash: String,
+    #[serde(default)]
+    pub r_preimage: String,
     pub state: String,
     #[serde_as(as = "DisplayFromStr")]
     pub amt_paid_msat: u64,
@@ -126,6 +130,14 @@ pub struct DeleteCanceledInvoiceResponse {}
 #[derive(Debug, Deserialize)]
 pub struct GetinfoResponse {
     pub chains: Option<Vec<Chain>>,
+    pub block_height: Option<u32>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct EstimateFeeResponse {
+    #[serde_as(as = "DisplayFromStr")]
+    pub sat_per_kw: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -168,6 +180,9 @@ impl From<InvoiceResponse> for Invoice {
                 invoice.status = InvoiceStatus::Settled;
                 invoice.payment_time = Some(Utc.timestamp_opt(val.settle_date, 0).unwrap());
                 invoice.amount_received_msat = Some(val.amt_paid_msat);
+                if let Some(ln_invoice) = invoice.ln_invoice.as_mut() {
+                    ln_invoice.payment_preimage = Some(hex_from_base64(&val.r_preimage));
+                }
             }
             "OPEN" | "ACCEPTED" => {
                 invoice.status = InvoiceStatus::Pending;
@@ -189,6 +204,7 @@ impl From<InvoiceResponse> for LnInvoicePaidEvent {
             amount_received_msat: val.amt_paid_msat,
             fee_msat: 0,
             payment_time: Utc.timestamp_opt(val.settle_date, 0).unwrap(),
+            payment_preimage: (!val.r_preimage.is_empty()).then(|| hex_from_base64(&val.r_preimage)),
         }
     }
 }
@@ -237,6 +253,8 @@ pub struct FinalizePsbtRequest {
 #[serde_as]
 #[derive(Debug, Deserialize)]
 pub struct FinalizePsbtResponse {
+    #[serde(default)]
+    pub signed_psbt: String,
     pub raw_final_tx: String,
 }
 
@@ -252,6 +270,42 @@ pub struct PublishTransactionResponse {
     pub publish_error: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ListUnspentRequest {
+    pub min_confs: i32,
+    pub max_confs: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListUnspentResponse {
+    #[serde(default)]
+    pub utxos: Vec<UnspentOutput>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct UnspentOutput {
+    pub address: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount_sat: u64,
+    pub outpoint: OutPoint,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct LeaseOutputRequest {
+    pub id: String,
+    pub outpoint: OutPoint,
+    #[serde_as(as = "DisplayFromStr")]
+    pub expiration_seconds: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct LeaseOutputResponse {
+    pub expiration: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ReleaseOutputRequest {
     pub id: String,
@@ -264,6 +318,16 @@ pub struct ReleaseOutputResponse {
     pub status: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SharedKeyRequest {
+    pub ephemeral_pubkey: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SharedKeyResponse {
+    pub shared_key: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UtxoLease {
     pub id: String,
@@ -44,6 +44,7 @@ pub(crate) fn invoice_from_bolt11(val: Bolt11Invoice) -> Invoice {
             min_final_cltv_expiry_delta: val.min_final_cltv_expiry_delta(),
             expiry: val.expiry_time(),
             expires_at: timestamp + val.expiry_time(),
+            payment_preimage: None,
         }),
         ..Default::default()
     }
@@ -1,7 +1,9 @@
 pub mod app;
 pub mod axum;
+pub mod bitcoind;
 pub mod config;
 pub mod database;
+pub mod dns;
 pub mod jwt;
 pub mod lightning;
 pub mod logging;
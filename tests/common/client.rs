@@ -13,6 +13,8 @@ pub enum Auth<'a> {
     /// bytes), and the middleware base64-decodes the `api-key` header, so it is
     /// sent verbatim rather than re-encoded.
     ApiKey(&'a str),
+    /// NIP-98 event, base64 encoded, sent as `Authorization: Nostr <event>`.
+    Nostr(&'a str),
 }
 
 /// A decoded HTTP response: status plus the parsed JSON body (`Null` when the
@@ -68,6 +70,7 @@ impl ApiClient {
             Auth::None => req,
             Auth::Bearer(token) => req.bearer_auth(token),
             Auth::ApiKey(key) => req.header("api-key", key),
+            Auth::Nostr(event) => req.header(reqwest::header::AUTHORIZATION, format!("Nostr {event}")),
         };
         if let Some(body) = body {
             req = req.json(&body);
@@ -83,6 +86,16 @@ impl ApiClient {
         self.request(Method::POST, path, auth, Some(to_value(body))).await
     }
 
+    /// POST a `text/plain` body, for endpoints that do not speak JSON (e.g. BIP78 payjoin).
+    pub async fn post_text(&self, path: &str, body: &str) -> TestResponse {
+        let req = self
+            .http
+            .post(self.url(path))
+            .header(reqwest::header::CONTENT_TYPE, "text/plain")
+            .body(body.to_string());
+        decode(req.send().await.expect("HTTP request should complete")).await
+    }
+
     pub async fn put(&self, path: &str, auth: Auth<'_>, body: impl Serialize) -> TestResponse {
         self.request(Method::PUT, path, auth, Some(to_value(body))).await
     }
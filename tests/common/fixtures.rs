@@ -17,6 +17,9 @@ static COUNTER: AtomicU64 = AtomicU64::new(0);
 const MAINNET_BTC_ASSET_ID: &str = "00000000-0000-4000-8000-000000000001";
 const REGTEST_BTC_ASSET_ID: &str = "00000000-0000-4000-8000-000000000004";
 const SIGNET_BTC_ASSET_ID: &str = "00000000-0000-4000-8000-000000000006";
+// Ed25519 key of RFC 8032 test vector 1.
+const PASSKEY_SEED: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const PASSKEY_PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
 
 /// A process-unique, lowercase identifier with the given prefix, so tests
 /// sharing one instance never collide on identity subjects, usernames, etc.
@@ -132,6 +135,7 @@ impl TestApp {
                     permissions,
                     description: None,
                     expiry: None,
+                    wallet_ids: None,
                 },
             )
             .await;
@@ -156,6 +160,7 @@ impl TestApp {
                     permissions,
                     description: None,
                     expiry: None,
+                    wallet_ids: None,
                 },
             )
             .await;
@@ -211,3 +216,136 @@ pub fn signet_btc_asset_id() -> Uuid {
 fn seeded_asset_id(value: &str) -> Uuid {
     Uuid::parse_str(value).expect("seeded BTC asset ID is valid")
 }
+
+/// The TOTP code of the base32 `secret`, `step_offset` 30 second steps from now. Each step is only accepted
+/// once by the server, so tests signing in again after a verification use the code of the next step.
+pub fn totp_code(secret: &str, step_offset: i64) -> String {
+    use bitcoin::hashes::{hmac, sha1, Hash, HashEngine};
+
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut key = Vec::new();
+    let (mut buffer, mut bits) = (0u64, 0);
+    for c in secret.bytes() {
+        let value = ALPHABET.iter().position(|a| *a == c).expect("base32 TOTP secret") as u64;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            key.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock after epoch")
+        .as_secs() as i64;
+    let counter = (now / 30 + step_offset) as u64;
+
+    let mut engine = hmac::HmacEngine::<sha1::Hash>::new(&key);
+    engine.input(&counter.to_be_bytes());
+    let digest = hmac::Hmac::<sha1::Hash>::from_engine(engine).to_byte_array();
+    let offset = (digest[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes(digest[offset..offset + 4].try_into().unwrap()) & 0x7fff_ffff;
+
+    format!("{:06}", binary % 1_000_000)
+}
+
+/// Software WebAuthn authenticator with an Ed25519 key, performing the ceremonies of
+/// a passkey bound to `rp_id` and `origin`.
+pub struct SoftPasskey {
+    pub credential_id: Vec<u8>,
+    rp_id: String,
+    origin: String,
+    sign_count: u32,
+}
+
+impl SoftPasskey {
+    pub fn new(rp_id: &str, origin: &str) -> Self {
+        Self {
+            credential_id: Uuid::new_v4().as_bytes().to_vec(),
+            rp_id: rp_id.to_string(),
+            origin: origin.to_string(),
+            sign_count: 0,
+        }
+    }
+
+    fn encode(bytes: &[u8]) -> String {
+        use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+
+        BASE64_URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn client_data(&self, ceremony_type: &str, challenge: &str) -> Vec<u8> {
+        serde_json::json!({ "type": ceremony_type, "challenge": challenge, "origin": self.origin })
+            .to_string()
+            .into_bytes()
+    }
+
+    fn authenticator_data(&self, flags: u8, attested_credential_data: &[u8]) -> Vec<u8> {
+        use bitcoin::hashes::{sha256, Hash};
+
+        let mut data = sha256::Hash::hash(self.rp_id.as_bytes()).to_byte_array().to_vec();
+        data.push(flags);
+        data.extend(self.sign_count.to_be_bytes());
+        data.extend(attested_credential_data);
+        data
+    }
+
+    /// The `credential` of a passkey registration answering `challenge`, with a `none` attestation.
+    pub fn register(&self, challenge: &str) -> serde_json::Value {
+        // COSE key {1: 1, 3: -8, -1: 6, -2: x} after the AAGUID and credential ID.
+        let mut attested_credential_data = vec![0u8; 16];
+        attested_credential_data.extend((self.credential_id.len() as u16).to_be_bytes());
+        attested_credential_data.extend(&self.credential_id);
+        attested_credential_data.extend([0xa4, 0x01, 0x01, 0x03, 0x27, 0x20, 0x06, 0x21, 0x58, 0x20]);
+        attested_credential_data.extend(hex::decode(PASSKEY_PUBLIC_KEY).unwrap());
+        let auth_data = self.authenticator_data(0x45, &attested_credential_data);
+
+        // {"fmt": "none", "attStmt": {}, "authData": auth_data}
+        let mut attestation_object = vec![0xa3, 0x63];
+        attestation_object.extend(b"fmt");
+        attestation_object.push(0x64);
+        attestation_object.extend(b"none");
+        attestation_object.push(0x67);
+        attestation_object.extend(b"attStmt");
+        attestation_object.push(0xa0);
+        attestation_object.push(0x68);
+        attestation_object.extend(b"authData");
+        attestation_object.extend([0x59, (auth_data.len() >> 8) as u8, auth_data.len() as u8]);
+        attestation_object.extend(auth_data);
+
+        serde_json::json!({
+            "id": Self::encode(&self.credential_id),
+            "response": {
+                "clientDataJSON": Self::encode(&self.client_data("webauthn.create", challenge)),
+                "attestationObject": Self::encode(&attestation_object),
+            },
+        })
+    }
+
+    /// The `credential` of a passkey sign-in answering `challenge`, incrementing the signature counter.
+    pub fn assert(&mut self, challenge: &str) -> serde_json::Value {
+        use bitcoin::hashes::{sha256, Hash};
+        use jsonwebtoken::{crypto, Algorithm, EncodingKey};
+
+        self.sign_count += 1;
+        let client_data = self.client_data("webauthn.get", challenge);
+        let auth_data = self.authenticator_data(0x05, &[]);
+        let mut message = auth_data.clone();
+        message.extend(sha256::Hash::hash(&client_data).to_byte_array());
+
+        let mut pkcs8 = hex::decode("302e020100300506032b657004220420").unwrap();
+        pkcs8.extend(hex::decode(PASSKEY_SEED).unwrap());
+        let signature = crypto::sign(&message, &EncodingKey::from_ed_der(&pkcs8), Algorithm::EdDSA).unwrap();
+
+        serde_json::json!({
+            "id": Self::encode(&self.credential_id),
+            "response": {
+                "clientDataJSON": Self::encode(&client_data),
+                "authenticatorData": Self::encode(&auth_data),
+                "signature": signature,
+            },
+        })
+    }
+}
@@ -176,7 +176,7 @@ impl TestApp {
 
 /// Sign up the first admin (all permissions); fall back to sign-in if a prior
 /// run already created it (e.g. a reused database).
-async fn bootstrap_admin(api: &ApiClient) -> String {
+pub async fn bootstrap_admin(api: &ApiClient) -> String {
     let signup = api
         .post("/v1/auth/sign-up", Auth::None, json!({ "password": ADMIN_PASSWORD }))
         .await;
@@ -9,6 +9,8 @@
 //! selected per request `client_id`, shaped by
 //! `tests/itest/config/mock-oauth2/config.json`.
 
+use std::{fs, path::Path};
+
 use reqwest::Client;
 use serde_json::Value;
 use tokio::sync::OnceCell;
@@ -29,6 +31,16 @@ pub const CLIENT_READONLY: &str = "itest-readonly"; // read:wallet only
 pub const CLIENT_CONCURRENT: &str = "itest-concurrent"; // fresh provisioning subject
 pub const CLIENT_WRONG_AUD: &str = "itest-wrong-aud"; // mismatched audience
 
+/// Issuers trusted by the multi-issuer instance, by their issuer id at the IdP.
+pub const ISSUER_CORPORATE: &str = "corporate"; // staff, permissions mapped from `groups`
+pub const ISSUER_CONSUMER: &str = "consumer"; // customers, default permissions only
+pub const ISSUER_PARTNER: &str = "partner"; // existing accounts only
+
+/// `client_id`s of the multi-issuer claim sets, one per issuer.
+pub const CLIENT_STAFF: &str = "itest-staff";
+pub const CLIENT_CUSTOMER: &str = "itest-customer"; // same `sub` as the staff
+pub const CLIENT_PARTNER: &str = "itest-partner";
+
 /// Host:port at which both the binary and the tests reach the IdP, so the
 /// discovered issuer and the token `iss` agree. Maps to the compose service's
 /// published port (see `docker-compose.yml`); overridable for non-default stacks.
@@ -44,6 +56,14 @@ pub async fn oauth2_app() -> &'static OAuth2App {
     OAUTH2_APP.get_or_init(OAuth2App::start).await
 }
 
+static MULTI_ISSUER_APP: OnceCell<OAuth2App> = OnceCell::const_new();
+
+/// The process-wide shared instance with local accounts and the corporate,
+/// consumer and partner issuers, spawned on first use.
+pub async fn multi_issuer_app() -> &'static OAuth2App {
+    MULTI_ISSUER_APP.get_or_init(OAuth2App::start_multi_issuer).await
+}
+
 pub struct OAuth2App {
     pub base_url: String,
     idp_base: String,
@@ -78,6 +98,59 @@ impl OAuth2App {
         }
     }
 
+    /// Local accounts (the default `auth_provider = jwt`) alongside three
+    /// `[[oauth2_issuers]]`. Arrays of tables cannot be set through env vars, so
+    /// they are appended to a copy of `config/itest.toml` selected as run mode.
+    async fn start_multi_issuer() -> OAuth2App {
+        let (database, provider) = matrix_cell();
+        let idp_base = idp_base();
+        let label = format!("{database}-{provider}-oauth2-issuers");
+
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let itest_config = fs::read_to_string(root.join("config/itest.toml")).expect("read config/itest.toml");
+        let issuer = |name: &str, settings: &str| {
+            format!(
+                r#"
+[[oauth2_issuers]]
+name = "{name}"
+domain = "{idp_base}/{name}"
+audience = "{AUDIENCE}"
+jwks_refresh_interval = "1h"
+leeway = "60s"
+{settings}
+"#
+            )
+        };
+        let config = [
+            itest_config,
+            issuer(
+                ISSUER_CORPORATE,
+                r#"permissions_claim = "groups"
+permission_mapping = [{ value = "swissknife-treasury", permissions = ["read:account", "read:wallet"] }]"#,
+            ),
+            issuer(ISSUER_CONSUMER, r#"default_permissions = ["read:wallet"]"#),
+            issuer(ISSUER_PARTNER, r#"provisioning = "existing""#),
+        ]
+        .concat();
+        let run_mode = format!("target/itest/{label}");
+        fs::create_dir_all(root.join("target/itest")).expect("create itest artifact dir");
+        fs::write(root.join(format!("{run_mode}.toml")), config).expect("write multi-issuer config");
+
+        let spawned = spawn_instance(
+            &database,
+            &provider,
+            &label,
+            // Run modes are loaded from `config/<run mode>`.
+            &[("RUN_MODE", format!("../{run_mode}"))],
+        )
+        .await;
+
+        OAuth2App {
+            base_url: spawned.base_url,
+            idp_base,
+        }
+    }
+
     /// A fresh HTTP client bound to this instance.
     pub fn api(&self) -> ApiClient {
         ApiClient::new(self.base_url.clone())
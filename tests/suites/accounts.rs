@@ -97,8 +97,12 @@ mod lifecycle {
                 RegisterLnAddressRequest {
                     account_id: Some(created.id),
                     username: unique("deleted-account"),
+                    domain: None,
                     allows_nostr: false,
                     nostr_pubkey: None,
+                    wallet_id: None,
+                    pay_profile: None,
+                    splits: Vec::new(),
                 },
             )
             .await;
@@ -5,9 +5,9 @@
 
 use reqwest::StatusCode;
 
-use swissknife_types::{ApiKey, CreateApiKeyRequest, CreateWalletRequest, Permission};
+use swissknife_types::{ApiKey, CreateApiKeyRequest, CreateWalletRequest, Permission, RotateApiKeyRequest, Wallet};
 
-use crate::common::fixtures::unique;
+use crate::common::fixtures::{mainnet_btc_asset_id, unique};
 use crate::common::{app, assert_error, assert_status, Auth};
 
 fn new_key(account_id: uuid::Uuid, permissions: Vec<Permission>) -> CreateApiKeyRequest {
@@ -17,6 +17,7 @@ fn new_key(account_id: uuid::Uuid, permissions: Vec<Permission>) -> CreateApiKey
         permissions,
         description: None,
         expiry: None,
+        wallet_ids: None,
     }
 }
 
@@ -161,3 +162,247 @@ mod manage {
         assert_error(&res, StatusCode::NOT_FOUND);
     }
 }
+
+mod lifecycle {
+    use super::*;
+
+    #[tokio::test]
+    async fn an_expired_key_is_rejected() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let account_id = app.create_account(token, &unique("account")).await.id;
+
+        let key = app
+            .api()
+            .post(
+                "/v1/api-keys",
+                Auth::Bearer(token),
+                CreateApiKeyRequest {
+                    expiry: Some(1),
+                    ..new_key(account_id, vec![])
+                },
+            )
+            .await
+            .parse::<ApiKey>()
+            .key
+            .expect("a freshly created key returns its secret");
+        assert_status(&app.api().get("/v1/me", Auth::ApiKey(&key)).await, StatusCode::OK);
+
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+        assert_error(
+            &app.api().get("/v1/me", Auth::ApiKey(&key)).await,
+            StatusCode::UNAUTHORIZED,
+        );
+    }
+
+    #[tokio::test]
+    async fn usage_is_recorded() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let account_id = app.create_account(token, &unique("account")).await.id;
+
+        let created = app
+            .api()
+            .post("/v1/api-keys", Auth::Bearer(token), new_key(account_id, vec![]))
+            .await
+            .parse::<ApiKey>();
+        assert!(created.last_used_at.is_none());
+
+        let key = created.key.expect("a freshly created key returns its secret");
+        assert_status(&app.api().get("/v1/me", Auth::ApiKey(&key)).await, StatusCode::OK);
+
+        let used = app
+            .api()
+            .get(&format!("/v1/api-keys/{}", created.id), Auth::Bearer(token))
+            .await
+            .parse::<ApiKey>();
+        assert!(used.last_used_at.is_some(), "usage time is recorded");
+        assert_eq!(used.last_used_ip.as_deref(), Some("127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn rotation_replaces_the_key_and_retires_the_old_one() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let account_id = app.create_account(token, &unique("account")).await.id;
+
+        let created = app
+            .api()
+            .post("/v1/api-keys", Auth::Bearer(token), new_key(account_id, vec![]))
+            .await
+            .parse::<ApiKey>();
+        let old_key = created.key.clone().expect("a freshly created key returns its secret");
+
+        let res = app
+            .api()
+            .post(
+                &format!("/v1/api-keys/{}/rotate", created.id),
+                Auth::Bearer(token),
+                RotateApiKeyRequest::default(),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let replacement = res.parse::<ApiKey>();
+        assert_ne!(replacement.id, created.id);
+        assert_eq!(replacement.name, created.name);
+        assert_eq!(replacement.account_id, account_id);
+
+        let new_key = replacement.key.expect("a replacement returns its secret");
+        assert_status(&app.api().get("/v1/me", Auth::ApiKey(&new_key)).await, StatusCode::OK);
+        assert_error(
+            &app.api().get("/v1/me", Auth::ApiKey(&old_key)).await,
+            StatusCode::UNAUTHORIZED,
+        );
+
+        let again = app
+            .api()
+            .post(
+                &format!("/v1/api-keys/{}/rotate", created.id),
+                Auth::Bearer(token),
+                RotateApiKeyRequest::default(),
+            )
+            .await;
+        assert_error(&again, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn the_rotated_key_works_during_the_grace_period() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let account_id = app.create_account(token, &unique("account")).await.id;
+
+        let created = app
+            .api()
+            .post("/v1/api-keys", Auth::Bearer(token), new_key(account_id, vec![]))
+            .await
+            .parse::<ApiKey>();
+
+        let res = app
+            .api()
+            .post(
+                &format!("/v1/api-keys/{}/rotate", created.id),
+                Auth::Bearer(token),
+                RotateApiKeyRequest {
+                    grace_period: Some(3_600),
+                    expiry: None,
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        let old_key = created.key.expect("a freshly created key returns its secret");
+        assert_status(&app.api().get("/v1/me", Auth::ApiKey(&old_key)).await, StatusCode::OK);
+    }
+}
+
+mod wallet_scope {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_wallet_scoped_key_only_reaches_its_wallets() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let shop = app.create_account_with_wallet(token, &unique("shop")).await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/me/wallets",
+                Auth::ApiKey(&shop.key),
+                CreateWalletRequest {
+                    account_id: None,
+                    asset_id: mainnet_btc_asset_id(),
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let other_wallet = res.parse::<Wallet>();
+
+        let res = app
+            .api()
+            .post(
+                "/v1/api-keys",
+                Auth::Bearer(token),
+                CreateApiKeyRequest {
+                    wallet_ids: Some(vec![shop.wallet.id]),
+                    ..new_key(shop.account.id, vec![])
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let scoped = res.parse::<ApiKey>();
+        assert_eq!(scoped.wallet_ids, Some(vec![shop.wallet.id]));
+        let key = scoped.key.expect("a freshly created key returns its secret");
+
+        let own = app
+            .api()
+            .get(
+                &format!("/v1/me/wallets/{}/balance", shop.wallet.id),
+                Auth::ApiKey(&key),
+            )
+            .await;
+        assert_status(&own, StatusCode::OK);
+
+        let other = app
+            .api()
+            .get(
+                &format!("/v1/me/wallets/{}/balance", other_wallet.id),
+                Auth::ApiKey(&key),
+            )
+            .await;
+        assert_error(&other, StatusCode::FORBIDDEN);
+
+        let wallets = app.api().get("/v1/me/wallets", Auth::ApiKey(&key)).await;
+        assert_status(&wallets, StatusCode::OK);
+        let ids: Vec<_> = wallets.parse::<Vec<Wallet>>().into_iter().map(|w| w.id).collect();
+        assert_eq!(ids, vec![shop.wallet.id]);
+
+        let unscoped = app
+            .api()
+            .post(
+                "/v1/me/api-keys",
+                Auth::ApiKey(&key),
+                CreateApiKeyRequest {
+                    account_id: None,
+                    ..new_key(shop.account.id, vec![])
+                },
+            )
+            .await;
+        assert_error(&unscoped, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn a_wallet_scoped_key_cannot_hold_permissions_or_foreign_wallets() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let shop = app.create_account_with_wallet(token, &unique("shop")).await;
+        let stranger = app.create_account_with_wallet(token, &unique("stranger")).await;
+
+        let with_permissions = app
+            .api()
+            .post(
+                "/v1/api-keys",
+                Auth::Bearer(token),
+                CreateApiKeyRequest {
+                    wallet_ids: Some(vec![shop.wallet.id]),
+                    ..new_key(shop.account.id, vec![Permission::ReadWallet])
+                },
+            )
+            .await;
+        assert_error(&with_permissions, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let foreign = app
+            .api()
+            .post(
+                "/v1/api-keys",
+                Auth::Bearer(token),
+                CreateApiKeyRequest {
+                    wallet_ids: Some(vec![stranger.wallet.id]),
+                    ..new_key(shop.account.id, vec![])
+                },
+            )
+            .await;
+        assert_error(&foreign, StatusCode::NOT_FOUND);
+    }
+}
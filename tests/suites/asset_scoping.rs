@@ -110,6 +110,8 @@ async fn keeps_balances_resources_and_aggregates_separate_across_bitcoin_network
                 input: invoice.ln_invoice.expect("Lightning invoice").bolt11,
                 amount_msat: None,
                 comment: None,
+                conf_target: None,
+                feerate_sat_vb: None,
             },
         )
         .await;
@@ -0,0 +1,114 @@
+//! `/v1/audit` — every state-changing request leaves an entry naming its actor,
+//! target resource and status, with the state it changed before and after.
+
+use reqwest::StatusCode;
+
+use swissknife_types::{AuditEntry, SignInRequest, UpdateAccountRequest};
+
+use crate::common::fixtures::unique;
+use crate::common::{app, assert_status, Auth};
+
+#[tokio::test]
+async fn records_changes_with_their_actor_and_target() {
+    let app = app().await;
+    let admin = app.admin_token().await;
+    let account = app.create_account(admin, &unique("audited")).await;
+
+    let updated = app
+        .api()
+        .put(
+            &format!("/v1/accounts/{}", account.id),
+            Auth::Bearer(admin),
+            UpdateAccountRequest {
+                display_name: Some("Audited account".to_string()),
+            },
+        )
+        .await;
+    assert_status(&updated, StatusCode::OK);
+
+    let res = app
+        .api()
+        .get(&format!("/v1/audit?resource_id={}", account.id), Auth::Bearer(admin))
+        .await;
+    assert_status(&res, StatusCode::OK);
+    let entries = res.parse::<Vec<AuditEntry>>();
+    let entry = entries
+        .iter()
+        .find(|entry| entry.method == "PUT")
+        .expect("the update is audited");
+    assert_eq!(entry.path, format!("/v1/accounts/{}", account.id));
+    assert_eq!(entry.status, 200);
+    assert!(entry.account_id.is_some(), "the actor is recorded");
+    let diff = entry.diff.as_ref().expect("the change is recorded");
+    assert_eq!(
+        diff.before.as_ref().and_then(|before| before["id"].as_str()),
+        Some(account.id.to_string().as_str())
+    );
+    assert!(diff
+        .before
+        .as_ref()
+        .is_some_and(|before| before["display_name"] != "Audited account"));
+    assert_eq!(
+        diff.after.as_ref().and_then(|after| after["display_name"].as_str()),
+        Some("Audited account")
+    );
+}
+
+#[tokio::test]
+async fn records_sign_ins_without_their_password() {
+    let app = app().await;
+    let admin = app.admin_token().await;
+    let password = unique("wrong-password");
+
+    let res = app
+        .api()
+        .post(
+            "/v1/auth/sign-in",
+            Auth::None,
+            SignInRequest {
+                login: None,
+                password: password.clone(),
+                otp: None,
+            },
+        )
+        .await;
+    assert_status(&res, StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .api()
+        .get("/v1/audit?path=/v1/auth/sign-in&limit=1000", Auth::Bearer(admin))
+        .await;
+    assert_status(&res, StatusCode::OK);
+    let entries = res.parse::<Vec<AuditEntry>>();
+    assert!(entries
+        .iter()
+        .any(|entry| entry.status == 401 && entry.account_id.is_none()));
+    assert!(
+        !res.body.to_string().contains(&password),
+        "passwords are never recorded"
+    );
+}
+
+#[tokio::test]
+async fn does_not_record_reads() {
+    let app = app().await;
+    let admin = app.admin_token().await;
+    let account = app.create_account(admin, &unique("read-only")).await;
+
+    let fetched = app
+        .api()
+        .get(&format!("/v1/accounts/{}", account.id), Auth::Bearer(admin))
+        .await;
+    assert_status(&fetched, StatusCode::OK);
+
+    let res = app
+        .api()
+        .get(&format!("/v1/audit?resource_id={}", account.id), Auth::Bearer(admin))
+        .await;
+    assert_status(&res, StatusCode::OK);
+    let entries = res.parse::<Vec<AuditEntry>>();
+    assert!(
+        entries.iter().all(|entry| entry.method == "POST"),
+        "only the creation of the account is audited"
+    );
+}
@@ -3,9 +3,15 @@
 use futures_util::future::join_all;
 use reqwest::StatusCode;
 
-use swissknife_types::{ChangePasswordRequest, SignInRequest, SignInResponse, SignUpRequest};
+use swissknife_types::{
+    Account, ApiKey, ChangePasswordRequest, CreateApiKeyRequest, CreateInvitationRequest, CreatePasswordResetRequest,
+    Invitation, Passkey, PasskeyAuthenticationOptions, PasskeyRegistrationOptions, PasswordReset, Permission,
+    RefreshTokenRequest, RegisterRequest, ResetPasswordRequest, SignInRequest, SignInResponse, SignUpRequest,
+    TotpCodeRequest, TotpEnrollment, TotpRecoveryCodes,
+};
 
 use crate::common::client::ApiClient;
+use crate::common::fixtures::{totp_code, unique, SoftPasskey};
 use crate::common::harness::{matrix_cell, spawn_instance, ADMIN_PASSWORD};
 use crate::common::{app, assert_error, assert_status, Auth};
 
@@ -22,7 +28,9 @@ mod sign_in {
                 "/v1/auth/sign-in",
                 Auth::None,
                 SignInRequest {
+                    login: None,
                     password: ADMIN_PASSWORD.to_string(),
+                    otp: None,
                 },
             )
             .await;
@@ -40,7 +48,9 @@ mod sign_in {
                 "/v1/auth/sign-in",
                 Auth::None,
                 SignInRequest {
+                    login: None,
                     password: "wrong-password".to_string(),
+                    otp: None,
                 },
             )
             .await;
@@ -166,13 +176,19 @@ mod change_password {
             )
             .await;
         assert_status(&res, StatusCode::NO_CONTENT);
+        assert_error(
+            &api.get("/v1/me", Auth::Bearer(&admin_token)).await,
+            StatusCode::UNAUTHORIZED,
+        );
 
         let old_password = api
             .post(
                 "/v1/auth/sign-in",
                 Auth::None,
                 SignInRequest {
+                    login: None,
                     password: ADMIN_PASSWORD.to_string(),
+                    otp: None,
                 },
             )
             .await;
@@ -183,7 +199,9 @@ mod change_password {
                 "/v1/auth/sign-in",
                 Auth::None,
                 SignInRequest {
+                    login: None,
                     password: new_password.to_string(),
+                    otp: None,
                 },
             )
             .await;
@@ -242,3 +260,640 @@ mod protected_routes {
         assert_status(&res, StatusCode::OK);
     }
 }
+
+mod local_accounts {
+    use super::*;
+
+    const PASSWORD: &str = "local-account-password";
+
+    async fn invite(api: &ApiClient, token: &str, permissions: Vec<Permission>) -> String {
+        let res = api
+            .post(
+                "/v1/auth/invitations",
+                Auth::Bearer(token),
+                CreateInvitationRequest {
+                    email: None,
+                    permissions,
+                    expiry: None,
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        res.parse::<Invitation>().token.expect("invitation token")
+    }
+
+    fn registration(username: &str, invitation: Option<String>) -> RegisterRequest {
+        RegisterRequest {
+            username: username.to_string(),
+            email: Some(format!("{username}@example.com")),
+            password: PASSWORD.to_string(),
+            display_name: None,
+            invitation,
+        }
+    }
+
+    async fn sign_in(api: &ApiClient, login: &str, password: &str) -> crate::common::client::TestResponse {
+        api.post(
+            "/v1/auth/sign-in",
+            Auth::None,
+            SignInRequest {
+                login: Some(login.to_string()),
+                password: password.to_string(),
+                otp: None,
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn an_invited_user_signs_in_with_username_or_email_and_the_granted_permissions() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let username = unique("invited");
+        let invitation = invite(&app.api(), admin, vec![Permission::ReadAccount]).await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&username, Some(invitation)),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        let by_email = sign_in(&app.api(), &format!("{username}@example.com"), PASSWORD).await;
+        assert_status(&by_email, StatusCode::OK);
+
+        let by_username = sign_in(&app.api(), &username, PASSWORD).await;
+        assert_status(&by_username, StatusCode::OK);
+        let token = by_username.parse::<SignInResponse>().token;
+
+        let me = app.api().get("/v1/me", Auth::Bearer(&token)).await;
+        assert_status(&me, StatusCode::OK);
+        assert_eq!(me.parse::<Account>().permissions, Some(vec![Permission::ReadAccount]));
+
+        let accounts = app.api().get("/v1/accounts", Auth::Bearer(&token)).await;
+        assert_status(&accounts, StatusCode::OK);
+        let escalation = app
+            .api()
+            .post(
+                "/v1/auth/invitations",
+                Auth::Bearer(&token),
+                CreateInvitationRequest {
+                    email: None,
+                    permissions: vec![Permission::WriteAccount],
+                    expiry: None,
+                },
+            )
+            .await;
+        assert_error(&escalation, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn an_invitation_can_only_be_used_once() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let invitation = invite(&app.api(), admin, vec![]).await;
+
+        let first = app
+            .api()
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&unique("first"), Some(invitation.clone())),
+            )
+            .await;
+        assert_status(&first, StatusCode::OK);
+
+        let second = app
+            .api()
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&unique("second"), Some(invitation)),
+            )
+            .await;
+        assert_error(&second, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn registration_without_an_invitation_is_closed_by_default() {
+        let app = app().await;
+        let res = app
+            .api()
+            .post("/v1/auth/register", Auth::None, registration(&unique("closed"), None))
+            .await;
+        assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn self_registered_accounts_have_no_permissions() {
+        let (database, provider) = matrix_cell();
+        let label = format!("{database}-{provider}-auth-self-registration");
+        let spawned = spawn_instance(
+            &database,
+            &provider,
+            &label,
+            &[("SWISSKNIFE_LOCAL_AUTH__SELF_REGISTRATION", "true".to_string())],
+        )
+        .await;
+        let api = ApiClient::new(spawned.base_url);
+
+        let res = api
+            .post("/v1/auth/register", Auth::None, registration("newcomer", None))
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let token = res.parse::<SignInResponse>().token;
+
+        let me = api.get("/v1/me", Auth::Bearer(&token)).await;
+        assert_status(&me, StatusCode::OK);
+        assert_eq!(me.parse::<Account>().permissions, Some(vec![]));
+
+        let duplicate = api
+            .post("/v1/auth/register", Auth::None, registration("newcomer", None))
+            .await;
+        assert_error(&duplicate, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn a_password_reset_token_sets_a_new_password_once() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let username = unique("forgetful");
+        let invitation = invite(&app.api(), admin, vec![]).await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&username, Some(invitation)),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let token = res.parse::<SignInResponse>().token;
+        let account_id = app
+            .api()
+            .get("/v1/me", Auth::Bearer(&token))
+            .await
+            .parse::<Account>()
+            .id;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/password-resets",
+                Auth::Bearer(admin),
+                CreatePasswordResetRequest { account_id },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let reset = res.parse::<PasswordReset>();
+
+        let reset_request = ResetPasswordRequest {
+            token: reset.token,
+            new_password: "a-brand-new-password".to_string(),
+        };
+        let res = app
+            .api()
+            .post("/v1/auth/reset-password", Auth::None, &reset_request)
+            .await;
+        assert_status(&res, StatusCode::NO_CONTENT);
+        assert_error(
+            &app.api().get("/v1/me", Auth::Bearer(&token)).await,
+            StatusCode::UNAUTHORIZED,
+        );
+
+        let reused = app
+            .api()
+            .post("/v1/auth/reset-password", Auth::None, &reset_request)
+            .await;
+        assert_error(&reused, StatusCode::UNPROCESSABLE_ENTITY);
+
+        assert_error(
+            &sign_in(&app.api(), &username, PASSWORD).await,
+            StatusCode::UNAUTHORIZED,
+        );
+        assert_status(
+            &sign_in(&app.api(), &username, "a-brand-new-password").await,
+            StatusCode::OK,
+        );
+    }
+
+    async fn register_invited(api: &ApiClient, admin: &str) -> SignInResponse {
+        let invitation = invite(api, admin, vec![]).await;
+        let res = api
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&unique("session"), Some(invitation)),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        res.parse::<SignInResponse>()
+    }
+
+    async fn refresh(api: &ApiClient, refresh_token: &str) -> crate::common::client::TestResponse {
+        api.post(
+            "/v1/auth/refresh",
+            Auth::None,
+            RefreshTokenRequest {
+                refresh_token: refresh_token.to_string(),
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn a_refresh_token_is_exchanged_once_for_new_tokens() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let session = register_invited(&app.api(), admin).await;
+
+        let res = refresh(&app.api(), &session.refresh_token).await;
+        assert_status(&res, StatusCode::OK);
+        let renewed = res.parse::<SignInResponse>();
+        assert_ne!(renewed.refresh_token, session.refresh_token);
+        assert_status(
+            &app.api().get("/v1/me", Auth::Bearer(&renewed.token)).await,
+            StatusCode::OK,
+        );
+
+        assert_error(
+            &refresh(&app.api(), &session.refresh_token).await,
+            StatusCode::UNAUTHORIZED,
+        );
+        assert_status(&refresh(&app.api(), &renewed.refresh_token).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn signing_out_revokes_the_tokens_of_the_session_only() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let username = unique("signout");
+        let invitation = invite(&app.api(), admin, vec![]).await;
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&username, Some(invitation)),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let first = res.parse::<SignInResponse>();
+        let second = sign_in(&app.api(), &username, PASSWORD).await.parse::<SignInResponse>();
+
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/sign-out",
+                Auth::None,
+                RefreshTokenRequest {
+                    refresh_token: first.refresh_token.clone(),
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::NO_CONTENT);
+
+        assert_error(
+            &app.api().get("/v1/me", Auth::Bearer(&first.token)).await,
+            StatusCode::UNAUTHORIZED,
+        );
+        assert_error(
+            &refresh(&app.api(), &first.refresh_token).await,
+            StatusCode::UNAUTHORIZED,
+        );
+        assert_status(
+            &app.api().get("/v1/me", Auth::Bearer(&second.token)).await,
+            StatusCode::OK,
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_out_of_all_sessions_revokes_every_token_of_the_account() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let username = unique("signoutall");
+        let invitation = invite(&app.api(), admin, vec![]).await;
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                registration(&username, Some(invitation)),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let first = res.parse::<SignInResponse>();
+        let second = sign_in(&app.api(), &username, PASSWORD).await.parse::<SignInResponse>();
+
+        let res = app
+            .api()
+            .post(
+                "/v1/auth/sign-out-all",
+                Auth::Bearer(&first.token),
+                serde_json::json!({}),
+            )
+            .await;
+        assert_status(&res, StatusCode::NO_CONTENT);
+
+        for session in [&first, &second] {
+            assert_error(
+                &app.api().get("/v1/me", Auth::Bearer(&session.token)).await,
+                StatusCode::UNAUTHORIZED,
+            );
+            assert_error(
+                &refresh(&app.api(), &session.refresh_token).await,
+                StatusCode::UNAUTHORIZED,
+            );
+        }
+        assert_status(&sign_in(&app.api(), &username, PASSWORD).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn deleting_the_account_revokes_its_tokens() {
+        let app = app().await;
+        let admin = app.admin_token().await;
+        let session = register_invited(&app.api(), admin).await;
+        let account_id = app
+            .api()
+            .get("/v1/me", Auth::Bearer(&session.token))
+            .await
+            .parse::<Account>()
+            .id;
+
+        let res = app
+            .api()
+            .delete(&format!("/v1/accounts/{account_id}"), Auth::Bearer(admin))
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        assert_error(
+            &app.api().get("/v1/me", Auth::Bearer(&session.token)).await,
+            StatusCode::UNAUTHORIZED,
+        );
+        assert_error(
+            &refresh(&app.api(), &session.refresh_token).await,
+            StatusCode::UNAUTHORIZED,
+        );
+    }
+}
+
+mod two_factor {
+    use std::time::Duration;
+
+    use super::*;
+
+    const PASSWORD: &str = "two-factor-password";
+
+    fn sign_in_request(username: &str, otp: Option<String>) -> SignInRequest {
+        SignInRequest {
+            login: Some(username.to_string()),
+            password: PASSWORD.to_string(),
+            otp,
+        }
+    }
+
+    fn api_key_request() -> CreateApiKeyRequest {
+        CreateApiKeyRequest {
+            account_id: None,
+            name: unique("key"),
+            permissions: vec![],
+            description: None,
+            expiry: None,
+            wallet_ids: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_confirmed_factor_is_required_at_sign_in_and_for_sensitive_operations() {
+        let (database, provider) = matrix_cell();
+        let label = format!("{database}-{provider}-auth-two-factor");
+        let spawned = spawn_instance(
+            &database,
+            &provider,
+            &label,
+            &[
+                ("SWISSKNIFE_LOCAL_AUTH__SELF_REGISTRATION", "true".to_string()),
+                ("SWISSKNIFE_LOCAL_AUTH__STEP_UP_WINDOW", "2s".to_string()),
+            ],
+        )
+        .await;
+        let api = ApiClient::new(spawned.base_url);
+
+        let res = api
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                RegisterRequest {
+                    username: "second-factor".to_string(),
+                    email: None,
+                    password: PASSWORD.to_string(),
+                    display_name: None,
+                    invitation: None,
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let token = res.parse::<SignInResponse>().token;
+
+        let res = api
+            .post("/v1/auth/totp", Auth::Bearer(&token), serde_json::json!({}))
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let enrollment = res.parse::<TotpEnrollment>();
+        assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"), "{}", res.body);
+
+        let wrong_code = api
+            .post(
+                "/v1/auth/totp/confirm",
+                Auth::Bearer(&token),
+                TotpCodeRequest {
+                    code: "000000".to_string(),
+                },
+            )
+            .await;
+        assert_error(&wrong_code, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let res = api
+            .post(
+                "/v1/auth/totp/confirm",
+                Auth::Bearer(&token),
+                TotpCodeRequest {
+                    code: totp_code(&enrollment.secret, 0),
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let recovery_codes = res.parse::<TotpRecoveryCodes>().recovery_codes;
+        assert_eq!(recovery_codes.len(), 10);
+
+        let without_code = api
+            .post("/v1/auth/sign-in", Auth::None, sign_in_request("second-factor", None))
+            .await;
+        assert_error(&without_code, StatusCode::UNAUTHORIZED);
+        let res = api
+            .post(
+                "/v1/auth/sign-in",
+                Auth::None,
+                sign_in_request("second-factor", Some(totp_code(&enrollment.secret, 1))),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let token = res.parse::<SignInResponse>().token;
+
+        let res = api
+            .post("/v1/me/api-keys", Auth::Bearer(&token), api_key_request())
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let api_key = res.parse::<ApiKey>().key.expect("API key secret");
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        let res = api
+            .post("/v1/me/api-keys", Auth::Bearer(&token), api_key_request())
+            .await;
+        assert_error(&res, StatusCode::FORBIDDEN);
+        let res = api
+            .post("/v1/me/api-keys", Auth::ApiKey(&api_key), api_key_request())
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        let step_up = TotpCodeRequest {
+            code: recovery_codes[0].clone(),
+        };
+        let res = api.post("/v1/auth/step-up", Auth::Bearer(&token), &step_up).await;
+        assert_status(&res, StatusCode::NO_CONTENT);
+        let res = api
+            .post("/v1/me/api-keys", Auth::Bearer(&token), api_key_request())
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let reused = api.post("/v1/auth/step-up", Auth::Bearer(&token), &step_up).await;
+        assert_error(&reused, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let res = api
+            .post(
+                "/v1/auth/totp/disable",
+                Auth::Bearer(&token),
+                TotpCodeRequest {
+                    code: recovery_codes[1].clone(),
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::NO_CONTENT);
+        let res = api
+            .post("/v1/auth/sign-in", Auth::None, sign_in_request("second-factor", None))
+            .await;
+        assert_status(&res, StatusCode::OK);
+    }
+}
+
+mod passkeys {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_registered_passkey_signs_in_until_deleted() {
+        let (database, provider) = matrix_cell();
+        let label = format!("{database}-{provider}-auth-passkeys");
+        let spawned = spawn_instance(
+            &database,
+            &provider,
+            &label,
+            &[("SWISSKNIFE_LOCAL_AUTH__SELF_REGISTRATION", "true".to_string())],
+        )
+        .await;
+        let api = ApiClient::new(spawned.base_url.clone());
+
+        let res = api
+            .post(
+                "/v1/auth/register",
+                Auth::None,
+                RegisterRequest {
+                    username: "passkey-owner".to_string(),
+                    email: None,
+                    password: "passkey-password".to_string(),
+                    display_name: None,
+                    invitation: None,
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let token = res.parse::<SignInResponse>().token;
+
+        let res = api
+            .post(
+                "/v1/auth/passkeys/registration-options",
+                Auth::Bearer(&token),
+                serde_json::json!({}),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let options = res.parse::<PasskeyRegistrationOptions>();
+        assert_eq!(options.public_key.user.name, "passkey-owner");
+        let mut authenticator = SoftPasskey::new(&options.public_key.rp.id, &spawned.base_url);
+
+        let registration = serde_json::json!({
+            "ceremony_id": options.ceremony_id,
+            "name": "Soft authenticator",
+            "credential": authenticator.register(&options.public_key.challenge),
+        });
+        let res = api.post("/v1/auth/passkeys", Auth::Bearer(&token), &registration).await;
+        assert_status(&res, StatusCode::OK);
+        let passkey = res.parse::<Passkey>();
+        assert_eq!(passkey.name, "Soft authenticator");
+        let replayed = api.post("/v1/auth/passkeys", Auth::Bearer(&token), &registration).await;
+        assert_error(&replayed, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let res = api
+            .post(
+                "/v1/auth/passkeys/authentication-options",
+                Auth::None,
+                serde_json::json!({}),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let options = res.parse::<PasskeyAuthenticationOptions>();
+        assert!(options.public_key.allow_credentials.is_empty());
+        let sign_in = serde_json::json!({
+            "ceremony_id": options.ceremony_id,
+            "credential": authenticator.assert(&options.public_key.challenge),
+        });
+        let res = api.post("/v1/auth/passkeys/sign-in", Auth::None, &sign_in).await;
+        assert_status(&res, StatusCode::OK);
+        let passkey_token = res.parse::<SignInResponse>().token;
+        let replayed = api.post("/v1/auth/passkeys/sign-in", Auth::None, &sign_in).await;
+        assert_error(&replayed, StatusCode::UNAUTHORIZED);
+
+        let res = api.get("/v1/auth/passkeys", Auth::Bearer(&passkey_token)).await;
+        assert_status(&res, StatusCode::OK);
+        let passkeys = res.parse::<Vec<Passkey>>();
+        assert_eq!(passkeys.len(), 1);
+        assert!(passkeys[0].last_used_at.is_some());
+
+        let res = api
+            .delete(&format!("/v1/auth/passkeys/{}", passkey.id), Auth::Bearer(&token))
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        let res = api
+            .post(
+                "/v1/auth/passkeys/authentication-options",
+                Auth::None,
+                serde_json::json!({}),
+            )
+            .await;
+        let options = res.parse::<PasskeyAuthenticationOptions>();
+        let res = api
+            .post(
+                "/v1/auth/passkeys/sign-in",
+                Auth::None,
+                serde_json::json!({
+                    "ceremony_id": options.ceremony_id,
+                    "credential": authenticator.assert(&options.public_key.challenge),
+                }),
+            )
+            .await;
+        assert_error(&res, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -268,6 +268,8 @@ mod withdraw {
                     input: target.clone(),
                     amount_msat: Some(amount_msat),
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -341,6 +343,8 @@ mod withdraw {
                     input: payee_addr,
                     amount_msat: Some(amount_msat),
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -385,6 +389,8 @@ mod withdraw {
                     input: new_address().await,
                     amount_msat: Some(500_000_000),
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -412,6 +418,8 @@ mod withdraw {
                     input: own,
                     amount_msat: Some(100_000_000),
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
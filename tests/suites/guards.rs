@@ -82,6 +82,8 @@ async fn every_admin_endpoint_enforces_its_permission() {
             input: "guard".to_string(),
             amount_msat: None,
             comment: None,
+            conf_target: None,
+            feerate_sat_vb: None,
         }),
     ));
     cases.push((
@@ -93,6 +95,8 @@ async fn every_admin_endpoint_enforces_its_permission() {
             input: "guard".to_string(),
             amount_msat: None,
             comment: None,
+            conf_target: None,
+            feerate_sat_vb: None,
         }),
     ));
 
@@ -107,6 +111,7 @@ async fn every_admin_endpoint_enforces_its_permission() {
             permissions: vec![],
             description: None,
             expiry: None,
+            wallet_ids: None,
         }),
     ));
 
@@ -118,8 +123,12 @@ async fn every_admin_endpoint_enforces_its_permission() {
         body(RegisterLnAddressRequest {
             account_id: None,
             username: unique("guard"),
+            domain: None,
             allows_nostr: false,
             nostr_pubkey: None,
+            wallet_id: None,
+            pay_profile: None,
+            splits: Vec::new(),
         }),
     ));
     cases.push((
@@ -131,6 +140,9 @@ async fn every_admin_endpoint_enforces_its_permission() {
             active: Some(false),
             allows_nostr: None,
             nostr_pubkey: None,
+            pay_profile: None,
+            wallet_id: None,
+            splits: None,
         }),
     ));
 
@@ -145,6 +157,8 @@ async fn every_admin_endpoint_enforces_its_permission() {
         }),
     ));
 
+    cases.push((Method::GET, "/v1/audit".to_string(), Permission::ReadAudit, None));
+
     for (method, path, required, payload) in cases {
         let scopes: Vec<Permission> = Permission::all_permissions()
             .into_iter()
@@ -3,10 +3,14 @@
 //! matrix. Each test uses its own wallet so list/filter counts stay deterministic
 //! on the shared instance.
 
+use std::time::Duration;
+
 use reqwest::StatusCode;
 
-use swissknife_types::{Invoice, InvoiceStatus, NewInvoiceRequest};
+use swissknife_types::{Invoice, InvoiceStatus, NewInvoiceRequest, NewPaymentRequest, PaymentRequest};
 
+use crate::common::chain::{mine, send_to_address};
+use crate::common::wait::wait_until;
 use crate::common::{app, assert_error, assert_status, Auth, TestApp};
 
 /// Generate a Lightning invoice for `wallet_id` and return it.
@@ -80,6 +84,99 @@ mod generate {
     }
 }
 
+mod unified {
+    use super::*;
+
+    async fn new_payment_request(
+        app: &TestApp,
+        token: &str,
+        wallet_id: uuid::Uuid,
+        amount_msat: u64,
+    ) -> PaymentRequest {
+        let res = app
+            .api()
+            .post(
+                "/v1/invoices/unified",
+                Auth::Bearer(token),
+                NewPaymentRequest {
+                    wallet_id: Some(wallet_id),
+                    amount_msat,
+                    description: Some("itest unified".to_string()),
+                    expiry: None,
+                    address_type: None,
+                },
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        res.parse()
+    }
+
+    #[tokio::test]
+    async fn combines_a_fresh_address_and_the_invoice_in_a_bip21_uri() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "inv-unified").await;
+
+        let payment_request = new_payment_request(app, token, wallet.id, 50_000_000).await;
+
+        let bolt11 = &payment_request.invoice.ln_invoice.as_ref().unwrap().bolt11;
+        assert_eq!(payment_request.address.invoice_id, Some(payment_request.invoice.id));
+        assert!(payment_request
+            .uri
+            .starts_with(&format!("bitcoin:{}?amount=0.0005&", payment_request.address.address)));
+        assert!(payment_request.uri.ends_with(&format!("&lightning={bolt11}")));
+
+        // Each request gets its own address, so an on-chain payment identifies the request it settles.
+        let other = new_payment_request(app, token, wallet.id, 50_000_000).await;
+        assert_ne!(other.address.address, payment_request.address.address);
+    }
+
+    #[tokio::test]
+    async fn an_on_chain_payment_settles_the_invoice() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "inv-unified-onchain").await;
+
+        let payment_request = new_payment_request(app, token, wallet.id, 50_000_000).await;
+
+        send_to_address(&payment_request.address.address, 50_000).await;
+        mine(6).await;
+
+        let path = format!("/v1/invoices/{}", payment_request.invoice.id);
+        wait_until(Duration::from_secs(90), "payment request settled on-chain", || async {
+            let invoice: Invoice = app.api().get(&path, Auth::Bearer(token)).await.parse();
+            invoice.status == InvoiceStatus::Settled
+        })
+        .await;
+
+        // Only the on-chain deposit is credited; the cancelled invoice received nothing.
+        assert_eq!(app.wallet_balance(token, wallet.id).await.available_msat, 50_000_000);
+    }
+
+    #[tokio::test]
+    async fn rejects_amounts_not_payable_on_chain() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "inv-unified-msat").await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/invoices/unified",
+                Auth::Bearer(token),
+                NewPaymentRequest {
+                    wallet_id: Some(wallet.id),
+                    amount_msat: 1_500,
+                    description: None,
+                    expiry: None,
+                    address_type: None,
+                },
+            )
+            .await;
+        assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
+
 mod query {
     use super::*;
 
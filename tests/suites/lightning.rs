@@ -78,6 +78,8 @@ mod send {
                     input: bolt11.clone(),
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -102,6 +104,8 @@ mod send {
                     input: bolt11,
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -163,6 +167,8 @@ mod failure {
                     input: bolt11,
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -1,7 +1,7 @@
 //! `/v1/lightning-addresses` — admin LN-address management, permission-gated
-//! (`*:ln_address`). Each test registers under its own account (an account holds at
-//! most one address) with a process-unique, globally-unique username. Nostr is
-//! left off (`allows_nostr: false`) — its serving path is mocked separately.
+//! (`*:ln_address`). Each test registers under its own account with a
+//! process-unique, globally-unique username. Nostr is left off
+//! (`allows_nostr: false`) — its serving path is mocked separately.
 
 use reqwest::StatusCode;
 
@@ -14,8 +14,12 @@ fn register_req(wallet: &Wallet, username: &str) -> RegisterLnAddressRequest {
     RegisterLnAddressRequest {
         account_id: Some(wallet.account_id),
         username: username.to_string(),
+        domain: None,
         allows_nostr: false,
         nostr_pubkey: None,
+        wallet_id: None,
+        pay_profile: None,
+        splits: Vec::new(),
     }
 }
 
@@ -57,8 +61,12 @@ mod register {
                 RegisterLnAddressRequest {
                     account_id: Some(uuid::Uuid::new_v4()),
                     username: "noauth".to_string(),
+                    domain: None,
                     allows_nostr: false,
                     nostr_pubkey: None,
+                    wallet_id: None,
+                    pay_profile: None,
+                    splits: Vec::new(),
                 },
             )
             .await;
@@ -114,31 +122,34 @@ mod register {
     }
 
     #[tokio::test]
-    async fn rejects_a_second_address_for_one_account() {
+    async fn accepts_several_addresses_for_one_account() {
         let app = app().await;
         let token = app.admin_token().await;
-        let wallet = app.create_wallet(token, "lnaddr-one").await;
+        let wallet = app.create_wallet(token, "lnaddr-many").await;
+
+        for username in [unique("lnaddr-one"), unique("lnaddr-two")] {
+            let res = app
+                .api()
+                .post(
+                    "/v1/lightning-addresses",
+                    Auth::Bearer(token),
+                    register_req(&wallet, &username),
+                )
+                .await;
+            assert_status(&res, StatusCode::OK);
+        }
 
-        let res = app
-            .api()
-            .post(
-                "/v1/lightning-addresses",
-                Auth::Bearer(token),
-                register_req(&wallet, &unique("lnaddr-one")),
-            )
-            .await;
-        assert_status(&res, StatusCode::OK);
-
-        // An account may hold only one address.
-        let again = app
+        let list = app
             .api()
-            .post(
-                "/v1/lightning-addresses",
+            .get(
+                &format!("/v1/lightning-addresses?account_id={}", wallet.account_id),
                 Auth::Bearer(token),
-                register_req(&wallet, &unique("lnaddr-two")),
             )
             .await;
-        assert_error(&again, StatusCode::CONFLICT);
+        assert_status(&list, StatusCode::OK);
+        let addresses = list.parse::<Vec<LnAddress>>();
+        assert_eq!(addresses.len(), 2, "both addresses are kept");
+        assert!(addresses.iter().all(|addr| addr.wallet_id == wallet.id));
     }
 }
 
@@ -194,6 +205,9 @@ mod manage {
                     active: Some(false),
                     allows_nostr: None,
                     nostr_pubkey: None,
+                    pay_profile: None,
+                    wallet_id: None,
+                    splits: None,
                 },
             )
             .await;
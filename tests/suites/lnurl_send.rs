@@ -13,7 +13,9 @@
 use reqwest::StatusCode;
 use serde_json::json;
 
-use swissknife_types::{Ledger, Payment, PaymentFeeEstimate, PaymentStatus, SendPaymentRequest};
+use swissknife_types::{
+    Ledger, LnUrlPayPreview, Payment, PaymentFeeEstimate, PaymentStatus, PreviewPaymentRequest, SendPaymentRequest,
+};
 
 use crate::common::counterparty::Counterparty;
 use crate::common::fixtures::unique;
@@ -26,6 +28,8 @@ fn pay(wallet_id: uuid::Uuid, input: String, amount_msat: u64, comment: Option<&
         input,
         amount_msat: Some(amount_msat),
         comment: comment.map(str::to_string),
+        conf_target: None,
+        feerate_sat_vb: None,
     }
 }
 
@@ -76,6 +80,13 @@ mod pay {
         let quote = quote.parse::<PaymentFeeEstimate>();
         assert_eq!(quote.ledger, Ledger::Lightning);
         assert_eq!(quote.amount_msat, amount_msat);
+        let terms = quote
+            .lnurl_pay
+            .as_ref()
+            .expect("the quote carries the remote pay terms");
+        assert_eq!(terms.min_sendable, 1_000);
+        assert_eq!(terms.max_sendable, 250_000_000_000);
+        assert_eq!(terms.comment_allowed, 255);
         let estimated_fee_msat = quote
             .estimated_fee_msat
             .expect("the real Lightning provider returns an LNURL route estimate");
@@ -194,6 +205,74 @@ mod pay {
             "callback received the comment: {pairs:?}"
         );
     }
+
+    /// LUD-18: when the service requires payer data, the sending wallet's
+    /// Lightning Address is forwarded as `payerdata` on the callback. Optional
+    /// fields are not shared.
+    #[tokio::test]
+    async fn forwards_payer_data_to_the_callback() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "lnurl-payerdata").await;
+
+        let username = unique("payer").to_lowercase();
+        let res = app
+            .api()
+            .post(
+                "/v1/lightning-addresses",
+                Auth::Bearer(token),
+                json!({ "account_id": wallet.account_id, "username": username }),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        let user = unique("payee");
+        let mock = MockLnurl::start().await;
+        mock.mount_metadata(
+            &user,
+            json!({
+                "tag": "payRequest",
+                "callback": format!("{}/lnurl/callback", mock.uri()),
+                "minSendable": 1_000,
+                "maxSendable": 1_000_000_000,
+                "metadata": "[[\"text/plain\",\"itest lnurl pay\"]]",
+                "payerData": {
+                    "name": { "mandatory": false },
+                    "identifier": { "mandatory": true },
+                    "email": { "mandatory": false },
+                },
+            }),
+        )
+        .await;
+        mock.mount_callback_error("not today").await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/payments",
+                Auth::Bearer(token),
+                pay(wallet.id, mock.lnurlp_url(&user), 100_000, None),
+            )
+            .await;
+        assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let callbacks = mock.callback_requests().await;
+        assert_eq!(callbacks.len(), 1, "the callback was hit once");
+        let payer_data = callbacks[0]
+            .url
+            .query_pairs()
+            .find(|(k, _)| k == "payerdata")
+            .map(|(_, v)| serde_json::from_str::<serde_json::Value>(&v).expect("payerdata is JSON"))
+            .expect("callback received payerdata");
+        assert!(payer_data.get("name").is_none(), "optional fields are not sent");
+        assert!(
+            payer_data["identifier"]
+                .as_str()
+                .is_some_and(|identifier| identifier.starts_with(&format!("{username}@"))),
+            "payerdata identifies the wallet by its address: {payer_data}"
+        );
+        assert!(payer_data.get("email").is_none(), "unknown fields are not sent");
+    }
 }
 
 mod validation {
@@ -350,4 +429,105 @@ mod validation {
             "callback must not be contacted for an out-of-range amount"
         );
     }
+
+    /// A comment or amount outside of the terms of the service gets the terms
+    /// back without a fee, before the callback is ever contacted.
+    #[tokio::test]
+    async fn terms_are_returned_without_calling_back_until_satisfied() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "lnurl-nocomment").await;
+
+        let user = unique("payee");
+        let mock = MockLnurl::start().await;
+        mock.mount_pay_request(&user, 1_000, 1_000_000_000, 0).await;
+        mock.mount_callback_invoice("unused").await;
+
+        for request in [
+            pay(wallet.id, mock.lnurlp_url(&user), 100_000, Some("hello")),
+            pay(wallet.id, mock.lnurlp_url(&user), 500, None),
+            SendPaymentRequest {
+                amount_msat: None,
+                ..pay(wallet.id, mock.lnurlp_url(&user), 0, None)
+            },
+        ] {
+            let res = app
+                .api()
+                .post("/v1/payments/fee-estimate", Auth::Bearer(token), request)
+                .await;
+            assert_status(&res, StatusCode::OK);
+            let quote = res.parse::<PaymentFeeEstimate>();
+            assert_eq!(quote.estimated_fee_msat, None);
+            let terms = quote.lnurl_pay.expect("the quote carries the remote pay terms");
+            assert_eq!(terms.min_sendable, 1_000);
+            assert_eq!(terms.comment_allowed, 0);
+        }
+
+        assert!(
+            mock.callback_requests().await.is_empty(),
+            "callback must not be contacted for unsatisfied terms"
+        );
+    }
+}
+
+mod preview {
+    use super::*;
+
+    fn preview(wallet_id: uuid::Uuid, input: String) -> PreviewPaymentRequest {
+        PreviewPaymentRequest {
+            wallet_id: Some(wallet_id),
+            input,
+        }
+    }
+
+    /// The preview exposes the remote pay terms without requesting an invoice.
+    #[tokio::test]
+    async fn returns_the_remote_pay_terms() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "lnurl-preview").await;
+
+        let user = unique("payee");
+        let mock = MockLnurl::start().await;
+        mock.mount_pay_request(&user, 2_000, 80_000_000, 120).await;
+        mock.mount_callback_invoice("unused").await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/payments/lnurl-preview",
+                Auth::Bearer(token),
+                preview(wallet.id, mock.lnurlp_url(&user)),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let terms = res.parse::<LnUrlPayPreview>();
+        assert_eq!(terms.min_sendable, 2_000);
+        assert_eq!(terms.max_sendable, 80_000_000);
+        assert_eq!(terms.comment_allowed, 120);
+        assert_eq!(terms.description.as_deref(), Some("itest lnurl pay"));
+        assert!(
+            mock.callback_requests().await.is_empty(),
+            "previewing must not request an invoice"
+        );
+    }
+
+    /// Inputs that are not LNURL-pay requests have nothing to preview.
+    #[tokio::test]
+    async fn rejects_non_lnurl_inputs() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "lnurl-preview-bad").await;
+
+        let bolt11 = Counterparty::for_provider(&app.provider).invoice(10_000, &unique("lnurl-pv"));
+        let res = app
+            .api()
+            .post(
+                "/v1/payments/lnurl-preview",
+                Auth::Bearer(token),
+                preview(wallet.id, bolt11),
+            )
+            .await;
+        assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+    }
 }
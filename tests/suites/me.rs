@@ -325,6 +325,8 @@ mod payments {
                     input: "not-a-payment".to_string(),
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -348,6 +350,8 @@ mod payments {
                     input: "not-a-payment".to_string(),
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -390,6 +394,8 @@ mod payments {
                     input: bolt11,
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -417,6 +423,8 @@ mod payments {
                     input: "notapaymentinput".to_string(),
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -461,6 +469,8 @@ mod payments {
                     input: bolt11,
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -521,8 +531,12 @@ mod ln_address {
                 RegisterLnAddressRequest {
                     account_id: None,
                     username: username.clone(),
+                    domain: None,
                     allows_nostr: false,
                     nostr_pubkey: None,
+                    wallet_id: None,
+                    pay_profile: None,
+                    splits: Vec::new(),
                 },
             )
             .await;
@@ -545,6 +559,9 @@ mod ln_address {
                     active: Some(false),
                     allows_nostr: None,
                     nostr_pubkey: None,
+                    pay_profile: None,
+                    wallet_id: None,
+                    splits: None,
                 },
             )
             .await;
@@ -562,6 +579,73 @@ mod ln_address {
             .await;
         assert!(after.parse::<Option<LnAddress>>().is_none());
     }
+
+    #[tokio::test]
+    async fn manages_several_addresses_scoped_to_the_account() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let account = app.create_account_with_wallet(token, "me-lnaddrs").await;
+        let other = app.create_account_with_wallet(token, "me-lnaddrs-other").await;
+
+        let mut registered = Vec::new();
+        for username in [unique("me-lnaddrs-a"), unique("me-lnaddrs-b")] {
+            let res = app
+                .api()
+                .post(
+                    "/v1/me/lightning-addresses",
+                    Auth::ApiKey(&account.key),
+                    RegisterLnAddressRequest {
+                        account_id: None,
+                        username,
+                        domain: None,
+                        allows_nostr: false,
+                        nostr_pubkey: None,
+                        wallet_id: Some(account.wallet.id),
+                        pay_profile: None,
+                        splits: Vec::new(),
+                    },
+                )
+                .await;
+            assert_status(&res, StatusCode::OK);
+            registered.push(res.parse::<LnAddress>());
+        }
+
+        let list = app
+            .api()
+            .get("/v1/me/lightning-addresses", Auth::ApiKey(&account.key))
+            .await;
+        assert_status(&list, StatusCode::OK);
+        assert_eq!(list.parse::<Vec<LnAddress>>().len(), 2);
+
+        // The singular route keeps serving the oldest address.
+        let oldest = app
+            .api()
+            .get("/v1/me/lightning-address", Auth::ApiKey(&account.key))
+            .await;
+        assert_eq!(
+            oldest.parse::<Option<LnAddress>>().expect("registered").id,
+            registered[0].id
+        );
+
+        // Another account cannot see or delete them.
+        let foreign = app
+            .api()
+            .delete(
+                &format!("/v1/me/lightning-addresses/{}", registered[1].id),
+                Auth::ApiKey(&other.key),
+            )
+            .await;
+        assert_error(&foreign, StatusCode::NOT_FOUND);
+
+        let del = app
+            .api()
+            .delete(
+                &format!("/v1/me/lightning-addresses/{}", registered[1].id),
+                Auth::ApiKey(&account.key),
+            )
+            .await;
+        assert_status(&del, StatusCode::OK);
+    }
 }
 
 mod contacts {
@@ -605,6 +689,7 @@ mod api_keys {
                     permissions: vec![Permission::ReadWallet],
                     description: None,
                     expiry: None,
+                    wallet_ids: None,
                 },
             )
             .await;
@@ -651,6 +736,7 @@ mod api_keys {
                     permissions: vec![],
                     description: None,
                     expiry: None,
+                    wallet_ids: None,
                 },
             )
             .await
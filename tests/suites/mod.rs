@@ -3,6 +3,7 @@
 mod accounts;
 mod api_keys;
 mod asset_scoping;
+mod audit;
 mod auth;
 mod bitcoin;
 mod guards;
@@ -11,8 +12,13 @@ mod lightning;
 mod ln_addresses;
 mod lnurl_send;
 mod me;
+mod nostr_auth;
 mod oauth2;
+mod oauth2_issuers;
+mod pay_links;
+mod payjoin;
 mod payments;
+mod rate_limit;
 mod system;
 mod wallets;
 mod well_known;
@@ -0,0 +1,217 @@
+//! NIP-98 authentication — Nostr keys linked at `/v1/auth/nostr-keys`
+//! authenticate requests with `Authorization: Nostr <base64 event>`, each event
+//! signed for the exact URL, method and body of its request.
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use bitcoin::hashes::{sha256, Hash};
+use nostr_sdk::prelude::{EventBuilder, FinalizeEvent, Keys, Kind, Tag, Timestamp};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+
+use swissknife_types::{
+    Account, AuthIdentity, AuthProvider, CreateInvitationRequest, Invitation, LinkNostrKeyRequest, RegisterRequest,
+    SignInResponse,
+};
+
+use crate::common::fixtures::unique;
+use crate::common::{app, assert_error, assert_status, Auth, TestApp};
+
+/// Base64 NIP-98 event signed by `keys` for `method` on `path`, committing to `body` if any.
+fn nip98(app: &TestApp, keys: &Keys, method: &Method, path: &str, body: Option<&Value>) -> String {
+    let mut tags = vec![
+        Tag::parse(["u".to_string(), format!("{}{path}", app.base_url)]).unwrap(),
+        Tag::parse(["method", method.as_str()]).unwrap(),
+    ];
+    if let Some(body) = body {
+        // The client sends the body as serialized here.
+        let hash = sha256::Hash::hash(&serde_json::to_vec(body).unwrap());
+        tags.push(Tag::parse(["payload".to_string(), hex::encode(hash.to_byte_array())]).unwrap());
+    }
+
+    let event = EventBuilder::new(Kind::HttpAuth, "")
+        .tags(tags)
+        .custom_created_at(Timestamp::now())
+        .finalize(keys)
+        .unwrap();
+    BASE64_STANDARD.encode(event.as_json())
+}
+
+/// A freshly registered local account, signed in with a session.
+async fn register(app: &TestApp) -> SignInResponse {
+    let admin = app.admin_token().await;
+    let invitation = app
+        .api()
+        .post(
+            "/v1/auth/invitations",
+            Auth::Bearer(admin),
+            CreateInvitationRequest {
+                email: None,
+                permissions: vec![],
+                expiry: None,
+            },
+        )
+        .await
+        .parse::<Invitation>()
+        .token
+        .expect("invitation token");
+
+    let res = app
+        .api()
+        .post(
+            "/v1/auth/register",
+            Auth::None,
+            RegisterRequest {
+                username: unique("nostr"),
+                email: None,
+                password: "nostr-account-password".to_string(),
+                display_name: None,
+                invitation: Some(invitation),
+            },
+        )
+        .await;
+    assert_status(&res, StatusCode::OK);
+    res.parse::<SignInResponse>()
+}
+
+async fn link(app: &TestApp, token: &str, keys: &Keys) -> AuthIdentity {
+    let event = nip98(app, keys, &Method::POST, "/v1/auth/nostr-keys", None);
+    let res = app
+        .api()
+        .post(
+            "/v1/auth/nostr-keys",
+            Auth::Bearer(token),
+            LinkNostrKeyRequest { event },
+        )
+        .await;
+    assert_status(&res, StatusCode::OK);
+    res.parse::<AuthIdentity>()
+}
+
+#[tokio::test]
+async fn a_linked_key_authenticates_requests_signed_for_them() {
+    let app = app().await;
+    let session = register(app).await;
+    let keys = Keys::generate();
+
+    let identity = link(app, &session.token, &keys).await;
+    assert_eq!(identity.provider, AuthProvider::Nostr);
+    assert_eq!(identity.subject, keys.public_key().to_hex());
+
+    let event = nip98(app, &keys, &Method::GET, "/v1/me", None);
+    let res = app.api().get("/v1/me", Auth::Nostr(&event)).await;
+    assert_status(&res, StatusCode::OK);
+    let by_session = app.api().get("/v1/me", Auth::Bearer(&session.token)).await;
+    assert_eq!(res.parse::<Account>().id, by_session.parse::<Account>().id);
+
+    let body = json!({ "display_name": "Nostr bot" });
+    let event = nip98(app, &keys, &Method::PUT, "/v1/me", Some(&body));
+    let res = app.api().put("/v1/me", Auth::Nostr(&event), &body).await;
+    assert_status(&res, StatusCode::OK);
+    assert_eq!(res.parse::<Account>().display_name.as_deref(), Some("Nostr bot"));
+
+    let listed = app.api().get("/v1/auth/nostr-keys", Auth::Bearer(&session.token)).await;
+    assert_status(&listed, StatusCode::OK);
+    assert_eq!(
+        listed
+            .parse::<Vec<AuthIdentity>>()
+            .into_iter()
+            .map(|identity| identity.id)
+            .collect::<Vec<_>>(),
+        vec![identity.id]
+    );
+}
+
+#[tokio::test]
+async fn events_for_another_request_are_rejected() {
+    let app = app().await;
+    let session = register(app).await;
+    let keys = Keys::generate();
+    link(app, &session.token, &keys).await;
+
+    let other_path = nip98(app, &keys, &Method::GET, "/v1/me/wallets", None);
+    assert_error(
+        &app.api().get("/v1/me", Auth::Nostr(&other_path)).await,
+        StatusCode::UNAUTHORIZED,
+    );
+
+    let other_method = nip98(app, &keys, &Method::GET, "/v1/me", None);
+    let body = json!({ "display_name": "Tampered" });
+    assert_error(
+        &app.api().put("/v1/me", Auth::Nostr(&other_method), &body).await,
+        StatusCode::UNAUTHORIZED,
+    );
+
+    let signed = json!({ "display_name": "Signed" });
+    let other_body = nip98(app, &keys, &Method::PUT, "/v1/me", Some(&signed));
+    assert_error(
+        &app.api().put("/v1/me", Auth::Nostr(&other_body), &body).await,
+        StatusCode::UNAUTHORIZED,
+    );
+}
+
+#[tokio::test]
+async fn unlinked_keys_do_not_authenticate() {
+    let app = app().await;
+    let session = register(app).await;
+    let keys = Keys::generate();
+
+    let unknown = nip98(app, &keys, &Method::GET, "/v1/me", None);
+    assert_error(
+        &app.api().get("/v1/me", Auth::Nostr(&unknown)).await,
+        StatusCode::UNAUTHORIZED,
+    );
+
+    let identity = link(app, &session.token, &keys).await;
+    let res = app
+        .api()
+        .delete(
+            &format!("/v1/auth/nostr-keys/{}", identity.id),
+            Auth::Bearer(&session.token),
+        )
+        .await;
+    assert_status(&res, StatusCode::OK);
+
+    let unlinked = nip98(app, &keys, &Method::GET, "/v1/me", None);
+    assert_error(
+        &app.api().get("/v1/me", Auth::Nostr(&unlinked)).await,
+        StatusCode::UNAUTHORIZED,
+    );
+}
+
+#[tokio::test]
+async fn a_key_is_linked_to_a_single_account() {
+    let app = app().await;
+    let first = register(app).await;
+    let second = register(app).await;
+    let keys = Keys::generate();
+    link(app, &first.token, &keys).await;
+
+    let event = nip98(app, &keys, &Method::POST, "/v1/auth/nostr-keys", None);
+    let res = app
+        .api()
+        .post(
+            "/v1/auth/nostr-keys",
+            Auth::Bearer(&second.token),
+            LinkNostrKeyRequest { event },
+        )
+        .await;
+    assert_error(&res, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn linking_requires_a_proof_signed_by_the_key() {
+    let app = app().await;
+    let session = register(app).await;
+
+    // Signed for another endpoint, so it cannot be replayed to link the key.
+    let event = nip98(app, &Keys::generate(), &Method::GET, "/v1/me", None);
+    let res = app
+        .api()
+        .post(
+            "/v1/auth/nostr-keys",
+            Auth::Bearer(&session.token),
+            LinkNostrKeyRequest { event },
+        )
+        .await;
+    assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+}
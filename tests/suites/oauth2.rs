@@ -114,8 +114,12 @@ mod rejects {
                 RegisterLnAddressRequest {
                     account_id: None,
                     username: unique("oauth2-guard"),
+                    domain: None,
                     allows_nostr: false,
                     nostr_pubkey: None,
+                    wallet_id: None,
+                    pay_profile: None,
+                    splits: Vec::new(),
                 },
             )
             .await;
@@ -0,0 +1,97 @@
+//! Several trusted OAuth2/OIDC issuers at once, alongside local accounts. The
+//! instance (see `common::oauth2::multi_issuer_app`) trusts a corporate issuer
+//! mapping `groups` to permissions, a consumer issuer granting default
+//! permissions, and a partner issuer that does not provision accounts.
+
+use reqwest::StatusCode;
+
+use swissknife_types::Account;
+
+use crate::common::harness::bootstrap_admin;
+use crate::common::oauth2::{
+    multi_issuer_app, CLIENT_CUSTOMER, CLIENT_FULL, CLIENT_PARTNER, CLIENT_STAFF, ISSUER_CONSUMER, ISSUER_CORPORATE,
+    ISSUER_ID, ISSUER_PARTNER,
+};
+use crate::common::{assert_error, assert_status, Auth};
+
+mod accepts {
+    use super::*;
+
+    /// Staff permissions come from the groups the corporate IdP puts in their token.
+    #[tokio::test]
+    async fn staff_through_the_corporate_issuer_with_mapped_permissions() {
+        let app = multi_issuer_app().await;
+        let token = app.token_from(ISSUER_CORPORATE, CLIENT_STAFF).await;
+
+        let res = app.api().get("/v1/accounts", Auth::Bearer(&token)).await;
+        assert_status(&res, StatusCode::OK);
+        let res = app.api().get("/v1/wallets", Auth::Bearer(&token)).await;
+        assert_status(&res, StatusCode::OK);
+    }
+
+    /// Customers get the default permissions of the consumer issuer only.
+    #[tokio::test]
+    async fn customers_through_the_consumer_issuer_with_default_permissions() {
+        let app = multi_issuer_app().await;
+        let token = app.token_from(ISSUER_CONSUMER, CLIENT_CUSTOMER).await;
+
+        let res = app.api().get("/v1/wallets", Auth::Bearer(&token)).await;
+        assert_status(&res, StatusCode::OK);
+        let res = app.api().get("/v1/accounts", Auth::Bearer(&token)).await;
+        assert_error(&res, StatusCode::FORBIDDEN);
+    }
+
+    /// Subjects are namespaced by issuer, so the same `sub` from two issuers
+    /// maps to two accounts.
+    #[tokio::test]
+    async fn the_same_subject_of_two_issuers_as_distinct_accounts() {
+        let app = multi_issuer_app().await;
+        let staff = app.token_from(ISSUER_CORPORATE, CLIENT_STAFF).await;
+        let customer = app.token_from(ISSUER_CONSUMER, CLIENT_CUSTOMER).await;
+
+        let res = app.api().get("/v1/me", Auth::Bearer(&staff)).await;
+        assert_status(&res, StatusCode::OK);
+        let staff_account = res.parse::<Account>();
+        let res = app.api().get("/v1/me", Auth::Bearer(&customer)).await;
+        assert_status(&res, StatusCode::OK);
+        let customer_account = res.parse::<Account>();
+
+        assert_ne!(staff_account.id, customer_account.id);
+        assert_eq!(
+            staff_account.identity.map(|identity| identity.subject).as_deref(),
+            Some("corporate:itest-person")
+        );
+    }
+
+    #[tokio::test]
+    async fn local_accounts_alongside_the_issuers() {
+        let app = multi_issuer_app().await;
+        let token = bootstrap_admin(&app.api()).await;
+
+        let res = app.api().get("/v1/me", Auth::Bearer(&token)).await;
+        assert_status(&res, StatusCode::OK);
+    }
+}
+
+mod rejects {
+    use super::*;
+
+    #[tokio::test]
+    async fn unknown_subjects_of_an_issuer_without_provisioning() {
+        let app = multi_issuer_app().await;
+        let token = app.token_from(ISSUER_PARTNER, CLIENT_PARTNER).await;
+
+        let res = app.api().get("/v1/me", Auth::Bearer(&token)).await;
+        assert_error(&res, StatusCode::UNAUTHORIZED);
+    }
+
+    /// The default issuer of the IdP is not among the issuers of this instance.
+    #[tokio::test]
+    async fn tokens_of_an_untrusted_issuer() {
+        let app = multi_issuer_app().await;
+        let token = app.token_from(ISSUER_ID, CLIENT_FULL).await;
+
+        let res = app.api().get("/v1/me", Auth::Bearer(&token)).await;
+        assert_error(&res, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,144 @@
+//! `/v1/pay-links` and the public `/lnurl/pay-links` endpoints: reusable
+//! LNURL-pay links of a wallet. Each test registers its links under a fresh
+//! wallet; the LNURL flow is followed through the advertised URLs exactly as a
+//! point-of-sale payer would.
+
+use reqwest::StatusCode;
+
+use swissknife_types::{Invoice, LnURLPayRequest, LnUrlCallback, PayLink, RegisterPayLinkRequest, Wallet};
+
+use crate::common::fixtures::unique;
+use crate::common::{app, assert_error, assert_status, Auth, TestApp};
+
+async fn register_link(app: &TestApp, token: &str, wallet: &Wallet, amount_msat: Option<u64>) -> PayLink {
+    let res = app
+        .api()
+        .post(
+            "/v1/pay-links",
+            Auth::Bearer(token),
+            RegisterPayLinkRequest {
+                wallet_id: Some(wallet.id),
+                name: unique("register"),
+                description: None,
+                amount_msat,
+                reference: None,
+            },
+        )
+        .await;
+    assert_status(&res, StatusCode::OK);
+    res.parse::<PayLink>()
+}
+
+mod register {
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_a_reachable_lnurl() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "paylink-create").await;
+
+        let link = register_link(app, token, &wallet, Some(21_000)).await;
+        assert!(link.active, "a newly registered link is active");
+        assert!(link.lnurl.is_some_and(|lnurl| lnurl.starts_with("lnurl1")));
+
+        let res = app
+            .api()
+            .get(&format!("/lnurl/pay-links/{}", link.id), Auth::None)
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let pay = res.parse::<LnURLPayRequest>();
+        assert_eq!(pay.min_sendable, 21_000);
+        assert_eq!(pay.max_sendable, 21_000);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_duplicate_name() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "paylink-dup").await;
+        let link = register_link(app, token, &wallet, None).await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/pay-links",
+                Auth::Bearer(token),
+                RegisterPayLinkRequest {
+                    wallet_id: Some(wallet.id),
+                    name: link.name,
+                    description: None,
+                    amount_msat: None,
+                    reference: None,
+                },
+            )
+            .await;
+        assert_error(&res, StatusCode::CONFLICT);
+    }
+}
+
+mod lnurl {
+    use super::*;
+
+    #[tokio::test]
+    async fn the_callback_tags_the_invoice_with_the_reference() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "paylink-ref").await;
+        let link = register_link(app, token, &wallet, None).await;
+        let reference = unique("order");
+
+        let pay = app
+            .api()
+            .get(
+                &format!("/lnurl/pay-links/{}?reference={reference}", link.id),
+                Auth::None,
+            )
+            .await
+            .parse::<LnURLPayRequest>();
+        assert!(pay.callback.starts_with(&app.base_url), "{}", pay.callback);
+
+        let res = reqwest::get(format!("{}&amount=100000", pay.callback))
+            .await
+            .expect("reach the advertised callback");
+        assert_eq!(res.status().as_u16(), 200);
+        let cb = res
+            .json::<LnUrlCallback>()
+            .await
+            .expect("callback returns an LnUrlCallback");
+        assert!(cb.pr.to_lowercase().starts_with("lnbcrt"), "{}", cb.pr);
+
+        let invoices = app
+            .api()
+            .get(&format!("/v1/invoices?reference={reference}"), Auth::Bearer(token))
+            .await
+            .parse::<Vec<Invoice>>();
+        assert_eq!(invoices.len(), 1);
+        assert_eq!(invoices[0].wallet_id, wallet.id);
+        assert_eq!(invoices[0].pay_link_id, Some(link.id));
+    }
+
+    #[tokio::test]
+    async fn inactive_links_are_not_served() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "paylink-off").await;
+        let link = register_link(app, token, &wallet, None).await;
+
+        let res = app
+            .api()
+            .put(
+                &format!("/v1/pay-links/{}", link.id),
+                Auth::Bearer(token),
+                serde_json::json!({ "active": false }),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+
+        let res = app
+            .api()
+            .get(&format!("/lnurl/pay-links/{}", link.id), Auth::None)
+            .await;
+        assert_error(&res, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,229 @@
+//! BIP78 payjoin (`/v1/payjoin` and `pj=` BIP21 payments). The sender side is
+//! exercised against a wiremock stand-in for the receiver: it either answers
+//! with a BIP78 error, so the payment must fall back to broadcasting the
+//! original transaction, or contributes a miner-wallet input to the original
+//! and signs it, so the payment must broadcast the payjoin. Payjoin is
+//! disabled on the shared instance, so the receiver endpoint answers
+//! `unavailable`.
+
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bitcoin::{psbt::Input, Address, Amount, OutPoint, Psbt, ScriptBuf, TxIn, TxOut, Txid};
+use reqwest::StatusCode;
+use serde_json::json;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use swissknife_types::{Ledger, Payment, PaymentStatus, SendPaymentRequest};
+
+use crate::common::chain::{bitcoin_rpc, mine, send_to_address};
+use crate::common::{app, assert_status, Auth};
+
+mod receive {
+    use super::*;
+
+    #[tokio::test]
+    async fn is_unavailable_when_payjoin_is_disabled() {
+        let app = app().await;
+
+        let res = app.api().post_text("/v1/payjoin?v=1", "cHNidP8BAA==").await;
+
+        assert_status(&res, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.body["errorCode"], "unavailable");
+    }
+}
+
+mod send {
+    use super::*;
+
+    /// Fee the stand-in receiver adds for the input it contributes.
+    const RECEIVER_INPUT_FEE_SAT: u64 = 300;
+
+    /// Stand-in BIP78 receiver backed by the miner wallet. It adds the miner output matching the sender's input
+    /// type, moves its value (minus the fee of the extra input) to the payment output and has bitcoind sign it.
+    struct MinerReceiver {
+        payee: Address,
+        utxos: Vec<(OutPoint, TxOut)>,
+    }
+
+    impl Respond for MinerReceiver {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let original = Psbt::deserialize(&STANDARD.decode(&request.body).unwrap()).unwrap();
+            let sender_type = original.inputs[0]
+                .witness_utxo
+                .as_ref()
+                .and_then(|utxo| utxo.script_pubkey.witness_version());
+            let (outpoint, utxo) = self
+                .utxos
+                .iter()
+                .find(|(_, utxo)| utxo.script_pubkey.witness_version() == sender_type)
+                .cloned()
+                .expect("a miner output of the sender's input type");
+
+            let mut proposal = original.clone();
+            for input in proposal.inputs.iter_mut() {
+                input.final_script_sig = None;
+                input.final_script_witness = None;
+            }
+            proposal.unsigned_tx.input.push(TxIn {
+                previous_output: outpoint,
+                sequence: original.unsigned_tx.input[0].sequence,
+                ..Default::default()
+            });
+            proposal.inputs.push(Input {
+                witness_utxo: Some(utxo.clone()),
+                ..Default::default()
+            });
+            let payee = proposal
+                .unsigned_tx
+                .output
+                .iter_mut()
+                .find(|output| output.script_pubkey == self.payee.script_pubkey())
+                .expect("the payment output");
+            payee.value += utxo.value - Amount::from_sat(RECEIVER_INPUT_FEE_SAT);
+
+            // `respond` is synchronous: sign from a separate runtime so the test runtime is not blocked on itself.
+            let proposal = STANDARD.encode(proposal.serialize());
+            let signed = std::thread::spawn(move || {
+                tokio::runtime::Runtime::new()
+                    .unwrap()
+                    .block_on(bitcoin_rpc("walletprocesspsbt", json!([proposal])))
+            })
+            .join()
+            .unwrap();
+
+            ResponseTemplate::new(200).set_body_string(signed["psbt"].as_str().unwrap())
+        }
+    }
+
+    /// Confirmed miner outputs of each supported segwit type, for the stand-in receiver to contribute.
+    async fn miner_utxos() -> Vec<(OutPoint, TxOut)> {
+        let mut utxos = Vec::new();
+        for address_type in ["bech32", "bech32m"] {
+            let address = bitcoin_rpc("getnewaddress", json!(["payjoin-receiver", address_type])).await;
+            let address = address.as_str().unwrap().to_string();
+            send_to_address(&address, 100_000).await;
+            mine(1).await;
+
+            let unspent = bitcoin_rpc("listunspent", json!([1, 9_999_999, [address]])).await;
+            let unspent = &unspent[0];
+            utxos.push((
+                OutPoint {
+                    txid: Txid::from_str(unspent["txid"].as_str().unwrap()).unwrap(),
+                    vout: unspent["vout"].as_u64().unwrap() as u32,
+                },
+                TxOut {
+                    value: Amount::from_btc(unspent["amount"].as_f64().unwrap()).unwrap(),
+                    script_pubkey: ScriptBuf::from_hex(unspent["scriptPubKey"].as_str().unwrap()).unwrap(),
+                },
+            ));
+        }
+        utxos
+    }
+
+    #[tokio::test]
+    async fn broadcasts_the_payjoin_signed_by_the_receiver() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "payjoin-send-accepted").await;
+        app.fund_onchain(token, wallet.id, 200_000).await;
+
+        let utxos = miner_utxos().await;
+        let payee = bitcoin_rpc("getnewaddress", json!(["payjoin", "bech32"])).await;
+        let payee = payee.as_str().unwrap().to_string();
+        let receiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pj"))
+            .respond_with(MinerReceiver {
+                payee: Address::from_str(&payee).unwrap().assume_checked(),
+                utxos: utxos.clone(),
+            })
+            .expect(1)
+            .mount(&receiver)
+            .await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/payments",
+                Auth::Bearer(token),
+                SendPaymentRequest {
+                    wallet_id: Some(wallet.id),
+                    input: format!("bitcoin:{payee}?amount=0.0005&pj={}/pj", receiver.uri()),
+                    amount_msat: None,
+                    comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
+                },
+            )
+            .await;
+
+        assert_status(&res, StatusCode::OK);
+        let payment = res.parse::<Payment>();
+        assert_eq!(payment.status, PaymentStatus::Pending);
+        assert_eq!(payment.amount_msat, 50_000_000);
+
+        let txid = payment.bitcoin.expect("on-chain payment details").txid;
+        let tx = bitcoin_rpc("getrawtransaction", json!([txid, true])).await;
+        let inputs = tx["vin"].as_array().unwrap();
+        assert!(inputs.len() >= 2, "the payjoin carries the receiver's input");
+        assert!(
+            inputs.iter().any(|input| utxos.iter().any(|(outpoint, _)| {
+                input["txid"].as_str() == Some(&outpoint.txid.to_string())
+                    && input["vout"].as_u64() == Some(u64::from(outpoint.vout))
+            })),
+            "the broadcast transaction is the payjoin, not the original"
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_original_transaction_when_the_receiver_rejects_it() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "payjoin-send").await;
+        app.fund_onchain(token, wallet.id, 200_000).await;
+
+        let receiver = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pj"))
+            .and(query_param("v", "1"))
+            .and(query_param("disableoutputsubstitution", "true"))
+            .and(header("content-type", "text/plain"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "errorCode": "original-psbt-rejected",
+                "message": "rejected by the test receiver"
+            })))
+            .expect(1)
+            .mount(&receiver)
+            .await;
+
+        let address = bitcoin_rpc("getnewaddress", json!(["payjoin", "bech32"])).await;
+        let input = format!(
+            "bitcoin:{}?amount=0.0005&pj={}/pj",
+            address.as_str().unwrap(),
+            receiver.uri()
+        );
+        let res = app
+            .api()
+            .post(
+                "/v1/payments",
+                Auth::Bearer(token),
+                SendPaymentRequest {
+                    wallet_id: Some(wallet.id),
+                    input,
+                    amount_msat: None,
+                    comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
+                },
+            )
+            .await;
+
+        assert_status(&res, StatusCode::OK);
+        let payment = res.parse::<Payment>();
+        assert_eq!(payment.ledger, Ledger::Onchain);
+        assert_eq!(payment.status, PaymentStatus::Pending);
+        assert_eq!(payment.amount_msat, 50_000_000);
+    }
+}
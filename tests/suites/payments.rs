@@ -1,12 +1,12 @@
 //! `/v1/payments` — admin payment management, permission-gated (`*:transaction`).
 //! The happy LN-routed send lives in the lightning suite; here we cover the
 //! input/validation 422s, an instance-internal bolt11 settlement between two
-//! wallets, and CRUD. Each test uses its own wallet(s) for balance isolation.
+//! wallets, invoice-less transfers, and CRUD. Each test uses its own wallet(s) for balance isolation.
 
 use reqwest::StatusCode;
 
 use swissknife_types::{
-    Invoice, Ledger, NewInvoiceRequest, Payment, PaymentFeeEstimate, PaymentStatus, SendPaymentRequest,
+    Invoice, Ledger, NewInvoiceRequest, Payment, PaymentFeeEstimate, PaymentStatus, SendPaymentRequest, TransferRequest,
 };
 
 use crate::common::chain;
@@ -39,6 +39,8 @@ fn pay(wallet_id: uuid::Uuid, input: String) -> SendPaymentRequest {
         input,
         amount_msat: None,
         comment: None,
+        conf_target: None,
+        feerate_sat_vb: None,
     }
 }
 
@@ -74,6 +76,8 @@ mod fee_estimate {
                     input: "not-a-payment".to_string(),
                     amount_msat: None,
                     comment: None,
+                    conf_target: None,
+                    feerate_sat_vb: None,
                 },
             )
             .await;
@@ -110,6 +114,8 @@ mod fee_estimate {
             input: address,
             amount_msat: Some(amount_msat),
             comment: None,
+            conf_target: None,
+            feerate_sat_vb: None,
         };
 
         let first = app
@@ -279,6 +285,109 @@ mod internal {
     }
 }
 
+mod transfer {
+    use super::*;
+
+    fn transfer(wallet_id: uuid::Uuid, to_wallet_id: uuid::Uuid, amount_msat: u64) -> TransferRequest {
+        TransferRequest {
+            wallet_id: Some(wallet_id),
+            to_wallet_id: Some(to_wallet_id),
+            to_account_id: None,
+            to_ln_address: None,
+            amount_msat,
+            comment: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn moves_funds_between_two_wallets_without_an_invoice() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let payer = app.create_wallet(token, "transfer-src").await;
+        let payee = app.create_wallet(token, "transfer-dst").await;
+
+        app.fund_onchain(token, payer.id, 200_000).await;
+        let payer_before = app.wallet_balance(token, payer.id).await.available_msat;
+        let payee_before = app.wallet_balance(token, payee.id).await.available_msat;
+
+        let amount_msat = 50_000_000i64;
+        let res = app
+            .api()
+            .post(
+                "/v1/payments/transfers",
+                Auth::Bearer(token),
+                transfer(payer.id, payee.id, amount_msat as u64),
+            )
+            .await;
+        assert_status(&res, StatusCode::OK);
+        let payment = res.parse::<Payment>();
+        assert_eq!(payment.status, PaymentStatus::Settled);
+        assert_eq!(payment.ledger, Ledger::Internal);
+        let transfer_id = payment
+            .internal
+            .and_then(|internal| internal.transfer_id)
+            .expect("a transfer ID");
+
+        assert_eq!(
+            app.wallet_balance(token, payer.id).await.available_msat,
+            payer_before - amount_msat
+        );
+        assert_eq!(
+            app.wallet_balance(token, payee.id).await.available_msat,
+            payee_before + amount_msat
+        );
+
+        // The counterpart invoice shares the transfer ID and was never a Lightning invoice.
+        let invoices = app
+            .api()
+            .get(&format!("/v1/invoices?wallet_id={}", payee.id), Auth::Bearer(token))
+            .await;
+        assert_status(&invoices, StatusCode::OK);
+        let invoice = invoices
+            .parse::<Vec<Invoice>>()
+            .into_iter()
+            .find(|invoice| invoice.transfer_id == Some(transfer_id))
+            .expect("the counterpart invoice");
+        assert_eq!(invoice.ledger, Ledger::Internal);
+        assert!(invoice.ln_invoice.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_when_funds_are_insufficient() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let payer = app.create_wallet(token, "transfer-broke").await; // zero balance
+        let payee = app.create_wallet(token, "transfer-broke-dst").await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/payments/transfers",
+                Auth::Bearer(token),
+                transfer(payer.id, payee.id, 1_000),
+            )
+            .await;
+        assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn rejects_the_same_wallet() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "transfer-self").await;
+
+        let res = app
+            .api()
+            .post(
+                "/v1/payments/transfers",
+                Auth::Bearer(token),
+                transfer(wallet.id, wallet.id, 1_000),
+            )
+            .await;
+        assert_error(&res, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
+
 mod manage {
     use super::*;
 
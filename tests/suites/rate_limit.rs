@@ -0,0 +1,92 @@
+//! Rate limiting on an instance of its own, since the shared one runs without
+//! it: sign-in attempts, LNURL callbacks and authenticated accounts are
+//! rejected with 429 once over their limits.
+
+use reqwest::StatusCode;
+
+use swissknife_types::{SignInRequest, SignInResponse, SignUpRequest};
+
+use crate::common::client::ApiClient;
+use crate::common::harness::{matrix_cell, spawn_instance, ADMIN_PASSWORD};
+use crate::common::{assert_error, assert_status, Auth};
+
+/// A fresh instance, with its admin signed up, allowing 3 sign-in attempts,
+/// 2 callbacks per LNURL recipient and 5 requests per account.
+async fn limited_instance(name: &str) -> (ApiClient, String) {
+    let (database, provider) = matrix_cell();
+    let label = format!("{database}-{provider}-rate-limit-{name}");
+    let spawned = spawn_instance(
+        &database,
+        &provider,
+        &label,
+        &[
+            ("SWISSKNIFE_WEB__RATE_LIMIT__ENABLED", "true".to_string()),
+            ("SWISSKNIFE_WEB__RATE_LIMIT__SIGN_IN__REQUESTS", "3".to_string()),
+            (
+                "SWISSKNIFE_WEB__RATE_LIMIT__LNURL_CALLBACK_RECIPIENT__REQUESTS",
+                "2".to_string(),
+            ),
+            ("SWISSKNIFE_WEB__RATE_LIMIT__ACCOUNT__REQUESTS", "5".to_string()),
+        ],
+    )
+    .await;
+    let api = ApiClient::new(spawned.base_url);
+
+    let res = api
+        .post(
+            "/v1/auth/sign-up",
+            Auth::None,
+            SignUpRequest {
+                password: ADMIN_PASSWORD.to_string(),
+            },
+        )
+        .await;
+    assert_status(&res, StatusCode::OK);
+    let token = res.parse::<SignInResponse>().token;
+
+    (api, token)
+}
+
+fn sign_in(password: &str) -> SignInRequest {
+    SignInRequest {
+        login: None,
+        password: password.to_string(),
+        otp: None,
+    }
+}
+
+#[tokio::test]
+async fn sign_in_attempts_are_limited_per_ip() {
+    let (api, _) = limited_instance("sign-in").await;
+
+    for _ in 0..3 {
+        let res = api
+            .post("/v1/auth/sign-in", Auth::None, sign_in("wrong-password"))
+            .await;
+        assert_error(&res, StatusCode::UNAUTHORIZED);
+    }
+
+    let res = api.post("/v1/auth/sign-in", Auth::None, sign_in(ADMIN_PASSWORD)).await;
+    assert_error(&res, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn lnurl_callbacks_and_accounts_are_limited() {
+    let (api, token) = limited_instance("callbacks").await;
+
+    // Limited before the recipient is even looked up.
+    for _ in 0..2 {
+        let res = api.get("/lnurlp/nobody/callback?amount=1000", Auth::None).await;
+        assert_error(&res, StatusCode::NOT_FOUND);
+    }
+    let res = api.get("/lnurlp/nobody/callback?amount=1000", Auth::None).await;
+    assert_error(&res, StatusCode::TOO_MANY_REQUESTS);
+    let res = api.get("/lnurlp/somebody/callback?amount=1000", Auth::None).await;
+    assert_error(&res, StatusCode::NOT_FOUND);
+
+    for _ in 0..5 {
+        assert_status(&api.get("/v1/me", Auth::Bearer(&token)).await, StatusCode::OK);
+    }
+    let res = api.get("/v1/me", Auth::Bearer(&token)).await;
+    assert_error(&res, StatusCode::TOO_MANY_REQUESTS);
+}
@@ -148,8 +148,12 @@ mod list_overviews {
         RegisterLnAddressRequest {
             account_id: Some(wallet.account_id),
             username: username.to_string(),
+            domain: None,
             allows_nostr: false,
             nostr_pubkey: None,
+            wallet_id: None,
+            pay_profile: None,
+            splits: Vec::new(),
         }
     }
 
@@ -159,6 +163,8 @@ mod list_overviews {
             input,
             amount_msat: Some(amount_msat),
             comment: None,
+            conf_target: None,
+            feerate_sat_vb: None,
         }
     }
 
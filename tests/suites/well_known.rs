@@ -8,7 +8,10 @@ use std::time::Duration;
 
 use reqwest::StatusCode;
 
-use swissknife_types::{LnAddress, LnURLPayRequest, LnUrlCallback, NostrNIP05Response, UpdateLnAddressRequest, Wallet};
+use swissknife_types::{
+    LnAddress, LnAddressPayProfile, LnAddressPayerData, LnAddressSuccessAction, LnURLPayRequest, LnUrlCallback,
+    LnUrlVerifyResponse, NostrNIP05Response, UpdateLnAddressRequest, Wallet,
+};
 
 use crate::common::counterparty::Counterparty;
 use crate::common::fixtures::unique;
@@ -131,6 +134,88 @@ mod lnurl {
         .await;
     }
 
+    #[tokio::test]
+    async fn an_external_payment_is_split_between_wallets() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let wallet = app.create_wallet(token, "lnurl-split").await;
+        let platform = app.create_wallet(token, "lnurl-split-platform").await;
+        let body = serde_json::json!({
+            "account_id": wallet.account_id,
+            "username": unique("lnurl-split"),
+            "splits": [{ "wallet_id": platform.id, "share_bps": 1000 }],
+        });
+        let addr = app
+            .api()
+            .post("/v1/lightning-addresses", Auth::Bearer(token), body)
+            .await
+            .parse::<LnAddress>();
+
+        let amount_msat = 100_000_000u64;
+        let pay = app
+            .api()
+            .get(&format!("/.well-known/lnurlp/{}", addr.username), Auth::None)
+            .await
+            .parse::<LnURLPayRequest>();
+        let cb = follow_callback(&pay.callback, amount_msat).await;
+        Counterparty::for_provider(&app.provider).pay(&cb.pr);
+
+        wait_until(
+            Duration::from_secs(45),
+            "the share passed on to the platform",
+            || async { app.wallet_balance(token, platform.id).await.available_msat > 0 },
+        )
+        .await;
+        assert_eq!(app.wallet_balance(token, platform.id).await.available_msat, 10_000_000);
+        assert_eq!(app.wallet_balance(token, wallet.id).await.available_msat, 90_000_000);
+    }
+
+    #[tokio::test]
+    async fn the_advertised_verify_url_reports_settlement() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let (_wallet, addr) = register_address(app, token, "lnurl-verify", None).await;
+
+        let pay = app
+            .api()
+            .get(&format!("/.well-known/lnurlp/{}", addr.username), Auth::None)
+            .await
+            .parse::<LnURLPayRequest>();
+        let cb = follow_callback(&pay.callback, 100_000_000).await;
+        let verify_url = cb.verify.expect("the callback advertises a LUD-21 verify URL");
+        assert!(
+            verify_url.starts_with(&app.base_url),
+            "the verify URL targets this server: {verify_url}"
+        );
+
+        let verify = reqwest::get(&verify_url)
+            .await
+            .expect("reach the advertised verify URL")
+            .json::<LnUrlVerifyResponse>()
+            .await
+            .expect("verify returns an LnUrlVerifyResponse");
+        assert_eq!(verify.status, "OK");
+        assert!(!verify.settled && verify.preimage.is_none(), "unpaid: {verify:?}");
+        assert_eq!(verify.pr, cb.pr);
+
+        Counterparty::for_provider(&app.provider).pay(&cb.pr);
+
+        wait_until(
+            Duration::from_secs(45),
+            "verify reports the invoice settled",
+            || async {
+                let verify = reqwest::get(&verify_url)
+                    .await
+                    .expect("reach the advertised verify URL")
+                    .json::<LnUrlVerifyResponse>()
+                    .await
+                    .expect("verify returns an LnUrlVerifyResponse");
+                verify.settled && verify.preimage.is_some()
+            },
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn an_inactive_address_is_not_found() {
         let app = app().await;
@@ -148,6 +233,9 @@ mod lnurl {
                     active: Some(false),
                     allows_nostr: None,
                     nostr_pubkey: None,
+                    pay_profile: None,
+                    wallet_id: None,
+                    splits: None,
                 },
             )
             .await;
@@ -162,6 +250,65 @@ mod lnurl {
         assert_error(&res, StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn a_custom_pay_profile_is_served_and_enforced() {
+        let app = app().await;
+        let token = app.admin_token().await;
+        let (_wallet, addr) = register_address(app, token, "lnurl-profile", None).await;
+
+        let updated = app
+            .api()
+            .put(
+                &format!("/v1/lightning-addresses/{}", addr.id),
+                Auth::Bearer(token),
+                UpdateLnAddressRequest {
+                    username: None,
+                    active: None,
+                    allows_nostr: None,
+                    nostr_pubkey: None,
+                    pay_profile: Some(LnAddressPayProfile {
+                        min_sendable: Some(5_000),
+                        max_sendable: Some(20_000),
+                        comment_allowed: Some(0),
+                        description: Some("Espresso bar".to_string()),
+                        image: None,
+                        success_action: Some(LnAddressSuccessAction::Message {
+                            message: "Enjoy your coffee".to_string(),
+                        }),
+                        payer_data: Some(LnAddressPayerData {
+                            name: Some(false),
+                            ..Default::default()
+                        }),
+                    }),
+                    wallet_id: None,
+                    splits: None,
+                },
+            )
+            .await;
+        assert_status(&updated, StatusCode::OK);
+
+        let pay = app
+            .api()
+            .get(&format!("/.well-known/lnurlp/{}", addr.username), Auth::None)
+            .await
+            .parse::<LnURLPayRequest>();
+        assert_eq!((pay.min_sendable, pay.max_sendable), (5_000, 20_000));
+        assert_eq!(pay.comment_allowed, 0);
+        assert!(pay.metadata.contains("Espresso bar"), "metadata: {}", pay.metadata);
+        let payer_data = pay.payer_data.expect("payer data is advertised");
+        assert!(payer_data.name.is_some() && payer_data.email.is_none());
+
+        // Amounts outside the profile range are refused by the callback.
+        let res = reqwest::get(format!("{}?amount=1000", pay.callback))
+            .await
+            .expect("reach the advertised LNURL callback");
+        assert_eq!(res.status().as_u16(), 422);
+
+        let cb = follow_callback(&pay.callback, 10_000).await;
+        let success_action = cb.success_action.expect("profile success action");
+        assert_eq!(success_action.message.as_deref(), Some("Enjoy your coffee"));
+    }
+
     #[tokio::test]
     async fn unknown_username_is_not_found() {
         let app = app().await;